@@ -0,0 +1,34 @@
+-- Migration: 008_fills
+-- Description: Record actual order fills reported by the Polymarket CLOB
+-- Created: 2026-10-16
+--
+-- The trades table stores what we asked for; this table stores what the
+-- exchange actually filled, so P&L can be reconciled locally instead of
+-- scraping the Data API activity feed.
+
+-- =============================================================================
+-- FILLS TABLE
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS fills (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    market_id UUID NOT NULL REFERENCES markets(id),
+    order_id TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    side VARCHAR(3) NOT NULL,  -- 'YES' or 'NO'
+    filled_shares DECIMAL(20, 8) NOT NULL,
+    avg_price DECIMAL(10, 4) NOT NULL,
+    fee DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    filled_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fills_market ON fills(market_id);
+CREATE INDEX IF NOT EXISTS idx_fills_order_id ON fills(order_id);
+CREATE INDEX IF NOT EXISTS idx_fills_filled_at ON fills(filled_at DESC);
+
+-- =============================================================================
+-- COMMENTS
+-- =============================================================================
+COMMENT ON TABLE fills IS 'Actual fills per order as reported by the Polymarket CLOB';
+COMMENT ON COLUMN fills.filled_shares IS 'Shares matched when polling finished (after any cancel)';
+COMMENT ON COLUMN fills.avg_price IS 'Average fill price per share';
+COMMENT ON COLUMN fills.fee IS 'Fee paid in USDC for this fill';
@@ -0,0 +1,33 @@
+-- Migration: 009_settlements
+-- Description: Durable record of resolved positions and their realized P&L
+-- Created: 2026-10-16
+--
+-- The traders compute P&L when a position's market resolves but previously
+-- only logged it. One row per resolved position (live or dry-run) so
+-- reporting can aggregate realized results without replaying logs.
+
+-- =============================================================================
+-- SETTLEMENTS TABLE
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS settlements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    market_id UUID NOT NULL REFERENCES markets(id),
+    side VARCHAR(3) NOT NULL,          -- 'YES' or 'NO' (the side we held)
+    shares DECIMAL(20, 8) NOT NULL,
+    cost DECIMAL(20, 8) NOT NULL,
+    winning_side VARCHAR(3) NOT NULL,  -- 'YES' or 'NO'
+    pnl DECIMAL(20, 8) NOT NULL,
+    is_dry_run BOOLEAN NOT NULL DEFAULT false,
+    resolved_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlements_market ON settlements(market_id);
+CREATE INDEX IF NOT EXISTS idx_settlements_resolved_at ON settlements(resolved_at DESC);
+
+-- =============================================================================
+-- COMMENTS
+-- =============================================================================
+COMMENT ON TABLE settlements IS 'Resolved positions with realized P&L, one row per position';
+COMMENT ON COLUMN settlements.cost IS 'USDC paid to open the position';
+COMMENT ON COLUMN settlements.pnl IS 'Realized P&L in USDC: shares - cost on a win, -cost on a loss';
+COMMENT ON COLUMN settlements.is_dry_run IS 'True for simulated (dry-run) positions';
@@ -0,0 +1,15 @@
+-- Migration: 010_opportunity_depth
+-- Description: Keep the leg depth used to size cross-platform opportunities
+-- Created: 2026-10-16
+--
+-- Opportunities only stored top-of-book prices, so there was no way to check
+-- after the fact whether sizing was right. Both columns hold the ask ladder of
+-- the leg being bought, in the same [{price, size}] form as orderbook_snapshots.
+-- NULL when depth wasn't captured.
+
+ALTER TABLE cross_platform_opportunities
+    ADD COLUMN IF NOT EXISTS yes_leg_depth JSONB,
+    ADD COLUMN IF NOT EXISTS no_leg_depth JSONB;
+
+COMMENT ON COLUMN cross_platform_opportunities.yes_leg_depth IS 'YES asks on buy_yes_platform at detection time';
+COMMENT ON COLUMN cross_platform_opportunities.no_leg_depth IS 'NO asks on buy_no_platform at detection time';
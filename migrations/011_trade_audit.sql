@@ -0,0 +1,36 @@
+-- Migration: 011_trade_audit
+-- Description: Cross-strategy audit trail of trade intents and their outcomes
+-- Created: 2026-10-16
+--
+-- Each trader builds orders inline and logs them its own way. Every order a
+-- strategy decides to place (live or dry-run) is recorded here with the price
+-- it was sent at, the fill it expected and how it ended, so strategies can be
+-- compared from one table.
+
+-- =============================================================================
+-- TRADE AUDIT TABLE
+-- =============================================================================
+CREATE TABLE IF NOT EXISTS trade_audit (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    market_id UUID NOT NULL REFERENCES markets(id),
+    token_id TEXT NOT NULL,
+    side VARCHAR(3) NOT NULL,            -- 'YES' or 'NO'
+    shares DECIMAL(20, 8) NOT NULL,
+    limit_price DECIMAL(10, 4) NOT NULL,
+    effective_price DECIMAL(10, 4) NOT NULL,
+    strategy_tag VARCHAR(32) NOT NULL,   -- 'momentum', 'misprice', ...
+    mode VARCHAR(8) NOT NULL,            -- 'dry_run' or 'live'
+    outcome VARCHAR(16) NOT NULL,        -- 'placed', 'filled', 'rejected', 'skipped'
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_audit_market ON trade_audit(market_id);
+CREATE INDEX IF NOT EXISTS idx_trade_audit_strategy ON trade_audit(strategy_tag, created_at DESC);
+
+-- =============================================================================
+-- COMMENTS
+-- =============================================================================
+COMMENT ON TABLE trade_audit IS 'Every trade intent across strategies with its outcome';
+COMMENT ON COLUMN trade_audit.limit_price IS 'Price the order was sent at';
+COMMENT ON COLUMN trade_audit.effective_price IS 'Expected average fill price after walking the book';
+COMMENT ON COLUMN trade_audit.outcome IS 'placed (accepted, fill tracked separately), filled, rejected or skipped';
@@ -0,0 +1,219 @@
+//! Tradeable assets and their price-feed symbols.
+//!
+//! [`ASSET_REGISTRY`] is the single asset <-> symbol table the traders share;
+//! supporting a new asset means adding a row here. `--assets` values are
+//! parsed by [`parse_assets`], where `ALL` defers to whatever crypto assets
+//! the database currently has active markets for.
+
+use anyhow::{bail, Result};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::repository::get_active_crypto_assets;
+
+/// `--assets` sentinel for every active crypto asset in the database.
+pub const ALL_ASSETS: &str = "ALL";
+
+/// Price-feed symbols for one asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetSymbols {
+    pub asset: &'static str,
+    /// Binance spot pair (e.g. "BTCUSDT")
+    pub binance: &'static str,
+    /// Chainlink feed on Polymarket RTDS (e.g. "btc/usd"), if one exists
+    pub chainlink: Option<&'static str>,
+    /// Kalshi series prefix (e.g. "KXBTC"), if Kalshi lists the asset
+    pub kalshi: Option<&'static str>,
+}
+
+/// Every asset the traders know how to price.
+pub const ASSET_REGISTRY: &[AssetSymbols] = &[
+    AssetSymbols {
+        asset: "BTC",
+        binance: "BTCUSDT",
+        chainlink: Some("btc/usd"),
+        kalshi: Some("KXBTC"),
+    },
+    AssetSymbols {
+        asset: "ETH",
+        binance: "ETHUSDT",
+        chainlink: Some("eth/usd"),
+        kalshi: Some("KXETH"),
+    },
+    AssetSymbols {
+        asset: "SOL",
+        binance: "SOLUSDT",
+        chainlink: Some("sol/usd"),
+        kalshi: Some("KXSOL"),
+    },
+    AssetSymbols {
+        asset: "XRP",
+        binance: "XRPUSDT",
+        chainlink: Some("xrp/usd"),
+        kalshi: Some("KXXRP"),
+    },
+];
+
+/// Registry entry for `asset` (case-insensitive).
+pub fn lookup_asset(asset: &str) -> Option<&'static AssetSymbols> {
+    ASSET_REGISTRY
+        .iter()
+        .find(|a| a.asset.eq_ignore_ascii_case(asset))
+}
+
+/// Binance pair for `asset`, if registered.
+pub fn binance_symbol(asset: &str) -> Option<&'static str> {
+    lookup_asset(asset).map(|a| a.binance)
+}
+
+/// Chainlink RTDS feed for `asset`, if registered and one exists.
+pub fn chainlink_symbol(asset: &str) -> Option<&'static str> {
+    lookup_asset(asset).and_then(|a| a.chainlink)
+}
+
+/// Asset priced by Chainlink feed `symbol` (case-insensitive).
+pub fn asset_for_chainlink(symbol: &str) -> Option<&'static str> {
+    ASSET_REGISTRY
+        .iter()
+        .find(|a| a.chainlink.is_some_and(|c| c.eq_ignore_ascii_case(symbol)))
+        .map(|a| a.asset)
+}
+
+/// Asset of a Kalshi series or market ticker, e.g. "KXBTC15M" or
+/// "KXETH-25JAN13-T3500" (case-insensitive).
+pub fn kalshi_asset(symbol: &str) -> Option<&'static str> {
+    let upper = symbol.to_uppercase();
+    ASSET_REGISTRY
+        .iter()
+        .find(|a| a.kalshi.is_some_and(|k| upper.starts_with(k)))
+        .map(|a| a.asset)
+}
+
+/// Assets selected by an `--assets` flag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetSelection {
+    /// Every active crypto asset in the database.
+    All,
+    /// An explicit, upper-cased list.
+    List(Vec<String>),
+}
+
+impl AssetSelection {
+    /// The concrete asset list; `All` is looked up in the database.
+    pub async fn resolve(&self, pool: &PgPool) -> Result<Vec<String>> {
+        match self {
+            AssetSelection::List(assets) => Ok(assets.clone()),
+            AssetSelection::All => {
+                let assets = get_active_crypto_assets(pool).await?;
+                if assets.is_empty() {
+                    bail!("--assets ALL found no active markets in the database");
+                }
+                info!("[ASSETS] ALL resolved to: {}", assets.join(","));
+                Ok(assets)
+            }
+        }
+    }
+}
+
+/// Parse an `--assets` value: a comma-separated list, or `ALL` on its own.
+pub fn parse_assets(s: &str) -> Result<AssetSelection> {
+    let assets: Vec<String> = s
+        .split(',')
+        .map(|a| a.trim().to_uppercase())
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    if assets.is_empty() {
+        bail!("No valid assets specified");
+    }
+    if assets.iter().any(|a| a == ALL_ASSETS) {
+        if assets.len() > 1 {
+            bail!("--assets ALL cannot be combined with other assets");
+        }
+        return Ok(AssetSelection::All);
+    }
+    Ok(AssetSelection::List(assets))
+}
+
+/// Keep the assets `symbol_for` can map, logging the rest as skipped.
+///
+/// Returns `(asset, symbol)` pairs in input order.
+pub fn mapped_assets(
+    assets: &[String],
+    feed: &str,
+    symbol_for: impl Fn(&str) -> Option<&'static str>,
+) -> Vec<(String, &'static str)> {
+    assets
+        .iter()
+        .filter_map(|asset| match symbol_for(asset) {
+            Some(symbol) => Some((asset.clone(), symbol)),
+            None => {
+                warn!("[ASSETS] No {} symbol for {}, skipping", feed, asset);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assets() {
+        assert_eq!(parse_assets("ALL").unwrap(), AssetSelection::All);
+        assert_eq!(parse_assets(" all ").unwrap(), AssetSelection::All);
+        assert_eq!(
+            parse_assets("btc, eth,,SOL").unwrap(),
+            AssetSelection::List(vec!["BTC".into(), "ETH".into(), "SOL".into()])
+        );
+
+        assert!(parse_assets("").is_err());
+        assert!(parse_assets(" , ").is_err());
+        assert!(parse_assets("ALL,BTC").is_err());
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        assert_eq!(binance_symbol("BTC"), Some("BTCUSDT"));
+        assert_eq!(binance_symbol("xrp"), Some("XRPUSDT"));
+        assert_eq!(chainlink_symbol("eth"), Some("eth/usd"));
+        assert_eq!(binance_symbol("DOGE"), None);
+        assert_eq!(chainlink_symbol("UNKNOWN"), None);
+
+        // No asset is registered twice
+        for entry in ASSET_REGISTRY {
+            assert_eq!(lookup_asset(entry.asset), Some(entry));
+        }
+
+        assert_eq!(kalshi_asset("KXBTC15M"), Some("BTC"));
+        assert_eq!(kalshi_asset("kxsol-25jan13-t200"), Some("SOL"));
+        assert_eq!(kalshi_asset("KXDOGE"), None);
+        assert_eq!(asset_for_chainlink("doge/usd"), None);
+
+        // Unmapped assets are dropped, order preserved
+        let assets: Vec<String> = ["SOL", "DOGE", "BTC"].map(String::from).to_vec();
+        assert_eq!(
+            mapped_assets(&assets, "Binance", binance_symbol),
+            vec![
+                ("SOL".to_string(), "SOLUSDT"),
+                ("BTC".to_string(), "BTCUSDT")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        for entry in ASSET_REGISTRY {
+            let asset = entry.asset;
+            assert_eq!(binance_symbol(asset), Some(entry.binance));
+            if let Some(feed) = chainlink_symbol(asset) {
+                assert_eq!(asset_for_chainlink(feed), Some(asset));
+            }
+            if let Some(series) = entry.kalshi {
+                assert_eq!(kalshi_asset(series), Some(asset));
+                assert_eq!(kalshi_asset(&format!("{}15M", series)), Some(asset));
+            }
+        }
+    }
+}
@@ -1,8 +1,9 @@
 //! Binance WebSocket client for real-time market data.
 //!
-//! Supports two stream types:
+//! Supports three stream types:
 //! - **bookTicker**: Real-time best bid/ask updates (~10ms latency, 150+ updates/sec)
 //! - **kline_1m**: 1-minute candlestick data (~2s updates, for momentum calculation)
+//! - **aggTrade**: Executed trades with aggressor side (for volume confirmation)
 
 use std::collections::VecDeque;
 use std::time::Duration;
@@ -18,6 +19,12 @@ use tracing::{debug, error, info, warn};
 /// Default Binance WebSocket URL for combined streams.
 pub const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443/stream";
 
+/// Binance REST endpoint for historical klines (used to backfill reconnect gaps).
+pub const BINANCE_KLINES_URL: &str = "https://api.binance.com/api/v3/klines";
+
+/// Binance caps the REST klines `limit` parameter at 1000.
+const MAX_KLINES_LIMIT: u32 = 1000;
+
 /// Stream type to subscribe to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinanceStreamType {
@@ -27,6 +34,8 @@ pub enum BinanceStreamType {
     Kline1m,
     /// Both bookTicker and kline (for services needing both)
     Both,
+    /// bookTicker, kline and aggTrade (Both plus executed volume)
+    Trades,
 }
 
 /// Real-time book ticker data from Binance.
@@ -93,6 +102,9 @@ pub struct BinanceKline {
     pub close: Decimal,
     pub volume: Decimal,
     pub is_closed: bool,
+    /// When this update was received; the same clock as
+    /// [`BinanceBookTicker::timestamp`]
+    pub timestamp: DateTime<Utc>,
 }
 
 /// Raw kline event from Binance WebSocket.
@@ -134,6 +146,35 @@ struct KlineData {
     is_closed: bool,
 }
 
+/// An aggregated trade from Binance: fills of one taker order at one price.
+#[derive(Debug, Clone)]
+pub struct BinanceTrade {
+    pub symbol: String,
+    pub price: Decimal,
+    pub qty: Decimal,
+    /// The buyer's order was resting, so the taker sold
+    pub is_buyer_maker: bool,
+    pub trade_time: DateTime<Utc>,
+}
+
+/// Raw aggTrade event from Binance WebSocket.
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct AggTradeEvent {
+    #[serde(rename = "e")]
+    event_type: String,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "q")]
+    qty: String,
+    #[serde(rename = "T")]
+    trade_time: i64,
+    #[serde(rename = "m")]
+    is_buyer_maker: bool,
+}
+
 /// Combined stream wrapper message.
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -142,11 +183,12 @@ struct CombinedStreamMessage {
     data: serde_json::Value,
 }
 
-/// Unified market data event (either ticker or kline).
+/// Unified market data event (ticker, kline or trade).
 #[derive(Debug, Clone)]
 pub enum BinanceEvent {
     Ticker(BinanceBookTicker),
     Kline(BinanceKline),
+    Trade(BinanceTrade),
 }
 
 /// Binance WebSocket client for streaming market data.
@@ -211,6 +253,16 @@ impl BinanceWsClient {
                 }
                 streams
             }
+            BinanceStreamType::Trades => {
+                let mut streams = Vec::with_capacity(self.symbols.len() * 3);
+                for s in &self.symbols {
+                    let lower = s.to_lowercase();
+                    streams.push(format!("{}@bookTicker", lower));
+                    streams.push(format!("{}@kline_1m", lower));
+                    streams.push(format!("{}@aggTrade", lower));
+                }
+                streams
+            }
         };
 
         format!("{}?streams={}", self.ws_url, streams.join("/"))
@@ -257,6 +309,121 @@ impl BinanceWsClient {
             max_retries
         ))
     }
+
+    /// Fetch the most recent closed 1m klines for a symbol via REST.
+    /// Returns klines sorted by open time (oldest first).
+    pub async fn fetch_recent_klines(
+        &self,
+        symbol: &str,
+        limit: u32,
+    ) -> anyhow::Result<Vec<BinanceKline>> {
+        let limit = limit.clamp(1, MAX_KLINES_LIMIT);
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        let response = client
+            .get(BINANCE_KLINES_URL)
+            .query(&[
+                ("symbol", symbol.to_uppercase()),
+                ("interval", "1m".to_string()),
+                ("limit", limit.to_string()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "[BINANCE] Failed to fetch klines for {}: HTTP {}",
+                symbol,
+                response.status()
+            ));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        Ok(parse_rest_klines(symbol, &body, Utc::now()))
+    }
+
+    /// Backfill any minutes missing from the buffer (e.g. after a reconnect).
+    /// Returns the total number of klines inserted across all symbols.
+    pub async fn backfill_klines(&self, buffer: &mut KlineBuffer) -> usize {
+        let now = Utc::now();
+        let mut inserted = 0;
+
+        for symbol in &self.symbols {
+            let limit = buffer.missing_minutes(symbol, now);
+            if limit == 0 {
+                continue;
+            }
+
+            // +1 because the newest REST kline is still forming and gets dropped
+            match self.fetch_recent_klines(symbol, limit + 1).await {
+                Ok(klines) => {
+                    let added = buffer.backfill(klines);
+                    if added > 0 {
+                        info!("[BINANCE] Backfilled {} klines for {}", added, symbol);
+                    }
+                    inserted += added;
+                }
+                Err(e) => {
+                    warn!("[BINANCE] Kline backfill failed for {}: {}", symbol, e);
+                }
+            }
+        }
+
+        inserted
+    }
+}
+
+/// Parse a Binance REST klines response (array of arrays) into closed klines.
+///
+/// Each row is `[open_time, open, high, low, close, volume, close_time, ...]`.
+/// Klines whose close time is not before `now` are still forming and are skipped.
+fn parse_rest_klines(
+    symbol: &str,
+    body: &serde_json::Value,
+    now: DateTime<Utc>,
+) -> Vec<BinanceKline> {
+    let rows = match body.as_array() {
+        Some(rows) => rows,
+        None => return Vec::new(),
+    };
+
+    rows.iter()
+        .filter_map(|row| {
+            let row = row.as_array()?;
+            let decimal = |i: usize| row.get(i)?.as_str()?.parse::<Decimal>().ok();
+            let close_time = Utc.timestamp_millis_opt(row.get(6)?.as_i64()?).single()?;
+            Some(BinanceKline {
+                symbol: symbol.to_uppercase(),
+                open_time: Utc.timestamp_millis_opt(row.first()?.as_i64()?).single()?,
+                close_time,
+                open: decimal(1)?,
+                high: decimal(2)?,
+                low: decimal(3)?,
+                close: decimal(4)?,
+                volume: decimal(5)?,
+                is_closed: close_time < now,
+                timestamp: now,
+            })
+        })
+        .filter(|k| k.is_closed)
+        .collect()
+}
+
+/// Convert a raw aggTrade event, rejecting other event types and bad numbers.
+fn trade_from_event(event: &AggTradeEvent) -> Option<BinanceTrade> {
+    if event.event_type != "aggTrade" {
+        return None;
+    }
+
+    Some(BinanceTrade {
+        symbol: event.symbol.clone(),
+        price: event.price.parse().ok()?,
+        qty: event.qty.parse().ok()?,
+        is_buyer_maker: event.is_buyer_maker,
+        trade_time: Utc.timestamp_millis_opt(event.trade_time).single()?,
+    })
 }
 
 /// Active WebSocket stream for receiving market data.
@@ -288,7 +455,7 @@ impl BinanceWsStream {
         }
     }
 
-    /// Receive the next event (ticker or kline) from the stream.
+    /// Receive the next event (ticker, kline or trade) from the stream.
     pub async fn next_event(&mut self) -> Option<BinanceEvent> {
         loop {
             // Send ping every 30 seconds to keep connection alive
@@ -347,23 +514,23 @@ impl BinanceWsStream {
     }
 
     /// Receive the next book ticker (blocks until one arrives).
-    /// Skips kline events if stream is Both.
+    /// Skips kline and trade events on combined streams.
     pub async fn next_ticker(&mut self) -> Option<BinanceBookTicker> {
         loop {
             match self.next_event().await? {
                 BinanceEvent::Ticker(ticker) => return Some(ticker),
-                BinanceEvent::Kline(_) => continue, // Skip klines
+                _ => continue, // Skip klines and trades
             }
         }
     }
 
     /// Receive the next kline (blocks until one arrives).
-    /// Skips ticker events if stream is Both.
+    /// Skips ticker and trade events on combined streams.
     pub async fn next_kline(&mut self) -> Option<BinanceKline> {
         loop {
             match self.next_event().await? {
                 BinanceEvent::Kline(kline) => return Some(kline),
-                BinanceEvent::Ticker(_) => continue, // Skip tickers
+                _ => continue, // Skip tickers and trades
             }
         }
     }
@@ -402,10 +569,16 @@ impl BinanceWsStream {
             }
             // Check if it's a kline stream
             if combined.stream.contains("@kline") {
-                if let Ok(event) = serde_json::from_value::<KlineEvent>(combined.data) {
+                if let Ok(event) = serde_json::from_value::<KlineEvent>(combined.data.clone()) {
                     return self.kline_from_event(&event);
                 }
             }
+            // Check if it's an aggTrade stream
+            if combined.stream.ends_with("@aggTrade") {
+                if let Ok(event) = serde_json::from_value::<AggTradeEvent>(combined.data) {
+                    return trade_from_event(&event).map(BinanceEvent::Trade);
+                }
+            }
         }
 
         // Try parsing as direct bookTicker event
@@ -418,6 +591,11 @@ impl BinanceWsStream {
             return self.kline_from_event(&event);
         }
 
+        // Try parsing as direct aggTrade event
+        if let Ok(event) = serde_json::from_str::<AggTradeEvent>(text) {
+            return trade_from_event(&event).map(BinanceEvent::Trade);
+        }
+
         debug!("[BINANCE] Failed to parse message: {}", text);
         None
     }
@@ -450,6 +628,7 @@ impl BinanceWsStream {
             close: k.close.parse().ok()?,
             volume: k.volume.parse().ok()?,
             is_closed: k.is_closed,
+            timestamp: Utc::now(),
         }))
     }
 
@@ -478,6 +657,16 @@ pub struct KlineBuffer {
     max_size: usize,
     /// Latest ticker prices (from bookTicker stream)
     latest_prices: std::collections::HashMap<String, Decimal>,
+    /// When each latest price was received
+    latest_price_times: std::collections::HashMap<String, DateTime<Utc>>,
+    /// Recent (timestamp, mid price) ticker samples for short-window trend checks
+    tick_history: std::collections::HashMap<String, VecDeque<(DateTime<Utc>, Decimal)>>,
+    /// How many seconds of ticker samples to retain (0 = none)
+    tick_history_secs: i64,
+    /// Recent (trade time, taker buy qty, taker sell qty) per symbol
+    trade_history: std::collections::HashMap<String, VecDeque<(DateTime<Utc>, Decimal, Decimal)>>,
+    /// How many seconds of trades to retain (0 = none)
+    trade_history_secs: i64,
 }
 
 impl KlineBuffer {
@@ -490,14 +679,33 @@ impl KlineBuffer {
             buffers: std::collections::HashMap::new(),
             max_size,
             latest_prices: std::collections::HashMap::new(),
+            latest_price_times: std::collections::HashMap::new(),
+            tick_history: std::collections::HashMap::new(),
+            tick_history_secs: 0,
+            trade_history: std::collections::HashMap::new(),
+            trade_history_secs: 0,
         }
     }
 
+    /// Retain `secs` seconds of ticker prices so `short_term_change` can be used.
+    pub fn with_tick_history(mut self, secs: u64) -> Self {
+        self.tick_history_secs = secs as i64;
+        self
+    }
+
+    /// Retain `secs` seconds of aggTrade volume so `trade_volume` can be used.
+    pub fn with_trade_history(mut self, secs: u64) -> Self {
+        self.trade_history_secs = secs as i64;
+        self
+    }
+
     /// Add a kline to the buffer.
     /// Only adds closed klines to avoid partial data.
     pub fn add(&mut self, kline: BinanceKline) {
         // Update latest price from kline close
         self.latest_prices.insert(kline.symbol.clone(), kline.close);
+        self.latest_price_times
+            .insert(kline.symbol.clone(), kline.timestamp);
 
         // Only store closed klines for accurate momentum calculation
         if !kline.is_closed {
@@ -537,10 +745,128 @@ impl KlineBuffer {
         }
     }
 
+    /// Merge historical closed klines into the buffer, filling any gaps.
+    /// Existing klines are kept; the buffer stays ordered by open time.
+    /// Returns the number of klines inserted.
+    pub fn backfill(&mut self, klines: Vec<BinanceKline>) -> usize {
+        let mut inserted = 0;
+
+        for kline in klines.into_iter().filter(|k| k.is_closed) {
+            let buffer = self
+                .buffers
+                .entry(kline.symbol.clone())
+                .or_insert_with(|| VecDeque::with_capacity(self.max_size + 1));
+
+            // Skip klines older than the retained window once the buffer is full
+            if buffer.len() >= self.max_size
+                && buffer
+                    .front()
+                    .is_some_and(|f| kline.open_time < f.open_time)
+            {
+                continue;
+            }
+
+            match buffer.binary_search_by_key(&kline.open_time, |k| k.open_time) {
+                Ok(_) => continue,
+                Err(idx) => {
+                    buffer.insert(idx, kline);
+                    inserted += 1;
+                }
+            }
+
+            while buffer.len() > self.max_size {
+                buffer.pop_front();
+            }
+        }
+
+        inserted
+    }
+
+    /// Number of 1m klines needed to cover the gap between the newest buffered
+    /// kline and `now` (the whole buffer if empty), capped at the buffer size.
+    pub fn missing_minutes(&self, symbol: &str, now: DateTime<Utc>) -> u32 {
+        let newest = self
+            .buffers
+            .get(symbol)
+            .and_then(|b| b.iter().rev().find(|k| k.is_closed));
+
+        let missing = match newest {
+            Some(k) => (now - k.close_time).num_minutes().max(0) as usize,
+            None => self.max_size,
+        };
+
+        missing.min(self.max_size) as u32
+    }
+
     /// Update latest price from a book ticker event.
     pub fn update_price(&mut self, ticker: &BinanceBookTicker) {
-        self.latest_prices
-            .insert(ticker.symbol.clone(), ticker.mid_price());
+        let mid = ticker.mid_price();
+        self.latest_prices.insert(ticker.symbol.clone(), mid);
+        self.latest_price_times
+            .insert(ticker.symbol.clone(), ticker.timestamp);
+
+        if self.tick_history_secs > 0 {
+            let history = self.tick_history.entry(ticker.symbol.clone()).or_default();
+            history.push_back((ticker.timestamp, mid));
+
+            // Keep one sample at or before the cutoff so the full window stays covered
+            let cutoff = ticker.timestamp - chrono::Duration::seconds(self.tick_history_secs);
+            while history.len() > 1 && history[1].0 <= cutoff {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Fractional price change over the last `window_secs` of ticker data,
+    /// measured from the sample at the start of the window to the newest one.
+    /// Returns None if the retained history does not yet span the window.
+    pub fn short_term_change(&self, symbol: &str, window_secs: u64) -> Option<Decimal> {
+        let history = self.tick_history.get(symbol)?;
+        let &(latest_time, latest_price) = history.back()?;
+        let start = latest_time - chrono::Duration::seconds(window_secs as i64);
+
+        // Price at the window start: newest sample at or before it
+        let &(_, base_price) = history.iter().rev().find(|(ts, _)| *ts <= start)?;
+        if base_price == Decimal::ZERO {
+            return None;
+        }
+
+        Some((latest_price - base_price) / base_price)
+    }
+
+    /// Record an executed trade's volume. No-op unless trade history is enabled.
+    pub fn add_trade(&mut self, trade: &BinanceTrade) {
+        if self.trade_history_secs == 0 {
+            return;
+        }
+
+        let (buy, sell) = if trade.is_buyer_maker {
+            (Decimal::ZERO, trade.qty)
+        } else {
+            (trade.qty, Decimal::ZERO)
+        };
+        let history = self.trade_history.entry(trade.symbol.clone()).or_default();
+        history.push_back((trade.trade_time, buy, sell));
+
+        let cutoff = trade.trade_time - chrono::Duration::seconds(self.trade_history_secs);
+        while history.front().is_some_and(|(ts, _, _)| *ts < cutoff) {
+            history.pop_front();
+        }
+    }
+
+    /// Taker buy and sell volume over the `window_secs` before `now`.
+    /// Windows longer than the retained history only see what was retained.
+    pub fn trade_volume(&self, symbol: &str, window_secs: u64, now: DateTime<Utc>) -> TradeVolume {
+        let start = now - chrono::Duration::seconds(window_secs as i64);
+        self.trade_history
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .filter(|(ts, _, _)| *ts > start && *ts <= now)
+            .fold(TradeVolume::default(), |acc, (_, buy, sell)| TradeVolume {
+                buy: acc.buy + buy,
+                sell: acc.sell + sell,
+            })
     }
 
     /// Calculate momentum (percentage change) over the lookback window.
@@ -588,6 +914,37 @@ impl KlineBuffer {
         Some((change, direction))
     }
 
+    /// Weighted composite momentum across several lookback windows.
+    ///
+    /// Each window's change is computed as in `calculate_momentum`; the result
+    /// is their weighted average. Returns None if any window lacks enough data,
+    /// so a short window never stands in for a longer one still warming up.
+    pub fn weighted_momentum(
+        &self,
+        symbol: &str,
+        windows: &[MomentumWindow],
+    ) -> Option<(Decimal, MomentumDirection)> {
+        let total_weight: Decimal = windows.iter().map(|w| w.weight).sum();
+        if total_weight <= Decimal::ZERO {
+            return None;
+        }
+
+        let mut weighted = Decimal::ZERO;
+        for window in windows {
+            let (change, _) = self.calculate_momentum(symbol, window.minutes)?;
+            weighted += change * window.weight;
+        }
+
+        let composite = weighted / total_weight;
+        let direction = if composite > Decimal::ZERO {
+            MomentumDirection::Up
+        } else {
+            MomentumDirection::Down
+        };
+
+        Some((composite, direction))
+    }
+
     /// Get the current price for a symbol (from bookTicker or kline).
     pub fn current_price(&self, symbol: &str) -> Option<Decimal> {
         // Prefer real-time ticker price
@@ -667,6 +1024,61 @@ impl KlineBuffer {
                 .map(|k| k.close)
         })
     }
+
+    /// Get the latest price together with the time it was received.
+    ///
+    /// Falls back to the newest buffered kline, timestamped at its close (or
+    /// now, if the kline is still open).
+    pub fn get_latest_close_with_time(&self, symbol: &str) -> Option<(Decimal, DateTime<Utc>)> {
+        if let (Some(price), Some(at)) = (
+            self.latest_prices.get(symbol),
+            self.latest_price_times.get(symbol),
+        ) {
+            return Some((*price, *at));
+        }
+        self.buffers
+            .get(symbol)
+            .and_then(|b| b.back())
+            .map(|k| (k.close, k.close_time.min(Utc::now())))
+    }
+}
+
+/// Executed volume split by aggressor side, in the base asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TradeVolume {
+    /// Volume where the taker bought
+    pub buy: Decimal,
+    /// Volume where the taker sold
+    pub sell: Decimal,
+}
+
+impl TradeVolume {
+    pub fn total(&self) -> Decimal {
+        self.buy + self.sell
+    }
+
+    /// Fraction of volume taken in `direction` (buys for Up, sells for Down),
+    /// or None when nothing traded.
+    pub fn share_toward(&self, direction: MomentumDirection) -> Option<Decimal> {
+        let total = self.total();
+        if total == Decimal::ZERO {
+            return None;
+        }
+        let toward = match direction {
+            MomentumDirection::Up => self.buy,
+            MomentumDirection::Down => self.sell,
+        };
+        Some(toward / total)
+    }
+}
+
+/// A lookback window and its weight in a composite momentum score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MomentumWindow {
+    /// Lookback in minutes (1m klines)
+    pub minutes: usize,
+    /// Relative weight; weights need not sum to 1
+    pub weight: Decimal,
 }
 
 /// Direction of price momentum.
@@ -686,6 +1098,14 @@ impl MomentumDirection {
             MomentumDirection::Down => "NO",
         }
     }
+
+    /// Whether a price change moves in this direction (a flat change agrees with neither).
+    pub fn agrees_with(&self, change: Decimal) -> bool {
+        match self {
+            MomentumDirection::Up => change > Decimal::ZERO,
+            MomentumDirection::Down => change < Decimal::ZERO,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -709,6 +1129,7 @@ mod tests {
                 close: Decimal::from(100 + i + 1),
                 volume: dec!(1000),
                 is_closed: true,
+                timestamp: Utc::now(),
             });
         }
 
@@ -735,4 +1156,308 @@ mod tests {
         assert_eq!(ticker.mid_price(), dec!(100.5));
         assert_eq!(ticker.spread(), dec!(1));
     }
+
+    fn minute_kline(symbol: &str, base: DateTime<Utc>, minute: i64) -> BinanceKline {
+        let open_time = base + chrono::Duration::minutes(minute);
+        BinanceKline {
+            symbol: symbol.to_string(),
+            open_time,
+            close_time: open_time + chrono::Duration::milliseconds(59_999),
+            open: Decimal::from(100 + minute),
+            high: Decimal::from(101 + minute),
+            low: Decimal::from(99 + minute),
+            close: Decimal::from(101 + minute),
+            volume: dec!(10),
+            is_closed: true,
+            timestamp: open_time + chrono::Duration::milliseconds(59_999),
+        }
+    }
+
+    fn open_times(buffer: &KlineBuffer, symbol: &str) -> Vec<DateTime<Utc>> {
+        buffer.buffers[symbol].iter().map(|k| k.open_time).collect()
+    }
+
+    #[test]
+    fn test_kline_buffer_backfill_fills_gap() {
+        let base = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut buffer = KlineBuffer::new(10);
+
+        // Minutes 0-2 buffered, then a reconnect gap, then minute 6 arrives live
+        for m in [0, 1, 2, 6] {
+            buffer.add(minute_kline("BTCUSDT", base, m));
+        }
+
+        // REST backfill returns minutes 1-7 (overlapping both sides of the gap)
+        let backfill: Vec<_> = (1..=7).map(|m| minute_kline("BTCUSDT", base, m)).collect();
+        assert_eq!(buffer.backfill(backfill), 4); // minutes 3, 4, 5, 7
+
+        let times = open_times(&buffer, "BTCUSDT");
+        assert_eq!(times.len(), 8);
+        for pair in times.windows(2) {
+            assert_eq!(pair[1] - pair[0], chrono::Duration::minutes(1));
+        }
+
+        // Backfilling the same data again is a no-op
+        let again: Vec<_> = (0..=7).map(|m| minute_kline("BTCUSDT", base, m)).collect();
+        assert_eq!(buffer.backfill(again), 0);
+    }
+
+    #[test]
+    fn test_kline_buffer_backfill_respects_max_size() {
+        let base = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut buffer = KlineBuffer::new(5);
+        buffer.add(minute_kline("BTCUSDT", base, 0));
+
+        let backfill: Vec<_> = (0..10).map(|m| minute_kline("BTCUSDT", base, m)).collect();
+        buffer.backfill(backfill);
+
+        // Keeps the newest 5 minutes
+        let times = open_times(&buffer, "BTCUSDT");
+        assert_eq!(times.len(), 5);
+        assert_eq!(times[0], base + chrono::Duration::minutes(5));
+        assert_eq!(times[4], base + chrono::Duration::minutes(9));
+    }
+
+    #[test]
+    fn test_kline_buffer_missing_minutes() {
+        let base = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut buffer = KlineBuffer::new(10);
+
+        // Empty buffer needs a full window
+        assert_eq!(buffer.missing_minutes("BTCUSDT", base), 10);
+
+        buffer.add(minute_kline("BTCUSDT", base, 0));
+        // Last kline closes at 12:00:59.999; at 12:04:30 minutes 1-3 are missing
+        let now = base + chrono::Duration::seconds(270);
+        assert_eq!(buffer.missing_minutes("BTCUSDT", now), 3);
+        // Still inside the next minute - nothing missing
+        let now = base + chrono::Duration::seconds(90);
+        assert_eq!(buffer.missing_minutes("BTCUSDT", now), 0);
+        // Capped at buffer size
+        let now = base + chrono::Duration::hours(2);
+        assert_eq!(buffer.missing_minutes("BTCUSDT", now), 10);
+    }
+
+    #[test]
+    fn test_parse_rest_klines() {
+        let body = serde_json::json!([
+            [
+                1735732800000i64,
+                "100.0",
+                "101.0",
+                "99.0",
+                "100.5",
+                "12.5",
+                1735732859999i64,
+                "0",
+                1,
+                "0",
+                "0",
+                "0"
+            ],
+            [
+                1735732860000i64,
+                "100.5",
+                "102.0",
+                "100.0",
+                "101.5",
+                "8.0",
+                1735732919999i64,
+                "0",
+                1,
+                "0",
+                "0",
+                "0"
+            ],
+            ["bad row"]
+        ]);
+        // "now" falls inside the second kline, so it is still forming
+        let now = Utc.timestamp_millis_opt(1735732890000).unwrap();
+
+        let klines = parse_rest_klines("btcusdt", &body, now);
+        assert_eq!(klines.len(), 1);
+        assert_eq!(klines[0].symbol, "BTCUSDT");
+        assert_eq!(klines[0].open, dec!(100.0));
+        assert_eq!(klines[0].close, dec!(100.5));
+        assert_eq!(klines[0].volume, dec!(12.5));
+        assert!(klines[0].is_closed);
+    }
+
+    #[test]
+    fn test_short_term_change_over_ticker_window() {
+        let mut buffer = KlineBuffer::new(10).with_tick_history(5);
+        let base = Utc.timestamp_millis_opt(1735732800000).unwrap();
+        let tick = |secs: i64, mid: Decimal| BinanceBookTicker {
+            symbol: "BTCUSDT".to_string(),
+            best_bid: mid,
+            best_bid_qty: dec!(1),
+            best_ask: mid,
+            best_ask_qty: dec!(1),
+            timestamp: base + chrono::Duration::seconds(secs),
+        };
+
+        buffer.update_price(&tick(0, dec!(100)));
+        buffer.update_price(&tick(2, dec!(100)));
+        // History does not span 3 seconds yet
+        assert_eq!(buffer.short_term_change("BTCUSDT", 3), None);
+
+        buffer.update_price(&tick(4, dec!(102)));
+        buffer.update_price(&tick(7, dec!(99)));
+        // Window start (t=4) priced at 102, now 99: price just reversed down
+        let change = buffer.short_term_change("BTCUSDT", 3).unwrap();
+        assert!(change < Decimal::ZERO);
+        assert!(!MomentumDirection::Up.agrees_with(change));
+        assert!(MomentumDirection::Down.agrees_with(change));
+
+        // Samples older than the retention window are pruned (t=0 dropped, t=2 kept)
+        assert_eq!(buffer.short_term_change("BTCUSDT", 5), Some(dec!(-0.01)));
+        assert_eq!(buffer.short_term_change("BTCUSDT", 7), None);
+        assert!(!MomentumDirection::Up.agrees_with(Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_parse_agg_trade() {
+        let text = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1735732800123,"s":"BTCUSDT","a":3207558127,"p":"94250.10","q":"0.01500","f":4393052510,"l":4393052512,"T":1735732800120,"m":true,"M":true}}"#;
+        let combined: CombinedStreamMessage = serde_json::from_str(text).unwrap();
+        assert!(combined.stream.ends_with("@aggTrade"));
+        let event: AggTradeEvent = serde_json::from_value(combined.data).unwrap();
+        let trade = trade_from_event(&event).unwrap();
+
+        assert_eq!(trade.symbol, "BTCUSDT");
+        assert_eq!(trade.price, dec!(94250.10));
+        assert_eq!(trade.qty, dec!(0.015));
+        assert!(trade.is_buyer_maker);
+        assert_eq!(trade.trade_time.timestamp_millis(), 1735732800120);
+
+        // Other event types sharing the fields are rejected
+        let other = AggTradeEvent {
+            event_type: "trade".to_string(),
+            ..event
+        };
+        assert!(trade_from_event(&other).is_none());
+    }
+
+    #[test]
+    fn test_trade_volume_window() {
+        let base = Utc.timestamp_millis_opt(1735732800000).unwrap();
+        let trade = |secs: i64, qty: Decimal, is_buyer_maker: bool| BinanceTrade {
+            symbol: "BTCUSDT".to_string(),
+            price: dec!(100),
+            qty,
+            is_buyer_maker,
+            trade_time: base + chrono::Duration::seconds(secs),
+        };
+
+        // Disabled by default
+        let mut buffer = KlineBuffer::new(10);
+        buffer.add_trade(&trade(0, dec!(1), false));
+        assert_eq!(
+            buffer.trade_volume("BTCUSDT", 10, base),
+            TradeVolume::default()
+        );
+
+        let mut buffer = KlineBuffer::new(10).with_trade_history(10);
+        buffer.add_trade(&trade(0, dec!(5), true)); // pruned by t=12
+        buffer.add_trade(&trade(4, dec!(2), false));
+        buffer.add_trade(&trade(8, dec!(1), true));
+        buffer.add_trade(&trade(12, dec!(3), false));
+        let now = base + chrono::Duration::seconds(12);
+
+        let volume = buffer.trade_volume("BTCUSDT", 10, now);
+        assert_eq!(volume.buy, dec!(5));
+        assert_eq!(volume.sell, dec!(1));
+        assert_eq!(
+            volume.share_toward(MomentumDirection::Up),
+            Some(dec!(5) / dec!(6))
+        );
+
+        // Shorter window only sees t=8 and t=12
+        let volume = buffer.trade_volume("BTCUSDT", 5, now);
+        assert_eq!(volume.total(), dec!(4));
+        assert_eq!(
+            volume.share_toward(MomentumDirection::Down),
+            Some(dec!(0.25))
+        );
+
+        // Nothing traded recently
+        let later = now + chrono::Duration::seconds(30);
+        assert_eq!(
+            buffer
+                .trade_volume("BTCUSDT", 10, later)
+                .share_toward(MomentumDirection::Up),
+            None
+        );
+        assert_eq!(
+            buffer.trade_volume("ETHUSDT", 10, now).total(),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_weighted_momentum_across_windows() {
+        let mut buffer = KlineBuffer::new(10);
+        let base = Utc.timestamp_millis_opt(1735732800000).unwrap();
+        let kline = |minute: i64, open: Decimal, close: Decimal| BinanceKline {
+            symbol: "BTCUSDT".to_string(),
+            open_time: base + chrono::Duration::minutes(minute),
+            close_time: base + chrono::Duration::minutes(minute + 1),
+            open,
+            high: open.max(close),
+            low: open.min(close),
+            close,
+            volume: dec!(1),
+            is_closed: true,
+            timestamp: base + chrono::Duration::minutes(minute + 1),
+        };
+        // Flat for 2 minutes from 100, then a jump to 110 in the last minute
+        buffer.add(kline(0, dec!(100), dec!(100)));
+        buffer.add(kline(1, dec!(100), dec!(100)));
+        buffer.add(kline(2, dec!(100), dec!(110)));
+        let windows = |short: Decimal, long: Decimal| {
+            [
+                MomentumWindow {
+                    minutes: 1,
+                    weight: short,
+                },
+                MomentumWindow {
+                    minutes: 3,
+                    weight: long,
+                },
+            ]
+        };
+
+        // Both windows see +10% (current 110 vs opens of 100)
+        let (change, direction) = buffer
+            .weighted_momentum("BTCUSDT", &windows(dec!(1), dec!(1)))
+            .unwrap();
+        assert_eq!(change, dec!(0.1));
+        assert_eq!(direction, MomentumDirection::Up);
+
+        // A reversal below 100 is weighted toward the short window's open (100)
+        buffer.add(kline(3, dec!(110), dec!(99)));
+        // 1m: 99 vs 110 = -10%; 3m: 99 vs 100 = -1%; weights 3:1 -> -7.75%
+        let (change, direction) = buffer
+            .weighted_momentum("BTCUSDT", &windows(dec!(3), dec!(1)))
+            .unwrap();
+        assert_eq!(change, dec!(-0.0775));
+        assert_eq!(direction, MomentumDirection::Down);
+
+        // A window longer than the buffer makes the composite unavailable
+        let too_long = [
+            MomentumWindow {
+                minutes: 1,
+                weight: dec!(1),
+            },
+            MomentumWindow {
+                minutes: 15,
+                weight: dec!(1),
+            },
+        ];
+        assert_eq!(buffer.weighted_momentum("BTCUSDT", &too_long), None);
+        assert_eq!(buffer.weighted_momentum("BTCUSDT", &[]), None);
+        assert_eq!(
+            buffer.weighted_momentum("ETHUSDT", &windows(dec!(1), dec!(1))),
+            None
+        );
+    }
 }
@@ -0,0 +1,48 @@
+//! Trading-loop cadence.
+//!
+//! Markets near expiry need frequent checks; with nothing close to expiry the
+//! loop can idle. [`cycle_interval`] picks the delay between cycles.
+
+use std::time::Duration;
+
+/// Time-to-expiry at or below which trading cycles run at the minimum interval.
+const CADENCE_NEAR_EXPIRY_SECS: i64 = 60;
+/// Time-to-expiry at or above which trading cycles run at the maximum interval.
+const CADENCE_FAR_EXPIRY_SECS: i64 = 600;
+
+/// Delay before the next trading cycle, given the soonest time-to-expiry (in
+/// seconds) across tradeable markets.
+///
+/// Scales linearly from `min` when a market expires within a minute up to `max`
+/// at ten minutes or more. With no candidates the loop idles at `max`.
+pub fn cycle_interval(soonest_expiry_secs: Option<i64>, min: Duration, max: Duration) -> Duration {
+    let secs = match soonest_expiry_secs {
+        Some(secs) => secs.clamp(CADENCE_NEAR_EXPIRY_SECS, CADENCE_FAR_EXPIRY_SECS),
+        None => return max,
+    };
+    let fraction = (secs - CADENCE_NEAR_EXPIRY_SECS) as f64
+        / (CADENCE_FAR_EXPIRY_SECS - CADENCE_NEAR_EXPIRY_SECS) as f64;
+    min + max.saturating_sub(min).mul_f64(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_interval_scales_with_time_to_expiry() {
+        let min = Duration::from_millis(100);
+        let max = Duration::from_millis(1000);
+
+        assert_eq!(cycle_interval(None, min, max), max);
+        assert_eq!(cycle_interval(Some(5), min, max), min);
+        assert_eq!(cycle_interval(Some(-10), min, max), min);
+        assert_eq!(cycle_interval(Some(60), min, max), min);
+        assert_eq!(
+            cycle_interval(Some(330), min, max),
+            Duration::from_millis(550)
+        );
+        assert_eq!(cycle_interval(Some(600), min, max), max);
+        assert_eq!(cycle_interval(Some(3600), min, max), max);
+    }
+}
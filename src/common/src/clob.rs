@@ -211,9 +211,13 @@ impl ClobClient {
     /// Create a new CLOB client.
     pub fn new(config: &Config) -> Self {
         // Use the market channel endpoint
-        let ws_url = format!("{}/market", config.clob_ws_url);
+        Self::with_url(format!("{}/market", config.clob_ws_url))
+    }
+
+    /// Create a client for the channel at `ws_url` exactly, e.g. a local mock server.
+    pub fn with_url(ws_url: impl Into<String>) -> Self {
         Self {
-            ws_url,
+            ws_url: ws_url.into(),
             reconnect_delay: Duration::from_secs(1),
             max_reconnect_delay: Duration::from_secs(30),
         }
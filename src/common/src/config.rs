@@ -1,6 +1,7 @@
 //! Configuration loading from environment variables.
 
 use std::env;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -12,6 +13,12 @@ pub enum ConfigError {
     InvalidUrl(String),
 }
 
+/// Default cap on pooled database connections.
+const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+
+/// Default wait for a pooled connection, in seconds.
+const DEFAULT_ACQUIRE_TIMEOUT_SECS: u64 = 30;
+
 /// Application configuration loaded from environment.
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -26,6 +33,15 @@ pub struct Config {
 
     /// Market scanner poll interval in seconds
     pub scan_interval_secs: u64,
+
+    /// Upper bound on pooled database connections
+    pub max_connections: u32,
+
+    /// Connections the pool keeps open even when idle
+    pub min_connections: u32,
+
+    /// How long a query waits for a free pooled connection
+    pub acquire_timeout: Duration,
 }
 
 impl Config {
@@ -38,18 +54,32 @@ impl Config {
     /// - GAMMA_API_URL: Gamma API base URL
     /// - CLOB_WS_URL: CLOB WebSocket URL
     /// - SCAN_INTERVAL_SECS: Poll interval (default: 60)
+    /// - DB_MAX_CONNECTIONS: Pool size cap (default: 10)
+    /// - DB_MIN_CONNECTIONS: Idle connections kept open (default: 0, capped at max)
+    /// - DB_ACQUIRE_TIMEOUT_SECS: Wait for a pooled connection (default: 30)
     pub fn from_env() -> Result<Self, ConfigError> {
         // Load .env file if present
         dotenvy::dotenv().ok();
         Self::from_env_only()
     }
 
+    /// Load configuration for a run that never connects to the database
+    /// (e.g. `--no-db`). DATABASE_URL is not required and is left empty.
+    pub fn from_env_without_db() -> Self {
+        dotenvy::dotenv().ok();
+        Self::with_database_url(String::new())
+    }
+
     /// Load configuration from environment variables only (no .env file).
     /// Useful for testing.
     pub fn from_env_only() -> Result<Self, ConfigError> {
         let database_url = env::var("DATABASE_URL")
             .map_err(|_| ConfigError::MissingVar("DATABASE_URL".to_string()))?;
+        Ok(Self::with_database_url(database_url))
+    }
 
+    /// Read everything except DATABASE_URL from the environment.
+    fn with_database_url(database_url: String) -> Self {
         let gamma_api_url = env::var("GAMMA_API_URL")
             .unwrap_or_else(|_| "https://gamma-api.polymarket.com".to_string());
 
@@ -61,15 +91,35 @@ impl Config {
             .and_then(|s| s.parse().ok())
             .unwrap_or(60);
 
-        Ok(Self {
+        let max_connections = env_parse::<u32>("DB_MAX_CONNECTIONS")
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_MAX_CONNECTIONS);
+        let min_connections = env_parse::<u32>("DB_MIN_CONNECTIONS")
+            .unwrap_or(0)
+            .min(max_connections);
+        let acquire_timeout = Duration::from_secs(
+            env_parse::<u64>("DB_ACQUIRE_TIMEOUT_SECS")
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_ACQUIRE_TIMEOUT_SECS),
+        );
+
+        Self {
             database_url,
             gamma_api_url,
             clob_ws_url,
             scan_interval_secs,
-        })
+            max_connections,
+            min_connections,
+            acquire_timeout,
+        }
     }
 }
 
+/// Parse an optional numeric variable; unset or unparseable reads as None.
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().and_then(|s| s.trim().parse().ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,6 +142,19 @@ mod tests {
         }
     }
 
+    #[test]
+    #[serial]
+    fn test_config_without_db_needs_no_database_url() {
+        env::remove_var("DATABASE_URL");
+        env::set_var("CLOB_WS_URL", "ws://127.0.0.1:9000");
+
+        let config = Config::from_env_without_db();
+        assert!(config.database_url.is_empty());
+        assert_eq!(config.clob_ws_url, "ws://127.0.0.1:9000");
+
+        env::remove_var("CLOB_WS_URL");
+    }
+
     #[test]
     #[serial]
     fn test_config_with_defaults() {
@@ -107,8 +170,49 @@ mod tests {
             "wss://ws-subscriptions-clob.polymarket.com/ws"
         );
         assert_eq!(config.scan_interval_secs, 60);
+        assert_eq!(config.max_connections, 10);
+        assert_eq!(config.min_connections, 0);
+        assert_eq!(config.acquire_timeout, Duration::from_secs(30));
 
         // Cleanup
         env::remove_var("DATABASE_URL");
     }
+
+    #[test]
+    #[serial]
+    fn test_config_pool_sizing() {
+        env::set_var("DATABASE_URL", "postgres://localhost/test");
+        env::set_var("DB_MAX_CONNECTIONS", "25");
+        env::set_var("DB_MIN_CONNECTIONS", "5");
+        env::set_var("DB_ACQUIRE_TIMEOUT_SECS", "8");
+
+        let config = Config::from_env_only().unwrap();
+        assert_eq!(config.max_connections, 25);
+        assert_eq!(config.min_connections, 5);
+        assert_eq!(config.acquire_timeout, Duration::from_secs(8));
+
+        // min never exceeds max
+        env::set_var("DB_MAX_CONNECTIONS", "2");
+        let config = Config::from_env_only().unwrap();
+        assert_eq!(config.max_connections, 2);
+        assert_eq!(config.min_connections, 2);
+
+        // Zero or garbage falls back to the defaults
+        env::set_var("DB_MAX_CONNECTIONS", "0");
+        env::set_var("DB_MIN_CONNECTIONS", "lots");
+        env::set_var("DB_ACQUIRE_TIMEOUT_SECS", "0");
+        let config = Config::from_env_only().unwrap();
+        assert_eq!(config.max_connections, 10);
+        assert_eq!(config.min_connections, 0);
+        assert_eq!(config.acquire_timeout, Duration::from_secs(30));
+
+        for var in [
+            "DATABASE_URL",
+            "DB_MAX_CONNECTIONS",
+            "DB_MIN_CONNECTIONS",
+            "DB_ACQUIRE_TIMEOUT_SECS",
+        ] {
+            env::remove_var(var);
+        }
+    }
 }
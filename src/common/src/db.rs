@@ -2,6 +2,7 @@
 
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use thiserror::Error;
+use tracing::info;
 
 use crate::Config;
 
@@ -21,13 +22,22 @@ pub struct Database {
 }
 
 impl Database {
-    /// Create a new database connection pool.
+    /// Create a new database connection pool sized by `config`.
     pub async fn connect(config: &Config) -> Result<Self, DbError> {
         let pool = PgPoolOptions::new()
-            .max_connections(10)
+            .max_connections(config.max_connections)
+            .min_connections(config.min_connections)
+            .acquire_timeout(config.acquire_timeout)
             .connect(&config.database_url)
             .await?;
 
+        info!(
+            "Database pool: max {} / min {} connections, acquire timeout {}s",
+            config.max_connections,
+            config.min_connections,
+            config.acquire_timeout.as_secs()
+        );
+
         Ok(Self { pool })
     }
 
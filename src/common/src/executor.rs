@@ -3,35 +3,149 @@
 //! Provides reusable components for dry-run portfolio tracking,
 //! Polymarket SDK authentication, and order execution.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::str::FromStr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use alloy::primitives::Address;
 use alloy::signers::local::PrivateKeySigner;
 use alloy::signers::Signer;
-use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use polymarket_client_sdk::clob::types::SignatureType;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::stream::{self, StreamExt};
+use polymarket_client_sdk::clob::types::request::OrdersRequest;
+use polymarket_client_sdk::clob::types::{BalanceAllowanceRequest, OrderType, SignatureType};
 use polymarket_client_sdk::clob::{Client as ClobClient, Config as ClobConfig};
 use polymarket_client_sdk::POLYGON;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::{
-    get_market_resolutions_batch, upsert_market_resolution, GammaClient, MarketResolutionInsert,
+    GammaApi, MarketRepository, MarketResolutionInsert, MarketWithOrderbook, ResolutionDetail,
+    SettlementInsert,
 };
 
 const CLOB_HOST: &str = "https://clob.polymarket.com";
 const ORDER_TIMEOUT_SECS: u64 = 30;
 
-/// Maximum allowed shares per order (sanity check)
-pub const MAX_SHARES: Decimal = dec!(99.99);
+/// Default cap on shares per order (sanity check); traders override it
+/// with `--max-shares`.
+pub const DEFAULT_MAX_SHARES: Decimal = dec!(99.99);
+
+/// Decimal places the CLOB accepts for order sizes.
+const SHARE_DECIMALS: u32 = 2;
+
+/// Check a normalized order size against the CLOB's lot size and `max_shares`.
+///
+/// Sizes that round to zero at [`SHARE_DECIMALS`] places, or exceed the cap,
+/// are rejected before anything is signed.
+pub fn check_order_size(shares: Decimal, max_shares: Decimal) -> Result<()> {
+    let shares = shares.round_dp(SHARE_DECIMALS);
+    if shares <= Decimal::ZERO {
+        bail!(
+            "Order size {} rounds to zero at {} decimal places",
+            shares,
+            SHARE_DECIMALS
+        );
+    }
+    if shares > max_shares {
+        bail!("Order size {} exceeds max shares {}", shares, max_shares);
+    }
+    Ok(())
+}
+
+/// Normalize an order's price and size to exactly what gets submitted.
+///
+/// Log the returned values (with [`fmt_price`] / [`fmt_shares`]) so log lines
+/// reconcile with fills.
+pub fn normalize_order(price: Decimal, shares: Decimal) -> (Decimal, Decimal) {
+    (price.normalize(), shares.round_dp(SHARE_DECIMALS))
+}
+
+/// Tick size assumed when the CLOB can't tell us a token's own.
+pub const DEFAULT_TICK_SIZE: Decimal = dec!(0.01);
+
+/// Round a limit price onto the market's tick size in the given direction.
+///
+/// Buys round down ([`RoundingStrategy::ToNegativeInfinity`]) and sells round
+/// up ([`RoundingStrategy::ToPositiveInfinity`]) so snapping never pays more
+/// or accepts less than the caller asked for. The result is kept within
+/// `[tick, 1 - tick]` so it is always a price the CLOB accepts. A
+/// non-positive tick leaves the price unrounded.
+pub fn normalize_price_to_tick(
+    price: Decimal,
+    tick_size: Decimal,
+    rounding: RoundingStrategy,
+) -> Decimal {
+    if tick_size <= Decimal::ZERO {
+        return price.normalize();
+    }
+    let ticks = (price / tick_size).round_dp_with_strategy(0, rounding);
+    (ticks * tick_size)
+        .clamp(tick_size, Decimal::ONE - tick_size)
+        .normalize()
+}
+
+/// Look up a token's minimum tick size, falling back to
+/// [`DEFAULT_TICK_SIZE`]. The SDK caches it per token, so this is free once
+/// the cache is warm.
+async fn token_tick_size(auth: &CachedAuth, token_id: &str) -> Decimal {
+    match timeout(
+        Duration::from_secs(ORDER_TIMEOUT_SECS),
+        auth.client.tick_size(token_id),
+    )
+    .await
+    {
+        Ok(Ok(response)) => Decimal::from(response.minimum_tick_size),
+        Ok(Err(e)) => {
+            warn!(
+                "[TICK] Token {}: {:#}, assuming {}",
+                token_id, e, DEFAULT_TICK_SIZE
+            );
+            DEFAULT_TICK_SIZE
+        }
+        Err(_) => {
+            warn!(
+                "[TICK] Token {}: tick size lookup timed out, assuming {}",
+                token_id, DEFAULT_TICK_SIZE
+            );
+            DEFAULT_TICK_SIZE
+        }
+    }
+}
+
+/// Minimum tick size of `token_id`'s market, authenticating first if needed.
+/// Falls back to [`DEFAULT_TICK_SIZE`] when the CLOB can't be reached.
+pub async fn fetch_tick_size(cached_auth: &mut Option<CachedAuth>, token_id: &str) -> Decimal {
+    match ensure_authenticated(cached_auth).await {
+        Ok(auth) => token_tick_size(auth, token_id).await,
+        Err(e) => {
+            warn!(
+                "[TICK] Token {}: {:#}, assuming {}",
+                token_id, e, DEFAULT_TICK_SIZE
+            );
+            DEFAULT_TICK_SIZE
+        }
+    }
+}
+
+/// Format an order price exactly, without display rounding (e.g. `$0.455`).
+pub fn fmt_price(price: Decimal) -> String {
+    format!("${}", price.normalize())
+}
+
+/// Format an order size exactly as it is submitted (e.g. `12.5`).
+pub fn fmt_shares(shares: Decimal) -> String {
+    shares.round_dp(SHARE_DECIMALS).normalize().to_string()
+}
 
-/// Maximum resolution retry attempts before force-expiring a position
-const MAX_RESOLUTION_RETRIES: u32 = 30; // 30 retries with backoff = ~2 hours total
+/// Default resolution retry attempts before a position's settlement times out
+pub const DEFAULT_MAX_RESOLUTION_RETRIES: u32 = 30; // 30 retries with backoff = ~2 hours total
 
 /// Calculate backoff delay in seconds based on retry count.
 /// Uses exponential backoff: 60s, 120s, 240s... capped at 600s (10 min)
@@ -42,6 +156,375 @@ fn resolution_backoff_secs(retries: u32) -> i64 {
     delay.min(max_delay)
 }
 
+/// How to account for dry-run positions still unresolved when a run ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnresolvedPolicy {
+    /// Value each position at its last known price
+    #[default]
+    MarkToMarket,
+    /// Treat every unresolved position as a total loss
+    AssumeLoss,
+    /// Treat as a win if the last known price is above 0.50, otherwise a loss
+    WinIfAboveHalf,
+    /// Leave positions pending (excluded from realized P&L)
+    LeavePending,
+}
+
+impl UnresolvedPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnresolvedPolicy::MarkToMarket => "mark-to-market",
+            UnresolvedPolicy::AssumeLoss => "assume-loss",
+            UnresolvedPolicy::WinIfAboveHalf => "win-if-above-half",
+            UnresolvedPolicy::LeavePending => "leave-pending",
+        }
+    }
+
+    /// P&L for settling `pos` under this policy, or None to leave it pending.
+    pub fn settle_pnl(&self, pos: &SimulatedPosition) -> Option<Decimal> {
+        let mark = pos.mark_price();
+        match self {
+            UnresolvedPolicy::MarkToMarket => Some(pos.shares * mark - pos.cost),
+            UnresolvedPolicy::AssumeLoss => Some(-pos.cost),
+            UnresolvedPolicy::WinIfAboveHalf if mark > dec!(0.5) => Some(pos.shares - pos.cost),
+            UnresolvedPolicy::WinIfAboveHalf => Some(-pos.cost),
+            UnresolvedPolicy::LeavePending => None,
+        }
+    }
+}
+
+impl FromStr for UnresolvedPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mark-to-market" | "mtm" => Ok(UnresolvedPolicy::MarkToMarket),
+            "assume-loss" | "loss" => Ok(UnresolvedPolicy::AssumeLoss),
+            "win-if-above-half" => Ok(UnresolvedPolicy::WinIfAboveHalf),
+            "leave-pending" | "pending" => Ok(UnresolvedPolicy::LeavePending),
+            other => Err(format!(
+                "Unknown unresolved policy '{}' (expected mark-to-market, assume-loss, win-if-above-half, leave-pending)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for UnresolvedPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// How to account for an expired position whose resolution never arrives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SettlementTimeoutPolicy {
+    /// Count the full stake as a loss
+    #[default]
+    Loss,
+    /// Drop the position from P&L and count it separately
+    Unknown,
+    /// Settle at zero P&L (neither a win nor a loss)
+    Breakeven,
+}
+
+impl SettlementTimeoutPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettlementTimeoutPolicy::Loss => "loss",
+            SettlementTimeoutPolicy::Unknown => "unknown",
+            SettlementTimeoutPolicy::Breakeven => "breakeven",
+        }
+    }
+
+    /// P&L for a timed-out position costing `cost`, or None to exclude it from P&L.
+    pub fn timeout_pnl(&self, cost: Decimal) -> Option<Decimal> {
+        match self {
+            SettlementTimeoutPolicy::Loss => Some(-cost),
+            SettlementTimeoutPolicy::Unknown => None,
+            SettlementTimeoutPolicy::Breakeven => Some(Decimal::ZERO),
+        }
+    }
+}
+
+impl FromStr for SettlementTimeoutPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "loss" => Ok(SettlementTimeoutPolicy::Loss),
+            "unknown" => Ok(SettlementTimeoutPolicy::Unknown),
+            "breakeven" => Ok(SettlementTimeoutPolicy::Breakeven),
+            other => Err(format!(
+                "Unknown settlement timeout policy '{}' (expected loss, unknown, breakeven)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SettlementTimeoutPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Retry cap and timeout treatment used when resolving expired positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettlementTimeout {
+    pub max_retries: u32,
+    pub policy: SettlementTimeoutPolicy,
+}
+
+impl Default for SettlementTimeout {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RESOLUTION_RETRIES,
+            policy: SettlementTimeoutPolicy::default(),
+        }
+    }
+}
+
+/// Payout per share when a market resolves 50/50 instead of to a winner.
+pub const AMBIGUOUS_PAYOUT: Decimal = dec!(0.5);
+
+/// `winning_side` recorded in `settlements` for a 50/50 resolution.
+pub const AMBIGUOUS_WINNING_SIDE: &str = "TIE";
+
+/// Realized P&L for `shares` bought for `cost` in a market that resolved 50/50.
+pub fn ambiguous_settlement_pnl(shares: Decimal, cost: Decimal) -> Decimal {
+    shares * AMBIGUOUS_PAYOUT - cost
+}
+
+/// `winning_side` recorded in `settlements` for a position settled by the
+/// timeout policy because its resolution never arrived.
+pub const TIMED_OUT_WINNING_SIDE: &str = "N/A";
+
+/// Settlement row for a position settled under `policy` after its resolution
+/// never arrived. A position the policy keeps out of P&L is recorded at zero.
+pub fn timed_out_settlement(
+    market_id: Uuid,
+    side: &str,
+    shares: Decimal,
+    cost: Decimal,
+    policy: SettlementTimeoutPolicy,
+    is_dry_run: bool,
+    resolved_at: DateTime<Utc>,
+) -> SettlementInsert {
+    SettlementInsert {
+        market_id,
+        side: side.to_uppercase(),
+        shares,
+        cost,
+        winning_side: TIMED_OUT_WINNING_SIDE.to_string(),
+        pnl: policy.timeout_pnl(cost).unwrap_or(Decimal::ZERO),
+        is_dry_run,
+        resolved_at,
+    }
+}
+
+/// Which trade sides a strategy may act on. Signals for a disabled side are
+/// still detected but not traded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SideFilter {
+    #[default]
+    Both,
+    YesOnly,
+    NoOnly,
+}
+
+impl SideFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SideFilter::Both => "both",
+            SideFilter::YesOnly => "yes-only",
+            SideFilter::NoOnly => "no-only",
+        }
+    }
+
+    /// Whether a trade on `side` ("YES" or "NO") is allowed.
+    pub fn allows(&self, side: &str) -> bool {
+        match self {
+            SideFilter::Both => true,
+            SideFilter::YesOnly => side.eq_ignore_ascii_case("YES"),
+            SideFilter::NoOnly => side.eq_ignore_ascii_case("NO"),
+        }
+    }
+}
+
+impl FromStr for SideFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "both" => Ok(SideFilter::Both),
+            "yes-only" | "yes" => Ok(SideFilter::YesOnly),
+            "no-only" | "no" => Ok(SideFilter::NoOnly),
+            other => Err(format!(
+                "Unknown side filter '{}' (expected both, yes-only, no-only)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SideFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Where an entry order is priced relative to the book. `Ask` crosses the
+/// spread; the other modes post a passive order that rests until filled or
+/// cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EntryOffset {
+    #[default]
+    Ask,
+    Midpoint,
+    JoinBid,
+}
+
+impl EntryOffset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryOffset::Ask => "ask",
+            EntryOffset::Midpoint => "midpoint",
+            EntryOffset::JoinBid => "join-bid",
+        }
+    }
+
+    /// Whether orders in this mode rest on the book instead of taking.
+    pub fn is_passive(&self) -> bool {
+        !matches!(self, EntryOffset::Ask)
+    }
+
+    /// Entry price for a book with `best_ask` and optional `best_bid`.
+    ///
+    /// Passive prices stay strictly below the ask: the midpoint is rounded
+    /// down to `tick_size`, and join-bid falls back to the bid itself when
+    /// the spread is a single tick. Without a bid there is nothing to price
+    /// against, so every mode returns the ask.
+    pub fn price(
+        &self,
+        best_bid: Option<Decimal>,
+        best_ask: Decimal,
+        tick_size: Decimal,
+    ) -> Decimal {
+        let bid = match best_bid {
+            Some(bid) if bid > Decimal::ZERO && bid < best_ask && tick_size > Decimal::ZERO => bid,
+            _ => return best_ask,
+        };
+        match self {
+            EntryOffset::Ask => best_ask,
+            EntryOffset::Midpoint => {
+                let mid = (bid + best_ask) / dec!(2);
+                ((mid / tick_size).floor() * tick_size).max(bid).normalize()
+            }
+            EntryOffset::JoinBid => {
+                let price = bid + tick_size;
+                if price < best_ask {
+                    price.normalize()
+                } else {
+                    bid
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for EntryOffset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ask" => Ok(EntryOffset::Ask),
+            "midpoint" | "mid" => Ok(EntryOffset::Midpoint),
+            "join-bid" | "bid" => Ok(EntryOffset::JoinBid),
+            other => Err(format!(
+                "Unknown entry offset '{}' (expected ask, midpoint, join-bid)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for EntryOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// How an entry order meets the book: resting at its limit price, or taking
+/// what is available right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrderKind {
+    /// Good-til-cancelled limit order at the configured price
+    #[default]
+    Limit,
+    /// Fill-and-kill at the current best ask; any unfilled size is cancelled
+    /// by the exchange instead of resting
+    Marketable,
+    /// Fill-or-kill at the current best ask: the whole size fills now or the
+    /// order is rejected, so there is never a partial position
+    FillOrKill,
+}
+
+impl OrderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderKind::Limit => "limit",
+            OrderKind::Marketable => "marketable",
+            OrderKind::FillOrKill => "fok",
+        }
+    }
+
+    /// Time in force passed to the SDK order builder.
+    pub fn sdk_order_type(&self) -> OrderType {
+        match self {
+            OrderKind::Limit => OrderType::GTC,
+            OrderKind::Marketable => OrderType::FAK,
+            OrderKind::FillOrKill => OrderType::FOK,
+        }
+    }
+
+    /// Price to send: `limit_price` for a resting order, `best_ask` for an
+    /// immediate one.
+    pub fn order_price(&self, limit_price: Decimal, best_ask: Decimal) -> Decimal {
+        match self {
+            OrderKind::Limit => limit_price,
+            OrderKind::Marketable | OrderKind::FillOrKill => best_ask,
+        }
+    }
+
+    /// Whether an unfilled order stays on the book and may need cancelling.
+    pub fn rests(&self) -> bool {
+        matches!(self, OrderKind::Limit)
+    }
+}
+
+impl FromStr for OrderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "limit" => Ok(OrderKind::Limit),
+            "marketable" | "fak" => Ok(OrderKind::Marketable),
+            "fok" => Ok(OrderKind::FillOrKill),
+            other => Err(format!(
+                "Unknown order type '{}' (expected limit, marketable, fok)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OrderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Simulated position for dry-run portfolio tracking.
 #[derive(Debug, Clone)]
 pub struct SimulatedPosition {
@@ -64,6 +547,8 @@ pub struct SimulatedPosition {
     pub effective_fill_price: Decimal,
     /// Actual cost = shares * effective_fill_price
     pub cost: Decimal,
+    /// Latest market price for our side (best bid, else best ask), refreshed each cycle
+    pub last_price: Decimal,
     pub end_time: DateTime<Utc>,
     /// When the position was created (for staleness tracking)
     pub created_at: DateTime<Utc>,
@@ -73,6 +558,148 @@ pub struct SimulatedPosition {
     pub last_retry_time: Option<DateTime<Utc>>,
 }
 
+impl SimulatedPosition {
+    /// Last known market price for our side, used to value unresolved positions.
+    pub fn mark_price(&self) -> Decimal {
+        self.last_price
+    }
+
+    /// Whether we have a usable current price for this position.
+    pub fn has_price(&self) -> bool {
+        self.last_price > Decimal::ZERO
+    }
+
+    /// Unrealized P&L at the last known price.
+    pub fn unrealized_pnl(&self) -> Decimal {
+        self.shares * (self.last_price - self.effective_fill_price)
+    }
+}
+
+/// Combined view of a market where both YES and NO are held.
+///
+/// YES + NO pays exactly $1 at resolution, so the matched shares are a hedge
+/// with a guaranteed payout; only the unmatched remainder is still at risk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetPosition {
+    pub market_id: Uuid,
+    pub market_name: String,
+    pub yes_shares: Decimal,
+    pub no_shares: Decimal,
+    pub yes_cost: Decimal,
+    pub no_cost: Decimal,
+    /// Last known price per side (None if unpriced)
+    pub yes_mark: Option<Decimal>,
+    pub no_mark: Option<Decimal>,
+}
+
+impl NetPosition {
+    /// Build the net view from one market's legs; None unless both sides are held.
+    pub fn from_legs<'a>(legs: impl IntoIterator<Item = &'a SimulatedPosition>) -> Option<Self> {
+        let mut net: Option<NetPosition> = None;
+        for leg in legs {
+            let net = net.get_or_insert_with(|| NetPosition {
+                market_id: leg.market_id,
+                market_name: leg.market_name.clone(),
+                yes_shares: Decimal::ZERO,
+                no_shares: Decimal::ZERO,
+                yes_cost: Decimal::ZERO,
+                no_cost: Decimal::ZERO,
+                yes_mark: None,
+                no_mark: None,
+            });
+            let mark = leg.has_price().then_some(leg.last_price);
+            if leg.side == "YES" {
+                net.yes_shares += leg.shares;
+                net.yes_cost += leg.cost;
+                net.yes_mark = mark.or(net.yes_mark);
+            } else {
+                net.no_shares += leg.shares;
+                net.no_cost += leg.cost;
+                net.no_mark = mark.or(net.no_mark);
+            }
+        }
+        net.filter(|n| n.yes_shares > Decimal::ZERO && n.no_shares > Decimal::ZERO)
+    }
+
+    /// Combined cost of both legs.
+    pub fn cost(&self) -> Decimal {
+        self.yes_cost + self.no_cost
+    }
+
+    /// Shares held on both sides.
+    pub fn hedged_shares(&self) -> Decimal {
+        self.yes_shares.min(self.no_shares)
+    }
+
+    /// Paid at resolution whichever side wins ($1 per hedged share).
+    pub fn guaranteed_payout(&self) -> Decimal {
+        self.hedged_shares()
+    }
+
+    /// P&L at resolution if YES (`true`) or NO wins.
+    pub fn pnl_if(&self, yes_wins: bool) -> Decimal {
+        let payout = if yes_wins {
+            self.yes_shares
+        } else {
+            self.no_shares
+        };
+        payout - self.cost()
+    }
+
+    /// P&L at resolution if the heavier side loses.
+    pub fn worst_case_pnl(&self) -> Decimal {
+        self.guaranteed_payout() - self.cost()
+    }
+
+    /// Unrealized P&L: hedged shares at their $1 payout, the remainder at its
+    /// side's last price (or its fill price, i.e. flat, when unpriced).
+    pub fn unrealized_pnl(&self) -> Decimal {
+        let hedged = self.hedged_shares();
+        let (excess, excess_shares, excess_cost, excess_mark) = if self.yes_shares > hedged {
+            (
+                self.yes_shares - hedged,
+                self.yes_shares,
+                self.yes_cost,
+                self.yes_mark,
+            )
+        } else {
+            (
+                self.no_shares - hedged,
+                self.no_shares,
+                self.no_cost,
+                self.no_mark,
+            )
+        };
+        let excess_value = match excess_mark {
+            Some(mark) => excess * mark,
+            None if excess_shares > Decimal::ZERO => excess * excess_cost / excess_shares,
+            None => Decimal::ZERO,
+        };
+        hedged + excess_value - self.cost()
+    }
+}
+
+/// Realized results for one timeframe of a dry-run portfolio.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeframeStats {
+    /// Cost of the realized positions
+    pub invested: Decimal,
+    pub pnl: Decimal,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+impl TimeframeStats {
+    pub fn win_rate(&self) -> f64 {
+        let total = self.wins + self.losses;
+        if total > 0 {
+            (self.wins as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Dry-run portfolio tracker.
 #[derive(Debug, Default)]
 pub struct DryRunPortfolio {
@@ -82,6 +709,16 @@ pub struct DryRunPortfolio {
     pub realized_wins: u32,
     pub realized_losses: u32,
     pub pending_count: u32,
+    /// Positions that timed out under `SettlementTimeoutPolicy::Unknown` (excluded from P&L)
+    pub timed_out_unknown: u32,
+    /// Realized results keyed by market timeframe
+    by_timeframe: HashMap<String, TimeframeStats>,
+    /// P&L realized so far by partially closed positions, keyed by (market, side)
+    partial_pnl: HashMap<(Uuid, String), Decimal>,
+    /// Virtual starting bankroll; None leaves capital unbounded
+    starting_balance: Option<Decimal>,
+    /// Positions refused because they would overdraw the virtual balance
+    pub balance_rejections: u32,
 }
 
 impl DryRunPortfolio {
@@ -89,15 +726,150 @@ impl DryRunPortfolio {
         Self::default()
     }
 
-    pub fn add_position(&mut self, position: SimulatedPosition) {
+    /// Bound the portfolio to a virtual bankroll of `balance` USDC.
+    pub fn with_balance(mut self, balance: Decimal) -> Self {
+        self.starting_balance = Some(balance);
+        self
+    }
+
+    /// Cost of the positions still open.
+    pub fn open_cost(&self) -> Decimal {
+        self.positions.iter().map(|p| p.cost).sum()
+    }
+
+    /// Capital free for new positions: starting balance plus realized P&L,
+    /// minus what open positions tie up. None without a virtual balance.
+    pub fn available_balance(&self) -> Option<Decimal> {
+        self.starting_balance
+            .map(|start| start + self.total_pnl - self.open_cost())
+    }
+
+    /// Add a position, or refuse it (returning false) if its cost exceeds the
+    /// available virtual balance.
+    pub fn add_position(&mut self, mut position: SimulatedPosition) -> bool {
+        if let Some(available) = self.available_balance() {
+            if position.cost > available {
+                self.balance_rejections += 1;
+                info!(
+                    "[PORTFOLIO] Insufficient virtual balance for {} {}: cost ${:.2} > available ${:.2}",
+                    position.side, position.market_name, position.cost, available
+                );
+                return false;
+            }
+        }
+        if position.last_price.is_zero() {
+            position.last_price = position.best_ask_price;
+        }
         self.total_invested += position.cost;
         self.pending_count += 1;
         self.positions.push(position);
+        true
+    }
+
+    /// Fold a realized position into its timeframe's stats. `won` is None for
+    /// outcomes counted as neither a win nor a loss.
+    fn record_timeframe(&mut self, pos: &SimulatedPosition, pnl: Decimal, won: Option<bool>) {
+        let stats = self.by_timeframe.entry(pos.timeframe.clone()).or_default();
+        stats.invested += pos.cost;
+        stats.pnl += pnl;
+        match won {
+            Some(true) => stats.wins += 1,
+            Some(false) => stats.losses += 1,
+            None => {}
+        }
+    }
+
+    /// Forget the tranche P&L of a position that is leaving the portfolio,
+    /// returning what its earlier partial closes realized.
+    fn clear_partial(&mut self, pos: &SimulatedPosition) -> Decimal {
+        self.partial_pnl
+            .remove(&(pos.market_id, pos.side.clone()))
+            .unwrap_or_default()
+    }
+
+    /// Realized invested, P&L and win/loss counts per timeframe (e.g. "5m", "15m").
+    pub fn summary_by_timeframe(&self) -> HashMap<String, TimeframeStats> {
+        self.by_timeframe.clone()
+    }
+
+    /// Settle a position whose resolution did not arrive within the retry cap.
+    async fn settle_timed_out(
+        &mut self,
+        repo: &dyn MarketRepository,
+        pos: &SimulatedPosition,
+        policy: SettlementTimeoutPolicy,
+    ) {
+        self.pending_count = self.pending_count.saturating_sub(1);
+        self.clear_partial(pos);
+        let settlement = timed_out_settlement(
+            pos.market_id,
+            &pos.side,
+            pos.shares,
+            pos.cost,
+            policy,
+            true,
+            Utc::now(),
+        );
+        if let Err(e) = repo.insert_settlement(&settlement).await {
+            warn!("[PORTFOLIO] Failed to record settlement: {}", e);
+        }
+        match policy.timeout_pnl(pos.cost) {
+            Some(pnl) => {
+                self.total_pnl += pnl;
+                if pnl < Decimal::ZERO {
+                    self.realized_losses += 1;
+                    self.record_timeframe(pos, pnl, Some(false));
+                } else {
+                    self.record_timeframe(pos, pnl, None);
+                }
+            }
+            None => self.timed_out_unknown += 1,
+        }
+    }
+
+    /// Settle a position whose market resolved 50/50: each share pays
+    /// [`AMBIGUOUS_PAYOUT`], counted as neither a win nor a loss.
+    async fn settle_ambiguous(
+        &mut self,
+        repo: &dyn MarketRepository,
+        pos: &SimulatedPosition,
+        resolved_at: DateTime<Utc>,
+    ) {
+        self.pending_count = self.pending_count.saturating_sub(1);
+        self.clear_partial(pos);
+        let pnl = ambiguous_settlement_pnl(pos.shares, pos.cost);
+        self.total_pnl += pnl;
+        self.record_timeframe(pos, pnl, None);
+        info!(
+            "[PORTFOLIO] ⚖️ SPLIT: {} {} resolved 50/50 -> ${:.2}",
+            pos.side, pos.market_name, pnl
+        );
+
+        let settlement = SettlementInsert {
+            market_id: pos.market_id,
+            side: pos.side.to_uppercase(),
+            shares: pos.shares,
+            cost: pos.cost,
+            winning_side: AMBIGUOUS_WINNING_SIDE.to_string(),
+            pnl,
+            is_dry_run: true,
+            resolved_at,
+        };
+        if let Err(e) = repo.insert_settlement(&settlement).await {
+            warn!("[PORTFOLIO] Failed to record settlement: {}", e);
+        }
     }
 
     /// Resolve expired positions and calculate P&L using actual market resolutions.
     /// Fetches from Gamma API if not in database, then records to database.
-    pub async fn resolve_expired(&mut self, pool: &sqlx::PgPool, gamma: &GammaClient) -> bool {
+    /// Positions still unresolved after `settlement.max_retries` attempts are
+    /// settled according to `settlement.policy`.
+    pub async fn resolve_expired(
+        &mut self,
+        repo: &dyn MarketRepository,
+        gamma: &dyn GammaApi,
+        settlement: SettlementTimeout,
+    ) -> bool {
         let now = Utc::now();
 
         // Find positions that have expired (with 60s buffer for resolution data)
@@ -116,7 +888,8 @@ impl DryRunPortfolio {
 
         // First, try to get resolutions from database
         let market_ids: Vec<Uuid> = expired.iter().map(|p| p.market_id).collect();
-        let db_resolutions = get_market_resolutions_batch(pool, &market_ids)
+        let db_resolutions = repo
+            .get_market_resolutions_batch(&market_ids)
             .await
             .unwrap_or_default();
 
@@ -126,7 +899,6 @@ impl DryRunPortfolio {
             .collect();
 
         let mut resolved_any = false;
-        let mut api_calls_made = 0u32;
 
         for pos in expired {
             // Check if we already have resolution from DB
@@ -144,15 +916,25 @@ impl DryRunPortfolio {
                     }
                 }
 
-                // Rate limit: add delay between API calls (max 2 per second)
-                if api_calls_made > 0 {
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                }
-                api_calls_made += 1;
-
-                // Fetch from Gamma API using token_id
-                match gamma.fetch_market_resolution(&pos.yes_token_id).await {
-                    Ok(Some(ws)) => {
+                // Fetch from Gamma API using token_id (the client paces its
+                // own requests)
+                match gamma
+                    .fetch_market_resolution_detail(&pos.yes_token_id)
+                    .await
+                {
+                    Ok(Some(detail)) if detail.ambiguous => {
+                        // Resolved 50/50: no winner will ever show up
+                        let mut pos = pos;
+                        pos.last_price = AMBIGUOUS_PAYOUT;
+                        self.settle_ambiguous(repo, &pos, detail.resolved_at.unwrap_or(now))
+                            .await;
+                        resolved_any = true;
+                        continue;
+                    }
+                    Ok(Some(ResolutionDetail {
+                        winning_side: Some(ws),
+                        ..
+                    })) => {
                         let ws_upper = ws.to_uppercase();
                         info!(
                             "[PORTFOLIO] Fetched resolution from API: {} -> {}",
@@ -171,26 +953,24 @@ impl DryRunPortfolio {
                             winning_side: ws_upper.clone(),
                             end_time: pos.end_time,
                         };
-                        if let Err(e) = upsert_market_resolution(pool, &insert).await {
+                        if let Err(e) = repo.upsert_market_resolution(&insert).await {
                             warn!("[PORTFOLIO] Failed to record resolution: {}", e);
                         }
 
                         resolution_map.insert(pos.market_id, ws_upper.clone());
                         ws_upper
                     }
-                    Ok(None) => {
+                    Ok(_) => {
                         // Not resolved yet - put back in queue with incremented retry count
                         let mut pos = pos;
                         pos.resolution_retries += 1;
                         pos.last_retry_time = Some(now);
-                        if pos.resolution_retries >= MAX_RESOLUTION_RETRIES {
+                        if pos.resolution_retries >= settlement.max_retries {
                             warn!(
-                                "[PORTFOLIO] ⚠️ EXPIRED: {} - max retries ({}) exceeded, treating as loss",
-                                pos.market_name, MAX_RESOLUTION_RETRIES
+                                "[PORTFOLIO] ⚠️ EXPIRED: {} - max retries ({}) exceeded, settling as {}",
+                                pos.market_name, settlement.max_retries, settlement.policy
                             );
-                            self.pending_count = self.pending_count.saturating_sub(1);
-                            self.total_pnl -= pos.cost;
-                            self.realized_losses += 1;
+                            self.settle_timed_out(repo, &pos, settlement.policy).await;
                             continue;
                         }
                         let next_backoff = resolution_backoff_secs(pos.resolution_retries);
@@ -198,7 +978,7 @@ impl DryRunPortfolio {
                             "[PORTFOLIO] Market {} not yet resolved (retry {}/{}, next in {}s)",
                             pos.market_name,
                             pos.resolution_retries,
-                            MAX_RESOLUTION_RETRIES,
+                            settlement.max_retries,
                             next_backoff
                         );
                         self.positions.push(pos);
@@ -209,20 +989,18 @@ impl DryRunPortfolio {
                         let mut pos = pos;
                         pos.resolution_retries += 1;
                         pos.last_retry_time = Some(now);
-                        if pos.resolution_retries >= MAX_RESOLUTION_RETRIES {
+                        if pos.resolution_retries >= settlement.max_retries {
                             warn!(
-                                "[PORTFOLIO] ⚠️ EXPIRED: {} - max retries ({}) exceeded after API errors, treating as loss",
-                                pos.market_name, MAX_RESOLUTION_RETRIES
+                                "[PORTFOLIO] ⚠️ EXPIRED: {} - max retries ({}) exceeded after API errors, settling as {}",
+                                pos.market_name, settlement.max_retries, settlement.policy
                             );
-                            self.pending_count = self.pending_count.saturating_sub(1);
-                            self.total_pnl -= pos.cost;
-                            self.realized_losses += 1;
+                            self.settle_timed_out(repo, &pos, settlement.policy).await;
                             continue;
                         }
                         let next_backoff = resolution_backoff_secs(pos.resolution_retries);
                         warn!(
                             "[PORTFOLIO] Failed to fetch resolution for {} (retry {}/{}, next in {}s): {}",
-                            pos.market_name, pos.resolution_retries, MAX_RESOLUTION_RETRIES, next_backoff, e
+                            pos.market_name, pos.resolution_retries, settlement.max_retries, next_backoff, e
                         );
                         self.positions.push(pos);
                         continue;
@@ -242,35 +1020,121 @@ impl DryRunPortfolio {
 
             resolved_any = true;
             self.pending_count = self.pending_count.saturating_sub(1);
+            self.clear_partial(&pos);
 
-            // Check if our bet won
+            // Check if our bet won; the position is now worth its payout
             let we_won = pos.side.to_uppercase() == winning_side;
+            let mut pos = pos;
+            pos.last_price = if we_won { Decimal::ONE } else { Decimal::ZERO };
 
-            if we_won {
+            let pnl = if we_won {
                 // Win: get $1 per share, profit = shares - cost
                 let payout = pos.shares;
                 let profit = payout - pos.cost;
                 self.total_pnl += profit;
                 self.realized_wins += 1;
+                self.record_timeframe(&pos, profit, Some(true));
                 info!(
                     "[PORTFOLIO] ✅ WIN: {} {} (mkt: ${:.2}) -> +${:.2} (resolved: {})",
                     pos.side, pos.market_name, pos.best_ask_price, profit, winning_side
                 );
+                profit
             } else {
                 // Loss: lose entire stake
                 let loss = pos.cost;
                 self.total_pnl -= loss;
                 self.realized_losses += 1;
+                self.record_timeframe(&pos, -loss, Some(false));
                 info!(
                     "[PORTFOLIO] ❌ LOSS: {} {} (mkt: ${:.2}) -> -${:.2} (resolved: {})",
                     pos.side, pos.market_name, pos.best_ask_price, loss, winning_side
                 );
+                -loss
+            };
+
+            let settlement = SettlementInsert {
+                market_id: pos.market_id,
+                side: pos.side.to_uppercase(),
+                shares: pos.shares,
+                cost: pos.cost,
+                winning_side,
+                pnl,
+                is_dry_run: true,
+                resolved_at: now,
+            };
+            if let Err(e) = repo.insert_settlement(&settlement).await {
+                warn!("[PORTFOLIO] Failed to record settlement: {}", e);
             }
         }
 
         resolved_any
     }
 
+    /// Refresh `last_price` on open positions from the latest orderbook snapshots.
+    /// Returns the number of positions updated.
+    pub fn update_last_prices(&mut self, markets: &[MarketWithOrderbook]) -> usize {
+        let mut updated = 0;
+        for pos in &mut self.positions {
+            let market = match markets.iter().find(|m| m.id == pos.market_id) {
+                Some(m) => m,
+                None => continue,
+            };
+            let price = if pos.side.eq_ignore_ascii_case("YES") {
+                market.yes_best_bid.or(market.yes_best_ask)
+            } else {
+                market.no_best_bid.or(market.no_best_ask)
+            };
+            if let Some(price) = price {
+                pos.last_price = price;
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// Total unrealized P&L of open positions at their last known prices.
+    /// Positions without a current price are excluded (see `unpriced_count`).
+    pub fn unrealized_pnl(&self) -> Decimal {
+        let net = self.net_positions();
+        let hedged: HashSet<Uuid> = net.iter().map(|n| n.market_id).collect();
+        let single: Decimal = self
+            .positions
+            .iter()
+            .filter(|p| p.has_price() && !hedged.contains(&p.market_id))
+            .map(|p| p.unrealized_pnl())
+            .sum();
+        single + net.iter().map(|n| n.unrealized_pnl()).sum::<Decimal>()
+    }
+
+    /// Number of open positions excluded from unrealized P&L for lack of a
+    /// price. Legs of hedged markets are always valued.
+    pub fn unpriced_count(&self) -> usize {
+        let hedged: HashSet<Uuid> = self.net_positions().iter().map(|n| n.market_id).collect();
+        self.positions
+            .iter()
+            .filter(|p| !p.has_price() && !hedged.contains(&p.market_id))
+            .count()
+    }
+
+    /// Markets where both YES and NO are held, netted into one position each.
+    pub fn net_positions(&self) -> Vec<NetPosition> {
+        let mut by_market: HashMap<Uuid, Vec<&SimulatedPosition>> = HashMap::new();
+        for pos in &self.positions {
+            by_market.entry(pos.market_id).or_default().push(pos);
+        }
+        let mut net: Vec<NetPosition> = by_market
+            .into_values()
+            .filter_map(NetPosition::from_legs)
+            .collect();
+        net.sort_by(|a, b| (&a.market_name, a.market_id).cmp(&(&b.market_name, b.market_id)));
+        net
+    }
+
+    /// Realized plus unrealized P&L.
+    pub fn total_equity_pnl(&self) -> Decimal {
+        self.total_pnl + self.unrealized_pnl()
+    }
+
     pub fn win_rate(&self) -> f64 {
         let total_trades = self.realized_wins + self.realized_losses;
         if total_trades > 0 {
@@ -285,11 +1149,15 @@ impl DryRunPortfolio {
     pub fn close_position(&mut self, market_id: Uuid, exit_price: Decimal, pnl: Decimal) {
         // Find and remove the position
         if let Some(pos_idx) = self.positions.iter().position(|p| p.market_id == market_id) {
-            let pos = self.positions.remove(pos_idx);
+            let mut pos = self.positions.remove(pos_idx);
+            pos.last_price = exit_price;
             self.pending_count = self.pending_count.saturating_sub(1);
             self.total_pnl += pnl;
+            // A position already scaled out of wins or loses on all its tranches
+            let won = self.clear_partial(&pos) + pnl >= Decimal::ZERO;
+            self.record_timeframe(&pos, pnl, Some(won));
 
-            if pnl >= Decimal::ZERO {
+            if won {
                 self.realized_wins += 1;
                 info!(
                     "[PORTFOLIO] ✅ CLOSED (profit): {} {} @ ${:.3} -> +${:.2}",
@@ -307,6 +1175,85 @@ impl DryRunPortfolio {
         }
     }
 
+    /// Close part of a position, e.g. one tranche of a chased exit.
+    ///
+    /// Realizes P&L on `shares_closed` (capped at the shares held) at
+    /// `exit_price` against a proportional slice of the cost. The position is
+    /// only removed once no shares remain; its win or loss is counted then, on
+    /// the P&L of all its tranches together. Returns the P&L of this tranche,
+    /// or None if there is no matching position.
+    pub fn close_partial(
+        &mut self,
+        market_id: Uuid,
+        side: &str,
+        shares_closed: Decimal,
+        exit_price: Decimal,
+    ) -> Option<Decimal> {
+        let pos_idx = match self
+            .positions
+            .iter()
+            .position(|p| p.market_id == market_id && p.side.eq_ignore_ascii_case(side))
+        {
+            Some(idx) => idx,
+            None => {
+                warn!(
+                    "[PORTFOLIO] {} position {} not found for partial close",
+                    side, market_id
+                );
+                return None;
+            }
+        };
+
+        let pos = &mut self.positions[pos_idx];
+        let closed = shares_closed.min(pos.shares);
+        if closed <= Decimal::ZERO {
+            return None;
+        }
+        let cost_closed = if closed == pos.shares {
+            pos.cost
+        } else {
+            pos.cost * closed / pos.shares
+        };
+        let pnl = closed * exit_price - cost_closed;
+
+        pos.shares -= closed;
+        pos.cost -= cost_closed;
+        pos.last_price = exit_price;
+        let remaining = pos.shares;
+        let mut tranche = pos.clone();
+        tranche.shares = closed;
+        tranche.cost = cost_closed;
+
+        self.total_pnl += pnl;
+        let key = (market_id, tranche.side.clone());
+        let position_pnl = self.partial_pnl.remove(&key).unwrap_or_default() + pnl;
+
+        if remaining > Decimal::ZERO {
+            self.partial_pnl.insert(key, position_pnl);
+            self.record_timeframe(&tranche, pnl, None);
+            info!(
+                "[PORTFOLIO] PARTIAL CLOSE: {} {} {} @ ${:.3} -> ${:.2} ({} shares left)",
+                tranche.side, tranche.market_name, closed, exit_price, pnl, remaining
+            );
+            return Some(pnl);
+        }
+
+        self.positions.remove(pos_idx);
+        self.pending_count = self.pending_count.saturating_sub(1);
+        let won = position_pnl >= Decimal::ZERO;
+        self.record_timeframe(&tranche, pnl, Some(won));
+        if won {
+            self.realized_wins += 1;
+        } else {
+            self.realized_losses += 1;
+        }
+        info!(
+            "[PORTFOLIO] CLOSED (final tranche): {} {} @ ${:.3} -> ${:.2} (position ${:.2})",
+            tranche.side, tranche.market_name, exit_price, pnl, position_pnl
+        );
+        Some(pnl)
+    }
+
     /// Get a position by market_id for exit manager.
     pub fn get_position(&self, market_id: &Uuid) -> Option<&SimulatedPosition> {
         self.positions.iter().find(|p| p.market_id == *market_id)
@@ -332,8 +1279,10 @@ impl DryRunPortfolio {
                 pos.market_name, pos.cost
             );
             self.pending_count = self.pending_count.saturating_sub(1);
+            self.clear_partial(&pos);
             self.total_pnl -= pos.cost;
             self.realized_losses += 1;
+            self.record_timeframe(&pos, -pos.cost, Some(false));
         }
 
         let cleaned = before_count - self.positions.len();
@@ -342,7 +1291,44 @@ impl DryRunPortfolio {
         }
     }
 
-    pub fn print_summary(&self) {
+    /// Settle positions still pending at the end of a run according to `policy`.
+    /// Returns the number of positions settled.
+    pub fn settle_unresolved(&mut self, policy: UnresolvedPolicy) -> usize {
+        let mut settled = 0;
+
+        for pos in std::mem::take(&mut self.positions) {
+            let pnl = match policy.settle_pnl(&pos) {
+                Some(pnl) => pnl,
+                None => {
+                    self.positions.push(pos);
+                    continue;
+                }
+            };
+
+            settled += 1;
+            self.pending_count = self.pending_count.saturating_sub(1);
+            self.clear_partial(&pos);
+            self.total_pnl += pnl;
+            self.record_timeframe(&pos, pnl, Some(pnl >= Decimal::ZERO));
+            if pnl >= Decimal::ZERO {
+                self.realized_wins += 1;
+            } else {
+                self.realized_losses += 1;
+            }
+            info!(
+                "[PORTFOLIO] UNRESOLVED ({}): {} {} (mkt: ${:.3}) -> ${:.2}",
+                policy,
+                pos.side,
+                pos.market_name,
+                pos.mark_price(),
+                pnl
+            );
+        }
+
+        settled
+    }
+
+    pub fn print_summary(&self) {
         let win_rate = self.win_rate();
 
         info!("╔════════════════════════════════════════════════════════════╗");
@@ -356,20 +1342,192 @@ impl DryRunPortfolio {
             "║  Realized P&L:      ${:<10.2}                           ║",
             self.total_pnl
         );
+        let unpriced = self.unpriced_count();
+        if unpriced > 0 {
+            info!(
+                "║  Unrealized P&L:    ${:<10.2} ({} unpriced excluded)      ║",
+                self.unrealized_pnl(),
+                unpriced
+            );
+        } else {
+            info!(
+                "║  Unrealized P&L:    ${:<10.2}                           ║",
+                self.unrealized_pnl()
+            );
+        }
+        info!(
+            "║  Total P&L:         ${:<10.2}                           ║",
+            self.total_equity_pnl()
+        );
         info!(
             "║  Pending Positions: {:<10}                             ║",
             self.pending_count
         );
+        if let Some(available) = self.available_balance() {
+            info!(
+                "║  Available Balance: ${:<10.2} ({} refused)              ║",
+                available, self.balance_rejections
+            );
+        }
+        let net = self.net_positions();
+        if !net.is_empty() {
+            let locked: Decimal = net.iter().map(|n| n.worst_case_pnl()).sum();
+            info!(
+                "║  Hedged Markets:    {:<3} (worst case ${:<8.2})             ║",
+                net.len(),
+                locked
+            );
+        }
         info!(
             "║  Wins / Losses:     {} / {}                                  ║",
             self.realized_wins, self.realized_losses
         );
+        if self.timed_out_unknown > 0 {
+            info!(
+                "║  Timed Out (unk):   {:<10}                             ║",
+                self.timed_out_unknown
+            );
+        }
         info!(
             "║  Win Rate:          {:<6.1}%                               ║",
             win_rate
         );
         info!("╚════════════════════════════════════════════════════════════╝");
     }
+
+    /// Log realized results per timeframe (nothing until a position resolves).
+    pub fn print_timeframe_summary(&self) {
+        let mut timeframes: Vec<_> = self.by_timeframe.iter().collect();
+        timeframes.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (timeframe, stats) in timeframes {
+            info!(
+                "[PORTFOLIO] {:>4}: invested ${:.2}, P&L ${:.2}, {}W / {}L ({:.1}%)",
+                timeframe,
+                stats.invested,
+                stats.pnl,
+                stats.wins,
+                stats.losses,
+                stats.win_rate()
+            );
+        }
+    }
+}
+
+/// How dry-run limit orders are filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaperFillMode {
+    /// Fill immediately at the depth-based effective price
+    #[default]
+    Optimistic,
+    /// Rest at the limit until a later snapshot's best ask reaches it
+    Realistic,
+}
+
+impl PaperFillMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaperFillMode::Optimistic => "optimistic",
+            PaperFillMode::Realistic => "realistic",
+        }
+    }
+}
+
+impl FromStr for PaperFillMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "optimistic" => Ok(PaperFillMode::Optimistic),
+            "realistic" => Ok(PaperFillMode::Realistic),
+            other => Err(format!(
+                "Unknown paper fill mode '{}' (expected optimistic, realistic)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for PaperFillMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Dry-run limit orders resting on the book under `PaperFillMode::Realistic`.
+///
+/// Each order holds the position it becomes once filled. An order fills at
+/// its limit price when a snapshot captured after it was placed shows the
+/// best ask at or below that limit, and is cancelled if that doesn't happen
+/// within the timeout.
+#[derive(Debug)]
+pub struct PaperOrders {
+    pending: Vec<SimulatedPosition>,
+    timeout: chrono::Duration,
+}
+
+/// Orders resolved by one `PaperOrders::check` pass.
+#[derive(Debug, Default)]
+pub struct PaperFills {
+    /// Filled positions, priced at their limit
+    pub filled: Vec<SimulatedPosition>,
+    /// Orders cancelled unfilled after the timeout
+    pub expired: Vec<SimulatedPosition>,
+}
+
+impl PaperOrders {
+    pub fn new(timeout_secs: u64) -> Self {
+        Self {
+            pending: Vec::new(),
+            timeout: chrono::Duration::seconds(timeout_secs as i64),
+        }
+    }
+
+    /// Rest an order at `position.entry_price`, placed at `position.created_at`.
+    pub fn place(&mut self, position: SimulatedPosition) {
+        self.pending.push(position);
+    }
+
+    pub fn has_pending(&self, market_id: &Uuid, side: &str) -> bool {
+        self.pending
+            .iter()
+            .any(|p| p.market_id == *market_id && p.side == side)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Fill orders whose book traded through their limit and expire the rest
+    /// once they are older than the timeout.
+    pub fn check(&mut self, markets: &[MarketWithOrderbook], now: DateTime<Utc>) -> PaperFills {
+        let mut fills = PaperFills::default();
+
+        for mut order in std::mem::take(&mut self.pending) {
+            let crossed = markets
+                .iter()
+                .find(|m| m.id == order.market_id && m.captured_at > order.created_at)
+                .and_then(|m| m.best_ask(&order.side))
+                .filter(|ask| *ask <= order.entry_price);
+
+            if let Some(ask) = crossed {
+                order.effective_fill_price = order.entry_price;
+                order.cost = order.shares * order.entry_price;
+                order.last_price = ask;
+                fills.filled.push(order);
+            } else if now - order.created_at >= self.timeout {
+                fills.expired.push(order);
+            } else {
+                self.pending.push(order);
+            }
+        }
+
+        fills
+    }
 }
 
 /// Cached authentication state for Polymarket CLOB.
@@ -381,47 +1539,91 @@ pub struct CachedAuth {
     pub authenticated_at: DateTime<Utc>,
 }
 
-/// Ensure we have a valid authenticated CLOB client.
-/// Authenticates on first call, reuses cached client thereafter.
-pub async fn ensure_authenticated(cached_auth: &mut Option<CachedAuth>) -> Result<&CachedAuth> {
-    if cached_auth.is_some() {
-        debug!("[AUTH] Using cached authentication");
-        return Ok(cached_auth.as_ref().unwrap());
-    }
-
-    info!("[AUTH] Authenticating with Polymarket CLOB...");
+/// Resolve the Polymarket signer, signature type and funder from the
+/// environment (`WALLET_PRIVATE_KEY`, `POLYMARKET_WALLET_ADDRESS`).
+///
+/// A proxy wallet address selects `GnosisSafe` with the proxy as funder;
+/// without one the key's own address trades as an `Eoa`.
+pub fn resolve_signature_config() -> Result<(PrivateKeySigner, SignatureType, Option<Address>)> {
+    let private_key = std::env::var("WALLET_PRIVATE_KEY").ok();
+    let proxy_wallet = std::env::var("POLYMARKET_WALLET_ADDRESS").ok();
+    signature_config_from(private_key.as_deref(), proxy_wallet.as_deref())
+}
 
-    // Get private key
-    let private_key = std::env::var("WALLET_PRIVATE_KEY")
+/// [`resolve_signature_config`] over explicit values instead of the environment.
+pub fn signature_config_from(
+    private_key: Option<&str>,
+    proxy_wallet: Option<&str>,
+) -> Result<(PrivateKeySigner, SignatureType, Option<Address>)> {
+    let private_key = private_key
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
         .context("Missing WALLET_PRIVATE_KEY environment variable")?;
-
     let private_key = if private_key.starts_with("0x") {
-        private_key
+        private_key.to_string()
     } else {
         format!("0x{}", private_key)
     };
 
-    // Create signer
     let signer = PrivateKeySigner::from_str(&private_key)
         .context("Invalid private key format")?
         .with_chain_id(Some(POLYGON));
 
-    // Determine signature type
-    let proxy_wallet = std::env::var("POLYMARKET_WALLET_ADDRESS").ok();
-    let signature_type = if proxy_wallet.is_some() {
+    // A blank `POLYMARKET_WALLET_ADDRESS=` line in .env means no proxy
+    let funder = match proxy_wallet.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(proxy) => Some(parse_proxy_address(proxy)?),
+        None => None,
+    };
+    let signature_type = if funder.is_some() {
         SignatureType::GnosisSafe
     } else {
         SignatureType::Eoa
     };
 
+    // The funder of a Safe is the proxy, never the key itself
+    if funder == Some(signer.address()) {
+        warn!(
+            "[AUTH] POLYMARKET_WALLET_ADDRESS is the signer's own address {}; \
+             unset it to trade as an EOA",
+            signer.address()
+        );
+    }
+
+    Ok((signer, signature_type, funder))
+}
+
+/// Parse a proxy wallet address, requiring the full `0x` + 40 hex digit form.
+fn parse_proxy_address(proxy: &str) -> Result<Address> {
+    let well_formed = proxy
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+    if !well_formed {
+        bail!(
+            "Invalid POLYMARKET_WALLET_ADDRESS '{}': expected 0x followed by 40 hex digits",
+            proxy
+        );
+    }
+    proxy.parse().context("Invalid proxy wallet address")
+}
+
+/// Ensure we have a valid authenticated CLOB client.
+/// Authenticates on first call, reuses cached client thereafter.
+pub async fn ensure_authenticated(cached_auth: &mut Option<CachedAuth>) -> Result<&CachedAuth> {
+    if cached_auth.is_some() {
+        debug!("[AUTH] Using cached authentication");
+        return Ok(cached_auth.as_ref().unwrap());
+    }
+
+    info!("[AUTH] Authenticating with Polymarket CLOB...");
+
+    let (signer, signature_type, funder) = resolve_signature_config()?;
+
     // Build authentication
     let mut auth_builder = ClobClient::new(CLOB_HOST, ClobConfig::default())?
         .authentication_builder(&signer)
         .signature_type(signature_type);
 
-    if let Some(ref proxy) = proxy_wallet {
-        let funder_address: alloy::primitives::Address =
-            proxy.parse().context("Invalid proxy wallet address")?;
+    if let Some(funder_address) = funder {
         auth_builder = auth_builder.funder(funder_address);
     }
 
@@ -444,6 +1646,8 @@ pub async fn ensure_authenticated(cached_auth: &mut Option<CachedAuth>) -> Resul
 }
 
 /// Execute a trade on Polymarket. Returns the order ID on success.
+///
+/// Orders larger than `max_shares` are refused (see [`check_order_size`]).
 pub async fn execute_trade(
     cached_auth: &mut Option<CachedAuth>,
     token_id: &str,
@@ -451,18 +1655,53 @@ pub async fn execute_trade(
     price: Decimal,
     side: &str,
     market_name: &str,
+    max_shares: Decimal,
+) -> Result<String> {
+    execute_trade_with_kind(
+        cached_auth,
+        token_id,
+        shares,
+        price,
+        side,
+        market_name,
+        max_shares,
+        OrderKind::Limit,
+    )
+    .await
+}
+
+/// [`execute_trade`] with an explicit time in force. An accepted immediate
+/// order has not necessarily filled; confirm with
+/// [`query_order_fill_standalone`].
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_trade_with_kind(
+    cached_auth: &mut Option<CachedAuth>,
+    token_id: &str,
+    shares: Decimal,
+    price: Decimal,
+    side: &str,
+    market_name: &str,
+    max_shares: Decimal,
+    order_kind: OrderKind,
 ) -> Result<String> {
+    // Reject an unplaceable size before authenticating
+    check_order_size(shares, max_shares)?;
+
     // Ensure authenticated
     let auth = ensure_authenticated(cached_auth).await?;
 
-    // Normalize price and shares to remove trailing zeros
-    let price = price.normalize();
-    let shares = shares.round_dp(2);
+    // Snap the price to the market's tick, then strip trailing zeros
+    let tick_size = token_tick_size(auth, token_id).await;
+    let price = normalize_price_to_tick(price, tick_size, RoundingStrategy::ToNegativeInfinity);
+    let (price, shares) = normalize_order(price, shares);
 
     // Build order
     info!(
-        "[TRADE] Building {} order: {} shares @ ${}",
-        side, shares, price
+        market = market_name,
+        side,
+        shares = %fmt_shares(shares),
+        price = %fmt_price(price),
+        "[TRADE] Building order"
     );
 
     let order = timeout(
@@ -473,6 +1712,7 @@ pub async fn execute_trade(
             .size(shares)
             .price(price)
             .side(polymarket_client_sdk::clob::types::Side::Buy)
+            .order_type(order_kind.sdk_order_type())
             .build(),
     )
     .await
@@ -506,8 +1746,12 @@ pub async fn execute_trade(
             .unwrap_or(false);
         if !order.order_id.is_empty() && !has_error {
             info!(
-                "[TRADE] Order placed successfully: {} (order_id: {})",
-                market_name, order.order_id
+                market = market_name,
+                side,
+                shares = %fmt_shares(shares),
+                price = %fmt_price(price),
+                order_id = %order.order_id,
+                "[TRADE] Order placed successfully"
             );
             Ok(order.order_id.clone())
         } else if let Some(ref error) = order.error_msg {
@@ -520,6 +1764,262 @@ pub async fn execute_trade(
     }
 }
 
+/// Why the CLOB (or the SDK on its behalf) refused an order.
+///
+/// Only `PriceMoved` is worth retrying against a fresh snapshot; the others
+/// will fail the same way again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderRejection {
+    /// The book moved past our price before the order could match.
+    PriceMoved,
+    /// Not enough USDC balance or allowance.
+    InsufficientBalance,
+    /// Credentials were rejected (API key, signature, L1/L2 auth).
+    Auth,
+    /// Order nonce was stale or already used.
+    Nonce,
+    /// Anything else (timeouts, invalid size, market closed, ...).
+    Other,
+}
+
+impl OrderRejection {
+    /// Classify an order error by its message, including any context chain
+    /// (pass `format!("{:#}", err)`).
+    pub fn classify(message: &str) -> Self {
+        let msg = message.to_lowercase();
+
+        if msg.contains("nonce") {
+            Self::Nonce
+        } else if msg.contains("unauthorized")
+            || msg.contains("api key")
+            || msg.contains("invalid signature")
+            || msg.contains("authenticate")
+        {
+            Self::Auth
+        } else if msg.contains("not enough balance") || msg.contains("allowance") {
+            Self::InsufficientBalance
+        } else if msg.contains("couldn't be fully filled")
+            || msg.contains("no orders found to match")
+            || msg.contains("crosses the book")
+            || msg.contains("price moved")
+        {
+            Self::PriceMoved
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Whether a fresh snapshot could let the same order through.
+    pub fn is_price_movement(&self) -> bool {
+        matches!(self, Self::PriceMoved)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PriceMoved => "price moved",
+            Self::InsufficientBalance => "insufficient balance",
+            Self::Auth => "auth",
+            Self::Nonce => "nonce",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for OrderRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Global minimum gap between live orders, independent of per-market
+/// cooldowns, to stay clear of exchange rate limits in volatile periods.
+///
+/// A zero interval (and [`TradeThrottle::disabled`], used in dry-run) never
+/// throttles.
+#[derive(Debug, Clone)]
+pub struct TradeThrottle {
+    min_interval: Duration,
+    last_order_at: Option<Instant>,
+}
+
+impl TradeThrottle {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_order_at: None,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.min_interval.is_zero()
+    }
+
+    /// Time left before another order may be placed, or None if clear.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        let last = self.last_order_at?;
+        let wait = self
+            .min_interval
+            .saturating_sub(now.saturating_duration_since(last));
+        (!wait.is_zero()).then_some(wait)
+    }
+
+    /// Note that an order was just submitted.
+    pub fn record_order(&mut self, now: Instant) {
+        if self.is_enabled() {
+            self.last_order_at = Some(now);
+        }
+    }
+}
+
+/// Which daily P&L bound halted new entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DailyStopReason {
+    ProfitTarget,
+    MaxLoss,
+}
+
+impl DailyStopReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DailyStopReason::ProfitTarget => "profit target",
+            DailyStopReason::MaxLoss => "max loss",
+        }
+    }
+}
+
+impl std::fmt::Display for DailyStopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Session stop on the day's realized P&L.
+///
+/// Once the P&L realized since UTC midnight reaches `profit_target`, or falls
+/// to `-max_loss`, no new positions should be opened until the next UTC day.
+/// Positions already open are unaffected and keep exiting and settling.
+/// Callers feed the session's cumulative realized P&L; the stop keeps its own
+/// baseline for each day.
+#[derive(Debug, Clone, Default)]
+pub struct DailyPnlStop {
+    profit_target: Option<Decimal>,
+    max_loss: Option<Decimal>,
+    /// UTC day currently being tracked
+    day: Option<NaiveDate>,
+    /// Cumulative realized P&L when `day` began
+    day_start_pnl: Decimal,
+    /// Realized P&L since `day` began, as of the last update
+    day_pnl: Decimal,
+    halted: Option<DailyStopReason>,
+}
+
+impl DailyPnlStop {
+    /// Stop at `profit_target` USDC of daily profit or `max_loss` USDC of
+    /// daily loss (a positive amount); either may be None.
+    pub fn new(profit_target: Option<Decimal>, max_loss: Option<Decimal>) -> Self {
+        Self {
+            profit_target,
+            max_loss,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.profit_target.is_some() || self.max_loss.is_some()
+    }
+
+    /// Update with the cumulative realized P&L at `now`.
+    ///
+    /// Returns the reason when this update halts entries; later updates on
+    /// the same day return None. The first update of a new UTC day clears
+    /// any halt.
+    pub fn update(&mut self, realized_pnl: Decimal, now: DateTime<Utc>) -> Option<DailyStopReason> {
+        let today = now.date_naive();
+        if self.day != Some(today) {
+            if let (Some(day), Some(reason)) = (self.day, self.halted) {
+                info!(
+                    "[DAILY STOP] New UTC day; resuming entries ({} hit on {}, day P&L ${:.2})",
+                    reason, day, self.day_pnl
+                );
+            }
+            self.day = Some(today);
+            self.day_start_pnl = realized_pnl;
+            self.halted = None;
+        }
+        self.day_pnl = realized_pnl - self.day_start_pnl;
+
+        if self.halted.is_some() {
+            return None;
+        }
+        let reason = if self.profit_target.is_some_and(|t| self.day_pnl >= t) {
+            DailyStopReason::ProfitTarget
+        } else if self.max_loss.is_some_and(|l| self.day_pnl <= -l) {
+            DailyStopReason::MaxLoss
+        } else {
+            return None;
+        };
+        self.halted = Some(reason);
+        Some(reason)
+    }
+
+    /// Whether new positions may be opened.
+    pub fn allows_entry(&self) -> bool {
+        self.halted.is_none()
+    }
+
+    /// The bound that halted entries today, if any.
+    pub fn halted(&self) -> Option<DailyStopReason> {
+        self.halted
+    }
+
+    /// Realized P&L since UTC midnight, as of the last update.
+    pub fn day_pnl(&self) -> Decimal {
+        self.day_pnl
+    }
+}
+
+/// Run `check` over trade signals with at most `max_concurrency` in flight.
+///
+/// Signals are keyed by (market_id, side). A key already in `traded`, or
+/// repeated later in `signals`, is dropped before checking, so evaluating in
+/// parallel can never enter the same position twice. Signals whose check
+/// passes come back in their original order, keeping whatever the caller
+/// mutates afterwards (dry-run portfolio, order placement) deterministic.
+pub async fn check_signals_concurrently<S, K, F, Fut>(
+    signals: Vec<S>,
+    key: K,
+    traded: &HashSet<(Uuid, String)>,
+    max_concurrency: usize,
+    check: F,
+) -> Vec<S>
+where
+    K: Fn(&S) -> (Uuid, String),
+    F: Fn(&S) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let mut queued = HashSet::new();
+    let unique = signals.into_iter().filter(|signal| {
+        let key = key(signal);
+        !traded.contains(&key) && queued.insert(key)
+    });
+
+    let mut passed: Vec<(usize, S)> = stream::iter(unique.enumerate())
+        .map(|(i, signal)| {
+            let pending = check(&signal);
+            async move { (i, signal, pending.await) }
+        })
+        .buffer_unordered(max_concurrency.max(1))
+        .filter_map(|(i, signal, ok)| async move { ok.then_some((i, signal)) })
+        .collect()
+        .await;
+    passed.sort_by_key(|(i, _)| *i);
+    passed.into_iter().map(|(_, signal)| signal).collect()
+}
+
 /// Execute a sell order on Polymarket. Returns the order ID on success.
 pub async fn execute_sell_order(
     cached_auth: &mut Option<CachedAuth>,
@@ -527,18 +2027,42 @@ pub async fn execute_sell_order(
     shares: Decimal,
     price: Decimal,
     market_name: &str,
+) -> Result<String> {
+    execute_sell_order_with_kind(
+        cached_auth,
+        token_id,
+        shares,
+        price,
+        market_name,
+        OrderKind::Limit,
+    )
+    .await
+}
+
+/// [`execute_sell_order`] with an explicit time in force.
+pub async fn execute_sell_order_with_kind(
+    cached_auth: &mut Option<CachedAuth>,
+    token_id: &str,
+    shares: Decimal,
+    price: Decimal,
+    market_name: &str,
+    order_kind: OrderKind,
 ) -> Result<String> {
     // Ensure authenticated
     let auth = ensure_authenticated(cached_auth).await?;
 
-    // Normalize price and shares to remove trailing zeros
-    let price = price.normalize();
-    let shares = shares.round_dp(2);
+    // Snap the price to the market's tick, then strip trailing zeros
+    let tick_size = token_tick_size(auth, token_id).await;
+    let price = normalize_price_to_tick(price, tick_size, RoundingStrategy::ToPositiveInfinity);
+    let (price, shares) = normalize_order(price, shares);
 
     // Build sell order
     info!(
-        "[TRADE] Building SELL order: {} shares @ ${}",
-        shares, price
+        market = market_name,
+        side = "SELL",
+        shares = %fmt_shares(shares),
+        price = %fmt_price(price),
+        "[TRADE] Building order"
     );
 
     let order = timeout(
@@ -549,6 +2073,7 @@ pub async fn execute_sell_order(
             .size(shares)
             .price(price)
             .side(polymarket_client_sdk::clob::types::Side::Sell)
+            .order_type(order_kind.sdk_order_type())
             .build(),
     )
     .await
@@ -582,8 +2107,12 @@ pub async fn execute_sell_order(
             .unwrap_or(false);
         if !order.order_id.is_empty() && !has_error {
             info!(
-                "[TRADE] Sell order placed successfully: {} (order_id: {})",
-                market_name, order.order_id
+                market = market_name,
+                side = "SELL",
+                shares = %fmt_shares(shares),
+                price = %fmt_price(price),
+                order_id = %order.order_id,
+                "[TRADE] Sell order placed successfully"
             );
             Ok(order.order_id.clone())
         } else if let Some(ref error) = order.error_msg {
@@ -640,3 +2169,1591 @@ pub async fn query_order_fill_standalone(order_id: &str) -> Result<Decimal> {
 
     Ok(order_info.size_matched)
 }
+
+/// Timeout for live orderbook fetches from the CLOB REST API
+const BOOK_FETCH_TIMEOUT_SECS: u64 = 5;
+
+/// CLOB orderbook response from the `/book` REST endpoint.
+#[derive(Debug, serde::Deserialize)]
+pub struct ClobBook {
+    #[serde(default)]
+    pub bids: Vec<ClobLevel>,
+    #[serde(default)]
+    pub asks: Vec<ClobLevel>,
+}
+
+/// CLOB orderbook price level (the API returns decimals as strings)
+#[derive(Debug, serde::Deserialize)]
+pub struct ClobLevel {
+    pub price: String,
+    #[serde(default)]
+    pub size: String,
+}
+
+impl ClobBook {
+    /// Lowest ask price, ignoring unparseable levels.
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks
+            .iter()
+            .filter_map(|l| l.price.parse::<Decimal>().ok())
+            .min()
+    }
+
+    /// Highest bid price, ignoring unparseable levels.
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids
+            .iter()
+            .filter_map(|l| l.price.parse::<Decimal>().ok())
+            .max()
+    }
+}
+
+/// HTTP client shared by live book fetches (connection pooled).
+fn book_http_client() -> &'static reqwest::Client {
+    static CLIENT: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// Fetch a token's orderbook from the CLOB REST API.
+pub async fn fetch_clob_book(token_id: &str) -> Result<ClobBook> {
+    fetch_clob_book_from(CLOB_HOST, token_id).await
+}
+
+/// Fetch a token's orderbook from the CLOB REST API at `host`.
+///
+/// A non-2xx response (rate limit, server error) is an error rather than an
+/// empty book, so callers never mistake it for "no asks".
+async fn fetch_clob_book_from(host: &str, token_id: &str) -> Result<ClobBook> {
+    let url = format!("{}/book?token_id={}", host, token_id);
+    let resp = timeout(
+        Duration::from_secs(BOOK_FETCH_TIMEOUT_SECS),
+        book_http_client().get(&url).send(),
+    )
+    .await
+    .context("Orderbook fetch timed out")?
+    .context("Failed to fetch orderbook")?
+    .error_for_status()
+    .context("Orderbook request rejected")?;
+
+    resp.json::<ClobBook>()
+        .await
+        .context("Failed to parse orderbook")
+}
+
+/// Fetch the live best ask for a token straight from the CLOB, bypassing the
+/// DB snapshot. Returns None (after logging) if the fetch fails or the book
+/// has no asks.
+pub async fn fetch_live_best_ask(token_id: &str) -> Option<Decimal> {
+    match fetch_clob_book(token_id).await {
+        Ok(book) => book.best_ask(),
+        Err(e) => {
+            warn!("[LIVE-PRICE] Token {}: {:#}", token_id, e);
+            None
+        }
+    }
+}
+
+/// Whether the live ask has moved above the DB ask by more than `max_divergence`.
+///
+/// Only upward moves count: a cheaper live ask never blocks a buy.
+pub fn price_divergence_exceeded(
+    db_ask: Decimal,
+    live_ask: Decimal,
+    max_divergence: Decimal,
+) -> bool {
+    live_ask - db_ask > max_divergence
+}
+
+/// An order resting on the CLOB book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenOrder {
+    pub order_id: String,
+    pub token_id: String,
+    pub side: String,
+    pub price: Decimal,
+    pub original_size: Decimal,
+    pub size_matched: Decimal,
+}
+
+/// Cursor the CLOB returns once the last page of results has been served.
+const END_CURSOR: &str = "LTE=";
+
+/// Order-management calls needed for startup reconciliation.
+///
+/// Implemented by [`CachedAuth`] for the live CLOB and by [`MockOrderApi`] for tests.
+#[async_trait]
+pub trait OrderApi: Send + Sync {
+    /// All open orders for the authenticated account.
+    async fn open_orders(&self) -> Result<Vec<OpenOrder>>;
+
+    /// Cancel a single order by ID.
+    async fn cancel(&self, order_id: &str) -> Result<()>;
+}
+
+#[async_trait]
+impl OrderApi for CachedAuth {
+    async fn open_orders(&self) -> Result<Vec<OpenOrder>> {
+        let request = OrdersRequest::default();
+        let mut cursor: Option<String> = None;
+        let mut orders = Vec::new();
+
+        loop {
+            let page = timeout(
+                Duration::from_secs(10),
+                self.client.orders(&request, cursor.clone()),
+            )
+            .await
+            .context("Open orders query timed out")?
+            .context("Failed to list open orders")?;
+
+            orders.extend(page.data.into_iter().map(|o| OpenOrder {
+                order_id: o.id,
+                token_id: o.asset_id,
+                side: format!("{:?}", o.side).to_uppercase(),
+                price: o.price,
+                original_size: o.original_size,
+                size_matched: o.size_matched,
+            }));
+
+            if page.next_cursor.is_empty() || page.next_cursor == END_CURSOR {
+                break;
+            }
+            cursor = Some(page.next_cursor);
+        }
+
+        Ok(orders)
+    }
+
+    async fn cancel(&self, order_id: &str) -> Result<()> {
+        timeout(Duration::from_secs(10), self.client.cancel_order(order_id))
+            .await
+            .context("Order cancellation timed out")?
+            .context("Failed to cancel order")?;
+        Ok(())
+    }
+}
+
+/// List all open orders for the authenticated account.
+pub async fn list_open_orders(cached_auth: &mut Option<CachedAuth>) -> Result<Vec<OpenOrder>> {
+    let auth = ensure_authenticated(cached_auth).await?;
+    auth.open_orders().await
+}
+
+/// Whether the wallet has approved enough USDC for the CLOB exchange to
+/// spend. Without an approval, live orders are rejected with unhelpful errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllowanceStatus {
+    /// The allowance covers the required amount
+    Sufficient { allowance: Decimal },
+    /// Some USDC is approved, but less than required
+    Insufficient {
+        allowance: Decimal,
+        required: Decimal,
+    },
+    /// No USDC is approved for the exchange at all
+    NotApproved,
+}
+
+impl AllowanceStatus {
+    /// Classify an allowance (in USDC, see [`usdc_allowance`]) against `required`.
+    pub fn classify(allowance: Option<Decimal>, required: Decimal) -> Self {
+        match allowance {
+            None => AllowanceStatus::NotApproved,
+            Some(a) if a <= Decimal::ZERO => AllowanceStatus::NotApproved,
+            Some(a) if a >= required => AllowanceStatus::Sufficient { allowance: a },
+            Some(a) => AllowanceStatus::Insufficient {
+                allowance: a,
+                required,
+            },
+        }
+    }
+
+    pub fn is_sufficient(&self) -> bool {
+        matches!(self, AllowanceStatus::Sufficient { .. })
+    }
+}
+
+/// Format an allowance, showing max-uint approvals as "unlimited".
+fn fmt_allowance(allowance: Decimal) -> String {
+    if allowance == Decimal::MAX {
+        "unlimited".to_string()
+    } else {
+        format!("${:.2}", allowance)
+    }
+}
+
+impl std::fmt::Display for AllowanceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AllowanceStatus::Sufficient { allowance } => {
+                write!(
+                    f,
+                    "USDC allowance {} is sufficient",
+                    fmt_allowance(*allowance)
+                )
+            }
+            AllowanceStatus::Insufficient {
+                allowance,
+                required,
+            } => write!(
+                f,
+                "USDC allowance {} is below the ${:.2} required",
+                fmt_allowance(*allowance),
+                required
+            ),
+            AllowanceStatus::NotApproved => write!(f, "USDC is not approved for the exchange"),
+        }
+    }
+}
+
+/// USDC allowance from the CLOB's per-contract allowances (micro-USDC strings).
+///
+/// Orders can route through any exchange contract, so the smallest allowance
+/// is the one that binds. Amounts too large for `Decimal` - the usual max-uint
+/// approval - count as unlimited (`Decimal::MAX`); unparseable entries are
+/// ignored. None when no allowance is reported.
+pub fn usdc_allowance<S: AsRef<str>>(raw: &[S]) -> Option<Decimal> {
+    raw.iter()
+        .filter_map(|r| {
+            let r = r.as_ref().trim();
+            if r.is_empty() || !r.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some(match Decimal::from_str(r) {
+                Ok(micro) => micro / Decimal::from(1_000_000),
+                Err(_) => Decimal::MAX,
+            })
+        })
+        .min()
+}
+
+/// Check the wallet's USDC allowance for the exchange against `required`
+/// (e.g. the trader's maximum total exposure).
+pub async fn check_usdc_allowance(
+    cached_auth: &mut Option<CachedAuth>,
+    required: Decimal,
+) -> Result<AllowanceStatus> {
+    let auth = ensure_authenticated(cached_auth).await?;
+    let response = timeout(
+        Duration::from_secs(10),
+        auth.client
+            .balance_allowance(&BalanceAllowanceRequest::default()),
+    )
+    .await
+    .context("Allowance query timed out")?
+    .context("Failed to fetch USDC allowance")?;
+
+    let raw: Vec<String> = response
+        .allowances
+        .values()
+        .map(|a| a.to_string())
+        .collect();
+    Ok(AllowanceStatus::classify(usdc_allowance(&raw), required))
+}
+
+/// Find open orders on any of `token_ids` left over from a previous run.
+///
+/// Each orphan is logged; when `cancel` is set they are also cancelled.
+/// Cancellation failures are logged and do not abort the sweep. Returns the
+/// orphans found.
+pub async fn reconcile_open_orders(
+    api: &dyn OrderApi,
+    token_ids: &HashSet<String>,
+    cancel: bool,
+) -> Result<Vec<OpenOrder>> {
+    let orphans: Vec<OpenOrder> = api
+        .open_orders()
+        .await?
+        .into_iter()
+        .filter(|o| token_ids.contains(&o.token_id))
+        .collect();
+
+    if orphans.is_empty() {
+        info!("[ORPHAN] No open orders found for configured tokens");
+        return Ok(orphans);
+    }
+
+    for order in &orphans {
+        warn!(
+            "[ORPHAN] {} {} {} @ ${} (filled {}/{}) token={}",
+            order.order_id,
+            order.side,
+            order.original_size - order.size_matched,
+            order.price,
+            order.size_matched,
+            order.original_size,
+            order.token_id
+        );
+
+        if cancel {
+            match api.cancel(&order.order_id).await {
+                Ok(()) => info!("[ORPHAN] Cancelled {}", order.order_id),
+                Err(e) => warn!("[ORPHAN] Failed to cancel {}: {}", order.order_id, e),
+            }
+        }
+    }
+
+    if !cancel {
+        warn!(
+            "[ORPHAN] {} open order(s) left resting; pass --cancel-orphans to cancel them",
+            orphans.len()
+        );
+    }
+
+    Ok(orphans)
+}
+
+/// In-memory [`OrderApi`] for tests.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Default)]
+pub struct MockOrderApi {
+    orders: Vec<OpenOrder>,
+    failing_cancels: HashSet<String>,
+    cancelled: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MockOrderApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_order(mut self, order: OpenOrder) -> Self {
+        self.orders.push(order);
+        self
+    }
+
+    /// Make cancellation of `order_id` fail.
+    pub fn with_failing_cancel(mut self, order_id: &str) -> Self {
+        self.failing_cancels.insert(order_id.to_string());
+        self
+    }
+
+    /// Order IDs successfully cancelled so far.
+    pub fn cancelled(&self) -> Vec<String> {
+        self.cancelled.lock().unwrap().clone()
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+#[async_trait]
+impl OrderApi for MockOrderApi {
+    async fn open_orders(&self) -> Result<Vec<OpenOrder>> {
+        Ok(self.orders.clone())
+    }
+
+    async fn cancel(&self, order_id: &str) -> Result<()> {
+        if self.failing_cancels.contains(order_id) {
+            anyhow::bail!("mock cancel failure for {}", order_id);
+        }
+        self.cancelled.lock().unwrap().push(order_id.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InMemoryMarketRepository, MockGammaApi};
+
+    fn test_position(side: &str, shares: Decimal, price: Decimal) -> SimulatedPosition {
+        SimulatedPosition {
+            market_id: Uuid::new_v4(),
+            condition_id: "cond".to_string(),
+            market_name: "BTC Up or Down".to_string(),
+            market_type: "up_down".to_string(),
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "yes".to_string(),
+            no_token_id: "no".to_string(),
+            side: side.to_string(),
+            shares,
+            entry_price: price,
+            best_ask_price: price,
+            effective_fill_price: price,
+            cost: shares * price,
+            last_price: price,
+            end_time: Utc::now() + chrono::Duration::minutes(5),
+            created_at: Utc::now(),
+            resolution_retries: 0,
+            last_retry_time: None,
+        }
+    }
+
+    #[test]
+    fn test_usdc_allowance_from_raw() {
+        // Micro-USDC strings; the smallest contract allowance binds
+        assert_eq!(
+            usdc_allowance(&["250000000", "1000000000"]),
+            Some(dec!(250))
+        );
+        // A max-uint approval overflows Decimal and counts as unlimited
+        let max_uint =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(usdc_allowance(&[max_uint]), Some(Decimal::MAX));
+        assert_eq!(usdc_allowance(&[max_uint, "5000000"]), Some(dec!(5)));
+        // Garbage is ignored; nothing reported is no allowance
+        assert_eq!(usdc_allowance(&["abc", "0"]), Some(dec!(0)));
+        assert_eq!(usdc_allowance::<&str>(&[]), None);
+        assert_eq!(usdc_allowance(&["-5", ""]), None);
+    }
+
+    #[test]
+    fn test_allowance_status_classify() {
+        let status = |raw: &[&str]| AllowanceStatus::classify(usdc_allowance(raw), dec!(1000));
+
+        let max_uint =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(
+            status(&[max_uint]),
+            AllowanceStatus::Sufficient {
+                allowance: Decimal::MAX
+            }
+        );
+        assert_eq!(
+            status(&["1000000000"]),
+            AllowanceStatus::Sufficient {
+                allowance: dec!(1000)
+            }
+        );
+        assert_eq!(
+            status(&["999990000"]),
+            AllowanceStatus::Insufficient {
+                allowance: dec!(999.99),
+                required: dec!(1000)
+            }
+        );
+        assert_eq!(status(&["0"]), AllowanceStatus::NotApproved);
+        assert_eq!(status(&[]), AllowanceStatus::NotApproved);
+
+        assert!(status(&[max_uint]).is_sufficient());
+        assert!(!status(&["0"]).is_sufficient());
+        assert_eq!(
+            status(&["500000000"]).to_string(),
+            "USDC allowance $500.00 is below the $1000.00 required"
+        );
+        assert_eq!(
+            status(&[max_uint]).to_string(),
+            "USDC allowance unlimited is sufficient"
+        );
+    }
+
+    #[test]
+    fn test_paper_fill_mode_parse() {
+        assert_eq!(
+            "optimistic".parse::<PaperFillMode>(),
+            Ok(PaperFillMode::Optimistic)
+        );
+        assert_eq!(
+            "Realistic".parse::<PaperFillMode>(),
+            Ok(PaperFillMode::Realistic)
+        );
+        assert!("instant".parse::<PaperFillMode>().is_err());
+        assert_eq!(PaperFillMode::default(), PaperFillMode::Optimistic);
+    }
+
+    #[test]
+    fn test_paper_orders_fill_only_when_book_crosses_limit() {
+        let placed = Utc::now() - chrono::Duration::seconds(5);
+        let mut order = test_position("YES", dec!(10), dec!(0.40));
+        order.created_at = placed;
+        order.effective_fill_price = dec!(0.45);
+        let market_id = order.market_id;
+
+        let mut orders = PaperOrders::new(30);
+        orders.place(order.clone());
+        assert!(orders.has_pending(&market_id, "YES"));
+        assert!(!orders.has_pending(&market_id, "NO"));
+
+        let book = |ask: Decimal, captured_at: DateTime<Utc>| {
+            let mut market = orderbook_for(&order, Some(ask - dec!(0.02)), None);
+            market.yes_best_ask = Some(ask);
+            market.captured_at = captured_at;
+            market
+        };
+
+        // Ask above the limit: still resting
+        let fills = orders.check(&[book(dec!(0.42), Utc::now())], Utc::now());
+        assert!(fills.filled.is_empty() && fills.expired.is_empty());
+        assert_eq!(orders.len(), 1);
+
+        // A crossing snapshot from before the order was placed doesn't count
+        let stale = placed - chrono::Duration::seconds(1);
+        let fills = orders.check(&[book(dec!(0.38), stale)], Utc::now());
+        assert!(fills.filled.is_empty());
+
+        // Ask trades through the limit: filled at the limit, not the old estimate
+        let fills = orders.check(&[book(dec!(0.39), Utc::now())], Utc::now());
+        assert_eq!(fills.filled.len(), 1);
+        assert_eq!(fills.filled[0].effective_fill_price, dec!(0.40));
+        assert_eq!(fills.filled[0].cost, dec!(4.0));
+        assert!(orders.is_empty());
+    }
+
+    #[test]
+    fn test_paper_orders_expire_without_cross() {
+        let mut order = test_position("NO", dec!(10), dec!(0.40));
+        order.created_at = Utc::now() - chrono::Duration::seconds(31);
+        let mut market = orderbook_for(&order, None, None);
+        market.no_best_ask = Some(dec!(0.41));
+
+        let mut orders = PaperOrders::new(30);
+        orders.place(order);
+        let fills = orders.check(&[market], Utc::now());
+        assert!(fills.filled.is_empty());
+        assert_eq!(fills.expired.len(), 1);
+        assert!(orders.is_empty());
+    }
+
+    fn portfolio_with(positions: Vec<SimulatedPosition>) -> DryRunPortfolio {
+        let mut portfolio = DryRunPortfolio::new();
+        for pos in positions {
+            portfolio.add_position(pos);
+        }
+        portfolio
+    }
+
+    #[test]
+    fn test_unresolved_policy_from_str() {
+        assert_eq!(
+            "mark-to-market".parse::<UnresolvedPolicy>(),
+            Ok(UnresolvedPolicy::MarkToMarket)
+        );
+        assert_eq!(
+            "assume-loss".parse::<UnresolvedPolicy>(),
+            Ok(UnresolvedPolicy::AssumeLoss)
+        );
+        assert_eq!(
+            "Win-If-Above-Half".parse::<UnresolvedPolicy>(),
+            Ok(UnresolvedPolicy::WinIfAboveHalf)
+        );
+        assert_eq!(
+            "leave-pending".parse::<UnresolvedPolicy>(),
+            Ok(UnresolvedPolicy::LeavePending)
+        );
+        assert!("bogus".parse::<UnresolvedPolicy>().is_err());
+        assert_eq!(UnresolvedPolicy::default(), UnresolvedPolicy::MarkToMarket);
+    }
+
+    #[test]
+    fn test_settle_unresolved_mark_to_market() {
+        let mut portfolio = portfolio_with(vec![test_position("YES", dec!(10), dec!(0.60))]);
+
+        assert_eq!(
+            portfolio.settle_unresolved(UnresolvedPolicy::MarkToMarket),
+            1
+        );
+        // Valued at the entry price, so flat
+        assert_eq!(portfolio.total_pnl, Decimal::ZERO);
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(portfolio.pending_count, 0);
+
+        let mut pos = test_position("YES", dec!(10), dec!(0.60));
+        pos.last_price = dec!(0.75);
+        let mut portfolio = portfolio_with(vec![pos]);
+        portfolio.settle_unresolved(UnresolvedPolicy::MarkToMarket);
+        assert_eq!(portfolio.total_pnl, dec!(1.5));
+        assert_eq!(portfolio.realized_wins, 1);
+    }
+
+    #[test]
+    fn test_settlement_timeout_policy_from_str() {
+        assert_eq!(
+            "loss".parse::<SettlementTimeoutPolicy>(),
+            Ok(SettlementTimeoutPolicy::Loss)
+        );
+        assert_eq!(
+            "Unknown".parse::<SettlementTimeoutPolicy>(),
+            Ok(SettlementTimeoutPolicy::Unknown)
+        );
+        assert_eq!(
+            "breakeven".parse::<SettlementTimeoutPolicy>(),
+            Ok(SettlementTimeoutPolicy::Breakeven)
+        );
+        assert!("bogus".parse::<SettlementTimeoutPolicy>().is_err());
+        assert_eq!(
+            SettlementTimeout::default(),
+            SettlementTimeout {
+                max_retries: DEFAULT_MAX_RESOLUTION_RETRIES,
+                policy: SettlementTimeoutPolicy::Loss,
+            }
+        );
+    }
+
+    #[test]
+    fn test_order_rejection_classify() {
+        let cases = [
+            (
+                "Order rejected: order couldn't be fully filled, FOK orders are fully filled/killed",
+                OrderRejection::PriceMoved,
+            ),
+            (
+                "Order rejected: no orders found to match with FOK order. FOK orders are fully filled or killed.",
+                OrderRejection::PriceMoved,
+            ),
+            (
+                "Order rejected: invalid post-only order: order crosses the book",
+                OrderRejection::PriceMoved,
+            ),
+            (
+                "Order rejected: not enough balance / allowance",
+                OrderRejection::InsufficientBalance,
+            ),
+            (
+                "Failed to post order: status 401: Unauthorized/Invalid api key",
+                OrderRejection::Auth,
+            ),
+            (
+                "Failed to authenticate with Polymarket: connection reset",
+                OrderRejection::Auth,
+            ),
+            ("Order rejected: invalid nonce", OrderRejection::Nonce),
+            (
+                "Order rejected: order is invalid. Price breaks minimum tick size rules",
+                OrderRejection::Other,
+            ),
+            ("Order posting timed out: deadline has elapsed", OrderRejection::Other),
+            ("No order response received", OrderRejection::Other),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(OrderRejection::classify(message), expected, "{}", message);
+        }
+
+        assert!(OrderRejection::PriceMoved.is_price_movement());
+        assert!(!OrderRejection::Auth.is_price_movement());
+        assert!(!OrderRejection::Nonce.is_price_movement());
+    }
+
+    #[test]
+    fn test_price_divergence_exceeded() {
+        // Live ask within tolerance of the DB ask
+        assert!(!price_divergence_exceeded(
+            dec!(0.40),
+            dec!(0.42),
+            dec!(0.02)
+        ));
+        // Just over tolerance
+        assert!(price_divergence_exceeded(
+            dec!(0.40),
+            dec!(0.421),
+            dec!(0.02)
+        ));
+        // Cheaper live ask never blocks
+        assert!(!price_divergence_exceeded(
+            dec!(0.40),
+            dec!(0.30),
+            dec!(0.02)
+        ));
+        // Zero tolerance requires the live ask to be no worse than the DB
+        assert!(!price_divergence_exceeded(
+            dec!(0.40),
+            dec!(0.40),
+            Decimal::ZERO
+        ));
+        assert!(price_divergence_exceeded(
+            dec!(0.40),
+            dec!(0.41),
+            Decimal::ZERO
+        ));
+    }
+
+    #[test]
+    fn test_clob_book_best_prices() {
+        let book: ClobBook = serde_json::from_str(
+            r#"{"bids": [{"price": "0.38", "size": "10"}, {"price": "0.40", "size": "5"}],
+                "asks": [{"price": "0.45", "size": "7"}, {"price": "0.42", "size": "3"}, {"price": "bad"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(book.best_ask(), Some(dec!(0.42)));
+        assert_eq!(book.best_bid(), Some(dec!(0.40)));
+
+        let empty: ClobBook = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty.best_ask(), None);
+        assert_eq!(empty.best_bid(), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_clob_book_rejects_error_status() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A rate-limited response with a JSON body that would parse as an empty book
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let body = r#"{"error": "rate limited"}"#;
+            let response = format!(
+                "HTTP/1.1 429 Too Many Requests\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let err = fetch_clob_book_from(&host, "yes-token").await.unwrap_err();
+        assert!(format!("{:#}", err).contains("429"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_side_filter() {
+        assert_eq!("both".parse::<SideFilter>(), Ok(SideFilter::Both));
+        assert_eq!("YES-only".parse::<SideFilter>(), Ok(SideFilter::YesOnly));
+        assert_eq!("no".parse::<SideFilter>(), Ok(SideFilter::NoOnly));
+        assert!("up".parse::<SideFilter>().is_err());
+        assert_eq!(SideFilter::default(), SideFilter::Both);
+
+        assert!(SideFilter::Both.allows("YES") && SideFilter::Both.allows("NO"));
+        assert!(SideFilter::YesOnly.allows("YES") && !SideFilter::YesOnly.allows("NO"));
+        assert!(SideFilter::NoOnly.allows("no") && !SideFilter::NoOnly.allows("YES"));
+    }
+
+    #[test]
+    fn test_entry_offset_parse() {
+        assert_eq!("ask".parse::<EntryOffset>(), Ok(EntryOffset::Ask));
+        assert_eq!("Midpoint".parse::<EntryOffset>(), Ok(EntryOffset::Midpoint));
+        assert_eq!("join-bid".parse::<EntryOffset>(), Ok(EntryOffset::JoinBid));
+        assert!("last".parse::<EntryOffset>().is_err());
+        assert_eq!(EntryOffset::default(), EntryOffset::Ask);
+        assert!(!EntryOffset::Ask.is_passive() && EntryOffset::JoinBid.is_passive());
+    }
+
+    #[test]
+    fn test_order_kind_selects_sdk_order_type() {
+        assert_eq!("limit".parse::<OrderKind>(), Ok(OrderKind::Limit));
+        assert_eq!("Marketable".parse::<OrderKind>(), Ok(OrderKind::Marketable));
+        assert!("market".parse::<OrderKind>().is_err());
+        assert_eq!(OrderKind::default(), OrderKind::Limit);
+
+        // Limit rests at the configured price until filled or cancelled
+        assert!(matches!(OrderKind::Limit.sdk_order_type(), OrderType::GTC));
+        assert_eq!(
+            OrderKind::Limit.order_price(dec!(0.99), dec!(0.93)),
+            dec!(0.99)
+        );
+        assert!(OrderKind::Limit.rests());
+
+        // Marketable takes the best ask now and never rests
+        assert!(matches!(
+            OrderKind::Marketable.sdk_order_type(),
+            OrderType::FAK
+        ));
+        assert_eq!(
+            OrderKind::Marketable.order_price(dec!(0.99), dec!(0.93)),
+            dec!(0.93)
+        );
+        assert!(!OrderKind::Marketable.rests());
+
+        // Fill-or-kill also takes the ask, but all or nothing
+        assert_eq!("FOK".parse::<OrderKind>(), Ok(OrderKind::FillOrKill));
+        assert!(matches!(
+            OrderKind::FillOrKill.sdk_order_type(),
+            OrderType::FOK
+        ));
+        assert!(!OrderKind::FillOrKill.rests());
+    }
+
+    #[test]
+    fn test_entry_offset_price() {
+        let tick = dec!(0.01);
+
+        // Wide book: 0.40 / 0.46
+        assert_eq!(
+            EntryOffset::Ask.price(Some(dec!(0.40)), dec!(0.46), tick),
+            dec!(0.46)
+        );
+        assert_eq!(
+            EntryOffset::Midpoint.price(Some(dec!(0.40)), dec!(0.46), tick),
+            dec!(0.43)
+        );
+        assert_eq!(
+            EntryOffset::JoinBid.price(Some(dec!(0.40)), dec!(0.46), tick),
+            dec!(0.41)
+        );
+
+        // Odd spread: midpoint rounds down to stay passive
+        assert_eq!(
+            EntryOffset::Midpoint.price(Some(dec!(0.40)), dec!(0.45), tick),
+            dec!(0.42)
+        );
+
+        // One-tick spread: both passive modes sit on the bid
+        assert_eq!(
+            EntryOffset::Midpoint.price(Some(dec!(0.40)), dec!(0.41), tick),
+            dec!(0.40)
+        );
+        assert_eq!(
+            EntryOffset::JoinBid.price(Some(dec!(0.40)), dec!(0.41), tick),
+            dec!(0.40)
+        );
+
+        // Sub-cent ticks
+        assert_eq!(
+            EntryOffset::JoinBid.price(Some(dec!(0.952)), dec!(0.958), dec!(0.001)),
+            dec!(0.953)
+        );
+
+        // No usable bid (missing, empty, or crossed) falls back to the ask
+        assert_eq!(
+            EntryOffset::Midpoint.price(None, dec!(0.46), tick),
+            dec!(0.46)
+        );
+        assert_eq!(
+            EntryOffset::JoinBid.price(Some(dec!(0)), dec!(0.46), tick),
+            dec!(0.46)
+        );
+        assert_eq!(
+            EntryOffset::JoinBid.price(Some(dec!(0.47)), dec!(0.46), tick),
+            dec!(0.46)
+        );
+    }
+
+    /// Run one resolution pass over an expired, never-resolving position.
+    async fn resolve_timed_out(
+        max_retries: u32,
+        policy: SettlementTimeoutPolicy,
+    ) -> DryRunPortfolio {
+        let mut pos = test_position("YES", dec!(10), dec!(0.60));
+        pos.end_time = Utc::now() - chrono::Duration::minutes(5);
+        let mut portfolio = portfolio_with(vec![pos]);
+        let settlement = SettlementTimeout {
+            max_retries,
+            policy,
+        };
+        portfolio
+            .resolve_expired(
+                &InMemoryMarketRepository::new(),
+                &MockGammaApi::new(),
+                settlement,
+            )
+            .await;
+        portfolio
+    }
+
+    #[tokio::test]
+    async fn test_settlement_timeout_loss() {
+        let portfolio = resolve_timed_out(1, SettlementTimeoutPolicy::Loss).await;
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(portfolio.pending_count, 0);
+        assert_eq!(portfolio.total_pnl, dec!(-6));
+        assert_eq!(portfolio.realized_losses, 1);
+        assert_eq!(portfolio.timed_out_unknown, 0);
+    }
+
+    #[tokio::test]
+    async fn test_settlement_timeout_unknown() {
+        let portfolio = resolve_timed_out(1, SettlementTimeoutPolicy::Unknown).await;
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(portfolio.pending_count, 0);
+        assert_eq!(portfolio.total_pnl, Decimal::ZERO);
+        assert_eq!(portfolio.realized_losses, 0);
+        assert_eq!(portfolio.timed_out_unknown, 1);
+    }
+
+    #[tokio::test]
+    async fn test_settlement_timeout_breakeven() {
+        let portfolio = resolve_timed_out(1, SettlementTimeoutPolicy::Breakeven).await;
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(portfolio.pending_count, 0);
+        assert_eq!(portfolio.total_pnl, Decimal::ZERO);
+        assert_eq!(portfolio.realized_wins + portfolio.realized_losses, 0);
+        assert_eq!(portfolio.timed_out_unknown, 0);
+    }
+
+    #[tokio::test]
+    async fn test_settlement_timeout_waits_for_retry_cap() {
+        let portfolio = resolve_timed_out(2, SettlementTimeoutPolicy::Loss).await;
+        assert_eq!(portfolio.positions.len(), 1);
+        assert_eq!(portfolio.positions[0].resolution_retries, 1);
+        assert_eq!(portfolio.pending_count, 1);
+        assert_eq!(portfolio.total_pnl, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_settlement_timeout_is_recorded() {
+        let mut pos = test_position("YES", dec!(10), dec!(0.60));
+        pos.end_time = Utc::now() - chrono::Duration::minutes(5);
+        let mut portfolio = portfolio_with(vec![pos]);
+        let repo = InMemoryMarketRepository::new();
+        let settlement = SettlementTimeout {
+            max_retries: 1,
+            policy: SettlementTimeoutPolicy::Loss,
+        };
+        portfolio
+            .resolve_expired(&repo, &MockGammaApi::new(), settlement)
+            .await;
+
+        // Persisted so the position no longer reads as unsettled
+        let settlements = repo.settlements();
+        assert_eq!(settlements.len(), 1);
+        assert_eq!(settlements[0].winning_side, TIMED_OUT_WINNING_SIDE);
+        assert_eq!(settlements[0].pnl, dec!(-6));
+        assert!(settlements[0].is_dry_run);
+    }
+
+    #[tokio::test]
+    async fn test_ambiguous_resolution_settles_without_retrying() {
+        let mut pos = test_position("YES", dec!(10), dec!(0.60));
+        pos.end_time = Utc::now() - chrono::Duration::minutes(5);
+        let gamma = MockGammaApi::new().with_resolution_detail(
+            &pos.yes_token_id,
+            ResolutionDetail {
+                winning_side: None,
+                resolved_at: None,
+                ambiguous: true,
+            },
+        );
+        let mut portfolio = portfolio_with(vec![pos]);
+        let settlement = SettlementTimeout {
+            max_retries: 10,
+            policy: SettlementTimeoutPolicy::Loss,
+        };
+        let repo = InMemoryMarketRepository::new();
+        portfolio.resolve_expired(&repo, &gamma, settlement).await;
+
+        // Settled on the first pass, well before the retry cap, at half a
+        // dollar per share rather than through the timeout policy
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(portfolio.pending_count, 0);
+        assert_eq!(portfolio.total_pnl, dec!(-1));
+        assert_eq!(portfolio.realized_losses, 0);
+        let settlements = repo.settlements();
+        assert_eq!(settlements.len(), 1);
+        assert_eq!(settlements[0].winning_side, AMBIGUOUS_WINNING_SIDE);
+        assert_eq!(settlements[0].pnl, dec!(-1));
+    }
+
+    #[tokio::test]
+    async fn test_summary_by_timeframe() {
+        let expired = Utc::now() - chrono::Duration::minutes(5);
+        let position = |timeframe: &str, side: &str, shares, price| {
+            let mut pos = test_position(side, shares, price);
+            pos.timeframe = timeframe.to_string();
+            pos.condition_id = format!("cond-{}", pos.market_id);
+            pos.end_time = expired;
+            pos
+        };
+        let win_5m = position("5m", "YES", dec!(10), dec!(0.60));
+        let loss_5m = position("5m", "NO", dec!(5), dec!(0.40));
+        let mut open_15m = position("15m", "YES", dec!(10), dec!(0.50));
+        open_15m.end_time = Utc::now() + chrono::Duration::minutes(10);
+
+        let repo = InMemoryMarketRepository::new();
+        for pos in [&win_5m, &loss_5m] {
+            repo.insert_market(orderbook_for(pos, None, None));
+            repo.insert_resolution(pos.market_id, "YES");
+        }
+        let open_id = open_15m.market_id;
+        let mut portfolio = portfolio_with(vec![win_5m, loss_5m, open_15m]);
+
+        // Nothing realized yet
+        assert!(portfolio.summary_by_timeframe().is_empty());
+
+        portfolio
+            .resolve_expired(&repo, &MockGammaApi::new(), SettlementTimeout::default())
+            .await;
+        portfolio.close_position(open_id, dec!(0.65), dec!(1.5));
+
+        // Each resolved position is persisted with its P&L
+        let mut settlements = repo.settlements();
+        settlements.sort_by_key(|s| s.pnl);
+        assert_eq!(settlements.len(), 2);
+        assert_eq!(
+            (settlements[0].side.as_str(), settlements[0].pnl),
+            ("NO", dec!(-2))
+        );
+        assert_eq!(
+            (settlements[1].side.as_str(), settlements[1].pnl),
+            ("YES", dec!(4))
+        );
+        assert!(settlements
+            .iter()
+            .all(|s| s.is_dry_run && s.winning_side == "YES"));
+
+        let summary = portfolio.summary_by_timeframe();
+        assert_eq!(summary.len(), 2);
+
+        let five = &summary["5m"];
+        assert_eq!(five.invested, dec!(8));
+        // +4.00 on the YES win, -2.00 on the NO loss
+        assert_eq!(five.pnl, dec!(2));
+        assert_eq!((five.wins, five.losses), (1, 1));
+        assert_eq!(five.win_rate(), 50.0);
+
+        let fifteen = &summary["15m"];
+        assert_eq!(fifteen.invested, dec!(5));
+        assert_eq!(fifteen.pnl, dec!(1.5));
+        assert_eq!((fifteen.wins, fifteen.losses), (1, 0));
+
+        // Per-timeframe P&L adds up to the portfolio total
+        let pnl: Decimal = summary.values().map(|s| s.pnl).sum();
+        assert_eq!(pnl, portfolio.total_pnl);
+    }
+
+    #[tokio::test]
+    async fn test_virtual_balance_refuses_then_resumes() {
+        let mut portfolio = DryRunPortfolio::new().with_balance(dec!(10));
+        assert_eq!(portfolio.available_balance(), Some(dec!(10)));
+
+        // 6.00 + 4.00 uses the whole bankroll
+        let mut win = test_position("YES", dec!(10), dec!(0.60));
+        win.condition_id = format!("cond-{}", win.market_id);
+        win.end_time = Utc::now() - chrono::Duration::minutes(5);
+        assert!(portfolio.add_position(win.clone()));
+        assert!(portfolio.add_position(test_position("NO", dec!(10), dec!(0.40))));
+        assert_eq!(portfolio.available_balance(), Some(Decimal::ZERO));
+
+        // Nothing left: refused and counted, portfolio unchanged
+        assert!(!portfolio.add_position(test_position("YES", dec!(5), dec!(0.50))));
+        assert_eq!(portfolio.balance_rejections, 1);
+        assert_eq!(portfolio.positions.len(), 2);
+        assert_eq!(portfolio.pending_count, 2);
+        assert_eq!(portfolio.total_invested, dec!(10));
+
+        // The YES win settles: its 6.00 cost comes back plus 4.00 profit
+        let repo = InMemoryMarketRepository::new();
+        repo.insert_market(orderbook_for(&win, None, None));
+        repo.insert_resolution(win.market_id, "YES");
+        portfolio
+            .resolve_expired(&repo, &MockGammaApi::new(), SettlementTimeout::default())
+            .await;
+        assert_eq!(portfolio.positions.len(), 1);
+        assert_eq!(portfolio.available_balance(), Some(dec!(10)));
+
+        // Trading resumes with the freed capital
+        assert!(portfolio.add_position(test_position("YES", dec!(10), dec!(0.50))));
+        assert_eq!(portfolio.available_balance(), Some(dec!(5)));
+
+        // Without a virtual balance nothing is refused
+        let mut unbounded = DryRunPortfolio::new();
+        assert_eq!(unbounded.available_balance(), None);
+        assert!(unbounded.add_position(test_position("YES", dec!(1000), dec!(0.99))));
+    }
+
+    #[test]
+    fn test_close_partial_in_two_tranches() {
+        let pos = test_position("YES", dec!(10), dec!(0.50));
+        let market_id = pos.market_id;
+        let mut portfolio = portfolio_with(vec![pos]);
+
+        // Wrong side or nothing to close leaves the position alone
+        assert_eq!(
+            portfolio.close_partial(market_id, "NO", dec!(4), dec!(0.70)),
+            None
+        );
+        assert_eq!(
+            portfolio.close_partial(market_id, "YES", Decimal::ZERO, dec!(0.70)),
+            None
+        );
+
+        // 4 of 10 shares at 0.70 against 2.00 of the 5.00 cost
+        assert_eq!(
+            portfolio.close_partial(market_id, "YES", dec!(4), dec!(0.70)),
+            Some(dec!(0.8))
+        );
+        assert_eq!(portfolio.positions.len(), 1);
+        assert_eq!(portfolio.positions[0].shares, dec!(6));
+        assert_eq!(portfolio.positions[0].cost, dec!(3));
+        assert_eq!(portfolio.pending_count, 1);
+        assert_eq!(portfolio.total_pnl, dec!(0.8));
+        assert_eq!(portfolio.realized_wins + portfolio.realized_losses, 0);
+
+        // Over-closing is capped at the 6 remaining shares: 2.40 - 3.00
+        assert_eq!(
+            portfolio.close_partial(market_id, "yes", dec!(10), dec!(0.40)),
+            Some(dec!(-0.6))
+        );
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(portfolio.pending_count, 0);
+        assert_eq!(portfolio.total_pnl, dec!(0.2));
+        // Counted once, as a win on the combined +0.20
+        assert_eq!((portfolio.realized_wins, portfolio.realized_losses), (1, 0));
+
+        let fifteen = &portfolio.summary_by_timeframe()["15m"];
+        assert_eq!(fifteen.invested, dec!(5));
+        assert_eq!(fifteen.pnl, dec!(0.2));
+        assert_eq!((fifteen.wins, fifteen.losses), (1, 0));
+    }
+
+    #[test]
+    fn test_close_position_clears_partial_pnl() {
+        let pos = test_position("YES", dec!(10), dec!(0.50));
+        let market_id = pos.market_id;
+        let mut portfolio = portfolio_with(vec![pos]);
+
+        // +0.80 on the first tranche, then the rest closes for -0.60
+        portfolio.close_partial(market_id, "YES", dec!(4), dec!(0.70));
+        portfolio.close_position(market_id, dec!(0.40), dec!(-0.6));
+        assert!(portfolio.partial_pnl.is_empty());
+        // Judged on the combined +0.20
+        assert_eq!((portfolio.realized_wins, portfolio.realized_losses), (1, 0));
+
+        // A later position on the same market starts from a clean slate
+        let mut reentry = test_position("YES", dec!(10), dec!(0.50));
+        reentry.market_id = market_id;
+        portfolio.add_position(reentry);
+        portfolio.close_partial(market_id, "YES", dec!(10), dec!(0.40));
+        assert_eq!((portfolio.realized_wins, portfolio.realized_losses), (1, 1));
+    }
+
+    fn orderbook_for(
+        pos: &SimulatedPosition,
+        yes_bid: Option<Decimal>,
+        no_bid: Option<Decimal>,
+    ) -> MarketWithOrderbook {
+        MarketWithOrderbook {
+            id: pos.market_id,
+            condition_id: pos.condition_id.clone(),
+            market_type: pos.market_type.clone(),
+            asset: pos.asset.clone(),
+            timeframe: pos.timeframe.clone(),
+            yes_token_id: pos.yes_token_id.clone(),
+            no_token_id: pos.no_token_id.clone(),
+            name: pos.market_name.clone(),
+            end_time: pos.end_time,
+            is_active: true,
+            yes_best_ask: Some(dec!(0.99)),
+            yes_best_bid: yes_bid,
+            no_best_ask: Some(dec!(0.99)),
+            no_best_bid: no_bid,
+            yes_asks: None,
+            no_asks: None,
+            yes_bids: None,
+            no_bids: None,
+            captured_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_update_last_prices_and_unrealized_pnl() {
+        let yes = test_position("YES", dec!(10), dec!(0.60));
+        let no = test_position("NO", dec!(5), dec!(0.40));
+        let untracked = test_position("YES", dec!(1), dec!(0.50));
+        let markets = vec![
+            orderbook_for(&yes, Some(dec!(0.70)), Some(dec!(0.28))),
+            orderbook_for(&no, Some(dec!(0.65)), None),
+        ];
+        let mut portfolio = portfolio_with(vec![yes, no, untracked]);
+
+        assert_eq!(portfolio.update_last_prices(&markets), 2);
+        // YES marks at its bid, NO falls back to its ask without a bid
+        assert_eq!(portfolio.positions[0].last_price, dec!(0.70));
+        assert_eq!(portfolio.positions[1].last_price, dec!(0.99));
+        assert_eq!(portfolio.positions[2].last_price, dec!(0.50));
+        // (7.00 - 6.00) + (4.95 - 2.00) + 0
+        assert_eq!(portfolio.unrealized_pnl(), dec!(3.95));
+    }
+
+    #[test]
+    fn test_net_position_yes_and_no_on_same_market() {
+        let mut yes = test_position("YES", dec!(10), dec!(0.60));
+        yes.last_price = dec!(0.65);
+        let mut no = test_position("NO", dec!(6), dec!(0.35));
+        no.market_id = yes.market_id;
+        no.last_price = dec!(0.30);
+        let mut other = test_position("YES", dec!(5), dec!(0.50));
+        other.last_price = dec!(0.60);
+        let mut portfolio = portfolio_with(vec![yes, no, other]);
+
+        let net = portfolio.net_positions();
+        assert_eq!(net.len(), 1);
+        let net = &net[0];
+        assert_eq!(net.cost(), dec!(8.10));
+        assert_eq!(net.hedged_shares(), dec!(6));
+        assert_eq!(net.guaranteed_payout(), dec!(6));
+        assert_eq!(net.pnl_if(true), dec!(1.90));
+        assert_eq!(net.pnl_if(false), dec!(-2.10));
+        assert_eq!(net.worst_case_pnl(), dec!(-2.10));
+
+        // 6 hedged shares pay $1, 4 unhedged YES marked at 0.65: 6 + 2.60 - 8.10.
+        // Summing the legs at their bids would give 0.50 - 0.30 = 0.20 instead.
+        assert_eq!(net.unrealized_pnl(), dec!(0.50));
+        // Plus the unhedged market's 5 * (0.60 - 0.50)
+        assert_eq!(portfolio.unrealized_pnl(), dec!(1.00));
+
+        // An unpriced remainder is held flat at its fill price: 6 + 2.40 - 8.10
+        portfolio.positions[0].last_price = Decimal::ZERO;
+        assert_eq!(portfolio.net_positions()[0].unrealized_pnl(), dec!(0.30));
+        assert_eq!(portfolio.unpriced_count(), 0);
+    }
+
+    #[test]
+    fn test_unrealized_pnl_excludes_unpriced_positions() {
+        let mut priced = test_position("YES", dec!(10), dec!(0.60));
+        priced.last_price = dec!(0.80);
+        let mut unpriced = test_position("NO", dec!(4), dec!(0.50));
+        unpriced.best_ask_price = Decimal::ZERO;
+        unpriced.last_price = Decimal::ZERO;
+        let mut portfolio = portfolio_with(vec![priced, unpriced]);
+        portfolio.total_pnl = dec!(1.25);
+
+        assert_eq!(portfolio.unpriced_count(), 1);
+        // 10 * (0.80 - 0.60); the unpriced position would otherwise count as -2.00
+        assert_eq!(portfolio.unrealized_pnl(), dec!(2.00));
+        assert_eq!(portfolio.total_equity_pnl(), dec!(3.25));
+    }
+
+    #[test]
+    fn test_add_position_defaults_last_price() {
+        let mut pos = test_position("YES", dec!(10), dec!(0.60));
+        pos.last_price = Decimal::ZERO;
+        let portfolio = portfolio_with(vec![pos]);
+        assert_eq!(portfolio.positions[0].last_price, dec!(0.60));
+    }
+
+    #[test]
+    fn test_settle_unresolved_assume_loss() {
+        let mut portfolio = portfolio_with(vec![
+            test_position("YES", dec!(10), dec!(0.60)),
+            test_position("NO", dec!(5), dec!(0.40)),
+        ]);
+
+        assert_eq!(portfolio.settle_unresolved(UnresolvedPolicy::AssumeLoss), 2);
+        assert_eq!(portfolio.total_pnl, dec!(-8));
+        assert_eq!(portfolio.realized_losses, 2);
+    }
+
+    #[test]
+    fn test_settle_unresolved_win_if_above_half() {
+        let mut portfolio = portfolio_with(vec![
+            test_position("YES", dec!(10), dec!(0.60)),
+            test_position("NO", dec!(5), dec!(0.40)),
+        ]);
+
+        assert_eq!(
+            portfolio.settle_unresolved(UnresolvedPolicy::WinIfAboveHalf),
+            2
+        );
+        // +4.00 on the 0.60 position, -2.00 on the 0.40 position
+        assert_eq!(portfolio.total_pnl, dec!(2));
+        assert_eq!(portfolio.realized_wins, 1);
+        assert_eq!(portfolio.realized_losses, 1);
+    }
+
+    #[test]
+    fn test_settle_unresolved_leave_pending() {
+        let mut portfolio = portfolio_with(vec![test_position("YES", dec!(10), dec!(0.60))]);
+
+        assert_eq!(
+            portfolio.settle_unresolved(UnresolvedPolicy::LeavePending),
+            0
+        );
+        assert_eq!(portfolio.positions.len(), 1);
+        assert_eq!(portfolio.pending_count, 1);
+        assert_eq!(portfolio.total_pnl, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_order_formatting_matches_submitted_values() {
+        let (price, shares) = normalize_order(dec!(0.4550), dec!(10.989));
+        assert_eq!(price, dec!(0.455));
+        assert_eq!(shares, dec!(10.99));
+
+        // Displayed values are the submitted ones, not a rounded view of them
+        assert_eq!(fmt_price(price), "$0.455");
+        assert_eq!(fmt_shares(shares), "10.99");
+        assert_eq!(fmt_price(dec!(0.40)), "$0.4");
+        assert_eq!(fmt_shares(dec!(12.50)), "12.5");
+        // Unnormalized input formats as what would be submitted
+        assert_eq!(fmt_shares(dec!(10.989)), "10.99");
+    }
+
+    #[test]
+    fn test_trade_throttle_enforces_min_interval() {
+        let mut throttle = TradeThrottle::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        // Nothing placed yet
+        assert_eq!(throttle.remaining(start), None);
+        throttle.record_order(start);
+
+        // A second trade right away has to wait out the interval
+        let rapid = start + Duration::from_millis(100);
+        assert_eq!(throttle.remaining(rapid), Some(Duration::from_millis(400)));
+        assert_eq!(
+            throttle.remaining(start + Duration::from_millis(499)),
+            Some(Duration::from_millis(1))
+        );
+
+        // Clear exactly at the boundary, and after it
+        assert_eq!(throttle.remaining(start + Duration::from_millis(500)), None);
+        let later = start + Duration::from_millis(750);
+        assert_eq!(throttle.remaining(later), None);
+        throttle.record_order(later);
+        assert!(throttle
+            .remaining(later + Duration::from_millis(10))
+            .is_some());
+    }
+
+    #[test]
+    fn test_trade_throttle_disabled_never_throttles() {
+        let mut throttle = TradeThrottle::disabled();
+        assert!(!throttle.is_enabled());
+
+        let now = Instant::now();
+        throttle.record_order(now);
+        throttle.record_order(now);
+        assert_eq!(throttle.remaining(now), None);
+    }
+
+    #[test]
+    fn test_daily_stop_halts_entries_but_not_settlements() {
+        let mut portfolio = DryRunPortfolio::new();
+        let first = test_position("YES", dec!(10), dec!(0.50));
+        let second = test_position("NO", dec!(10), dec!(0.40));
+        let (first_id, second_id) = (first.market_id, second.market_id);
+        assert!(portfolio.add_position(first));
+        assert!(portfolio.add_position(second));
+
+        let mut stop = DailyPnlStop::new(Some(dec!(4)), Some(dec!(3)));
+        let morning = "2026-03-02T09:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(stop.update(portfolio.total_pnl, morning), None);
+        assert!(stop.allows_entry());
+
+        // A winning settlement crosses the $4 target
+        portfolio.close_position(first_id, dec!(1), dec!(5));
+        let noon = morning + chrono::Duration::hours(3);
+        assert_eq!(
+            stop.update(portfolio.total_pnl, noon),
+            Some(DailyStopReason::ProfitTarget)
+        );
+        assert!(!stop.allows_entry());
+
+        // The open position still settles; the halt holds even as P&L falls back
+        portfolio.close_position(second_id, dec!(0), dec!(-4));
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(stop.update(portfolio.total_pnl, noon), None);
+        assert_eq!(stop.day_pnl(), dec!(1));
+        assert_eq!(stop.halted(), Some(DailyStopReason::ProfitTarget));
+
+        // UTC midnight resets the day from the current cumulative P&L
+        let next_day = morning + chrono::Duration::hours(15);
+        assert_eq!(stop.update(portfolio.total_pnl, next_day), None);
+        assert!(stop.allows_entry());
+        assert_eq!(stop.day_pnl(), dec!(0));
+
+        // A $3 loss on the new day trips the max-loss bound
+        assert_eq!(
+            stop.update(portfolio.total_pnl - dec!(3), next_day),
+            Some(DailyStopReason::MaxLoss)
+        );
+        assert!(!DailyPnlStop::new(None, None).is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_check_signals_concurrently_never_double_trades() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        // (market, side, check delay ms, check passes)
+        let signals = vec![
+            (a, "YES", 30, true),
+            (b, "NO", 5, true),
+            (a, "YES", 1, true), // duplicate of the first
+            (a, "NO", 10, false),
+            (c, "YES", 1, true), // already traded
+            (b, "YES", 20, true),
+        ];
+        let traded: HashSet<(Uuid, String)> = [(c, "YES".to_string())].into();
+
+        let checks = AtomicUsize::new(0);
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let passed = check_signals_concurrently(
+            signals,
+            |&(market, side, _, _)| (market, side.to_string()),
+            &traded,
+            2,
+            |&(_, _, delay_ms, ok)| {
+                let (checks, in_flight, peak) = (&checks, &in_flight, &peak);
+                async move {
+                    checks.fetch_add(1, Ordering::SeqCst);
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    ok
+                }
+            },
+        )
+        .await;
+
+        let keys: Vec<(Uuid, &str)> = passed.iter().map(|&(m, side, _, _)| (m, side)).collect();
+        // One entry per (market, side), in input order, rejected and traded dropped
+        assert_eq!(keys, vec![(a, "YES"), (b, "NO"), (b, "YES")]);
+        // Duplicates and traded keys are never checked
+        assert_eq!(checks.load(Ordering::SeqCst), 4);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_normalize_price_to_tick_cent() {
+        let tick = dec!(0.01);
+        let buy = |p| normalize_price_to_tick(p, tick, RoundingStrategy::ToNegativeInfinity);
+        let sell = |p| normalize_price_to_tick(p, tick, RoundingStrategy::ToPositiveInfinity);
+        assert_eq!(buy(dec!(0.45)), dec!(0.45));
+        assert_eq!(sell(dec!(0.45)), dec!(0.45));
+        // Buys never round up past the caller's price
+        assert_eq!(buy(dec!(0.4549)), dec!(0.45));
+        assert_eq!(buy(dec!(0.455)), dec!(0.45));
+        assert_eq!(buy(dec!(0.4599)), dec!(0.45));
+        // Sells never round down below it
+        assert_eq!(sell(dec!(0.4501)), dec!(0.46));
+        assert_eq!(sell(dec!(0.445)), dec!(0.45));
+        // Clamped to the valid price range
+        assert_eq!(buy(dec!(0.004)), dec!(0.01));
+        assert_eq!(sell(dec!(0.996)), dec!(0.99));
+        assert_eq!(buy(dec!(0.01)), dec!(0.01));
+        assert_eq!(sell(dec!(0.99)), dec!(0.99));
+        // Trailing zeros are stripped
+        assert_eq!(buy(dec!(0.4000)).to_string(), "0.4");
+    }
+
+    #[test]
+    fn test_normalize_price_to_tick_mill() {
+        let tick = dec!(0.001);
+        let buy = |p| normalize_price_to_tick(p, tick, RoundingStrategy::ToNegativeInfinity);
+        let sell = |p| normalize_price_to_tick(p, tick, RoundingStrategy::ToPositiveInfinity);
+        assert_eq!(buy(dec!(0.455)), dec!(0.455));
+        assert_eq!(buy(dec!(0.45549)), dec!(0.455));
+        assert_eq!(sell(dec!(0.45501)), dec!(0.456));
+        assert_eq!(buy(dec!(0.0004)), dec!(0.001));
+        assert_eq!(sell(dec!(0.0005)), dec!(0.001));
+        assert_eq!(buy(dec!(0.9994)), dec!(0.999));
+        assert_eq!(sell(dec!(0.9994)), dec!(0.999));
+
+        // A non-positive tick leaves the price alone
+        assert_eq!(
+            normalize_price_to_tick(
+                dec!(0.4555),
+                Decimal::ZERO,
+                RoundingStrategy::ToPositiveInfinity
+            ),
+            dec!(0.4555)
+        );
+    }
+
+    #[test]
+    fn test_check_order_size_respects_cap() {
+        // $50 at $0.40 needs 125 shares: over the default cap, fine under a raised one
+        let shares = (dec!(50) / dec!(0.40)).round_dp(2);
+        assert_eq!(shares, dec!(125));
+        assert!(check_order_size(shares, DEFAULT_MAX_SHARES).is_err());
+        assert!(check_order_size(shares, dec!(125)).is_ok());
+        assert!(check_order_size(shares, dec!(250)).is_ok());
+        assert!(check_order_size(dec!(250.01), dec!(250)).is_err());
+
+        // Sizes are checked at the CLOB's two decimals
+        assert!(check_order_size(dec!(99.994), DEFAULT_MAX_SHARES).is_ok());
+        assert!(check_order_size(dec!(0.004), DEFAULT_MAX_SHARES).is_err());
+        assert!(check_order_size(Decimal::ZERO, DEFAULT_MAX_SHARES).is_err());
+    }
+
+    fn open_order(order_id: &str, token_id: &str) -> OpenOrder {
+        OpenOrder {
+            order_id: order_id.to_string(),
+            token_id: token_id.to_string(),
+            side: "BUY".to_string(),
+            price: dec!(0.45),
+            original_size: dec!(10),
+            size_matched: dec!(2),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_open_orders_cancels_only_configured_tokens() {
+        let api = MockOrderApi::new()
+            .with_order(open_order("order-1", "btc-yes"))
+            .with_order(open_order("order-2", "eth-no"))
+            .with_order(open_order("order-3", "other-token"));
+        let tokens: HashSet<String> = ["btc-yes", "eth-no"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        let orphans = reconcile_open_orders(&api, &tokens, true).await.unwrap();
+
+        assert_eq!(orphans.len(), 2);
+        assert_eq!(api.cancelled(), vec!["order-1", "order-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_open_orders_log_only_and_cancel_failure() {
+        let api = MockOrderApi::new()
+            .with_order(open_order("order-1", "btc-yes"))
+            .with_order(open_order("order-2", "btc-yes"))
+            .with_failing_cancel("order-1");
+        let tokens: HashSet<String> = ["btc-yes".to_string()].into_iter().collect();
+
+        let orphans = reconcile_open_orders(&api, &tokens, false).await.unwrap();
+        assert_eq!(orphans.len(), 2);
+        assert!(api.cancelled().is_empty());
+
+        // A failed cancel doesn't stop the remaining orders from being cancelled
+        let orphans = reconcile_open_orders(&api, &tokens, true).await.unwrap();
+        assert_eq!(orphans.len(), 2);
+        assert_eq!(api.cancelled(), vec!["order-2"]);
+    }
+
+    // Well-known development key (anvil account #0), never funded on Polygon
+    const TEST_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const TEST_KEY_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const TEST_PROXY: &str = "0x1111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_signature_config_env_matrix() {
+        // No key at all, or a blank one
+        assert!(signature_config_from(None, None).is_err());
+        assert!(signature_config_from(Some("  "), Some(TEST_PROXY)).is_err());
+        assert!(signature_config_from(Some("not-a-key"), None).is_err());
+
+        // Key without proxy trades as an EOA; the 0x prefix is optional
+        for key in [TEST_KEY.to_string(), format!("0x{}", TEST_KEY)] {
+            let (signer, signature_type, funder) = signature_config_from(Some(&key), None).unwrap();
+            assert_eq!(
+                signer.address(),
+                TEST_KEY_ADDRESS.parse::<Address>().unwrap()
+            );
+            assert!(matches!(signature_type, SignatureType::Eoa));
+            assert!(funder.is_none());
+        }
+
+        // A blank proxy line is the same as no proxy
+        let (_, signature_type, funder) = signature_config_from(Some(TEST_KEY), Some("")).unwrap();
+        assert!(matches!(signature_type, SignatureType::Eoa));
+        assert!(funder.is_none());
+
+        // Key with proxy trades as a Safe funded by the proxy
+        let (_, signature_type, funder) =
+            signature_config_from(Some(TEST_KEY), Some(TEST_PROXY)).unwrap();
+        assert!(matches!(signature_type, SignatureType::GnosisSafe));
+        assert_eq!(funder, Some(TEST_PROXY.parse().unwrap()));
+
+        // Proxy equal to the signer is suspicious but allowed (warned)
+        let (_, signature_type, funder) =
+            signature_config_from(Some(TEST_KEY), Some(TEST_KEY_ADDRESS)).unwrap();
+        assert!(matches!(signature_type, SignatureType::GnosisSafe));
+        assert_eq!(funder, Some(TEST_KEY_ADDRESS.parse().unwrap()));
+    }
+
+    #[test]
+    fn test_signature_config_rejects_malformed_proxy() {
+        for proxy in [
+            "1111111111111111111111111111111111111111",
+            "0x111111111111111111111111111111111111111",
+            "0x11111111111111111111111111111111111111111",
+            "0x111111111111111111111111111111111111111g",
+            "my-proxy-wallet",
+        ] {
+            let err = signature_config_from(Some(TEST_KEY), Some(proxy)).unwrap_err();
+            assert!(
+                err.to_string().contains("POLYMARKET_WALLET_ADDRESS"),
+                "{}: {}",
+                proxy,
+                err
+            );
+        }
+    }
+}
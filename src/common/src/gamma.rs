@@ -3,12 +3,17 @@
 //! Uses the /events endpoint to fetch crypto Up/Down markets by series_id.
 //! See: https://docs.polymarket.com/quickstart/fetching-data
 
+use std::sync::Arc;
+
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
+use crate::models::{binary_indices, binary_view, BinaryView, MultiOutcomeMarket, Outcome};
+use crate::rate_limit::{self, RateLimiter};
 use crate::Config;
 
 #[derive(Debug, Error)]
@@ -23,6 +28,18 @@ pub enum GammaError {
     ApiError(String),
 }
 
+/// Events per page when paging a single series.
+const SERIES_PAGE_SIZE: usize = 100;
+
+/// Events per page when paging all open events.
+const OPEN_EVENTS_PAGE_SIZE: usize = 500;
+
+/// Safety cap on pages fetched by one paginated `/events` query.
+const MAX_EVENT_PAGES: usize = 10;
+
+/// Default request rate for the Gamma API host.
+const GAMMA_REQUESTS_PER_SEC: f64 = 10.0;
+
 /// Known crypto series IDs for Up/Down markets (all timeframes)
 pub const CRYPTO_SERIES: &[(&str, &str)] = &[
     // BTC Up or Down
@@ -148,6 +165,9 @@ pub struct GammaMarket {
     pub clob_token_ids: Option<String>,
     pub active: Option<bool>,
     pub closed: Option<bool>,
+    /// When the market closed, e.g. "2026-01-15 12:15:03+00"
+    #[serde(rename = "closedTime")]
+    pub closed_time: Option<String>,
     pub slug: Option<String>,
     #[serde(rename = "marketMakerAddress")]
     pub market_maker_address: Option<String>,
@@ -157,9 +177,100 @@ pub struct GammaMarket {
     /// Best ask price
     #[serde(rename = "bestAsk")]
     pub best_ask: Option<f64>,
+    /// Market liquidity in dollars
+    #[serde(rename = "liquidityNum")]
+    pub liquidity_num: Option<f64>,
+}
+
+/// Outcome structure of a Gamma market.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutcomeShape {
+    /// Two outcomes, two token IDs
+    Binary,
+    /// More than two outcomes, one token ID each
+    MultiOutcome(usize),
+    /// Missing or unparseable outcomes/token IDs, or counts that disagree
+    Malformed,
+}
+
+/// How a closed binary market resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolutionDetail {
+    /// "YES" or "NO"; None when the market resolved ambiguously
+    pub winning_side: Option<String>,
+    /// When Gamma reports the market closed, if it says
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// Resolved 50/50 (how Polymarket settles invalid markets), so neither
+    /// side won outright and retrying will not produce a winner
+    pub ambiguous: bool,
 }
 
 impl GammaMarket {
+    /// Classify the market by its outcome and token counts.
+    ///
+    /// Only `Binary` markets can be mapped onto a YES/NO token pair.
+    pub fn outcome_shape(&self) -> OutcomeShape {
+        let (Some(outcomes), Some(token_ids)) = (self.parse_outcomes(), self.parse_token_ids())
+        else {
+            return OutcomeShape::Malformed;
+        };
+        match (outcomes.len(), token_ids.len()) {
+            (2, 2) => OutcomeShape::Binary,
+            (n, t) if n > 2 && n == t => OutcomeShape::MultiOutcome(n),
+            _ => OutcomeShape::Malformed,
+        }
+    }
+
+    /// Pair each outcome label with its token ID and last price.
+    ///
+    /// Bid/ask are left unset; Gamma only reports them for the first outcome.
+    /// None if outcomes/token IDs are missing or their counts disagree.
+    pub fn to_outcomes(&self) -> Option<Vec<Outcome>> {
+        let labels = self.parse_outcomes()?;
+        let token_ids = self.parse_token_ids()?;
+        if labels.len() != token_ids.len() {
+            return None;
+        }
+        let prices = self
+            .outcome_prices
+            .as_ref()
+            .and_then(|s| serde_json::from_str::<Vec<String>>(s).ok())
+            .filter(|p| p.len() == labels.len());
+
+        Some(
+            labels
+                .into_iter()
+                .zip(token_ids)
+                .enumerate()
+                .map(|(i, (label, token_id))| Outcome {
+                    label,
+                    token_id,
+                    best_bid: None,
+                    best_ask: None,
+                    last_price: prices.as_ref().and_then(|p| p[i].parse().ok()),
+                })
+                .collect(),
+        )
+    }
+
+    /// Build a multi-outcome model, or None if the market isn't one.
+    pub fn to_multi_outcome(&self) -> Option<MultiOutcomeMarket> {
+        if !matches!(self.outcome_shape(), OutcomeShape::MultiOutcome(_)) {
+            return None;
+        }
+
+        Some(MultiOutcomeMarket {
+            condition_id: self.condition_id.clone(),
+            name: self.question.clone(),
+            end_time: self
+                .end_date
+                .as_ref()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            outcomes: self.to_outcomes()?,
+        })
+    }
+
     /// Parse the clob_token_ids JSON string into a vector
     pub fn parse_token_ids(&self) -> Option<Vec<String>> {
         self.clob_token_ids
@@ -173,6 +284,79 @@ impl GammaMarket {
             .as_ref()
             .and_then(|s| serde_json::from_str::<Vec<String>>(s).ok())
     }
+
+    /// Parse closedTime, which Gamma sends either as RFC 3339 or as
+    /// "YYYY-MM-DD HH:MM:SS+00".
+    pub fn parse_closed_time(&self) -> Option<DateTime<Utc>> {
+        let s = self.closed_time.as_deref()?;
+        DateTime::parse_from_rfc3339(s)
+            .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%#z"))
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    }
+
+    /// Resolution of a closed binary market, read from its outcome prices.
+    ///
+    /// `["1", "0"]` means the first outcome won and `["0", "1"]` the second;
+    /// an even split means an ambiguous (50/50 or invalid) resolution. None
+    /// while the market is open, for non-binary markets, or when the prices
+    /// don't settle it yet.
+    pub fn resolution_detail(&self) -> Option<ResolutionDetail> {
+        if !self.closed.unwrap_or(false) {
+            debug!("Market {} is not yet closed", self.condition_id);
+            return None;
+        }
+
+        // A YES/NO resolution is meaningless for categorical markets
+        if let OutcomeShape::MultiOutcome(n) = self.outcome_shape() {
+            warn!(
+                "[NON-BINARY] Market {} has {} outcomes; no YES/NO resolution",
+                self.condition_id, n
+            );
+            return None;
+        }
+
+        let prices = self
+            .outcome_prices
+            .as_ref()
+            .and_then(|s| serde_json::from_str::<Vec<String>>(s).ok())
+            .filter(|p| p.len() == 2)?;
+        let (Ok(p0), Ok(p1)) = (prices[0].parse::<f64>(), prices[1].parse::<f64>()) else {
+            return None;
+        };
+
+        // Parse outcomes to determine which is YES/NO
+        let outcomes = self.parse_outcomes().unwrap_or_default();
+        let yes_idx = if outcomes.len() == 2 {
+            outcomes
+                .iter()
+                .position(|o| {
+                    let lower = o.to_lowercase();
+                    lower == "yes" || lower == "up" || lower == "higher" || lower == "above"
+                })
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        let winning_side = if p0 > 0.5 {
+            Some(if yes_idx == 0 { "YES" } else { "NO" })
+        } else if p1 > 0.5 {
+            Some(if yes_idx == 1 { "YES" } else { "NO" })
+        } else {
+            None
+        };
+        let ambiguous = winning_side.is_none() && p0 > 0.0 && p0 == p1;
+        if winning_side.is_none() && !ambiguous {
+            return None;
+        }
+
+        Some(ResolutionDetail {
+            winning_side: winning_side.map(str::to_string),
+            resolved_at: self.parse_closed_time(),
+            ambiguous,
+        })
+    }
 }
 
 /// Parsed market ready for database insertion.
@@ -194,96 +378,160 @@ pub struct ParsedMarket {
     pub no_best_bid: Option<rust_decimal::Decimal>,
     /// Best ask for NO outcome (calculated as 1 - yes_best_bid)
     pub no_best_ask: Option<rust_decimal::Decimal>,
+    /// All outcomes in Gamma order; the YES/NO fields above mirror [`Self::binary_view`]
+    pub outcomes: Vec<Outcome>,
+    /// Market liquidity in dollars (from Gamma API)
+    pub liquidity: Option<rust_decimal::Decimal>,
+}
+
+impl ParsedMarket {
+    /// The YES/NO outcome pair, or None if this isn't a two-outcome market.
+    pub fn binary_view(&self) -> Option<BinaryView<'_>> {
+        binary_view(&self.outcomes)
+    }
+}
+
+/// Gamma API operations used by the resolvers and traders.
+/// Implemented by `GammaClient` for production and `MockGammaApi` for tests/backtests.
+#[async_trait]
+pub trait GammaApi: Send + Sync {
+    /// Fetch a market's resolution by token_id ("YES"/"NO"), or None if unresolved.
+    async fn fetch_market_resolution(&self, token_id: &str) -> Result<Option<String>, GammaError>;
+
+    /// Fetch a market's full resolution by token_id, or None if unresolved.
+    async fn fetch_market_resolution_detail(
+        &self,
+        token_id: &str,
+    ) -> Result<Option<ResolutionDetail>, GammaError>;
+
+    /// Fetch and parse all supported markets.
+    async fn fetch_supported_markets(&self) -> Result<Vec<ParsedMarket>, GammaError>;
 }
 
 /// Gamma API client.
 pub struct GammaClient {
     client: Client,
     base_url: String,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl GammaClient {
     /// Create a new Gamma API client.
     pub fn new(config: &Config) -> Self {
+        Self::with_base_url(config.gamma_api_url.clone())
+    }
+
+    fn with_base_url(base_url: String) -> Self {
         Self {
             client: Client::new(),
-            base_url: config.gamma_api_url.clone(),
+            rate_limiter: rate_limit::for_url(&base_url, GAMMA_REQUESTS_PER_SEC),
+            base_url,
         }
     }
 
+    /// Pace requests with `rate_limiter` instead of the limiter shared by
+    /// every client of the API host.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     /// Fetch active events for a specific series from the Gamma API.
     pub async fn fetch_events_by_series(
         &self,
         series_id: &str,
     ) -> Result<Vec<GammaEvent>, GammaError> {
-        let url = format!("{}/events", self.base_url);
-
         debug!("Fetching events for series_id={}", series_id);
 
-        let response = self
-            .client
-            .get(&url)
-            .query(&[
-                ("series_id", series_id),
-                ("active", "true"),
-                ("closed", "false"),
-                ("limit", "100"),
-            ])
-            .send()
+        let (events, pages) = self
+            .fetch_events_paged(
+                &[
+                    ("series_id", series_id),
+                    ("active", "true"),
+                    ("closed", "false"),
+                ],
+                SERIES_PAGE_SIZE,
+            )
             .await?;
-
-        if !response.status().is_success() {
-            return Err(GammaError::ApiError(format!(
-                "API returned status: {}",
-                response.status()
-            )));
-        }
-
-        let events: Vec<GammaEvent> = response.json().await?;
-        debug!("Fetched {} events for series {}", events.len(), series_id);
+        debug!(
+            "Fetched {} events for series {} ({} pages)",
+            events.len(),
+            series_id,
+            pages
+        );
         Ok(events)
     }
 
     /// Fetch all open events (paginated, not limited to series).
     pub async fn fetch_all_open_events(&self) -> Result<Vec<GammaEvent>, GammaError> {
-        let mut all_events = Vec::new();
-        let mut offset = 0;
-        let max_events = 5000;
+        let (events, pages) = self
+            .fetch_events_paged(&[("closed", "false")], OPEN_EVENTS_PAGE_SIZE)
+            .await?;
+        info!(
+            "Fetched {} total open events ({} pages)",
+            events.len(),
+            pages
+        );
+        Ok(events)
+    }
 
-        while offset < max_events {
-            let url = format!("{}/events", self.base_url);
+    /// Page through `/events` with `query` until a short page, stopping after
+    /// `MAX_EVENT_PAGES`. Returns the events and the number of pages fetched.
+    ///
+    /// A failed first page is an error; a later failure keeps what was fetched.
+    async fn fetch_events_paged(
+        &self,
+        query: &[(&str, &str)],
+        page_size: usize,
+    ) -> Result<(Vec<GammaEvent>, usize), GammaError> {
+        let url = format!("{}/events", self.base_url);
+        let limit = page_size.to_string();
+        let mut all_events = Vec::new();
+        let mut pages = 0;
 
+        while pages < MAX_EVENT_PAGES {
+            let offset = (pages * page_size).to_string();
+            self.rate_limiter.acquire().await;
             let response = self
                 .client
                 .get(&url)
-                .query(&[
-                    ("closed", "false"),
-                    ("limit", "500"),
-                    ("offset", &offset.to_string()),
-                ])
+                .query(query)
+                .query(&[("limit", limit.as_str()), ("offset", offset.as_str())])
                 .send()
                 .await?;
 
             if !response.status().is_success() {
-                break;
+                if pages == 0 {
+                    return Err(GammaError::ApiError(format!(
+                        "API returned status: {}",
+                        response.status()
+                    )));
+                }
+                warn!(
+                    "[GAMMA] Page {} of /events returned {}, keeping {} events fetched so far",
+                    pages + 1,
+                    response.status(),
+                    all_events.len()
+                );
+                return Ok((all_events, pages));
             }
 
             let events: Vec<GammaEvent> = response.json().await?;
-            if events.is_empty() {
-                break;
-            }
-
+            pages += 1;
             let count = events.len();
             all_events.extend(events);
-            offset += 500;
 
-            if count < 500 {
-                break;
+            if count < page_size {
+                return Ok((all_events, pages));
             }
         }
 
-        info!("Fetched {} total open events", all_events.len());
-        Ok(all_events)
+        warn!(
+            "[GAMMA] Stopped after {} pages of /events ({} events); more may exist",
+            pages,
+            all_events.len()
+        );
+        Ok((all_events, pages))
     }
 
     /// Fetch all active crypto markets from known series.
@@ -334,16 +582,8 @@ impl GammaClient {
                     continue;
                 }
 
-                // Check for exactly 2 outcomes
-                if let Some(outcomes) = market.parse_outcomes() {
-                    if outcomes.len() == 2 {
-                        // Check for exactly 2 token IDs
-                        if let Some(token_ids) = market.parse_token_ids() {
-                            if token_ids.len() == 2 {
-                                all_markets.push(market);
-                            }
-                        }
-                    }
+                if market.outcome_shape() == OutcomeShape::Binary {
+                    all_markets.push(market);
                 }
             }
         }
@@ -388,6 +628,17 @@ impl GammaClient {
 
         info!("Combined {} total markets (deduped)", all_markets.len());
 
+        let non_binary = all_markets
+            .iter()
+            .filter(|m| matches!(m.outcome_shape(), OutcomeShape::MultiOutcome(_)))
+            .count();
+        if non_binary > 0 {
+            info!(
+                "[NON-BINARY] Excluding {} multi-outcome markets",
+                non_binary
+            );
+        }
+
         let parsed: Vec<ParsedMarket> = all_markets
             .into_iter()
             .filter_map(|m| self.parse_market(m))
@@ -412,53 +663,33 @@ impl GammaClient {
             return None;
         }
 
-        // Parse token IDs - should be array of 2 strings
-        let token_ids = market.parse_token_ids()?;
-        if token_ids.len() != 2 {
-            debug!(
-                "Skipping market with {} token IDs: {}",
-                token_ids.len(),
-                market.question
-            );
-            return None;
-        }
-
-        // Parse outcomes to determine YES/NO or UP/DOWN mapping
-        let outcomes = market.parse_outcomes()?;
-        if outcomes.len() != 2 {
-            debug!(
-                "Skipping market with {} outcomes: {}",
-                outcomes.len(),
-                market.question
-            );
-            return None;
+        // Only binary markets map onto a YES/NO token pair
+        match market.outcome_shape() {
+            OutcomeShape::Binary => {}
+            OutcomeShape::MultiOutcome(n) => {
+                debug!(
+                    "[NON-BINARY] Skipping {}-outcome market: {}",
+                    n, market.question
+                );
+                return None;
+            }
+            OutcomeShape::Malformed => {
+                debug!(
+                    "Skipping market with malformed outcomes/token IDs: {}",
+                    market.question
+                );
+                return None;
+            }
         }
+        let mut outcomes = market.to_outcomes()?;
 
-        // Find UP/YES and DOWN/NO token indices
+        // Find UP/YES and DOWN/NO outcomes
         // For Up/Down markets: "Up" is like "Yes" (positive outcome)
-        let (yes_idx, no_idx) = {
-            let yes_pos = outcomes.iter().position(|o| {
-                let lower = o.to_lowercase();
-                lower == "yes"
-                    || lower == "up"
-                    || lower.contains("higher")
-                    || lower.contains("above")
-            });
-            let no_pos = outcomes.iter().position(|o| {
-                let lower = o.to_lowercase();
-                lower == "no"
-                    || lower == "down"
-                    || lower.contains("lower")
-                    || lower.contains("below")
-            });
-            match (yes_pos, no_pos) {
-                (Some(y), Some(n)) => (y, n),
-                _ => (0, 1), // Default: first is Yes/Up, second is No/Down
-            }
-        };
+        let (yes_idx, no_idx) = binary_indices(&outcomes)?;
 
         // Determine market type - try outcomes first, then question text
-        let mut market_type = MarketType::from_outcomes(&outcomes);
+        let labels: Vec<String> = outcomes.iter().map(|o| o.label.clone()).collect();
+        let mut market_type = MarketType::from_outcomes(&labels);
         if market_type == MarketType::Unknown {
             market_type = MarketType::from_market_name(&market.question);
         }
@@ -482,6 +713,14 @@ impl GammaClient {
         let one = rust_decimal::Decimal::ONE;
         let no_best_bid = yes_best_ask.map(|ask| one - ask);
         let no_best_ask = yes_best_bid.map(|bid| one - bid);
+        let liquidity = market
+            .liquidity_num
+            .and_then(|l| rust_decimal::Decimal::try_from(l).ok());
+
+        outcomes[yes_idx].best_bid = yes_best_bid;
+        outcomes[yes_idx].best_ask = yes_best_ask;
+        outcomes[no_idx].best_bid = no_best_bid;
+        outcomes[no_idx].best_ask = no_best_ask;
 
         debug!(
             "Parsed market: {} | type={:?} | asset={} | timeframe={} | ends={} | yes_bid={:?} | yes_ask={:?}",
@@ -493,30 +732,45 @@ impl GammaClient {
             market_type,
             asset,
             timeframe,
-            yes_token_id: token_ids[yes_idx].clone(),
-            no_token_id: token_ids[no_idx].clone(),
+            yes_token_id: outcomes[yes_idx].token_id.clone(),
+            no_token_id: outcomes[no_idx].token_id.clone(),
             name: market.question,
             end_time,
             yes_best_bid,
             yes_best_ask,
             no_best_bid,
             no_best_ask,
+            outcomes,
+            liquidity,
         })
     }
 
     /// Fetch a market by token_id and return its resolution if closed.
-    /// Returns (winning_side, outcome_prices) where winning_side is "YES" or "NO".
+    /// Returns the winning side, "YES" or "NO".
+    pub async fn fetch_market_resolution(
+        &self,
+        token_id: &str,
+    ) -> Result<Option<String>, GammaError> {
+        Ok(self
+            .fetch_market_resolution_detail(token_id)
+            .await?
+            .and_then(|detail| detail.winning_side))
+    }
+
+    /// Fetch a market by token_id and return its full resolution if closed:
+    /// winning side, resolution time and whether it resolved ambiguously.
     ///
     /// Note: Uses the clob_token_ids query parameter since /markets/{id} only accepts
     /// numeric IDs, not condition_id hex values.
-    pub async fn fetch_market_resolution(
+    pub async fn fetch_market_resolution_detail(
         &self,
         token_id: &str,
-    ) -> Result<Option<String>, GammaError> {
+    ) -> Result<Option<ResolutionDetail>, GammaError> {
         let url = format!("{}/markets", self.base_url);
 
         debug!("Fetching market resolution for token_id={}", token_id);
 
+        self.rate_limiter.acquire().await;
         let response = self
             .client
             .get(&url)
@@ -543,54 +797,119 @@ impl GammaClient {
             }
         };
 
-        // Check if market is closed/resolved
-        if !market.closed.unwrap_or(false) {
-            debug!("Market {} is not yet closed", token_id);
-            return Ok(None);
+        let detail = market.resolution_detail();
+        match &detail {
+            Some(d) => debug!("Market {} resolved: {:?}", token_id, d),
+            None => debug!("Could not determine resolution for market {}", token_id),
         }
+        Ok(detail)
+    }
 
-        // Try to get resolution from outcome_prices
-        // Format: ["1", "0"] means first outcome (YES/Up) won
-        // Format: ["0", "1"] means second outcome (NO/Down) won
-        if let Some(prices_str) = &market.outcome_prices {
-            if let Ok(prices) = serde_json::from_str::<Vec<String>>(prices_str) {
-                if prices.len() == 2 {
-                    // Parse outcomes to determine which is YES/NO
-                    let outcomes = market.parse_outcomes().unwrap_or_default();
-                    let (yes_idx, _no_idx) = if outcomes.len() == 2 {
-                        let yes_pos = outcomes.iter().position(|o| {
-                            let lower = o.to_lowercase();
-                            lower == "yes" || lower == "up" || lower == "higher" || lower == "above"
-                        });
-                        let no_pos = outcomes.iter().position(|o| {
-                            let lower = o.to_lowercase();
-                            lower == "no" || lower == "down" || lower == "lower" || lower == "below"
-                        });
-                        (yes_pos.unwrap_or(0), no_pos.unwrap_or(1))
-                    } else {
-                        (0, 1)
-                    };
-
-                    // Check which outcome won (price = "1" means winner)
-                    if let (Ok(p0), Ok(p1)) = (prices[0].parse::<f64>(), prices[1].parse::<f64>()) {
-                        if p0 > 0.5 {
-                            // First outcome won
-                            let winning = if yes_idx == 0 { "YES" } else { "NO" };
-                            debug!("Market {} resolved to {}", token_id, winning);
-                            return Ok(Some(winning.to_string()));
-                        } else if p1 > 0.5 {
-                            // Second outcome won
-                            let winning = if yes_idx == 1 { "YES" } else { "NO" };
-                            debug!("Market {} resolved to {}", token_id, winning);
-                            return Ok(Some(winning.to_string()));
-                        }
-                    }
-                }
-            }
+    /// Fetch a single market of any kind, e.g. to check the API is reachable.
+    pub async fn fetch_one_market(&self) -> Result<Option<GammaMarket>, GammaError> {
+        let url = format!("{}/markets", self.base_url);
+
+        self.rate_limiter.acquire().await;
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("limit", "1")])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(GammaError::ApiError(format!(
+                "API returned status: {}",
+                response.status()
+            )));
+        }
+
+        let markets: Vec<GammaMarket> = response.json().await?;
+        Ok(markets.into_iter().next())
+    }
+}
+
+#[async_trait]
+impl GammaApi for GammaClient {
+    async fn fetch_market_resolution(&self, token_id: &str) -> Result<Option<String>, GammaError> {
+        GammaClient::fetch_market_resolution(self, token_id).await
+    }
+
+    async fn fetch_market_resolution_detail(
+        &self,
+        token_id: &str,
+    ) -> Result<Option<ResolutionDetail>, GammaError> {
+        GammaClient::fetch_market_resolution_detail(self, token_id).await
+    }
+
+    async fn fetch_supported_markets(&self) -> Result<Vec<ParsedMarket>, GammaError> {
+        GammaClient::fetch_supported_markets(self).await
+    }
+}
+
+/// In-memory Gamma API for tests and offline backtests.
+/// Resolutions are keyed by token_id; unknown tokens are reported as unresolved.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone, Default)]
+pub struct MockGammaApi {
+    pub resolutions: std::collections::HashMap<String, String>,
+    /// Full resolutions, taking precedence over `resolutions`
+    pub details: std::collections::HashMap<String, ResolutionDetail>,
+    pub markets: Vec<ParsedMarket>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MockGammaApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a resolution ("YES" or "NO") for a token_id.
+    pub fn with_resolution(mut self, token_id: &str, winning_side: &str) -> Self {
+        self.resolutions
+            .insert(token_id.to_string(), winning_side.to_string());
+        self
+    }
+
+    /// Register a full resolution for a token_id.
+    pub fn with_resolution_detail(mut self, token_id: &str, detail: ResolutionDetail) -> Self {
+        self.details.insert(token_id.to_string(), detail);
+        self
+    }
+
+    /// Set the markets returned by fetch_supported_markets.
+    pub fn with_markets(mut self, markets: Vec<ParsedMarket>) -> Self {
+        self.markets = markets;
+        self
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+#[async_trait]
+impl GammaApi for MockGammaApi {
+    async fn fetch_market_resolution(&self, token_id: &str) -> Result<Option<String>, GammaError> {
+        Ok(self
+            .fetch_market_resolution_detail(token_id)
+            .await?
+            .and_then(|detail| detail.winning_side))
+    }
+
+    async fn fetch_market_resolution_detail(
+        &self,
+        token_id: &str,
+    ) -> Result<Option<ResolutionDetail>, GammaError> {
+        if let Some(detail) = self.details.get(token_id) {
+            return Ok(Some(detail.clone()));
         }
+        Ok(self.resolutions.get(token_id).map(|side| ResolutionDetail {
+            winning_side: Some(side.clone()),
+            resolved_at: None,
+            ambiguous: false,
+        }))
+    }
 
-        debug!("Could not determine resolution for market {}", token_id);
-        Ok(None)
+    async fn fetch_supported_markets(&self) -> Result<Vec<ParsedMarket>, GammaError> {
+        Ok(self.markets.clone())
     }
 }
 
@@ -923,10 +1242,12 @@ mod tests {
             clob_token_ids: Some(r#"["token1", "token2"]"#.to_string()),
             active: Some(true),
             closed: None,
+            closed_time: None,
             slug: None,
             market_maker_address: None,
             best_bid: None,
             best_ask: None,
+            liquidity_num: None,
         };
 
         let tokens = market.parse_token_ids().unwrap();
@@ -934,4 +1255,291 @@ mod tests {
         assert_eq!(tokens[0], "token1");
         assert_eq!(tokens[1], "token2");
     }
+
+    fn gamma_market(outcomes: &str, token_ids: &str) -> GammaMarket {
+        GammaMarket {
+            condition_id: "cond".to_string(),
+            question: "Who will win?".to_string(),
+            description: None,
+            end_date: Some((Utc::now() + chrono::Duration::days(1)).to_rfc3339()),
+            outcomes: Some(outcomes.to_string()),
+            outcome_prices: None,
+            clob_token_ids: Some(token_ids.to_string()),
+            active: Some(true),
+            closed: Some(false),
+            closed_time: None,
+            slug: None,
+            market_maker_address: None,
+            best_bid: None,
+            best_ask: None,
+            liquidity_num: None,
+        }
+    }
+
+    #[test]
+    fn test_outcome_shape() {
+        let binary = gamma_market(r#"["Yes", "No"]"#, r#"["t1", "t2"]"#);
+        assert_eq!(binary.outcome_shape(), OutcomeShape::Binary);
+
+        let multi = gamma_market(r#"["A", "B", "C"]"#, r#"["t1", "t2", "t3"]"#);
+        assert_eq!(multi.outcome_shape(), OutcomeShape::MultiOutcome(3));
+
+        let mismatched = gamma_market(r#"["A", "B", "C"]"#, r#"["t1", "t2"]"#);
+        assert_eq!(mismatched.outcome_shape(), OutcomeShape::Malformed);
+
+        let mut missing = binary.clone();
+        missing.clob_token_ids = None;
+        assert_eq!(missing.outcome_shape(), OutcomeShape::Malformed);
+    }
+
+    fn closed_market(outcomes: &str, prices: &str) -> GammaMarket {
+        let token_ids = if outcomes.matches(',').count() == 1 {
+            r#"["t1", "t2"]"#
+        } else {
+            r#"["t1", "t2", "t3"]"#
+        };
+        let mut market = gamma_market(outcomes, token_ids);
+        market.closed = Some(true);
+        market.outcome_prices = Some(prices.to_string());
+        market
+    }
+
+    #[test]
+    fn test_resolution_detail_shapes() {
+        let side = |m: &GammaMarket| m.resolution_detail().and_then(|d| d.winning_side);
+
+        // Outright wins, with YES found by label rather than position
+        let yes = closed_market(r#"["Yes", "No"]"#, r#"["1", "0"]"#);
+        assert_eq!(side(&yes).as_deref(), Some("YES"));
+        let no = closed_market(r#"["Yes", "No"]"#, r#"["0", "1"]"#);
+        assert_eq!(side(&no).as_deref(), Some("NO"));
+        let down_first = closed_market(r#"["Down", "Up"]"#, r#"["1", "0"]"#);
+        assert_eq!(side(&down_first).as_deref(), Some("NO"));
+
+        // 50/50: resolved, but nobody won
+        let split = closed_market(r#"["Up", "Down"]"#, r#"["0.5", "0.5"]"#);
+        let detail = split.resolution_detail().unwrap();
+        assert!(detail.ambiguous);
+        assert_eq!(detail.winning_side, None);
+        assert!(!yes.resolution_detail().unwrap().ambiguous);
+
+        // Not (yet) resolved
+        let mut open = yes.clone();
+        open.closed = Some(false);
+        assert_eq!(open.resolution_detail(), None);
+        let unsettled = closed_market(r#"["Yes", "No"]"#, r#"["0", "0"]"#);
+        assert_eq!(unsettled.resolution_detail(), None);
+        let mut no_prices = yes.clone();
+        no_prices.outcome_prices = None;
+        assert_eq!(no_prices.resolution_detail(), None);
+        let multi = closed_market(r#"["A", "B", "C"]"#, r#"["1", "0", "0"]"#);
+        assert_eq!(multi.resolution_detail(), None);
+    }
+
+    #[test]
+    fn test_resolution_detail_resolved_at() {
+        let expected = "2026-01-15T12:15:03Z".parse::<DateTime<Utc>>().unwrap();
+        let mut market = closed_market(r#"["Up", "Down"]"#, r#"["1", "0"]"#);
+        assert_eq!(market.resolution_detail().unwrap().resolved_at, None);
+
+        for closed_time in ["2026-01-15 12:15:03+00", "2026-01-15T12:15:03Z"] {
+            market.closed_time = Some(closed_time.to_string());
+            assert_eq!(
+                market.resolution_detail().unwrap().resolved_at,
+                Some(expected),
+                "{}",
+                closed_time
+            );
+        }
+
+        market.closed_time = Some("yesterday".to_string());
+        assert_eq!(market.resolution_detail().unwrap().resolved_at, None);
+    }
+
+    #[test]
+    fn test_parse_market_skips_non_binary() {
+        let client = GammaClient::with_base_url(String::new());
+
+        let mut binary = gamma_market(r#"["No", "Yes"]"#, r#"["t-no", "t-yes"]"#);
+        binary.liquidity_num = Some(1234.5);
+        let parsed = client.parse_market(binary).unwrap();
+        assert_eq!(parsed.yes_token_id, "t-yes");
+        assert_eq!(parsed.no_token_id, "t-no");
+        assert_eq!(parsed.liquidity, Some(rust_decimal_macros::dec!(1234.5)));
+
+        let multi = gamma_market(r#"["A", "B", "C"]"#, r#"["t1", "t2", "t3"]"#);
+        assert!(client.parse_market(multi).is_none());
+    }
+
+    #[test]
+    fn test_binary_market_round_trips_through_outcomes() {
+        let client = GammaClient::with_base_url(String::new());
+
+        for (outcomes, tokens) in [
+            (r#"["Up", "Down"]"#, r#"["t-up", "t-down"]"#),
+            (r#"["Down", "Up"]"#, r#"["t-down", "t-up"]"#),
+            (r#"["Lakers", "Celtics"]"#, r#"["t-lal", "t-bos"]"#),
+        ] {
+            let mut market = gamma_market(outcomes, tokens);
+            market.best_bid = Some(0.41);
+            market.best_ask = Some(0.43);
+            let parsed = client.parse_market(market).unwrap();
+
+            assert_eq!(parsed.outcomes.len(), 2);
+            let view = parsed.binary_view().unwrap();
+            assert_eq!(view.yes.token_id, parsed.yes_token_id);
+            assert_eq!(view.no.token_id, parsed.no_token_id);
+            assert_eq!(view.yes.best_bid, parsed.yes_best_bid);
+            assert_eq!(view.yes.best_ask, parsed.yes_best_ask);
+            assert_eq!(view.no.best_bid, parsed.no_best_bid);
+            assert_eq!(view.no.best_ask, parsed.no_best_ask);
+        }
+
+        let parsed = client
+            .parse_market(gamma_market(r#"["Down", "Up"]"#, r#"["t-down", "t-up"]"#))
+            .unwrap();
+        assert_eq!(parsed.yes_token_id, "t-up");
+        assert_eq!(parsed.outcomes[0].label, "Down");
+    }
+
+    #[test]
+    fn test_to_multi_outcome() {
+        let mut market = gamma_market(r#"["Red", "Green", "Blue"]"#, r#"["t1", "t2", "t3"]"#);
+        market.outcome_prices = Some(r#"["0.2", "0.5", "0.3"]"#.to_string());
+
+        let multi = market.to_multi_outcome().unwrap();
+        assert_eq!(multi.outcomes.len(), 3);
+        let green = multi.outcome("green").unwrap();
+        assert_eq!(green.token_id, "t2");
+        assert_eq!(green.last_price, Some(rust_decimal::Decimal::new(5, 1)));
+
+        let binary = gamma_market(r#"["Yes", "No"]"#, r#"["t1", "t2"]"#);
+        assert!(binary.to_multi_outcome().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mock_gamma_api() {
+        let market = ParsedMarket {
+            condition_id: "cond-1".to_string(),
+            market_type: MarketType::UpDown,
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "yes-1".to_string(),
+            no_token_id: "no-1".to_string(),
+            name: "Bitcoin Up or Down".to_string(),
+            end_time: Utc::now(),
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
+        };
+        let mock = MockGammaApi::new()
+            .with_resolution("yes-1", "YES")
+            .with_markets(vec![market]);
+        let api: &dyn GammaApi = &mock;
+
+        assert_eq!(
+            api.fetch_market_resolution("yes-1").await.unwrap(),
+            Some("YES".to_string())
+        );
+        assert_eq!(api.fetch_market_resolution("unknown").await.unwrap(), None);
+
+        let markets = api.fetch_supported_markets().await.unwrap();
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].condition_id, "cond-1");
+    }
+
+    /// Minimal HTTP server answering `/events`: the first crypto series has
+    /// 103 events split over two pages, every other query is empty.
+    async fn spawn_paged_events_server() -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>)
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        let end_date = (Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let target = request.split_whitespace().nth(1).unwrap_or("").to_string();
+                seen.lock().unwrap().push(target.clone());
+
+                let param = |name: &str| {
+                    target
+                        .split(['?', '&'])
+                        .find_map(|kv| kv.strip_prefix(&format!("{}=", name)))
+                        .map(str::to_string)
+                };
+                let offset: usize = param("offset").and_then(|o| o.parse().ok()).unwrap_or(0);
+                let total = if param("series_id").as_deref() == Some(CRYPTO_SERIES[0].0) {
+                    103
+                } else {
+                    0
+                };
+                let limit: usize = param("limit").and_then(|l| l.parse().ok()).unwrap_or(100);
+                let events: Vec<serde_json::Value> = (offset..total.min(offset + limit))
+                    .map(|i| {
+                        serde_json::json!({
+                            "id": i.to_string(),
+                            "slug": format!("event-{}", i),
+                            "title": format!("Event {}", i),
+                            "markets": [{
+                                "conditionId": format!("cond-{}", i),
+                                "question": format!("Bitcoin Up or Down {}", i),
+                                "endDate": end_date,
+                                "outcomes": "[\"Up\", \"Down\"]",
+                                "clobTokenIds": format!("[\"up-{}\", \"down-{}\"]", i, i),
+                                "active": true,
+                                "closed": false
+                            }]
+                        })
+                    })
+                    .collect();
+                let body = serde_json::to_string(&events).unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_supported_markets_follows_pages() {
+        let (base_url, requests) = spawn_paged_events_server().await;
+        // Local mock server: no need to pace requests, and no need to touch
+        // the limiter other clients of the host share
+        let client = GammaClient::with_base_url(base_url)
+            .with_rate_limiter(Arc::new(RateLimiter::new(1000.0, 100)));
+
+        let markets = client.fetch_supported_markets().await.unwrap();
+        assert_eq!(markets.len(), 103);
+        assert!(markets.iter().any(|m| m.condition_id == "cond-0"));
+        assert!(markets.iter().any(|m| m.condition_id == "cond-102"));
+
+        // The paged series took two requests, at offsets 0 and 100
+        let series_param = format!("series_id={}", CRYPTO_SERIES[0].0);
+        let series_requests: Vec<String> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.contains(&series_param))
+            .cloned()
+            .collect();
+        assert_eq!(series_requests.len(), 2);
+        assert!(series_requests[0].contains("offset=0"));
+        assert!(series_requests[1].contains("offset=100"));
+    }
 }
@@ -12,17 +12,93 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
+use crate::assets::kalshi_asset;
+use crate::kalshi_ws::{KALSHI_DEMO_WS_URL, KALSHI_WS_URL};
+use crate::platform::Platform;
+use crate::rate_limit::{self, RateLimiter};
+
 /// Kalshi API base URL
 pub const KALSHI_API_URL: &str = "https://api.elections.kalshi.com/trade-api/v2";
 
+/// Kalshi demo (sandbox) API base URL - mock funds, separate API keys
+pub const KALSHI_DEMO_API_URL: &str = "https://demo-api.kalshi.co/trade-api/v2";
+
+/// Which Kalshi deployment to talk to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KalshiEnv {
+    /// Production: real markets and funds
+    #[default]
+    Prod,
+    /// Demo sandbox for testing without live funds
+    Demo,
+}
+
+impl KalshiEnv {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KalshiEnv::Prod => "prod",
+            KalshiEnv::Demo => "demo",
+        }
+    }
+
+    /// REST API base URL for this environment.
+    pub fn api_url(&self) -> &'static str {
+        match self {
+            KalshiEnv::Prod => KALSHI_API_URL,
+            KalshiEnv::Demo => KALSHI_DEMO_API_URL,
+        }
+    }
+
+    /// WebSocket URL for this environment.
+    pub fn ws_url(&self) -> &'static str {
+        match self {
+            KalshiEnv::Prod => KALSHI_WS_URL,
+            KalshiEnv::Demo => KALSHI_DEMO_WS_URL,
+        }
+    }
+
+    /// `markets.platform` tag for markets from this environment. Demo markets
+    /// get their own tag so queries for Kalshi never pick them up.
+    pub fn platform(&self) -> &'static str {
+        match self {
+            KalshiEnv::Prod => Platform::Kalshi.as_str(),
+            KalshiEnv::Demo => "kalshi_demo",
+        }
+    }
+}
+
+impl std::str::FromStr for KalshiEnv {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "prod" | "production" => Ok(KalshiEnv::Prod),
+            "demo" | "sandbox" => Ok(KalshiEnv::Demo),
+            other => Err(format!(
+                "Unknown Kalshi environment '{}' (expected prod, demo)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for KalshiEnv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Kalshi supported crypto assets for matching with Polymarket
 pub const KALSHI_CRYPTO_ASSETS: &[&str] = &["BTC", "ETH", "SOL", "XRP", "DOGE", "ADA"];
 
+/// Close times further out than this are treated as parse errors.
+/// The longest-dated Kalshi crypto markets settle at year end.
+const MAX_CLOSE_HORIZON_DAYS: i64 = 400;
+
 #[derive(Debug, Error)]
 pub enum KalshiError {
     #[error("HTTP request failed: {0}")]
@@ -172,6 +248,36 @@ pub struct ParsedKalshiMarket {
     pub rules_primary: Option<String>,
 }
 
+impl ParsedKalshiMarket {
+    /// Whether the close time is in the future and within a plausible horizon.
+    pub fn is_expiry_sane(&self) -> bool {
+        self.is_expiry_sane_at(Utc::now())
+    }
+
+    /// `is_expiry_sane` evaluated at `now`.
+    pub fn is_expiry_sane_at(&self, now: DateTime<Utc>) -> bool {
+        self.close_time > now
+            && self.close_time - now <= chrono::Duration::days(MAX_CLOSE_HORIZON_DAYS)
+    }
+}
+
+/// Parse a Kalshi timestamp into UTC.
+///
+/// Kalshi documents its times as UTC RFC3339 (`2025-01-13T17:00:00Z`), but
+/// offsets and offset-less ISO strings also show up. Explicit offsets are
+/// converted to UTC; offset-less strings are taken as UTC rather than local
+/// time.
+pub fn parse_kalshi_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveDateTime::parse_from_str(s, fmt).ok())
+        .map(|naive| naive.and_utc())
+}
+
 /// Market type classification for Kalshi markets.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KalshiMarketType {
@@ -217,34 +323,15 @@ struct OrderbookResponse {
     orderbook: KalshiOrderbook,
 }
 
-/// Simple rate limiter for Kalshi API (10 req/sec).
-struct RateLimiter {
-    last_request: Instant,
-    min_interval: Duration,
-}
-
-impl RateLimiter {
-    fn new(requests_per_second: u32) -> Self {
-        Self {
-            last_request: Instant::now() - Duration::from_secs(1),
-            min_interval: Duration::from_millis(1000 / requests_per_second as u64),
-        }
-    }
-
-    async fn wait(&mut self) {
-        let elapsed = self.last_request.elapsed();
-        if elapsed < self.min_interval {
-            tokio::time::sleep(self.min_interval - elapsed).await;
-        }
-        self.last_request = Instant::now();
-    }
-}
+/// Default request rate for the Kalshi API host (the limit is 10 req/sec,
+/// we use 8 to be safe).
+const KALSHI_REQUESTS_PER_SEC: f64 = 8.0;
 
 /// Kalshi API client.
 pub struct KalshiClient {
     client: Client,
     base_url: String,
-    rate_limiter: Arc<Mutex<RateLimiter>>,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl KalshiClient {
@@ -253,6 +340,11 @@ impl KalshiClient {
         Self::with_url(KALSHI_API_URL)
     }
 
+    /// Create a new Kalshi API client for `env` (production or demo).
+    pub fn new_with_env(env: KalshiEnv) -> Self {
+        Self::with_url(env.api_url())
+    }
+
     /// Create a new Kalshi API client with custom URL.
     pub fn with_url(base_url: &str) -> Self {
         Self {
@@ -261,14 +353,18 @@ impl KalshiClient {
                 .build()
                 .expect("Failed to create HTTP client"),
             base_url: base_url.to_string(),
-            // Kalshi rate limit is 10 req/sec, we use 8 to be safe
-            rate_limiter: Arc::new(Mutex::new(RateLimiter::new(8))),
+            rate_limiter: rate_limit::for_url(base_url, KALSHI_REQUESTS_PER_SEC),
         }
     }
 
+    /// REST API base URL this client sends requests to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     /// Wait for rate limiter before making a request.
     async fn rate_limit(&self) {
-        self.rate_limiter.lock().await.wait().await;
+        self.rate_limiter.acquire().await;
     }
 
     /// Fetch markets from Kalshi API with optional filters.
@@ -513,18 +609,20 @@ impl KalshiClient {
         }
 
         // Parse close time
-        let close_time = market
+        let raw_close = market
             .close_time
             .as_ref()
-            .or(market.expiration_time.as_ref())
-            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&Utc))?;
-
-        // Skip expired markets
-        if close_time < Utc::now() {
-            debug!("Skipping expired market: {}", market.ticker);
-            return None;
-        }
+            .or(market.expiration_time.as_ref())?;
+        let close_time = match parse_kalshi_timestamp(raw_close) {
+            Some(t) => t,
+            None => {
+                warn!(
+                    "Skipping market {} with unparseable close time '{}'",
+                    market.ticker, raw_close
+                );
+                return None;
+            }
+        };
 
         // Extract asset from ticker or title
         let asset = extract_asset_from_kalshi(&market.ticker, &market.title);
@@ -563,7 +661,7 @@ impl KalshiClient {
             .or(market.floor_strike)
             .or(market.cap_strike);
 
-        Some(ParsedKalshiMarket {
+        let parsed = ParsedKalshiMarket {
             ticker: market.ticker.clone(),
             event_ticker: market.event_ticker.clone(),
             name: market.title.clone(),
@@ -580,7 +678,21 @@ impl KalshiClient {
             strike_price,
             direction,
             rules_primary: market.rules_primary.clone(),
-        })
+        };
+
+        if !parsed.is_expiry_sane() {
+            if parsed.close_time <= Utc::now() {
+                debug!("Skipping expired market: {}", market.ticker);
+            } else {
+                warn!(
+                    "Skipping market {} with implausible close time {} (raw '{}')",
+                    market.ticker, parsed.close_time, raw_close
+                );
+            }
+            return None;
+        }
+
+        Some(parsed)
     }
 }
 
@@ -592,10 +704,14 @@ impl Default for KalshiClient {
 
 /// Extract cryptocurrency asset from Kalshi ticker and title.
 fn extract_asset_from_kalshi(ticker: &str, title: &str) -> String {
+    if let Some(asset) = kalshi_asset(ticker) {
+        return asset.to_string();
+    }
+
     let ticker_upper = ticker.to_uppercase();
     let title_upper = title.to_uppercase();
 
-    // Check ticker prefixes (KXBTC, INXBTC, etc.)
+    // Other ticker prefixes (INXBTC, etc.) and unregistered assets
     if ticker_upper.contains("BTC") || title_upper.contains("BITCOIN") {
         return "BTC".to_string();
     }
@@ -692,6 +808,37 @@ fn classify_kalshi_market(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_client_base_url_per_env() {
+        assert_eq!(KalshiClient::new().base_url(), KALSHI_API_URL);
+        assert_eq!(
+            KalshiClient::new_with_env(KalshiEnv::Prod).base_url(),
+            KALSHI_API_URL
+        );
+        assert_eq!(
+            KalshiClient::new_with_env(KalshiEnv::Demo).base_url(),
+            KALSHI_DEMO_API_URL
+        );
+        assert_eq!(KalshiEnv::Demo.ws_url(), KALSHI_DEMO_WS_URL);
+    }
+
+    #[test]
+    fn test_demo_markets_stored_apart_from_prod() {
+        assert_eq!(KalshiEnv::Prod.platform(), "kalshi");
+        assert_ne!(KalshiEnv::Demo.platform(), KalshiEnv::Prod.platform());
+        assert!("kalshi_demo".parse::<Platform>().is_err());
+    }
+
+    #[test]
+    fn test_kalshi_env_from_str() {
+        assert_eq!("prod".parse::<KalshiEnv>(), Ok(KalshiEnv::Prod));
+        assert_eq!("Demo".parse::<KalshiEnv>(), Ok(KalshiEnv::Demo));
+        assert_eq!("sandbox".parse::<KalshiEnv>(), Ok(KalshiEnv::Demo));
+        assert!("staging".parse::<KalshiEnv>().is_err());
+        assert_eq!(KalshiEnv::default(), KalshiEnv::Prod);
+    }
 
     #[test]
     fn test_extract_asset_from_kalshi() {
@@ -744,6 +891,77 @@ mod tests {
         assert_eq!(d, None);
     }
 
+    #[test]
+    fn test_parse_kalshi_timestamp() {
+        let expected = Utc.with_ymd_and_hms(2025, 1, 13, 17, 0, 0).unwrap();
+
+        // Documented format
+        assert_eq!(
+            parse_kalshi_timestamp("2025-01-13T17:00:00Z"),
+            Some(expected)
+        );
+        // Explicit offsets are normalized to UTC
+        assert_eq!(
+            parse_kalshi_timestamp("2025-01-13T12:00:00-05:00"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_kalshi_timestamp("2025-01-14T02:00:00+09:00"),
+            Some(expected)
+        );
+        // Fractional seconds and surrounding whitespace
+        assert_eq!(
+            parse_kalshi_timestamp(" 2025-01-13T17:00:00.250Z "),
+            Some(expected + chrono::Duration::milliseconds(250))
+        );
+        // Offset-less strings are UTC, not local time
+        assert_eq!(
+            parse_kalshi_timestamp("2025-01-13T17:00:00"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_kalshi_timestamp("2025-01-13 17:00:00"),
+            Some(expected)
+        );
+
+        assert_eq!(parse_kalshi_timestamp(""), None);
+        assert_eq!(parse_kalshi_timestamp("2025-01-13"), None);
+        assert_eq!(parse_kalshi_timestamp("1736787600"), None);
+        assert_eq!(parse_kalshi_timestamp("not a time"), None);
+    }
+
+    #[test]
+    fn test_is_expiry_sane() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 13, 17, 0, 0).unwrap();
+        let market_closing = |close_time| ParsedKalshiMarket {
+            ticker: "KXBTC-25JAN13".to_string(),
+            event_ticker: "KXBTC".to_string(),
+            name: "Bitcoin above $100,000?".to_string(),
+            asset: "BTC".to_string(),
+            timeframe: "daily".to_string(),
+            close_time,
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            last_price: None,
+            liquidity: None,
+            market_type: KalshiMarketType::AboveBelow,
+            strike_price: None,
+            direction: None,
+            rules_primary: None,
+        };
+
+        assert!(market_closing(now + chrono::Duration::minutes(15)).is_expiry_sane_at(now));
+        assert!(market_closing(now + chrono::Duration::days(350)).is_expiry_sane_at(now));
+
+        // Already closed
+        assert!(!market_closing(now).is_expiry_sane_at(now));
+        assert!(!market_closing(now - chrono::Duration::hours(5)).is_expiry_sane_at(now));
+        // Absurdly far out (e.g. a misparsed year)
+        assert!(!market_closing(now + chrono::Duration::days(401)).is_expiry_sane_at(now));
+    }
+
     #[test]
     fn test_kalshi_market_price_conversion() {
         let market = KalshiMarket {
@@ -23,9 +23,14 @@ use tokio::time::timeout;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 
+use crate::kalshi::KalshiEnv;
+
 /// Default Kalshi WebSocket URL
 pub const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
 
+/// Kalshi demo (sandbox) WebSocket URL
+pub const KALSHI_DEMO_WS_URL: &str = "wss://demo-api.kalshi.co/trade-api/ws/v2";
+
 /// Kalshi WebSocket message types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -236,13 +241,13 @@ impl KalshiAuth {
         })
     }
 
-    /// Generate authentication headers for WebSocket connection
-    pub fn generate_headers(&self) -> Result<Vec<(String, String)>> {
+    /// Generate authentication headers for a WebSocket connection to `path`
+    /// (e.g. "/trade-api/ws/v2").
+    pub fn generate_headers(&self, path: &str) -> Result<Vec<(String, String)>> {
         let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
 
         // Message to sign: timestamp + method + path
         let method = "GET";
-        let path = "/trade-api/ws/v2";
         let message = format!("{}{}{}", timestamp, method, path);
 
         // Sign with RSA-PSS SHA256
@@ -285,6 +290,12 @@ impl KalshiWsClient {
         })
     }
 
+    /// Point the client at `env`'s WebSocket endpoint.
+    pub fn with_env(mut self, env: KalshiEnv) -> Self {
+        self.ws_url = env.ws_url().to_string();
+        self
+    }
+
     /// Connect and stream orderbook updates
     pub async fn stream_orderbooks(
         &mut self,
@@ -293,12 +304,14 @@ impl KalshiWsClient {
     ) -> Result<()> {
         info!("Connecting to Kalshi WebSocket at {}", self.ws_url);
 
-        // Build connection request with auth headers if available
+        // Build connection request with auth headers if available; the host
+        // and signed path follow the URL so demo and production both work
+        let (host, path) = ws_host_and_path(&self.ws_url)?;
         let request = if let Some(auth) = &self.auth {
-            let headers = auth.generate_headers()?;
+            let headers = auth.generate_headers(&path)?;
             let mut req = http::Request::builder()
                 .uri(&self.ws_url)
-                .header("Host", "api.elections.kalshi.com");
+                .header("Host", host.as_str());
 
             for (key, value) in headers {
                 req = req.header(key.as_str(), value.as_str());
@@ -308,7 +321,7 @@ impl KalshiWsClient {
         } else {
             http::Request::builder()
                 .uri(&self.ws_url)
-                .header("Host", "api.elections.kalshi.com")
+                .header("Host", host.as_str())
                 .body(())?
         };
 
@@ -466,6 +479,16 @@ impl Default for KalshiWsClient {
     }
 }
 
+/// Host and path of a WebSocket URL, for the Host header and request signing.
+fn ws_host_and_path(ws_url: &str) -> Result<(String, String)> {
+    let url =
+        url::Url::parse(ws_url).map_err(|e| anyhow!("Invalid WebSocket URL {}: {}", ws_url, e))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("WebSocket URL has no host: {}", ws_url))?;
+    Ok((host.to_string(), url.path().to_string()))
+}
+
 /// Run Kalshi orderbook streaming with reconnection logic
 pub async fn run_kalshi_orderbook_stream(
     tickers: Vec<String>,
@@ -473,12 +496,14 @@ pub async fn run_kalshi_orderbook_stream(
     api_key: Option<String>,
     private_key_pem: Option<String>,
     reconnect_interval: Duration,
+    env: KalshiEnv,
 ) -> Result<()> {
     loop {
-        let mut client = match (&api_key, &private_key_pem) {
+        let client = match (&api_key, &private_key_pem) {
             (Some(key), Some(pem)) => KalshiWsClient::with_auth(key.clone(), pem)?,
             _ => KalshiWsClient::new(),
         };
+        let mut client = client.with_env(env);
 
         match client.stream_orderbooks(tickers.clone(), tx.clone()).await {
             Ok(_) => {
@@ -498,6 +523,25 @@ pub async fn run_kalshi_orderbook_stream(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ws_host_and_path_per_env() {
+        assert_eq!(
+            ws_host_and_path(KalshiEnv::Prod.ws_url()).unwrap(),
+            (
+                "api.elections.kalshi.com".to_string(),
+                "/trade-api/ws/v2".to_string()
+            )
+        );
+        assert_eq!(
+            ws_host_and_path(KalshiEnv::Demo.ws_url()).unwrap(),
+            (
+                "demo-api.kalshi.co".to_string(),
+                "/trade-api/ws/v2".to_string()
+            )
+        );
+        assert!(ws_host_and_path("not a url").is_err());
+    }
+
     #[test]
     fn test_cents_to_decimal() {
         assert_eq!(cents_to_decimal(50), dec!(0.50));
@@ -5,13 +5,22 @@
 //! - Database connection pooling
 //! - Gamma API client (Polymarket)
 //! - Kalshi API client
-//! - Limitless API client
+//! - Limitless API client and order placement
 //! - Platform abstraction for cross-platform arbitrage
 //! - Shared data models
 //! - Binance WebSocket client
+//! - Price source abstraction (Binance or Chainlink)
 //! - Trading executor utilities
+//! - Trading-loop cadence scaled by time-to-expiry
+//! - Logging setup (human or JSON)
+//! - Watchdog (dead-man's switch) for trading loops
+//! - Settlement tracking for live positions held to expiry
+//! - Asset registry and `--assets` parsing
+//! - Scripted mock WebSocket server for client tests
 
+pub mod assets;
 pub mod binance_ws;
+pub mod cadence;
 pub mod clob;
 pub mod config;
 pub mod db;
@@ -20,45 +29,95 @@ pub mod gamma;
 pub mod kalshi;
 pub mod kalshi_ws;
 pub mod limitless;
+pub mod limitless_order;
 pub mod limitless_ws;
+pub mod live_settlement;
+pub mod logging;
+pub mod market_repository;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock_ws;
 pub mod models;
 pub mod platform;
 pub mod polymarket_rtds;
+pub mod price_source;
+pub mod rate_limit;
 pub mod repository;
+pub mod validation;
+pub mod watchdog;
 
 pub use binance_ws::{
-    BinanceBookTicker, BinanceEvent, BinanceKline, BinanceStreamType, BinanceWsClient,
-    BinanceWsStream, KlineBuffer, MomentumDirection,
+    BinanceBookTicker, BinanceEvent, BinanceKline, BinanceStreamType, BinanceTrade,
+    BinanceWsClient, BinanceWsStream, KlineBuffer, MomentumDirection, MomentumWindow, TradeVolume,
 };
 pub use clob::{BookMessage, ClobClient, ClobMessage, PriceChange, PriceChangeMessage, PriceLevel};
 pub use config::Config;
 pub use db::Database;
 pub use executor::{
-    cancel_order, cancel_order_standalone, ensure_authenticated, execute_sell_order, execute_trade,
-    query_order_fill_standalone, CachedAuth, DryRunPortfolio, SimulatedPosition, MAX_SHARES,
+    ambiguous_settlement_pnl, cancel_order, cancel_order_standalone, check_order_size,
+    check_signals_concurrently, check_usdc_allowance, ensure_authenticated, execute_sell_order,
+    execute_sell_order_with_kind, execute_trade, execute_trade_with_kind, fetch_clob_book,
+    fetch_live_best_ask, fetch_tick_size, fmt_price, fmt_shares, list_open_orders, normalize_order,
+    normalize_price_to_tick, price_divergence_exceeded, query_order_fill_standalone,
+    reconcile_open_orders, resolve_signature_config, signature_config_from, timed_out_settlement,
+    usdc_allowance, AllowanceStatus, CachedAuth, ClobBook, ClobLevel, DailyPnlStop,
+    DailyStopReason, DryRunPortfolio, EntryOffset, NetPosition, OpenOrder, OrderApi, OrderKind,
+    OrderRejection, PaperFillMode, PaperFills, PaperOrders, SettlementTimeout,
+    SettlementTimeoutPolicy, SideFilter, SimulatedPosition, TimeframeStats, TradeThrottle,
+    UnresolvedPolicy, AMBIGUOUS_PAYOUT, AMBIGUOUS_WINNING_SIDE, DEFAULT_MAX_RESOLUTION_RETRIES,
+    DEFAULT_MAX_SHARES, DEFAULT_TICK_SIZE, TIMED_OUT_WINNING_SIDE,
+};
+pub use gamma::{
+    GammaApi, GammaClient, GammaMarket, MarketType, OutcomeShape, ParsedMarket, ResolutionDetail,
+};
+pub use live_settlement::{
+    log_unresolved_live_positions, resolve_live_settlements, LivePosition, LivePositions,
+    LiveSettlement,
+};
+pub use market_repository::{
+    fresh_retry_ask, record_trade_audit, refresh_market, InMemoryMarketRepository, MarketRepository,
 };
-pub use gamma::{GammaClient, GammaMarket, MarketType, ParsedMarket};
 pub use repository::{
+    aggregate_depth_to_ticks,
+    audit_trade,
     calculate_effective_fill_price,
+    calculate_fill_price_from_levels,
     calculate_fill_price_with_slippage,
     deactivate_expired_markets,
+    depth_levels,
+    effective_fill_from_levels,
+    expire_stale_cross_platform_matches,
     get_15m_updown_markets_with_fresh_orderbooks,
     get_15m_updown_markets_with_orderbooks,
+    get_active_crypto_assets,
     get_active_markets,
     get_active_markets_expiring_within,
     get_cross_platform_matches,
+    get_fills_for_market,
     get_latest_orderbook_snapshot,
     get_limitless_markets_with_prices,
     get_market_by_condition_id,
     get_market_resolution,
     get_market_resolutions_batch,
+    get_market_resolutions_by_condition,
+    get_markets_by_condition_ids,
     get_markets_by_platform,
     get_markets_with_fresh_orderbooks,
+    get_markets_without_fresh_orderbooks,
     get_platform_markets_with_prices,
     get_priority_markets_hybrid,
     get_recent_opportunities,
+    get_recent_opportunities_with_depth,
+    get_settlements_summary,
+    get_trade_audit_for_market,
+    get_unsettled_traded_markets,
+    get_winrate_breakdown,
+    insert_fill,
     insert_orderbook_snapshot,
+    insert_settlement,
+    prioritize_markets,
+    prune_orderbook_snapshots,
     record_cross_platform_opportunity,
+    retain_valid_depth,
     update_kalshi_prices,
     update_limitless_prices,
     update_no_best_prices,
@@ -73,30 +132,45 @@ pub use repository::{
     upsert_limitless_market,
     upsert_market,
     upsert_market_resolution,
+    validate_depth_json,
     CrossPlatformMatchInsert,
+    DepthError,
+    Fill,
     FillEstimate,
+    FillInsert,
+    FillPriceModel,
     KalshiMarketInsert,
     LimitlessMarketInsert,
     MarketResolution,
     MarketResolutionInsert,
+    MarketRotation,
     MarketWithOrderbook,
     MarketWithPlatform,
     MarketWithPrices,
+    OpportunityWithDepth,
     OrderbookLevel,
+    SettlementInsert,
+    SettlementSummary,
+    TradeAudit,
+    WinrateRow,
 };
 
 // Kalshi API client
 pub use kalshi::{
-    KalshiClient, KalshiError, KalshiMarket, KalshiMarketType, KalshiOrderbook, ParsedKalshiMarket,
-    KALSHI_API_URL, KALSHI_CRYPTO_ASSETS,
+    KalshiClient, KalshiEnv, KalshiError, KalshiMarket, KalshiMarketType, KalshiOrderbook,
+    ParsedKalshiMarket, KALSHI_API_URL, KALSHI_CRYPTO_ASSETS, KALSHI_DEMO_API_URL,
 };
 
 // Platform abstraction for cross-platform arbitrage
-pub use platform::{CrossPlatformOpportunity, MarketPair, OrderbookDepth, Platform, UnifiedMarket};
+pub use platform::{
+    synthesize_depth_from_best, CrossPlatformOpportunity, MarketPair, OrderbookDepth, Platform,
+    UnifiedMarket,
+};
 
 // Kalshi WebSocket streaming
 pub use kalshi_ws::{
-    run_kalshi_orderbook_stream, KalshiOrderbookUpdate, KalshiWsClient, KALSHI_WS_URL,
+    run_kalshi_orderbook_stream, KalshiOrderbookUpdate, KalshiWsClient, KALSHI_DEMO_WS_URL,
+    KALSHI_WS_URL,
 };
 
 // Limitless API client
@@ -105,13 +179,34 @@ pub use limitless::{
     ParsedLimitlessMarket, LIMITLESS_API_URL, LIMITLESS_CRYPTO_ASSETS, LIMITLESS_WS_URL,
 };
 
+// Limitless order placement
+pub use limitless_order::{LimitlessOrderSide, LimitlessTrader};
+
 // Limitless WebSocket streaming
 pub use limitless_ws::{
     run_limitless_orderbook_stream, LimitlessOrderbookUpdate, LimitlessWsClient,
 };
 
+// Test doubles, for this crate's tests and dependents enabling `test-util`
+#[cfg(any(test, feature = "test-util"))]
+pub use executor::MockOrderApi;
+#[cfg(any(test, feature = "test-util"))]
+pub use gamma::MockGammaApi;
+#[cfg(any(test, feature = "test-util"))]
+pub use mock_ws::{MockStep, MockWsServer};
+
 // Polymarket RTDS (Chainlink prices)
 pub use polymarket_rtds::{
     asset_to_chainlink_symbol, chainlink_symbol_to_asset, ChainlinkPrice, ChainlinkPriceBuffer,
-    PolymarketRtdsClient, RtdsStream, TimestampedPrice, POLYMARKET_RTDS_URL,
+    MessageRateMonitor, PolymarketRtdsClient, RetryConfig, RtdsStream, StreamHealth,
+    TimestampedPrice, POLYMARKET_RTDS_URL,
 };
+
+// Trading-loop cadence
+pub use cadence::cycle_interval;
+
+// Price source abstraction
+pub use price_source::{BinancePriceSource, ChainlinkPriceSource, PriceSource};
+
+// Dead-man's switch for trading loops
+pub use watchdog::{cancel_on_stall, spawn_watchdog, Heartbeat, RestingOrders};
@@ -0,0 +1,372 @@
+//! Limitless Exchange order placement.
+//!
+//! Limitless is a Polymarket CTF Exchange fork on Base, so orders are the
+//! same EIP-712 `Order` struct signed against the market's exchange contract
+//! and posted to the REST API.
+//!
+//! Environment:
+//! - `WALLET_PRIVATE_KEY`: signing key (shared with the Polymarket executor)
+//! - `LIMITLESS_API_KEY`: API key sent as `X-API-Key`
+//! - `LIMITLESS_OWNER_ID`: numeric profile ID that owns the orders
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tracing::info;
+
+use crate::limitless::{LimitlessClient, LimitlessError, LIMITLESS_API_URL};
+
+/// Base mainnet chain ID.
+pub const BASE_CHAIN_ID: u64 = 8453;
+
+/// Collateral (USDC) and outcome tokens both use 6 decimals.
+const TOKEN_DECIMALS: u32 = 6;
+
+const ORDER_TYPEHASH_INPUT: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+const DOMAIN_TYPEHASH_INPUT: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const DOMAIN_NAME: &str = "Limitless CTF Exchange";
+const DOMAIN_VERSION: &str = "1";
+
+/// Order side as encoded in the signed struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitlessOrderSide {
+    Buy = 0,
+    Sell = 1,
+}
+
+/// Maker/taker amounts in 6-decimal base units for `shares` at `price`.
+///
+/// A buy gives USDC for shares; a sell gives shares for USDC. Returns None
+/// for non-positive sizes or prices outside (0, 1).
+pub fn order_amounts(
+    side: LimitlessOrderSide,
+    shares: Decimal,
+    price: Decimal,
+) -> Option<(u128, u128)> {
+    if shares <= Decimal::ZERO || price <= Decimal::ZERO || price >= Decimal::ONE {
+        return None;
+    }
+    let scale = Decimal::from(10u64.pow(TOKEN_DECIMALS));
+    let share_units = (shares * scale).floor().to_u128()?;
+    let usdc_units = (shares * price * scale).floor().to_u128()?;
+    if share_units == 0 || usdc_units == 0 {
+        return None;
+    }
+    Some(match side {
+        LimitlessOrderSide::Buy => (usdc_units, share_units),
+        LimitlessOrderSide::Sell => (share_units, usdc_units),
+    })
+}
+
+/// An unsigned CTF Exchange order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitlessOrder {
+    pub salt: u64,
+    pub maker: Address,
+    pub signer: Address,
+    pub taker: Address,
+    pub token_id: U256,
+    pub maker_amount: u128,
+    pub taker_amount: u128,
+    pub expiration: u64,
+    pub nonce: u64,
+    pub fee_rate_bps: u64,
+    pub side: LimitlessOrderSide,
+    /// 0 = EOA
+    pub signature_type: u8,
+}
+
+impl LimitlessOrder {
+    /// EIP-712 struct hash of the order.
+    pub fn struct_hash(&self) -> B256 {
+        let words = [
+            keccak256(ORDER_TYPEHASH_INPUT.as_bytes()),
+            U256::from(self.salt).into(),
+            self.maker.into_word(),
+            self.signer.into_word(),
+            self.taker.into_word(),
+            self.token_id.into(),
+            U256::from(self.maker_amount).into(),
+            U256::from(self.taker_amount).into(),
+            U256::from(self.expiration).into(),
+            U256::from(self.nonce).into(),
+            U256::from(self.fee_rate_bps).into(),
+            U256::from(self.side as u8).into(),
+            U256::from(self.signature_type).into(),
+        ];
+        hash_words(&words)
+    }
+
+    /// Digest to sign for an order on the exchange at `exchange`.
+    pub fn signing_hash(&self, chain_id: u64, exchange: Address) -> B256 {
+        let mut payload = Vec::with_capacity(66);
+        payload.extend_from_slice(&[0x19, 0x01]);
+        payload.extend_from_slice(domain_separator(chain_id, exchange).as_slice());
+        payload.extend_from_slice(self.struct_hash().as_slice());
+        keccak256(payload)
+    }
+}
+
+/// EIP-712 domain separator for the Limitless exchange contract.
+pub fn domain_separator(chain_id: u64, exchange: Address) -> B256 {
+    let words = [
+        keccak256(DOMAIN_TYPEHASH_INPUT.as_bytes()),
+        keccak256(DOMAIN_NAME.as_bytes()),
+        keccak256(DOMAIN_VERSION.as_bytes()),
+        U256::from(chain_id).into(),
+        exchange.into_word(),
+    ];
+    hash_words(&words)
+}
+
+/// keccak256 of ABI-encoded static words.
+fn hash_words(words: &[B256]) -> B256 {
+    let mut encoded = Vec::with_capacity(32 * words.len());
+    for word in words {
+        encoded.extend_from_slice(word.as_slice());
+    }
+    keccak256(encoded)
+}
+
+/// Signs and submits orders to Limitless.
+pub struct LimitlessTrader {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    owner_id: u64,
+    signer: PrivateKeySigner,
+    markets: LimitlessClient,
+}
+
+impl LimitlessTrader {
+    /// Build a trader from `WALLET_PRIVATE_KEY`, `LIMITLESS_API_KEY` and
+    /// `LIMITLESS_OWNER_ID`.
+    pub fn from_env() -> Result<Self, LimitlessError> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| LimitlessError::ApiError(format!("Missing {}", name)))
+        };
+        let private_key = var("WALLET_PRIVATE_KEY")?;
+        let private_key = if private_key.starts_with("0x") {
+            private_key
+        } else {
+            format!("0x{}", private_key)
+        };
+        let signer = PrivateKeySigner::from_str(&private_key)
+            .map_err(|e| LimitlessError::ApiError(format!("Invalid private key: {}", e)))?
+            .with_chain_id(Some(BASE_CHAIN_ID));
+        let owner_id = var("LIMITLESS_OWNER_ID")?
+            .parse()
+            .map_err(|e| LimitlessError::ApiError(format!("Invalid LIMITLESS_OWNER_ID: {}", e)))?;
+
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .expect("Failed to create HTTP client"),
+            base_url: LIMITLESS_API_URL.to_string(),
+            api_key: var("LIMITLESS_API_KEY")?,
+            owner_id,
+            signer,
+            markets: LimitlessClient::new(),
+        })
+    }
+
+    /// Place a fill-or-kill order for `shares` of `token_id` on market `slug`.
+    /// Returns the Limitless order ID.
+    ///
+    /// Fill-or-kill means an accepted order is fully filled, so callers never
+    /// have to track a resting remainder.
+    pub async fn place_order(
+        &self,
+        slug: &str,
+        token_id: &str,
+        side: LimitlessOrderSide,
+        shares: Decimal,
+        price: Decimal,
+    ) -> Result<String, LimitlessError> {
+        let (maker_amount, taker_amount) = order_amounts(side, shares, price).ok_or_else(|| {
+            LimitlessError::ApiError(format!("Invalid order size {} @ {}", shares, price))
+        })?;
+        let token_id = U256::from_str(token_id)
+            .map_err(|e| LimitlessError::ParseError(format!("token id {}: {}", token_id, e)))?;
+        let exchange = self.exchange_address(slug).await?;
+
+        let order = LimitlessOrder {
+            salt: chrono::Utc::now().timestamp_millis() as u64,
+            maker: self.signer.address(),
+            signer: self.signer.address(),
+            taker: Address::ZERO,
+            token_id,
+            maker_amount,
+            taker_amount,
+            expiration: 0,
+            nonce: 0,
+            fee_rate_bps: 0,
+            side,
+            signature_type: 0,
+        };
+        let signature = self
+            .signer
+            .sign_hash(&order.signing_hash(BASE_CHAIN_ID, exchange))
+            .await
+            .map_err(|e| LimitlessError::ApiError(format!("Failed to sign order: {}", e)))?;
+
+        let body = json!({
+            "order": {
+                "salt": order.salt,
+                "maker": order.maker.to_string(),
+                "signer": order.signer.to_string(),
+                "taker": order.taker.to_string(),
+                "tokenId": order.token_id.to_string(),
+                "makerAmount": order.maker_amount.to_string(),
+                "takerAmount": order.taker_amount.to_string(),
+                "expiration": order.expiration.to_string(),
+                "nonce": order.nonce,
+                "feeRateBps": order.fee_rate_bps,
+                "side": order.side as u8,
+                "signatureType": order.signature_type,
+                "price": price.to_f64(),
+                "signature": format!("0x{}", hex_encode(&signature.as_bytes())),
+            },
+            "ownerId": self.owner_id,
+            "orderType": "FOK",
+            "marketSlug": slug,
+        });
+
+        info!(
+            market = slug,
+            side = ?side,
+            shares = %shares,
+            price = %price,
+            "[LIMITLESS] Posting order"
+        );
+        let response = self
+            .http
+            .post(format!("{}/orders", self.base_url))
+            .header("X-API-Key", &self.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.as_u16() == 429 {
+            return Err(LimitlessError::RateLimitExceeded);
+        }
+        let payload: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| LimitlessError::ParseError(e.to_string()))?;
+        if !status.is_success() {
+            return Err(LimitlessError::ApiError(format!(
+                "Order rejected ({}): {}",
+                status, payload
+            )));
+        }
+
+        payload
+            .pointer("/order/id")
+            .or_else(|| payload.get("id"))
+            .map(|id| {
+                id.as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| id.to_string())
+            })
+            .ok_or_else(|| LimitlessError::ParseError(format!("No order id in {}", payload)))
+    }
+
+    /// Exchange contract the market's orders must be signed against.
+    async fn exchange_address(&self, slug: &str) -> Result<Address, LimitlessError> {
+        let market = self
+            .markets
+            .fetch_market(slug)
+            .await?
+            .ok_or_else(|| LimitlessError::MarketNotFound(slug.to_string()))?;
+        let exchange = market
+            .venue
+            .and_then(|v| v.exchange)
+            .ok_or_else(|| LimitlessError::ApiError(format!("No exchange for {}", slug)))?;
+        Address::from_str(&exchange)
+            .map_err(|e| LimitlessError::ParseError(format!("exchange {}: {}", exchange, e)))
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_order_amounts() {
+        // Buying 10 shares at $0.45 pays 4.5 USDC for 10 shares
+        assert_eq!(
+            order_amounts(LimitlessOrderSide::Buy, dec!(10), dec!(0.45)),
+            Some((4_500_000, 10_000_000))
+        );
+        // Selling swaps maker and taker
+        assert_eq!(
+            order_amounts(LimitlessOrderSide::Sell, dec!(10), dec!(0.45)),
+            Some((10_000_000, 4_500_000))
+        );
+        // Sub-unit remainders are truncated
+        assert_eq!(
+            order_amounts(LimitlessOrderSide::Buy, dec!(3.3333333), dec!(0.333)),
+            Some((1_109_999, 3_333_333))
+        );
+
+        assert_eq!(
+            order_amounts(LimitlessOrderSide::Buy, dec!(0), dec!(0.5)),
+            None
+        );
+        assert_eq!(
+            order_amounts(LimitlessOrderSide::Buy, dec!(10), dec!(0)),
+            None
+        );
+        assert_eq!(
+            order_amounts(LimitlessOrderSide::Buy, dec!(10), dec!(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_signing_hash_binds_order_and_exchange() {
+        let order = LimitlessOrder {
+            salt: 1,
+            maker: Address::repeat_byte(0x11),
+            signer: Address::repeat_byte(0x11),
+            taker: Address::ZERO,
+            token_id: U256::from(42u64),
+            maker_amount: 4_500_000,
+            taker_amount: 10_000_000,
+            expiration: 0,
+            nonce: 0,
+            fee_rate_bps: 0,
+            side: LimitlessOrderSide::Buy,
+            signature_type: 0,
+        };
+        let exchange = Address::repeat_byte(0x22);
+        let hash = order.signing_hash(BASE_CHAIN_ID, exchange);
+
+        // Deterministic
+        assert_eq!(hash, order.signing_hash(BASE_CHAIN_ID, exchange));
+        // Any field, the exchange or the chain changes the digest
+        let mut sell = order.clone();
+        sell.side = LimitlessOrderSide::Sell;
+        assert_ne!(hash, sell.signing_hash(BASE_CHAIN_ID, exchange));
+        assert_ne!(
+            hash,
+            order.signing_hash(BASE_CHAIN_ID, Address::repeat_byte(0x33))
+        );
+        assert_ne!(hash, order.signing_hash(1, exchange));
+    }
+}
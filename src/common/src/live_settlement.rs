@@ -0,0 +1,395 @@
+//! Settlement tracking for live positions held to expiry.
+//!
+//! Live traders record a [`LivePosition`] for each filled (market, side).
+//! Once the market has ended, [`resolve_live_settlements`] looks up its
+//! resolution on Gamma, records the outcome in `settlements` and returns it
+//! so the caller can fold the realized P&L into its own metrics.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::executor::{
+    ambiguous_settlement_pnl, timed_out_settlement, SettlementTimeout, SettlementTimeoutPolicy,
+    AMBIGUOUS_WINNING_SIDE,
+};
+use crate::gamma::{GammaApi, ResolutionDetail};
+use crate::market_repository::MarketRepository;
+use crate::repository::SettlementInsert;
+
+/// Live positions keyed by (market_id, side), so YES and NO on the same
+/// market are tracked separately.
+pub type LivePositions = HashMap<(Uuid, String), LivePosition>;
+
+/// Tracks a live position for settlement resolution.
+///
+/// Unlike exit-manager positions, these survive past exit failures and
+/// are used to query on-chain settlement outcomes for expired markets.
+#[derive(Debug, Clone)]
+pub struct LivePosition {
+    pub yes_token_id: String,
+    pub side: String,
+    /// Shares actually filled, which may be fewer than were ordered
+    pub shares: Decimal,
+    /// Cost of the filled shares
+    pub cost: Decimal,
+    pub end_time: DateTime<Utc>,
+    pub market_name: String,
+    /// Whether this position was successfully exited before expiry
+    pub exited: bool,
+    /// Number of resolution fetch attempts
+    pub resolution_retries: u32,
+    /// Last time we attempted to fetch resolution
+    pub last_retry_time: Option<DateTime<Utc>>,
+}
+
+impl LivePosition {
+    pub fn new(
+        yes_token_id: String,
+        side: String,
+        shares: Decimal,
+        price: Decimal,
+        end_time: DateTime<Utc>,
+        market_name: String,
+    ) -> Self {
+        Self {
+            yes_token_id,
+            side,
+            shares,
+            cost: shares * price,
+            end_time,
+            market_name,
+            exited: false,
+            resolution_retries: 0,
+            last_retry_time: None,
+        }
+    }
+
+    /// Drop `shares` sold by a partial exit, along with their share of the cost.
+    pub fn reduce(&mut self, shares: Decimal) {
+        let sold = shares.min(self.shares);
+        if sold > Decimal::ZERO {
+            self.cost -= self.cost * sold / self.shares;
+            self.shares -= sold;
+        }
+    }
+
+    /// Realized P&L once the market resolves: a winning share pays $1, a
+    /// losing position forfeits its cost.
+    pub fn settlement_pnl(&self, won: bool) -> Decimal {
+        if won {
+            self.shares - self.cost
+        } else {
+            -self.cost
+        }
+    }
+}
+
+/// How an expired live position was settled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LiveSettlement {
+    /// The market resolved to a winner.
+    Resolved { pnl: Decimal, won: bool },
+    /// The market resolved 50/50; counted as neither a win nor a loss.
+    Ambiguous { pnl: Decimal },
+    /// No resolution arrived within the retry cap; P&L follows `policy`.
+    TimedOut {
+        policy: SettlementTimeoutPolicy,
+        cost: Decimal,
+    },
+}
+
+/// Calculate backoff delay in seconds based on retry count.
+/// Uses exponential backoff: 60s, 120s, 240s... capped at 600s (10 min)
+fn live_resolution_backoff_secs(retries: u32) -> i64 {
+    let base_delay = 60i64;
+    let max_delay = 600i64;
+    let delay = base_delay * (1i64 << retries.min(4));
+    delay.min(max_delay)
+}
+
+/// Record a position settled by the timeout policy, so it no longer shows up
+/// as unsettled, and return its settlement.
+async fn settle_timed_out(
+    repo: &dyn MarketRepository,
+    market_id: Uuid,
+    pos: &LivePosition,
+    policy: SettlementTimeoutPolicy,
+    now: DateTime<Utc>,
+) -> LiveSettlement {
+    let record = timed_out_settlement(
+        market_id, &pos.side, pos.shares, pos.cost, policy, false, now,
+    );
+    if let Err(e) = repo.insert_settlement(&record).await {
+        warn!("[SETTLEMENT] Failed to record settlement: {}", e);
+    }
+    LiveSettlement::TimedOut {
+        policy,
+        cost: pos.cost,
+    }
+}
+
+/// Resolve expired live positions by querying Gamma API for settlement outcomes.
+///
+/// Only processes positions that:
+/// - Were NOT already exited before expiry
+/// - Have expired (end_time + 60s buffer < now)
+/// - Are within retry limits
+///
+/// Positions still unresolved after `settlement.max_retries` attempts are
+/// settled according to `settlement.policy`. Every settled position is
+/// recorded in the `settlements` table. Returns every settlement made this pass.
+pub async fn resolve_live_settlements(
+    live_positions: &mut LivePositions,
+    repo: &dyn MarketRepository,
+    gamma: &dyn GammaApi,
+    settlement: SettlementTimeout,
+) -> Vec<LiveSettlement> {
+    let now = Utc::now();
+    let expired_cutoff = now - chrono::Duration::seconds(60);
+    let mut settled = Vec::new();
+
+    // Collect expired unexited position keys to process
+    let expired_keys: Vec<(Uuid, String)> = live_positions
+        .iter()
+        .filter(|(_, p)| !p.exited && p.end_time < expired_cutoff)
+        .map(|(k, _)| k.clone())
+        .collect();
+
+    // Clean up exited positions that have expired (no longer needed)
+    live_positions.retain(|_, p| !(p.exited && p.end_time < expired_cutoff));
+
+    for key in expired_keys {
+        let pos = match live_positions.get(&key) {
+            Some(p) => p,
+            None => continue,
+        };
+
+        // Check backoff
+        let backoff_secs = live_resolution_backoff_secs(pos.resolution_retries);
+        if let Some(last_retry) = pos.last_retry_time {
+            let elapsed = (now - last_retry).num_seconds();
+            if elapsed < backoff_secs {
+                continue;
+            }
+        }
+
+        // The Gamma client paces its own requests
+        match gamma
+            .fetch_market_resolution_detail(&pos.yes_token_id)
+            .await
+        {
+            Ok(Some(detail)) if detail.ambiguous => {
+                // Resolved 50/50: retrying will never produce a winner, and
+                // each share redeems for half a dollar
+                let pnl = ambiguous_settlement_pnl(pos.shares, pos.cost);
+                settled.push(LiveSettlement::Ambiguous { pnl });
+                info!(
+                    "[SETTLEMENT] ⚖️ SPLIT: {} {} resolved 50/50 -> ${:.2}",
+                    pos.side, pos.market_name, pnl
+                );
+
+                let record = SettlementInsert {
+                    market_id: key.0,
+                    side: pos.side.to_uppercase(),
+                    shares: pos.shares,
+                    cost: pos.cost,
+                    winning_side: AMBIGUOUS_WINNING_SIDE.to_string(),
+                    pnl,
+                    is_dry_run: false,
+                    resolved_at: detail.resolved_at.unwrap_or(now),
+                };
+                if let Err(e) = repo.insert_settlement(&record).await {
+                    warn!("[SETTLEMENT] Failed to record settlement: {}", e);
+                }
+
+                live_positions.remove(&key);
+            }
+            Ok(Some(ResolutionDetail {
+                winning_side: Some(winning_side),
+                resolved_at,
+                ..
+            })) => {
+                let winning_side = winning_side.to_uppercase();
+                if winning_side != "YES" && winning_side != "NO" {
+                    // Treat as unresolved - increment retries to avoid infinite loop
+                    let pos = live_positions.get_mut(&key).unwrap();
+                    pos.resolution_retries += 1;
+                    pos.last_retry_time = Some(now);
+                    warn!(
+                        "[SETTLEMENT] Invalid winning_side '{}' for {} (retry {}/{})",
+                        winning_side,
+                        pos.market_name,
+                        pos.resolution_retries,
+                        settlement.max_retries
+                    );
+                    if pos.resolution_retries >= settlement.max_retries {
+                        warn!(
+                            "[SETTLEMENT] ⚠️ EXPIRED: {} - max retries exceeded with invalid resolution, settling as {} (cost ${:.2})",
+                            pos.market_name, settlement.policy, pos.cost
+                        );
+                        settled
+                            .push(settle_timed_out(repo, key.0, pos, settlement.policy, now).await);
+                        live_positions.remove(&key);
+                    }
+                    continue;
+                }
+
+                let we_won = pos.side.to_uppercase() == winning_side;
+                let pnl = pos.settlement_pnl(we_won);
+                settled.push(LiveSettlement::Resolved { pnl, won: we_won });
+
+                if we_won {
+                    info!(
+                        "[SETTLEMENT] ✅ WIN: {} {} -> +${:.2} (resolved: {})",
+                        pos.side, pos.market_name, pnl, winning_side
+                    );
+                } else {
+                    info!(
+                        "[SETTLEMENT] ❌ LOSS: {} {} -> -${:.2} (resolved: {})",
+                        pos.side, pos.market_name, pos.cost, winning_side
+                    );
+                }
+
+                let record = SettlementInsert {
+                    market_id: key.0,
+                    side: pos.side.to_uppercase(),
+                    shares: pos.shares,
+                    cost: pos.cost,
+                    winning_side,
+                    pnl,
+                    is_dry_run: false,
+                    resolved_at: resolved_at.unwrap_or(now),
+                };
+                if let Err(e) = repo.insert_settlement(&record).await {
+                    warn!("[SETTLEMENT] Failed to record settlement: {}", e);
+                }
+
+                live_positions.remove(&key);
+            }
+            Ok(_) => {
+                // Not resolved yet, retry next heartbeat
+                let pos = live_positions.get_mut(&key).unwrap();
+                pos.resolution_retries += 1;
+                pos.last_retry_time = Some(now);
+
+                if pos.resolution_retries >= settlement.max_retries {
+                    warn!(
+                        "[SETTLEMENT] ⚠️ EXPIRED: {} - max retries ({}) exceeded, settling as {} (cost ${:.2})",
+                        pos.market_name, settlement.max_retries, settlement.policy, pos.cost
+                    );
+                    settled.push(settle_timed_out(repo, key.0, pos, settlement.policy, now).await);
+                    live_positions.remove(&key);
+                } else {
+                    let next_backoff = live_resolution_backoff_secs(pos.resolution_retries);
+                    debug!(
+                        "[SETTLEMENT] {} not yet resolved (retry {}/{}, next in {}s)",
+                        pos.market_name,
+                        pos.resolution_retries,
+                        settlement.max_retries,
+                        next_backoff
+                    );
+                }
+            }
+            Err(e) => {
+                // API error, retry with backoff
+                let pos = live_positions.get_mut(&key).unwrap();
+                pos.resolution_retries += 1;
+                pos.last_retry_time = Some(now);
+
+                if pos.resolution_retries >= settlement.max_retries {
+                    warn!(
+                        "[SETTLEMENT] ⚠️ EXPIRED: {} - max retries ({}) exceeded after API errors, settling as {} (cost ${:.2})",
+                        pos.market_name, settlement.max_retries, settlement.policy, pos.cost
+                    );
+                    settled.push(settle_timed_out(repo, key.0, pos, settlement.policy, now).await);
+                    live_positions.remove(&key);
+                } else {
+                    warn!(
+                        "[SETTLEMENT] Failed to fetch resolution for {} (retry {}/{}): {}",
+                        pos.market_name, pos.resolution_retries, settlement.max_retries, e
+                    );
+                }
+            }
+        }
+    }
+
+    settled
+}
+
+/// Log live positions still awaiting settlement at shutdown.
+pub fn log_unresolved_live_positions(live_positions: &LivePositions) {
+    let unresolved: Vec<_> = live_positions.values().filter(|p| !p.exited).collect();
+    if unresolved.is_empty() {
+        return;
+    }
+    info!(
+        "[SHUTDOWN] {} unresolved live positions (will settle on-chain):",
+        unresolved.len()
+    );
+    for pos in unresolved {
+        info!(
+            "  {} {} | cost: ${:.2} | expires: {}",
+            pos.side,
+            pos.market_name,
+            pos.cost,
+            pos.end_time.format("%H:%M:%S UTC")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_half_filled_position_settles_on_filled_shares() {
+        let position = |shares| {
+            LivePosition::new(
+                "yes-token".to_string(),
+                "YES".to_string(),
+                shares,
+                dec!(0.40),
+                Utc::now(),
+                "BTC Up or Down".to_string(),
+            )
+        };
+        let full = position(dec!(10));
+        let half = position(dec!(5));
+
+        assert_eq!(half.cost, full.cost / dec!(2));
+        assert_eq!(half.cost, dec!(2.00));
+        // Payout is $1 per held share: 5 back on a win, not 10
+        assert_eq!(half.settlement_pnl(true) + half.cost, dec!(5));
+        assert_eq!(
+            half.settlement_pnl(true),
+            full.settlement_pnl(true) / dec!(2)
+        );
+        assert_eq!(half.settlement_pnl(false), dec!(-2.00));
+    }
+
+    #[test]
+    fn test_scaled_out_position_settles_remainder() {
+        let mut position = LivePosition::new(
+            "yes-token".to_string(),
+            "YES".to_string(),
+            dec!(10),
+            dec!(0.40),
+            Utc::now(),
+            "BTC Up or Down".to_string(),
+        );
+        position.reduce(dec!(5));
+        assert_eq!(position.shares, dec!(5));
+        assert_eq!(position.cost, dec!(2.00));
+        assert_eq!(position.settlement_pnl(true), dec!(3.00));
+
+        // Selling more than is held empties the position
+        position.reduce(dec!(8));
+        assert_eq!(position.shares, dec!(0));
+        assert_eq!(position.cost, dec!(0));
+    }
+}
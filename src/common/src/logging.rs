@@ -0,0 +1,107 @@
+//! Shared tracing setup for all binaries.
+//!
+//! Human-readable compact output by default; newline-delimited JSON (one
+//! object per event, with structured fields preserved) for log aggregators.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable that switches to JSON output when set to `json`.
+pub const LOG_FORMAT_ENV: &str = "LOG_FORMAT";
+
+/// Whether JSON output is requested by the `--json-logs` flag or `LOG_FORMAT`.
+pub fn json_requested(flag: bool, log_format: Option<&str>) -> bool {
+    flag || log_format.is_some_and(|f| f.trim().eq_ignore_ascii_case("json"))
+}
+
+/// Install the global tracing subscriber.
+///
+/// The filter comes from `RUST_LOG`, defaulting to `info`. Pass the binary's
+/// `--json-logs` flag; `LOG_FORMAT=json` enables JSON output without it.
+pub fn init(json: bool) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let log_format = std::env::var(LOG_FORMAT_ENV).ok();
+
+    if json_requested(json, log_format.as_deref()) {
+        tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_target(false)
+            .compact()
+            .init();
+    }
+}
+
+/// Rate limiter for messages that would otherwise repeat on every cycle.
+///
+/// Counts occurrences per key (e.g. a skip reason for one market) and lets
+/// through the first one and then every `rate`-th after it.
+#[derive(Debug)]
+pub struct SampledLogger<K> {
+    rate: u64,
+    counts: HashMap<K, u64>,
+}
+
+impl<K: Hash + Eq> SampledLogger<K> {
+    /// Log 1 in `rate` occurrences of each key; 0 and 1 log every occurrence.
+    pub fn new(rate: u64) -> Self {
+        Self {
+            rate: rate.max(1),
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Record an occurrence of `key`. Returns the number of occurrences seen
+    /// so far when this one should be logged, `None` when it is sampled out.
+    pub fn sample(&mut self, key: K) -> Option<u64> {
+        let count = self.counts.entry(key).or_insert(0);
+        *count += 1;
+        (*count - 1).is_multiple_of(self.rate).then_some(*count)
+    }
+
+    /// Forget keys that no longer occur (e.g. expired markets).
+    pub fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        self.counts.retain(|key, _| keep(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_requested() {
+        assert!(!json_requested(false, None));
+        assert!(json_requested(true, None));
+        assert!(json_requested(false, Some("json")));
+        assert!(json_requested(false, Some(" JSON ")));
+        assert!(json_requested(true, Some("text")));
+        assert!(!json_requested(false, Some("text")));
+        assert!(!json_requested(false, Some("")));
+    }
+
+    #[test]
+    fn test_sampled_logger_emits_one_in_n() {
+        let mut sampler = SampledLogger::new(5);
+        let emitted: Vec<u64> = (0..20).filter_map(|_| sampler.sample("skip")).collect();
+        assert_eq!(emitted, vec![1, 6, 11, 16]);
+
+        // Keys are counted independently
+        assert_eq!(sampler.sample("other"), Some(1));
+        assert_eq!(sampler.sample("other"), None);
+
+        sampler.retain(|key| *key == "other");
+        assert_eq!(sampler.sample("skip"), Some(1));
+
+        let mut everything = SampledLogger::new(0);
+        assert!((0..3).all(|_| everything.sample("skip").is_some()));
+    }
+}
@@ -0,0 +1,592 @@
+//! Repository abstraction over the hot-path market queries.
+//!
+//! The trading loops only need a handful of reads (and a few writes) against
+//! Postgres. `MarketRepository` covers those so the loops can run against
+//! either the real database or `InMemoryMarketRepository` in tests.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::models::{TradeIntent, TradeOutcome};
+use crate::repository::{
+    self, MarketResolution, MarketResolutionInsert, MarketWithOrderbook, MarketWithPrices,
+    SettlementInsert,
+};
+
+/// Market queries used by the trading loops.
+#[async_trait]
+pub trait MarketRepository: Send + Sync {
+    /// Short-timeframe up/down markets with full orderbook depth.
+    /// See [`repository::get_15m_updown_markets_with_orderbooks`].
+    async fn get_15m_updown_markets_with_orderbooks(
+        &self,
+        max_age_seconds: i32,
+        assets: &[String],
+        max_expiry_seconds: i64,
+        timeframes: &[String],
+    ) -> Result<Vec<MarketWithOrderbook>, sqlx::Error>;
+
+    /// Markets with fresh best prices on both sides.
+    /// See [`repository::get_markets_with_fresh_orderbooks`].
+    async fn get_markets_with_fresh_orderbooks(
+        &self,
+        max_age_seconds: i32,
+        assets: &[String],
+        max_expiry_seconds: i64,
+    ) -> Result<Vec<MarketWithPrices>, sqlx::Error>;
+
+    /// Recorded resolutions for the given market IDs.
+    async fn get_market_resolutions_batch(
+        &self,
+        market_ids: &[Uuid],
+    ) -> Result<Vec<MarketResolution>, sqlx::Error>;
+
+    /// Insert or update a market resolution.
+    async fn upsert_market_resolution(
+        &self,
+        resolution: &MarketResolutionInsert,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Record a resolved position. See [`repository::insert_settlement`].
+    async fn insert_settlement(&self, settlement: &SettlementInsert) -> Result<Uuid, sqlx::Error>;
+
+    /// Record a trade intent's outcome. See [`repository::audit_trade`].
+    async fn audit_trade(
+        &self,
+        intent: &TradeIntent,
+        outcome: TradeOutcome,
+    ) -> Result<Uuid, sqlx::Error>;
+}
+
+#[async_trait]
+impl MarketRepository for PgPool {
+    async fn get_15m_updown_markets_with_orderbooks(
+        &self,
+        max_age_seconds: i32,
+        assets: &[String],
+        max_expiry_seconds: i64,
+        timeframes: &[String],
+    ) -> Result<Vec<MarketWithOrderbook>, sqlx::Error> {
+        repository::get_15m_updown_markets_with_orderbooks(
+            self,
+            max_age_seconds,
+            assets,
+            max_expiry_seconds,
+            timeframes,
+        )
+        .await
+    }
+
+    async fn get_markets_with_fresh_orderbooks(
+        &self,
+        max_age_seconds: i32,
+        assets: &[String],
+        max_expiry_seconds: i64,
+    ) -> Result<Vec<MarketWithPrices>, sqlx::Error> {
+        repository::get_markets_with_fresh_orderbooks(
+            self,
+            max_age_seconds,
+            assets,
+            max_expiry_seconds,
+        )
+        .await
+    }
+
+    async fn get_market_resolutions_batch(
+        &self,
+        market_ids: &[Uuid],
+    ) -> Result<Vec<MarketResolution>, sqlx::Error> {
+        repository::get_market_resolutions_batch(self, market_ids).await
+    }
+
+    async fn upsert_market_resolution(
+        &self,
+        resolution: &MarketResolutionInsert,
+    ) -> Result<(), sqlx::Error> {
+        repository::upsert_market_resolution(self, resolution).await
+    }
+
+    async fn insert_settlement(&self, settlement: &SettlementInsert) -> Result<Uuid, sqlx::Error> {
+        repository::insert_settlement(self, settlement).await
+    }
+
+    async fn audit_trade(
+        &self,
+        intent: &TradeIntent,
+        outcome: TradeOutcome,
+    ) -> Result<Uuid, sqlx::Error> {
+        repository::audit_trade(self, intent, outcome).await
+    }
+}
+
+/// Re-read the latest snapshot for `market` through the up/down query.
+///
+/// Narrows the query to the market's own asset and timeframe. Returns None if
+/// the market no longer has a fresh snapshot inside the expiry window.
+pub async fn refresh_market(
+    repo: &dyn MarketRepository,
+    market: &MarketWithOrderbook,
+    max_age_seconds: i32,
+    max_expiry_seconds: i64,
+) -> Result<Option<MarketWithOrderbook>, sqlx::Error> {
+    let markets = repo
+        .get_15m_updown_markets_with_orderbooks(
+            max_age_seconds,
+            std::slice::from_ref(&market.asset),
+            max_expiry_seconds,
+            std::slice::from_ref(&market.timeframe),
+        )
+        .await?;
+    Ok(markets.into_iter().find(|m| m.id == market.id))
+}
+
+/// Re-read the market's snapshot after a price-movement rejection and return
+/// the fresh ask for `side` to retry at.
+///
+/// The rejected order already sat at `rejected_price`, so only an ask above it
+/// and at or below the strategy's `max_price` is worth retrying. None if there
+/// is no fresh snapshot or the book has moved past `max_price`.
+pub async fn fresh_retry_ask(
+    repo: &dyn MarketRepository,
+    market: &MarketWithOrderbook,
+    side: &str,
+    rejected_price: Decimal,
+    max_price: Decimal,
+    max_age_seconds: i32,
+    max_expiry_seconds: i64,
+) -> Result<Option<Decimal>, sqlx::Error> {
+    let Some(fresh) = refresh_market(repo, market, max_age_seconds, max_expiry_seconds).await?
+    else {
+        info!("[RETRY] {} no fresh snapshot, not retrying", market.name);
+        return Ok(None);
+    };
+
+    match fresh.best_ask(side) {
+        Some(ask) if ask > rejected_price && ask <= max_price => {
+            info!(
+                "[RETRY] {} {} price moved, retrying at fresh ask ${:.3}",
+                market.name, side, ask
+            );
+            Ok(Some(ask))
+        }
+        ask => {
+            info!(
+                "[SKIP] {} {} price moved to {:?}, no retry between ${:.3} and max ${:.2}",
+                market.name, side, ask, rejected_price, max_price
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Record how `intent` ended in the audit trail.
+///
+/// The order has already been placed (or simulated) by the time this runs,
+/// so a failed write is logged rather than returned.
+pub async fn record_trade_audit(
+    repo: &dyn MarketRepository,
+    intent: &TradeIntent,
+    outcome: TradeOutcome,
+) {
+    if let Err(e) = repo.audit_trade(intent, outcome).await {
+        warn!(
+            "[AUDIT] Failed to record {} ({}): {}",
+            intent,
+            outcome.as_str(),
+            e
+        );
+    }
+}
+
+/// In-memory repository for hermetic tests of the trading loops.
+///
+/// Markets are stored with their latest orderbook snapshot; filtering mirrors
+/// the SQL queries (active, asset/timeframe match, expiry window, snapshot age).
+#[derive(Debug, Default)]
+pub struct InMemoryMarketRepository {
+    markets: RwLock<Vec<MarketWithOrderbook>>,
+    /// Winning side keyed by condition_id (as in `market_resolutions`)
+    resolutions: RwLock<HashMap<String, MarketResolution>>,
+    settlements: RwLock<Vec<SettlementInsert>>,
+    audits: RwLock<Vec<(TradeIntent, TradeOutcome)>>,
+}
+
+impl InMemoryMarketRepository {
+    /// Create an empty repository.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a market (with its latest orderbook snapshot), replacing any with the same ID.
+    pub fn insert_market(&self, market: MarketWithOrderbook) {
+        let mut markets = self.markets.write().unwrap();
+        markets.retain(|m| m.id != market.id);
+        markets.push(market);
+    }
+
+    /// Remove all markets.
+    pub fn clear_markets(&self) {
+        self.markets.write().unwrap().clear();
+    }
+
+    /// Record a resolution for a stored market.
+    pub fn insert_resolution(&self, market_id: Uuid, winning_side: &str) {
+        let condition_id = self
+            .markets
+            .read()
+            .unwrap()
+            .iter()
+            .find(|m| m.id == market_id)
+            .map(|m| m.condition_id.clone());
+
+        if let Some(condition_id) = condition_id {
+            self.resolutions.write().unwrap().insert(
+                condition_id.clone(),
+                MarketResolution {
+                    market_id,
+                    condition_id,
+                    winning_side: winning_side.to_string(),
+                    resolved_at: Some(Utc::now()),
+                },
+            );
+        }
+    }
+
+    /// Settlements recorded so far, in insertion order.
+    pub fn settlements(&self) -> Vec<SettlementInsert> {
+        self.settlements.read().unwrap().clone()
+    }
+
+    /// Audited trade intents and their outcomes, in insertion order.
+    pub fn audits(&self) -> Vec<(TradeIntent, TradeOutcome)> {
+        self.audits.read().unwrap().clone()
+    }
+
+    /// Filter stored markets the way the fresh-orderbook SQL queries do.
+    fn fresh_markets(
+        &self,
+        max_age_seconds: i32,
+        max_expiry_seconds: i64,
+        predicate: impl Fn(&MarketWithOrderbook) -> bool,
+    ) -> Vec<MarketWithOrderbook> {
+        let now = Utc::now();
+        let snapshot_cutoff = now - Duration::seconds(max_age_seconds as i64);
+        let expiry_cutoff = now + Duration::seconds(max_expiry_seconds);
+
+        let mut results: Vec<_> = self
+            .markets
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|m| {
+                m.is_active
+                    && m.captured_at > snapshot_cutoff
+                    && m.end_time > now
+                    && m.end_time <= expiry_cutoff
+                    && predicate(m)
+            })
+            .cloned()
+            .collect();
+
+        results.sort_by_key(|m| m.end_time);
+        results
+    }
+}
+
+#[async_trait]
+impl MarketRepository for InMemoryMarketRepository {
+    async fn get_15m_updown_markets_with_orderbooks(
+        &self,
+        max_age_seconds: i32,
+        assets: &[String],
+        max_expiry_seconds: i64,
+        timeframes: &[String],
+    ) -> Result<Vec<MarketWithOrderbook>, sqlx::Error> {
+        Ok(
+            self.fresh_markets(max_age_seconds, max_expiry_seconds, |m| {
+                m.market_type == "up_down"
+                    && assets.contains(&m.asset)
+                    && timeframes.contains(&m.timeframe)
+            }),
+        )
+    }
+
+    async fn get_markets_with_fresh_orderbooks(
+        &self,
+        max_age_seconds: i32,
+        assets: &[String],
+        max_expiry_seconds: i64,
+    ) -> Result<Vec<MarketWithPrices>, sqlx::Error> {
+        let all_assets = assets.iter().any(|a| a.eq_ignore_ascii_case("ALL"));
+        let markets = self.fresh_markets(max_age_seconds, max_expiry_seconds, |m| {
+            all_assets || assets.contains(&m.asset)
+        });
+
+        Ok(markets
+            .into_iter()
+            .map(|m| MarketWithPrices {
+                id: m.id,
+                condition_id: m.condition_id,
+                market_type: m.market_type,
+                asset: m.asset,
+                timeframe: m.timeframe,
+                yes_token_id: m.yes_token_id,
+                no_token_id: m.no_token_id,
+                name: m.name,
+                end_time: m.end_time,
+                is_active: m.is_active,
+                yes_best_ask: m.yes_best_ask,
+                yes_best_bid: m.yes_best_bid,
+                no_best_ask: m.no_best_ask,
+                no_best_bid: m.no_best_bid,
+                captured_at: m.captured_at,
+            })
+            .collect())
+    }
+
+    async fn get_market_resolutions_batch(
+        &self,
+        market_ids: &[Uuid],
+    ) -> Result<Vec<MarketResolution>, sqlx::Error> {
+        let markets = self.markets.read().unwrap();
+        let resolutions = self.resolutions.read().unwrap();
+
+        Ok(markets
+            .iter()
+            .filter(|m| market_ids.contains(&m.id))
+            .filter_map(|m| {
+                resolutions.get(&m.condition_id).map(|r| MarketResolution {
+                    market_id: m.id,
+                    condition_id: m.condition_id.clone(),
+                    winning_side: r.winning_side.clone(),
+                    resolved_at: r.resolved_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn upsert_market_resolution(
+        &self,
+        resolution: &MarketResolutionInsert,
+    ) -> Result<(), sqlx::Error> {
+        let market_id = self
+            .markets
+            .read()
+            .unwrap()
+            .iter()
+            .find(|m| m.condition_id == resolution.condition_id)
+            .map(|m| m.id)
+            .unwrap_or_default();
+
+        self.resolutions.write().unwrap().insert(
+            resolution.condition_id.clone(),
+            MarketResolution {
+                market_id,
+                condition_id: resolution.condition_id.clone(),
+                winning_side: resolution.winning_side.clone(),
+                resolved_at: Some(Utc::now()),
+            },
+        );
+        Ok(())
+    }
+
+    async fn insert_settlement(&self, settlement: &SettlementInsert) -> Result<Uuid, sqlx::Error> {
+        self.settlements.write().unwrap().push(settlement.clone());
+        Ok(Uuid::new_v4())
+    }
+
+    async fn audit_trade(
+        &self,
+        intent: &TradeIntent,
+        outcome: TradeOutcome,
+    ) -> Result<Uuid, sqlx::Error> {
+        self.audits.write().unwrap().push((intent.clone(), outcome));
+        Ok(Uuid::new_v4())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn test_market(asset: &str, timeframe: &str, expires_in_secs: i64) -> MarketWithOrderbook {
+        let now = Utc::now();
+        MarketWithOrderbook {
+            id: Uuid::new_v4(),
+            condition_id: format!("cond-{}", Uuid::new_v4()),
+            market_type: "up_down".to_string(),
+            asset: asset.to_string(),
+            timeframe: timeframe.to_string(),
+            yes_token_id: "yes".to_string(),
+            no_token_id: "no".to_string(),
+            name: format!("{} Up or Down {}", asset, timeframe),
+            end_time: now + Duration::seconds(expires_in_secs),
+            is_active: true,
+            yes_best_ask: Some(dec!(0.55)),
+            yes_best_bid: Some(dec!(0.53)),
+            no_best_ask: Some(dec!(0.47)),
+            no_best_bid: Some(dec!(0.45)),
+            yes_asks: None,
+            no_asks: None,
+            yes_bids: None,
+            no_bids: None,
+            captured_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_updown_filters() {
+        let repo = InMemoryMarketRepository::new();
+        let btc = test_market("BTC", "15m", 300);
+        let eth_later = test_market("ETH", "5m", 600);
+        let mut stale = test_market("BTC", "15m", 300);
+        stale.captured_at = Utc::now() - Duration::seconds(60);
+        let expired = test_market("BTC", "15m", -10);
+        let too_far = test_market("BTC", "15m", 7200);
+        let hourly = test_market("BTC", "1h", 300);
+        let mut inactive = test_market("BTC", "15m", 300);
+        inactive.is_active = false;
+
+        for m in [
+            eth_later.clone(),
+            btc.clone(),
+            stale,
+            expired,
+            too_far,
+            hourly,
+            inactive,
+        ] {
+            repo.insert_market(m);
+        }
+
+        let assets = vec!["BTC".to_string(), "ETH".to_string()];
+        let timeframes = vec!["5m".to_string(), "15m".to_string()];
+        let markets = repo
+            .get_15m_updown_markets_with_orderbooks(5, &assets, 900, &timeframes)
+            .await
+            .unwrap();
+
+        let ids: Vec<Uuid> = markets.iter().map(|m| m.id).collect();
+        // Sorted by end_time ascending
+        assert_eq!(ids, vec![btc.id, eth_later.id]);
+
+        let btc_only = repo
+            .get_15m_updown_markets_with_orderbooks(5, &["BTC".to_string()], 900, &timeframes)
+            .await
+            .unwrap();
+        assert_eq!(btc_only.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_market_returns_latest_snapshot() {
+        let repo = InMemoryMarketRepository::new();
+        let market = test_market("BTC", "15m", 300);
+        repo.insert_market(market.clone());
+        repo.insert_market(test_market("BTC", "15m", 400));
+
+        let mut repriced = market.clone();
+        repriced.yes_best_ask = Some(dec!(0.58));
+        repo.insert_market(repriced);
+
+        let fresh = refresh_market(&repo, &market, 5, 900)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fresh.id, market.id);
+        assert_eq!(fresh.best_ask("YES"), Some(dec!(0.58)));
+
+        // Snapshot went stale
+        let mut stale = market.clone();
+        stale.captured_at = Utc::now() - Duration::seconds(60);
+        repo.insert_market(stale);
+        assert!(refresh_market(&repo, &market, 5, 900)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fresh_retry_ask_bounded_by_max_price() {
+        let repo = InMemoryMarketRepository::new();
+        let market = test_market("BTC", "15m", 300);
+        let mut repriced = market.clone();
+        repriced.yes_best_ask = Some(dec!(0.58));
+        repo.insert_market(repriced);
+
+        // Ask moved above the rejected price but stays under the max
+        let ask = fresh_retry_ask(&repo, &market, "YES", dec!(0.55), dec!(0.60), 5, 900)
+            .await
+            .unwrap();
+        assert_eq!(ask, Some(dec!(0.58)));
+
+        // Rejected price was already the max: no retry above it
+        let ask = fresh_retry_ask(&repo, &market, "YES", dec!(0.55), dec!(0.55), 5, 900)
+            .await
+            .unwrap();
+        assert_eq!(ask, None);
+
+        // Snapshot still shows the rejected price: retrying would fail again
+        let ask = fresh_retry_ask(&repo, &market, "YES", dec!(0.58), dec!(0.60), 5, 900)
+            .await
+            .unwrap();
+        assert_eq!(ask, None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_fresh_orderbooks_all_assets() {
+        let repo = InMemoryMarketRepository::new();
+        repo.insert_market(test_market("BTC", "15m", 300));
+        repo.insert_market(test_market("SOL", "1h", 600));
+
+        let markets = repo
+            .get_markets_with_fresh_orderbooks(5, &["ALL".to_string()], 900)
+            .await
+            .unwrap();
+        assert_eq!(markets.len(), 2);
+        assert_eq!(markets[0].yes_best_ask, Some(dec!(0.55)));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_resolutions() {
+        let repo = InMemoryMarketRepository::new();
+        let a = test_market("BTC", "15m", 300);
+        let b = test_market("ETH", "15m", 300);
+        repo.insert_market(a.clone());
+        repo.insert_market(b.clone());
+
+        repo.insert_resolution(a.id, "YES");
+        repo.upsert_market_resolution(&MarketResolutionInsert {
+            condition_id: b.condition_id.clone(),
+            market_type: b.market_type.clone(),
+            asset: b.asset.clone(),
+            timeframe: b.timeframe.clone(),
+            name: b.name.clone(),
+            yes_token_id: b.yes_token_id.clone(),
+            no_token_id: b.no_token_id.clone(),
+            winning_side: "NO".to_string(),
+            end_time: b.end_time,
+        })
+        .await
+        .unwrap();
+
+        let mut resolutions = repo
+            .get_market_resolutions_batch(&[a.id, b.id, Uuid::new_v4()])
+            .await
+            .unwrap();
+        resolutions.sort_by_key(|r| r.winning_side.clone());
+
+        assert_eq!(resolutions.len(), 2);
+        assert_eq!(resolutions[0].market_id, b.id);
+        assert_eq!(resolutions[0].winning_side, "NO");
+        assert_eq!(resolutions[1].market_id, a.id);
+        assert_eq!(resolutions[1].winning_side, "YES");
+    }
+}
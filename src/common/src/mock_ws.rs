@@ -0,0 +1,163 @@
+//! Scripted local WebSocket server for exercising WS clients in tests.
+//!
+//! Point a client at [`MockWsServer::url`] instead of the real endpoint, then
+//! inspect what it sent with [`MockWsServer::received`].
+
+use std::sync::{Arc, Mutex};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+/// One step of a [`MockWsServer`] script.
+#[derive(Debug, Clone)]
+pub enum MockStep {
+    /// Wait until the client sends a text frame (e.g. its subscription)
+    AwaitClientText,
+    /// Send a frame to the client
+    Send(Message),
+}
+
+impl MockStep {
+    /// Send a text frame.
+    pub fn text(body: impl Into<String>) -> Self {
+        MockStep::Send(Message::Text(body.into().into()))
+    }
+}
+
+/// WebSocket server on a random local port that plays the same script to
+/// every connection and records the text frames clients send.
+///
+/// Once the script is done the connection stays open, still recording, until
+/// the client closes it. The server stops accepting when dropped.
+pub struct MockWsServer {
+    url: String,
+    received: Arc<Mutex<Vec<String>>>,
+    task: JoinHandle<()>,
+}
+
+impl MockWsServer {
+    /// Bind to 127.0.0.1 and start serving `script`.
+    pub async fn start(script: Vec<MockStep>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = received.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let script = script.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    if let Ok(ws) = tokio_tungstenite::accept_async(stream).await {
+                        play(ws, script, seen).await;
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            url,
+            received,
+            task,
+        })
+    }
+
+    /// `ws://` URL of the server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Text frames received from clients so far, in arrival order.
+    pub fn received(&self) -> Vec<String> {
+        self.received
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for MockWsServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Run `script` on one connection, then record until the client goes away.
+async fn play(
+    mut ws: WebSocketStream<TcpStream>,
+    script: Vec<MockStep>,
+    seen: Arc<Mutex<Vec<String>>>,
+) {
+    let record = |text: &str| {
+        seen.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(text.to_string());
+    };
+
+    for step in script {
+        match step {
+            MockStep::AwaitClientText => loop {
+                match ws.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        record(&text);
+                        break;
+                    }
+                    Some(Ok(_)) => continue,
+                    _ => return,
+                }
+            },
+            MockStep::Send(msg) => {
+                if ws.send(msg).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    while let Some(Ok(msg)) = ws.next().await {
+        if let Message::Text(text) = msg {
+            record(&text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_server_plays_script_after_client_message() {
+        let server = MockWsServer::start(vec![
+            MockStep::AwaitClientText,
+            MockStep::text("first"),
+            MockStep::Send(Message::Ping(vec![1].into())),
+            MockStep::text("second"),
+        ])
+        .await
+        .unwrap();
+
+        let (mut ws, _) = tokio_tungstenite::connect_async(server.url())
+            .await
+            .unwrap();
+        ws.send(Message::Text("hello".into())).await.unwrap();
+
+        let mut texts = Vec::new();
+        while texts.len() < 2 {
+            match ws.next().await.unwrap().unwrap() {
+                Message::Text(text) => texts.push(text.to_string()),
+                Message::Ping(_) => {}
+                other => panic!("unexpected frame {:?}", other),
+            }
+        }
+        assert_eq!(texts, vec!["first", "second"]);
+
+        // Frames sent after the script are still recorded
+        ws.send(Message::Text("bye".into())).await.unwrap();
+        ws.close(None).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(server.received(), vec!["hello", "bye"]);
+    }
+}
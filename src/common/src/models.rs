@@ -1,5 +1,7 @@
 //! Shared data models for markets and orderbooks.
 
+use std::fmt;
+
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -33,6 +35,85 @@ pub struct Market {
     pub updated_at: DateTime<Utc>,
 }
 
+/// One tradeable outcome of a market.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub label: String,
+    pub token_id: String,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    /// Last outcome price from Gamma, if reported
+    pub last_price: Option<Decimal>,
+}
+
+impl Outcome {
+    /// Label reads as the positive side ("Yes", "Up", "Higher", "Above").
+    fn is_yes_like(&self) -> bool {
+        let lower = self.label.to_lowercase();
+        lower == "yes" || lower == "up" || lower.contains("higher") || lower.contains("above")
+    }
+
+    /// Label reads as the negative side ("No", "Down", "Lower", "Below").
+    fn is_no_like(&self) -> bool {
+        let lower = self.label.to_lowercase();
+        lower == "no" || lower == "down" || lower.contains("lower") || lower.contains("below")
+    }
+}
+
+/// The YES/NO pair of a binary market's outcomes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinaryView<'a> {
+    pub yes: &'a Outcome,
+    pub no: &'a Outcome,
+}
+
+/// Indices of the YES and NO outcomes in a two-outcome list.
+///
+/// Up/Higher/Above count as YES and Down/Lower/Below as NO. When the labels
+/// don't say (e.g. team names), the first outcome is YES. Returns None unless
+/// there are exactly two outcomes.
+pub fn binary_indices(outcomes: &[Outcome]) -> Option<(usize, usize)> {
+    if outcomes.len() != 2 {
+        return None;
+    }
+    let yes_pos = outcomes.iter().position(Outcome::is_yes_like);
+    let no_pos = outcomes.iter().position(Outcome::is_no_like);
+    match (yes_pos, no_pos) {
+        (Some(y), Some(n)) if y != n => Some((y, n)),
+        _ => Some((0, 1)),
+    }
+}
+
+/// YES/NO view of a two-outcome list. See [`binary_indices`].
+pub fn binary_view(outcomes: &[Outcome]) -> Option<BinaryView<'_>> {
+    let (yes, no) = binary_indices(outcomes)?;
+    Some(BinaryView {
+        yes: &outcomes[yes],
+        no: &outcomes[no],
+    })
+}
+
+/// A categorical market with more than two outcomes.
+///
+/// Kept apart from [`Market`], whose YES/NO token pair only makes sense for
+/// binary markets. Not persisted yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiOutcomeMarket {
+    pub condition_id: String,
+    pub name: String,
+    pub end_time: Option<DateTime<Utc>>,
+    pub outcomes: Vec<Outcome>,
+}
+
+impl MultiOutcomeMarket {
+    /// Look up an outcome by label (case-insensitive).
+    pub fn outcome(&self, label: &str) -> Option<&Outcome> {
+        self.outcomes
+            .iter()
+            .find(|o| o.label.eq_ignore_ascii_case(label))
+    }
+}
+
 /// Orderbook snapshot from the database.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct OrderbookSnapshot {
@@ -82,3 +163,88 @@ pub struct Position {
     pub opened_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
 }
+
+/// Whether a trade is simulated or sent to the exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeMode {
+    DryRun,
+    Live,
+}
+
+impl TradeMode {
+    /// Mode for a trader's `--dry-run` flag.
+    pub fn from_dry_run(dry_run: bool) -> Self {
+        if dry_run {
+            TradeMode::DryRun
+        } else {
+            TradeMode::Live
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeMode::DryRun => "dry_run",
+            TradeMode::Live => "live",
+        }
+    }
+}
+
+/// How a trade intent ended, as recorded in the audit trail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeOutcome {
+    /// Accepted by the exchange; the fill is tracked separately.
+    Placed,
+    /// Filled in full, or booked by the dry-run portfolio.
+    Filled,
+    /// Rejected by the exchange or venue.
+    Rejected,
+    /// Dropped locally after the intent was formed (balance gate, moved book).
+    Skipped,
+}
+
+impl TradeOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeOutcome::Placed => "placed",
+            TradeOutcome::Filled => "filled",
+            TradeOutcome::Rejected => "rejected",
+            TradeOutcome::Skipped => "skipped",
+        }
+    }
+}
+
+/// A buy a strategy has decided to place, built before execution so every
+/// binary logs and audits its orders the same way.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeIntent {
+    pub market_id: Uuid,
+    pub token_id: String,
+    /// "YES" or "NO"
+    pub side: String,
+    pub shares: Decimal,
+    /// Price the order is sent at
+    pub limit_price: Decimal,
+    /// Expected average fill price after walking the book
+    pub effective_price: Decimal,
+    /// Strategy that produced the intent (e.g. "momentum")
+    pub strategy_tag: String,
+    pub mode: TradeMode,
+}
+
+impl fmt::Display for TradeIntent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} BUY {} {} @ {} (eff {}) market={}",
+            self.strategy_tag,
+            self.mode.as_str(),
+            self.side,
+            self.shares.normalize(),
+            self.limit_price.normalize(),
+            self.effective_price.round_dp(4).normalize(),
+            self.market_id
+        )
+    }
+}
@@ -3,10 +3,17 @@
 //! Provides unified types for markets across Polymarket and Kalshi.
 
 use chrono::{DateTime, Utc};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+
+/// Kalshi taker fee multiplier: fee = 0.07 * contracts * P * (1 - P).
+const KALSHI_TAKER_FEE_MULTIPLIER: Decimal = dec!(0.07);
+
+/// Polymarket taker fee rate (feeRateBps / 10000). Currently 0 on CLOB markets.
+const POLYMARKET_TAKER_FEE_RATE: Decimal = Decimal::ZERO;
 
 /// Supported prediction market platforms.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -18,10 +25,12 @@ pub enum Platform {
 }
 
 impl Platform {
-    /// Get the platform fee rate for trading.
+    /// Get the flat platform fee rate for trading.
     /// Polymarket: 0% (no taker fees)
     /// Kalshi: ~1% (varies by contract)
     /// Limitless: 0% (no fees)
+    ///
+    /// This is a rough approximation; use `fee_for` for the actual fee curve.
     pub fn fee_rate(&self) -> Decimal {
         match self {
             Platform::Polymarket => Decimal::ZERO,
@@ -30,6 +39,31 @@ impl Platform {
         }
     }
 
+    /// Unrounded taker fee for a single contract bought at `price`.
+    /// Kalshi: 0.07 * P * (1 - P), peaking at 1.75c at P = 0.50
+    /// Polymarket: rate * min(P, 1 - P)
+    /// Limitless: 0
+    pub fn fee_per_contract(&self, price: Decimal) -> Decimal {
+        match self {
+            Platform::Polymarket => POLYMARKET_TAKER_FEE_RATE * price.min(Decimal::ONE - price),
+            Platform::Kalshi => KALSHI_TAKER_FEE_MULTIPLIER * price * (Decimal::ONE - price),
+            Platform::Limitless => Decimal::ZERO,
+        }
+    }
+
+    /// Total taker fee in dollars for buying `size` contracts at `price`.
+    /// Kalshi rounds the fee for each order up to the next cent.
+    pub fn fee_for(&self, price: Decimal, size: Decimal) -> Decimal {
+        if size <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        let fee = self.fee_per_contract(price) * size;
+        match self {
+            Platform::Kalshi => fee.round_dp_with_strategy(2, RoundingStrategy::AwayFromZero),
+            Platform::Polymarket | Platform::Limitless => fee,
+        }
+    }
+
     /// Get the platform name as a string for database storage.
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -39,16 +73,6 @@ impl Platform {
         }
     }
 
-    /// Parse platform from string.
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
-            "polymarket" => Some(Platform::Polymarket),
-            "kalshi" => Some(Platform::Kalshi),
-            "limitless" => Some(Platform::Limitless),
-            _ => None,
-        }
-    }
-
     /// Check if platform has WebSocket orderbook support.
     pub fn has_websocket_orderbook(&self) -> bool {
         match self {
@@ -77,6 +101,23 @@ impl Platform {
     }
 }
 
+impl FromStr for Platform {
+    type Err = String;
+
+    /// Parse a platform name, case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "polymarket" => Ok(Platform::Polymarket),
+            "kalshi" => Ok(Platform::Kalshi),
+            "limitless" => Ok(Platform::Limitless),
+            other => Err(format!(
+                "unknown platform '{}' (expected polymarket, kalshi, limitless)",
+                other
+            )),
+        }
+    }
+}
+
 impl fmt::Display for Platform {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_str())
@@ -114,6 +155,61 @@ impl OrderbookDepth {
     }
 }
 
+/// Build a synthetic ask ladder for a market that only reports its best level
+/// (Kalshi).
+///
+/// This is an approximation, not real depth. `best_size` contracts (e.g. the
+/// market's reported liquidity at `best_price`) are spread over up to `levels`
+/// asks, `step` apart from `best_price` upward. Each worse level holds more
+/// than the one before it, so the least size sits at the best price and fill
+/// estimates err on the expensive side. More levels or a wider step make the
+/// ladder more conservative; `levels = 1` assumes the whole size is at the
+/// best price. Levels that would reach the $1.00 payout are dropped and their
+/// share moved to the last valid level, so sizes always sum to `best_size`.
+pub fn synthesize_depth_from_best(
+    best_price: Decimal,
+    best_size: Decimal,
+    levels: usize,
+    step: Decimal,
+) -> OrderbookDepth {
+    if best_price <= Decimal::ZERO
+        || best_price >= Decimal::ONE
+        || best_size <= Decimal::ZERO
+        || levels == 0
+    {
+        return OrderbookDepth::default();
+    }
+
+    // Prices below the payout cap; always at least the best price itself
+    let level_count = if step > Decimal::ZERO { levels } else { 1 };
+    let prices: Vec<Decimal> = (0..level_count)
+        .map(|i| best_price + step * Decimal::from(i))
+        .take_while(|p| *p < Decimal::ONE)
+        .collect();
+
+    // Linearly increasing weights 1, 2, ..., n; the last level takes the
+    // rounding remainder so the total is exact
+    let n = prices.len();
+    let total_weight = Decimal::from(n * (n + 1) / 2);
+    let mut remaining = best_size;
+    let asks = prices
+        .iter()
+        .enumerate()
+        .map(|(i, &price)| {
+            let size = if i + 1 == n {
+                remaining
+            } else {
+                (best_size * Decimal::from(i + 1) / total_weight)
+                    .round_dp_with_strategy(2, RoundingStrategy::ToZero)
+            };
+            remaining -= size;
+            OrderbookLevel { price, size }
+        })
+        .collect();
+
+    OrderbookDepth::new(asks, vec![])
+}
+
 /// Unified market representation for cross-platform comparison.
 /// Contains only the fields needed for arbitrage detection.
 #[derive(Debug, Clone)]
@@ -307,9 +403,9 @@ impl CrossPlatformOpportunity {
         let gross_profit = Decimal::ONE - total_cost;
         let gross_profit_pct = (gross_profit / total_cost) * dec!(100);
 
-        // Calculate fees
-        let yes_fee = yes_price * buy_yes_on.fee_rate();
-        let no_fee = no_price * buy_no_on.fee_rate();
+        // Calculate per-contract fees
+        let yes_fee = buy_yes_on.fee_per_contract(yes_price);
+        let no_fee = buy_no_on.fee_per_contract(no_price);
         let total_fees = yes_fee + no_fee;
 
         // Net profit after fees
@@ -375,13 +471,73 @@ mod tests {
         assert_eq!(Platform::Limitless.fee_rate(), Decimal::ZERO);
     }
 
+    #[test]
+    fn test_platform_fee_for_kalshi_curve() {
+        // 0.07 * 100 * 0.50 * 0.50 = 1.75
+        assert_eq!(Platform::Kalshi.fee_for(dec!(0.50), dec!(100)), dec!(1.75));
+        // 0.07 * 100 * 0.10 * 0.90 = 0.63
+        assert_eq!(Platform::Kalshi.fee_for(dec!(0.10), dec!(100)), dec!(0.63));
+        // Symmetric around 0.50
+        assert_eq!(
+            Platform::Kalshi.fee_for(dec!(0.90), dec!(100)),
+            Platform::Kalshi.fee_for(dec!(0.10), dec!(100))
+        );
+        // 0.07 * 1 * 0.50 * 0.50 = 0.0175 -> rounded up to 0.02
+        assert_eq!(Platform::Kalshi.fee_for(dec!(0.50), dec!(1)), dec!(0.02));
+        // 0.07 * 10 * 0.99 * 0.01 = 0.00693 -> rounded up to 0.01
+        assert_eq!(Platform::Kalshi.fee_for(dec!(0.99), dec!(10)), dec!(0.01));
+        // No fee at the boundaries or for empty orders
+        assert_eq!(Platform::Kalshi.fee_for(dec!(1), dec!(100)), Decimal::ZERO);
+        assert_eq!(
+            Platform::Kalshi.fee_for(dec!(0.50), Decimal::ZERO),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_platform_fee_for_zero_fee_platforms() {
+        assert_eq!(
+            Platform::Polymarket.fee_for(dec!(0.50), dec!(100)),
+            Decimal::ZERO
+        );
+        assert_eq!(
+            Platform::Limitless.fee_for(dec!(0.50), dec!(100)),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_platform_fee_per_contract() {
+        assert_eq!(Platform::Kalshi.fee_per_contract(dec!(0.50)), dec!(0.0175));
+        assert_eq!(
+            Platform::Kalshi.fee_per_contract(dec!(0.44)),
+            dec!(0.017248)
+        );
+        assert_eq!(
+            Platform::Polymarket.fee_per_contract(dec!(0.44)),
+            Decimal::ZERO
+        );
+    }
+
     #[test]
     fn test_platform_from_str() {
-        assert_eq!(Platform::from_str("polymarket"), Some(Platform::Polymarket));
-        assert_eq!(Platform::from_str("Kalshi"), Some(Platform::Kalshi));
-        assert_eq!(Platform::from_str("limitless"), Some(Platform::Limitless));
-        assert_eq!(Platform::from_str("Limitless"), Some(Platform::Limitless));
-        assert_eq!(Platform::from_str("unknown"), None);
+        assert_eq!("polymarket".parse(), Ok(Platform::Polymarket));
+        assert_eq!("Kalshi".parse(), Ok(Platform::Kalshi));
+        assert_eq!("limitless".parse(), Ok(Platform::Limitless));
+        assert_eq!("Limitless".parse(), Ok(Platform::Limitless));
+        assert!("unknown".parse::<Platform>().is_err());
+    }
+
+    #[test]
+    fn test_platform_display_round_trips() {
+        for platform in [Platform::Polymarket, Platform::Kalshi, Platform::Limitless] {
+            assert_eq!(platform.to_string(), platform.as_str());
+            assert_eq!(platform.to_string().parse(), Ok(platform));
+        }
+
+        let err = "polymarkt".parse::<Platform>().unwrap_err();
+        assert!(err.contains("polymarkt"), "{}", err);
+        assert!("".parse::<Platform>().is_err());
     }
 
     #[test]
@@ -468,8 +624,8 @@ mod tests {
 
         // Total cost: 0.50 (Poly YES) + 0.44 (Kalshi NO) = 0.94
         // Gross profit: 1.0 - 0.94 = 0.06 = 6.38%
-        // Kalshi fee: 0.44 * 0.01 = 0.0044
-        // Net profit: 0.06 - 0.0044 = 0.0556 = 5.91%
+        // Kalshi fee: 0.07 * 0.44 * 0.56 = 0.017248
+        // Net profit: 0.06 - 0.017248 = 0.042752 = 4.55%
         let opp = CrossPlatformOpportunity::calculate(pair, dec!(1.0)).unwrap();
 
         assert_eq!(opp.buy_yes_on, Platform::Polymarket);
@@ -478,6 +634,54 @@ mod tests {
         assert_eq!(opp.no_price, dec!(0.44));
         assert_eq!(opp.total_cost, dec!(0.94));
         assert!(opp.gross_profit_pct > dec!(6.0));
-        assert!(opp.net_profit_pct > dec!(5.0));
+        assert!(opp.net_profit_pct > dec!(4.5));
+        assert!(opp.net_profit_pct < dec!(4.6));
+    }
+
+    #[test]
+    fn test_synthesize_depth_from_best() {
+        let depth = synthesize_depth_from_best(dec!(0.40), dec!(1000), 5, dec!(0.01));
+
+        assert_eq!(depth.asks.len(), 5);
+        assert_eq!(depth.best_ask(), Some(dec!(0.40)));
+        assert!(depth.bids.is_empty());
+        // Prices strictly rise and sizes never shrink going down the book
+        for pair in depth.asks.windows(2) {
+            assert!(pair[1].price > pair[0].price);
+            assert!(pair[1].size >= pair[0].size);
+        }
+        assert_eq!(depth.asks[4].price, dec!(0.44));
+        let total: Decimal = depth.asks.iter().map(|l| l.size).sum();
+        assert_eq!(total, dec!(1000));
+
+        // Rounding remainder lands on the last level
+        let depth = synthesize_depth_from_best(dec!(0.40), dec!(100), 3, dec!(0.01));
+        let sizes: Vec<Decimal> = depth.asks.iter().map(|l| l.size).collect();
+        assert_eq!(sizes, vec![dec!(16.66), dec!(33.33), dec!(50.01)]);
+    }
+
+    #[test]
+    fn test_synthesize_depth_caps_below_payout() {
+        let depth = synthesize_depth_from_best(dec!(0.97), dec!(60), 5, dec!(0.01));
+        assert_eq!(depth.asks.len(), 3);
+        assert_eq!(depth.asks[2].price, dec!(0.99));
+        let total: Decimal = depth.asks.iter().map(|l| l.size).sum();
+        assert_eq!(total, dec!(60));
+
+        // A single level (or no step) puts everything at the best price
+        let depth = synthesize_depth_from_best(dec!(0.40), dec!(50), 1, dec!(0.01));
+        assert_eq!(depth.asks.len(), 1);
+        assert_eq!(depth.asks[0].size, dec!(50));
+        assert_eq!(
+            synthesize_depth_from_best(dec!(0.40), dec!(50), 4, Decimal::ZERO)
+                .asks
+                .len(),
+            1
+        );
+
+        // Unusable inputs produce no depth
+        assert!(!synthesize_depth_from_best(Decimal::ZERO, dec!(50), 5, dec!(0.01)).has_depth());
+        assert!(!synthesize_depth_from_best(dec!(0.40), Decimal::ZERO, 5, dec!(0.01)).has_depth());
+        assert!(!synthesize_depth_from_best(dec!(0.40), dec!(50), 0, dec!(0.01)).has_depth());
     }
 }
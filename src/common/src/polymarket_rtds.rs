@@ -9,8 +9,10 @@
 //! - Latency: ~800ms (vs ~10ms for Binance)
 //! - No klines available - must synthesize open price from first price at market start
 
+use std::collections::hash_map::RandomState;
 use std::collections::{HashMap, VecDeque};
-use std::time::Duration;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
 
 use chrono::{DateTime, TimeZone, Utc};
 use futures_util::{SinkExt, StreamExt};
@@ -20,31 +22,120 @@ use tokio::time::timeout;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{debug, error, info, warn};
 
+use crate::assets::{asset_for_chainlink, chainlink_symbol};
+
 /// Polymarket RTDS WebSocket URL.
 pub const POLYMARKET_RTDS_URL: &str = "wss://ws-live-data.polymarket.com";
 
-/// Map asset name to Chainlink symbol format.
+/// Map asset name to Chainlink symbol format (see [`crate::assets::ASSET_REGISTRY`]).
 pub fn asset_to_chainlink_symbol(asset: &str) -> Option<&'static str> {
-    match asset.to_uppercase().as_str() {
-        "BTC" => Some("btc/usd"),
-        "ETH" => Some("eth/usd"),
-        "SOL" => Some("sol/usd"),
-        "XRP" => Some("xrp/usd"),
-        _ => {
-            warn!("Unsupported Chainlink asset: {}, skipping", asset);
-            None
-        }
+    let symbol = chainlink_symbol(asset);
+    if symbol.is_none() {
+        warn!("Unsupported Chainlink asset: {}, skipping", asset);
     }
+    symbol
 }
 
 /// Map Chainlink symbol back to asset name.
 pub fn chainlink_symbol_to_asset(symbol: &str) -> Option<&'static str> {
-    match symbol.to_lowercase().as_str() {
-        "btc/usd" => Some("BTC"),
-        "eth/usd" => Some("ETH"),
-        "sol/usd" => Some("SOL"),
-        "xrp/usd" => Some("XRP"),
-        _ => None,
+    asset_for_chainlink(symbol)
+}
+
+/// Window over which per-symbol message rates are measured.
+const HEALTH_WINDOW_SECS: i64 = 60;
+/// Minimum messages per symbol per window (~75% of the ~1/sec Chainlink rate).
+const MIN_MESSAGES_PER_WINDOW: usize = 45;
+/// A symbol silent for this long is degraded.
+const DEGRADED_AFTER_SECS: i64 = 10;
+/// Every symbol silent for this long means the stream is stalled.
+const STALLED_AFTER_SECS: i64 = 30;
+/// Arrival timestamps kept per symbol (ring buffer).
+const ARRIVAL_BUFFER_CAPACITY: usize = 128;
+
+/// Health of the RTDS price stream, judged from per-symbol arrival times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamHealth {
+    /// Every symbol is arriving at roughly the expected rate
+    Healthy,
+    /// A symbol has gone quiet or is arriving too slowly.
+    /// `last_seen` is None if it has never been received.
+    Degraded {
+        symbol: String,
+        last_seen: Option<DateTime<Utc>>,
+    },
+    /// No subscribed symbol has arrived recently; the stream should be reconnected
+    Stalled,
+}
+
+/// Ring-buffers message arrival times per symbol to judge stream health.
+#[derive(Debug)]
+pub struct MessageRateMonitor {
+    symbols: Vec<String>,
+    arrivals: HashMap<String, VecDeque<DateTime<Utc>>>,
+    started_at: DateTime<Utc>,
+}
+
+impl MessageRateMonitor {
+    /// Create a monitor for `symbols`, treating `started_at` as the connection time.
+    pub fn new(symbols: Vec<String>, started_at: DateTime<Utc>) -> Self {
+        Self {
+            symbols,
+            arrivals: HashMap::new(),
+            started_at,
+        }
+    }
+
+    /// Record a message for `symbol` arriving at `at`.
+    pub fn record(&mut self, symbol: &str, at: DateTime<Utc>) {
+        let arrivals = self
+            .arrivals
+            .entry(symbol.to_string())
+            .or_insert_with(|| VecDeque::with_capacity(ARRIVAL_BUFFER_CAPACITY));
+        if arrivals.len() == ARRIVAL_BUFFER_CAPACITY {
+            arrivals.pop_front();
+        }
+        arrivals.push_back(at);
+    }
+
+    /// Most recent arrival for `symbol`.
+    pub fn last_seen(&self, symbol: &str) -> Option<DateTime<Utc>> {
+        self.arrivals.get(symbol).and_then(|a| a.back().copied())
+    }
+
+    /// Messages for `symbol` within the health window ending at `now`.
+    pub fn messages_in_window(&self, symbol: &str, now: DateTime<Utc>) -> usize {
+        let cutoff = now - chrono::Duration::seconds(HEALTH_WINDOW_SECS);
+        self.arrivals
+            .get(symbol)
+            .map_or(0, |a| a.iter().rev().take_while(|t| **t > cutoff).count())
+    }
+
+    /// Judge stream health as of `now`.
+    pub fn health_at(&self, now: DateTime<Utc>) -> StreamHealth {
+        let silent_for = |symbol: &str| now - self.last_seen(symbol).unwrap_or(self.started_at);
+
+        if self
+            .symbols
+            .iter()
+            .all(|s| silent_for(s) >= chrono::Duration::seconds(STALLED_AFTER_SECS))
+        {
+            return StreamHealth::Stalled;
+        }
+
+        // Rates are only meaningful once a full window has elapsed
+        let full_window = now - self.started_at >= chrono::Duration::seconds(HEALTH_WINDOW_SECS);
+
+        self.symbols
+            .iter()
+            .filter(|s| {
+                silent_for(s) >= chrono::Duration::seconds(DEGRADED_AFTER_SECS)
+                    || (full_window && self.messages_in_window(s, now) < MIN_MESSAGES_PER_WINDOW)
+            })
+            .min_by_key(|s| self.last_seen(s))
+            .map_or(StreamHealth::Healthy, |s| StreamHealth::Degraded {
+                symbol: s.clone(),
+                last_seen: self.last_seen(s),
+            })
     }
 }
 
@@ -113,11 +204,70 @@ struct ChainlinkPricePayload {
     value: f64,
 }
 
+/// Reconnect policy for [`PolymarketRtdsClient::connect_with_retry_config`].
+///
+/// Delays grow exponentially from `base_delay` up to `max_delay`, then each one
+/// is shortened by a random fraction of up to `jitter` so bot instances that
+/// lost the connection together don't all reconnect in the same instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryConfig {
+    /// Connection attempts before giving up (at least 1)
+    pub max_attempts: u32,
+    /// Delay after the first failed attempt
+    pub base_delay: Duration,
+    /// Cap on the un-jittered delay
+    pub max_delay: Duration,
+    /// Fraction of each delay that may be randomized away (0.0 = fixed, 1.0 = full jitter)
+    pub jitter: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Un-jittered delay after failed attempt `attempt` (1-based).
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Delay after attempt `attempt` for a random `roll` in [0, 1).
+    pub fn jittered_delay(&self, attempt: u32, roll: f64) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        self.backoff_delay(attempt)
+            .mul_f64(1.0 - jitter * roll.clamp(0.0, 1.0))
+    }
+
+    /// Delay after attempt `attempt` with a freshly drawn jitter.
+    pub fn next_delay(&self, attempt: u32) -> Duration {
+        self.jittered_delay(attempt, jitter_roll())
+    }
+}
+
+/// Random value in [0, 1) for reconnect jitter.
+///
+/// `RandomState` is seeded per process, which is all that's needed to keep
+/// separate bot instances from sleeping in lockstep.
+fn jitter_roll() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(since_epoch) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(since_epoch.as_nanos());
+    }
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
 /// Polymarket RTDS WebSocket client.
 pub struct PolymarketRtdsClient {
     symbols: Vec<String>,
-    reconnect_delay: Duration,
-    max_reconnect_delay: Duration,
+    retry: RetryConfig,
 }
 
 impl PolymarketRtdsClient {
@@ -128,14 +278,25 @@ impl PolymarketRtdsClient {
     pub fn new(symbols: Vec<String>) -> Self {
         Self {
             symbols,
-            reconnect_delay: Duration::from_secs(1),
-            max_reconnect_delay: Duration::from_secs(30),
+            retry: RetryConfig::default(),
         }
     }
 
-    /// Connect to RTDS WebSocket with retry.
+    /// Connect to RTDS WebSocket with retry, using the default backoff.
     pub async fn connect_with_retry(&self, max_retries: u32) -> anyhow::Result<RtdsStream> {
-        let mut delay = self.reconnect_delay;
+        self.connect_with_retry_config(RetryConfig {
+            max_attempts: max_retries,
+            ..self.retry
+        })
+        .await
+    }
+
+    /// Connect to RTDS WebSocket, retrying with jittered exponential backoff.
+    pub async fn connect_with_retry_config(
+        &self,
+        config: RetryConfig,
+    ) -> anyhow::Result<RtdsStream> {
+        let max_attempts = config.max_attempts.max(1);
         let mut attempts = 0;
 
         loop {
@@ -143,20 +304,20 @@ impl PolymarketRtdsClient {
                 Ok(stream) => return Ok(stream),
                 Err(e) => {
                     attempts += 1;
-                    if attempts >= max_retries {
+                    if attempts >= max_attempts {
                         return Err(anyhow::anyhow!(
                             "Failed to connect to RTDS after {} attempts: {}",
-                            max_retries,
+                            max_attempts,
                             e
                         ));
                     }
 
+                    let delay = config.next_delay(attempts);
                     warn!(
                         "RTDS connection attempt {} failed: {}. Retrying in {:?}...",
                         attempts, e, delay
                     );
                     tokio::time::sleep(delay).await;
-                    delay = std::cmp::min(delay * 2, self.max_reconnect_delay);
                 }
             }
         }
@@ -195,6 +356,7 @@ impl PolymarketRtdsClient {
             ws_stream: read,
             _write: write,
             symbols: self.symbols.clone(),
+            rate_monitor: MessageRateMonitor::new(self.symbols.clone(), Utc::now()),
         })
     }
 
@@ -219,6 +381,7 @@ pub struct RtdsStream {
         Message,
     >,
     symbols: Vec<String>,
+    rate_monitor: MessageRateMonitor,
 }
 
 impl RtdsStream {
@@ -234,6 +397,7 @@ impl RtdsStream {
             match msg_result {
                 Ok(Some(Ok(Message::Text(text)))) => {
                     if let Some(price) = self.parse_message(&text) {
+                        self.rate_monitor.record(&price.symbol, Utc::now());
                         return Some(price);
                     }
                     // Non-price message, continue
@@ -324,6 +488,11 @@ impl RtdsStream {
     pub fn symbols(&self) -> &[String] {
         &self.symbols
     }
+
+    /// Current stream health based on per-symbol message arrival times.
+    pub fn health_status(&self) -> StreamHealth {
+        self.rate_monitor.health_at(Utc::now())
+    }
 }
 
 /// Buffer for storing Chainlink prices and synthesizing open prices.
@@ -335,6 +504,8 @@ impl RtdsStream {
 pub struct ChainlinkPriceBuffer {
     /// Current latest price per symbol
     latest_prices: HashMap<String, Decimal>,
+    /// Chainlink timestamp of the latest price per symbol
+    latest_timestamps: HashMap<String, DateTime<Utc>>,
     /// Captured open prices keyed by (symbol, market_start_rounded_to_minute)
     /// The key uses minute precision to handle slight timing differences
     open_prices: HashMap<(String, i64), Decimal>,
@@ -359,6 +530,7 @@ impl ChainlinkPriceBuffer {
     pub fn new(max_history: usize) -> Self {
         Self {
             latest_prices: HashMap::new(),
+            latest_timestamps: HashMap::new(),
             open_prices: HashMap::new(),
             history: HashMap::new(),
             max_history,
@@ -369,6 +541,8 @@ impl ChainlinkPriceBuffer {
     pub fn update(&mut self, price: &ChainlinkPrice) {
         // Update latest price
         self.latest_prices.insert(price.symbol.clone(), price.value);
+        self.latest_timestamps
+            .insert(price.symbol.clone(), price.timestamp);
 
         // Add to history
         let history = self
@@ -392,6 +566,63 @@ impl ChainlinkPriceBuffer {
         self.latest_prices.get(symbol).copied()
     }
 
+    /// Get the latest price for a symbol with its Chainlink timestamp.
+    pub fn get_latest_with_time(&self, symbol: &str) -> Option<(Decimal, DateTime<Utc>)> {
+        let price = self.latest_prices.get(symbol)?;
+        let at = self.latest_timestamps.get(symbol)?;
+        Some((*price, *at))
+    }
+
+    /// Exponential moving average of a symbol's buffered prices, oldest first:
+    /// `ema = alpha * price + (1 - alpha) * ema`, seeded with the oldest price.
+    ///
+    /// Damps single-tick jitter around the open. `alpha` of 1 (or more) is the
+    /// raw latest price; smaller values smooth harder. Returns None if the
+    /// symbol has no price.
+    pub fn ema(&self, symbol: &str, alpha: Decimal) -> Option<Decimal> {
+        if alpha >= Decimal::ONE {
+            return self.get_latest(symbol);
+        }
+        let mut prices = self.history.get(symbol)?.iter().map(|p| p.value);
+        let seed = prices.next()?;
+        Some(prices.fold(seed, |ema, price| {
+            alpha * price + (Decimal::ONE - alpha) * ema
+        }))
+    }
+
+    /// How old a symbol's latest price is, by its Chainlink timestamp.
+    ///
+    /// RTDS can stall for one symbol while others keep updating, so a price
+    /// that is present may still be frozen. Returns None if the symbol has
+    /// never had a price.
+    pub fn age(&self, symbol: &str) -> Option<Duration> {
+        self.age_at(symbol, Utc::now())
+    }
+
+    /// [`age`](Self::age) measured at `now`. Timestamps ahead of `now` count
+    /// as zero age.
+    pub fn age_at(&self, symbol: &str, now: DateTime<Utc>) -> Option<Duration> {
+        let timestamp = self.latest_timestamps.get(symbol)?;
+        Some((now - *timestamp).to_std().unwrap_or_default())
+    }
+
+    /// Symbols whose latest price is older than `max_age`, sorted.
+    pub fn stale_symbols(&self, max_age: Duration) -> Vec<String> {
+        self.stale_symbols_at(max_age, Utc::now())
+    }
+
+    /// [`stale_symbols`](Self::stale_symbols) measured at `now`.
+    pub fn stale_symbols_at(&self, max_age: Duration, now: DateTime<Utc>) -> Vec<String> {
+        let mut stale: Vec<String> = self
+            .latest_timestamps
+            .keys()
+            .filter(|symbol| self.age_at(symbol, now).is_some_and(|age| age > max_age))
+            .cloned()
+            .collect();
+        stale.sort();
+        stale
+    }
+
     /// Get or capture the open price for a market start time.
     ///
     /// If we don't have a captured open price for this (symbol, start_time),
@@ -426,6 +657,40 @@ impl ChainlinkPriceBuffer {
         None
     }
 
+    /// Get or capture the open price from history: the earliest buffered price
+    /// at or after `start_time`, as long as it arrived within `tolerance` of it.
+    ///
+    /// Unlike [`get_or_capture_open`](Self::get_or_capture_open), a market
+    /// discovered partway through still gets its real open while the history
+    /// reaches back to the start. Returns None, capturing nothing, when no
+    /// buffered price is close enough to the start.
+    pub fn get_or_capture_open_near(
+        &mut self,
+        symbol: &str,
+        start_time: DateTime<Utc>,
+        tolerance: Duration,
+    ) -> Option<Decimal> {
+        let minute_key = start_time.timestamp() / 60;
+        let key = (symbol.to_string(), minute_key);
+
+        if let Some(&open) = self.open_prices.get(&key) {
+            return Some(open);
+        }
+
+        let first = self
+            .history
+            .get(symbol)?
+            .iter()
+            .filter(|p| p.timestamp >= start_time)
+            .min_by_key(|p| p.timestamp)?;
+        if (first.timestamp - start_time).to_std().unwrap_or_default() > tolerance {
+            return None;
+        }
+
+        self.open_prices.insert(key, first.value);
+        Some(first.value)
+    }
+
     /// Check if we have a captured open price for a market.
     pub fn has_open(&self, symbol: &str, start_time: DateTime<Utc>) -> bool {
         let minute_key = start_time.timestamp() / 60;
@@ -493,6 +758,36 @@ mod tests {
         assert_eq!(chainlink_symbol_to_asset("unknown"), None);
     }
 
+    #[test]
+    fn test_retry_backoff_schedule() {
+        let config = RetryConfig {
+            max_attempts: 8,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+        };
+
+        // Doubles from the base, then holds at the cap
+        let schedule: Vec<u64> = (1..=6).map(|n| config.backoff_delay(n).as_secs()).collect();
+        assert_eq!(schedule, vec![1, 2, 4, 8, 10, 10]);
+        assert_eq!(config.backoff_delay(64), Duration::from_secs(10));
+
+        // Jitter only ever shortens the delay, by at most the jitter fraction
+        assert_eq!(config.jittered_delay(3, 0.0), Duration::from_secs(4));
+        assert_eq!(config.jittered_delay(3, 0.5), Duration::from_secs(3));
+        assert_eq!(config.jittered_delay(5, 1.0), Duration::from_secs(5));
+        for _ in 0..100 {
+            let delay = config.next_delay(4);
+            assert!(delay > Duration::from_secs(4) && delay <= Duration::from_secs(8));
+        }
+
+        let fixed = RetryConfig {
+            jitter: 0.0,
+            ..config
+        };
+        assert_eq!(fixed.next_delay(2), Duration::from_secs(2));
+    }
+
     #[test]
     fn test_price_buffer_basics() {
         let mut buffer = ChainlinkPriceBuffer::new(100);
@@ -509,6 +804,84 @@ mod tests {
         assert_eq!(buffer.get_latest("eth/usd"), None);
     }
 
+    #[test]
+    fn test_ema_converges_on_known_series() {
+        let mut buffer = ChainlinkPriceBuffer::new(100);
+        let start = Utc::now();
+        let push = |buffer: &mut ChainlinkPriceBuffer, value, secs| {
+            buffer.update(&ChainlinkPrice {
+                symbol: "btc/usd".to_string(),
+                value,
+                timestamp: start + chrono::Duration::seconds(secs),
+            })
+        };
+        push(&mut buffer, dec!(100), 0);
+        push(&mut buffer, dec!(110), 1);
+        push(&mut buffer, dec!(120), 2);
+        // 100 -> 105 -> 112.5
+        assert_eq!(buffer.ema("btc/usd", dec!(0.5)), Some(dec!(112.5)));
+        assert_eq!(buffer.ema("btc/usd", Decimal::ONE), Some(dec!(120)));
+        assert_eq!(buffer.ema("eth/usd", dec!(0.5)), None);
+
+        // A one-tick spike barely moves a heavily smoothed price
+        push(&mut buffer, dec!(200), 3);
+        assert_eq!(buffer.ema("btc/usd", dec!(0.1)), Some(dec!(112.61)));
+
+        // A sustained level pulls the average in: the gap halves every tick
+        for secs in 4..24 {
+            push(&mut buffer, dec!(200), secs);
+        }
+        let ema = buffer.ema("btc/usd", dec!(0.5)).unwrap();
+        assert!(ema < dec!(200) && dec!(200) - ema < dec!(0.0001), "{}", ema);
+    }
+
+    #[test]
+    fn test_price_age_and_staleness() {
+        let mut buffer = ChainlinkPriceBuffer::new(100);
+        let now = Utc::now();
+        for (symbol, secs_ago) in [("btc/usd", 2), ("eth/usd", 45), ("sol/usd", 10)] {
+            buffer.update(&ChainlinkPrice {
+                symbol: symbol.to_string(),
+                value: dec!(100),
+                timestamp: now - chrono::Duration::seconds(secs_ago),
+            });
+        }
+
+        assert_eq!(buffer.age_at("btc/usd", now), Some(Duration::from_secs(2)));
+        assert_eq!(buffer.age_at("eth/usd", now), Some(Duration::from_secs(45)));
+        assert_eq!(buffer.age_at("xrp/usd", now), None);
+
+        assert_eq!(
+            buffer.stale_symbols_at(Duration::from_secs(5), now),
+            vec!["eth/usd".to_string(), "sol/usd".to_string()]
+        );
+        // Exactly at the threshold is not stale
+        assert_eq!(
+            buffer.stale_symbols_at(Duration::from_secs(10), now),
+            vec!["eth/usd".to_string()]
+        );
+
+        // A fresh update resets the age
+        buffer.update(&ChainlinkPrice {
+            symbol: "eth/usd".to_string(),
+            value: dec!(101),
+            timestamp: now,
+        });
+        assert_eq!(buffer.age_at("eth/usd", now), Some(Duration::ZERO));
+        assert!(buffer
+            .stale_symbols_at(Duration::from_secs(10), now)
+            .is_empty());
+
+        // Timestamps slightly ahead of the local clock count as fresh
+        let later = now + chrono::Duration::seconds(3);
+        buffer.update(&ChainlinkPrice {
+            symbol: "btc/usd".to_string(),
+            value: dec!(100),
+            timestamp: later,
+        });
+        assert_eq!(buffer.age_at("btc/usd", now), Some(Duration::ZERO));
+    }
+
     #[test]
     fn test_open_price_capture() {
         let mut buffer = ChainlinkPriceBuffer::new(100);
@@ -542,6 +915,82 @@ mod tests {
         assert_eq!(buffer.get_latest("btc/usd"), Some(dec!(88000)));
     }
 
+    fn monitor_with_arrivals(every_ms: i64, secs: i64) -> (MessageRateMonitor, DateTime<Utc>) {
+        let start = Utc::now();
+        let symbols = vec!["btc/usd".to_string(), "eth/usd".to_string()];
+        let mut monitor = MessageRateMonitor::new(symbols.clone(), start);
+        let mut at = start;
+        while at < start + chrono::Duration::seconds(secs) {
+            at += chrono::Duration::milliseconds(every_ms);
+            for symbol in &symbols {
+                monitor.record(symbol, at);
+            }
+        }
+        (monitor, at)
+    }
+
+    #[test]
+    fn test_health_healthy_at_expected_rate() {
+        let (monitor, now) = monitor_with_arrivals(1000, 200);
+        assert_eq!(monitor.health_at(now), StreamHealth::Healthy);
+        // The ring buffer has wrapped but still covers the full window
+        assert_eq!(monitor.arrivals["btc/usd"].len(), ARRIVAL_BUFFER_CAPACITY);
+        assert_eq!(monitor.messages_in_window("btc/usd", now), 60);
+    }
+
+    #[test]
+    fn test_health_degraded_when_symbol_goes_quiet() {
+        let (mut monitor, mut now) = monitor_with_arrivals(1000, 90);
+        let eth_last = now;
+        for _ in 0..15 {
+            now += chrono::Duration::seconds(1);
+            monitor.record("btc/usd", now);
+        }
+
+        assert_eq!(
+            monitor.health_at(now),
+            StreamHealth::Degraded {
+                symbol: "eth/usd".to_string(),
+                last_seen: Some(eth_last),
+            }
+        );
+    }
+
+    #[test]
+    fn test_health_degraded_on_low_rate() {
+        // One message every 2s is half the expected rate
+        let (monitor, now) = monitor_with_arrivals(2000, 90);
+        assert!(matches!(
+            monitor.health_at(now),
+            StreamHealth::Degraded { .. }
+        ));
+
+        // The rate check waits for a full window after connecting
+        let (monitor, now) = monitor_with_arrivals(2000, 20);
+        assert_eq!(monitor.health_at(now), StreamHealth::Healthy);
+    }
+
+    #[test]
+    fn test_health_stalled_when_all_symbols_silent() {
+        let (monitor, now) = monitor_with_arrivals(1000, 90);
+        assert_eq!(
+            monitor.health_at(now + chrono::Duration::seconds(STALLED_AFTER_SECS)),
+            StreamHealth::Stalled
+        );
+
+        // Never receiving anything also counts once the stall timeout passes
+        let start = Utc::now();
+        let monitor = MessageRateMonitor::new(vec!["btc/usd".to_string()], start);
+        assert!(matches!(
+            monitor.health_at(start + chrono::Duration::seconds(5)),
+            StreamHealth::Healthy
+        ));
+        assert_eq!(
+            monitor.health_at(start + chrono::Duration::seconds(STALLED_AFTER_SECS)),
+            StreamHealth::Stalled
+        );
+    }
+
     #[test]
     fn test_no_price_returns_none() {
         let mut buffer = ChainlinkPriceBuffer::new(100);
@@ -0,0 +1,206 @@
+//! Price source abstraction over the Binance and Chainlink feeds.
+//!
+//! Each source pairs a live stream with the buffer that accumulates its
+//! updates, so a trader can be written once against `PriceSource` and pick
+//! the feed at startup.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+
+use crate::binance_ws::{BinanceEvent, BinanceWsStream, KlineBuffer};
+use crate::polymarket_rtds::{ChainlinkPrice, ChainlinkPriceBuffer, RtdsStream, TimestampedPrice};
+
+/// A live feed of underlying asset prices.
+#[async_trait]
+pub trait PriceSource: Send {
+    /// Wait for the next price update and fold it into the source's buffer.
+    /// Returns None when the underlying stream has closed.
+    async fn next_price(&mut self) -> Option<TimestampedPrice>;
+
+    /// Latest known price for `symbol` (in the source's own symbol format).
+    fn latest(&self, symbol: &str) -> Option<Decimal>;
+}
+
+/// Binance bookTicker/kline stream feeding a `KlineBuffer`.
+pub struct BinancePriceSource {
+    stream: BinanceWsStream,
+    buffer: KlineBuffer,
+}
+
+impl BinancePriceSource {
+    pub fn new(stream: BinanceWsStream, buffer: KlineBuffer) -> Self {
+        Self { stream, buffer }
+    }
+
+    /// Kline history backing this source (open prices, momentum).
+    pub fn buffer(&self) -> &KlineBuffer {
+        &self.buffer
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut KlineBuffer {
+        &mut self.buffer
+    }
+}
+
+/// Record a Binance event in the buffer, returning the price it carried.
+fn apply_binance_event(buffer: &mut KlineBuffer, event: BinanceEvent) -> TimestampedPrice {
+    match event {
+        BinanceEvent::Ticker(ticker) => {
+            buffer.update_price(&ticker);
+            TimestampedPrice {
+                value: ticker.mid_price(),
+                timestamp: ticker.timestamp,
+            }
+        }
+        BinanceEvent::Kline(kline) => {
+            // In-progress klines report a close_time in the future
+            let price = TimestampedPrice {
+                value: kline.close,
+                timestamp: kline.close_time.min(Utc::now()),
+            };
+            buffer.add(kline);
+            price
+        }
+        BinanceEvent::Trade(trade) => {
+            buffer.add_trade(&trade);
+            TimestampedPrice {
+                value: trade.price,
+                timestamp: trade.trade_time,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for BinancePriceSource {
+    async fn next_price(&mut self) -> Option<TimestampedPrice> {
+        let event = self.stream.next_event().await?;
+        Some(apply_binance_event(&mut self.buffer, event))
+    }
+
+    fn latest(&self, symbol: &str) -> Option<Decimal> {
+        self.buffer.current_price(symbol)
+    }
+}
+
+/// Polymarket RTDS Chainlink stream feeding a `ChainlinkPriceBuffer`.
+pub struct ChainlinkPriceSource {
+    stream: RtdsStream,
+    buffer: ChainlinkPriceBuffer,
+}
+
+impl ChainlinkPriceSource {
+    pub fn new(stream: RtdsStream, buffer: ChainlinkPriceBuffer) -> Self {
+        Self { stream, buffer }
+    }
+
+    /// Price buffer backing this source (captured open prices).
+    pub fn buffer(&self) -> &ChainlinkPriceBuffer {
+        &self.buffer
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut ChainlinkPriceBuffer {
+        &mut self.buffer
+    }
+}
+
+/// Record a Chainlink price in the buffer, returning it timestamped.
+fn apply_chainlink_price(
+    buffer: &mut ChainlinkPriceBuffer,
+    price: &ChainlinkPrice,
+) -> TimestampedPrice {
+    buffer.update(price);
+    TimestampedPrice {
+        value: price.value,
+        timestamp: price.timestamp,
+    }
+}
+
+#[async_trait]
+impl PriceSource for ChainlinkPriceSource {
+    async fn next_price(&mut self) -> Option<TimestampedPrice> {
+        let price = self.stream.next_price().await?;
+        Some(apply_chainlink_price(&mut self.buffer, &price))
+    }
+
+    fn latest(&self, symbol: &str) -> Option<Decimal> {
+        self.buffer.get_latest(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binance_ws::{BinanceBookTicker, BinanceKline};
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn assert_price_source<T: PriceSource>() {}
+
+    #[test]
+    fn test_both_sources_implement_price_source() {
+        assert_price_source::<BinancePriceSource>();
+        assert_price_source::<ChainlinkPriceSource>();
+
+        // Usable behind a trait object for runtime selection
+        fn _boxed(source: BinancePriceSource) -> Box<dyn PriceSource> {
+            Box::new(source)
+        }
+    }
+
+    #[test]
+    fn test_apply_binance_event() {
+        let mut buffer = KlineBuffer::new(10);
+        let now = Utc::now();
+
+        let ticker = BinanceBookTicker {
+            symbol: "BTCUSDT".to_string(),
+            best_bid: dec!(99990),
+            best_bid_qty: dec!(1),
+            best_ask: dec!(100010),
+            best_ask_qty: dec!(1),
+            timestamp: now,
+        };
+        let price = apply_binance_event(&mut buffer, BinanceEvent::Ticker(ticker));
+        assert_eq!(price.value, dec!(100000));
+        assert_eq!(price.timestamp, now);
+        assert_eq!(buffer.current_price("BTCUSDT"), Some(dec!(100000)));
+
+        let open_time = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let kline = BinanceKline {
+            symbol: "ETHUSDT".to_string(),
+            open_time,
+            close_time: open_time + Duration::seconds(59),
+            open: dec!(3000),
+            high: dec!(3010),
+            low: dec!(2995),
+            close: dec!(3005),
+            volume: dec!(12),
+            is_closed: true,
+            timestamp: open_time + Duration::seconds(59),
+        };
+        let price = apply_binance_event(&mut buffer, BinanceEvent::Kline(kline));
+        assert_eq!(price.value, dec!(3005));
+        assert_eq!(price.timestamp, open_time + Duration::seconds(59));
+        assert_eq!(buffer.current_price("ETHUSDT"), Some(dec!(3005)));
+        assert_eq!(buffer.len("ETHUSDT"), 1);
+    }
+
+    #[test]
+    fn test_apply_chainlink_price() {
+        let mut buffer = ChainlinkPriceBuffer::new(10);
+        let timestamp = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let update = ChainlinkPrice {
+            symbol: "btc/usd".to_string(),
+            value: dec!(99880.5),
+            timestamp,
+        };
+
+        let price = apply_chainlink_price(&mut buffer, &update);
+        assert_eq!(price.value, dec!(99880.5));
+        assert_eq!(price.timestamp, timestamp);
+        assert_eq!(buffer.get_latest("btc/usd"), Some(dec!(99880.5)));
+        assert_eq!(buffer.get_history("btc/usd").map(|h| h.len()), Some(1));
+    }
+}
@@ -0,0 +1,204 @@
+//! Token-bucket rate limiting for external HTTP APIs.
+//!
+//! Clients call [`RateLimiter::acquire`] before each request. Limiters are
+//! shared per host through [`for_url`], so every client and code path hitting
+//! the same API draws from one budget instead of throttling independently.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Token bucket: `capacity` permits refilled at `rate` per second.
+///
+/// `tokens` goes negative while callers are queued; each caller's wait is
+/// fixed when it reserves its permit.
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Async token-bucket rate limiter.
+#[derive(Debug)]
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_second` on average, in bursts of up to `burst`
+    /// requests (at least 1).
+    ///
+    /// # Panics
+    /// If `requests_per_second` is not positive.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second > 0.0,
+            "rate limit must be positive (got {})",
+            requests_per_second
+        );
+        let capacity = burst.max(1) as f64;
+        Self {
+            bucket: Mutex::new(Bucket {
+                rate: requests_per_second,
+                capacity,
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Change the rate and burst, keeping any permits already reserved.
+    pub fn set_rate(&self, requests_per_second: f64, burst: u32) {
+        assert!(
+            requests_per_second > 0.0,
+            "rate limit must be positive (got {})",
+            requests_per_second
+        );
+        let mut bucket = self.lock();
+        bucket.rate = requests_per_second;
+        bucket.capacity = burst.max(1) as f64;
+        bucket.tokens = bucket.tokens.min(bucket.capacity);
+    }
+
+    /// Take a permit and return how long to wait before using it.
+    fn reserve(&self) -> Duration {
+        let mut bucket = self.lock();
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.last_refill).as_secs_f64() * bucket.rate;
+        bucket.tokens = (bucket.tokens + refill).min(bucket.capacity);
+        bucket.last_refill = now;
+
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / bucket.rate)
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        self.bucket.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Limiters keyed by host.
+fn registry() -> &'static Mutex<HashMap<String, Arc<RateLimiter>>> {
+    static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+    LIMITERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Host part of `url`, or the whole string if it does not parse.
+fn host_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_lowercase))
+        .unwrap_or_else(|| url.to_lowercase())
+}
+
+/// The limiter shared by every client of `url`'s host.
+///
+/// The first caller for a host sets its rate to `default_rate` requests per
+/// second (no bursting) unless [`configure_host`] already did.
+pub fn for_url(url: &str, default_rate: f64) -> Arc<RateLimiter> {
+    let mut limiters = registry().lock().unwrap_or_else(|e| e.into_inner());
+    limiters
+        .entry(host_key(url))
+        .or_insert_with(|| Arc::new(RateLimiter::new(default_rate, 1)))
+        .clone()
+}
+
+/// Set the rate for `host`, overriding client defaults. Clients already
+/// holding the host's limiter pick up the change.
+pub fn configure_host(host: &str, requests_per_second: f64, burst: u32) {
+    let key = host_key(host);
+    let mut limiters = registry().lock().unwrap_or_else(|e| e.into_inner());
+    match limiters.get(&key) {
+        Some(limiter) => limiter.set_rate(requests_per_second, burst),
+        None => {
+            limiters.insert(key, Arc::new(RateLimiter::new(requests_per_second, burst)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limiter_enforces_rate() {
+        // 20/s with a burst of 2: the first two pass at once, the next eight
+        // are spaced 50ms apart
+        let limiter = RateLimiter::new(20.0, 2);
+        let start = std::time::Instant::now();
+        for _ in 0..10 {
+            limiter.acquire().await;
+        }
+        let elapsed = start.elapsed();
+
+        assert!(elapsed >= Duration::from_millis(390), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "{:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_limiter_allows_burst_then_refills() {
+        let limiter = RateLimiter::new(10.0, 3);
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // Idle time refills the bucket up to its capacity
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_callers_share_budget() {
+        let limiter = Arc::new(RateLimiter::new(20.0, 1));
+        let start = std::time::Instant::now();
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire().await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // One immediate permit, then four more at 50ms intervals
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[test]
+    fn test_for_url_shares_limiter_per_host() {
+        let a = for_url("https://rate-limit-test.example/events?x=1", 5.0);
+        let b = for_url("https://RATE-LIMIT-TEST.example/markets", 50.0);
+        let other = for_url("https://other-rate-limit-test.example", 5.0);
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &other));
+
+        // Configuring the host updates the limiter clients already hold
+        configure_host("rate-limit-test.example", 100.0, 4);
+        assert_eq!(a.lock().capacity, 4.0);
+        assert_eq!(a.lock().rate, 100.0);
+    }
+}
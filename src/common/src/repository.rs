@@ -1,12 +1,14 @@
 //! Database repository functions for markets and orderbooks.
 
 use chrono::{DateTime, Utc};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use sqlx::PgPool;
+use thiserror::Error;
 use uuid::Uuid;
 
 use crate::gamma::{MarketType, ParsedMarket};
-use crate::models::Market;
+use crate::models::{Market, TradeIntent, TradeOutcome};
+use crate::platform::Platform;
 
 /// Market with fresh orderbook prices (result of LATERAL JOIN query).
 #[derive(Debug, Clone)]
@@ -50,9 +52,219 @@ pub struct MarketWithOrderbook {
     // Full orderbook depth (JSON arrays of {price, size})
     pub yes_asks: Option<serde_json::Value>,
     pub no_asks: Option<serde_json::Value>,
+    pub yes_bids: Option<serde_json::Value>,
+    pub no_bids: Option<serde_json::Value>,
     pub captured_at: DateTime<Utc>,
 }
 
+impl MarketWithOrderbook {
+    /// The lower of the YES and NO best asks.
+    pub fn cheapest_ask(&self) -> Option<Decimal> {
+        match (self.yes_best_ask, self.no_best_ask) {
+            (Some(yes), Some(no)) => Some(yes.min(no)),
+            (yes, no) => yes.or(no),
+        }
+    }
+
+    /// Best ask for `side` ("YES" or "NO").
+    pub fn best_ask(&self, side: &str) -> Option<Decimal> {
+        match side.to_uppercase().as_str() {
+            "YES" => self.yes_best_ask,
+            "NO" => self.no_best_ask,
+            _ => None,
+        }
+    }
+
+    /// Cost of buying one YES and one NO share at the best asks.
+    ///
+    /// One side always pays $1, so above 1.0 the book is overpriced: holding
+    /// both is a guaranteed loss. None if either ask is missing.
+    pub fn combined_ask_cost(&self) -> Option<Decimal> {
+        Some(self.yes_best_ask? + self.no_best_ask?)
+    }
+
+    /// Bid depth over ask depth for `side` ("YES" or "NO"), summed across all levels.
+    ///
+    /// Above 1 means more resting buyers than sellers. Returns None when either
+    /// depth array is missing or unparseable, or both sides are empty; an empty
+    /// ask side against resting bids yields `Decimal::MAX`.
+    pub fn imbalance_ratio(&self, side: &str) -> Option<Decimal> {
+        let (bids, asks) = match side.to_uppercase().as_str() {
+            "YES" => (&self.yes_bids, &self.yes_asks),
+            "NO" => (&self.no_bids, &self.no_asks),
+            _ => return None,
+        };
+        let bid_depth = total_depth(bids.as_ref()?)?;
+        let ask_depth = total_depth(asks.as_ref()?)?;
+
+        if ask_depth > Decimal::ZERO {
+            Some(bid_depth / ask_depth)
+        } else if bid_depth > Decimal::ZERO {
+            Some(Decimal::MAX)
+        } else {
+            None
+        }
+    }
+
+    /// USDC resting on the ask side of `side` ("YES" or "NO"): the sum of
+    /// price * size across all levels, i.e. what it would cost to sweep the book.
+    ///
+    /// Returns None when the depth array is missing or unparseable.
+    pub fn ask_liquidity_usdc(&self, side: &str) -> Option<Decimal> {
+        let asks = match side.to_uppercase().as_str() {
+            "YES" => &self.yes_asks,
+            "NO" => &self.no_asks,
+            _ => return None,
+        };
+        let levels = depth_levels(asks.as_ref()?)?;
+        Some(
+            levels
+                .iter()
+                .map(|l| l.price.max(Decimal::ZERO) * l.size.max(Decimal::ZERO))
+                .sum(),
+        )
+    }
+
+    /// Check every depth array present on the snapshot with `validate_depth_json`.
+    pub fn validate_depth(&self) -> Result<(), DepthError> {
+        [&self.yes_asks, &self.no_asks, &self.yes_bids, &self.no_bids]
+            .into_iter()
+            .flatten()
+            .try_for_each(validate_depth_json)
+    }
+}
+
+/// Why a JSON depth array was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DepthError {
+    #[error("depth is not an array")]
+    NotArray,
+
+    #[error("level {0} is not an object")]
+    NotObject(usize),
+
+    #[error("level {index} has a missing or non-numeric {field}")]
+    Unparseable { index: usize, field: &'static str },
+
+    #[error("level {index} price {price} is outside (0, 1]")]
+    PriceOutOfRange { index: usize, price: Decimal },
+
+    #[error("level {index} size {size} is not positive")]
+    NonPositiveSize { index: usize, size: Decimal },
+}
+
+/// Strictly check a JSON depth array before trading on it.
+///
+/// The parsing helpers above tolerate bad levels (defaulting or skipping them),
+/// which can make a corrupt snapshot read as a thin book. This requires an
+/// array of objects whose `price` (string or number) lies in (0, 1] and whose
+/// `size` is positive. An empty array is valid.
+pub fn validate_depth_json(value: &serde_json::Value) -> Result<(), DepthError> {
+    let levels = value.as_array().ok_or(DepthError::NotArray)?;
+    for (index, level) in levels.iter().enumerate() {
+        let level = level.as_object().ok_or(DepthError::NotObject(index))?;
+        let field = |field: &'static str| {
+            let parsed = match level.get(field) {
+                Some(serde_json::Value::String(s)) => s.trim().parse::<Decimal>().ok(),
+                Some(serde_json::Value::Number(n)) => n.to_string().parse::<Decimal>().ok(),
+                _ => None,
+            };
+            parsed.ok_or(DepthError::Unparseable { index, field })
+        };
+
+        let price = field("price")?;
+        if price <= Decimal::ZERO || price > Decimal::ONE {
+            return Err(DepthError::PriceOutOfRange { index, price });
+        }
+        let size = field("size")?;
+        if size <= Decimal::ZERO {
+            return Err(DepthError::NonPositiveSize { index, size });
+        }
+    }
+    Ok(())
+}
+
+/// Drop markets whose depth snapshot fails `validate_depth`.
+///
+/// Returns the name and reason for each market removed, for the caller to log
+/// and count.
+pub fn retain_valid_depth(markets: &mut Vec<MarketWithOrderbook>) -> Vec<(String, DepthError)> {
+    let mut rejected = Vec::new();
+    markets.retain(|m| match m.validate_depth() {
+        Ok(()) => true,
+        Err(e) => {
+            rejected.push((m.name.clone(), e));
+            false
+        }
+    });
+    rejected
+}
+
+/// Parse a JSON depth array of {price, size} levels.
+pub fn depth_levels(levels: &serde_json::Value) -> Option<Vec<OrderbookLevel>> {
+    serde_json::from_value(levels.clone()).ok()
+}
+
+/// Sum of sizes across a JSON depth array of {price, size} levels.
+fn total_depth(levels: &serde_json::Value) -> Option<Decimal> {
+    let levels = depth_levels(levels)?;
+    Some(levels.iter().map(|l| l.size.max(Decimal::ZERO)).sum())
+}
+
+/// Rank markets for processing and keep at most `max` of them.
+///
+/// Markets closest to expiry come first (their window closes soonest), then
+/// the cheapest ask. Returns how many markets were deferred.
+pub fn prioritize_markets(markets: &mut Vec<MarketWithOrderbook>, max: Option<usize>) -> usize {
+    markets.sort_by(|a, b| {
+        a.end_time.cmp(&b.end_time).then_with(|| {
+            let ask = |m: &MarketWithOrderbook| m.cheapest_ask().unwrap_or(Decimal::MAX);
+            ask(a).cmp(&ask(b))
+        })
+    });
+
+    match max {
+        Some(max) if markets.len() > max => {
+            let deferred = markets.len() - max;
+            markets.truncate(max);
+            deferred
+        }
+        _ => 0,
+    }
+}
+
+/// Rotates which markets a per-cycle cap defers, so no market is starved.
+///
+/// A fixed ranking would defer the same markets every cycle and they would
+/// never be seen. Half of the cap (rounded down) always goes to the most
+/// urgent markets; the remaining slots walk through the rest of the ranking,
+/// picking up where the previous cycle stopped.
+#[derive(Debug, Clone, Default)]
+pub struct MarketRotation {
+    cursor: usize,
+}
+
+impl MarketRotation {
+    /// Rank `markets` (see [`prioritize_markets`]) and keep at most `max` of
+    /// them. Returns how many markets were deferred this cycle.
+    pub fn select(&mut self, markets: &mut Vec<MarketWithOrderbook>, max: Option<usize>) -> usize {
+        prioritize_markets(markets, None);
+        let max = match max {
+            Some(max) if markets.len() > max => max,
+            _ => return 0,
+        };
+
+        let deferred = markets.len() - max;
+        let mut rest = markets.split_off(max / 2);
+        let rotating = max - markets.len();
+        let start = self.cursor % rest.len();
+        rest.rotate_left(start);
+        markets.extend(rest.into_iter().take(rotating));
+        self.cursor = start + rotating;
+        deferred
+    }
+}
+
 /// A price level in the orderbook.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OrderbookLevel {
@@ -60,11 +272,90 @@ pub struct OrderbookLevel {
     pub size: Decimal,
 }
 
+/// Merge depth levels whose prices round to the same multiple of `tick_size`.
+///
+/// Prices are rounded to a tick with `rounding` and sizes at the same rounded
+/// price summed. Pass `ToPositiveInfinity` for asks and `ToNegativeInfinity`
+/// for bids, so a merged level is never quoted better than the liquidity in
+/// it. Empty buckets are dropped and the result is sorted by price ascending.
+/// A non-positive `tick_size` returns the levels unchanged.
+pub fn aggregate_depth_to_ticks(
+    levels: &[OrderbookLevel],
+    tick_size: Decimal,
+    rounding: RoundingStrategy,
+) -> Vec<OrderbookLevel> {
+    if tick_size <= Decimal::ZERO {
+        return levels.to_vec();
+    }
+
+    let mut buckets: std::collections::BTreeMap<Decimal, Decimal> =
+        std::collections::BTreeMap::new();
+    for level in levels {
+        let ticks = (level.price / tick_size).round_dp_with_strategy(0, rounding);
+        *buckets.entry(ticks * tick_size).or_default() += level.size.max(Decimal::ZERO);
+    }
+
+    buckets
+        .into_iter()
+        .filter(|(_, size)| *size > Decimal::ZERO)
+        .map(|(price, size)| OrderbookLevel { price, size })
+        .collect()
+}
+
+/// Which price a depth walk reports as the planning price for an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillPriceModel {
+    /// Size-weighted average across every level consumed
+    #[default]
+    Vwap,
+    /// Price of the deepest level the order reaches
+    WorstTouched,
+    /// Best price only, ignoring the rest of the walk
+    BestOnly,
+}
+
+impl FillPriceModel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FillPriceModel::Vwap => "vwap",
+            FillPriceModel::WorstTouched => "worst-touched",
+            FillPriceModel::BestOnly => "best-only",
+        }
+    }
+}
+
+impl std::str::FromStr for FillPriceModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "vwap" => Ok(FillPriceModel::Vwap),
+            "worst-touched" => Ok(FillPriceModel::WorstTouched),
+            "best-only" => Ok(FillPriceModel::BestOnly),
+            other => Err(format!(
+                "Unknown fill model '{}' (expected vwap, worst-touched, best-only)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for FillPriceModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Result of fill price calculation.
 #[derive(Debug, Clone)]
 pub struct FillEstimate {
-    /// Weighted average fill price across all levels
+    /// Planning price for the fill under `model`
     pub effective_price: Decimal,
+    /// How `effective_price` was derived from the walk
+    pub model: FillPriceModel,
+    /// Size-weighted average over the walk (or the slippage fallback),
+    /// whatever `model` plans at; slippage is always measured on this
+    pub vwap_price: Decimal,
     /// Total shares that can be filled at this price
     pub filled_shares: Decimal,
     /// Whether the full order can be filled
@@ -73,21 +364,46 @@ pub struct FillEstimate {
     pub best_price_depth: Decimal,
 }
 
+impl FillEstimate {
+    /// Slippage of the depth-walked VWAP over `best_price`, in percent (e.g. 5 = 5%).
+    pub fn slippage_pct(&self, best_price: Decimal) -> Decimal {
+        if best_price <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        (self.vwap_price - best_price) / best_price * Decimal::from(100)
+    }
+
+    /// Whether slippage over `best_price` is strictly above `max_slippage_pct` (percent).
+    /// A breach means the book is too thin to trust, whatever the fill model plans at.
+    pub fn exceeds_slippage_cap(&self, best_price: Decimal, max_slippage_pct: Decimal) -> bool {
+        self.slippage_pct(best_price) > max_slippage_pct
+    }
+}
+
 /// Calculate effective fill price based on orderbook depth.
-/// Returns weighted average price for filling `shares` from the orderbook.
+/// Returns the `model` price (VWAP, worst level touched, or best level) for
+/// filling `shares` from the orderbook.
 /// Note: Sorts orderbook levels by price ascending (best price first).
 pub fn calculate_effective_fill_price(
     orderbook_json: Option<&serde_json::Value>,
     shares: Decimal,
+    model: FillPriceModel,
 ) -> Option<FillEstimate> {
-    let orderbook = orderbook_json?;
-
-    // Parse orderbook levels
-    let mut levels: Vec<OrderbookLevel> = serde_json::from_value(orderbook.clone()).ok()?;
+    let levels = depth_levels(orderbook_json?)?;
+    effective_fill_from_levels(&levels, shares, model)
+}
 
+/// [`calculate_effective_fill_price`] over already parsed levels, e.g. the
+/// output of [`aggregate_depth_to_ticks`].
+pub fn effective_fill_from_levels(
+    levels: &[OrderbookLevel],
+    shares: Decimal,
+    model: FillPriceModel,
+) -> Option<FillEstimate> {
     if levels.is_empty() {
         return None;
     }
+    let mut levels = levels.to_vec();
 
     // Sort by price ascending (best/lowest price first for asks)
     levels.sort_by(|a, b| a.price.cmp(&b.price));
@@ -97,6 +413,8 @@ pub fn calculate_effective_fill_price(
     let mut remaining = shares;
     let mut total_cost = Decimal::ZERO;
     let mut total_filled = Decimal::ZERO;
+    let mut best_touched = None;
+    let mut worst_touched = Decimal::ZERO;
 
     for level in &levels {
         if remaining <= Decimal::ZERO {
@@ -112,16 +430,25 @@ pub fn calculate_effective_fill_price(
         total_cost += fill_at_level * level.price;
         total_filled += fill_at_level;
         remaining -= fill_at_level;
+        best_touched.get_or_insert(level.price);
+        worst_touched = level.price;
     }
 
     if total_filled == Decimal::ZERO {
         return None;
     }
 
-    let effective_price = total_cost / total_filled;
+    let vwap_price = total_cost / total_filled;
+    let effective_price = match model {
+        FillPriceModel::Vwap => vwap_price,
+        FillPriceModel::WorstTouched => worst_touched,
+        FillPriceModel::BestOnly => best_touched.unwrap_or(worst_touched),
+    };
 
     Some(FillEstimate {
         effective_price,
+        model,
+        vwap_price,
         filled_shares: total_filled,
         fully_filled: remaining <= Decimal::ZERO,
         best_price_depth,
@@ -129,7 +456,8 @@ pub fn calculate_effective_fill_price(
 }
 
 /// Calculate fill price with slippage fallback.
-/// Uses orderbook depth when available, otherwise applies slippage percentage.
+/// Uses orderbook depth when available, otherwise applies slippage percentage
+/// (except under `FillPriceModel::BestOnly`, which plans at the best ask).
 /// Note: When using slippage fallback, `fully_filled` is false (unknown) and
 /// `best_price_depth` is zero (unknown). Callers should handle this appropriately.
 pub fn calculate_fill_price_with_slippage(
@@ -137,19 +465,38 @@ pub fn calculate_fill_price_with_slippage(
     best_ask: Decimal,
     shares: Decimal,
     slippage_pct: Decimal,
+    model: FillPriceModel,
+) -> FillEstimate {
+    let levels = orderbook_json.and_then(depth_levels);
+    calculate_fill_price_from_levels(levels.as_deref(), best_ask, shares, slippage_pct, model)
+}
+
+/// [`calculate_fill_price_with_slippage`] over already parsed levels, so
+/// callers can pre-process depth (see [`aggregate_depth_to_ticks`]).
+pub fn calculate_fill_price_from_levels(
+    levels: Option<&[OrderbookLevel]>,
+    best_ask: Decimal,
+    shares: Decimal,
+    slippage_pct: Decimal,
+    model: FillPriceModel,
 ) -> FillEstimate {
     // Try orderbook-based calculation first
-    if let Some(estimate) = calculate_effective_fill_price(orderbook_json, shares) {
+    if let Some(estimate) = levels.and_then(|l| effective_fill_from_levels(l, shares, model)) {
         return estimate;
     }
 
     // Fallback: apply slippage to best ask
     // We don't know actual depth, so be conservative with flags
-    let slippage_multiplier = Decimal::ONE + (slippage_pct / Decimal::from(100));
-    let effective_price = best_ask * slippage_multiplier;
+    let vwap_price = best_ask * (Decimal::ONE + (slippage_pct / Decimal::from(100)));
+    let effective_price = match model {
+        FillPriceModel::BestOnly => best_ask,
+        FillPriceModel::Vwap | FillPriceModel::WorstTouched => vwap_price,
+    };
 
     FillEstimate {
         effective_price,
+        model,
+        vwap_price,
         filled_shares: shares,
         fully_filled: false,             // Unknown - orderbook depth unavailable
         best_price_depth: Decimal::ZERO, // Unknown - no orderbook data
@@ -169,8 +516,8 @@ pub async fn upsert_market(pool: &PgPool, market: &ParsedMarket) -> Result<Uuid,
 
     let result = sqlx::query_scalar!(
         r#"
-        INSERT INTO markets (platform, condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
-        VALUES ('polymarket', $1, $2, $3, $4, $5, $6, $7, $8, true)
+        INSERT INTO markets (platform, condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, liquidity_dollars, is_active)
+        VALUES ('polymarket', $1, $2, $3, $4, $5, $6, $7, $8, $9, true)
         ON CONFLICT (platform, condition_id) DO UPDATE SET
             market_type = EXCLUDED.market_type,
             asset = EXCLUDED.asset,
@@ -179,6 +526,7 @@ pub async fn upsert_market(pool: &PgPool, market: &ParsedMarket) -> Result<Uuid,
             no_token_id = EXCLUDED.no_token_id,
             name = EXCLUDED.name,
             end_time = EXCLUDED.end_time,
+            liquidity_dollars = COALESCE(EXCLUDED.liquidity_dollars, markets.liquidity_dollars),
             is_active = true,
             updated_at = NOW()
         RETURNING id
@@ -191,6 +539,7 @@ pub async fn upsert_market(pool: &PgPool, market: &ParsedMarket) -> Result<Uuid,
         market.no_token_id,
         market.name,
         market.end_time,
+        market.liquidity,
     )
     .fetch_one(pool)
     .await?;
@@ -243,6 +592,26 @@ pub async fn get_active_markets(pool: &PgPool) -> Result<Vec<Market>, sqlx::Erro
     Ok(markets)
 }
 
+/// Distinct assets with active, unexpired markets, alphabetically.
+///
+/// Markets whose asset could not be identified at discovery are left out.
+pub async fn get_active_crypto_assets(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    let assets = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT asset
+        FROM markets
+        WHERE is_active = true
+          AND end_time > NOW()
+          AND asset <> 'UNKNOWN'
+        ORDER BY asset
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(assets)
+}
+
 /// Get active markets expiring within a given number of hours.
 /// This is optimized for orderbook streaming where we want to focus on
 /// near-term markets that are relevant for trading.
@@ -284,8 +653,14 @@ pub async fn get_active_markets_expiring_within(
 }
 
 /// Get priority markets using hybrid strategy:
-/// - Crypto markets (BTC, ETH, SOL, XRP) expiring within crypto_hours
-/// - Event markets (all other assets) expiring within event_days
+/// - Crypto markets (BTC, ETH, SOL, XRP) expiring within crypto_hours, soonest first
+/// - Polymarket event markets (all other assets) expiring within event_days,
+///   most liquid first (then soonest), so `event_limit` keeps the most
+///   tradeable ones
+///
+/// With `event_min_liquidity`, event markets below that liquidity (or with
+/// none recorded) are dropped. Crypto markets come first in the result, then
+/// event markets in priority order.
 ///
 /// This enables monitoring short-term crypto markets alongside longer-dated event markets.
 pub async fn get_priority_markets_hybrid(
@@ -294,6 +669,7 @@ pub async fn get_priority_markets_hybrid(
     event_days: i32,
     crypto_limit: i64,
     event_limit: i64,
+    event_min_liquidity: Option<Decimal>,
 ) -> Result<Vec<Market>, sqlx::Error> {
     // Fetch crypto and event markets separately, then combine
     // This avoids UNION ALL issues with sqlx type inference
@@ -345,22 +721,24 @@ pub async fn get_priority_markets_hybrid(
             COALESCE(updated_at, NOW()) as "updated_at!"
         FROM markets
         WHERE is_active = true
+          AND platform = 'polymarket'
           AND asset NOT IN ('BTC', 'ETH', 'SOL', 'XRP')
           AND end_time > NOW()
           AND end_time <= NOW() + ($1 || ' days')::interval
-        ORDER BY end_time ASC
+          AND ($3::numeric IS NULL OR liquidity_dollars >= $3)
+        ORDER BY liquidity_dollars DESC NULLS LAST, end_time ASC
         LIMIT $2
         "#,
         event_days.to_string(),
-        event_limit
+        event_limit,
+        event_min_liquidity
     )
     .fetch_all(pool)
     .await?;
 
-    // Combine and sort by end_time
+    // Keep each group's priority order so subscriptions follow it
     let mut markets = crypto_markets;
     markets.extend(event_markets);
-    markets.sort_by(|a, b| a.end_time.cmp(&b.end_time));
 
     Ok(markets)
 }
@@ -602,6 +980,45 @@ pub async fn get_latest_orderbook_snapshot(
     Ok(snapshot)
 }
 
+/// Delete orderbook snapshots captured before `older_than`, always keeping
+/// the newest `keep_per_market` snapshots of each market.
+///
+/// Snapshots are upserted one row per market, so stale rows belong to markets
+/// that stopped streaming; `keep_per_market = 0` removes those entirely.
+/// Returns the number of snapshots deleted.
+pub async fn prune_orderbook_snapshots(
+    pool: &PgPool,
+    older_than: DateTime<Utc>,
+    keep_per_market: i64,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM orderbook_snapshots
+        WHERE id IN (
+            SELECT id
+            FROM (
+                SELECT
+                    id,
+                    captured_at,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY market_id
+                        ORDER BY captured_at DESC NULLS LAST, id DESC
+                    ) AS rn
+                FROM orderbook_snapshots
+            ) ranked
+            WHERE ranked.captured_at < $1
+              AND ranked.rn > $2
+        )
+        "#,
+        older_than,
+        keep_per_market,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
 /// Get market by condition_id.
 pub async fn get_market_by_condition_id(
     pool: &PgPool,
@@ -794,12 +1211,14 @@ pub async fn get_15m_updown_markets_with_orderbooks(
             o.no_best_bid,
             o.yes_asks,
             o.no_asks,
+            o.yes_bids,
+            o.no_bids,
             o.captured_at as "captured_at!"
         FROM markets m
         INNER JOIN (
             SELECT DISTINCT ON (market_id)
                 market_id, yes_best_ask, yes_best_bid, no_best_ask, no_best_bid,
-                yes_asks, no_asks, captured_at
+                yes_asks, no_asks, yes_bids, no_bids, captured_at
             FROM orderbook_snapshots
             WHERE yes_updated_at > $1
               AND no_updated_at > $1
@@ -986,6 +1405,77 @@ pub async fn update_position_fills(
     Ok(())
 }
 
+/// Actual fill of a live order, as reported by the CLOB.
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub id: Uuid,
+    pub market_id: Uuid,
+    pub order_id: String,
+    pub token_id: String,
+    pub side: String,
+    pub filled_shares: Decimal,
+    pub avg_price: Decimal,
+    pub fee: Decimal,
+    pub filled_at: DateTime<Utc>,
+}
+
+/// Input for recording a fill
+#[derive(Debug, Clone)]
+pub struct FillInsert {
+    pub market_id: Uuid,
+    pub order_id: String,
+    pub token_id: String,
+    pub side: String,
+    pub filled_shares: Decimal,
+    pub avg_price: Decimal,
+    pub fee: Decimal,
+    pub filled_at: DateTime<Utc>,
+}
+
+/// Record the actual fill of a live order.
+pub async fn insert_fill(pool: &PgPool, fill: &FillInsert) -> Result<Uuid, sqlx::Error> {
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO fills (market_id, order_id, token_id, side, filled_shares, avg_price, fee, filled_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#,
+        fill.market_id,
+        fill.order_id,
+        fill.token_id,
+        fill.side,
+        fill.filled_shares,
+        fill.avg_price,
+        fill.fee,
+        fill.filled_at,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Get all recorded fills for a market, oldest first.
+pub async fn get_fills_for_market(
+    pool: &PgPool,
+    market_id: Uuid,
+) -> Result<Vec<Fill>, sqlx::Error> {
+    let fills = sqlx::query_as!(
+        Fill,
+        r#"
+        SELECT id, market_id, order_id, token_id, side, filled_shares, avg_price, fee, filled_at
+        FROM fills
+        WHERE market_id = $1
+        ORDER BY filled_at ASC
+        "#,
+        market_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(fills)
+}
+
 /// Get all open positions.
 pub async fn get_open_positions(
     pool: &PgPool,
@@ -1044,6 +1534,7 @@ pub async fn close_position(
 #[derive(Debug, Clone)]
 pub struct MarketResolution {
     pub market_id: Uuid,
+    pub condition_id: String,
     pub winning_side: String,
     pub resolved_at: Option<DateTime<Utc>>,
 }
@@ -1104,6 +1595,7 @@ pub async fn get_market_resolution(
         r#"
         SELECT
             m.id as market_id,
+            r.condition_id,
             r.winning_side,
             r.resolved_at
         FROM markets m
@@ -1132,6 +1624,7 @@ pub async fn get_market_resolutions_batch(
         r#"
         SELECT
             m.id as market_id,
+            r.condition_id,
             r.winning_side,
             r.resolved_at
         FROM markets m
@@ -1146,78 +1639,400 @@ pub async fn get_market_resolutions_batch(
     Ok(results)
 }
 
-// =============================================================================
-// KALSHI AND CROSS-PLATFORM FUNCTIONS
-// =============================================================================
+/// Get market resolutions keyed by condition_id.
+///
+/// Returns at most one resolution per condition_id; duplicate or unknown IDs
+/// in the input are ignored. When several markets share a condition_id (the
+/// same market listed on more than one platform), `market_id` is the
+/// Polymarket market, falling back to the earliest discovered.
+pub async fn get_market_resolutions_by_condition(
+    pool: &PgPool,
+    condition_ids: &[String],
+) -> Result<Vec<MarketResolution>, sqlx::Error> {
+    if condition_ids.is_empty() {
+        return Ok(Vec::new());
+    }
 
-use crate::kalshi::ParsedKalshiMarket;
-use crate::limitless::ParsedLimitlessMarket;
+    let results = sqlx::query_as!(
+        MarketResolution,
+        r#"
+        SELECT DISTINCT ON (r.condition_id)
+            m.id as market_id,
+            r.condition_id,
+            r.winning_side,
+            r.resolved_at
+        FROM market_resolutions r
+        JOIN markets m ON m.condition_id = r.condition_id
+        WHERE r.condition_id = ANY($1)
+        ORDER BY r.condition_id, (m.platform = 'polymarket') DESC, m.discovered_at
+        "#,
+        condition_ids
+    )
+    .fetch_all(pool)
+    .await?;
 
-/// Kalshi market for database insertion.
+    Ok(results)
+}
+
+/// Realized win-rate for one asset/timeframe bucket.
 #[derive(Debug, Clone)]
-pub struct KalshiMarketInsert {
-    pub ticker: String,
-    pub name: String,
+pub struct WinrateRow {
     pub asset: String,
     pub timeframe: String,
-    pub end_time: DateTime<Utc>,
-    pub yes_best_bid: Option<Decimal>,
-    pub yes_best_ask: Option<Decimal>,
-    pub no_best_bid: Option<Decimal>,
-    pub no_best_ask: Option<Decimal>,
-    pub liquidity: Option<Decimal>,
-    pub rules_primary: Option<String>,
-    pub strike_price: Option<f64>,
-    pub direction: Option<String>,
+    pub trades: i64,
+    pub wins: i64,
+    /// Wins as a percentage of trades (0-100)
+    pub win_rate: f64,
 }
 
-impl From<&ParsedKalshiMarket> for KalshiMarketInsert {
-    fn from(m: &ParsedKalshiMarket) -> Self {
-        Self {
-            ticker: m.ticker.clone(),
-            name: m.name.clone(),
-            asset: m.asset.clone(),
-            timeframe: m.timeframe.clone(),
-            end_time: m.close_time,
-            yes_best_bid: m.yes_best_bid,
-            yes_best_ask: m.yes_best_ask,
-            no_best_bid: m.no_best_bid,
-            no_best_ask: m.no_best_ask,
-            liquidity: m.liquidity,
-            rules_primary: m.rules_primary.clone(),
-            strike_price: m.strike_price,
-            direction: m.direction.clone(),
-        }
-    }
+/// Win-rate of resolved buy trades since `since`, grouped by asset and timeframe.
+/// Counts executor trades (via positions) and momentum trades; a trade wins when
+/// its side matches the market's recorded winning side. Dry-run trades are left
+/// out unless `include_dry_run` is set.
+pub async fn get_winrate_breakdown(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    include_dry_run: bool,
+) -> Result<Vec<WinrateRow>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        WITH fills AS (
+            SELECT p.market_id, t.side, t.executed_at AS traded_at,
+                   COALESCE(p.is_dry_run, true) AS is_dry_run
+            FROM trades t
+            JOIN positions p ON p.id = t.position_id
+            WHERE t.action = 'buy'
+            UNION ALL
+            SELECT mt.market_id, mt.side, mt.created_at AS traded_at,
+                   COALESCE(mt.is_dry_run, true) AS is_dry_run
+            FROM momentum_trades mt
+            WHERE mt.market_id IS NOT NULL
+        )
+        SELECT
+            r.asset,
+            r.timeframe,
+            COUNT(*) AS "trades!",
+            COUNT(*) FILTER (WHERE LOWER(f.side) = LOWER(r.winning_side)) AS "wins!"
+        FROM fills f
+        JOIN markets m ON m.id = f.market_id
+        JOIN market_resolutions r ON r.condition_id = m.condition_id
+        WHERE f.traded_at >= $1
+          AND ($2 OR NOT f.is_dry_run)
+        GROUP BY r.asset, r.timeframe
+        ORDER BY r.asset, r.timeframe
+        "#,
+        since,
+        include_dry_run
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| WinrateRow {
+            win_rate: if r.trades > 0 {
+                r.wins as f64 / r.trades as f64 * 100.0
+            } else {
+                0.0
+            },
+            asset: r.asset,
+            timeframe: r.timeframe,
+            trades: r.trades,
+            wins: r.wins,
+        })
+        .collect())
 }
 
-/// Upsert a Kalshi market into the database.
-/// Uses platform + condition_id (ticker for Kalshi) as unique key.
-pub async fn upsert_kalshi_market(
+/// Input for recording a resolved position.
+#[derive(Debug, Clone)]
+pub struct SettlementInsert {
+    pub market_id: Uuid,
+    pub side: String,
+    pub shares: Decimal,
+    pub cost: Decimal,
+    /// "YES", "NO", [`crate::AMBIGUOUS_WINNING_SIDE`] for a 50/50 resolution, or
+    /// [`crate::TIMED_OUT_WINNING_SIDE`] when the resolution never arrived
+    pub winning_side: String,
+    pub pnl: Decimal,
+    pub is_dry_run: bool,
+    pub resolved_at: DateTime<Utc>,
+}
+
+/// Record a resolved position and its realized P&L.
+pub async fn insert_settlement(
     pool: &PgPool,
-    market: &KalshiMarketInsert,
+    settlement: &SettlementInsert,
 ) -> Result<Uuid, sqlx::Error> {
-    // Determine market type from direction
-    let market_type = match market.direction.as_deref() {
-        Some("above") | Some("below") => "above",
-        _ => "unknown",
-    };
-
-    let result = sqlx::query_scalar!(
+    let id = sqlx::query_scalar!(
         r#"
-        INSERT INTO markets (
-            platform, condition_id, market_type, asset, timeframe,
-            yes_token_id, no_token_id, name, end_time,
-            rules_primary, liquidity_dollars, strike_price, direction,
-            is_active
-        )
-        VALUES (
-            'kalshi', $1, $2, $3, $4,
-            $1, $1, $5, $6,
-            $7, $8, $9, $10,
-            true
-        )
-        ON CONFLICT (platform, condition_id) DO UPDATE SET
+        INSERT INTO settlements (market_id, side, shares, cost, winning_side, pnl, is_dry_run, resolved_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#,
+        settlement.market_id,
+        settlement.side,
+        settlement.shares,
+        settlement.cost,
+        settlement.winning_side,
+        settlement.pnl,
+        settlement.is_dry_run,
+        settlement.resolved_at,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Aggregate realized results for live or dry-run settlements.
+#[derive(Debug, Clone)]
+pub struct SettlementSummary {
+    pub is_dry_run: bool,
+    pub settlements: i64,
+    pub wins: i64,
+    pub total_cost: Decimal,
+    pub total_pnl: Decimal,
+}
+
+impl SettlementSummary {
+    /// Wins as a percentage of settlements (0-100).
+    pub fn win_rate(&self) -> f64 {
+        if self.settlements > 0 {
+            self.wins as f64 / self.settlements as f64 * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Settlements resolved since `since`, summed separately for live and
+/// dry-run positions (live first). A settlement wins when its P&L is positive.
+pub async fn get_settlements_summary(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> Result<Vec<SettlementSummary>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        SettlementSummary,
+        r#"
+        SELECT
+            is_dry_run,
+            COUNT(*) AS "settlements!",
+            COUNT(*) FILTER (WHERE pnl > 0) AS "wins!",
+            SUM(cost) AS "total_cost!",
+            SUM(pnl) AS "total_pnl!"
+        FROM settlements
+        WHERE resolved_at >= $1
+        GROUP BY is_dry_run
+        ORDER BY is_dry_run
+        "#,
+        since
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Record a trade intent and how it ended in the cross-strategy audit trail.
+pub async fn audit_trade(
+    pool: &PgPool,
+    intent: &TradeIntent,
+    outcome: TradeOutcome,
+) -> Result<Uuid, sqlx::Error> {
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO trade_audit (market_id, token_id, side, shares, limit_price, effective_price, strategy_tag, mode, outcome)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id
+        "#,
+        intent.market_id,
+        intent.token_id,
+        intent.side,
+        intent.shares,
+        intent.limit_price,
+        intent.effective_price,
+        intent.strategy_tag,
+        intent.mode.as_str(),
+        outcome.as_str(),
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// A recorded trade intent from the audit trail.
+#[derive(Debug, Clone)]
+pub struct TradeAudit {
+    pub id: Uuid,
+    pub market_id: Uuid,
+    pub token_id: String,
+    pub side: String,
+    pub shares: Decimal,
+    pub limit_price: Decimal,
+    pub effective_price: Decimal,
+    pub strategy_tag: String,
+    pub mode: String,
+    pub outcome: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Get the audit trail for a market, oldest first.
+pub async fn get_trade_audit_for_market(
+    pool: &PgPool,
+    market_id: Uuid,
+) -> Result<Vec<TradeAudit>, sqlx::Error> {
+    let rows = sqlx::query_as!(
+        TradeAudit,
+        r#"
+        SELECT id, market_id, token_id, side, shares, limit_price, effective_price,
+               strategy_tag, mode, outcome, created_at
+        FROM trade_audit
+        WHERE market_id = $1
+        ORDER BY created_at ASC
+        "#,
+        market_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Markets the bot traded live that ended before `before` but have no live
+/// settlement recorded, soonest-ended first.
+///
+/// A market counts as traded when it has a live `trade_audit` row that was
+/// placed or filled by one of `strategy_tags`. Pass only strategies whose
+/// traders record settlements; others would always show up as unsettled.
+/// These are positions left behind when a trader stopped before resolution;
+/// look up `market_resolutions` for each to see whether the outcome is known yet.
+pub async fn get_unsettled_traded_markets(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+    strategy_tags: &[&str],
+) -> Result<Vec<MarketWithPlatform>, sqlx::Error> {
+    let markets = sqlx::query_as!(
+        MarketWithPlatform,
+        r#"
+        SELECT
+            m.id,
+            COALESCE(m.platform, 'polymarket') as "platform!",
+            m.condition_id,
+            m.market_type,
+            m.asset,
+            m.timeframe,
+            m.yes_token_id,
+            m.no_token_id,
+            m.name,
+            m.end_time,
+            COALESCE(m.is_active, true) as "is_active!",
+            m.direction,
+            m.strike_price::float8 as "strike_price: f64",
+            m.liquidity_dollars,
+            NULL::DECIMAL as yes_best_ask,
+            NULL::DECIMAL as yes_best_bid,
+            NULL::DECIMAL as no_best_ask,
+            NULL::DECIMAL as no_best_bid,
+            NULL::TIMESTAMPTZ as captured_at
+        FROM markets m
+        WHERE m.end_time < $1
+          AND EXISTS (
+              SELECT 1 FROM trade_audit a
+              WHERE a.market_id = m.id
+                AND a.mode = 'live'
+                AND a.outcome IN ('placed', 'filled')
+                AND a.strategy_tag = ANY($2)
+          )
+          AND NOT EXISTS (
+              SELECT 1 FROM settlements s
+              WHERE s.market_id = m.id AND NOT s.is_dry_run
+          )
+        ORDER BY m.end_time ASC
+        "#,
+        before,
+        strategy_tags,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(markets)
+}
+
+// =============================================================================
+// KALSHI AND CROSS-PLATFORM FUNCTIONS
+// =============================================================================
+
+use crate::kalshi::{KalshiEnv, ParsedKalshiMarket};
+use crate::limitless::ParsedLimitlessMarket;
+
+/// Kalshi market for database insertion.
+#[derive(Debug, Clone)]
+pub struct KalshiMarketInsert {
+    pub ticker: String,
+    pub name: String,
+    pub asset: String,
+    pub timeframe: String,
+    pub end_time: DateTime<Utc>,
+    pub yes_best_bid: Option<Decimal>,
+    pub yes_best_ask: Option<Decimal>,
+    pub no_best_bid: Option<Decimal>,
+    pub no_best_ask: Option<Decimal>,
+    pub liquidity: Option<Decimal>,
+    pub rules_primary: Option<String>,
+    pub strike_price: Option<f64>,
+    pub direction: Option<String>,
+}
+
+impl From<&ParsedKalshiMarket> for KalshiMarketInsert {
+    fn from(m: &ParsedKalshiMarket) -> Self {
+        Self {
+            ticker: m.ticker.clone(),
+            name: m.name.clone(),
+            asset: m.asset.clone(),
+            timeframe: m.timeframe.clone(),
+            end_time: m.close_time,
+            yes_best_bid: m.yes_best_bid,
+            yes_best_ask: m.yes_best_ask,
+            no_best_bid: m.no_best_bid,
+            no_best_ask: m.no_best_ask,
+            liquidity: m.liquidity,
+            rules_primary: m.rules_primary.clone(),
+            strike_price: m.strike_price,
+            direction: m.direction.clone(),
+        }
+    }
+}
+
+/// Upsert a Kalshi market into the database.
+/// Uses platform + condition_id (ticker for Kalshi) as unique key; demo
+/// markets are stored under their own platform tag (see [`KalshiEnv::platform`]).
+pub async fn upsert_kalshi_market(
+    pool: &PgPool,
+    market: &KalshiMarketInsert,
+    env: KalshiEnv,
+) -> Result<Uuid, sqlx::Error> {
+    // Determine market type from direction
+    let market_type = match market.direction.as_deref() {
+        Some("above") | Some("below") => "above",
+        _ => "unknown",
+    };
+
+    let result = sqlx::query_scalar!(
+        r#"
+        INSERT INTO markets (
+            platform, condition_id, market_type, asset, timeframe,
+            yes_token_id, no_token_id, name, end_time,
+            rules_primary, liquidity_dollars, strike_price, direction,
+            is_active
+        )
+        VALUES (
+            $11, $1, $2, $3, $4,
+            $1, $1, $5, $6,
+            $7, $8, $9, $10,
+            true
+        )
+        ON CONFLICT (platform, condition_id) DO UPDATE SET
             market_type = EXCLUDED.market_type,
             asset = EXCLUDED.asset,
             timeframe = EXCLUDED.timeframe,
@@ -1241,6 +2056,7 @@ pub async fn upsert_kalshi_market(
         market.liquidity,
         market.strike_price,
         market.direction,
+        env.platform(),
     )
     .fetch_one(pool)
     .await?;
@@ -1276,7 +2092,7 @@ pub struct MarketWithPlatform {
 /// Get active markets for a specific platform.
 pub async fn get_markets_by_platform(
     pool: &PgPool,
-    platform: &str,
+    platform: Platform,
 ) -> Result<Vec<MarketWithPlatform>, sqlx::Error> {
     let markets = sqlx::query_as!(
         MarketWithPlatform,
@@ -1307,7 +2123,55 @@ pub async fn get_markets_by_platform(
           AND m.end_time > NOW()
         ORDER BY m.end_time ASC
         "#,
-        platform
+        platform.as_str()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(markets)
+}
+
+/// Get markets for a batch of condition IDs in one query.
+///
+/// Includes inactive and expired markets. IDs with no market row are simply
+/// absent from the result; a condition ID listed on several platforms yields
+/// one row per platform.
+pub async fn get_markets_by_condition_ids(
+    pool: &PgPool,
+    condition_ids: &[String],
+) -> Result<Vec<MarketWithPlatform>, sqlx::Error> {
+    if condition_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let markets = sqlx::query_as!(
+        MarketWithPlatform,
+        r#"
+        SELECT
+            m.id,
+            COALESCE(m.platform, 'polymarket') as "platform!",
+            m.condition_id,
+            m.market_type,
+            m.asset,
+            m.timeframe,
+            m.yes_token_id,
+            m.no_token_id,
+            m.name,
+            m.end_time,
+            COALESCE(m.is_active, true) as "is_active!",
+            m.direction,
+            m.strike_price::float8 as "strike_price: f64",
+            m.liquidity_dollars,
+            NULL::DECIMAL as yes_best_ask,
+            NULL::DECIMAL as yes_best_bid,
+            NULL::DECIMAL as no_best_ask,
+            NULL::DECIMAL as no_best_bid,
+            NULL::TIMESTAMPTZ as captured_at
+        FROM markets m
+        WHERE m.condition_id = ANY($1)
+        ORDER BY m.condition_id, m.discovered_at
+        "#,
+        condition_ids
     )
     .fetch_all(pool)
     .await?;
@@ -1318,7 +2182,7 @@ pub async fn get_markets_by_platform(
 /// Get active markets for a platform with fresh orderbook prices.
 pub async fn get_platform_markets_with_prices(
     pool: &PgPool,
-    platform: &str,
+    platform: Platform,
     max_age_seconds: i32,
     assets: &[String],
     max_expiry_seconds: i64,
@@ -1365,7 +2229,7 @@ pub async fn get_platform_markets_with_prices(
         ORDER BY m.end_time ASC
         "#,
         snapshot_cutoff,
-        platform,
+        platform.as_str(),
         assets,
         expiry_cutoff,
     )
@@ -1375,6 +2239,63 @@ pub async fn get_platform_markets_with_prices(
     Ok(results)
 }
 
+/// Get active markets whose latest orderbook snapshot is older than
+/// `max_age_seconds`, or that have no snapshot at all.
+///
+/// These are markets the streaming services should be covering but aren't;
+/// prices and `captured_at` come from the stale snapshot when there is one.
+pub async fn get_markets_without_fresh_orderbooks(
+    pool: &PgPool,
+    max_age_seconds: i32,
+    assets: &[String],
+) -> Result<Vec<MarketWithPlatform>, sqlx::Error> {
+    let snapshot_cutoff = Utc::now() - chrono::Duration::seconds(max_age_seconds as i64);
+
+    let results = sqlx::query_as!(
+        MarketWithPlatform,
+        r#"
+        SELECT
+            m.id,
+            COALESCE(m.platform, 'polymarket') as "platform!",
+            m.condition_id,
+            m.market_type,
+            m.asset,
+            m.timeframe,
+            m.yes_token_id,
+            m.no_token_id,
+            m.name,
+            m.end_time,
+            COALESCE(m.is_active, true) as "is_active!",
+            m.direction,
+            m.strike_price::float8 as "strike_price: f64",
+            m.liquidity_dollars,
+            o.yes_best_ask as "yes_best_ask?",
+            o.yes_best_bid as "yes_best_bid?",
+            o.no_best_ask as "no_best_ask?",
+            o.no_best_bid as "no_best_bid?",
+            o.captured_at as "captured_at?"
+        FROM markets m
+        LEFT JOIN (
+            SELECT DISTINCT ON (market_id)
+                market_id, yes_best_ask, yes_best_bid, no_best_ask, no_best_bid, captured_at
+            FROM orderbook_snapshots
+            ORDER BY market_id, captured_at DESC NULLS LAST
+        ) o ON o.market_id = m.id
+        WHERE m.is_active = true
+          AND m.asset = ANY($2)
+          AND m.end_time > NOW()
+          AND (o.captured_at IS NULL OR o.captured_at <= $1)
+        ORDER BY m.end_time ASC
+        "#,
+        snapshot_cutoff,
+        assets,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results)
+}
+
 /// Update orderbook prices for a Kalshi market.
 /// Kalshi doesn't have WebSocket, so we update via REST polling.
 pub async fn update_kalshi_prices(
@@ -1570,7 +2491,7 @@ pub async fn get_limitless_markets_with_prices(
 ) -> Result<Vec<MarketWithPlatform>, sqlx::Error> {
     get_platform_markets_with_prices(
         pool,
-        "limitless",
+        Platform::Limitless,
         max_age_seconds,
         assets,
         max_expiry_seconds,
@@ -1591,6 +2512,7 @@ pub struct CrossPlatformMatchInsert {
 }
 
 /// Insert or update a cross-platform match.
+/// Re-confirming an existing match refreshes `validated_at` and clears `invalidated_at`.
 pub async fn upsert_cross_platform_match(
     pool: &PgPool,
     m: &CrossPlatformMatchInsert,
@@ -1605,7 +2527,8 @@ pub async fn upsert_cross_platform_match(
         ON CONFLICT (polymarket_id, kalshi_id) DO UPDATE SET
             match_confidence = EXCLUDED.match_confidence,
             match_reason = EXCLUDED.match_reason,
-            validated_at = NOW()
+            validated_at = NOW(),
+            invalidated_at = NULL
         RETURNING id
         "#,
         m.polymarket_id,
@@ -1659,20 +2582,57 @@ pub async fn get_cross_platform_matches(
         .collect())
 }
 
-/// Record a detected cross-platform arbitrage opportunity.
-#[allow(clippy::too_many_arguments)]
-pub async fn record_cross_platform_opportunity(
+/// Invalidate cross-platform matches that are no longer usable.
+///
+/// A match is invalidated when any of its markets has been deactivated, or when it
+/// hasn't been re-confirmed (via `upsert_cross_platform_match`) within `max_age_secs`.
+/// Returns the number of matches invalidated.
+pub async fn expire_stale_cross_platform_matches(
     pool: &PgPool,
-    match_id: Uuid,
-    buy_yes_platform: &str,
-    buy_no_platform: &str,
-    yes_price: Decimal,
-    no_price: Decimal,
-    total_cost: Decimal,
-    gross_profit_pct: Decimal,
-    net_profit_pct: Decimal,
-    min_liquidity: Option<Decimal>,
+    max_age_secs: i64,
+) -> Result<u64, sqlx::Error> {
+    let validated_cutoff = Utc::now() - chrono::Duration::seconds(max_age_secs);
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE cross_platform_matches cpm
+        SET invalidated_at = NOW()
+        WHERE cpm.invalidated_at IS NULL
+          AND (
+            COALESCE(cpm.validated_at, cpm.discovered_at) < $1
+            OR EXISTS (
+                SELECT 1 FROM markets m
+                WHERE m.id IN (cpm.polymarket_id, cpm.kalshi_id, cpm.limitless_id)
+                  AND m.is_active = false
+            )
+          )
+        "#,
+        validated_cutoff,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Record a detected cross-platform arbitrage opportunity.
+/// `yes_leg_depth`/`no_leg_depth` are the ask ladders used for sizing
+/// (`[{price, size}]` JSON), kept for post-mortem checks of the sizing.
+#[allow(clippy::too_many_arguments)]
+pub async fn record_cross_platform_opportunity(
+    pool: &PgPool,
+    match_id: Uuid,
+    buy_yes_platform: Platform,
+    buy_no_platform: Platform,
+    yes_price: Decimal,
+    no_price: Decimal,
+    total_cost: Decimal,
+    gross_profit_pct: Decimal,
+    net_profit_pct: Decimal,
+    min_liquidity: Option<Decimal>,
     expires_at: DateTime<Utc>,
+    yes_leg_depth: Option<&serde_json::Value>,
+    no_leg_depth: Option<&serde_json::Value>,
 ) -> Result<Uuid, sqlx::Error> {
     let result = sqlx::query_scalar!(
         r#"
@@ -1680,14 +2640,15 @@ pub async fn record_cross_platform_opportunity(
             match_id, buy_yes_platform, buy_no_platform,
             yes_price, no_price, total_cost,
             gross_profit_pct, net_profit_pct,
-            min_liquidity, expires_at
+            min_liquidity, expires_at,
+            yes_leg_depth, no_leg_depth
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         RETURNING id
         "#,
         match_id,
-        buy_yes_platform,
-        buy_no_platform,
+        buy_yes_platform.as_str(),
+        buy_no_platform.as_str(),
         yes_price,
         no_price,
         total_cost,
@@ -1695,6 +2656,8 @@ pub async fn record_cross_platform_opportunity(
         net_profit_pct,
         min_liquidity,
         expires_at,
+        yes_leg_depth,
+        no_leg_depth,
     )
     .fetch_one(pool)
     .await?;
@@ -1757,11 +2720,470 @@ pub async fn get_recent_opportunities(
         .collect())
 }
 
+/// A recorded cross-platform opportunity with the depth it was sized on.
+#[derive(Debug, Clone)]
+pub struct OpportunityWithDepth {
+    pub id: Uuid,
+    pub match_id: Uuid,
+    pub buy_yes_platform: String,
+    pub buy_no_platform: String,
+    pub yes_price: Decimal,
+    pub no_price: Decimal,
+    pub total_cost: Decimal,
+    pub net_profit_pct: Decimal,
+    pub detected_at: DateTime<Utc>,
+    /// YES asks on `buy_yes_platform`, if captured
+    pub yes_leg_depth: Option<serde_json::Value>,
+    /// NO asks on `buy_no_platform`, if captured
+    pub no_leg_depth: Option<serde_json::Value>,
+}
+
+impl OpportunityWithDepth {
+    /// Parsed YES leg ladder (empty when not captured).
+    pub fn yes_levels(&self) -> Vec<OrderbookLevel> {
+        self.yes_leg_depth
+            .as_ref()
+            .and_then(depth_levels)
+            .unwrap_or_default()
+    }
+
+    /// Parsed NO leg ladder (empty when not captured).
+    pub fn no_levels(&self) -> Vec<OrderbookLevel> {
+        self.no_leg_depth
+            .as_ref()
+            .and_then(depth_levels)
+            .unwrap_or_default()
+    }
+}
+
+/// Get recent cross-platform opportunities along with their recorded leg depth.
+pub async fn get_recent_opportunities_with_depth(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<Vec<OpportunityWithDepth>, sqlx::Error> {
+    let results = sqlx::query!(
+        r#"
+        SELECT
+            cpo.id,
+            cpo.match_id,
+            cpo.buy_yes_platform,
+            cpo.buy_no_platform,
+            cpo.yes_price,
+            cpo.no_price,
+            cpo.total_cost,
+            cpo.net_profit_pct,
+            cpo.detected_at,
+            cpo.yes_leg_depth,
+            cpo.no_leg_depth
+        FROM cross_platform_opportunities cpo
+        WHERE cpo.status = 'detected'
+        ORDER BY cpo.detected_at DESC
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results
+        .into_iter()
+        .map(|r| OpportunityWithDepth {
+            id: r.id,
+            match_id: r.match_id,
+            buy_yes_platform: r.buy_yes_platform,
+            buy_no_platform: r.buy_no_platform,
+            yes_price: r.yes_price,
+            no_price: r.no_price,
+            total_cost: r.total_cost,
+            net_profit_pct: r.net_profit_pct,
+            detected_at: r.detected_at.unwrap_or_else(Utc::now),
+            yes_leg_depth: r.yes_leg_depth,
+            no_leg_depth: r.no_leg_depth,
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::TradeMode;
     use crate::{Config, Database};
     use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    /// A BTC up/down market with the given snapshot depth and no best prices.
+    fn market_with_depth(
+        yes_asks: Option<serde_json::Value>,
+        no_asks: Option<serde_json::Value>,
+        yes_bids: Option<serde_json::Value>,
+        no_bids: Option<serde_json::Value>,
+    ) -> MarketWithOrderbook {
+        MarketWithOrderbook {
+            id: Uuid::new_v4(),
+            condition_id: "cond".to_string(),
+            market_type: "up_down".to_string(),
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "yes".to_string(),
+            no_token_id: "no".to_string(),
+            name: "BTC Up or Down".to_string(),
+            end_time: Utc::now() + Duration::minutes(5),
+            is_active: true,
+            yes_best_ask: None,
+            yes_best_bid: None,
+            no_best_ask: None,
+            no_best_bid: None,
+            yes_asks,
+            no_asks,
+            yes_bids,
+            no_bids,
+            captured_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_slippage_cap_boundary() {
+        let book = serde_json::json!([
+            {"price": "0.44", "size": "5"},
+            {"price": "0.40", "size": "5"},
+        ]);
+        // 10 shares: half at 0.40, half at 0.44 -> 0.42 effective, 5% over best
+        let estimate = calculate_fill_price_with_slippage(
+            Some(&book),
+            dec!(0.40),
+            dec!(10),
+            dec!(20),
+            FillPriceModel::Vwap,
+        );
+        assert_eq!(estimate.effective_price, dec!(0.42));
+        assert_eq!(estimate.slippage_pct(dec!(0.40)), dec!(5));
+
+        // Exactly at the cap is allowed; just under it skips
+        assert!(!estimate.exceeds_slippage_cap(dec!(0.40), dec!(5)));
+        assert!(estimate.exceeds_slippage_cap(dec!(0.40), dec!(4.99)));
+
+        // Fill within the best level has no slippage
+        let shallow = calculate_fill_price_with_slippage(
+            Some(&book),
+            dec!(0.40),
+            dec!(5),
+            dec!(20),
+            FillPriceModel::Vwap,
+        );
+        assert!(!shallow.exceeds_slippage_cap(dec!(0.40), Decimal::ZERO));
+
+        // Fallback estimate carries the assumed slippage
+        let fallback = calculate_fill_price_with_slippage(
+            None,
+            dec!(0.40),
+            dec!(10),
+            dec!(20),
+            FillPriceModel::Vwap,
+        );
+        assert_eq!(fallback.slippage_pct(dec!(0.40)), dec!(20));
+    }
+
+    #[test]
+    fn test_fill_price_models() {
+        let book = serde_json::json!([
+            {"price": "0.50", "size": "10"},
+            {"price": "0.45", "size": "5"},
+            {"price": "0.47", "size": "10"},
+            {"price": "0.60", "size": "100"},
+        ]);
+        let fill = |model| {
+            calculate_fill_price_with_slippage(Some(&book), dec!(0.45), dec!(20), dec!(20), model)
+        };
+
+        // 20 shares: 5 @ 0.45 + 10 @ 0.47 + 5 @ 0.50 = 9.45
+        let vwap = fill(FillPriceModel::Vwap);
+        assert_eq!(vwap.effective_price, dec!(0.4725));
+        assert_eq!(vwap.model, FillPriceModel::Vwap);
+        assert!(vwap.fully_filled);
+
+        // The 0.60 level is never reached
+        let worst = fill(FillPriceModel::WorstTouched);
+        assert_eq!(worst.effective_price, dec!(0.50));
+        assert_eq!(worst.filled_shares, dec!(20));
+
+        let best = fill(FillPriceModel::BestOnly);
+        assert_eq!(best.effective_price, dec!(0.45));
+        assert_eq!(best.best_price_depth, dec!(5));
+        // Planning at the best ask doesn't hide the walk from the slippage cap
+        assert_eq!(best.slippage_pct(dec!(0.45)), dec!(5));
+        assert!(best.exceeds_slippage_cap(dec!(0.45), dec!(4.99)));
+
+        // Without depth: slippage fallback, except best-only plans at the ask
+        let fallback = |model| {
+            calculate_fill_price_with_slippage(None, dec!(0.40), dec!(10), dec!(20), model)
+                .effective_price
+        };
+        assert_eq!(fallback(FillPriceModel::Vwap), dec!(0.48));
+        assert_eq!(fallback(FillPriceModel::WorstTouched), dec!(0.48));
+        assert_eq!(fallback(FillPriceModel::BestOnly), dec!(0.40));
+
+        assert_eq!("worst-touched".parse(), Ok(FillPriceModel::WorstTouched));
+        assert_eq!("VWAP".parse(), Ok(FillPriceModel::Vwap));
+        assert!("median".parse::<FillPriceModel>().is_err());
+    }
+
+    #[test]
+    fn test_aggregate_depth_to_ticks_merges_near_identical_levels() {
+        let level = |price, size| OrderbookLevel { price, size };
+        let raw = vec![
+            level(dec!(0.4501), dec!(10)),
+            level(dec!(0.4499), dec!(5)),
+            level(dec!(0.45), dec!(2)),
+            level(dec!(0.455), dec!(1)),
+            level(dec!(0.4698), dec!(7)),
+            level(dec!(0.47), dec!(0)),
+            level(dec!(0.52), dec!(0)),
+        ];
+
+        let merged =
+            aggregate_depth_to_ticks(&raw, dec!(0.01), RoundingStrategy::ToPositiveInfinity);
+        let prices: Vec<Decimal> = merged.iter().map(|l| l.price).collect();
+        let sizes: Vec<Decimal> = merged.iter().map(|l| l.size).collect();
+        // Asks round up: 0.4501 and 0.455 join the 0.46 bucket rather than
+        // posing as cheaper liquidity; the empty 0.52 level is dropped
+        assert_eq!(prices, vec![dec!(0.45), dec!(0.46), dec!(0.47)]);
+        assert_eq!(sizes, vec![dec!(7), dec!(11), dec!(7)]);
+
+        // The merged ladder fills the same as one clean level per tick
+        let fill = calculate_fill_price_from_levels(
+            Some(&merged),
+            dec!(0.45),
+            dec!(20),
+            dec!(20),
+            FillPriceModel::WorstTouched,
+        );
+        assert_eq!(fill.effective_price, dec!(0.47));
+        assert_eq!(fill.best_price_depth, dec!(7));
+
+        // Bids round down: 0.4501 stays in the 0.45 bucket, 0.4698 drops to 0.46
+        let bids = aggregate_depth_to_ticks(&raw, dec!(0.01), RoundingStrategy::ToNegativeInfinity);
+        let prices: Vec<Decimal> = bids.iter().map(|l| l.price).collect();
+        let sizes: Vec<Decimal> = bids.iter().map(|l| l.size).collect();
+        assert_eq!(prices, vec![dec!(0.44), dec!(0.45), dec!(0.46)]);
+        assert_eq!(sizes, vec![dec!(5), dec!(13), dec!(7)]);
+
+        // No tick size: nothing is merged
+        assert_eq!(
+            aggregate_depth_to_ticks(&raw, Decimal::ZERO, RoundingStrategy::ToPositiveInfinity)
+                .len(),
+            raw.len()
+        );
+    }
+
+    #[test]
+    fn test_prioritize_markets_ranks_and_caps() {
+        let now = Utc::now();
+        let market = |name: &str, mins: i64, ask: Option<Decimal>| MarketWithOrderbook {
+            name: name.to_string(),
+            end_time: now + Duration::minutes(mins),
+            yes_best_ask: ask,
+            no_best_ask: ask.map(|a| dec!(1) - a + dec!(0.02)),
+            ..market_with_depth(None, None, None, None)
+        };
+
+        let mut markets = vec![
+            market("late", 9, Some(dec!(0.30))),
+            market("soon-no-book", 2, None),
+            market("soon-cheap", 2, Some(dec!(0.20))),
+            market("mid", 5, Some(dec!(0.45))),
+        ];
+
+        // No cap: everything kept, ranked
+        let mut all = markets.clone();
+        assert_eq!(prioritize_markets(&mut all, None), 0);
+        let names: Vec<_> = all.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["soon-cheap", "soon-no-book", "mid", "late"]);
+
+        assert_eq!(prioritize_markets(&mut markets, Some(2)), 2);
+        let names: Vec<_> = markets.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["soon-cheap", "soon-no-book"]);
+
+        // Rotation keeps the most urgent market and cycles the others through
+        // the remaining slot, so every market is seen
+        let mut rotation = MarketRotation::default();
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            let mut cycle = all.clone();
+            assert_eq!(rotation.select(&mut cycle, Some(2)), 2);
+            assert_eq!(cycle[0].name, "soon-cheap");
+            seen.push(cycle[1].name.clone());
+        }
+        assert_eq!(seen, ["soon-no-book", "mid", "late"]);
+
+        // Under the cap nothing is deferred
+        let mut cycle = all.clone();
+        assert_eq!(rotation.select(&mut cycle, Some(4)), 0);
+        assert_eq!(cycle.len(), 4);
+    }
+
+    #[test]
+    fn test_validate_depth_json_accepts_well_formed() {
+        let depth = serde_json::json!([
+            {"price": "0.52", "size": "40"},
+            {"price": 0.55, "size": 60},
+            {"price": "1", "size": "0.5"},
+        ]);
+        assert_eq!(validate_depth_json(&depth), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_depth_json_accepts_empty() {
+        assert_eq!(validate_depth_json(&serde_json::json!([])), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_depth_json_rejects_malformed() {
+        let check = |v: serde_json::Value| validate_depth_json(&v).unwrap_err();
+
+        assert_eq!(
+            check(serde_json::json!({"price": "0.5"})),
+            DepthError::NotArray
+        );
+        assert_eq!(check(serde_json::json!(null)), DepthError::NotArray);
+        assert_eq!(
+            check(serde_json::json!([{"price": "0.5", "size": "1"}, "0.6"])),
+            DepthError::NotObject(1)
+        );
+        assert_eq!(
+            check(serde_json::json!([{"price": "abc", "size": "1"}])),
+            DepthError::Unparseable {
+                index: 0,
+                field: "price"
+            }
+        );
+        assert_eq!(
+            check(serde_json::json!([{"price": "0.5"}])),
+            DepthError::Unparseable {
+                index: 0,
+                field: "size"
+            }
+        );
+        assert_eq!(
+            check(serde_json::json!([{"price": "1.5", "size": "1"}])),
+            DepthError::PriceOutOfRange {
+                index: 0,
+                price: dec!(1.5)
+            }
+        );
+        assert_eq!(
+            check(serde_json::json!([{"price": "0", "size": "1"}])),
+            DepthError::PriceOutOfRange {
+                index: 0,
+                price: dec!(0)
+            }
+        );
+        assert_eq!(
+            check(serde_json::json!([{"price": "0.5", "size": "0"}])),
+            DepthError::NonPositiveSize {
+                index: 0,
+                size: dec!(0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_retain_valid_depth_drops_broken_snapshots() {
+        let market = |name: &str, yes_asks: serde_json::Value| MarketWithOrderbook {
+            name: name.to_string(),
+            ..market_with_depth(Some(yes_asks), None, None, None)
+        };
+
+        let mut markets = vec![
+            market("good", serde_json::json!([{"price": "0.52", "size": "40"}])),
+            market("bad", serde_json::json!([{"price": "0.52", "size": "-1"}])),
+        ];
+        let rejected = retain_valid_depth(&mut markets);
+
+        assert_eq!(markets.len(), 1);
+        assert_eq!(markets[0].name, "good");
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].0, "bad");
+    }
+
+    #[test]
+    fn test_imbalance_ratio_from_depth() {
+        let mut market = market_with_depth(
+            Some(serde_json::json!([
+                {"price": "0.52", "size": "40"},
+                {"price": "0.55", "size": "60"},
+            ])),
+            Some(serde_json::json!([{"price": "0.50", "size": "150"}])),
+            Some(serde_json::json!([
+                {"price": "0.50", "size": "120"},
+                {"price": "0.48", "size": "80"},
+            ])),
+            Some(serde_json::json!([{"price": "0.48", "size": "75"}])),
+        );
+
+        // YES: 200 bid vs 100 ask; NO: 75 bid vs 150 ask
+        assert_eq!(market.imbalance_ratio("YES"), Some(dec!(2)));
+        assert_eq!(market.imbalance_ratio("no"), Some(dec!(0.5)));
+        assert_eq!(market.imbalance_ratio("MAYBE"), None);
+
+        // Empty ask side against resting bids is maximally bid-heavy
+        market.yes_asks = Some(serde_json::json!([]));
+        assert_eq!(market.imbalance_ratio("YES"), Some(Decimal::MAX));
+
+        // Empty bid side
+        market.no_bids = Some(serde_json::json!([]));
+        assert_eq!(market.imbalance_ratio("NO"), Some(Decimal::ZERO));
+
+        // Both sides empty, missing or malformed depth: no reading
+        market.yes_bids = Some(serde_json::json!([]));
+        assert_eq!(market.imbalance_ratio("YES"), None);
+        market.no_asks = None;
+        assert_eq!(market.imbalance_ratio("NO"), None);
+        market.no_asks = Some(serde_json::json!({"price": "0.50"}));
+        assert_eq!(market.imbalance_ratio("NO"), None);
+    }
+
+    #[test]
+    fn test_combined_ask_cost() {
+        let mut market = MarketWithOrderbook {
+            yes_best_ask: Some(dec!(0.55)),
+            no_best_ask: Some(dec!(0.48)),
+            ..market_with_depth(None, None, None, None)
+        };
+        assert_eq!(market.combined_ask_cost(), Some(dec!(1.03)));
+
+        market.no_best_ask = Some(dec!(0.44));
+        assert_eq!(market.combined_ask_cost(), Some(dec!(0.99)));
+
+        // Either side missing: no reading
+        market.yes_best_ask = None;
+        assert_eq!(market.combined_ask_cost(), None);
+    }
+
+    #[test]
+    fn test_ask_liquidity_usdc_from_depth() {
+        let mut market = market_with_depth(
+            Some(serde_json::json!([
+                {"price": "0.52", "size": "40"},
+                {"price": "0.55", "size": "60"},
+            ])),
+            Some(serde_json::json!([{"price": "0.50", "size": "150"}])),
+            None,
+            None,
+        );
+
+        // YES: 0.52*40 + 0.55*60; NO: 0.50*150
+        assert_eq!(market.ask_liquidity_usdc("YES"), Some(dec!(53.80)));
+        assert_eq!(market.ask_liquidity_usdc("no"), Some(dec!(75)));
+        assert_eq!(market.ask_liquidity_usdc("MAYBE"), None);
+
+        // Empty book has no liquidity; missing or malformed depth is unknown
+        market.yes_asks = Some(serde_json::json!([]));
+        assert_eq!(market.ask_liquidity_usdc("YES"), Some(Decimal::ZERO));
+        market.no_asks = None;
+        assert_eq!(market.ask_liquidity_usdc("NO"), None);
+        market.no_asks = Some(serde_json::json!({"price": "0.50"}));
+        assert_eq!(market.ask_liquidity_usdc("NO"), None);
+    }
 
     #[tokio::test]
     async fn test_upsert_market() {
@@ -1782,6 +3204,8 @@ mod tests {
             yes_best_ask: None,
             no_best_bid: None,
             no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
         };
 
         // Insert new market
@@ -1807,45 +3231,1209 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_deactivate_expired_markets() {
+    async fn test_insert_and_get_fills() {
         dotenvy::dotenv().ok();
         let config = Config::from_env().expect("Config should load");
         let db = Database::connect(&config).await.expect("DB should connect");
 
-        // Insert an expired market
-        let condition_id = format!("test-expired-{}", Uuid::new_v4());
-        let expired_time = Utc::now() - Duration::hours(1);
+        let test_market = ParsedMarket {
+            condition_id: format!("test-fills-{}", Uuid::new_v4()),
+            market_type: MarketType::UpDown,
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "test-yes-token".to_string(),
+            no_token_id: "test-no-token".to_string(),
+            name: "Test market: fills".to_string(),
+            end_time: Utc::now() + Duration::hours(1),
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
+        };
+        let market_id = upsert_market(db.pool(), &test_market)
+            .await
+            .expect("Upsert should succeed");
+
+        let first = FillInsert {
+            market_id,
+            order_id: "order-1".to_string(),
+            token_id: "test-yes-token".to_string(),
+            side: "YES".to_string(),
+            filled_shares: dec!(10),
+            avg_price: dec!(0.45),
+            fee: dec!(0.0045),
+            filled_at: Utc::now() - Duration::seconds(30),
+        };
+        let second = FillInsert {
+            order_id: "order-2".to_string(),
+            token_id: "test-no-token".to_string(),
+            side: "NO".to_string(),
+            filled_shares: dec!(4.5),
+            avg_price: dec!(0.52),
+            fee: Decimal::ZERO,
+            filled_at: Utc::now(),
+            ..first.clone()
+        };
+        insert_fill(db.pool(), &second)
+            .await
+            .expect("Insert should succeed");
+        insert_fill(db.pool(), &first)
+            .await
+            .expect("Insert should succeed");
+
+        // Returned oldest first regardless of insert order
+        let fills = get_fills_for_market(db.pool(), market_id)
+            .await
+            .expect("Query should succeed");
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].order_id, "order-1");
+        assert_eq!(fills[0].filled_shares, dec!(10));
+        assert_eq!(fills[0].avg_price, dec!(0.45));
+        assert_eq!(fills[1].order_id, "order-2");
+        assert_eq!(fills[1].side, "NO");
 
+        // Clean up
+        sqlx::query!("DELETE FROM fills WHERE market_id = $1", market_id)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
         sqlx::query!(
-            r#"
-            INSERT INTO markets (condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
-            VALUES ($1, 'up_down', 'BTC', '1h', 'yes', 'no', 'Expired test', $2, true)
-            "#,
-            condition_id,
-            expired_time,
+            "DELETE FROM markets WHERE condition_id = $1",
+            test_market.condition_id
         )
         .execute(db.pool())
         .await
-        .expect("Insert should succeed");
+        .expect("Cleanup should succeed");
+    }
 
-        // Deactivate expired
-        let count = deactivate_expired_markets(db.pool())
+    #[tokio::test]
+    async fn test_insert_settlements_and_summary() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let test_market = ParsedMarket {
+            condition_id: format!("test-settlements-{}", Uuid::new_v4()),
+            market_type: MarketType::UpDown,
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "test-yes-token".to_string(),
+            no_token_id: "test-no-token".to_string(),
+            name: "Test market: settlements".to_string(),
+            end_time: Utc::now() - Duration::minutes(5),
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
+        };
+        let market_id = upsert_market(db.pool(), &test_market)
             .await
-            .expect("Deactivate should succeed");
-        assert!(count >= 1, "Should deactivate at least 1 market");
+            .expect("Upsert should succeed");
 
-        // Verify deactivated
-        let market = sqlx::query!(
-            "SELECT is_active FROM markets WHERE condition_id = $1",
-            condition_id
+        // Future window keeps real data out of the summary
+        let since = Utc::now() + Duration::days(365);
+        let win = SettlementInsert {
+            market_id,
+            side: "YES".to_string(),
+            shares: dec!(10),
+            cost: dec!(6),
+            winning_side: "YES".to_string(),
+            pnl: dec!(4),
+            is_dry_run: false,
+            resolved_at: since + Duration::minutes(1),
+        };
+        let loss = SettlementInsert {
+            side: "NO".to_string(),
+            shares: dec!(5),
+            cost: dec!(2),
+            pnl: dec!(-2),
+            ..win.clone()
+        };
+        let dry_run = SettlementInsert {
+            is_dry_run: true,
+            ..win.clone()
+        };
+        let too_old = SettlementInsert {
+            resolved_at: since - Duration::minutes(1),
+            ..loss.clone()
+        };
+        for settlement in [&win, &loss, &dry_run, &too_old] {
+            insert_settlement(db.pool(), settlement)
+                .await
+                .expect("Insert should succeed");
+        }
+
+        let summary = get_settlements_summary(db.pool(), since)
+            .await
+            .expect("Summary should succeed");
+        assert_eq!(summary.len(), 2);
+
+        // Live first
+        assert!(!summary[0].is_dry_run);
+        assert_eq!((summary[0].settlements, summary[0].wins), (2, 1));
+        assert_eq!(summary[0].total_cost, dec!(8));
+        assert_eq!(summary[0].total_pnl, dec!(2));
+        assert_eq!(summary[0].win_rate(), 50.0);
+
+        assert!(summary[1].is_dry_run);
+        assert_eq!((summary[1].settlements, summary[1].wins), (1, 1));
+        assert_eq!(summary[1].total_pnl, dec!(4));
+
+        // Clean up
+        sqlx::query!("DELETE FROM settlements WHERE market_id = $1", market_id)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+        sqlx::query!(
+            "DELETE FROM markets WHERE condition_id = $1",
+            test_market.condition_id
         )
-        .fetch_one(db.pool())
+        .execute(db.pool())
         .await
-        .expect("Fetch should succeed");
-        assert!(!market.is_active.unwrap_or(true));
+        .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_audit_trade_round_trip() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let test_market = ParsedMarket {
+            condition_id: format!("test-audit-{}", Uuid::new_v4()),
+            market_type: MarketType::UpDown,
+            asset: "ETH".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "test-yes-token".to_string(),
+            no_token_id: "test-no-token".to_string(),
+            name: "Test market: trade audit".to_string(),
+            end_time: Utc::now() + Duration::hours(1),
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
+        };
+        let market_id = upsert_market(db.pool(), &test_market)
+            .await
+            .expect("Upsert should succeed");
+
+        let live = TradeIntent {
+            market_id,
+            token_id: "test-yes-token".to_string(),
+            side: "YES".to_string(),
+            shares: dec!(12.5),
+            limit_price: dec!(0.55),
+            effective_price: dec!(0.5612),
+            strategy_tag: "momentum".to_string(),
+            mode: TradeMode::Live,
+        };
+        let dry_run = TradeIntent {
+            token_id: "test-no-token".to_string(),
+            side: "NO".to_string(),
+            strategy_tag: "misprice".to_string(),
+            mode: TradeMode::DryRun,
+            ..live.clone()
+        };
+        audit_trade(db.pool(), &live, TradeOutcome::Placed)
+            .await
+            .expect("Audit should succeed");
+        audit_trade(db.pool(), &dry_run, TradeOutcome::Skipped)
+            .await
+            .expect("Audit should succeed");
+
+        let rows = get_trade_audit_for_market(db.pool(), market_id)
+            .await
+            .expect("Query should succeed");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].strategy_tag, "momentum");
+        assert_eq!(rows[0].side, "YES");
+        assert_eq!(rows[0].shares, dec!(12.5));
+        assert_eq!(rows[0].limit_price, dec!(0.55));
+        assert_eq!(rows[0].effective_price, dec!(0.5612));
+        assert_eq!(rows[0].mode, "live");
+        assert_eq!(rows[0].outcome, "placed");
+        assert_eq!(rows[1].token_id, "test-no-token");
+        assert_eq!(rows[1].mode, "dry_run");
+        assert_eq!(rows[1].outcome, "skipped");
 
         // Clean up
-        sqlx::query!("DELETE FROM markets WHERE condition_id = $1", condition_id)
+        sqlx::query!("DELETE FROM trade_audit WHERE market_id = $1", market_id)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+        sqlx::query!(
+            "DELETE FROM markets WHERE condition_id = $1",
+            test_market.condition_id
+        )
+        .execute(db.pool())
+        .await
+        .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_get_unsettled_traded_markets() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let ended = |label: &str| ParsedMarket {
+            condition_id: format!("test-unsettled-{}-{}", label, Uuid::new_v4()),
+            market_type: MarketType::UpDown,
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "test-yes-token".to_string(),
+            no_token_id: "test-no-token".to_string(),
+            name: format!("Test market: {}", label),
+            end_time: Utc::now() - Duration::minutes(30),
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
+        };
+        let settled_market = ended("settled");
+        let unsettled_market = ended("unsettled");
+        let settled = upsert_market(db.pool(), &settled_market)
+            .await
+            .expect("Upsert should succeed");
+        let unsettled = upsert_market(db.pool(), &unsettled_market)
+            .await
+            .expect("Upsert should succeed");
+
+        // Settled: filled and settled live
+        let filled = TradeIntent {
+            market_id: settled,
+            token_id: "test-yes-token".to_string(),
+            side: "YES".to_string(),
+            shares: dec!(10),
+            limit_price: dec!(0.45),
+            effective_price: dec!(0.45),
+            strategy_tag: "misprice".to_string(),
+            mode: TradeMode::Live,
+        };
+        audit_trade(db.pool(), &filled, TradeOutcome::Filled)
+            .await
+            .expect("Audit should succeed");
+        insert_settlement(
+            db.pool(),
+            &SettlementInsert {
+                market_id: settled,
+                side: "YES".to_string(),
+                shares: dec!(10),
+                cost: dec!(4.5),
+                winning_side: "YES".to_string(),
+                pnl: dec!(5.5),
+                is_dry_run: false,
+                resolved_at: Utc::now() - Duration::minutes(20),
+            },
+        )
+        .await
+        .expect("Insert should succeed");
+
+        // Unsettled: placed live, and only a dry-run settlement recorded
+        let intent = TradeIntent {
+            market_id: unsettled,
+            token_id: "test-no-token".to_string(),
+            side: "NO".to_string(),
+            shares: dec!(8),
+            limit_price: dec!(0.40),
+            effective_price: dec!(0.40),
+            strategy_tag: "misprice".to_string(),
+            mode: TradeMode::Live,
+        };
+        audit_trade(db.pool(), &intent, TradeOutcome::Placed)
+            .await
+            .expect("Audit should succeed");
+        insert_settlement(
+            db.pool(),
+            &SettlementInsert {
+                market_id: unsettled,
+                side: "NO".to_string(),
+                shares: dec!(8),
+                cost: dec!(3.2),
+                winning_side: "YES".to_string(),
+                pnl: dec!(-3.2),
+                is_dry_run: true,
+                resolved_at: Utc::now() - Duration::minutes(20),
+            },
+        )
+        .await
+        .expect("Insert should succeed");
+
+        let ids: Vec<Uuid> = get_unsettled_traded_markets(db.pool(), Utc::now(), &["misprice"])
+            .await
+            .expect("Query should succeed")
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert!(ids.contains(&unsettled));
+        assert!(!ids.contains(&settled));
+
+        // Strategies that never record settlements are not reported
+        let other = get_unsettled_traded_markets(db.pool(), Utc::now(), &["momentum"])
+            .await
+            .expect("Query should succeed");
+        assert!(other.iter().all(|m| m.id != unsettled));
+
+        // Markets ending after the cutoff are not due yet
+        let earlier =
+            get_unsettled_traded_markets(db.pool(), Utc::now() - Duration::hours(1), &["misprice"])
+                .await
+                .expect("Query should succeed");
+        assert!(earlier.iter().all(|m| m.id != unsettled));
+
+        // Clean up
+        for (market_id, condition_id) in [
+            (settled, &settled_market.condition_id),
+            (unsettled, &unsettled_market.condition_id),
+        ] {
+            sqlx::query!("DELETE FROM fills WHERE market_id = $1", market_id)
+                .execute(db.pool())
+                .await
+                .expect("Cleanup should succeed");
+            sqlx::query!("DELETE FROM settlements WHERE market_id = $1", market_id)
+                .execute(db.pool())
+                .await
+                .expect("Cleanup should succeed");
+            sqlx::query!("DELETE FROM trade_audit WHERE market_id = $1", market_id)
+                .execute(db.pool())
+                .await
+                .expect("Cleanup should succeed");
+            sqlx::query!("DELETE FROM markets WHERE condition_id = $1", condition_id)
+                .execute(db.pool())
+                .await
+                .expect("Cleanup should succeed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_without_fresh_orderbooks() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let market = |label: &str| ParsedMarket {
+            condition_id: format!("test-coverage-{}-{}", label, Uuid::new_v4()),
+            market_type: MarketType::UpDown,
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "test-yes-token".to_string(),
+            no_token_id: "test-no-token".to_string(),
+            name: format!("Test market: coverage {}", label),
+            end_time: Utc::now() + Duration::hours(1),
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
+        };
+        let mut ids = Vec::new();
+        for label in ["covered", "stale", "missing"] {
+            ids.push(
+                upsert_market(db.pool(), &market(label))
+                    .await
+                    .expect("Upsert should succeed"),
+            );
+        }
+        let (covered_id, stale_id, missing_id) = (ids[0], ids[1], ids[2]);
+
+        for (market_id, captured_at) in [
+            (covered_id, Utc::now()),
+            (stale_id, Utc::now() - Duration::minutes(10)),
+        ] {
+            insert_orderbook_snapshot(
+                db.pool(),
+                market_id,
+                Some(dec!(0.55)),
+                Some(dec!(0.53)),
+                Some(dec!(0.47)),
+                Some(dec!(0.45)),
+                None,
+                None,
+                None,
+                None,
+                Some(captured_at),
+            )
+            .await
+            .expect("Snapshot insert should succeed");
+        }
+
+        let gaps = get_markets_without_fresh_orderbooks(db.pool(), 60, &["BTC".to_string()])
+            .await
+            .expect("Query should succeed");
+
+        assert!(!gaps.iter().any(|m| m.id == covered_id));
+        let stale = gaps
+            .iter()
+            .find(|m| m.id == stale_id)
+            .expect("Stale market should be reported");
+        assert!(stale.captured_at.is_some());
+        assert_eq!(stale.yes_best_ask, Some(dec!(0.55)));
+        let missing = gaps
+            .iter()
+            .find(|m| m.id == missing_id)
+            .expect("Market without snapshots should be reported");
+        assert!(missing.captured_at.is_none());
+
+        // Filtered by asset
+        let gaps = get_markets_without_fresh_orderbooks(db.pool(), 60, &["DOGE".to_string()])
+            .await
+            .expect("Query should succeed");
+        assert!(!gaps.iter().any(|m| ids.contains(&m.id)));
+
+        // Clean up (snapshots cascade)
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &ids)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_get_priority_markets_hybrid_orders_events_by_liquidity() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        // Liquidity well above anything real, so these rank first
+        let base = dec!(1000000000);
+        let cases = [
+            ("thin", base, 2),
+            ("deep-late", base * dec!(3), 20),
+            ("deep-soon", base * dec!(3), 5),
+            ("mid", base * dec!(2), 1),
+            ("below-floor", dec!(50), 1),
+        ];
+        let mut ids = Vec::new();
+        for (label, liquidity, days) in cases {
+            let market = ParsedMarket {
+                condition_id: format!("test-hybrid-{}-{}", label, Uuid::new_v4()),
+                market_type: MarketType::Unknown,
+                asset: "ELECTION".to_string(),
+                timeframe: "event".to_string(),
+                yes_token_id: "test-yes-token".to_string(),
+                no_token_id: "test-no-token".to_string(),
+                name: format!("Test market: hybrid {}", label),
+                end_time: Utc::now() + Duration::days(days),
+                yes_best_bid: None,
+                yes_best_ask: None,
+                no_best_bid: None,
+                no_best_ask: None,
+                outcomes: Vec::new(),
+                liquidity: Some(liquidity),
+            };
+            let id = upsert_market(db.pool(), &market)
+                .await
+                .expect("Upsert should succeed");
+            ids.push(id);
+        }
+
+        let markets = get_priority_markets_hybrid(db.pool(), 1, 30, 0, 4, Some(dec!(100)))
+            .await
+            .expect("Query should succeed");
+        let names: Vec<_> = markets.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Test market: hybrid deep-soon",
+                "Test market: hybrid deep-late",
+                "Test market: hybrid mid",
+                "Test market: hybrid thin",
+            ]
+        );
+
+        // Without a floor the low-liquidity market is kept
+        let markets = get_priority_markets_hybrid(db.pool(), 1, 30, 0, 10000, None)
+            .await
+            .expect("Query should succeed");
+        assert!(markets.iter().any(|m| m.id == ids[4]));
+
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &ids)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_prune_orderbook_snapshots_keeps_newest() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let market = |label: &str| ParsedMarket {
+            condition_id: format!("test-prune-{}-{}", label, Uuid::new_v4()),
+            market_type: MarketType::UpDown,
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "test-yes-token".to_string(),
+            no_token_id: "test-no-token".to_string(),
+            name: format!("Test market: prune {}", label),
+            end_time: Utc::now() + Duration::hours(1),
+            yes_best_bid: None,
+            yes_best_ask: None,
+            no_best_bid: None,
+            no_best_ask: None,
+            outcomes: Vec::new(),
+            liquidity: None,
+        };
+        let stale_market = market("stale");
+        let fresh_market = market("fresh");
+        let stale_id = upsert_market(db.pool(), &stale_market)
+            .await
+            .expect("Upsert should succeed");
+        let fresh_id = upsert_market(db.pool(), &fresh_market)
+            .await
+            .expect("Upsert should succeed");
+
+        // Backdate far enough that the cutoff only touches this test's rows
+        let ancient = Utc::now() - Duration::days(365 * 20);
+        let cutoff = ancient + Duration::days(1);
+        for (market_id, captured_at) in [(stale_id, ancient), (fresh_id, Utc::now())] {
+            insert_orderbook_snapshot(
+                db.pool(),
+                market_id,
+                Some(dec!(0.55)),
+                Some(dec!(0.53)),
+                Some(dec!(0.47)),
+                Some(dec!(0.45)),
+                None,
+                None,
+                None,
+                None,
+                Some(captured_at),
+            )
+            .await
+            .expect("Snapshot insert should succeed");
+        }
+
+        // Keeping the newest snapshot per market retains the stale one
+        prune_orderbook_snapshots(db.pool(), cutoff, 1)
+            .await
+            .expect("Prune should succeed");
+        assert!(get_latest_orderbook_snapshot(db.pool(), stale_id)
+            .await
+            .expect("Query should succeed")
+            .is_some());
+
+        // Without retention, only the snapshot older than the cutoff goes
+        let deleted = prune_orderbook_snapshots(db.pool(), cutoff, 0)
+            .await
+            .expect("Prune should succeed");
+        assert!(deleted >= 1);
+        assert!(get_latest_orderbook_snapshot(db.pool(), stale_id)
+            .await
+            .expect("Query should succeed")
+            .is_none());
+        assert!(get_latest_orderbook_snapshot(db.pool(), fresh_id)
+            .await
+            .expect("Query should succeed")
+            .is_some());
+
+        // Clean up (snapshots cascade)
+        let market_ids = vec![stale_id, fresh_id];
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &market_ids)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_deactivate_expired_markets() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        // Insert an expired market
+        let condition_id = format!("test-expired-{}", Uuid::new_v4());
+        let expired_time = Utc::now() - Duration::hours(1);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO markets (condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
+            VALUES ($1, 'up_down', 'BTC', '1h', 'yes', 'no', 'Expired test', $2, true)
+            "#,
+            condition_id,
+            expired_time,
+        )
+        .execute(db.pool())
+        .await
+        .expect("Insert should succeed");
+
+        // Deactivate expired
+        let count = deactivate_expired_markets(db.pool())
+            .await
+            .expect("Deactivate should succeed");
+        assert!(count >= 1, "Should deactivate at least 1 market");
+
+        // Verify deactivated
+        let market = sqlx::query!(
+            "SELECT is_active FROM markets WHERE condition_id = $1",
+            condition_id
+        )
+        .fetch_one(db.pool())
+        .await
+        .expect("Fetch should succeed");
+        assert!(!market.is_active.unwrap_or(true));
+
+        // Clean up
+        sqlx::query!("DELETE FROM markets WHERE condition_id = $1", condition_id)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_expire_stale_cross_platform_matches() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        // Fixture markets: poly, two active counterparts, one deactivated counterpart
+        let prefix = format!("test-cpm-{}", Uuid::new_v4());
+        let mut market_ids = Vec::new();
+        for (suffix, is_active) in [
+            ("poly", true),
+            ("fresh", true),
+            ("stale", true),
+            ("dead", false),
+        ] {
+            let id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO markets (condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
+                VALUES ($1, 'up_down', 'BTC', '1h', 'yes', 'no', 'Match fixture', $2, $3)
+                RETURNING id
+                "#,
+                format!("{}-{}", prefix, suffix),
+                Utc::now() + Duration::hours(2),
+                is_active,
+            )
+            .fetch_one(db.pool())
+            .await
+            .expect("Insert should succeed");
+            market_ids.push(id);
+        }
+        let (poly, fresh, stale, dead) =
+            (market_ids[0], market_ids[1], market_ids[2], market_ids[3]);
+
+        let insert_match = |other: Uuid| CrossPlatformMatchInsert {
+            polymarket_id: poly,
+            kalshi_id: other,
+            match_confidence: Decimal::new(95, 2),
+            match_reason: Some("fixture".to_string()),
+            entity_asset: Some("BTC".to_string()),
+            entity_timeframe: Some("1h".to_string()),
+            entity_direction: None,
+        };
+
+        let fresh_match = upsert_cross_platform_match(db.pool(), &insert_match(fresh))
+            .await
+            .expect("Upsert should succeed");
+        let stale_match = upsert_cross_platform_match(db.pool(), &insert_match(stale))
+            .await
+            .expect("Upsert should succeed");
+        let dead_match = upsert_cross_platform_match(db.pool(), &insert_match(dead))
+            .await
+            .expect("Upsert should succeed");
+
+        // Age the stale match past the max age
+        sqlx::query!(
+            "UPDATE cross_platform_matches SET validated_at = $2, discovered_at = $2 WHERE id = $1",
+            stale_match,
+            Utc::now() - Duration::hours(2),
+        )
+        .execute(db.pool())
+        .await
+        .expect("Update should succeed");
+
+        let count = expire_stale_cross_platform_matches(db.pool(), 3600)
+            .await
+            .expect("Expire should succeed");
+        assert!(count >= 2, "Should invalidate the stale and dead matches");
+
+        let invalidated = |id: Uuid| {
+            let pool = db.pool().clone();
+            async move {
+                sqlx::query_scalar!(
+                    "SELECT invalidated_at FROM cross_platform_matches WHERE id = $1",
+                    id
+                )
+                .fetch_one(&pool)
+                .await
+                .expect("Fetch should succeed")
+                .is_some()
+            }
+        };
+        assert!(!invalidated(fresh_match).await);
+        assert!(invalidated(stale_match).await);
+        assert!(invalidated(dead_match).await);
+
+        // Invalidated matches are no longer returned
+        let active: Vec<Uuid> = get_cross_platform_matches(db.pool(), Decimal::ZERO)
+            .await
+            .expect("Fetch should succeed")
+            .into_iter()
+            .map(|m| m.0)
+            .collect();
+        assert!(active.contains(&fresh_match));
+        assert!(!active.contains(&stale_match));
+        assert!(!active.contains(&dead_match));
+
+        // Re-confirming a stale match revives it
+        upsert_cross_platform_match(db.pool(), &insert_match(stale))
+            .await
+            .expect("Upsert should succeed");
+        assert!(!invalidated(stale_match).await);
+
+        // Clean up (matches cascade)
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &market_ids)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_record_opportunity_depth_round_trip() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let prefix = format!("test-cpo-{}", Uuid::new_v4());
+        let mut market_ids = Vec::new();
+        for suffix in ["poly", "other"] {
+            let id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO markets (condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
+                VALUES ($1, 'up_down', 'BTC', '1h', 'yes', 'no', 'Opportunity fixture', $2, true)
+                RETURNING id
+                "#,
+                format!("{}-{}", prefix, suffix),
+                Utc::now() + Duration::hours(2),
+            )
+            .fetch_one(db.pool())
+            .await
+            .expect("Insert should succeed");
+            market_ids.push(id);
+        }
+        let match_id = upsert_cross_platform_match(
+            db.pool(),
+            &CrossPlatformMatchInsert {
+                polymarket_id: market_ids[0],
+                kalshi_id: market_ids[1],
+                match_confidence: Decimal::new(95, 2),
+                match_reason: Some("fixture".to_string()),
+                entity_asset: Some("BTC".to_string()),
+                entity_timeframe: Some("1h".to_string()),
+                entity_direction: None,
+            },
+        )
+        .await
+        .expect("Upsert should succeed");
+
+        let yes_depth = serde_json::json!([
+            {"price": "0.45", "size": "10"},
+            {"price": "0.47", "size": "25"},
+        ]);
+        let no_depth = serde_json::json!([{"price": "0.50", "size": "40"}]);
+        let mut recorded = Vec::new();
+        for (yes, no) in [(Some(&yes_depth), Some(&no_depth)), (None, None)] {
+            let id = record_cross_platform_opportunity(
+                db.pool(),
+                match_id,
+                Platform::Polymarket,
+                Platform::Kalshi,
+                dec!(0.45),
+                dec!(0.50),
+                dec!(0.95),
+                dec!(5.26),
+                dec!(3.10),
+                Some(dec!(10)),
+                Utc::now() + Duration::hours(2),
+                yes,
+                no,
+            )
+            .await
+            .expect("Record should succeed");
+            recorded.push(id);
+        }
+        let (with_depth, without_depth) = (recorded[0], recorded[1]);
+
+        let recent = get_recent_opportunities_with_depth(db.pool(), 1000)
+            .await
+            .expect("Fetch should succeed");
+        let find = |id: Uuid| {
+            recent
+                .iter()
+                .find(|o| o.id == id)
+                .expect("Should be listed")
+        };
+
+        let opp = find(with_depth);
+        assert_eq!(opp.match_id, match_id);
+        assert_eq!(opp.yes_leg_depth.as_ref(), Some(&yes_depth));
+        assert_eq!(opp.no_leg_depth.as_ref(), Some(&no_depth));
+        let yes_levels = opp.yes_levels();
+        assert_eq!(yes_levels.len(), 2);
+        assert_eq!(yes_levels[1].price, dec!(0.47));
+        assert_eq!(yes_levels[1].size, dec!(25));
+        assert_eq!(opp.no_levels()[0].size, dec!(40));
+
+        let opp = find(without_depth);
+        assert!(opp.yes_leg_depth.is_none() && opp.no_leg_depth.is_none());
+        assert!(opp.yes_levels().is_empty());
+
+        // Clean up (matches and opportunities cascade)
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &market_ids)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_get_winrate_breakdown_groups_by_asset_and_timeframe() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        // Unique timeframe keeps fixture buckets apart from real data
+        let prefix = format!("test-wr-{}", Uuid::new_v4());
+        let timeframe = format!("wr-{}", &Uuid::new_v4().to_string()[..8]);
+        let since = Utc::now() - Duration::hours(1);
+
+        // (suffix, asset, winning side); "open" stays unresolved
+        let mut market_ids = Vec::new();
+        for (suffix, asset, winner) in [
+            ("btc", "BTC", Some("YES")),
+            ("eth", "ETH", Some("NO")),
+            ("open", "BTC", None),
+        ] {
+            let condition_id = format!("{}-{}", prefix, suffix);
+            let id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO markets (condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
+                VALUES ($1, 'up_down', $2, $3, 'yes', 'no', 'Winrate fixture', $4, false)
+                RETURNING id
+                "#,
+                condition_id,
+                asset,
+                timeframe,
+                Utc::now() - Duration::minutes(5),
+            )
+            .fetch_one(db.pool())
+            .await
+            .expect("Insert should succeed");
+            market_ids.push(id);
+
+            if let Some(winner) = winner {
+                upsert_market_resolution(
+                    db.pool(),
+                    &MarketResolutionInsert {
+                        condition_id,
+                        market_type: "up_down".to_string(),
+                        asset: asset.to_string(),
+                        timeframe: timeframe.clone(),
+                        name: "Winrate fixture".to_string(),
+                        yes_token_id: "yes".to_string(),
+                        no_token_id: "no".to_string(),
+                        winning_side: winner.to_string(),
+                        end_time: Utc::now() - Duration::minutes(5),
+                    },
+                )
+                .await
+                .expect("Resolution upsert should succeed");
+            }
+        }
+        let (btc, eth, open) = (market_ids[0], market_ids[1], market_ids[2]);
+
+        // BTC: yes (win) + no (loss) via positions, YES (win) via momentum
+        let btc_pos = create_position(db.pool(), btc, dec!(10), dec!(10), dec!(10), false)
+            .await
+            .expect("Position should insert");
+        record_trade(db.pool(), btc_pos, "yes", "buy", dec!(0.5), dec!(10))
+            .await
+            .expect("Trade should insert");
+        record_trade(db.pool(), btc_pos, "no", "buy", dec!(0.5), dec!(10))
+            .await
+            .expect("Trade should insert");
+        sqlx::query!(
+            "INSERT INTO momentum_trades (market_id, side, entry_price, shares, is_dry_run) VALUES ($1, 'YES', 0.6, 5, false)",
+            btc
+        )
+        .execute(db.pool())
+        .await
+        .expect("Momentum trade should insert");
+
+        // BTC: a dry-run yes (win) that only counts when dry-run is included
+        let dry_pos = create_position(db.pool(), btc, dec!(10), Decimal::ZERO, dec!(5), true)
+            .await
+            .expect("Position should insert");
+        record_trade(db.pool(), dry_pos, "yes", "buy", dec!(0.5), dec!(10))
+            .await
+            .expect("Trade should insert");
+
+        // ETH: yes (loss) now, plus a no trade from before `since` that must be ignored
+        let eth_pos = create_position(db.pool(), eth, dec!(10), dec!(10), dec!(10), false)
+            .await
+            .expect("Position should insert");
+        record_trade(db.pool(), eth_pos, "yes", "buy", dec!(0.5), dec!(10))
+            .await
+            .expect("Trade should insert");
+        let old_trade = record_trade(db.pool(), eth_pos, "no", "buy", dec!(0.5), dec!(10))
+            .await
+            .expect("Trade should insert");
+        sqlx::query!(
+            "UPDATE trades SET executed_at = $2 WHERE id = $1",
+            old_trade,
+            since - Duration::hours(1),
+        )
+        .execute(db.pool())
+        .await
+        .expect("Update should succeed");
+
+        // Unresolved market is excluded
+        let open_pos = create_position(db.pool(), open, dec!(10), Decimal::ZERO, dec!(5), false)
+            .await
+            .expect("Position should insert");
+        record_trade(db.pool(), open_pos, "yes", "buy", dec!(0.5), dec!(10))
+            .await
+            .expect("Trade should insert");
+
+        let rows: Vec<WinrateRow> = get_winrate_breakdown(db.pool(), since, false)
+            .await
+            .expect("Breakdown should succeed")
+            .into_iter()
+            .filter(|r| r.timeframe == timeframe)
+            .collect();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].asset, "BTC");
+        assert_eq!((rows[0].trades, rows[0].wins), (3, 2));
+        assert!((rows[0].win_rate - 66.666).abs() < 0.01);
+        assert_eq!(rows[1].asset, "ETH");
+        assert_eq!((rows[1].trades, rows[1].wins), (1, 0));
+        assert_eq!(rows[1].win_rate, 0.0);
+
+        let with_dry_run: Vec<WinrateRow> = get_winrate_breakdown(db.pool(), since, true)
+            .await
+            .expect("Breakdown should succeed")
+            .into_iter()
+            .filter(|r| r.timeframe == timeframe)
+            .collect();
+        assert_eq!((with_dry_run[0].trades, with_dry_run[0].wins), (4, 3));
+
+        // Clean up (trades cascade from positions)
+        sqlx::query!(
+            "DELETE FROM momentum_trades WHERE market_id = ANY($1)",
+            &market_ids
+        )
+        .execute(db.pool())
+        .await
+        .expect("Cleanup should succeed");
+        sqlx::query!(
+            "DELETE FROM positions WHERE market_id = ANY($1)",
+            &market_ids
+        )
+        .execute(db.pool())
+        .await
+        .expect("Cleanup should succeed");
+        sqlx::query!(
+            "DELETE FROM market_resolutions WHERE condition_id LIKE $1",
+            format!("{}-%", prefix)
+        )
+        .execute(db.pool())
+        .await
+        .expect("Cleanup should succeed");
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &market_ids)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_get_market_resolutions_by_condition() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let prefix = format!("test-res-cond-{}", Uuid::new_v4());
+        let timeframe = "1h".to_string();
+
+        // (suffix, asset, winning side); "open" stays unresolved
+        let mut market_ids = Vec::new();
+        for (suffix, asset, winner) in [
+            ("btc", "BTC", Some("YES")),
+            ("eth", "ETH", Some("NO")),
+            ("open", "BTC", None),
+        ] {
+            let condition_id = format!("{}-{}", prefix, suffix);
+            let id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO markets (condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
+                VALUES ($1, 'up_down', $2, $3, 'yes', 'no', 'Resolution fixture', $4, false)
+                RETURNING id
+                "#,
+                condition_id,
+                asset,
+                timeframe,
+                Utc::now() - Duration::minutes(5),
+            )
+            .fetch_one(db.pool())
+            .await
+            .expect("Insert should succeed");
+            market_ids.push(id);
+
+            if let Some(winner) = winner {
+                upsert_market_resolution(
+                    db.pool(),
+                    &MarketResolutionInsert {
+                        condition_id,
+                        market_type: "up_down".to_string(),
+                        asset: asset.to_string(),
+                        timeframe: timeframe.clone(),
+                        name: "Resolution fixture".to_string(),
+                        yes_token_id: "yes".to_string(),
+                        no_token_id: "no".to_string(),
+                        winning_side: winner.to_string(),
+                        end_time: Utc::now() - Duration::minutes(5),
+                    },
+                )
+                .await
+                .expect("Resolution upsert should succeed");
+            }
+        }
+        let (btc, eth) = (market_ids[0], market_ids[1]);
+
+        // Same condition_id listed on a second platform must not duplicate the row
+        let limitless_id = upsert_limitless_market(
+            db.pool(),
+            &LimitlessMarketInsert {
+                slug: format!("{}-btc", prefix),
+                name: "Resolution fixture".to_string(),
+                asset: "BTC".to_string(),
+                timeframe: timeframe.clone(),
+                end_time: Utc::now() - Duration::minutes(5),
+                yes_position_id: "yes".to_string(),
+                no_position_id: "no".to_string(),
+                yes_best_bid: None,
+                yes_best_ask: None,
+                no_best_bid: None,
+                no_best_ask: None,
+                liquidity: None,
+                direction: Some("up".to_string()),
+                exchange_address: None,
+            },
+        )
+        .await
+        .expect("Limitless upsert should succeed");
+        market_ids.push(limitless_id);
+
+        let condition = |suffix: &str| format!("{}-{}", prefix, suffix);
+        let mut resolutions = get_market_resolutions_by_condition(
+            db.pool(),
+            &[
+                condition("btc"),
+                condition("eth"),
+                condition("btc"),
+                condition("open"),
+                condition("unknown"),
+            ],
+        )
+        .await
+        .expect("Query should succeed");
+        resolutions.sort_by(|a, b| a.condition_id.cmp(&b.condition_id));
+
+        assert_eq!(resolutions.len(), 2);
+        assert_eq!(resolutions[0].condition_id, condition("btc"));
+        assert_eq!(resolutions[0].market_id, btc);
+        assert_eq!(resolutions[0].winning_side, "YES");
+        assert!(resolutions[0].resolved_at.is_some());
+        assert_eq!(resolutions[1].condition_id, condition("eth"));
+        assert_eq!(resolutions[1].market_id, eth);
+        assert_eq!(resolutions[1].winning_side, "NO");
+
+        assert!(get_market_resolutions_by_condition(db.pool(), &[])
+            .await
+            .expect("Query should succeed")
+            .is_empty());
+
+        // Clean up
+        sqlx::query!(
+            "DELETE FROM market_resolutions WHERE condition_id LIKE $1",
+            format!("{}-%", prefix)
+        )
+        .execute(db.pool())
+        .await
+        .expect("Cleanup should succeed");
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &market_ids)
+            .execute(db.pool())
+            .await
+            .expect("Cleanup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_get_markets_by_condition_ids() {
+        dotenvy::dotenv().ok();
+        let config = Config::from_env().expect("Config should load");
+        let db = Database::connect(&config).await.expect("DB should connect");
+
+        let prefix = format!("test-by-cond-{}", Uuid::new_v4());
+        let condition = |suffix: &str| format!("{}-{}", prefix, suffix);
+
+        let mut market_ids = Vec::new();
+        for (suffix, asset, is_active) in [("btc", "BTC", true), ("eth", "ETH", false)] {
+            let id = sqlx::query_scalar!(
+                r#"
+                INSERT INTO markets (condition_id, market_type, asset, timeframe, yes_token_id, no_token_id, name, end_time, is_active)
+                VALUES ($1, 'up_down', $2, '15m', 'yes', 'no', 'Batch lookup fixture', $3, $4)
+                RETURNING id
+                "#,
+                condition(suffix),
+                asset,
+                Utc::now() + Duration::minutes(10),
+                is_active,
+            )
+            .fetch_one(db.pool())
+            .await
+            .expect("Insert should succeed");
+            market_ids.push(id);
+        }
+
+        let markets = get_markets_by_condition_ids(
+            db.pool(),
+            &[condition("eth"), condition("missing"), condition("btc")],
+        )
+        .await
+        .expect("Query should succeed");
+
+        assert_eq!(markets.len(), 2);
+        assert_eq!(markets[0].condition_id, condition("btc"));
+        assert_eq!(markets[0].id, market_ids[0]);
+        assert_eq!(markets[0].platform, "polymarket");
+        assert!(markets[0].is_active);
+        // Inactive markets are still hydrated
+        assert_eq!(markets[1].condition_id, condition("eth"));
+        assert_eq!(markets[1].id, market_ids[1]);
+        assert!(!markets[1].is_active);
+
+        assert!(
+            get_markets_by_condition_ids(db.pool(), &[condition("missing")])
+                .await
+                .expect("Query should succeed")
+                .is_empty()
+        );
+        assert!(get_markets_by_condition_ids(db.pool(), &[])
+            .await
+            .expect("Query should succeed")
+            .is_empty());
+
+        // Clean up
+        sqlx::query!("DELETE FROM markets WHERE id = ANY($1)", &market_ids)
             .execute(db.pool())
             .await
             .expect("Cleanup should succeed");
@@ -0,0 +1,184 @@
+//! Fail-fast checks for trader command-line arguments.
+//!
+//! Each function takes the flag name as shown on the command line (without
+//! the leading `--`) so errors point straight at the offending option.
+
+use std::ops::RangeInclusive;
+
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Lowest price Polymarket accepts for a limit order.
+const MIN_ORDER_PRICE: f64 = 0.01;
+/// Highest price Polymarket accepts for a limit order.
+const MAX_ORDER_PRICE: f64 = 0.99;
+/// Smallest order size the CLOB can represent (sizes have two decimals).
+const MIN_ORDER_SHARES: Decimal = dec!(0.01);
+
+/// Check that `--min-expiry-minutes`/`--max-expiry-minutes` form a usable window.
+pub fn validate_expiry_window(min_minutes: i64, max_minutes: i64) -> Result<()> {
+    if min_minutes < 0 {
+        bail!(
+            "--min-expiry-minutes must be non-negative (got {})",
+            min_minutes
+        );
+    }
+    if max_minutes < 1 {
+        bail!(
+            "--max-expiry-minutes must be at least 1 (got {})",
+            max_minutes
+        );
+    }
+    if min_minutes > max_minutes {
+        bail!(
+            "--min-expiry-minutes ({}) must not exceed --max-expiry-minutes ({})",
+            min_minutes,
+            max_minutes
+        );
+    }
+    Ok(())
+}
+
+/// Check that a fractional or percentage flag lies within `range`.
+pub fn validate_pct(name: &str, value: f64, range: RangeInclusive<f64>) -> Result<()> {
+    if !range.contains(&value) {
+        bail!(
+            "--{} must be between {:?} and {:?} (got {})",
+            name,
+            range.start(),
+            range.end(),
+            value
+        );
+    }
+    Ok(())
+}
+
+/// Check that an order price flag is a price Polymarket will accept.
+pub fn validate_price(name: &str, value: f64) -> Result<()> {
+    validate_pct(name, value, MIN_ORDER_PRICE..=MAX_ORDER_PRICE)
+}
+
+/// Check that a size or amount flag is strictly positive.
+pub fn validate_positive(name: &str, value: f64) -> Result<()> {
+    if value.is_nan() || value <= 0.0 {
+        bail!("--{} must be positive (got {})", name, value);
+    }
+    Ok(())
+}
+
+/// Check that `--max-shares` leaves room for at least one CLOB lot.
+pub fn validate_max_shares(max_shares: Decimal) -> Result<()> {
+    if max_shares < MIN_ORDER_SHARES {
+        bail!(
+            "--max-shares must be at least {} (got {})",
+            MIN_ORDER_SHARES,
+            max_shares
+        );
+    }
+    Ok(())
+}
+
+/// Check a take-profit target against the trailing stop it rides on and the
+/// entry price it is measured from.
+///
+/// Take-profit is only evaluated while the trailing stop is enabled, must sit
+/// above entry, and must be reachable before the $1.00 payout cap.
+pub fn validate_exit_targets(
+    entry_price: f64,
+    trailing_stop_pct: f64,
+    take_profit_pct: Option<f64>,
+) -> Result<()> {
+    validate_pct("trailing-stop-pct", trailing_stop_pct, 0.0..=1.0)?;
+
+    let tp = match take_profit_pct {
+        Some(tp) => tp,
+        None => return Ok(()),
+    };
+    validate_pct("take-profit-pct", tp, 0.0..=10.0)?;
+    if trailing_stop_pct == 0.0 {
+        bail!("--take-profit-pct requires --trailing-stop-pct to be enabled");
+    }
+    if tp == 0.0 {
+        bail!("--take-profit-pct must be above entry (got 0)");
+    }
+    let target = entry_price * (1.0 + tp);
+    if target > 1.0 {
+        bail!(
+            "--take-profit-pct {} targets ${:.2} from a ${:.2} entry, above the $1.00 payout",
+            tp,
+            target,
+            entry_price
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_expiry_window() {
+        assert!(validate_expiry_window(1, 10).is_ok());
+        assert!(validate_expiry_window(0, 1).is_ok());
+        assert!(validate_expiry_window(5, 5).is_ok());
+
+        // Inverted window
+        assert!(validate_expiry_window(10, 1).is_err());
+        // Negative minimum, empty maximum
+        assert!(validate_expiry_window(-1, 10).is_err());
+        assert!(validate_expiry_window(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_validate_pct_and_price() {
+        assert!(validate_pct("slippage-pct", 20.0, 0.0..=100.0).is_ok());
+        assert!(validate_pct("slippage-pct", 100.0, 0.0..=100.0).is_ok());
+        assert!(validate_pct("slippage-pct", -0.1, 0.0..=100.0).is_err());
+        assert!(validate_pct("slippage-pct", 100.1, 0.0..=100.0).is_err());
+        assert!(validate_pct("slippage-pct", f64::NAN, 0.0..=100.0).is_err());
+
+        let err = validate_pct("trailing-stop-pct", 1.5, 0.0..=1.0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--trailing-stop-pct must be between 0.0 and 1.0 (got 1.5)"
+        );
+
+        assert!(validate_price("limit-price", 0.40).is_ok());
+        assert!(validate_price("limit-price", 0.0).is_err());
+        assert!(validate_price("limit-price", 1.0).is_err());
+
+        assert!(validate_positive("position-size", 5.0).is_ok());
+        assert!(validate_positive("position-size", 0.0).is_err());
+        assert!(validate_positive("position-size", -5.0).is_err());
+        assert!(validate_positive("position-size", f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_validate_max_shares() {
+        assert!(validate_max_shares(crate::DEFAULT_MAX_SHARES).is_ok());
+        assert!(validate_max_shares(dec!(500)).is_ok());
+        assert!(validate_max_shares(dec!(0.01)).is_ok());
+        assert!(validate_max_shares(dec!(0.005)).is_err());
+        assert!(validate_max_shares(Decimal::ZERO).is_err());
+        assert!(validate_max_shares(dec!(-1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_exit_targets() {
+        assert!(validate_exit_targets(0.40, 0.0, None).is_ok());
+        assert!(validate_exit_targets(0.40, 0.10, None).is_ok());
+        assert!(validate_exit_targets(0.40, 0.10, Some(0.50)).is_ok());
+
+        // Trailing stop out of range
+        assert!(validate_exit_targets(0.40, 1.5, None).is_err());
+        // Take-profit without a trailing stop is never evaluated
+        assert!(validate_exit_targets(0.40, 0.0, Some(0.50)).is_err());
+        // Take-profit at or below entry
+        assert!(validate_exit_targets(0.40, 0.10, Some(0.0)).is_err());
+        assert!(validate_exit_targets(0.40, 0.10, Some(-0.2)).is_err());
+        // Take-profit above the $1.00 payout: 0.60 * 2.0 = 1.20
+        assert!(validate_exit_targets(0.60, 0.10, Some(1.0)).is_err());
+    }
+}
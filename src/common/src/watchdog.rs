@@ -0,0 +1,246 @@
+//! Dead-man's switch for trading loops.
+//!
+//! The main loop bumps a [`Heartbeat`] once per cycle. A watchdog task,
+//! spawned on its own and independent of the loop's `select!`, checks the
+//! heartbeat and runs a stall handler when it stops advancing - typically
+//! cancelling everything in a [`RestingOrders`] registry so a hung loop
+//! cannot leave live orders on the book.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info};
+
+use crate::executor::cancel_order_standalone;
+
+/// Timestamp (unix millis) of the last completed loop cycle, shared between
+/// the loop and its watchdog.
+#[derive(Debug, Clone)]
+pub struct Heartbeat(Arc<AtomicI64>);
+
+impl Heartbeat {
+    /// Create a heartbeat that last beat now.
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicI64::new(Utc::now().timestamp_millis())))
+    }
+
+    /// Record a completed cycle.
+    pub fn beat(&self) {
+        self.beat_at(Utc::now());
+    }
+
+    /// Record a completed cycle at `now`.
+    pub fn beat_at(&self, now: DateTime<Utc>) {
+        self.0.store(now.timestamp_millis(), Ordering::Relaxed);
+    }
+
+    /// Unix millis of the last beat.
+    pub fn last_beat_millis(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Whether the heartbeat has gone more than `timeout` without a beat.
+    pub fn is_stalled_at(&self, now: DateTime<Utc>, timeout: Duration) -> bool {
+        let age_ms = now.timestamp_millis() - self.last_beat_millis();
+        age_ms > timeout.as_millis() as i64
+    }
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Spawn a watchdog that calls `on_stall` when `heartbeat` goes `timeout`
+/// without a beat.
+///
+/// The handler runs once per stall; the watchdog re-arms as soon as the
+/// heartbeat advances again. Abort the returned handle to stop it.
+pub fn spawn_watchdog<F, Fut>(
+    heartbeat: Heartbeat,
+    timeout: Duration,
+    on_stall: F,
+) -> JoinHandle<()>
+where
+    F: Fn(Duration) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    // Check several times per timeout so a stall is caught close to the limit
+    let check_every = (timeout / 4).max(Duration::from_millis(10));
+
+    tokio::spawn(async move {
+        let mut fired_for: Option<i64> = None;
+        loop {
+            tokio::time::sleep(check_every).await;
+
+            let last_beat = heartbeat.last_beat_millis();
+            if !heartbeat.is_stalled_at(Utc::now(), timeout) {
+                fired_for = None;
+                continue;
+            }
+            if fired_for == Some(last_beat) {
+                continue;
+            }
+            fired_for = Some(last_beat);
+
+            let stalled_for = Utc::now().timestamp_millis() - last_beat;
+            on_stall(Duration::from_millis(stalled_for.max(0) as u64)).await;
+        }
+    })
+}
+
+/// Order IDs currently resting on the book, shared so the watchdog can cancel
+/// them without going through the (possibly stuck) owner.
+#[derive(Debug, Clone, Default)]
+pub struct RestingOrders(Arc<Mutex<HashSet<String>>>);
+
+impl RestingOrders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a resting order.
+    pub fn insert(&self, order_id: &str) {
+        self.lock().insert(order_id.to_string());
+    }
+
+    /// Forget an order that was filled or cancelled.
+    pub fn remove(&self, order_id: &str) {
+        self.lock().remove(order_id);
+    }
+
+    /// Number of registered orders.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove and return every registered order ID.
+    pub fn take_all(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.lock().drain().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Cancel every registered order. Returns how many cancels succeeded.
+    pub async fn cancel_all(&self) -> usize {
+        let order_ids = self.take_all();
+        if order_ids.is_empty() {
+            return 0;
+        }
+
+        info!(
+            "[WATCHDOG] Cancelling {} resting orders...",
+            order_ids.len()
+        );
+        let mut cancelled = 0;
+        for order_id in order_ids {
+            match cancel_order_standalone(order_id.clone()).await {
+                Ok(()) => {
+                    info!("[WATCHDOG] Cancelled order {}", order_id);
+                    cancelled += 1;
+                }
+                Err(e) => {
+                    // Not necessarily an error - order might have been filled
+                    debug!(
+                        "[WATCHDOG] Cancel {} returned: {} (may be filled)",
+                        order_id, e
+                    );
+                }
+            }
+        }
+        cancelled
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        // A panic while holding the lock cannot leave the set inconsistent
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Log the critical alert for a stalled loop and cancel `resting`.
+pub async fn cancel_on_stall(resting: &RestingOrders, stalled_for: Duration) {
+    error!(
+        "[WATCHDOG] CRITICAL: main loop stalled for {}s, cancelling {} resting orders",
+        stalled_for.as_secs(),
+        resting.len()
+    );
+    let cancelled = resting.cancel_all().await;
+    error!("[WATCHDOG] Cancelled {} orders after stall", cancelled);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_heartbeat_stall_boundary() {
+        let heartbeat = Heartbeat::new();
+        let t0 = Utc::now();
+        heartbeat.beat_at(t0);
+        let timeout = Duration::from_secs(30);
+
+        assert!(!heartbeat.is_stalled_at(t0 + chrono::Duration::seconds(30), timeout));
+        assert!(heartbeat.is_stalled_at(t0 + chrono::Duration::seconds(31), timeout));
+
+        // A fresh beat clears the stall
+        heartbeat.beat_at(t0 + chrono::Duration::seconds(31));
+        assert!(!heartbeat.is_stalled_at(t0 + chrono::Duration::seconds(31), timeout));
+    }
+
+    #[test]
+    fn test_resting_orders_take_all() {
+        let resting = RestingOrders::new();
+        resting.insert("b");
+        resting.insert("a");
+        resting.insert("c");
+        resting.remove("c");
+
+        assert_eq!(resting.len(), 2);
+        assert_eq!(resting.take_all(), vec!["a".to_string(), "b".to_string()]);
+        assert!(resting.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_fires_when_heartbeat_stops() {
+        let heartbeat = Heartbeat::new();
+        let fired = Arc::new(AtomicUsize::new(0));
+        let timeout = Duration::from_millis(100);
+
+        let counter = fired.clone();
+        let handle = spawn_watchdog(heartbeat.clone(), timeout, move |_| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        // Beating keeps the watchdog quiet
+        for _ in 0..6 {
+            tokio::time::sleep(Duration::from_millis(40)).await;
+            heartbeat.beat();
+        }
+        assert_eq!(fired.load(Ordering::SeqCst), 0);
+
+        // Stop beating: fires once, not once per check
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+        // Re-arms after the heartbeat resumes and stalls again
+        heartbeat.beat();
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(fired.load(Ordering::SeqCst), 2);
+
+        handle.abort();
+    }
+}
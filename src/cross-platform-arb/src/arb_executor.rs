@@ -0,0 +1,888 @@
+//! Two-legged execution for cross-platform arbitrage.
+//!
+//! An opportunity is only risk-free once both legs are on. Legs are placed
+//! one after the other as fill-or-kill orders, and each fill is confirmed
+//! before moving on: if the first doesn't fill nothing is held, and if the
+//! second doesn't fill the first is sold back into a freshly fetched bid so
+//! the position is flat again. A leg whose fill can't be confirmed is
+//! reported as exposure rather than guessed at. Only Polymarket and
+//! Limitless (both CLOB venues) are wired.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use common::models::{TradeIntent, TradeMode, TradeOutcome};
+use common::{
+    execute_sell_order_with_kind, execute_trade_with_kind, fetch_clob_book,
+    query_order_fill_standalone, CachedAuth, CrossPlatformOpportunity, LimitlessClient,
+    LimitlessOrderSide, LimitlessTrader, OrderKind, Platform, UnifiedMarket,
+};
+use rust_decimal::Decimal;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// One side of an arb: buy `shares` of one outcome on one platform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbLeg {
+    pub platform: Platform,
+    /// Polymarket condition ID or Limitless slug
+    pub market_id: String,
+    /// Database UUID of the market
+    pub db_id: Uuid,
+    pub market_name: String,
+    /// "YES" or "NO"
+    pub outcome: &'static str,
+    pub token_id: String,
+    pub shares: Decimal,
+    pub price: Decimal,
+}
+
+impl ArbLeg {
+    /// The leg as a live trade intent for logging and the audit trail.
+    pub fn intent(&self) -> TradeIntent {
+        TradeIntent {
+            market_id: self.db_id,
+            token_id: self.token_id.clone(),
+            side: self.outcome.to_string(),
+            shares: self.shares,
+            limit_price: self.price,
+            effective_price: self.price,
+            strategy_tag: "cross_platform_arb".to_string(),
+            mode: TradeMode::Live,
+        }
+    }
+}
+
+/// A platform that can buy and sell outcome tokens with fill-or-kill orders.
+#[async_trait]
+pub trait Venue: Send {
+    /// Buy the leg at its price. Returns the order ID.
+    async fn buy(&mut self, leg: &ArbLeg) -> Result<String>;
+
+    /// Sell the leg's shares back at `price`. Returns the order ID.
+    async fn sell(&mut self, leg: &ArbLeg, price: Decimal) -> Result<String>;
+
+    /// Shares matched by an accepted order for this leg.
+    async fn filled_shares(&mut self, leg: &ArbLeg, order_id: &str) -> Result<Decimal>;
+
+    /// Best bid for the leg's outcome, fetched fresh from the book.
+    async fn best_bid(&mut self, leg: &ArbLeg) -> Result<Option<Decimal>>;
+}
+
+/// Polymarket CLOB via the shared executor.
+pub struct PolymarketVenue {
+    cached_auth: Option<CachedAuth>,
+    max_shares: Decimal,
+}
+
+impl PolymarketVenue {
+    /// Venue that refuses orders larger than `max_shares`.
+    pub fn new(max_shares: Decimal) -> Self {
+        Self {
+            cached_auth: None,
+            max_shares,
+        }
+    }
+}
+
+#[async_trait]
+impl Venue for PolymarketVenue {
+    async fn buy(&mut self, leg: &ArbLeg) -> Result<String> {
+        execute_trade_with_kind(
+            &mut self.cached_auth,
+            &leg.token_id,
+            leg.shares,
+            leg.price,
+            leg.outcome,
+            &leg.market_name,
+            self.max_shares,
+            OrderKind::FillOrKill,
+        )
+        .await
+    }
+
+    async fn sell(&mut self, leg: &ArbLeg, price: Decimal) -> Result<String> {
+        execute_sell_order_with_kind(
+            &mut self.cached_auth,
+            &leg.token_id,
+            leg.shares,
+            price,
+            &leg.market_name,
+            OrderKind::FillOrKill,
+        )
+        .await
+    }
+
+    async fn filled_shares(&mut self, _leg: &ArbLeg, order_id: &str) -> Result<Decimal> {
+        query_order_fill_standalone(order_id).await
+    }
+
+    async fn best_bid(&mut self, leg: &ArbLeg) -> Result<Option<Decimal>> {
+        Ok(fetch_clob_book(&leg.token_id).await?.best_bid())
+    }
+}
+
+/// Limitless CLOB via signed fill-or-kill orders.
+pub struct LimitlessVenue {
+    trader: LimitlessTrader,
+    markets: LimitlessClient,
+}
+
+impl LimitlessVenue {
+    pub fn new(trader: LimitlessTrader) -> Self {
+        Self {
+            trader,
+            markets: LimitlessClient::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Venue for LimitlessVenue {
+    async fn buy(&mut self, leg: &ArbLeg) -> Result<String> {
+        Ok(self
+            .trader
+            .place_order(
+                &leg.market_id,
+                &leg.token_id,
+                LimitlessOrderSide::Buy,
+                leg.shares,
+                leg.price,
+            )
+            .await?)
+    }
+
+    async fn sell(&mut self, leg: &ArbLeg, price: Decimal) -> Result<String> {
+        Ok(self
+            .trader
+            .place_order(
+                &leg.market_id,
+                &leg.token_id,
+                LimitlessOrderSide::Sell,
+                leg.shares,
+                price,
+            )
+            .await?)
+    }
+
+    /// Limitless only accepts fill-or-kill orders, so an accepted order is
+    /// fully filled.
+    async fn filled_shares(&mut self, leg: &ArbLeg, _order_id: &str) -> Result<Decimal> {
+        Ok(leg.shares)
+    }
+
+    async fn best_bid(&mut self, leg: &ArbLeg) -> Result<Option<Decimal>> {
+        let book = self.markets.fetch_orderbook(&leg.market_id).await?;
+        let side = if leg.outcome == "YES" {
+            &book.yes
+        } else {
+            &book.no
+        };
+        Ok(side
+            .bids
+            .iter()
+            .filter_map(|level| Decimal::try_from(level.price).ok())
+            .max())
+    }
+}
+
+/// Where a leg stands once its order has been sent and checked.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegFill {
+    /// The full size matched.
+    Filled { order_id: String },
+    /// Nothing is held: the order was rejected or matched nothing.
+    Unfilled { reason: String },
+    /// The fill couldn't be confirmed, or only part of it matched, so what
+    /// is held is unknown.
+    Unconfirmed { reason: String },
+}
+
+impl LegFill {
+    /// Classify an accepted order from its matched size.
+    pub fn from_matched(leg: &ArbLeg, order_id: String, matched: Result<Decimal>) -> Self {
+        match matched {
+            Ok(shares) if shares >= leg.shares => LegFill::Filled { order_id },
+            Ok(shares) if shares <= Decimal::ZERO => LegFill::Unfilled {
+                reason: format!("order {} matched nothing", order_id),
+            },
+            Ok(shares) => LegFill::Unconfirmed {
+                reason: format!(
+                    "order {} matched {} of {} shares",
+                    order_id, shares, leg.shares
+                ),
+            },
+            Err(e) => LegFill::Unconfirmed {
+                reason: format!("order {} fill unknown: {:#}", order_id, e),
+            },
+        }
+    }
+}
+
+/// What to do next given which legs have filled so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegAction {
+    /// First leg is on; place the second.
+    PlaceSecond,
+    /// First leg didn't fill; nothing is held, so stop.
+    Abort,
+    /// Both legs are on; the arb is hedged.
+    Done,
+    /// Second leg didn't fill; sell the first back to get flat.
+    UnwindFirst,
+}
+
+/// Decide the next step from whether the first leg filled and, once
+/// attempted, whether the second did.
+pub fn next_action(first_filled: bool, second_filled: Option<bool>) -> LegAction {
+    match (first_filled, second_filled) {
+        (false, _) => LegAction::Abort,
+        (true, None) => LegAction::PlaceSecond,
+        (true, Some(true)) => LegAction::Done,
+        (true, Some(false)) => LegAction::UnwindFirst,
+    }
+}
+
+/// Result of attempting an arb.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArbOutcome {
+    /// Both legs filled.
+    Hedged {
+        first_order: String,
+        second_order: String,
+    },
+    /// First leg didn't fill; no position taken.
+    Aborted { reason: String },
+    /// Second leg didn't fill and the first leg was sold back.
+    Unwound {
+        reason: String,
+        unwind_order: String,
+    },
+    /// A leg's fill is unknown, or the second leg didn't fill and the first
+    /// could not be sold back. The position needs manual attention.
+    Exposed { reason: String },
+}
+
+impl ArbOutcome {
+    /// Audit outcome of the (first, second) legs, in placement order.
+    pub fn leg_outcomes(&self) -> (TradeOutcome, TradeOutcome) {
+        match self {
+            ArbOutcome::Hedged { .. } => (TradeOutcome::Placed, TradeOutcome::Placed),
+            ArbOutcome::Aborted { .. } => (TradeOutcome::Rejected, TradeOutcome::Skipped),
+            ArbOutcome::Unwound { .. } | ArbOutcome::Exposed { .. } => {
+                (TradeOutcome::Placed, TradeOutcome::Rejected)
+            }
+        }
+    }
+}
+
+/// Places both legs of an opportunity with rollback.
+pub struct ArbExecutor {
+    polymarket: Box<dyn Venue>,
+    limitless: Box<dyn Venue>,
+    /// (Polymarket market, Limitless market) pairs already attempted, so the
+    /// same opportunity isn't re-entered every scan
+    attempted: HashSet<(String, String)>,
+}
+
+impl ArbExecutor {
+    pub fn new(polymarket: Box<dyn Venue>, limitless: Box<dyn Venue>) -> Self {
+        Self {
+            polymarket,
+            limitless,
+            attempted: HashSet::new(),
+        }
+    }
+
+    /// Whether this pair has already been attempted.
+    pub fn already_attempted(&self, opp: &CrossPlatformOpportunity) -> bool {
+        self.attempted.contains(&pair_key(opp))
+    }
+
+    fn venue(&mut self, platform: Platform) -> Result<&mut dyn Venue> {
+        match platform {
+            Platform::Polymarket => Ok(self.polymarket.as_mut()),
+            Platform::Limitless => Ok(self.limitless.as_mut()),
+            other => bail!("No execution venue for {}", other),
+        }
+    }
+
+    /// Buy a leg and confirm how much of it filled.
+    async fn place(&mut self, leg: &ArbLeg) -> LegFill {
+        let venue = match self.venue(leg.platform) {
+            Ok(venue) => venue,
+            Err(e) => {
+                return LegFill::Unfilled {
+                    reason: format!("{:#}", e),
+                }
+            }
+        };
+        match venue.buy(leg).await {
+            Ok(order_id) => {
+                let matched = venue.filled_shares(leg, &order_id).await;
+                LegFill::from_matched(leg, order_id, matched)
+            }
+            Err(e) => LegFill::Unfilled {
+                reason: format!("rejected: {:#}", e),
+            },
+        }
+    }
+
+    /// Place both legs of `opp`, unwinding the first if the second doesn't fill.
+    pub async fn execute(
+        &mut self,
+        opp: &CrossPlatformOpportunity,
+        yes: ArbLeg,
+        no: ArbLeg,
+    ) -> ArbOutcome {
+        self.attempted.insert(pair_key(opp));
+        let (first, second) = order_legs(yes, no);
+
+        let first_fill = self.place(&first).await;
+        let first_filled = matches!(first_fill, LegFill::Filled { .. });
+        let first_order = match (next_action(first_filled, None), first_fill) {
+            (LegAction::PlaceSecond, LegFill::Filled { order_id }) => order_id,
+            (_, LegFill::Unconfirmed { reason }) => {
+                let reason = format!("{} {} leg {}", first.platform, first.outcome, reason);
+                error!("[ARB] {} - not placing the second leg", reason);
+                return ArbOutcome::Exposed { reason };
+            }
+            (_, fill) => {
+                let reason = format!(
+                    "{} {} leg not filled: {}",
+                    first.platform,
+                    first.outcome,
+                    fill_reason(fill)
+                );
+                warn!("[ARB] {} - nothing held", reason);
+                return ArbOutcome::Aborted { reason };
+            }
+        };
+
+        let second_fill = self.place(&second).await;
+        let second_filled = matches!(second_fill, LegFill::Filled { .. });
+        let reason = match (next_action(true, Some(second_filled)), second_fill) {
+            (LegAction::Done, LegFill::Filled { order_id }) => {
+                return ArbOutcome::Hedged {
+                    first_order,
+                    second_order: order_id,
+                };
+            }
+            (_, LegFill::Unconfirmed { reason }) => {
+                let reason = format!("{} {} leg {}", second.platform, second.outcome, reason);
+                error!(
+                    "[ARB] {} - {} {} left on, not unwinding blind",
+                    reason, first.platform, first.outcome
+                );
+                return ArbOutcome::Exposed { reason };
+            }
+            (_, fill) => format!(
+                "{} {} leg not filled: {}",
+                second.platform,
+                second.outcome,
+                fill_reason(fill)
+            ),
+        };
+
+        warn!(
+            "[ARB] {} - unwinding {} {}",
+            reason, first.platform, first.outcome
+        );
+        self.unwind(&first, reason).await
+    }
+
+    /// Sell a filled leg back into its current best bid.
+    async fn unwind(&mut self, leg: &ArbLeg, reason: String) -> ArbOutcome {
+        let venue = match self.venue(leg.platform) {
+            Ok(venue) => venue,
+            Err(e) => {
+                error!("[ARB] {:#} - position left open", e);
+                return ArbOutcome::Exposed { reason };
+            }
+        };
+
+        let price = match venue.best_bid(leg).await {
+            Ok(Some(price)) if price > Decimal::ZERO => price,
+            Ok(_) => {
+                error!(
+                    "[ARB] No bid to unwind {} {} {} - position left open",
+                    leg.platform, leg.outcome, leg.market_name
+                );
+                return ArbOutcome::Exposed { reason };
+            }
+            Err(e) => {
+                error!(
+                    "[ARB] Book fetch to unwind {} {} {} failed: {:#} - position left open",
+                    leg.platform, leg.outcome, leg.market_name, e
+                );
+                return ArbOutcome::Exposed { reason };
+            }
+        };
+
+        let unwind_order = match venue.sell(leg, price).await {
+            Ok(order_id) => order_id,
+            Err(e) => {
+                error!(
+                    "[ARB] Unwind of {} {} {} failed: {:#} - position left open",
+                    leg.platform, leg.outcome, leg.market_name, e
+                );
+                return ArbOutcome::Exposed { reason };
+            }
+        };
+        let matched = venue.filled_shares(leg, &unwind_order).await;
+        match LegFill::from_matched(leg, unwind_order, matched) {
+            LegFill::Filled { order_id } => {
+                info!(
+                    "[ARB] Unwound {} {} {} @ {} ({})",
+                    leg.platform, leg.outcome, leg.market_name, price, order_id
+                );
+                ArbOutcome::Unwound {
+                    reason,
+                    unwind_order: order_id,
+                }
+            }
+            fill => {
+                error!(
+                    "[ARB] Unwind of {} {} {} @ {} not filled: {} - position left open",
+                    leg.platform,
+                    leg.outcome,
+                    leg.market_name,
+                    price,
+                    fill_reason(fill)
+                );
+                ArbOutcome::Exposed { reason }
+            }
+        }
+    }
+}
+
+fn fill_reason(fill: LegFill) -> String {
+    match fill {
+        LegFill::Filled { order_id } => format!("order {} filled", order_id),
+        LegFill::Unfilled { reason } | LegFill::Unconfirmed { reason } => reason,
+    }
+}
+
+fn pair_key(opp: &CrossPlatformOpportunity) -> (String, String) {
+    (
+        opp.pair.polymarket.market_id.clone(),
+        opp.pair.kalshi.market_id.clone(),
+    )
+}
+
+/// Put the Limitless leg first: its book is thinner, so it is the leg most
+/// likely to be killed, and a kill there leaves nothing to unwind.
+pub fn order_legs(yes: ArbLeg, no: ArbLeg) -> (ArbLeg, ArbLeg) {
+    if no.platform == Platform::Limitless && yes.platform != Platform::Limitless {
+        (no, yes)
+    } else {
+        (yes, no)
+    }
+}
+
+/// Build both legs for a sized opportunity, capped at `max_contracts`.
+///
+/// `tokens` maps market DB IDs to their (YES, NO) token IDs. Returns None if
+/// the opportunity is unsized or a token is unknown.
+pub fn build_legs(
+    opp: &CrossPlatformOpportunity,
+    tokens: &HashMap<Uuid, (String, String)>,
+    max_contracts: u64,
+) -> Option<(ArbLeg, ArbLeg)> {
+    let contracts = opp.max_contracts?.min(max_contracts);
+    if contracts == 0 {
+        return None;
+    }
+    let shares = Decimal::from(contracts);
+
+    let market_for = |platform: Platform| -> &UnifiedMarket {
+        if platform == Platform::Polymarket {
+            &opp.pair.polymarket
+        } else {
+            &opp.pair.kalshi
+        }
+    };
+    let leg = |market: &UnifiedMarket, outcome: &'static str, price| {
+        let db_id = market.db_id?;
+        let (yes_token, no_token) = tokens.get(&db_id)?;
+        Some(ArbLeg {
+            platform: market.platform,
+            market_id: market.market_id.clone(),
+            db_id,
+            market_name: market.name.clone(),
+            outcome,
+            token_id: if outcome == "YES" {
+                yes_token
+            } else {
+                no_token
+            }
+            .clone(),
+            shares,
+            price,
+        })
+    };
+
+    Some((
+        leg(market_for(opp.buy_yes_on), "YES", opp.yes_price)?,
+        leg(market_for(opp.buy_no_on), "NO", opp.no_price)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use common::MarketPair;
+    use rust_decimal_macros::dec;
+    use std::sync::{Arc, Mutex};
+
+    /// Venue that records calls and fails on request.
+    struct MockVenue {
+        fail_buy: bool,
+        fail_sell: bool,
+        /// Shares every accepted order matches; None fills the whole leg
+        matched: Option<Decimal>,
+        bid: Option<Decimal>,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Venue for MockVenue {
+        async fn buy(&mut self, leg: &ArbLeg) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("buy {} {}", leg.platform, leg.outcome));
+            if self.fail_buy {
+                bail!("mock buy rejected");
+            }
+            Ok(format!("{}-{}", leg.platform, leg.outcome))
+        }
+
+        async fn sell(&mut self, leg: &ArbLeg, price: Decimal) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("sell {} {} @ {}", leg.platform, leg.outcome, price));
+            if self.fail_sell {
+                bail!("mock sell rejected");
+            }
+            Ok("unwind".to_string())
+        }
+
+        async fn filled_shares(&mut self, leg: &ArbLeg, _order_id: &str) -> Result<Decimal> {
+            Ok(self.matched.unwrap_or(leg.shares))
+        }
+
+        async fn best_bid(&mut self, _leg: &ArbLeg) -> Result<Option<Decimal>> {
+            Ok(self.bid)
+        }
+    }
+
+    fn executor(
+        poly_fails: (bool, bool),
+        limitless_fails: (bool, bool),
+    ) -> (ArbExecutor, Arc<Mutex<Vec<String>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let venue = |(fail_buy, fail_sell)| {
+            Box::new(MockVenue {
+                fail_buy,
+                fail_sell,
+                matched: None,
+                bid: Some(dec!(0.43)),
+                calls: calls.clone(),
+            }) as Box<dyn Venue>
+        };
+        (
+            ArbExecutor::new(venue(poly_fails), venue(limitless_fails)),
+            calls,
+        )
+    }
+
+    fn leg(platform: Platform, outcome: &'static str) -> ArbLeg {
+        ArbLeg {
+            platform,
+            market_id: format!("{}-market", platform),
+            db_id: Uuid::nil(),
+            market_name: "BTC Up or Down".to_string(),
+            outcome,
+            token_id: format!("{}-token", outcome),
+            shares: dec!(10),
+            price: dec!(0.45),
+        }
+    }
+
+    fn unified(platform: Platform, db_id: Uuid) -> UnifiedMarket {
+        UnifiedMarket {
+            platform,
+            market_id: format!("{}-market", platform),
+            db_id: Some(db_id),
+            name: "BTC Up or Down".to_string(),
+            asset: "BTC".to_string(),
+            timeframe: "1h".to_string(),
+            end_time: Utc::now() + Duration::hours(1),
+            yes_best_ask: Some(dec!(0.45)),
+            yes_best_bid: Some(dec!(0.43)),
+            no_best_ask: Some(dec!(0.50)),
+            no_best_bid: Some(dec!(0.48)),
+            liquidity: Some(dec!(1000)),
+            price_updated_at: Some(Utc::now()),
+            direction: None,
+            strike_price: None,
+            yes_depth: None,
+            no_depth: None,
+        }
+    }
+
+    fn opportunity(poly_id: Uuid, limitless_id: Uuid) -> CrossPlatformOpportunity {
+        CrossPlatformOpportunity {
+            pair: MarketPair::new(
+                unified(Platform::Polymarket, poly_id),
+                unified(Platform::Limitless, limitless_id),
+                0.95,
+                "test".to_string(),
+            ),
+            buy_yes_on: Platform::Polymarket,
+            // The second market of a pair is labelled Kalshi by the detector
+            buy_no_on: Platform::Kalshi,
+            yes_price: dec!(0.45),
+            no_price: dec!(0.50),
+            total_cost: dec!(0.95),
+            gross_profit_pct: dec!(5.26),
+            net_profit_pct: dec!(4.0),
+            detected_at: Utc::now(),
+            max_contracts: Some(25),
+            max_investment: Some(dec!(23.75)),
+        }
+    }
+
+    #[test]
+    fn test_next_action() {
+        assert_eq!(next_action(false, None), LegAction::Abort);
+        assert_eq!(next_action(false, Some(true)), LegAction::Abort);
+        assert_eq!(next_action(true, None), LegAction::PlaceSecond);
+        assert_eq!(next_action(true, Some(true)), LegAction::Done);
+        assert_eq!(next_action(true, Some(false)), LegAction::UnwindFirst);
+    }
+
+    #[test]
+    fn test_order_legs_places_limitless_first() {
+        let (first, second) = order_legs(
+            leg(Platform::Polymarket, "YES"),
+            leg(Platform::Limitless, "NO"),
+        );
+        assert_eq!(first.platform, Platform::Limitless);
+        assert_eq!(second.platform, Platform::Polymarket);
+
+        let (first, _) = order_legs(
+            leg(Platform::Limitless, "YES"),
+            leg(Platform::Polymarket, "NO"),
+        );
+        assert_eq!(first.platform, Platform::Limitless);
+    }
+
+    #[tokio::test]
+    async fn test_execute_hedged() {
+        let (mut exec, calls) = executor((false, false), (false, false));
+        let opp = opportunity(Uuid::new_v4(), Uuid::new_v4());
+        let outcome = exec
+            .execute(
+                &opp,
+                leg(Platform::Polymarket, "YES"),
+                leg(Platform::Limitless, "NO"),
+            )
+            .await;
+
+        assert!(matches!(outcome, ArbOutcome::Hedged { .. }));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["buy Limitless NO", "buy Polymarket YES"]
+        );
+        assert!(exec.already_attempted(&opp));
+    }
+
+    #[tokio::test]
+    async fn test_execute_first_leg_rejected_places_nothing_else() {
+        let (mut exec, calls) = executor((false, false), (true, false));
+        let outcome = exec
+            .execute(
+                &opportunity(Uuid::new_v4(), Uuid::new_v4()),
+                leg(Platform::Polymarket, "YES"),
+                leg(Platform::Limitless, "NO"),
+            )
+            .await;
+
+        assert!(matches!(outcome, ArbOutcome::Aborted { .. }));
+        assert_eq!(*calls.lock().unwrap(), vec!["buy Limitless NO"]);
+        assert_eq!(
+            outcome.leg_outcomes(),
+            (TradeOutcome::Rejected, TradeOutcome::Skipped)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_second_leg_rejected_unwinds_first() {
+        let (mut exec, calls) = executor((true, false), (false, false));
+        let outcome = exec
+            .execute(
+                &opportunity(Uuid::new_v4(), Uuid::new_v4()),
+                leg(Platform::Polymarket, "YES"),
+                leg(Platform::Limitless, "NO"),
+            )
+            .await;
+
+        assert!(matches!(outcome, ArbOutcome::Unwound { .. }));
+        assert_eq!(
+            outcome.leg_outcomes(),
+            (TradeOutcome::Placed, TradeOutcome::Rejected)
+        );
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "buy Limitless NO",
+                "buy Polymarket YES",
+                "sell Limitless NO @ 0.43"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_failed_unwind_reports_exposure() {
+        let (mut exec, _) = executor((true, false), (false, true));
+        let outcome = exec
+            .execute(
+                &opportunity(Uuid::new_v4(), Uuid::new_v4()),
+                leg(Platform::Polymarket, "YES"),
+                leg(Platform::Limitless, "NO"),
+            )
+            .await;
+        assert!(matches!(outcome, ArbOutcome::Exposed { .. }));
+
+        // No bid to sell into is also exposure, without attempting a sale
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let venue = |fail_buy, bid| {
+            Box::new(MockVenue {
+                fail_buy,
+                fail_sell: false,
+                matched: None,
+                bid,
+                calls: calls.clone(),
+            }) as Box<dyn Venue>
+        };
+        let mut exec = ArbExecutor::new(venue(true, Some(dec!(0.43))), venue(false, None));
+        let outcome = exec
+            .execute(
+                &opportunity(Uuid::new_v4(), Uuid::new_v4()),
+                leg(Platform::Polymarket, "YES"),
+                leg(Platform::Limitless, "NO"),
+            )
+            .await;
+        assert!(matches!(outcome, ArbOutcome::Exposed { .. }));
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_accepted_but_unfilled_second_leg_unwinds() {
+        // The Polymarket order is accepted but matches nothing: not hedged
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let venue = |matched, bid| {
+            Box::new(MockVenue {
+                fail_buy: false,
+                fail_sell: false,
+                matched,
+                bid: Some(bid),
+                calls: calls.clone(),
+            }) as Box<dyn Venue>
+        };
+        let mut exec = ArbExecutor::new(venue(Some(dec!(0)), dec!(0.43)), venue(None, dec!(0.41)));
+        let outcome = exec
+            .execute(
+                &opportunity(Uuid::new_v4(), Uuid::new_v4()),
+                leg(Platform::Polymarket, "YES"),
+                leg(Platform::Limitless, "NO"),
+            )
+            .await;
+
+        assert!(matches!(outcome, ArbOutcome::Unwound { .. }));
+        // Sold back at the freshly fetched bid
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "buy Limitless NO",
+                "buy Polymarket YES",
+                "sell Limitless NO @ 0.41"
+            ]
+        );
+
+        // A partial or unknown fill is exposure: nothing is unwound blind
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let venue = |matched| {
+            Box::new(MockVenue {
+                fail_buy: false,
+                fail_sell: false,
+                matched,
+                bid: Some(dec!(0.43)),
+                calls: calls.clone(),
+            }) as Box<dyn Venue>
+        };
+        let mut exec = ArbExecutor::new(venue(Some(dec!(4))), venue(None));
+        let outcome = exec
+            .execute(
+                &opportunity(Uuid::new_v4(), Uuid::new_v4()),
+                leg(Platform::Polymarket, "YES"),
+                leg(Platform::Limitless, "NO"),
+            )
+            .await;
+        assert!(matches!(outcome, ArbOutcome::Exposed { .. }));
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_leg_fill_from_matched() {
+        let leg = leg(Platform::Polymarket, "YES");
+        assert!(matches!(
+            LegFill::from_matched(&leg, "o".into(), Ok(dec!(10))),
+            LegFill::Filled { .. }
+        ));
+        assert!(matches!(
+            LegFill::from_matched(&leg, "o".into(), Ok(dec!(0))),
+            LegFill::Unfilled { .. }
+        ));
+        assert!(matches!(
+            LegFill::from_matched(&leg, "o".into(), Ok(dec!(3))),
+            LegFill::Unconfirmed { .. }
+        ));
+        assert!(matches!(
+            LegFill::from_matched(&leg, "o".into(), Err(anyhow::anyhow!("timeout"))),
+            LegFill::Unconfirmed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_build_legs() {
+        let (poly_id, limitless_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let opp = opportunity(poly_id, limitless_id);
+        let mut tokens = HashMap::new();
+        tokens.insert(poly_id, ("poly-yes".to_string(), "poly-no".to_string()));
+        tokens.insert(limitless_id, ("ll-yes".to_string(), "ll-no".to_string()));
+
+        let (yes, no) = build_legs(&opp, &tokens, 10).expect("legs");
+        assert_eq!(yes.platform, Platform::Polymarket);
+        assert_eq!(yes.token_id, "poly-yes");
+        assert_eq!(yes.price, dec!(0.45));
+        assert_eq!(no.platform, Platform::Limitless);
+        assert_eq!(no.token_id, "ll-no");
+        assert_eq!(no.price, dec!(0.50));
+        // Capped by max_contracts
+        assert_eq!(yes.shares, dec!(10));
+        assert_eq!(no.shares, dec!(10));
+
+        // Unknown tokens or unsized opportunities produce no legs
+        tokens.remove(&limitless_id);
+        assert!(build_legs(&opp, &tokens, 10).is_none());
+        let mut unsized_opp = opportunity(poly_id, limitless_id);
+        unsized_opp.max_contracts = None;
+        assert!(build_legs(&unsized_opp, &HashMap::new(), 10).is_none());
+    }
+}
@@ -3,7 +3,7 @@
 //! Scans for profitable arbitrage opportunities between Polymarket and Kalshi.
 //! Arbitrage formula: YES_price(Platform A) + NO_price(Platform B) < $1.00
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use common::{CrossPlatformOpportunity, MarketPair, UnifiedMarket};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -28,6 +28,9 @@ pub struct DetectorConfig {
     pub max_price_staleness: i64,
     /// Match confidence threshold
     pub min_match_confidence: f64,
+    /// Reject a pair when either leg's price is older than
+    /// `max_price_staleness`, measured exactly rather than in whole seconds
+    pub require_both_fresh: bool,
 }
 
 impl Default for DetectorConfig {
@@ -41,6 +44,7 @@ impl Default for DetectorConfig {
             min_time_to_resolution_15m: 30, // 30 seconds for 15-minute markets
             max_price_staleness: 30,        // 30 seconds max price age
             min_match_confidence: 0.90,     // 90% match confidence
+            require_both_fresh: false,
         }
     }
 }
@@ -120,14 +124,25 @@ impl CrossPlatformDetector {
         }
 
         // Check price freshness
-        if !self.check_price_freshness(&pair.polymarket) {
+        if self.config.require_both_fresh {
+            if !self.both_legs_fresh(pair) {
+                let now = Utc::now();
+                debug!(
+                    "Skipping pair (stale leg: Polymarket {}, Limitless {}): {} vs {}",
+                    fmt_price_age(price_age(&pair.polymarket, now)),
+                    fmt_price_age(price_age(&pair.kalshi, now)),
+                    pair.polymarket.name,
+                    pair.kalshi.name
+                );
+                return false;
+            }
+        } else if !self.check_price_freshness(&pair.polymarket) {
             debug!(
                 "Skipping pair (stale Polymarket price): {}",
                 pair.polymarket.name
             );
             return false;
-        }
-        if !self.check_price_freshness(&pair.kalshi) {
+        } else if !self.check_price_freshness(&pair.kalshi) {
             debug!(
                 "Skipping pair (stale Limitless price): {}",
                 pair.kalshi.name
@@ -174,6 +189,16 @@ impl CrossPlatformDetector {
         }
     }
 
+    /// Whether both legs' prices are within `max_price_staleness`, whichever
+    /// platform they come from.
+    fn both_legs_fresh(&self, pair: &MarketPair) -> bool {
+        let now = Utc::now();
+        let max_age = Duration::seconds(self.config.max_price_staleness);
+        [&pair.polymarket, &pair.kalshi]
+            .into_iter()
+            .all(|market| price_age(market, now).is_some_and(|age| age <= max_age))
+    }
+
     /// Get minimum profit threshold for a timeframe.
     fn get_min_profit(&self, timeframe: &str) -> Decimal {
         match timeframe.to_lowercase().as_str() {
@@ -199,12 +224,94 @@ impl CrossPlatformDetector {
     }
 }
 
+/// How old a market's price is at `now`; None when it has no timestamp.
+pub fn price_age(market: &UnifiedMarket, now: DateTime<Utc>) -> Option<Duration> {
+    market.price_updated_at.map(|updated_at| now - updated_at)
+}
+
+/// Format a price age for logs, e.g. `4.2s`.
+pub fn fmt_price_age(age: Option<Duration>) -> String {
+    match age {
+        Some(age) => format!("{:.1}s", age.num_milliseconds() as f64 / 1000.0),
+        None => "n/a".to_string(),
+    }
+}
+
 impl Default for CrossPlatformDetector {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Field used to rank opportunities, highest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankKey {
+    /// Net profit percentage after fees
+    #[default]
+    NetProfitPct,
+    /// Largest profitable investment size
+    MaxInvestment,
+    /// Estimated dollar profit at the largest profitable size
+    Profit,
+}
+
+impl RankKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RankKey::NetProfitPct => "net-profit-pct",
+            RankKey::MaxInvestment => "max-investment",
+            RankKey::Profit => "profit",
+        }
+    }
+
+    /// Value to rank an opportunity by; None (unsized) ranks last.
+    fn value(&self, opp: &CrossPlatformOpportunity) -> Option<Decimal> {
+        match self {
+            RankKey::NetProfitPct => Some(opp.net_profit_pct),
+            RankKey::MaxInvestment => opp.max_investment,
+            RankKey::Profit => estimated_profit(opp),
+        }
+    }
+}
+
+impl std::str::FromStr for RankKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "net-profit-pct" => Ok(RankKey::NetProfitPct),
+            "max-investment" => Ok(RankKey::MaxInvestment),
+            "profit" => Ok(RankKey::Profit),
+            other => Err(format!(
+                "unknown sort key '{}' (expected net-profit-pct, max-investment, profit)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for RankKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Estimated dollar profit if the opportunity is taken at its max size.
+///
+/// Uses the top-of-book net profit percentage, so it overstates profit on
+/// thin books; None until the opportunity has been sized.
+pub fn estimated_profit(opp: &CrossPlatformOpportunity) -> Option<Decimal> {
+    opp.max_investment
+        .map(|inv| inv * opp.net_profit_pct / dec!(100))
+}
+
+/// Sort opportunities best-first by `by`.
+///
+/// The sort is stable, so ties keep their discovery order.
+pub fn rank_opportunities(opportunities: &mut [CrossPlatformOpportunity], by: RankKey) {
+    opportunities.sort_by(|a, b| by.value(b).cmp(&by.value(a)));
+}
+
 /// Summary of a scan run.
 #[derive(Debug, Clone)]
 pub struct ScanSummary {
@@ -229,7 +336,8 @@ impl ScanSummary {
         matches_found: usize,
         opportunities: &[CrossPlatformOpportunity],
     ) -> Self {
-        let best_profit_pct = opportunities.first().map(|o| o.net_profit_pct);
+        // Opportunities may be ranked by another key, so don't rely on order
+        let best_profit_pct = opportunities.iter().map(|o| o.net_profit_pct).max();
 
         Self {
             polymarket_count,
@@ -255,7 +363,6 @@ impl ScanSummary {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Duration;
     use common::Platform;
 
     fn create_test_pair(yes_poly: Decimal, no_kalshi: Decimal, timeframe: &str) -> MarketPair {
@@ -310,12 +417,12 @@ mod tests {
         let detector = CrossPlatformDetector::new();
 
         // Poly YES: 0.50, Kalshi NO: 0.44
-        // Total: 0.94, Profit: 6%
+        // Total: 0.94, Gross: 6.4%, Net after Kalshi fee (~1.72c): 4.5%
         let pair = create_test_pair(dec!(0.50), dec!(0.44), "1h");
         let opps = detector.scan(&[pair]);
 
         assert_eq!(opps.len(), 1);
-        assert!(opps[0].net_profit_pct > dec!(5.0));
+        assert!(opps[0].net_profit_pct > dec!(4.5));
     }
 
     #[test]
@@ -334,13 +441,115 @@ mod tests {
     fn test_15m_lower_threshold() {
         let detector = CrossPlatformDetector::new();
 
-        // Poly YES: 0.50, Kalshi NO: 0.48
-        // Total: 0.98, Profit: 2%
+        // Poly YES: 0.50, Kalshi NO: 0.47
+        // Total: 0.97, Gross: 3.1%, Net after Kalshi fee (~1.74c): 1.3%
         // Standard market would filter this, but 15m accepts it
-        let pair = create_test_pair(dec!(0.50), dec!(0.48), "15m");
+        let pair = create_test_pair(dec!(0.50), dec!(0.47), "15m");
         let opps = detector.scan(&[pair]);
 
         assert_eq!(opps.len(), 1);
         assert!(opps[0].net_profit_pct > dec!(1.0));
     }
+
+    #[test]
+    fn test_require_both_fresh_rejects_either_stale_leg() {
+        let lenient = CrossPlatformDetector::new();
+        let strict = CrossPlatformDetector::with_config(DetectorConfig {
+            require_both_fresh: true,
+            ..Default::default()
+        });
+
+        let fresh = create_test_pair(dec!(0.50), dec!(0.44), "1h");
+        assert_eq!(strict.scan(&[fresh.clone()]).len(), 1);
+
+        // Limitless leg just past the 30s bound: whole-second rounding lets it
+        // through the per-platform check, the strict gate does not
+        let mut lagging = fresh.clone();
+        lagging.kalshi.price_updated_at = Some(Utc::now() - Duration::milliseconds(30_500));
+        assert_eq!(lenient.scan(&[lagging.clone()]).len(), 1);
+        assert!(strict.scan(&[lagging]).is_empty());
+
+        // Either side can be the stale one
+        let mut stale_poly = fresh.clone();
+        stale_poly.polymarket.price_updated_at = Some(Utc::now() - Duration::seconds(45));
+        assert!(strict.scan(&[stale_poly]).is_empty());
+
+        let mut untimed = fresh;
+        untimed.kalshi.price_updated_at = None;
+        assert!(strict.scan(&[untimed]).is_empty());
+    }
+
+    #[test]
+    fn test_fmt_price_age() {
+        let now = Utc::now();
+        let mut pair = create_test_pair(dec!(0.50), dec!(0.44), "1h");
+        pair.polymarket.price_updated_at = Some(now - Duration::milliseconds(4_240));
+        pair.kalshi.price_updated_at = None;
+        assert_eq!(fmt_price_age(price_age(&pair.polymarket, now)), "4.2s");
+        assert_eq!(fmt_price_age(price_age(&pair.kalshi, now)), "n/a");
+    }
+
+    fn sized_opportunity(
+        yes_poly: Decimal,
+        no_kalshi: Decimal,
+        name: &str,
+        investment: Option<Decimal>,
+    ) -> CrossPlatformOpportunity {
+        let mut pair = create_test_pair(yes_poly, no_kalshi, "1h");
+        pair.polymarket.name = name.to_string();
+        let opp = CrossPlatformOpportunity::calculate(pair, Decimal::ZERO).unwrap();
+        match investment {
+            Some(inv) => opp.with_max_size(100, inv),
+            None => opp,
+        }
+    }
+
+    fn names(opps: &[CrossPlatformOpportunity]) -> Vec<&str> {
+        opps.iter()
+            .map(|o| o.pair.polymarket.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_opportunities() {
+        // A and B share prices (equal net %), C is more profitable per dollar
+        let opps = vec![
+            sized_opportunity(dec!(0.50), dec!(0.44), "A", Some(dec!(100))),
+            sized_opportunity(dec!(0.50), dec!(0.44), "B", Some(dec!(300))),
+            sized_opportunity(dec!(0.50), dec!(0.40), "C", Some(dec!(30))),
+            sized_opportunity(dec!(0.50), dec!(0.46), "D", None),
+        ];
+
+        // Ties keep discovery order (A before B)
+        let mut ranked = opps.clone();
+        rank_opportunities(&mut ranked, RankKey::NetProfitPct);
+        assert_eq!(names(&ranked), vec!["C", "A", "B", "D"]);
+
+        // Unsized opportunities rank last
+        let mut ranked = opps.clone();
+        rank_opportunities(&mut ranked, RankKey::MaxInvestment);
+        assert_eq!(names(&ranked), vec!["B", "A", "C", "D"]);
+
+        // B: 300 * ~4.6% beats A: 100 * ~4.6% beats C: 30 * ~9.2%
+        let mut ranked = opps;
+        rank_opportunities(&mut ranked, RankKey::Profit);
+        assert_eq!(names(&ranked), vec!["B", "A", "C", "D"]);
+        assert!(estimated_profit(&ranked[0]).unwrap() > estimated_profit(&ranked[1]).unwrap());
+        assert_eq!(estimated_profit(&ranked[3]), None);
+    }
+
+    #[test]
+    fn test_rank_key_from_str() {
+        assert_eq!(
+            "net-profit-pct".parse::<RankKey>(),
+            Ok(RankKey::NetProfitPct)
+        );
+        assert_eq!(
+            "Max-Investment".parse::<RankKey>(),
+            Ok(RankKey::MaxInvestment)
+        );
+        assert_eq!("profit".parse::<RankKey>(), Ok(RankKey::Profit));
+        assert!("volume".parse::<RankKey>().is_err());
+        assert_eq!(RankKey::default().to_string(), "net-profit-pct");
+    }
 }
@@ -6,9 +6,12 @@
 //! - Direction (up/down, above/below)
 //! - End time (within tolerance)
 
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use chrono::{DateTime, Utc};
 use common::{MarketPair, UnifiedMarket};
-use tracing::info;
+use tracing::{debug, info};
 
 /// Entity extracted from a market for matching.
 #[derive(Debug, Clone)]
@@ -54,9 +57,55 @@ impl Default for MatcherConfig {
     }
 }
 
+/// Market fields that feed entity extraction. A cached score is only reused
+/// while both markets still have the fingerprint it was computed from.
+#[derive(Debug, Clone, PartialEq)]
+struct MarketFingerprint {
+    name: String,
+    asset: String,
+    timeframe: String,
+    direction: Option<String>,
+    strike_price: Option<f64>,
+    end_time: DateTime<Utc>,
+}
+
+impl MarketFingerprint {
+    fn of(market: &UnifiedMarket) -> Self {
+        Self {
+            name: market.name.clone(),
+            asset: market.asset.clone(),
+            timeframe: market.timeframe.clone(),
+            direction: market.direction.clone(),
+            strike_price: market.strike_price,
+            end_time: market.end_time,
+        }
+    }
+}
+
+/// A memoized score for one (Polymarket, Limitless) pair.
+#[derive(Debug, Clone)]
+struct CachedScore {
+    poly: MarketFingerprint,
+    other: MarketFingerprint,
+    score: f64,
+    /// Only generated for scores at or above the confidence threshold
+    reason: Option<String>,
+}
+
+/// Hit/miss counts from the most recent cached match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchCacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    /// Entries dropped because their markets left the input set
+    pub evicted: usize,
+}
+
 /// Event matcher for cross-platform arbitrage.
 pub struct EventMatcher {
     config: MatcherConfig,
+    /// Scores keyed by (polymarket market_id, other market_id)
+    cache: Mutex<HashMap<(String, String), CachedScore>>,
 }
 
 impl EventMatcher {
@@ -67,7 +116,10 @@ impl EventMatcher {
 
     /// Create a new event matcher with custom configuration.
     pub fn with_config(config: MatcherConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            cache: Mutex::new(HashMap::new()),
+        }
     }
 
     /// Extract entity information from a unified market.
@@ -252,8 +304,6 @@ impl EventMatcher {
         polymarket: &[UnifiedMarket],
         kalshi: &[UnifiedMarket],
     ) -> Vec<MarketPair> {
-        let mut matches = Vec::new();
-
         info!(
             "Matching {} Polymarket markets against {} Limitless markets",
             polymarket.len(),
@@ -261,41 +311,125 @@ impl EventMatcher {
         );
 
         // Extract entities for all markets
-        let poly_entities: Vec<(usize, MarketEntity)> = polymarket
-            .iter()
-            .enumerate()
-            .map(|(i, m)| (i, self.extract_entity(m)))
-            .collect();
+        let poly_entities: Vec<MarketEntity> =
+            polymarket.iter().map(|m| self.extract_entity(m)).collect();
+        let kalshi_entities: Vec<MarketEntity> =
+            kalshi.iter().map(|m| self.extract_entity(m)).collect();
 
-        let kalshi_entities: Vec<(usize, MarketEntity)> = kalshi
-            .iter()
-            .enumerate()
-            .map(|(i, m)| (i, self.extract_entity(m)))
-            .collect();
+        let matches = self.best_matches(polymarket, kalshi, |poly_idx, kalshi_idx| {
+            self.score_with_reason(&poly_entities[poly_idx], &kalshi_entities[kalshi_idx])
+        });
+
+        info!("Found {} high-confidence matches", matches.len());
+        matches
+    }
 
-        // Find best matches
-        for (poly_idx, poly_entity) in &poly_entities {
+    /// Like [`match_markets`](Self::match_markets), but reuses scores from
+    /// earlier calls for pairs whose markets are unchanged.
+    ///
+    /// Entries are invalidated when either market's name, timing or terms
+    /// change, and dropped once a market leaves the input set.
+    pub fn match_markets_cached(
+        &self,
+        polymarket: &[UnifiedMarket],
+        kalshi: &[UnifiedMarket],
+    ) -> (Vec<MarketPair>, MatchCacheStats) {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats = MatchCacheStats::default();
+
+        // Forget pairs whose markets are no longer listed
+        let before = cache.len();
+        cache.retain(|(poly_id, kalshi_id), _| {
+            polymarket.iter().any(|m| &m.market_id == poly_id)
+                && kalshi.iter().any(|m| &m.market_id == kalshi_id)
+        });
+        stats.evicted = before - cache.len();
+
+        let poly_prints: Vec<MarketFingerprint> =
+            polymarket.iter().map(MarketFingerprint::of).collect();
+        let kalshi_prints: Vec<MarketFingerprint> =
+            kalshi.iter().map(MarketFingerprint::of).collect();
+
+        // Entities are only extracted for markets involved in a cache miss
+        let mut poly_entities: Vec<Option<MarketEntity>> = vec![None; polymarket.len()];
+        let mut kalshi_entities: Vec<Option<MarketEntity>> = vec![None; kalshi.len()];
+
+        let matches = self.best_matches(polymarket, kalshi, |poly_idx, kalshi_idx| {
+            let key = (
+                polymarket[poly_idx].market_id.clone(),
+                kalshi[kalshi_idx].market_id.clone(),
+            );
+            if let Some(cached) = cache.get(&key) {
+                if cached.poly == poly_prints[poly_idx] && cached.other == kalshi_prints[kalshi_idx]
+                {
+                    stats.hits += 1;
+                    return (cached.score, cached.reason.clone());
+                }
+            }
+
+            stats.misses += 1;
+            let poly_entity = poly_entities[poly_idx]
+                .get_or_insert_with(|| self.extract_entity(&polymarket[poly_idx]));
+            let kalshi_entity = kalshi_entities[kalshi_idx]
+                .get_or_insert_with(|| self.extract_entity(&kalshi[kalshi_idx]));
+            let (score, reason) = self.score_with_reason(poly_entity, kalshi_entity);
+            cache.insert(
+                key,
+                CachedScore {
+                    poly: poly_prints[poly_idx].clone(),
+                    other: kalshi_prints[kalshi_idx].clone(),
+                    score,
+                    reason: reason.clone(),
+                },
+            );
+            (score, reason)
+        });
+
+        debug!(
+            "Match cache: {} hits, {} misses, {} evicted",
+            stats.hits, stats.misses, stats.evicted
+        );
+        (matches, stats)
+    }
+
+    /// Score a pair, generating a reason only when it clears the threshold.
+    fn score_with_reason(&self, a: &MarketEntity, b: &MarketEntity) -> (f64, Option<String>) {
+        let score = self.score_match(a, b);
+        let reason =
+            (score >= self.config.min_confidence).then(|| self.generate_match_reason(a, b, score));
+        (score, reason)
+    }
+
+    /// Pick the best-scoring counterpart for each Polymarket market.
+    fn best_matches(
+        &self,
+        polymarket: &[UnifiedMarket],
+        kalshi: &[UnifiedMarket],
+        mut score_pair: impl FnMut(usize, usize) -> (f64, Option<String>),
+    ) -> Vec<MarketPair> {
+        let mut matches = Vec::new();
+
+        for (poly_idx, poly) in polymarket.iter().enumerate() {
             let mut best_match: Option<(usize, f64, String)> = None;
 
-            for (kalshi_idx, kalshi_entity) in &kalshi_entities {
+            for (kalshi_idx, other) in kalshi.iter().enumerate() {
                 // Quick filter: same asset
-                if poly_entity.asset != kalshi_entity.asset {
+                if poly.asset != other.asset {
                     continue;
                 }
 
-                let score = self.score_match(poly_entity, kalshi_entity);
+                let (score, reason) = score_pair(poly_idx, kalshi_idx);
 
-                if score >= self.config.min_confidence {
+                if let Some(reason) = reason {
                     if best_match.as_ref().map_or(true, |(_, s, _)| score > *s) {
-                        let reason = self.generate_match_reason(poly_entity, kalshi_entity, score);
-                        best_match = Some((*kalshi_idx, score, reason));
+                        best_match = Some((kalshi_idx, score, reason));
                     }
                 }
             }
 
             if let Some((kalshi_idx, score, reason)) = best_match {
                 matches.push(MarketPair::new(
-                    polymarket[*poly_idx].clone(),
+                    poly.clone(),
                     kalshi[kalshi_idx].clone(),
                     score,
                     reason,
@@ -303,7 +437,6 @@ impl EventMatcher {
             }
         }
 
-        info!("Found {} high-confidence matches", matches.len());
         matches
     }
 
@@ -471,4 +604,103 @@ mod tests {
         assert_eq!(matches[0].polymarket.asset, "BTC");
         assert_eq!(matches[0].kalshi.asset, "BTC");
     }
+
+    fn with_id(mut market: UnifiedMarket, id: &str) -> UnifiedMarket {
+        market.market_id = id.to_string();
+        market
+    }
+
+    fn pair_summary(pairs: &[MarketPair]) -> Vec<(String, String, f64, String)> {
+        pairs
+            .iter()
+            .map(|p| {
+                (
+                    p.polymarket.market_id.clone(),
+                    p.kalshi.market_id.clone(),
+                    p.confidence,
+                    p.match_reason.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_match_markets_cached_equals_uncached() {
+        let matcher = EventMatcher::new();
+        let end_time = Utc::now() + Duration::hours(1);
+
+        let poly_markets = vec![
+            with_id(
+                create_test_market(Platform::Polymarket, "BTC", "15m", end_time, Some("up")),
+                "poly-btc",
+            ),
+            with_id(
+                create_test_market(Platform::Polymarket, "ETH", "1h", end_time, Some("down")),
+                "poly-eth",
+            ),
+        ];
+        let limitless_markets = vec![
+            with_id(
+                create_test_market(Platform::Limitless, "BTC", "15m", end_time, Some("above")),
+                "lim-btc",
+            ),
+            with_id(
+                create_test_market(Platform::Limitless, "ETH", "1h", end_time, Some("below")),
+                "lim-eth",
+            ),
+            with_id(
+                create_test_market(Platform::Limitless, "SOL", "15m", end_time, Some("up")),
+                "lim-sol",
+            ),
+        ];
+
+        let uncached = matcher.match_markets(&poly_markets, &limitless_markets);
+        assert_eq!(uncached.len(), 2);
+
+        // First pass populates the cache
+        let (first, stats) = matcher.match_markets_cached(&poly_markets, &limitless_markets);
+        assert_eq!(pair_summary(&first), pair_summary(&uncached));
+        assert_eq!(stats.hits, 0);
+        assert_eq!(stats.misses, 2);
+
+        // Unchanged input is served entirely from the cache
+        let (second, stats) = matcher.match_markets_cached(&poly_markets, &limitless_markets);
+        assert_eq!(pair_summary(&second), pair_summary(&uncached));
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 0);
+    }
+
+    #[test]
+    fn test_match_cache_invalidation() {
+        let matcher = EventMatcher::new();
+        let end_time = Utc::now() + Duration::hours(1);
+
+        let poly_markets = vec![with_id(
+            create_test_market(Platform::Polymarket, "BTC", "15m", end_time, Some("up")),
+            "poly-btc",
+        )];
+        let mut limitless_markets = vec![with_id(
+            create_test_market(Platform::Limitless, "BTC", "15m", end_time, Some("up")),
+            "lim-btc",
+        )];
+        matcher.match_markets_cached(&poly_markets, &limitless_markets);
+
+        // A renamed market is rescored
+        limitless_markets[0].name = "BTC Up or Down (renamed)".to_string();
+        let (_, stats) = matcher.match_markets_cached(&poly_markets, &limitless_markets);
+        assert_eq!((stats.hits, stats.misses), (0, 1));
+
+        // Rescheduled end time drops the pair below the threshold
+        limitless_markets[0].end_time = end_time + Duration::hours(2);
+        let (matches, stats) = matcher.match_markets_cached(&poly_markets, &limitless_markets);
+        assert_eq!((stats.hits, stats.misses), (0, 1));
+        assert_eq!(
+            pair_summary(&matches),
+            pair_summary(&matcher.match_markets(&poly_markets, &limitless_markets))
+        );
+
+        // Markets that leave the set are evicted
+        let (_, stats) = matcher.match_markets_cached(&poly_markets, &[]);
+        assert_eq!(stats.evicted, 1);
+    }
 }
@@ -0,0 +1,189 @@
+//! Real-time Limitless price feed for the arb detector.
+//!
+//! Subscribes to the Limitless orderbook WebSocket for the markets the
+//! detector can match against and writes each top-of-book update through
+//! `update_limitless_prices`, so Limitless prices are as fresh as the
+//! Polymarket prices written by orderbook-stream.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use common::{
+    get_markets_by_platform, run_limitless_orderbook_stream, update_limitless_prices, Database,
+    LimitlessOrderbookUpdate, MarketWithPlatform, Platform,
+};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Map Limitless market slugs to DB ids for the markets worth streaming:
+/// requested assets, expiring within `max_expiry_secs` of `now`.
+pub fn subscription_map(
+    markets: &[MarketWithPlatform],
+    assets: &[String],
+    max_expiry_secs: i64,
+    now: DateTime<Utc>,
+) -> HashMap<String, Uuid> {
+    markets
+        .iter()
+        .filter(|m| assets.iter().any(|a| a.eq_ignore_ascii_case(&m.asset)))
+        .filter(|m| m.end_time > now && (m.end_time - now).num_seconds() <= max_expiry_secs)
+        .map(|m| (m.condition_id.clone(), m.id))
+        .collect()
+}
+
+/// Stream Limitless prices into the DB until the task is aborted.
+///
+/// The market list is reloaded every `refresh_interval` so newly listed
+/// markets get subscribed; the WebSocket itself reconnects after
+/// `reconnect_interval` like the other stream consumers.
+pub async fn run(
+    db: Database,
+    assets: Vec<String>,
+    max_expiry_secs: i64,
+    refresh_interval: Duration,
+    reconnect_interval: Duration,
+) {
+    loop {
+        let markets = match get_markets_by_platform(db.pool(), Platform::Limitless).await {
+            Ok(markets) => markets,
+            Err(e) => {
+                error!("[LIMITLESS-WS] Failed to load Limitless markets: {}", e);
+                sleep(reconnect_interval).await;
+                continue;
+            }
+        };
+
+        let slug_to_db_id = subscription_map(&markets, &assets, max_expiry_secs, Utc::now());
+        if slug_to_db_id.is_empty() {
+            warn!("[LIMITLESS-WS] No Limitless markets to subscribe to");
+            sleep(refresh_interval).await;
+            continue;
+        }
+
+        info!(
+            "[LIMITLESS-WS] Subscribing to {} Limitless markets",
+            slug_to_db_id.len()
+        );
+        stream_until_refresh(&db, &slug_to_db_id, refresh_interval, reconnect_interval).await;
+    }
+}
+
+/// Apply updates for one subscription set until it is due for a refresh or
+/// the WebSocket task exits.
+async fn stream_until_refresh(
+    db: &Database,
+    slug_to_db_id: &HashMap<String, Uuid>,
+    refresh_interval: Duration,
+    reconnect_interval: Duration,
+) {
+    let (tx, mut rx) = mpsc::channel::<LimitlessOrderbookUpdate>(1000);
+    let slugs: Vec<String> = slug_to_db_id.keys().cloned().collect();
+
+    let ws_handle = tokio::spawn(run_limitless_orderbook_stream(
+        slugs,
+        tx,
+        reconnect_interval,
+    ));
+
+    let started = Instant::now();
+    let mut update_count = 0u64;
+
+    while started.elapsed() < refresh_interval {
+        if ws_handle.is_finished() {
+            error!("[LIMITLESS-WS] WebSocket task has exited");
+            break;
+        }
+
+        match tokio::time::timeout(Duration::from_secs(30), rx.recv()).await {
+            Ok(Some(update)) => {
+                let market_id = match slug_to_db_id.get(&update.slug) {
+                    Some(&id) => id,
+                    None => continue,
+                };
+                update_count += 1;
+                if let Err(e) = update_limitless_prices(
+                    db.pool(),
+                    market_id,
+                    update.yes_best_ask,
+                    update.yes_best_bid,
+                    update.no_best_ask,
+                    update.no_best_bid,
+                )
+                .await
+                {
+                    warn!(
+                        "[LIMITLESS-WS] Failed to update prices for {}: {}",
+                        update.slug, e
+                    );
+                }
+            }
+            Ok(None) => {
+                error!("[LIMITLESS-WS] Update channel closed");
+                break;
+            }
+            Err(_) => {
+                warn!("[LIMITLESS-WS] No updates received for 30 seconds");
+            }
+        }
+    }
+
+    info!(
+        "[LIMITLESS-WS] {} price updates applied, refreshing subscriptions",
+        update_count
+    );
+    ws_handle.abort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn market(slug: &str, asset: &str, end_time: DateTime<Utc>) -> MarketWithPlatform {
+        MarketWithPlatform {
+            id: Uuid::new_v4(),
+            platform: "limitless".to_string(),
+            condition_id: slug.to_string(),
+            market_type: "up_down".to_string(),
+            asset: asset.to_string(),
+            timeframe: "1h".to_string(),
+            yes_token_id: String::new(),
+            no_token_id: String::new(),
+            name: slug.to_string(),
+            end_time,
+            is_active: true,
+            direction: None,
+            strike_price: None,
+            liquidity_dollars: None,
+            yes_best_ask: None,
+            yes_best_bid: None,
+            no_best_ask: None,
+            no_best_bid: None,
+            captured_at: None,
+        }
+    }
+
+    #[test]
+    fn test_subscription_map_filters_asset_and_expiry() {
+        let now = Utc::now();
+        let markets = vec![
+            market("btc-1h", "BTC", now + ChronoDuration::minutes(30)),
+            market("eth-1h", "eth", now + ChronoDuration::minutes(45)),
+            // Asset not requested
+            market("doge-1h", "DOGE", now + ChronoDuration::minutes(30)),
+            // Beyond the expiry window
+            market("btc-daily", "BTC", now + ChronoDuration::hours(5)),
+            // Already expired
+            market("btc-old", "BTC", now - ChronoDuration::minutes(1)),
+        ];
+        let assets = vec!["BTC".to_string(), "ETH".to_string()];
+
+        let map = subscription_map(&markets, &assets, 7200, now);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("btc-1h"), Some(&markets[0].id));
+        assert_eq!(map.get("eth-1h"), Some(&markets[1].id));
+    }
+}
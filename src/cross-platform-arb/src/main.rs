@@ -1,33 +1,49 @@
 //! Cross-Platform Arbitrage Service
 //!
 //! Detects arbitrage opportunities between Polymarket and Limitless prediction markets.
-//! Currently in detection-only mode (no auto-execution).
+//! Detection-only unless `--execute` is passed, in which case both legs of each
+//! sized opportunity are placed (see `arb_executor`).
 //!
 //! Architecture:
 //! - Polymarket: Uses existing market data from DB (orderbook-stream WebSocket)
-//! - Limitless: REST polling for prices via limitless-loader service
+//! - Limitless: REST polling for prices via limitless-loader service, or
+//!   WebSocket streaming with `--limitless-ws`
 //! - Matching: Entity extraction + scoring algorithm
 //! - Detection: Cross-platform spread calculation with fee adjustment
+//! - Execution: Two-legged orders with unwind on a rejected leg (`--execute`)
 
+mod arb_executor;
 mod detector;
 mod event_matcher;
+mod limitless_feed;
 mod slippage;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use chrono::Utc;
 use clap::Parser;
 use common::{
-    get_latest_orderbook_snapshot, get_platform_markets_with_prices, update_polymarket_prices,
-    Config, CrossPlatformOpportunity, Database, GammaClient, MarketWithPlatform, Platform,
-    UnifiedMarket,
+    expire_stale_cross_platform_matches, get_latest_orderbook_snapshot,
+    get_platform_markets_with_prices, record_trade_audit, synthesize_depth_from_best,
+    update_polymarket_prices, upsert_cross_platform_match, Config, CrossPlatformMatchInsert,
+    CrossPlatformOpportunity, Database, GammaApi, GammaClient, LimitlessTrader, MarketPair,
+    MarketWithPlatform, Platform, UnifiedMarket,
 };
 use rust_decimal::Decimal;
 use tokio::time::sleep;
 use tracing::{error, info, warn};
-use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
 
-use detector::{CrossPlatformDetector, DetectorConfig, ScanSummary};
+use arb_executor::{
+    build_legs, order_legs, ArbExecutor, ArbOutcome, LimitlessVenue, PolymarketVenue,
+};
+use detector::{
+    fmt_price_age, price_age, rank_opportunities, CrossPlatformDetector, DetectorConfig, RankKey,
+    ScanSummary,
+};
 use event_matcher::{EventMatcher, MatcherConfig};
 use slippage::{calculate_max_profitable_size, parse_polymarket_depth};
 
@@ -60,6 +76,12 @@ struct Args {
     #[arg(long, default_value = "30")]
     max_orderbook_age: i32,
 
+    /// Hold both legs to --max-orderbook-age: no extra slack for REST-fetched
+    /// Polymarket or polled Limitless prices, and any pair with a leg past the
+    /// bound is rejected
+    #[arg(long)]
+    require_both_fresh: bool,
+
     /// Maximum time to expiry in seconds
     #[arg(long, default_value = "7200")]
     max_expiry_secs: i64,
@@ -72,19 +94,85 @@ struct Args {
     #[arg(long, default_value = "BTC,ETH,SOL,XRP")]
     assets: String,
 
+    /// Invalidate cached matches not re-confirmed within this many seconds
+    #[arg(long, default_value = "3600")]
+    match_max_age_secs: i64,
+
+    /// Rank opportunities by this field, best first
+    /// (net-profit-pct, max-investment, profit)
+    #[arg(long, default_value = "net-profit-pct")]
+    sort_by: RankKey,
+
+    /// Only log the top N ranked opportunities each cycle
+    #[arg(long)]
+    top: Option<usize>,
+
+    /// Re-score every market pair each cycle instead of reusing cached scores
+    #[arg(long)]
+    no_match_cache: bool,
+
+    /// Stream Limitless prices over WebSocket instead of relying on REST polling
+    #[arg(long)]
+    limitless_ws: bool,
+
+    /// Limitless WebSocket reconnect interval in seconds
+    #[arg(long, default_value = "60")]
+    limitless_reconnect_interval: u64,
+
+    /// Re-subscribe to newly listed Limitless markets every N seconds
+    #[arg(long, default_value = "300")]
+    limitless_refresh_interval: u64,
+
+    /// Place both legs of each sized opportunity (LIVE TRADING)
+    #[arg(long)]
+    execute: bool,
+
+    /// Maximum contracts per leg when executing
+    #[arg(long, default_value = "10")]
+    max_arb_contracts: u64,
+
+    /// Price levels in the synthetic ladder built from Kalshi's best level
+    /// (Kalshi reports no depth). 1 assumes all liquidity sits at the best price.
+    #[arg(long, default_value = "5")]
+    kalshi_depth_levels: usize,
+
+    /// Price gap between synthetic Kalshi levels; wider is more conservative
+    #[arg(long, default_value = "0.01")]
+    kalshi_depth_step: f64,
+
     /// Verbose logging
     #[arg(long, short)]
     verbose: bool,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging with RUST_LOG env filter
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
+    if args.top == Some(0) {
+        bail!("--top must be at least 1");
+    }
+    if args.limitless_ws
+        && (args.limitless_reconnect_interval == 0 || args.limitless_refresh_interval == 0)
+    {
+        bail!("--limitless-reconnect-interval and --limitless-refresh-interval must be at least 1");
+    }
+    if args.execute && args.max_arb_contracts == 0 {
+        bail!("--max-arb-contracts must be at least 1");
+    }
+    if args.kalshi_depth_levels == 0 {
+        bail!("--kalshi-depth-levels must be at least 1");
+    }
+    if args.kalshi_depth_step <= 0.0 || args.kalshi_depth_step >= 1.0 {
+        bail!(
+            "--kalshi-depth-step must be between 0 and 1 (got {})",
+            args.kalshi_depth_step
+        );
+    }
 
     info!("Cross-Platform Arbitrage Detector starting...");
     info!(
@@ -101,6 +189,16 @@ async fn main() -> Result<()> {
         args.min_profit, args.min_profit_15m
     );
     info!("Assets: {}", args.assets);
+    if args.require_both_fresh {
+        info!(
+            "Freshness: both legs must be within {}s",
+            args.max_orderbook_age
+        );
+    }
+    match args.top {
+        Some(top) => info!("Showing top {} opportunities by {}", top, args.sort_by),
+        None => info!("Ranking opportunities by {}", args.sort_by),
+    }
 
     // Parse assets
     let assets: Vec<String> = args
@@ -135,13 +233,59 @@ async fn main() -> Result<()> {
         min_liquidity: Decimal::try_from(args.min_liquidity)?,
         max_price_staleness: args.max_orderbook_age as i64,
         min_match_confidence: args.min_match_confidence,
+        require_both_fresh: args.require_both_fresh,
         ..Default::default()
     };
     let detector = CrossPlatformDetector::with_config(detector_config);
 
+    // Stream Limitless prices in the background so they are as fresh as Polymarket's
+    let limitless_handle = if args.limitless_ws {
+        info!("Limitless prices: WebSocket streaming");
+        let feed = limitless_feed::run(
+            db.clone(),
+            assets.clone(),
+            args.max_expiry_secs,
+            Duration::from_secs(args.limitless_refresh_interval),
+            Duration::from_secs(args.limitless_reconnect_interval),
+        );
+        let handle = tokio::spawn(feed);
+        if args.once {
+            // Give the stream a chance to deliver a first round of prices
+            sleep(Duration::from_secs(5)).await;
+        }
+        Some(handle)
+    } else {
+        info!("Limitless prices: REST polling (limitless-loader)");
+        None
+    };
+
+    let mut executor = if args.execute {
+        warn!(
+            "LIVE EXECUTION ENABLED - up to {} contracts per leg",
+            args.max_arb_contracts
+        );
+        Some(ArbExecutor::new(
+            Box::new(PolymarketVenue::new(Decimal::from(args.max_arb_contracts))),
+            Box::new(LimitlessVenue::new(LimitlessTrader::from_env()?)),
+        ))
+    } else {
+        info!("Execution: disabled (detection only)");
+        None
+    };
+
     // Main loop
     loop {
-        match scan_cycle(&db, &gamma, &matcher, &detector, &assets, &args).await {
+        match scan_cycle(
+            &db,
+            &gamma,
+            &matcher,
+            &detector,
+            executor.as_mut(),
+            &assets,
+            &args,
+        )
+        .await
+        {
             Ok(summary) => {
                 summary.log();
             }
@@ -159,15 +303,20 @@ async fn main() -> Result<()> {
         sleep(Duration::from_secs(args.interval)).await;
     }
 
+    if let Some(handle) = limitless_handle {
+        handle.abort();
+    }
+
     Ok(())
 }
 
 /// Perform a single scan cycle.
 async fn scan_cycle(
     db: &Database,
-    gamma: &GammaClient,
+    gamma: &dyn GammaApi,
     matcher: &EventMatcher,
     detector: &CrossPlatformDetector,
+    executor: Option<&mut ArbExecutor>,
     assets: &[String],
     args: &Args,
 ) -> Result<ScanSummary> {
@@ -177,7 +326,7 @@ async fn scan_cycle(
     info!("Fetching Polymarket markets with fresh orderbooks...");
     let mut polymarket_markets = get_platform_markets_with_prices(
         db.pool(),
-        "polymarket",
+        Platform::Polymarket,
         args.max_orderbook_age,
         assets,
         args.max_expiry_secs,
@@ -196,11 +345,17 @@ async fn scan_cycle(
         {
             warn!("Failed to fetch Polymarket prices via REST: {}", e);
         }
-        // Try fetching again with the new prices
+        // Try fetching again with the new prices, allowing slightly more
+        // staleness for REST unless both legs must be fresh
+        let rest_max_age = if args.require_both_fresh {
+            args.max_orderbook_age
+        } else {
+            args.max_orderbook_age + 5
+        };
         polymarket_markets = get_platform_markets_with_prices(
             db.pool(),
-            "polymarket",
-            args.max_orderbook_age + 5, // Allow slightly more staleness for REST
+            Platform::Polymarket,
+            rest_max_age,
             assets,
             args.max_expiry_secs,
         )
@@ -211,11 +366,17 @@ async fn scan_cycle(
         );
     }
 
-    // Step 2: Fetch Limitless markets from DB
+    // Step 2: Fetch Limitless markets from DB. Streamed prices are held to the
+    // same staleness bound as Polymarket; REST-polled ones get extra slack.
+    let limitless_max_age = if args.limitless_ws || args.require_both_fresh {
+        args.max_orderbook_age
+    } else {
+        args.max_orderbook_age + 10
+    };
     let limitless_db_markets = get_platform_markets_with_prices(
         db.pool(),
-        "limitless",
-        args.max_orderbook_age + 10,
+        Platform::Limitless,
+        limitless_max_age,
         assets,
         args.max_expiry_secs,
     )
@@ -242,27 +403,60 @@ async fn scan_cycle(
         poly_unified.len(),
         limitless_unified.len()
     );
-    let matches = matcher.match_markets(&poly_unified, &limitless_unified);
-    info!("Found {} high-confidence matches", matches.len());
+    let match_started = Instant::now();
+    let matches = if args.no_match_cache {
+        let matches = matcher.match_markets(&poly_unified, &limitless_unified);
+        info!(
+            "Found {} high-confidence matches in {:.1}ms (cache disabled)",
+            matches.len(),
+            match_started.elapsed().as_secs_f64() * 1000.0
+        );
+        matches
+    } else {
+        let (matches, cache_stats) =
+            matcher.match_markets_cached(&poly_unified, &limitless_unified);
+        info!(
+            "Found {} high-confidence matches in {:.1}ms ({} cached, {} scored)",
+            matches.len(),
+            match_started.elapsed().as_secs_f64() * 1000.0,
+            cache_stats.hits,
+            cache_stats.misses
+        );
+        matches
+    };
+
+    // Re-confirm this cycle's pairs, then drop cached matches whose markets
+    // are gone or that the matcher no longer produces
+    confirm_matches(db, &matches).await;
+    match expire_stale_cross_platform_matches(db.pool(), args.match_max_age_secs).await {
+        Ok(0) => {}
+        Ok(n) => info!("Invalidated {} stale cross-platform matches", n),
+        Err(e) => warn!("Failed to expire stale cross-platform matches: {}", e),
+    }
 
     // Step 5: Detect arbitrage opportunities
     let mut opportunities = detector.scan(&matches);
 
     // Step 6: Calculate max profitable size for each opportunity
     for opp in &mut opportunities {
-        if let Some(sized_opp) = calculate_opportunity_size(db, opp, args.min_profit_15m).await {
+        if let Some(sized_opp) = calculate_opportunity_size(db, opp, args).await {
             *opp = sized_opp;
         }
     }
 
+    // Step 7: Rank so the best opportunities are logged first
+    rank_opportunities(&mut opportunities, args.sort_by);
+    let shown = args.top.unwrap_or(opportunities.len());
+
     // Log opportunities
-    for (i, opp) in opportunities.iter().enumerate() {
+    let now = Utc::now();
+    for (i, opp) in opportunities.iter().take(shown).enumerate() {
         let size_info = match (opp.max_contracts, opp.max_investment) {
             (Some(c), Some(inv)) => format!(" | Max: {} contracts (${:.0})", c, inv),
             _ => String::new(),
         };
         info!(
-            "Opportunity #{}: {} vs {} | Buy YES on {} @ {} + NO on {} @ {} = {} | Net: {:.2}%{}",
+            "Opportunity #{}: {} vs {} | Buy YES on {} @ {} + NO on {} @ {} = {} | Net: {:.2}%{} | Age: Polymarket {}, Limitless {}",
             i + 1,
             opp.pair.polymarket.name,
             opp.pair.kalshi.name, // Note: This is actually Limitless market (field name kept for compatibility)
@@ -272,10 +466,64 @@ async fn scan_cycle(
             opp.no_price,
             opp.total_cost,
             opp.net_profit_pct,
-            size_info
+            size_info,
+            fmt_price_age(price_age(&opp.pair.polymarket, now)),
+            fmt_price_age(price_age(&opp.pair.kalshi, now))
+        );
+    }
+    if opportunities.len() > shown {
+        info!(
+            "{} more opportunities not shown (--top {})",
+            opportunities.len() - shown,
+            shown
         );
     }
 
+    // Step 8: Execute, best first, skipping pairs already attempted
+    if let Some(executor) = executor {
+        let tokens: HashMap<_, _> = polymarket_markets
+            .iter()
+            .chain(&limitless_db_markets)
+            .map(|m| (m.id, (m.yes_token_id.clone(), m.no_token_id.clone())))
+            .collect();
+        for opp in &opportunities {
+            if executor.already_attempted(opp) {
+                continue;
+            }
+            let (yes, no) = match build_legs(opp, &tokens, args.max_arb_contracts) {
+                Some(legs) => legs,
+                None => continue,
+            };
+            let (first, second) = order_legs(yes.clone(), no.clone());
+            let intents = [first.intent(), second.intent()];
+            for intent in &intents {
+                info!("[INTENT] {}", intent);
+            }
+            let outcome = executor.execute(opp, yes, no).await;
+            let (first_outcome, second_outcome) = outcome.leg_outcomes();
+            record_trade_audit(db.pool(), &intents[0], first_outcome).await;
+            record_trade_audit(db.pool(), &intents[1], second_outcome).await;
+            match outcome {
+                ArbOutcome::Hedged {
+                    first_order,
+                    second_order,
+                } => info!(
+                    "[ARB] Hedged {} vs {}: orders {} + {}",
+                    opp.pair.polymarket.name, opp.pair.kalshi.name, first_order, second_order
+                ),
+                ArbOutcome::Aborted { reason } => info!("[ARB] Aborted: {}", reason),
+                ArbOutcome::Unwound {
+                    reason,
+                    unwind_order,
+                } => warn!("[ARB] Unwound ({}): {}", unwind_order, reason),
+                ArbOutcome::Exposed { reason } => error!(
+                    "[ARB] EXPOSED on {} vs {}: {}",
+                    opp.pair.polymarket.name, opp.pair.kalshi.name, reason
+                ),
+            }
+        }
+    }
+
     Ok(ScanSummary::new(
         poly_unified.len(),
         limitless_unified.len(),
@@ -284,6 +532,33 @@ async fn scan_cycle(
     ))
 }
 
+/// Upsert the matcher's pairs so still-valid matches refresh `validated_at`
+/// and survive the age-based expiry.
+async fn confirm_matches(db: &Database, matches: &[MarketPair]) {
+    for pair in matches {
+        let (Some(polymarket_id), Some(kalshi_id)) = (pair.polymarket.db_id, pair.kalshi.db_id)
+        else {
+            continue;
+        };
+        // The counterparty leg is stored as kalshi_id, like MarketPair.kalshi
+        let insert = CrossPlatformMatchInsert {
+            polymarket_id,
+            kalshi_id,
+            match_confidence: Decimal::try_from(pair.confidence).unwrap_or_default(),
+            match_reason: Some(pair.match_reason.clone()),
+            entity_asset: Some(pair.polymarket.asset.clone()),
+            entity_timeframe: Some(pair.polymarket.timeframe.clone()),
+            entity_direction: pair.polymarket.direction.clone(),
+        };
+        if let Err(e) = upsert_cross_platform_match(db.pool(), &insert).await {
+            warn!(
+                "Failed to confirm match {} vs {}: {}",
+                pair.polymarket.name, pair.kalshi.name, e
+            );
+        }
+    }
+}
+
 /// Convert MarketWithPlatform to UnifiedMarket.
 fn to_unified_market(m: &MarketWithPlatform, platform: Platform) -> Option<UnifiedMarket> {
     // Skip markets without prices
@@ -316,56 +591,51 @@ fn to_unified_market(m: &MarketWithPlatform, platform: Platform) -> Option<Unifi
 /// This is a fallback when WebSocket data is stale.
 async fn update_polymarket_prices_from_gamma(
     db: &Database,
-    gamma: &GammaClient,
+    gamma: &dyn GammaApi,
     assets: &[String],
     _max_expiry_secs: i64,
 ) -> Result<()> {
-    use common::get_market_by_condition_id;
+    use common::get_markets_by_condition_ids;
 
     // Fetch all crypto markets from Gamma API (includes prices)
     let parsed_markets = gamma.fetch_supported_markets().await?;
 
-    // Filter to relevant markets and update prices
-    let mut updated_count = 0;
+    // Filter to relevant markets with prices
+    let priced: Vec<_> = parsed_markets
+        .iter()
+        .filter(|m| assets.iter().any(|a| a.eq_ignore_ascii_case(&m.asset)))
+        .filter(|m| m.timeframe == "15m" || m.timeframe == "5m")
+        .filter(|m| m.yes_best_ask.is_some() || m.yes_best_bid.is_some())
+        .collect();
 
-    for market in &parsed_markets {
-        // Filter by asset and timeframe
-        if !assets.iter().any(|a| a.eq_ignore_ascii_case(&market.asset)) {
-            continue;
-        }
-        if market.timeframe != "15m" && market.timeframe != "5m" {
-            continue;
-        }
+    // Look up all markets in the database in one query; markets not in the
+    // DB yet are skipped
+    let condition_ids: Vec<String> = priced.iter().map(|m| m.condition_id.clone()).collect();
+    let db_ids: HashMap<String, Uuid> = get_markets_by_condition_ids(db.pool(), &condition_ids)
+        .await?
+        .into_iter()
+        .filter(|m| m.platform == Platform::Polymarket.as_str())
+        .map(|m| (m.condition_id, m.id))
+        .collect();
 
-        // Skip if no prices
-        if market.yes_best_ask.is_none() && market.yes_best_bid.is_none() {
+    let mut updated_count = 0;
+    for market in priced {
+        let Some(&market_id) = db_ids.get(&market.condition_id) else {
             continue;
-        }
-
-        // Look up market in database by condition_id
-        match get_market_by_condition_id(db.pool(), &market.condition_id).await {
-            Ok(Some(db_market)) => {
-                if let Err(e) = update_polymarket_prices(
-                    db.pool(),
-                    db_market.id,
-                    market.yes_best_ask,
-                    market.yes_best_bid,
-                    market.no_best_ask,
-                    market.no_best_bid,
-                )
-                .await
-                {
-                    warn!("Failed to update prices for {}: {}", market.name, e);
-                } else {
-                    updated_count += 1;
-                }
-            }
-            Ok(None) => {
-                // Market not in DB yet, skip
-            }
-            Err(e) => {
-                warn!("Failed to lookup market {}: {}", market.condition_id, e);
-            }
+        };
+        if let Err(e) = update_polymarket_prices(
+            db.pool(),
+            market_id,
+            market.yes_best_ask,
+            market.yes_best_bid,
+            market.no_best_ask,
+            market.no_best_bid,
+        )
+        .await
+        {
+            warn!("Failed to update prices for {}: {}", market.name, e);
+        } else {
+            updated_count += 1;
         }
     }
 
@@ -378,7 +648,7 @@ async fn update_polymarket_prices_from_gamma(
 async fn calculate_opportunity_size(
     db: &Database,
     opp: &CrossPlatformOpportunity,
-    min_profit_pct: f64,
+    args: &Args,
 ) -> Option<CrossPlatformOpportunity> {
     // Determine which market provides YES and which provides NO
     let (yes_market, no_market) = if opp.buy_yes_on == Platform::Polymarket {
@@ -388,7 +658,7 @@ async fn calculate_opportunity_size(
     };
 
     // Fetch YES depth
-    let yes_depth = match fetch_market_depth(db, yes_market, "yes").await {
+    let yes_depth = match fetch_market_depth(db, yes_market, "yes", args).await {
         Some(d) => d,
         None => {
             warn!(
@@ -400,7 +670,7 @@ async fn calculate_opportunity_size(
     };
 
     // Fetch NO depth
-    let no_depth = match fetch_market_depth(db, no_market, "no").await {
+    let no_depth = match fetch_market_depth(db, no_market, "no", args).await {
         Some(d) => d,
         None => {
             warn!(
@@ -412,11 +682,14 @@ async fn calculate_opportunity_size(
     };
 
     // Calculate max profitable size
-    let min_profit = Decimal::try_from(min_profit_pct).ok()?;
-    let yes_fee = opp.buy_yes_on.fee_rate();
-    let no_fee = opp.buy_no_on.fee_rate();
-
-    let result = calculate_max_profitable_size(&yes_depth, &no_depth, yes_fee, no_fee, min_profit)?;
+    let min_profit = Decimal::try_from(args.min_profit_15m).ok()?;
+    let result = calculate_max_profitable_size(
+        &yes_depth,
+        &no_depth,
+        opp.buy_yes_on,
+        opp.buy_no_on,
+        min_profit,
+    )?;
 
     // Calculate total investment
     let investment = result.total_cost_a + result.total_cost_b + result.total_fees;
@@ -429,6 +702,7 @@ async fn fetch_market_depth(
     db: &Database,
     market: &UnifiedMarket,
     side: &str,
+    args: &Args,
 ) -> Option<common::OrderbookDepth> {
     match market.platform {
         Platform::Polymarket | Platform::Limitless => {
@@ -489,9 +763,37 @@ async fn fetch_market_depth(
             Some(depth)
         }
         Platform::Kalshi => {
-            // Kalshi not used in this service
-            warn!("Kalshi platform not supported in cross-platform-arb");
-            None
+            // Kalshi only reports its best level; approximate a ladder from it
+            // and the market's reported liquidity
+            let best_ask = match side {
+                "yes" => market.yes_best_ask,
+                "no" => market.no_best_ask,
+                _ => None,
+            }
+            .filter(|p| *p > Decimal::ZERO)?;
+            let liquidity = match market.liquidity {
+                Some(l) if l > Decimal::ZERO => l,
+                _ => {
+                    warn!("No liquidity reported for Kalshi market {}", market.name);
+                    return None;
+                }
+            };
+
+            let step = Decimal::try_from(args.kalshi_depth_step).ok()?;
+            let depth = synthesize_depth_from_best(
+                best_ask,
+                liquidity / best_ask,
+                args.kalshi_depth_levels,
+                step,
+            );
+            if depth.asks.is_empty() {
+                warn!(
+                    "Could not synthesize {} depth for Kalshi {}",
+                    side, market.name
+                );
+                return None;
+            }
+            Some(depth)
         }
     }
 }
@@ -3,7 +3,7 @@
 //! Calculates the maximum profitable order size by walking through
 //! orderbook depth on both platforms.
 
-use common::{KalshiOrderbook, OrderbookDepth, OrderbookLevel};
+use common::{KalshiOrderbook, OrderbookDepth, OrderbookLevel, Platform};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tracing::debug;
@@ -86,8 +86,8 @@ pub struct SlippageLevel {
 /// # Arguments
 /// * `yes_asks` - Orderbook asks for YES side (platform where we buy YES)
 /// * `no_asks` - Orderbook asks for NO side (platform where we buy NO)
-/// * `yes_fee_rate` - Fee rate for YES platform (0-1)
-/// * `no_fee_rate` - Fee rate for NO platform (0-1)
+/// * `yes_platform` - Platform where we buy YES (determines fee curve)
+/// * `no_platform` - Platform where we buy NO (determines fee curve)
 /// * `min_profit_pct` - Minimum profit percentage threshold
 ///
 /// # Returns
@@ -95,8 +95,8 @@ pub struct SlippageLevel {
 pub fn calculate_max_profitable_size(
     yes_asks: &OrderbookDepth,
     no_asks: &OrderbookDepth,
-    yes_fee_rate: Decimal,
-    no_fee_rate: Decimal,
+    yes_platform: Platform,
+    no_platform: Platform,
     min_profit_pct: Decimal,
 ) -> Option<SlippageResult> {
     let yes_levels = &yes_asks.asks;
@@ -110,6 +110,7 @@ pub fn calculate_max_profitable_size(
     let mut total_contracts: u64 = 0;
     let mut total_yes_cost = Decimal::ZERO;
     let mut total_no_cost = Decimal::ZERO;
+    let mut total_fees = Decimal::ZERO;
     let mut levels = Vec::new();
 
     let mut y_idx = 0;
@@ -133,8 +134,8 @@ pub fn calculate_max_profitable_size(
         // Calculate profit at these prices
         let yes_price = y_level.price;
         let no_price = n_level.price;
-        let yes_fee = yes_price * yes_fee_rate;
-        let no_fee = no_price * no_fee_rate;
+        let yes_fee = yes_platform.fee_per_contract(yes_price);
+        let no_fee = no_platform.fee_per_contract(no_price);
         let total_cost_per_contract = yes_price + no_price + yes_fee + no_fee;
         let profit_per_contract = Decimal::ONE - total_cost_per_contract;
         let profit_pct = if total_cost_per_contract > Decimal::ZERO {
@@ -162,6 +163,8 @@ pub fn calculate_max_profitable_size(
         total_contracts += contracts;
         total_yes_cost += contracts_dec * yes_price;
         total_no_cost += contracts_dec * no_price;
+        total_fees += yes_platform.fee_for(yes_price, contracts_dec)
+            + no_platform.fee_for(no_price, contracts_dec);
 
         levels.push(SlippageLevel {
             contracts,
@@ -190,7 +193,6 @@ pub fn calculate_max_profitable_size(
     }
 
     let total_contracts_dec = Decimal::from(total_contracts);
-    let total_fees = total_yes_cost * yes_fee_rate + total_no_cost * no_fee_rate;
     let total_investment = total_yes_cost + total_no_cost + total_fees;
     let payout = total_contracts_dec;
     let net_profit = payout - total_investment;
@@ -269,15 +271,46 @@ mod tests {
         let result = calculate_max_profitable_size(
             &yes_depth,
             &no_depth,
-            Decimal::ZERO, // Polymarket 0% fee
-            dec!(0.01),    // Kalshi 1% fee
-            dec!(1.0),     // 1% min profit
+            Platform::Polymarket,
+            Platform::Kalshi,
+            dec!(1.0), // 1% min profit
         );
 
         assert!(result.is_some());
         let r = result.unwrap();
         assert!(r.max_contracts > 0);
         assert!(r.net_profit_pct >= dec!(1.0));
+        assert!(r.total_fees > Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_max_profitable_size_kalshi_fee_curve() {
+        // 0.45 + 0.53 = 0.98 leaves 2c gross per contract.
+        // Kalshi fee on the 0.53 NO leg is 0.07 * 0.53 * 0.47 = ~1.74c,
+        // which eats most of the edge and drops it under 1%.
+        let yes_depth = create_test_depth(vec![(0.45, 100.0)]);
+        let no_depth = create_test_depth(vec![(0.53, 100.0)]);
+
+        let result = calculate_max_profitable_size(
+            &yes_depth,
+            &no_depth,
+            Platform::Polymarket,
+            Platform::Kalshi,
+            dec!(1.0),
+        );
+        assert!(result.is_none());
+
+        // Same prices with no fees on either leg clear the threshold
+        let result = calculate_max_profitable_size(
+            &yes_depth,
+            &no_depth,
+            Platform::Polymarket,
+            Platform::Limitless,
+            dec!(1.0),
+        )
+        .unwrap();
+        assert_eq!(result.max_contracts, 100);
+        assert_eq!(result.total_fees, Decimal::ZERO);
     }
 
     #[test]
@@ -8,24 +8,26 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use alloy::signers::local::PrivateKeySigner;
-use alloy::signers::Signer;
 use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
 use clap::Parser;
-use polymarket_client_sdk::clob::types::SignatureType;
 use polymarket_client_sdk::clob::{Client as ClobClient, Config as ClobConfig};
-use polymarket_client_sdk::POLYGON;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
-use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
+use common::assets::parse_assets;
+use common::models::{TradeIntent, TradeMode, TradeOutcome};
 use common::{
-    calculate_fill_price_with_slippage, get_15m_updown_markets_with_orderbooks,
-    get_market_resolutions_batch, get_markets_with_fresh_orderbooks, upsert_market_resolution,
-    Config, Database, GammaClient, MarketResolutionInsert, MarketWithOrderbook,
+    calculate_fill_price_with_slippage, check_order_size, fetch_live_best_ask, fmt_price,
+    fmt_shares, get_15m_updown_markets_with_orderbooks, get_market_resolutions_batch,
+    get_markets_with_fresh_orderbooks, insert_settlement, normalize_order,
+    query_order_fill_standalone, record_trade_audit, resolve_signature_config, retain_valid_depth,
+    upsert_market_resolution, Config, Database, FillPriceModel, GammaApi, GammaClient,
+    MarketResolutionInsert, MarketRotation, MarketWithOrderbook, OrderKind, SettlementInsert,
+    SideFilter, TradeThrottle,
 };
 
 /// Simulated position for dry-run portfolio tracking
@@ -73,7 +75,7 @@ impl DryRunPortfolio {
 
     /// Resolve expired positions and calculate P&L using actual market resolutions
     /// Fetches from Gamma API if not in database, then records to database
-    async fn resolve_expired(&mut self, pool: &sqlx::PgPool, gamma: &GammaClient) -> bool {
+    async fn resolve_expired(&mut self, pool: &sqlx::PgPool, gamma: &dyn GammaApi) -> bool {
         let now = Utc::now();
 
         // Find positions that have expired (with 60s buffer for resolution data)
@@ -172,7 +174,7 @@ impl DryRunPortfolio {
             // Check if our bet won
             let we_won = pos.side.to_uppercase() == winning_side;
 
-            if we_won {
+            let pnl = if we_won {
                 // Win: get $1 per share, profit = shares - cost
                 let payout = pos.shares;
                 let profit = payout - pos.cost;
@@ -182,6 +184,7 @@ impl DryRunPortfolio {
                     "[PORTFOLIO] ✅ WIN: {} {} (mkt: ${:.2}) -> +${:.2} (resolved: {})",
                     pos.side, pos.market_name, pos.best_ask_price, profit, winning_side
                 );
+                profit
             } else {
                 // Loss: lose entire stake
                 let loss = pos.cost;
@@ -191,6 +194,21 @@ impl DryRunPortfolio {
                     "[PORTFOLIO] ❌ LOSS: {} {} (mkt: ${:.2}) -> -${:.2} (resolved: {})",
                     pos.side, pos.market_name, pos.best_ask_price, loss, winning_side
                 );
+                -loss
+            };
+
+            let settlement = SettlementInsert {
+                market_id: pos.market_id,
+                side: pos.side.to_uppercase(),
+                shares: pos.shares,
+                cost: pos.cost,
+                winning_side,
+                pnl,
+                is_dry_run: true,
+                resolved_at: now,
+            };
+            if let Err(e) = insert_settlement(pool, &settlement).await {
+                warn!("[PORTFOLIO] Failed to record settlement: {}", e);
             }
         }
 
@@ -235,8 +253,9 @@ impl DryRunPortfolio {
 const CLOB_HOST: &str = "https://clob.polymarket.com";
 const ORDER_TIMEOUT_SECS: u64 = 30;
 const CANCEL_TIMEOUT_SECS: u64 = 10;
-/// Maximum allowed shares per order (sanity check)
-const MAX_SHARES: Decimal = dec!(99.99);
+
+/// Strategy tag recorded with each trade intent.
+const STRATEGY_TAG: &str = "expiry_scalper";
 
 /// Pending order cancellation.
 #[derive(Debug, Clone)]
@@ -263,6 +282,10 @@ struct Args {
     #[arg(long, default_value = "5")]
     position_size: f64,
 
+    /// Maximum shares per order; a market needing more (position size / price) is skipped
+    #[arg(long, default_value_t = DEFAULT_MAX_SHARES)]
+    max_shares: Decimal,
+
     /// High price threshold (buy YES if price > this)
     #[arg(long, default_value = "0.75")]
     high_threshold: f64,
@@ -271,7 +294,12 @@ struct Args {
     #[arg(long, default_value = "30")]
     max_orderbook_age: i32,
 
-    /// Assets to trade (comma-separated)
+    /// Process at most this many markets per cycle, soonest expiry first;
+    /// the rest wait for a later cycle. Optional.
+    #[arg(long)]
+    max_markets_per_cycle: Option<usize>,
+
+    /// Assets to trade (comma-separated), or ALL for every asset with active markets
     #[arg(long, default_value = "BTC,ETH,SOL,XRP")]
     assets: String,
 
@@ -287,6 +315,11 @@ struct Args {
     #[arg(long)]
     contrarian: bool,
 
+    /// Only trade one side (both, yes-only, no-only); signals for the other
+    /// side are still logged but not acted upon
+    #[arg(long, default_value = "both")]
+    side_filter: SideFilter,
+
     /// Only trade 15-minute up/down markets
     #[arg(long)]
     only_15m_updown: bool,
@@ -295,14 +328,110 @@ struct Args {
     #[arg(long, default_value = "0")]
     cancel_after_secs: u64,
 
+    /// Minimum milliseconds between live orders across all markets, on top of
+    /// per-market cooldowns (0 = no limit; ignored in dry-run)
+    #[arg(long, default_value = "0")]
+    min_trade_interval_ms: u64,
+
+    /// Order type: limit (rests at the order price; see --cancel-after-secs)
+    /// or marketable (fill-and-kill at the current best ask, nothing rests)
+    #[arg(long, default_value = "limit")]
+    order_type: OrderKind,
+
+    /// Price used to plan fills from orderbook depth: vwap (size-weighted
+    /// average), worst-touched (deepest level reached) or best-only
+    #[arg(long, default_value = "vwap")]
+    fill_model: FillPriceModel,
+
     /// Slippage percentage for fill price estimation (default 20%)
     /// Used when orderbook depth is unavailable
     #[arg(long, default_value = "20")]
     slippage_pct: f64,
 
+    /// Skip a market when the depth-based fill is more than this percent above
+    /// the best ask (e.g. 5 = 5%), even if it is under the limit price. Optional.
+    #[arg(long)]
+    max_slippage_pct: Option<f64>,
+
     /// Minimum orderbook depth required at best price (skip if less)
     #[arg(long, default_value = "0")]
     min_depth: f64,
+
+    /// Skip markets whose YES + NO best asks sum above this (an overpriced book)
+    #[arg(long, default_value = "1.02")]
+    max_combined_cost: f64,
+
+    /// Position size ladder as "threshold:size" pairs (e.g. "0.75:3,0.90:7").
+    /// The size for the highest threshold at or below the signal price is used.
+    /// Overrides --position-size when set.
+    #[arg(long)]
+    size_ladder: Option<String>,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
+}
+
+/// Parse a size ladder like "0.75:3,0.90:7" into (threshold, size) pairs.
+/// Thresholds must be strictly ascending and within 0-1; sizes must be positive.
+fn parse_size_ladder(s: &str) -> Result<Vec<(Decimal, Decimal)>> {
+    let mut ladder: Vec<(Decimal, Decimal)> = Vec::new();
+
+    for entry in s.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let (threshold_str, size_str) = entry.split_once(':').with_context(|| {
+            format!("Invalid ladder entry '{}' (expected threshold:size)", entry)
+        })?;
+
+        let threshold = Decimal::from_str(threshold_str.trim())
+            .with_context(|| format!("Invalid ladder threshold '{}'", threshold_str))?;
+        let size = Decimal::from_str(size_str.trim())
+            .with_context(|| format!("Invalid ladder size '{}'", size_str))?;
+
+        if threshold < Decimal::ZERO || threshold > Decimal::ONE {
+            bail!("Ladder threshold {} must be between 0 and 1", threshold);
+        }
+        if size <= Decimal::ZERO {
+            bail!("Ladder size {} must be positive", size);
+        }
+        if size > dec!(10000) {
+            bail!("Ladder size {} cannot exceed 10000", size);
+        }
+        if let Some((prev, _)) = ladder.last() {
+            if threshold <= *prev {
+                bail!(
+                    "Ladder thresholds must be ascending ({} follows {})",
+                    threshold,
+                    prev
+                );
+            }
+        }
+
+        ladder.push((threshold, size));
+    }
+
+    if ladder.is_empty() {
+        bail!("Size ladder is empty");
+    }
+
+    Ok(ladder)
+}
+
+/// Pick the position size for a signal price.
+/// Without a ladder, returns the default size. With a ladder, returns the size of the
+/// highest bracket the price falls into, or None if the price is below every bracket.
+fn position_size_for_price(
+    price: Decimal,
+    ladder: Option<&[(Decimal, Decimal)]>,
+    default_size: Decimal,
+) -> Option<Decimal> {
+    match ladder {
+        None => Some(default_size),
+        Some(ladder) => ladder
+            .iter()
+            .rev()
+            .find(|(threshold, _)| price >= *threshold)
+            .map(|(_, size)| *size),
+    }
 }
 
 /// Cached authentication state
@@ -329,6 +458,7 @@ fn validate_args(args: &Args) -> Result<()> {
     if args.position_size > 10000.0 {
         bail!("position_size cannot exceed 10000");
     }
+    common::validation::validate_max_shares(args.max_shares)?;
     if args.high_threshold > 1.0 || args.high_threshold < 0.0 {
         bail!("high_threshold must be between 0 and 1");
     }
@@ -338,22 +468,46 @@ fn validate_args(args: &Args) -> Result<()> {
     if args.slippage_pct < 0.0 || args.slippage_pct > 100.0 {
         bail!("slippage_pct must be between 0 and 100");
     }
+    if args.max_slippage_pct.is_some_and(|max| max < 0.0) {
+        bail!("max_slippage_pct must be non-negative");
+    }
     if args.min_depth < 0.0 {
         bail!("min_depth must be non-negative");
     }
+    if args.max_combined_cost < 1.0 || args.max_combined_cost > 2.0 {
+        bail!("max_combined_cost must be between 1 and 2");
+    }
+    if let Some(ref ladder) = args.size_ladder {
+        parse_size_ladder(ladder).context("Invalid size_ladder")?;
+    }
+    if args.max_markets_per_cycle == Some(0) {
+        bail!("max_markets_per_cycle must be at least 1");
+    }
     Ok(())
 }
 
+/// IDs of the fetched markets that have not expired yet.
+fn live_market_ids(markets: impl Iterator<Item = (Uuid, DateTime<Utc>)>) -> HashSet<Uuid> {
+    let now = Utc::now();
+    markets
+        .filter(|(_, end_time)| *end_time > now)
+        .map(|(id, _)| id)
+        .collect()
+}
+
+fn log_deferred(processing: usize, deferred: usize) {
+    if deferred > 0 {
+        info!(
+            "[CAP] Processing {} markets this cycle, {} deferred",
+            processing, deferred
+        );
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
 
     // Validate arguments
     validate_args(&args)?;
@@ -361,6 +515,10 @@ async fn main() -> Result<()> {
     info!("=== Expiry Scalper ===");
     info!("Expiry window: {} minutes", args.expiry_minutes);
     info!("Position size: ${}", args.position_size);
+    info!("Max shares per order: {}", args.max_shares);
+    if let Some(ref ladder) = args.size_ladder {
+        info!("Size ladder: {}", ladder);
+    }
     info!("Threshold: buy if price >= {}", args.high_threshold);
     info!(
         "Limit price: {} (order placed at this price)",
@@ -369,10 +527,23 @@ async fn main() -> Result<()> {
     info!("Assets: {}", args.assets);
     info!("Poll interval: {}s", args.interval_secs);
     info!("Dry run: {}", args.dry_run);
+    if let Some(max) = args.max_slippage_pct {
+        info!("Max slippage: {}%", max);
+    }
+    info!("Fill model: {}", args.fill_model);
     info!("Contrarian mode: {}", args.contrarian);
+    if args.side_filter != SideFilter::Both {
+        info!("Side filter: {}", args.side_filter);
+    }
+    info!("Order type: {}", args.order_type);
+    info!("Max combined YES+NO ask: {}", args.max_combined_cost);
     info!("Only 15m up/down: {}", args.only_15m_updown);
     if args.cancel_after_secs > 0 {
-        info!("Auto-cancel after: {}s", args.cancel_after_secs);
+        if args.order_type.rests() {
+            info!("Auto-cancel after: {}s", args.cancel_after_secs);
+        } else {
+            warn!("--cancel-after-secs has no effect with --order-type marketable");
+        }
     }
 
     // Load config and connect to database
@@ -387,7 +558,13 @@ async fn main() -> Result<()> {
     let high_threshold =
         Decimal::try_from(args.high_threshold).context("Invalid high_threshold")?;
     let position_size = Decimal::try_from(args.position_size).context("Invalid position_size")?;
+    let max_shares = args.max_shares;
     let limit_price = Decimal::try_from(args.limit_price).context("Invalid limit_price")?;
+    let size_ladder = args
+        .size_ladder
+        .as_deref()
+        .map(parse_size_ladder)
+        .transpose()?;
 
     // Track markets we've already bet on
     let mut traded_markets: HashSet<Uuid> = HashSet::new();
@@ -398,21 +575,21 @@ async fn main() -> Result<()> {
     // Pending order cancellations
     let mut pending_cancels: Vec<PendingCancel> = Vec::new();
 
+    let mut throttle = if args.dry_run {
+        TradeThrottle::disabled()
+    } else {
+        TradeThrottle::new(Duration::from_millis(args.min_trade_interval_ms))
+    };
+
+    // Rotates which markets --max-markets-per-cycle defers
+    let mut rotation = MarketRotation::default();
+
     // Dry-run portfolio tracker
     let mut portfolio = DryRunPortfolio::new();
     let mut cycle_count: u32 = 0;
 
-    // Parse assets from CLI
-    let assets: Vec<String> = args
-        .assets
-        .split(',')
-        .map(|s| s.trim().to_uppercase())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    if assets.is_empty() {
-        bail!("No valid assets specified");
-    }
+    // Parse assets from CLI (ALL = every asset with active markets)
+    let assets = parse_assets(&args.assets)?.resolve(db.pool()).await?;
 
     info!("Trading assets: {:?}", assets);
 
@@ -430,11 +607,15 @@ async fn main() -> Result<()> {
                 &args,
                 high_threshold,
                 position_size,
+                max_shares,
+                size_ladder.as_deref(),
                 limit_price,
                 &mut traded_markets,
                 &mut cached_auth,
                 &mut pending_cancels,
+                &mut throttle,
                 &mut portfolio,
+                &mut rotation,
             ) => {}
         }
 
@@ -463,16 +644,21 @@ async fn main() -> Result<()> {
 /// Returns true if trade was placed/simulated, false otherwise.
 #[allow(clippy::too_many_arguments)]
 async fn process_market_with_orderbook(
+    pool: &sqlx::PgPool,
     market: &MarketWithOrderbook,
     args: &Args,
     high_threshold: Decimal,
     position_size: Decimal,
+    max_shares: Decimal,
+    size_ladder: Option<&[(Decimal, Decimal)]>,
     limit_price: Decimal,
     slippage_pct: Decimal,
     min_depth: Decimal,
+    max_combined_cost: Decimal,
     traded_markets: &mut HashSet<Uuid>,
     cached_auth: &mut Option<CachedAuth>,
     pending_cancels: &mut Vec<PendingCancel>,
+    throttle: &mut TradeThrottle,
     portfolio: &mut DryRunPortfolio,
 ) -> bool {
     // Skip if already traded
@@ -505,6 +691,17 @@ async fn process_market_with_orderbook(
         }
     };
 
+    // Overpriced book: YES + NO asks cost more than the $1 either side pays
+    if let Some(combined) = market.combined_ask_cost() {
+        if combined > max_combined_cost {
+            info!(
+                "Skipping {} - combined ask {} (YES {} + NO {}) above max {}",
+                market.name, combined, yes_price, no_price, max_combined_cost
+            );
+            return false;
+        }
+    }
+
     // Determine which side to trade and get orderbook depth
     // Returns: (side, token_id, order_price, best_ask, orderbook_depth)
     let (side, token_id, order_price, best_ask, orderbook) = if args.contrarian {
@@ -561,12 +758,38 @@ async fn process_market_with_orderbook(
         }
     };
 
+    // Respect the configured direction bias
+    if !args.side_filter.allows(side) {
+        debug!(
+            "[BIAS] {} {} signal suppressed by side filter ({})",
+            market.name, side, args.side_filter
+        );
+        return false;
+    }
+
+    // A marketable order takes the side's best ask instead of resting
+    let order_price = args.order_type.order_price(order_price, best_ask);
+
+    // Pick position size from the ladder bracket of the skewed (signal) price
+    let signal_price = yes_price.max(no_price);
+    let position_size = match position_size_for_price(signal_price, size_ladder, position_size) {
+        Some(size) => size,
+        None => {
+            debug!(
+                "Skipping {} - signal price {} below lowest ladder threshold",
+                market.name, signal_price
+            );
+            return false;
+        }
+    };
+
     // Calculate effective fill price using orderbook depth
     let fill_estimate = calculate_fill_price_with_slippage(
         orderbook.as_ref(),
         best_ask,
         position_size / best_ask, // Estimate shares for fill calculation
         slippage_pct,
+        args.fill_model,
     );
 
     // Check minimum depth if configured (only when we have actual orderbook data)
@@ -582,13 +805,32 @@ async fn process_market_with_orderbook(
         return false;
     }
 
+    // Thin book: skip on excessive slippage even if still under the limit
+    if let Some(max_slippage) = args
+        .max_slippage_pct
+        .and_then(|m| Decimal::try_from(m).ok())
+    {
+        if fill_estimate.exceeds_slippage_cap(best_ask, max_slippage) {
+            info!(
+                "[SKIP] {} {} slippage {:.2}% > max {}% (vwap ${:.3} vs ask ${:.3})",
+                market.name,
+                side,
+                fill_estimate.slippage_pct(best_ask),
+                max_slippage,
+                fill_estimate.vwap_price,
+                best_ask
+            );
+            return false;
+        }
+    }
+
     // Calculate shares based on effective fill price
     let shares = (position_size / fill_estimate.effective_price).round_dp(2);
 
-    if shares > MAX_SHARES {
+    if shares > max_shares {
         warn!(
             "Skipping {} - calculated shares {} exceeds max {}",
-            market.name, shares, MAX_SHARES
+            market.name, shares, max_shares
         );
         return false;
     }
@@ -641,6 +883,18 @@ async fn process_market_with_orderbook(
         fill_estimate.best_price_depth
     );
 
+    let mut intent = TradeIntent {
+        market_id: market.id,
+        token_id: token_id.clone(),
+        side: side.to_string(),
+        shares,
+        limit_price: order_price,
+        effective_price: fill_estimate.effective_price,
+        strategy_tag: STRATEGY_TAG.to_string(),
+        mode: TradeMode::from_dry_run(args.dry_run),
+    };
+    info!("[INTENT] {}", intent);
+
     if args.dry_run {
         let cost = shares * fill_estimate.effective_price;
         info!(
@@ -670,30 +924,88 @@ async fn process_market_with_orderbook(
             cost,
             end_time: market.end_time,
         });
+        record_trade_audit(pool, &intent, TradeOutcome::Filled).await;
 
         traded_markets.insert(market.id);
         return true;
     }
 
+    if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
+        info!(
+            "[THROTTLE] Skipping {} {}: next order allowed in {}ms",
+            side,
+            market.name,
+            wait.as_millis()
+        );
+        record_trade_audit(pool, &intent, TradeOutcome::Skipped).await;
+        return false;
+    }
+
+    // A marketable order crosses at the live ask; the DB snapshot can be stale
+    let order_price = if args.order_type.rests() {
+        order_price
+    } else {
+        match live_marketable_price(token_id, &market.name, side).await {
+            Some(live_ask) => live_ask,
+            None => {
+                record_trade_audit(pool, &intent, TradeOutcome::Skipped).await;
+                return false;
+            }
+        }
+    };
+    intent.limit_price = order_price;
+
     // Execute trade
-    match execute_trade(
+    let result = execute_trade(
         cached_auth,
         token_id,
         shares,
         order_price,
         side,
         &market.name,
+        max_shares,
+        args.order_type,
     )
-    .await
-    {
+    .await;
+    throttle.record_order(std::time::Instant::now());
+
+    match result {
         Ok(order_id) => {
+            let (outcome, filled) = placed_fill(args.order_type, &order_id, shares).await;
+            if filled.is_zero() {
+                info!(
+                    "[NO FILL] {} order {} for {} matched nothing @ {}",
+                    side,
+                    order_id,
+                    market.name,
+                    fmt_price(order_price)
+                );
+                record_trade_audit(pool, &intent, outcome).await;
+                return false;
+            }
+            intent.shares = filled;
+            record_trade_audit(pool, &intent, outcome).await;
+
+            if filled < shares {
+                info!(
+                    "[PARTIAL] {} {} filled {}/{} shares",
+                    market.name,
+                    side,
+                    fmt_shares(filled),
+                    fmt_shares(shares)
+                );
+            }
             info!(
-                "[SUCCESS] Placed {} order {} for {} @ ${}",
-                side, order_id, market.name, order_price
+                "[SUCCESS] Placed {} order {} for {} {} shares @ {}",
+                side,
+                order_id,
+                market.name,
+                fmt_shares(filled),
+                fmt_price(order_price)
             );
             traded_markets.insert(market.id);
 
-            if args.cancel_after_secs > 0 {
+            if args.order_type.rests() && args.cancel_after_secs > 0 {
                 let cancel_at =
                     Utc::now() + chrono::Duration::seconds(args.cancel_after_secs as i64);
                 pending_cancels.push(PendingCancel {
@@ -709,6 +1021,7 @@ async fn process_market_with_orderbook(
             true
         }
         Err(e) => {
+            record_trade_audit(pool, &intent, TradeOutcome::Rejected).await;
             error!("[FAILED] Trade execution for {}: {:#}", market.name, e);
             false
         }
@@ -716,18 +1029,23 @@ async fn process_market_with_orderbook(
 }
 
 /// Run a single trading cycle
+#[allow(clippy::too_many_arguments)]
 async fn run_cycle(
     db: &Database,
-    gamma: &GammaClient,
+    gamma: &dyn GammaApi,
     assets: &[String],
     args: &Args,
     high_threshold: Decimal,
     position_size: Decimal,
+    max_shares: Decimal,
+    size_ladder: Option<&[(Decimal, Decimal)]>,
     limit_price: Decimal,
     traded_markets: &mut HashSet<Uuid>,
     cached_auth: &mut Option<CachedAuth>,
     pending_cancels: &mut Vec<PendingCancel>,
+    throttle: &mut TradeThrottle,
     portfolio: &mut DryRunPortfolio,
+    rotation: &mut MarketRotation,
 ) {
     let cycle_start = std::time::Instant::now();
 
@@ -745,11 +1063,12 @@ async fn run_cycle(
     let expiry_seconds = args.expiry_minutes * 60;
     let slippage_pct = Decimal::try_from(args.slippage_pct).unwrap_or(dec!(20));
     let min_depth = Decimal::try_from(args.min_depth).unwrap_or(dec!(0));
+    let max_combined_cost = Decimal::try_from(args.max_combined_cost).unwrap_or(dec!(1.02));
 
     // For 15m up/down markets, use orderbook depth for realistic fill prices
     if args.only_15m_updown {
         let all_timeframes = vec!["5m".to_string(), "15m".to_string()];
-        let markets = match get_15m_updown_markets_with_orderbooks(
+        let mut markets = match get_15m_updown_markets_with_orderbooks(
             db.pool(),
             args.max_orderbook_age,
             assets,
@@ -771,34 +1090,46 @@ async fn run_cycle(
             args.expiry_minutes
         );
 
+        // Deferred markets are still live, so keep them out of the tracking cleanup
+        let live_ids = live_market_ids(markets.iter().map(|m| (m.id, m.end_time)));
+        for (name, e) in retain_valid_depth(&mut markets) {
+            warn!("[SKIP] {} - invalid depth snapshot: {}", name, e);
+        }
+        let deferred = rotation.select(&mut markets, args.max_markets_per_cycle);
+        log_deferred(markets.len(), deferred);
+
         for market in &markets {
             process_market_with_orderbook(
+                db.pool(),
                 market,
                 args,
                 high_threshold,
                 position_size,
+                max_shares,
+                size_ladder,
                 limit_price,
                 slippage_pct,
                 min_depth,
+                max_combined_cost,
                 traded_markets,
                 cached_auth,
                 pending_cancels,
+                throttle,
                 portfolio,
             )
             .await;
         }
 
         // Cleanup expired markets from tracking set
-        let now = Utc::now();
         let before_count = traded_markets.len();
-        traded_markets.retain(|id| markets.iter().any(|m| m.id == *id && m.end_time > now));
+        traded_markets.retain(|id| live_ids.contains(id));
         let cleaned = before_count - traded_markets.len();
         if cleaned > 0 {
             debug!("Cleaned {} expired markets from tracking set", cleaned);
         }
     } else {
         // Use general query for all markets (without orderbook depth)
-        let markets = match get_markets_with_fresh_orderbooks(
+        let mut markets = match get_markets_with_fresh_orderbooks(
             db.pool(),
             args.max_orderbook_age,
             assets,
@@ -819,6 +1150,15 @@ async fn run_cycle(
             args.expiry_minutes
         );
 
+        let live_ids = live_market_ids(markets.iter().map(|m| (m.id, m.end_time)));
+        markets.sort_by_key(|m| m.end_time);
+        let mut deferred = 0;
+        if let Some(max) = args.max_markets_per_cycle {
+            deferred = markets.len().saturating_sub(max);
+            markets.truncate(max);
+        }
+        log_deferred(markets.len(), deferred);
+
         // Process each market (legacy mode without orderbook depth)
         for market in &markets {
             if traded_markets.contains(&market.id) {
@@ -848,6 +1188,15 @@ async fn run_cycle(
                 }
             };
 
+            let combined = yes_price + no_price;
+            if combined > max_combined_cost {
+                info!(
+                    "Skipping {} - combined ask {} (YES {} + NO {}) above max {}",
+                    market.name, combined, yes_price, no_price, max_combined_cost
+                );
+                continue;
+            }
+
             let (side, token_id, order_price, market_price) = if args.contrarian {
                 if yes_price >= high_threshold {
                     ("NO", &market.no_token_id, limit_price, no_price)
@@ -868,6 +1217,16 @@ async fn run_cycle(
                 }
             };
 
+            if !args.side_filter.allows(side) {
+                debug!(
+                    "[BIAS] {} {} signal suppressed by side filter ({})",
+                    market.name, side, args.side_filter
+                );
+                continue;
+            }
+
+            let order_price = args.order_type.order_price(order_price, market_price);
+
             if !args.contrarian {
                 if order_price < dec!(0.01) || order_price > dec!(0.99) {
                     warn!(
@@ -878,8 +1237,18 @@ async fn run_cycle(
                 }
             }
 
+            let signal_price = yes_price.max(no_price);
+            let position_size =
+                match position_size_for_price(signal_price, size_ladder, position_size) {
+                    Some(size) => size,
+                    None => {
+                        debug!("Skipping {} - below lowest ladder threshold", market.name);
+                        continue;
+                    }
+                };
+
             let shares = (position_size / market_price).round_dp(2);
-            if shares > MAX_SHARES {
+            if shares > max_shares {
                 warn!("Skipping {} - shares {} exceeds max", market.name, shares);
                 continue;
             }
@@ -894,6 +1263,18 @@ async fn run_cycle(
                 mode_label, side, market.name, market_price, shares, yes_price, no_price
             );
 
+            let mut intent = TradeIntent {
+                market_id: market.id,
+                token_id: token_id.clone(),
+                side: side.to_string(),
+                shares,
+                limit_price: order_price,
+                effective_price: market_price,
+                strategy_tag: STRATEGY_TAG.to_string(),
+                mode: TradeMode::from_dry_run(args.dry_run),
+            };
+            info!("[INTENT] {}", intent);
+
             if args.dry_run {
                 let cost = shares * market_price;
                 info!(
@@ -921,29 +1302,86 @@ async fn run_cycle(
                     cost,
                     end_time: market.end_time,
                 });
+                record_trade_audit(db.pool(), &intent, TradeOutcome::Filled).await;
 
                 traded_markets.insert(market.id);
                 continue;
             }
 
-            match execute_trade(
+            if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
+                info!(
+                    "[THROTTLE] Skipping {} {}: next order allowed in {}ms",
+                    side,
+                    market.name,
+                    wait.as_millis()
+                );
+                record_trade_audit(db.pool(), &intent, TradeOutcome::Skipped).await;
+                continue;
+            }
+
+            let order_price = if args.order_type.rests() {
+                order_price
+            } else {
+                match live_marketable_price(token_id, &market.name, side).await {
+                    Some(live_ask) => live_ask,
+                    None => {
+                        record_trade_audit(db.pool(), &intent, TradeOutcome::Skipped).await;
+                        continue;
+                    }
+                }
+            };
+            intent.limit_price = order_price;
+
+            let result = execute_trade(
                 cached_auth,
                 token_id,
                 shares,
                 order_price,
                 side,
                 &market.name,
+                max_shares,
+                args.order_type,
             )
-            .await
-            {
+            .await;
+            throttle.record_order(std::time::Instant::now());
+
+            match result {
                 Ok(order_id) => {
+                    let (outcome, filled) = placed_fill(args.order_type, &order_id, shares).await;
+                    if filled.is_zero() {
+                        info!(
+                            "[NO FILL] {} order {} for {} matched nothing @ {}",
+                            side,
+                            order_id,
+                            market.name,
+                            fmt_price(order_price)
+                        );
+                        record_trade_audit(db.pool(), &intent, outcome).await;
+                        continue;
+                    }
+                    intent.shares = filled;
+                    record_trade_audit(db.pool(), &intent, outcome).await;
+
+                    if filled < shares {
+                        info!(
+                            "[PARTIAL] {} {} filled {}/{} shares",
+                            market.name,
+                            side,
+                            fmt_shares(filled),
+                            fmt_shares(shares)
+                        );
+                    }
                     info!(
-                        "[SUCCESS] Placed {} order {} for {} @ ${}",
-                        side, order_id, market.name, order_price
+                        "[SUCCESS] Placed {} order {} for {} {} shares @ {}",
+                        side,
+                        order_id,
+                        market.name,
+                        fmt_shares(filled),
+                        fmt_price(order_price)
                     );
                     traded_markets.insert(market.id);
 
-                    if args.cancel_after_secs > 0 {
+                    if args.order_type.rests() && args.cancel_after_secs > 0 {
                         let cancel_at =
                             Utc::now() + chrono::Duration::seconds(args.cancel_after_secs as i64);
                         pending_cancels.push(PendingCancel {
@@ -954,15 +1392,15 @@ async fn run_cycle(
                     }
                 }
                 Err(e) => {
+                    record_trade_audit(db.pool(), &intent, TradeOutcome::Rejected).await;
                     error!("[ERROR] Failed to place order for {}: {:#}", market.name, e);
                 }
             }
         }
 
         // Cleanup expired markets from tracking set
-        let now = Utc::now();
         let before_count = traded_markets.len();
-        traded_markets.retain(|id| markets.iter().any(|m| m.id == *id && m.end_time > now));
+        traded_markets.retain(|id| live_ids.contains(id));
         let cleaned = before_count - traded_markets.len();
         if cleaned > 0 {
             debug!("Cleaned {} expired markets from tracking set", cleaned);
@@ -1037,6 +1475,10 @@ async fn process_pending_cancels(
 }
 
 /// Execute a trade on Polymarket. Returns the order ID on success.
+///
+/// `order_kind` sets the time in force: a resting GTC limit, or a FAK that
+/// takes what the book has at `price` and cancels the rest.
+#[allow(clippy::too_many_arguments)]
 async fn execute_trade(
     cached_auth: &mut Option<CachedAuth>,
     token_id: &str,
@@ -1044,19 +1486,25 @@ async fn execute_trade(
     price: Decimal,
     side: &str,
     market_name: &str,
+    max_shares: Decimal,
+    order_kind: OrderKind,
 ) -> Result<String> {
+    check_order_size(shares, max_shares)?;
+
     // Ensure authenticated
     let auth = ensure_authenticated(cached_auth).await?;
 
     // Normalize price and shares to remove trailing zeros
     // Polymarket SDK requires price decimal places <= tick size decimal places
-    let price = price.normalize();
-    let shares = shares.round_dp(2); // Round shares to 2 decimal places
+    let (price, shares) = normalize_order(price, shares);
 
     // Build order
     info!(
-        "[TRADE] Building {} order: {} shares @ ${}",
-        side, shares, price
+        "[TRADE] Building {} {} order: {} shares @ {}",
+        side,
+        order_kind,
+        fmt_shares(shares),
+        fmt_price(price)
     );
 
     let order = timeout(
@@ -1067,6 +1515,7 @@ async fn execute_trade(
             .size(shares)
             .price(price)
             .side(polymarket_client_sdk::clob::types::Side::Buy)
+            .order_type(order_kind.sdk_order_type())
             .build(),
     )
     .await
@@ -1114,6 +1563,57 @@ async fn execute_trade(
     }
 }
 
+/// Live best ask for a marketable order, which crosses at whatever the book
+/// shows now rather than the DB snapshot. Returns None (after logging) when
+/// the ask can't be fetched or is outside the tradable range.
+async fn live_marketable_price(token_id: &str, market_name: &str, side: &str) -> Option<Decimal> {
+    match fetch_live_best_ask(token_id).await {
+        Some(ask) if ask >= dec!(0.01) && ask <= dec!(0.99) => Some(ask),
+        Some(ask) => {
+            info!(
+                "[SKIP] {} {} live ask {} out of range",
+                market_name, side, ask
+            );
+            None
+        }
+        None => {
+            info!(
+                "[SKIP] {} {} live ask unavailable, cannot price marketable order",
+                market_name, side
+            );
+            None
+        }
+    }
+}
+
+/// Outcome and share count to record for a placed order.
+///
+/// A resting order is booked as placed for its full size. A fill-and-kill
+/// order is booked for whatever matched, since the exchange cancels the rest;
+/// zero shares means nothing filled.
+async fn placed_fill(
+    order_kind: OrderKind,
+    order_id: &str,
+    shares: Decimal,
+) -> (TradeOutcome, Decimal) {
+    if order_kind.rests() {
+        return (TradeOutcome::Placed, shares);
+    }
+    match query_order_fill_standalone(order_id).await {
+        Ok(filled) if filled >= shares => (TradeOutcome::Filled, shares),
+        Ok(filled) => (TradeOutcome::Placed, filled),
+        Err(e) => {
+            warn!(
+                "[FILL] Could not query fill for order {}, assuming {} shares: {:#}",
+                order_id,
+                fmt_shares(shares),
+                e
+            );
+            (TradeOutcome::Placed, shares)
+        }
+    }
+}
+
 /// Ensure we have a valid authenticated CLOB client.
 /// Authenticates on first call, reuses cached client thereafter.
 async fn ensure_authenticated(cached_auth: &mut Option<CachedAuth>) -> Result<&CachedAuth> {
@@ -1124,37 +1624,14 @@ async fn ensure_authenticated(cached_auth: &mut Option<CachedAuth>) -> Result<&C
 
     info!("[AUTH] Authenticating with Polymarket CLOB...");
 
-    // Get private key
-    let private_key = std::env::var("WALLET_PRIVATE_KEY")
-        .context("Missing WALLET_PRIVATE_KEY environment variable")?;
-
-    let private_key = if private_key.starts_with("0x") {
-        private_key
-    } else {
-        format!("0x{}", private_key)
-    };
-
-    // Create signer
-    let signer = PrivateKeySigner::from_str(&private_key)
-        .context("Invalid private key format")?
-        .with_chain_id(Some(POLYGON));
-
-    // Determine signature type
-    let proxy_wallet = std::env::var("POLYMARKET_WALLET_ADDRESS").ok();
-    let signature_type = if proxy_wallet.is_some() {
-        SignatureType::GnosisSafe
-    } else {
-        SignatureType::Eoa
-    };
+    let (signer, signature_type, funder) = resolve_signature_config()?;
 
     // Build authentication
     let mut auth_builder = ClobClient::new(CLOB_HOST, ClobConfig::default())?
         .authentication_builder(&signer)
         .signature_type(signature_type);
 
-    if let Some(ref proxy) = proxy_wallet {
-        let funder_address: alloy::primitives::Address =
-            proxy.parse().context("Invalid proxy wallet address")?;
+    if let Some(funder_address) = funder {
         auth_builder = auth_builder.funder(funder_address);
     }
 
@@ -1175,3 +1652,57 @@ async fn ensure_authenticated(cached_auth: &mut Option<CachedAuth>) -> Result<&C
     // Safe because we just set it
     Ok(cached_auth.as_ref().unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size_ladder() {
+        let ladder = parse_size_ladder("0.75:3,0.90:7").unwrap();
+        assert_eq!(ladder, vec![(dec!(0.75), dec!(3)), (dec!(0.90), dec!(7))]);
+
+        // Whitespace is tolerated
+        let ladder = parse_size_ladder(" 0.8 : 5 , 0.95:10 ").unwrap();
+        assert_eq!(ladder, vec![(dec!(0.8), dec!(5)), (dec!(0.95), dec!(10))]);
+
+        // Not ascending
+        assert!(parse_size_ladder("0.90:7,0.75:3").is_err());
+        assert!(parse_size_ladder("0.80:3,0.80:7").is_err());
+        // Out of range threshold
+        assert!(parse_size_ladder("1.5:3").is_err());
+        assert!(parse_size_ladder("-0.1:3").is_err());
+        // Bad sizes and formats
+        assert!(parse_size_ladder("0.75:0").is_err());
+        assert!(parse_size_ladder("0.75").is_err());
+        assert!(parse_size_ladder("abc:3").is_err());
+        assert!(parse_size_ladder("").is_err());
+    }
+
+    #[test]
+    fn test_position_size_for_price() {
+        let ladder = vec![(dec!(0.75), dec!(3)), (dec!(0.90), dec!(7))];
+
+        assert_eq!(
+            position_size_for_price(dec!(0.80), Some(ladder.as_slice()), dec!(5)),
+            Some(dec!(3))
+        );
+        assert_eq!(
+            position_size_for_price(dec!(0.90), Some(ladder.as_slice()), dec!(5)),
+            Some(dec!(7))
+        );
+        assert_eq!(
+            position_size_for_price(dec!(0.97), Some(ladder.as_slice()), dec!(5)),
+            Some(dec!(7))
+        );
+        assert_eq!(
+            position_size_for_price(dec!(0.70), Some(ladder.as_slice()), dec!(5)),
+            None
+        );
+        // No ladder falls back to the single position size
+        assert_eq!(
+            position_size_for_price(dec!(0.70), None, dec!(5)),
+            Some(dec!(5))
+        );
+    }
+}
@@ -9,11 +9,10 @@ use anyhow::Result;
 use clap::Parser;
 use tokio::sync::mpsc;
 use tokio::time::sleep;
-use tracing::{error, info, warn, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::{error, info, warn};
 
 use common::{
-    run_kalshi_orderbook_stream, update_kalshi_prices, Config, Database, KalshiClient,
+    run_kalshi_orderbook_stream, update_kalshi_prices, Config, Database, KalshiClient, KalshiEnv,
     KalshiOrderbookUpdate,
 };
 
@@ -41,17 +40,22 @@ struct Args {
     /// Assets to stream (comma-separated)
     #[arg(long, default_value = "BTC,ETH,SOL,XRP")]
     assets: String,
+
+    /// Kalshi environment: prod, or demo to test against the sandbox
+    /// (demo needs its own KALSHI_API_KEY / private key; its markets are
+    /// stored under platform 'kalshi_demo', apart from production)
+    #[arg(long, default_value = "prod")]
+    kalshi_env: KalshiEnv,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    let log_level = std::env::var("RUST_LOG")
-        .map(|_| Level::DEBUG)
-        .unwrap_or(Level::INFO);
-    FmtSubscriber::builder().with_max_level(log_level).init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
 
     info!("Kalshi Orderbook Stream starting...");
     info!(
@@ -64,6 +68,7 @@ async fn main() -> Result<()> {
     );
     info!("Assets: {}", args.assets);
     info!("Max expiry: {} hours", args.max_expiry_hours);
+    info!("Kalshi environment: {}", args.kalshi_env);
 
     // Parse assets
     let assets: Vec<String> = args
@@ -96,7 +101,7 @@ async fn main() -> Result<()> {
     }
 
     // Create Kalshi REST client for market discovery
-    let kalshi = KalshiClient::new();
+    let kalshi = KalshiClient::new_with_env(args.kalshi_env);
 
     // Main loop
     loop {
@@ -165,7 +170,7 @@ async fn run_stream(
 
     for market in &filtered_markets {
         let insert: common::KalshiMarketInsert = market.into();
-        match common::upsert_kalshi_market(db.pool(), &insert).await {
+        match common::upsert_kalshi_market(db.pool(), &insert, args.kalshi_env).await {
             Ok(id) => {
                 ticker_to_db_id.insert(market.ticker.clone(), id);
             }
@@ -197,6 +202,7 @@ async fn run_stream(
     let ws_api_key = api_key.clone();
     let ws_private_key = private_key_pem.clone();
     let reconnect_interval = Duration::from_secs(args.reconnect_interval);
+    let kalshi_env = args.kalshi_env;
 
     let ws_handle = tokio::spawn(async move {
         run_kalshi_orderbook_stream(
@@ -205,6 +211,7 @@ async fn run_stream(
             ws_api_key,
             ws_private_key,
             reconnect_interval,
+            kalshi_env,
         )
         .await
     });
@@ -8,12 +8,11 @@ use std::time::Duration;
 use anyhow::Result;
 use clap::Parser;
 use tokio::time::sleep;
-use tracing::{error, info, warn, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::{error, info, warn};
 
 use common::{
-    deactivate_expired_markets, upsert_kalshi_market, upsert_market, Config, Database, GammaClient,
-    KalshiClient, KalshiMarketInsert,
+    deactivate_expired_markets, upsert_kalshi_market, upsert_market, Config, Database, GammaApi,
+    GammaClient, KalshiClient, KalshiEnv, KalshiMarketInsert,
 };
 
 /// Market Scanner - discovers and tracks prediction markets
@@ -32,14 +31,21 @@ struct Args {
     /// Kalshi assets to scan (comma-separated)
     #[arg(long, default_value = "BTC,ETH,SOL,XRP")]
     kalshi_assets: String,
+
+    /// Kalshi environment: prod, or demo to scan the sandbox (demo markets
+    /// are stored under platform 'kalshi_demo', apart from production)
+    #[arg(long, default_value = "prod")]
+    kalshi_env: KalshiEnv,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    FmtSubscriber::builder().with_max_level(Level::INFO).init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
 
     info!("Market Scanner starting...");
     info!(
@@ -65,8 +71,8 @@ async fn main() -> Result<()> {
     let gamma = GammaClient::new(&config);
     info!("Gamma API client initialized (Polymarket)");
 
-    let kalshi = KalshiClient::new();
-    info!("Kalshi API client initialized");
+    let kalshi = KalshiClient::new_with_env(args.kalshi_env);
+    info!("Kalshi API client initialized ({})", args.kalshi_env);
 
     // Parse Kalshi assets
     let kalshi_assets: Vec<String> = args
@@ -78,7 +84,7 @@ async fn main() -> Result<()> {
 
     // Main loop
     loop {
-        match scan_markets(&gamma, &kalshi, &db, &kalshi_assets).await {
+        match scan_markets(&gamma, &kalshi, args.kalshi_env, &db, &kalshi_assets).await {
             Ok(stats) => {
                 info!(
                     "Scan complete: {} Polymarket, {} Kalshi upserted, {} expired",
@@ -111,8 +117,9 @@ struct ScanStats {
 
 /// Perform a single market scan cycle.
 async fn scan_markets(
-    gamma: &GammaClient,
+    gamma: &dyn GammaApi,
     kalshi: &KalshiClient,
+    kalshi_env: KalshiEnv,
     db: &Database,
     kalshi_assets: &[String],
 ) -> Result<ScanStats> {
@@ -151,7 +158,7 @@ async fn scan_markets(
         }
 
         let insert: KalshiMarketInsert = market.into();
-        match upsert_kalshi_market(db.pool(), &insert).await {
+        match upsert_kalshi_market(db.pool(), &insert, kalshi_env).await {
             Ok(_) => kalshi_upserted += 1,
             Err(e) => warn!("Failed to upsert Kalshi {}: {}", market.ticker, e),
         }
@@ -4,13 +4,14 @@
 //! and detects when the direction flips (UP->DOWN or DOWN->UP).
 //!
 //! Uses Chainlink prices from Polymarket RTDS instead of Binance.
-//! Key difference: Chainlink has no klines, so we capture the first price
-//! at market discovery as the "open price".
+//! Key difference: Chainlink has no klines, so the "open price" is the first
+//! buffered price at or after the market's start.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
-use common::ChainlinkPriceBuffer;
+use common::{ChainlinkPriceBuffer, MarketWithOrderbook};
 use rust_decimal::Decimal;
 use tracing::{debug, info};
 use uuid::Uuid;
@@ -33,6 +34,16 @@ pub enum FlipType {
     UpToDown, // UP -> DOWN -> Buy NO
 }
 
+impl FlipType {
+    /// Confirmed direction the flip moved into.
+    pub fn direction(&self) -> Direction {
+        match self {
+            FlipType::DownToUp => Direction::Up,
+            FlipType::UpToDown => Direction::Down,
+        }
+    }
+}
+
 impl std::fmt::Display for FlipType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -57,26 +68,66 @@ pub struct MarketState {
     /// Count of consecutive readings in the same direction
     pub consecutive_count: u32,
     pub has_traded: bool,
+    /// Timestamp of the price reading that last updated the direction
+    pub last_price_at: Option<DateTime<Utc>>,
+    /// Confirmed flip held back by a gate, offered again next reading
+    pub deferred_flip: Option<(FlipType, &'static str)>,
 }
 
 /// Misprice detector that tracks direction flips using Chainlink prices.
 pub struct MispriceDetector {
     states: HashMap<Uuid, MarketState>,
+    /// How far the orderbook snapshot may trail the flip's price reading.
+    max_snapshot_lag: Option<chrono::Duration>,
+    /// EMA weight of the newest Chainlink tick (1 = raw latest price)
+    price_smoothing: Decimal,
 }
 
 impl MispriceDetector {
     pub fn new() -> Self {
         Self {
             states: HashMap::new(),
+            max_snapshot_lag: None,
+            price_smoothing: Decimal::ONE,
         }
     }
 
+    /// Veto flips whose orderbook snapshot is more than `max_lag` older than
+    /// the price reading that triggered them.
+    pub fn with_max_snapshot_lag(mut self, max_lag: Option<chrono::Duration>) -> Self {
+        self.max_snapshot_lag = max_lag;
+        self
+    }
+
+    /// Compare an EMA of recent Chainlink ticks to the open instead of the raw
+    /// latest tick, with `alpha` as the newest tick's weight (1 keeps raw prices).
+    pub fn with_price_smoothing(mut self, alpha: Decimal) -> Self {
+        self.price_smoothing = alpha;
+        self
+    }
+
+    /// Price to compare against the open, with the Chainlink timestamp of the
+    /// latest tick behind it. None if the symbol has no price yet.
+    pub fn current_price(
+        &self,
+        price_buffer: &ChainlinkPriceBuffer,
+        symbol: &str,
+    ) -> Option<(Decimal, DateTime<Utc>)> {
+        let (latest, price_at) = price_buffer.get_latest_with_time(symbol)?;
+        let price = price_buffer
+            .ema(symbol, self.price_smoothing)
+            .unwrap_or(latest);
+        Some((price, price_at))
+    }
+
     /// Get or create state for a market, capturing open price from Chainlink buffer.
     /// Returns (state_ref, is_new) where is_new indicates if this is a newly discovered market.
     /// Logs the open price when a new market is discovered.
     ///
     /// Unlike Binance which provides klines, Chainlink only provides point prices.
-    /// We capture the first price received after market discovery as the "open price".
+    /// The open is the earliest buffered price at or after `start_time`; if that
+    /// is more than `open_tolerance` after the start (or there is none yet), no
+    /// state is created and None is returned.
     pub fn get_or_create_state(
         &mut self,
         market_id: Uuid,
@@ -84,13 +135,15 @@ impl MispriceDetector {
         start_time: DateTime<Utc>,
         price_buffer: &mut ChainlinkPriceBuffer,
         symbol: &str,
+        open_tolerance: Duration,
     ) -> Option<(&mut MarketState, bool)> {
         let is_new = !self.states.contains_key(&market_id);
 
         if is_new {
-            // Get or capture the open price from the Chainlink buffer
-            // This captures the current price as the "open" if not already captured
-            let open_price = price_buffer.get_or_capture_open(symbol, start_time)?;
+            // Take the open from the buffer's history rather than the
+            // discovery-time price, which for a late-discovered market isn't the open
+            let open_price =
+                price_buffer.get_or_capture_open_near(symbol, start_time, open_tolerance)?;
 
             // Log discovery of new market with its open price
             info!(
@@ -112,6 +165,8 @@ impl MispriceDetector {
                     raw_direction: None,
                     consecutive_count: 0,
                     has_traded: false,
+                    last_price_at: None,
+                    deferred_flip: None,
                 },
             );
         }
@@ -134,10 +189,14 @@ impl MispriceDetector {
     /// Requires DEBOUNCE_COUNT consecutive readings on the same side before
     /// confirming a direction change. This prevents false signals from
     /// rapid price fluctuations around the open price.
+    ///
+    /// `price_at` is when `current_price` was observed; it is kept on the
+    /// state so a flip can be checked against the orderbook snapshot's age.
     pub fn update_and_check_flip(
         &mut self,
         market_id: &Uuid,
         current_price: Decimal,
+        price_at: DateTime<Utc>,
     ) -> Option<(FlipType, &'static str)> {
         let state = self.states.get_mut(market_id)?;
 
@@ -145,6 +204,7 @@ impl MispriceDetector {
         if state.has_traded {
             return None;
         }
+        state.last_price_at = Some(price_at);
 
         let new_direction = Self::calculate_direction(current_price, state.open_price);
 
@@ -162,6 +222,13 @@ impl MispriceDetector {
             );
         }
 
+        // A deferred flip fires again while the price still agrees with it
+        if let Some(flip) = state.deferred_flip.take() {
+            if flip.0.direction() == new_direction {
+                return Some(flip);
+            }
+        }
+
         // Check if we have enough consecutive readings to confirm direction
         if state.consecutive_count < DEBOUNCE_COUNT {
             return None;
@@ -198,6 +265,38 @@ impl MispriceDetector {
         None
     }
 
+    /// How far `market`'s orderbook snapshot trails the latest price reading
+    /// for it. Negative when the snapshot is newer; None before any reading.
+    pub fn snapshot_lag(&self, market: &MarketWithOrderbook) -> Option<chrono::Duration> {
+        let price_at = self.states.get(&market.id)?.last_price_at?;
+        Some(price_at - market.captured_at)
+    }
+
+    /// Whether `market`'s snapshot is recent enough to trade the latest flip.
+    ///
+    /// Always true without a configured maximum, and before any price reading.
+    pub fn snapshot_lag_allows(&self, market: &MarketWithOrderbook) -> bool {
+        match (self.max_snapshot_lag, self.snapshot_lag(market)) {
+            (Some(max), Some(lag)) => lag <= max,
+            _ => true,
+        }
+    }
+
+    /// Hold a confirmed flip that a gate vetoed this cycle so the next price
+    /// reading offers it again, unless the direction has reversed by then.
+    pub fn defer_flip(&mut self, market_id: &Uuid, flip: (FlipType, &'static str)) {
+        if let Some(state) = self.states.get_mut(market_id) {
+            state.deferred_flip = Some(flip);
+        }
+    }
+
+    /// Whether `market_id` has a flip waiting from an earlier cycle.
+    pub fn has_deferred_flip(&self, market_id: &Uuid) -> bool {
+        self.states
+            .get(market_id)
+            .is_some_and(|s| s.deferred_flip.is_some())
+    }
+
     /// Mark a market as traded (no more trades on this market).
     pub fn mark_traded(&mut self, market_id: &Uuid) {
         if let Some(state) = self.states.get_mut(market_id) {
@@ -327,8 +426,87 @@ impl Default for MispriceDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::ChainlinkPrice;
     use rust_decimal_macros::dec;
 
+    #[test]
+    fn test_open_price_from_buffer_history() {
+        let start = Utc::now() - chrono::Duration::minutes(2);
+        let mut buffer = ChainlinkPriceBuffer::new(600);
+        for (offset_secs, value) in [
+            (-30, dec!(100)),
+            (2, dec!(101)),
+            (3, dec!(102)),
+            (110, dec!(105)),
+        ] {
+            buffer.update(&ChainlinkPrice {
+                symbol: "btc/usd".to_string(),
+                value,
+                timestamp: start + chrono::Duration::seconds(offset_secs),
+            });
+        }
+
+        let mut detector = MispriceDetector::new();
+        let market_id = Uuid::new_v4();
+        let tolerance = Duration::from_secs(5);
+
+        // Discovered two minutes in: the open is the first price after the start,
+        // not the latest one
+        let (state, is_new) = detector
+            .get_or_create_state(
+                market_id,
+                "BTC 5m",
+                start,
+                &mut buffer,
+                "btc/usd",
+                tolerance,
+            )
+            .expect("open within tolerance");
+        assert!(is_new);
+        assert_eq!(state.open_price, dec!(101));
+
+        // Nothing close enough to the start: skipped, no state created
+        // (the only price after a start 90s in arrives 20s later)
+        let late_start = start + chrono::Duration::seconds(90);
+        let other_id = Uuid::new_v4();
+        assert!(detector
+            .get_or_create_state(
+                other_id,
+                "BTC 5m",
+                late_start,
+                &mut buffer,
+                "btc/usd",
+                Duration::from_secs(10)
+            )
+            .is_none());
+        assert!(detector.get_state(&other_id).is_none());
+
+        // A price inside a looser tolerance is accepted
+        let (state, _) = detector
+            .get_or_create_state(
+                other_id,
+                "BTC 5m",
+                late_start,
+                &mut buffer,
+                "btc/usd",
+                Duration::from_secs(30),
+            )
+            .expect("open within tolerance");
+        assert_eq!(state.open_price, dec!(105));
+
+        // Unknown symbol has no history
+        assert!(detector
+            .get_or_create_state(
+                Uuid::new_v4(),
+                "ETH 5m",
+                start,
+                &mut buffer,
+                "eth/usd",
+                tolerance
+            )
+            .is_none());
+    }
+
     #[test]
     fn test_direction_calculation() {
         assert_eq!(
@@ -369,25 +547,27 @@ mod tests {
                 raw_direction: Some(Direction::Down),
                 consecutive_count: DEBOUNCE_COUNT,
                 has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
             },
         );
 
         // Single reading above open should NOT trigger flip (debouncing)
-        let result = detector.update_and_check_flip(&market_id, dec!(101));
+        let result = detector.update_and_check_flip(&market_id, dec!(101), Utc::now());
         assert!(
             result.is_none(),
             "Single UP reading should not trigger flip"
         );
 
         // Second reading above open still shouldn't flip (need DEBOUNCE_COUNT)
-        let result = detector.update_and_check_flip(&market_id, dec!(102));
+        let result = detector.update_and_check_flip(&market_id, dec!(102), Utc::now());
         assert!(
             result.is_none(),
             "Two UP readings should not trigger flip yet"
         );
 
         // Third consecutive reading above open SHOULD trigger flip
-        let result = detector.update_and_check_flip(&market_id, dec!(103));
+        let result = detector.update_and_check_flip(&market_id, dec!(103), Utc::now());
         assert!(
             result.is_some(),
             "Three consecutive UP readings should trigger flip"
@@ -413,15 +593,17 @@ mod tests {
                 raw_direction: Some(Direction::Down),
                 consecutive_count: DEBOUNCE_COUNT,
                 has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
             },
         );
 
         // Two UP readings (not enough to flip)
-        detector.update_and_check_flip(&market_id, dec!(101));
-        detector.update_and_check_flip(&market_id, dec!(102));
+        detector.update_and_check_flip(&market_id, dec!(101), Utc::now());
+        detector.update_and_check_flip(&market_id, dec!(102), Utc::now());
 
         // Now price goes back down - should reset counter
-        let result = detector.update_and_check_flip(&market_id, dec!(99));
+        let result = detector.update_and_check_flip(&market_id, dec!(99), Utc::now());
         assert!(
             result.is_none(),
             "Direction change should reset counter, no flip"
@@ -433,6 +615,50 @@ mod tests {
         assert_eq!(state.raw_direction, Some(Direction::Down));
     }
 
+    #[test]
+    fn test_deferred_flip_fires_until_direction_reverses() {
+        let mut detector = MispriceDetector::new();
+        let market_id = Uuid::new_v4();
+        detector.states.insert(
+            market_id,
+            MarketState {
+                market_id,
+                market_name: "Test Market".to_string(),
+                start_time: Utc::now(),
+                open_price: dec!(100),
+                confirmed_direction: Some(Direction::Down),
+                raw_direction: Some(Direction::Down),
+                consecutive_count: DEBOUNCE_COUNT,
+                has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
+            },
+        );
+
+        let mut flip = None;
+        for price in [dec!(101), dec!(102), dec!(103)] {
+            flip = detector.update_and_check_flip(&market_id, price, Utc::now());
+        }
+        let flip = flip.expect("third UP reading confirms the flip");
+
+        // Vetoed this cycle: the next reading in the same direction offers it again
+        detector.defer_flip(&market_id, flip);
+        assert!(detector.has_deferred_flip(&market_id));
+        assert_eq!(
+            detector.update_and_check_flip(&market_id, dec!(104), Utc::now()),
+            Some((FlipType::DownToUp, "YES"))
+        );
+        assert!(!detector.has_deferred_flip(&market_id));
+
+        // Deferred again, but the price fell back below the open: dropped
+        detector.defer_flip(&market_id, flip);
+        assert_eq!(
+            detector.update_and_check_flip(&market_id, dec!(99), Utc::now()),
+            None
+        );
+        assert!(!detector.has_deferred_flip(&market_id));
+    }
+
     #[test]
     fn test_no_flip_on_first_direction_confirmation() {
         let mut detector = MispriceDetector::new();
@@ -451,12 +677,14 @@ mod tests {
                 raw_direction: None,
                 consecutive_count: 0,
                 has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
             },
         );
 
         // First DEBOUNCE_COUNT readings should confirm direction but NOT trigger flip
         for i in 0..DEBOUNCE_COUNT {
-            let result = detector.update_and_check_flip(&market_id, dec!(101));
+            let result = detector.update_and_check_flip(&market_id, dec!(101), Utc::now());
             assert!(
                 result.is_none(),
                 "Reading {} should not trigger flip on initial confirmation",
@@ -1,7 +1,8 @@
 //! Exit management for trailing take profit strategy.
 //!
 //! After entry, tracks peak price and exits when price drops X% from peak
-//! to lock in gains. Also supports optional hard take profit target.
+//! to lock in gains. Also supports optional hard take profit target and a
+//! time stop that closes positions shortly before expiry.
 
 use std::collections::{HashMap, HashSet};
 
@@ -11,7 +12,7 @@ use rust_decimal_macros::dec;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use common::{execute_sell_order, CachedAuth, MarketWithOrderbook};
+use common::{execute_sell_order, fmt_price, fmt_shares, CachedAuth, MarketWithOrderbook};
 
 /// Maximum number of exit attempts before abandoning a position.
 const MAX_EXIT_ATTEMPTS: u32 = 3;
@@ -43,6 +44,7 @@ pub struct ActivePosition {
 pub enum ExitReason {
     TrailingStop,
     TakeProfit,
+    TimeStop,
     MarketExpiry,
 }
 
@@ -51,11 +53,39 @@ impl std::fmt::Display for ExitReason {
         match self {
             ExitReason::TrailingStop => write!(f, "TRAILING_STOP"),
             ExitReason::TakeProfit => write!(f, "TAKE_PROFIT"),
+            ExitReason::TimeStop => write!(f, "TIME_STOP"),
             ExitReason::MarketExpiry => write!(f, "MARKET_EXPIRY"),
         }
     }
 }
 
+/// Close positions a fixed time before expiry rather than holding them into
+/// settlement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeStop {
+    /// Fire once the market is this many seconds (or fewer) from its end time
+    pub secs_before_expiry: i64,
+    /// Only fire while the position is below its entry price
+    pub only_if_underwater: bool,
+}
+
+impl TimeStop {
+    /// Whether a position entered at `entry_price`, currently bid at
+    /// `current_price`, should be closed at `now`.
+    pub fn triggered(
+        &self,
+        entry_price: Decimal,
+        current_price: Decimal,
+        end_time: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if (end_time - now).num_seconds() > self.secs_before_expiry {
+            return false;
+        }
+        !self.only_if_underwater || current_price < entry_price
+    }
+}
+
 /// Result of an exit attempt.
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -83,6 +113,8 @@ pub struct ExitManager {
     trailing_stop_pct: Decimal,
     /// Optional take profit percentage (e.g., 0.30 = 30% profit)
     take_profit_pct: Option<Decimal>,
+    /// Optional time-before-expiry exit
+    time_stop: Option<TimeStop>,
     /// Dry run mode
     dry_run: bool,
 }
@@ -98,13 +130,20 @@ impl ExitManager {
             active_positions: HashMap::new(),
             trailing_stop_pct,
             take_profit_pct,
+            time_stop: None,
             dry_run,
         }
     }
 
-    /// Check if trailing exit is enabled.
+    /// Also close positions shortly before expiry.
+    pub fn with_time_stop(mut self, time_stop: TimeStop) -> Self {
+        self.time_stop = Some(time_stop);
+        self
+    }
+
+    /// Check if any exit (trailing stop or time stop) is enabled.
     pub fn is_enabled(&self) -> bool {
-        self.trailing_stop_pct > dec!(0)
+        self.trailing_stop_pct > dec!(0) || self.time_stop.is_some()
     }
 
     /// Add a position after fill confirmed.
@@ -163,6 +202,7 @@ impl ExitManager {
         // Build a map for quick market lookup
         let market_map: HashMap<Uuid, &MarketWithOrderbook> =
             markets.iter().map(|m| (m.id, m)).collect();
+        let now = Utc::now();
 
         // First pass: update peaks and collect positions to exit
         let mut exits_to_process: Vec<(ActivePosition, Decimal, ExitReason)> = Vec::new();
@@ -230,6 +270,22 @@ impl ExitManager {
                 }
             }
 
+            // Check for time stop trigger
+            if let Some(time_stop) = self.time_stop {
+                if time_stop.triggered(position.entry_price, current_price, market.end_time, now) {
+                    info!(
+                        "[EXIT_MGR] TIME STOP triggered: {} {} @ ${:.3} ({}s to expiry, {:+.1}%)",
+                        position.market_name,
+                        position.side,
+                        current_price,
+                        (market.end_time - now).num_seconds(),
+                        profit_pct * dec!(100)
+                    );
+                    exits_to_process.push((position.clone(), current_price, ExitReason::TimeStop));
+                    continue;
+                }
+            }
+
             // Calculate drawdown from peak
             let drawdown = if position.peak_price > dec!(0) {
                 (position.peak_price - current_price) / position.peak_price
@@ -238,7 +294,7 @@ impl ExitManager {
             };
 
             // Check for trailing stop trigger
-            if drawdown >= self.trailing_stop_pct {
+            if self.trailing_stop_pct > dec!(0) && drawdown >= self.trailing_stop_pct {
                 info!(
                     "[EXIT_MGR] TRAILING STOP triggered: {} {} @ ${:.3} (peak ${:.3}, down {:.1}%)",
                     position.market_name,
@@ -348,8 +404,13 @@ impl ExitManager {
         {
             Ok(order_id) => {
                 info!(
-                    "[EXIT] {} {} {} @ ${:.3} (order_id: {})",
-                    reason, position.market_name, position.side, exit_price, order_id
+                    "[EXIT] {} {} {} {} shares @ {} (order_id: {})",
+                    reason,
+                    position.market_name,
+                    position.side,
+                    fmt_shares(position.shares),
+                    fmt_price(exit_price),
+                    order_id
                 );
 
                 ExitResult {
@@ -444,6 +505,17 @@ impl ExitManager {
         if let Some(tp) = self.take_profit_pct {
             info!("  Take Profit:       {:>6.1}%", tp * dec!(100));
         }
+        if let Some(ts) = self.time_stop {
+            info!(
+                "  Time Stop:         {:>6}s ({})",
+                ts.secs_before_expiry,
+                if ts.only_if_underwater {
+                    "underwater only"
+                } else {
+                    "always"
+                }
+            );
+        }
         info!("---------------------------------------------------------------");
 
         for pos in self.active_positions.values() {
@@ -460,6 +532,31 @@ impl ExitManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
+
+    fn market(id: Uuid, end_time: DateTime<Utc>, yes_best_bid: Decimal) -> MarketWithOrderbook {
+        MarketWithOrderbook {
+            id,
+            condition_id: "cond".to_string(),
+            market_type: "up_down".to_string(),
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "yes".to_string(),
+            no_token_id: "no".to_string(),
+            name: "Test Market".to_string(),
+            end_time,
+            is_active: true,
+            yes_best_ask: Some(yes_best_bid + dec!(0.01)),
+            yes_best_bid: Some(yes_best_bid),
+            no_best_ask: None,
+            no_best_bid: None,
+            yes_asks: None,
+            no_asks: None,
+            yes_bids: None,
+            no_bids: None,
+            captured_at: Utc::now(),
+        }
+    }
 
     #[test]
     fn test_exit_manager_creation() {
@@ -491,4 +588,79 @@ mod tests {
         let manager = ExitManager::new(dec!(0), None, true);
         assert!(!manager.is_enabled());
     }
+
+    #[test]
+    fn test_time_stop_boundary() {
+        let stop = TimeStop {
+            secs_before_expiry: 60,
+            only_if_underwater: false,
+        };
+        let now = Utc::now();
+        let fires = |secs_left| {
+            stop.triggered(
+                dec!(0.40),
+                dec!(0.50),
+                now + Duration::seconds(secs_left),
+                now,
+            )
+        };
+
+        assert!(!fires(61));
+        assert!(fires(60));
+        assert!(fires(10));
+        // Already past expiry still closes
+        assert!(fires(-5));
+    }
+
+    #[test]
+    fn test_time_stop_only_if_underwater() {
+        let stop = TimeStop {
+            secs_before_expiry: 60,
+            only_if_underwater: true,
+        };
+        let now = Utc::now();
+        let end = now + Duration::seconds(30);
+
+        assert!(stop.triggered(dec!(0.40), dec!(0.35), end, now));
+        assert!(!stop.triggered(dec!(0.40), dec!(0.40), end, now));
+        assert!(!stop.triggered(dec!(0.40), dec!(0.55), end, now));
+        // Underwater but outside the window
+        assert!(!stop.triggered(dec!(0.40), dec!(0.35), now + Duration::seconds(120), now));
+    }
+
+    #[tokio::test]
+    async fn test_check_exits_time_stop() {
+        // Time stop alone enables the manager; no trailing stop fires
+        let mut manager = ExitManager::new(dec!(0), None, true).with_time_stop(TimeStop {
+            secs_before_expiry: 60,
+            only_if_underwater: true,
+        });
+        assert!(manager.is_enabled());
+
+        let (underwater, profitable, early) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        for id in [underwater, profitable, early] {
+            manager.add_position(
+                id,
+                "Test Market".to_string(),
+                "token123".to_string(),
+                "YES".to_string(),
+                dec!(10),
+                dec!(0.40),
+            );
+        }
+        let now = Utc::now();
+        let markets = vec![
+            market(underwater, now + Duration::seconds(30), dec!(0.30)),
+            market(profitable, now + Duration::seconds(30), dec!(0.60)),
+            market(early, now + Duration::seconds(600), dec!(0.30)),
+        ];
+
+        let exits = manager.check_exits(&markets, &mut None).await;
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].market_id, underwater);
+        assert_eq!(exits[0].reason, ExitReason::TimeStop);
+        assert_eq!(exits[0].pnl, dec!(-1.0));
+        assert!(!manager.has_position(&underwater));
+        assert_eq!(manager.position_count(), 2);
+    }
 }
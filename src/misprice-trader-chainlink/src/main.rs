@@ -23,13 +23,20 @@ use clap::Parser;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tracing::{debug, error, info, warn};
-use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
+use common::assets::{chainlink_symbol, mapped_assets, parse_assets};
+use common::models::{TradeIntent, TradeMode, TradeOutcome};
 use common::{
-    asset_to_chainlink_symbol, calculate_fill_price_with_slippage, execute_trade,
-    get_15m_updown_markets_with_orderbooks, CachedAuth, ChainlinkPriceBuffer, Config, Database,
-    DryRunPortfolio, GammaClient, PolymarketRtdsClient, SimulatedPosition,
+    asset_to_chainlink_symbol, calculate_fill_price_with_slippage, cycle_interval, execute_trade,
+    fetch_tick_size, fmt_price, fmt_shares, fresh_retry_ask,
+    get_15m_updown_markets_with_orderbooks, log_unresolved_live_positions, record_trade_audit,
+    resolve_live_settlements, retain_valid_depth, CachedAuth, ChainlinkPriceBuffer, Config,
+    DailyPnlStop, Database, DryRunPortfolio, EntryOffset, FillPriceModel, GammaClient,
+    LivePosition, LivePositions, MarketRepository, MarketRotation, OrderRejection, PaperFillMode,
+    PaperOrders, PolymarketRtdsClient, RetryConfig, SettlementTimeout, SideFilter,
+    SimulatedPosition, StreamHealth, TradeThrottle, UnresolvedPolicy, DEFAULT_MAX_SHARES,
+    DEFAULT_TICK_SIZE,
 };
 
 mod detector;
@@ -38,10 +45,13 @@ mod metrics;
 mod order_manager;
 
 use detector::MispriceDetector;
-use exit_manager::ExitManager;
+use exit_manager::{ExitManager, TimeStop};
 use metrics::Metrics;
 use order_manager::OrderManager;
 
+/// Strategy tag recorded with each trade intent.
+const STRATEGY_TAG: &str = "misprice_chainlink";
+
 /// Misprice Trader (Chainlink) - trades on price direction flips using Chainlink prices
 #[derive(Parser, Debug)]
 #[command(name = "misprice-trader-chainlink")]
@@ -51,14 +61,60 @@ struct Args {
     #[arg(long)]
     dry_run: bool,
 
+    /// How to account for unresolved dry-run positions at shutdown
+    /// (mark-to-market, assume-loss, win-if-above-half, leave-pending)
+    #[arg(long, default_value = "mark-to-market")]
+    unresolved_policy: UnresolvedPolicy,
+
+    /// Virtual starting bankroll in USDC for dry-run; trades beyond the
+    /// available balance (bankroll + realized P&L - open cost) are refused
+    #[arg(long)]
+    dry_run_balance: Option<f64>,
+
+    /// Stop opening new positions once the day's realized P&L reaches this
+    /// many USDC (open positions still exit and settle; resets at UTC midnight)
+    #[arg(long)]
+    daily_profit_target: Option<f64>,
+
+    /// Stop opening new positions once the day's realized loss reaches this
+    /// many USDC (open positions still exit and settle; resets at UTC midnight)
+    #[arg(long)]
+    daily_max_loss: Option<f64>,
+
     /// Limit order price (place orders at this price)
     #[arg(long, default_value = "0.40")]
     limit_price: f64,
 
+    /// Where to price entries: ask (take at the limit price), midpoint
+    /// (between best bid and ask), or join-bid (best bid + one tick). The
+    /// passive modes rest on the book until filled or --cancel-timeout
+    #[arg(long, default_value = "ask")]
+    entry_offset: EntryOffset,
+
+    /// Highest price a retry after a price-movement rejection may pay
+    /// (default: the limit price, so no retry above it)
+    #[arg(long)]
+    retry_max_price: Option<f64>,
+
+    /// Price used to plan fills from orderbook depth: vwap (size-weighted
+    /// average), worst-touched (deepest level reached) or best-only
+    #[arg(long, default_value = "vwap")]
+    fill_model: FillPriceModel,
+
+    /// How dry-run orders fill: optimistic (immediately at the estimated
+    /// fill) or realistic (only once a later snapshot's ask reaches the
+    /// order price; cancelled after --cancel-timeout otherwise)
+    #[arg(long, default_value = "optimistic")]
+    paper_fill: PaperFillMode,
+
     /// Position size in USDC
     #[arg(long, default_value = "5")]
     position_size: f64,
 
+    /// Maximum shares per order; a signal needing more (position size / price) is skipped
+    #[arg(long, default_value_t = DEFAULT_MAX_SHARES)]
+    max_shares: Decimal,
+
     /// Maximum time to market expiry in minutes
     #[arg(long, default_value = "10")]
     max_expiry_minutes: i64,
@@ -71,7 +127,7 @@ struct Args {
     #[arg(long, default_value = "1")]
     max_orderbook_age: i32,
 
-    /// Assets to trade (comma-separated)
+    /// Assets to trade (comma-separated), or ALL for every asset with active markets
     #[arg(long, default_value = "BTC")]
     assets: String,
 
@@ -86,18 +142,81 @@ struct Args {
     /// Take profit percentage (exit immediately when profit exceeds this). Optional.
     #[arg(long)]
     take_profit_pct: Option<f64>,
+
+    /// Close any open position this many seconds before market expiry,
+    /// regardless of price. Optional.
+    #[arg(long)]
+    time_stop_secs: Option<i64>,
+
+    /// Only apply --time-stop-secs to positions trading below their entry price
+    #[arg(long)]
+    time_stop_if_underwater: bool,
+
+    /// Skip a signal when the depth-based fill is more than this percent above
+    /// the best ask (e.g. 5 = 5%), even if it is under the limit price. Optional.
+    #[arg(long)]
+    max_slippage_pct: Option<f64>,
+
+    /// Fully process at most this many markets per cycle, most urgent first
+    /// (soonest expiry, then cheapest ask); the rest wait for a later cycle. Optional.
+    #[arg(long)]
+    max_markets_per_cycle: Option<usize>,
+
+    /// Smooth Chainlink ticks with an EMA before comparing to the open: weight
+    /// of the newest tick, in (0, 1]. 1 uses the raw latest price
+    #[arg(long, default_value = "1")]
+    price_smoothing: f64,
+
+    /// Skip a flip when the orderbook snapshot is more than this many ms older
+    /// than the price tick that triggered it (the book hasn't caught up with
+    /// the move yet). Optional.
+    #[arg(long)]
+    max_snapshot_lag_ms: Option<u64>,
+
+    /// Only trade one side of a signal (both, yes-only, no-only); signals for
+    /// the other side are still tracked but not acted upon
+    #[arg(long, default_value = "both")]
+    side_filter: SideFilter,
+
+    /// Shortest interval between trading cycles in ms, used when a market is
+    /// about to expire
+    #[arg(long, default_value = "500")]
+    min_cycle_ms: u64,
+
+    /// Longest interval between trading cycles in ms, used when every market
+    /// is far from expiry
+    #[arg(long, default_value = "2000")]
+    max_cycle_ms: u64,
+
+    /// Minimum milliseconds between live orders across all markets, on top of
+    /// per-market cooldowns (0 = no limit; ignored in dry-run)
+    #[arg(long, default_value = "0")]
+    min_trade_interval_ms: u64,
+
+    /// Skip markets whose Chainlink price is older than this many seconds,
+    /// instead of trading on a frozen price
+    #[arg(long, default_value = "30")]
+    max_price_age_secs: u64,
+
+    /// A market's open is the first Chainlink price at or after its start;
+    /// skip the market if that price arrived more than this many seconds late
+    #[arg(long, default_value = "5")]
+    open_price_tolerance_secs: u64,
+
+    /// RTDS connection attempts (with jittered exponential backoff) before a
+    /// reconnect is given up
+    #[arg(long, default_value = "5")]
+    rtds_reconnect_attempts: u32,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
 
     // Validate percentage arguments
     if !(0.0..=1.0).contains(&args.trailing_stop_pct) {
@@ -106,6 +225,51 @@ async fn main() -> Result<()> {
             args.trailing_stop_pct
         );
     }
+    if let Some(max) = args.max_slippage_pct {
+        if max < 0.0 {
+            anyhow::bail!("--max-slippage-pct must be non-negative (got {})", max);
+        }
+    }
+    if args.max_price_age_secs == 0 {
+        anyhow::bail!("--max-price-age-secs must be at least 1");
+    }
+    if let Some(max) = args.retry_max_price {
+        if max < args.limit_price || max >= 1.0 {
+            anyhow::bail!(
+                "--retry-max-price must be between --limit-price ({}) and 1.0 (got {})",
+                args.limit_price,
+                max
+            );
+        }
+    }
+    if !(args.price_smoothing > 0.0 && args.price_smoothing <= 1.0) {
+        anyhow::bail!(
+            "--price-smoothing must be in (0, 1] (got {})",
+            args.price_smoothing
+        );
+    }
+    if args.rtds_reconnect_attempts == 0 {
+        anyhow::bail!("--rtds-reconnect-attempts must be at least 1");
+    }
+    if args.max_markets_per_cycle == Some(0) {
+        anyhow::bail!("--max-markets-per-cycle must be at least 1");
+    }
+    if args.min_cycle_ms == 0 || args.min_cycle_ms > args.max_cycle_ms {
+        anyhow::bail!(
+            "--min-cycle-ms must be at least 1 and no greater than --max-cycle-ms (got {} / {})",
+            args.min_cycle_ms,
+            args.max_cycle_ms
+        );
+    }
+    match args.time_stop_secs {
+        Some(secs) if secs <= 0 => {
+            anyhow::bail!("--time-stop-secs must be positive (got {})", secs);
+        }
+        None if args.time_stop_if_underwater => {
+            anyhow::bail!("--time-stop-if-underwater requires --time-stop-secs");
+        }
+        _ => {}
+    }
     if let Some(tp) = args.take_profit_pct {
         if !(0.0..=10.0).contains(&tp) {
             anyhow::bail!(
@@ -114,11 +278,26 @@ async fn main() -> Result<()> {
             );
         }
     }
+    common::validation::validate_max_shares(args.max_shares)?;
+    if let Some(balance) = args.dry_run_balance {
+        common::validation::validate_positive("dry-run-balance", balance)?;
+    }
+    if let Some(target) = args.daily_profit_target {
+        common::validation::validate_positive("daily-profit-target", target)?;
+    }
+    if let Some(loss) = args.daily_max_loss {
+        common::validation::validate_positive("daily-max-loss", loss)?;
+    }
 
     info!("=== Misprice Trader (CHAINLINK) ===");
     info!("Price source: Polymarket RTDS (Chainlink oracle)");
     info!("Limit price: ${}", args.limit_price);
+    if let Some(max) = args.retry_max_price {
+        info!("Retry max price: ${}", max);
+    }
+    info!("Entry offset: {}", args.entry_offset);
     info!("Position size: ${}", args.position_size);
+    info!("Max shares per order: {}", args.max_shares);
     info!(
         "Expiry window: {}-{} minutes",
         args.min_expiry_minutes, args.max_expiry_minutes
@@ -126,13 +305,54 @@ async fn main() -> Result<()> {
     info!("Max orderbook age: {}s", args.max_orderbook_age);
     info!("Assets: {}", args.assets);
     info!("Cancel timeout: {}s", args.cancel_timeout);
+    info!("Max Chainlink price age: {}s", args.max_price_age_secs);
+    info!("Open price tolerance: {}s", args.open_price_tolerance_secs);
+    info!("RTDS reconnect attempts: {}", args.rtds_reconnect_attempts);
     if args.trailing_stop_pct > 0.0 {
         info!("Trailing stop: {:.1}%", args.trailing_stop_pct * 100.0);
         if let Some(tp) = args.take_profit_pct {
             info!("Take profit: {:.1}%", tp * 100.0);
         }
     }
+    if let Some(secs) = args.time_stop_secs {
+        info!(
+            "Time stop: {}s before expiry{}",
+            secs,
+            if args.time_stop_if_underwater {
+                " (underwater positions only)"
+            } else {
+                ""
+            }
+        );
+    }
+    info!(
+        "Cycle interval: {}-{}ms (adaptive to time-to-expiry)",
+        args.min_cycle_ms, args.max_cycle_ms
+    );
+    if args.side_filter != SideFilter::Both {
+        info!("Side filter: {}", args.side_filter);
+    }
+    if args.price_smoothing < 1.0 {
+        info!("Price smoothing: EMA alpha {}", args.price_smoothing);
+    }
+    if let Some(ms) = args.max_snapshot_lag_ms {
+        info!("Max snapshot lag behind price: {}ms", ms);
+    }
     info!("Dry run: {}", args.dry_run);
+    info!("Fill model: {}", args.fill_model);
+    if args.dry_run {
+        info!("Paper fills: {}", args.paper_fill);
+        info!("Unresolved policy: {}", args.unresolved_policy);
+        if let Some(balance) = args.dry_run_balance {
+            info!("Virtual balance: ${:.2}", balance);
+        }
+    }
+    if let Some(target) = args.daily_profit_target {
+        info!("Daily profit target: ${:.2}", target);
+    }
+    if let Some(loss) = args.daily_max_loss {
+        info!("Daily max loss: ${:.2}", loss);
+    }
 
     // Load config and connect to database
     dotenvy::dotenv().ok();
@@ -142,23 +362,17 @@ async fn main() -> Result<()> {
 
     info!("Connected to database");
 
-    // Parse assets
-    let assets: Vec<String> = args
-        .assets
-        .split(',')
-        .map(|s| s.trim().to_uppercase())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    if assets.is_empty() {
-        anyhow::bail!("No valid assets specified");
-    }
-
-    // Build Chainlink symbols list
-    let chainlink_symbols: Vec<String> = assets
-        .iter()
-        .filter_map(|a| asset_to_chainlink_symbol(a).map(|s| s.to_string()))
-        .collect();
+    // Parse assets (ALL = every asset with active markets), keeping those
+    // with a Chainlink feed
+    let selection = parse_assets(&args.assets)?;
+    let (assets, chainlink_symbols): (Vec<String>, Vec<String>) = mapped_assets(
+        &selection.resolve(db.pool()).await?,
+        "Chainlink",
+        chainlink_symbol,
+    )
+    .into_iter()
+    .map(|(asset, symbol)| (asset, symbol.to_string()))
+    .unzip();
 
     if chainlink_symbols.is_empty() {
         anyhow::bail!("No supported assets specified");
@@ -169,16 +383,45 @@ async fn main() -> Result<()> {
     // Convert parameters to Decimal
     let limit_price = Decimal::try_from(args.limit_price).context("Invalid limit_price")?;
     let position_size = Decimal::try_from(args.position_size).context("Invalid position_size")?;
+    let max_shares = args.max_shares;
 
     // Initialize components
     // Chainlink buffer needs to track open prices per market start time
-    let mut price_buffer = ChainlinkPriceBuffer::new(120); // ~2 min history at 1/sec
-    let mut detector = MispriceDetector::new();
+    let mut price_buffer = ChainlinkPriceBuffer::new(600); // ~10 min history at 1/sec, for opens
+    let max_snapshot_lag = args
+        .max_snapshot_lag_ms
+        .map(|ms| chrono::Duration::milliseconds(ms as i64));
+    let price_smoothing =
+        Decimal::try_from(args.price_smoothing).context("Invalid price_smoothing")?;
+    let mut detector = MispriceDetector::new()
+        .with_max_snapshot_lag(max_snapshot_lag)
+        .with_price_smoothing(price_smoothing);
     let mut metrics = Metrics::new();
     let mut portfolio = DryRunPortfolio::new();
+    if let Some(balance) = args.dry_run_balance {
+        portfolio =
+            portfolio.with_balance(Decimal::try_from(balance).context("Invalid dry_run_balance")?);
+    }
+    let mut daily_stop = DailyPnlStop::new(
+        args.daily_profit_target
+            .map(Decimal::try_from)
+            .transpose()
+            .context("Invalid daily_profit_target")?,
+        args.daily_max_loss
+            .map(Decimal::try_from)
+            .transpose()
+            .context("Invalid daily_max_loss")?,
+    );
+    let mut paper_orders = PaperOrders::new(args.cancel_timeout);
     let mut cached_auth: Option<CachedAuth> = None;
+    let mut throttle = if args.dry_run {
+        TradeThrottle::disabled()
+    } else {
+        TradeThrottle::new(Duration::from_millis(args.min_trade_interval_ms))
+    };
     // Track (market_id, side) - only trade once per market per side
     let mut traded_positions: HashSet<(Uuid, String)> = HashSet::new();
+    let mut rotation = MarketRotation::default();
     // Order manager for tracking pending orders and auto-cancel (live trading only)
     let mut order_manager = OrderManager::new(args.cancel_timeout);
     // Exit manager for trailing stop and take profit exits
@@ -187,12 +430,24 @@ async fn main() -> Result<()> {
         .take_profit_pct
         .map(|tp| Decimal::try_from(tp).unwrap_or(dec!(0)));
     let mut exit_manager = ExitManager::new(trailing_stop_pct, take_profit_pct, args.dry_run);
+    if let Some(secs_before_expiry) = args.time_stop_secs {
+        exit_manager = exit_manager.with_time_stop(TimeStop {
+            secs_before_expiry,
+            only_if_underwater: args.time_stop_if_underwater,
+        });
+    }
+    // Live position tracker for settlement resolution (live mode only)
+    let mut live_positions = LivePositions::new();
 
     // Connect to Polymarket RTDS WebSocket (Chainlink prices)
     let rtds_client = PolymarketRtdsClient::new(chainlink_symbols.clone());
+    let rtds_retry = RetryConfig {
+        max_attempts: args.rtds_reconnect_attempts,
+        ..RetryConfig::default()
+    };
 
     info!("Connecting to Polymarket RTDS (Chainlink prices)...");
-    let mut rtds_stream = rtds_client.connect_with_retry(5).await?;
+    let mut rtds_stream = rtds_client.connect_with_retry_config(rtds_retry).await?;
     info!("Connected to RTDS WebSocket");
 
     // Wait for initial prices before starting main loop (fixes startup race condition)
@@ -215,7 +470,7 @@ async fn main() -> Result<()> {
             }
             None => {
                 warn!("RTDS disconnected during bootstrap, reconnecting...");
-                rtds_stream = rtds_client.connect_with_retry(5).await?;
+                rtds_stream = rtds_client.connect_with_retry_config(rtds_retry).await?;
             }
         }
     }
@@ -227,6 +482,9 @@ async fn main() -> Result<()> {
 
     // Main loop with graceful shutdown
     let mut last_cycle_time = std::time::Instant::now();
+    let min_cycle = Duration::from_millis(args.min_cycle_ms);
+    let max_cycle = Duration::from_millis(args.max_cycle_ms);
+    let mut next_cycle_after = max_cycle;
     let mut last_cleanup_time = std::time::Instant::now();
     let mut prices_since_heartbeat: u64 = 0;
 
@@ -242,27 +500,63 @@ async fn main() -> Result<()> {
             }
             _ = heartbeat_interval.tick() => {
                 // Heartbeat: print metrics and portfolio summary
-                info!("[ALIVE] Heartbeat - prices received: {}, markets tracked: {}, active positions: {}",
-                    prices_since_heartbeat, detector.tracked_count(), exit_manager.position_count());
+                info!("[ALIVE] Heartbeat - prices received: {}, markets tracked: {}, active positions: {}, live tracked: {}",
+                    prices_since_heartbeat, detector.tracked_count(), exit_manager.position_count(), live_positions.len());
 
                 // Stream health check: Chainlink updates ~1/sec per symbol
-                // With N symbols, expect ~60*N prices per minute (±20% variance)
-                let expected_min = (chainlink_symbols.len() as u64) * 45; // 75% of expected
-                let expected_max = (chainlink_symbols.len() as u64) * 80; // 133% of expected
-                if prices_since_heartbeat < expected_min {
-                    warn!(
-                        "[HEALTH] Low price rate: {} prices in 60s (expected {}-{}). Stream may be degraded.",
-                        prices_since_heartbeat, expected_min, expected_max
-                    );
-                } else if prices_since_heartbeat > expected_max {
+                match rtds_stream.health_status() {
+                    StreamHealth::Healthy => {}
+                    StreamHealth::Degraded { symbol, last_seen } => {
+                        warn!(
+                            "[HEALTH] {} degraded (last price: {}). Stream may be lagging.",
+                            symbol,
+                            last_seen
+                                .map_or("never".to_string(), |t| t.format("%H:%M:%S").to_string())
+                        );
+                    }
+                    StreamHealth::Stalled => {
+                        warn!("[HEALTH] RTDS stream stalled, reconnecting proactively...");
+                        match rtds_client.connect_with_retry_config(rtds_retry).await {
+                            Ok(new_stream) => {
+                                rtds_stream = new_stream;
+                                info!("Reconnected to RTDS WebSocket");
+                            }
+                            Err(e) => error!("Failed to reconnect: {}", e),
+                        }
+                    }
+                }
+
+                // Per-symbol health only catches silence; duplicates show up
+                // as too many prices (over 133% of ~60 per symbol per minute)
+                let expected_max = (chainlink_symbols.len() as u64) * 80;
+                if prices_since_heartbeat > expected_max {
                     warn!(
-                        "[HEALTH] High price rate: {} prices in 60s (expected {}-{}). Possible duplicate messages.",
-                        prices_since_heartbeat, expected_min, expected_max
+                        "[HEALTH] High price rate: {} prices in 60s (expected at most {}). Possible duplicate messages.",
+                        prices_since_heartbeat, expected_max
                     );
                 }
 
                 prices_since_heartbeat = 0;
 
+                // Per-symbol price age: RTDS can stall one symbol while the
+                // others keep updating
+                let ages: Vec<String> = chainlink_symbols
+                    .iter()
+                    .map(|symbol| match price_buffer.age(symbol) {
+                        Some(age) => format!("{} {}s", symbol, age.as_secs()),
+                        None => format!("{} never", symbol),
+                    })
+                    .collect();
+                info!("[HEALTH] Chainlink price age: {}", ages.join(", "));
+                let stale = price_buffer.stale_symbols(Duration::from_secs(args.max_price_age_secs));
+                if !stale.is_empty() {
+                    warn!(
+                        "[HEALTH] Stale Chainlink prices (> {}s), markets skipped: {}",
+                        args.max_price_age_secs,
+                        stale.join(", ")
+                    );
+                }
+
                 // Print tracked market status
                 detector.print_market_status(&price_buffer);
 
@@ -273,7 +567,21 @@ async fn main() -> Result<()> {
                 if args.dry_run {
                     portfolio.print_summary();
                     // Resolve expired positions during heartbeat
-                    portfolio.resolve_expired(db.pool(), &gamma).await;
+                    portfolio.resolve_expired(db.pool(), &gamma, SettlementTimeout::default())
+                        .await;
+                }
+                // Resolve expired live positions during heartbeat (live mode)
+                if !args.dry_run {
+                    for settled in resolve_live_settlements(
+                        &mut live_positions,
+                        db.pool(),
+                        &gamma,
+                        SettlementTimeout::default(),
+                    )
+                    .await
+                    {
+                        metrics.record_live_settlement(settled);
+                    }
                 }
             }
             price_opt = rtds_stream.next_price() => {
@@ -283,26 +591,50 @@ async fn main() -> Result<()> {
                         price_buffer.update(&price);
                         prices_since_heartbeat += 1;
 
-                        // Run trading cycle every 500ms (Chainlink updates ~1/sec)
-                        // This is slower than Binance version (100ms) due to slower update rate
-                        if last_cycle_time.elapsed() >= Duration::from_millis(500) {
+                        // Run trading cycle at an adaptive cadence: faster as the
+                        // soonest tradeable market approaches expiry
+                        if last_cycle_time.elapsed() >= next_cycle_after {
                             last_cycle_time = std::time::Instant::now();
 
-                            run_cycle(
-                                &db,
+                            // Realized P&L: paper results in dry-run, exits and settlements live
+                            if daily_stop.is_enabled() {
+                                let realized = if args.dry_run {
+                                    portfolio.total_pnl
+                                } else {
+                                    metrics.realized_pnl()
+                                };
+                                if let Some(reason) = daily_stop.update(realized, Utc::now()) {
+                                    warn!(
+                                        "[DAILY STOP] {} reached (day P&L ${:.2}); no new positions until UTC midnight, open positions will still settle",
+                                        reason,
+                                        daily_stop.day_pnl()
+                                    );
+                                }
+                            }
+
+                            let soonest_expiry_secs = run_cycle(
+                                db.pool(),
                                 &assets,
                                 &args,
                                 &mut price_buffer,
                                 &mut detector,
                                 &mut metrics,
                                 &mut portfolio,
+                                &mut paper_orders,
                                 &mut cached_auth,
+                                &mut throttle,
                                 &mut traded_positions,
                                 &mut order_manager,
                                 &mut exit_manager,
+                                &mut live_positions,
+                                &daily_stop,
+                                &mut rotation,
                                 limit_price,
                                 position_size,
+                                max_shares,
                             ).await;
+                            next_cycle_after =
+                                cycle_interval(soonest_expiry_secs, min_cycle, max_cycle);
                         }
 
                         // Cleanup every 5 minutes
@@ -336,14 +668,19 @@ async fn main() -> Result<()> {
                     }
                     None => {
                         warn!("RTDS WebSocket disconnected, reconnecting...");
-                        match rtds_client.connect_with_retry(5).await {
+                        match rtds_client.connect_with_retry_config(rtds_retry).await {
                             Ok(new_stream) => {
                                 rtds_stream = new_stream;
                                 info!("Reconnected to RTDS WebSocket");
                             }
                             Err(e) => {
                                 error!("Failed to reconnect: {}", e);
-                                tokio::time::sleep(Duration::from_secs(5)).await;
+                                // Jittered like the retries themselves, so
+                                // instances don't come back in lockstep
+                                tokio::time::sleep(
+                                    rtds_retry.next_delay(rtds_retry.max_attempts),
+                                )
+                                .await;
                             }
                         }
                     }
@@ -361,30 +698,43 @@ async fn main() -> Result<()> {
     info!("=== FINAL STATUS ===");
     metrics.print_summary();
     if args.dry_run {
+        portfolio.settle_unresolved(args.unresolved_policy);
         portfolio.print_summary();
     }
+    if !args.dry_run {
+        log_unresolved_live_positions(&live_positions);
+    }
 
     info!("Shutdown complete");
     Ok(())
 }
 
 /// Run a single trading cycle.
+///
+/// Returns the soonest time-to-expiry (seconds) among tradeable markets, or
+/// None when there were none.
 #[allow(clippy::too_many_arguments)]
 async fn run_cycle(
-    db: &Database,
+    repo: &dyn MarketRepository,
     assets: &[String],
     args: &Args,
     price_buffer: &mut ChainlinkPriceBuffer,
     detector: &mut MispriceDetector,
     metrics: &mut Metrics,
     portfolio: &mut DryRunPortfolio,
+    paper_orders: &mut PaperOrders,
     cached_auth: &mut Option<CachedAuth>,
+    throttle: &mut TradeThrottle,
     traded_positions: &mut HashSet<(Uuid, String)>,
     order_manager: &mut OrderManager,
     exit_manager: &mut ExitManager,
+    live_positions: &mut LivePositions,
+    daily_stop: &DailyPnlStop,
+    rotation: &mut MarketRotation,
     limit_price: Decimal,
     position_size: Decimal,
-) {
+    max_shares: Decimal,
+) -> Option<i64> {
     // Poll for completed cancel tasks and process results
     for result in order_manager.poll_completed() {
         if result.was_filled {
@@ -394,10 +744,24 @@ async fn run_cycle(
             );
             metrics.record_verified_fill();
 
+            // A partial fill holds only the matched shares
+            let held_shares = result.held_shares();
+            if let (Some(held), Some(ordered)) = (held_shares, result.shares) {
+                if held < ordered {
+                    info!(
+                        "[PARTIAL] {} {} filled {}/{} shares",
+                        result.market_name,
+                        result.side,
+                        fmt_shares(held),
+                        fmt_shares(ordered)
+                    );
+                }
+            }
+
             // Add to exit manager for trailing stop tracking (live mode)
             if exit_manager.is_enabled() && !args.dry_run {
                 if let (Some(token_id), Some(shares), Some(price)) =
-                    (&result.token_id, result.shares, result.price)
+                    (&result.token_id, held_shares, result.price)
                 {
                     exit_manager.add_position(
                         result.market_id,
@@ -414,6 +778,35 @@ async fn run_cycle(
                     );
                 }
             }
+
+            // Track in live positions for settlement resolution (live mode)
+            if !args.dry_run {
+                if let (Some(shares), Some(price), Some(yes_token_id), Some(end_time)) = (
+                    held_shares,
+                    result.price,
+                    &result.yes_token_id,
+                    result.end_time,
+                ) {
+                    live_positions.insert(
+                        (result.market_id, result.side.clone()),
+                        LivePosition::new(
+                            yes_token_id.clone(),
+                            result.side.clone(),
+                            shares,
+                            price,
+                            end_time,
+                            result.market_name.clone(),
+                        ),
+                    );
+                    debug!(
+                        "[LIVE_TRACK] Tracking position: {} {} @ {} ({} shares)",
+                        result.market_name,
+                        result.side,
+                        fmt_price(price),
+                        fmt_shares(shares)
+                    );
+                }
+            }
         } else {
             // Order was cancelled, remove from traded_positions to allow retry
             debug!(
@@ -430,23 +823,78 @@ async fn run_cycle(
     let min_expiry_seconds = args.min_expiry_minutes * 60;
 
     let all_timeframes = vec!["5m".to_string(), "15m".to_string()];
-    let markets = match get_15m_updown_markets_with_orderbooks(
-        db.pool(),
-        args.max_orderbook_age,
-        assets,
-        expiry_seconds,
-        &all_timeframes,
-    )
-    .await
+    let markets = match repo
+        .get_15m_updown_markets_with_orderbooks(
+            args.max_orderbook_age,
+            assets,
+            expiry_seconds,
+            &all_timeframes,
+        )
+        .await
     {
         Ok(m) => m,
         Err(e) => {
             error!("Failed to query markets: {}", e);
             metrics.record_db_error();
-            return;
+            return None;
         }
     };
 
+    // Mark open dry-run positions to the latest orderbook
+    if args.dry_run {
+        portfolio.update_last_prices(&markets);
+    }
+
+    // Fill resting paper orders the book has traded through; expired ones
+    // free the market/side for another attempt, like a cancelled live order
+    if !paper_orders.is_empty() {
+        let fills = paper_orders.check(&markets, Utc::now());
+        for position in fills.filled {
+            info!(
+                "[PAPER] {} {} filled @ {} ({} shares)",
+                position.side,
+                position.market_name,
+                fmt_price(position.entry_price),
+                fmt_shares(position.shares)
+            );
+            let key = (position.market_id, position.side.clone());
+            if !record_dry_run_fill(position, portfolio, exit_manager, metrics) {
+                // Refused for lack of balance: free the market/side for later
+                traded_positions.remove(&key);
+            }
+        }
+        for position in fills.expired {
+            debug!(
+                "[PAPER] {} {} not filled within timeout, allowing retry",
+                position.side, position.market_name
+            );
+            traded_positions.remove(&(position.market_id, position.side));
+            metrics.record_cancel();
+        }
+    }
+
+    // Check for trailing stop/take profit/time stop exits. Runs before the
+    // minimum-expiry filter so positions in markets about to expire are seen.
+    if exit_manager.is_enabled() && exit_manager.position_count() > 0 {
+        let exits = exit_manager.check_exits(&markets, cached_auth).await;
+        for exit in exits {
+            metrics.record_exit(&exit);
+            if args.dry_run && exit.success {
+                portfolio.close_position(exit.market_id, exit.exit_price, exit.pnl);
+            }
+            // Mark live position as exited so settlement doesn't double-count
+            if !args.dry_run && exit.success {
+                if let Some(pos) = live_positions.get_mut(&(exit.market_id, exit.side.clone())) {
+                    pos.exited = true;
+                    debug!(
+                        "[LIVE_TRACK] Marked {} {} as exited (exit manager)",
+                        pos.market_name, pos.side
+                    );
+                }
+            }
+        }
+    }
+
     // Filter by minimum expiry
     let now = Utc::now();
     let markets: Vec<_> = markets
@@ -458,23 +906,38 @@ async fn run_cycle(
         .collect();
 
     if markets.is_empty() {
-        return;
+        return None;
     }
+    let soonest_expiry_secs = markets
+        .iter()
+        .map(|m| (m.end_time - now).num_seconds())
+        .min();
 
     debug!("Found {} tradeable markets", markets.len());
 
-    // Check for trailing stop/take profit exits
-    if exit_manager.is_enabled() && exit_manager.position_count() > 0 {
-        let exits = exit_manager.check_exits(&markets, cached_auth).await;
-        for exit in exits {
-            metrics.record_exit(&exit);
-            if args.dry_run && exit.success {
-                portfolio.close_position(exit.market_id, exit.exit_price, exit.pnl);
-            }
-        }
+    let mut markets = markets;
+    // A malformed depth snapshot can pass for a thin book; never trade on one
+    let rejected = retain_valid_depth(&mut markets);
+    for (name, e) in &rejected {
+        warn!("[SKIP] {} - invalid depth snapshot: {}", name, e);
+    }
+    metrics.record_invalid_depth(rejected.len());
+
+    // Bound per-cycle work: most urgent markets first, the rest rotate
+    // through the remaining slots so none is deferred indefinitely
+    let deferred = rotation.select(&mut markets, args.max_markets_per_cycle);
+    if deferred > 0 {
+        debug!(
+            "[CAP] Processing {} markets this cycle, {} deferred",
+            markets.len(),
+            deferred
+        );
+        metrics.record_deferred(deferred);
     }
 
     // Process each market
+    let max_price_age = Duration::from_secs(args.max_price_age_secs);
+    let open_tolerance = Duration::from_secs(args.open_price_tolerance_secs);
     for market in &markets {
         // Calculate market start time based on timeframe
         let timeframe_minutes: i64 = match market.timeframe.as_str() {
@@ -493,6 +956,19 @@ async fn run_cycle(
             None => continue,
         };
 
+        // Don't capture opens or detect flips from a frozen price
+        if let Some(age) = price_buffer.age(chainlink_symbol) {
+            if age > max_price_age {
+                debug!(
+                    "[SKIP] {} Chainlink {} price is {}s old",
+                    market.name,
+                    chainlink_symbol,
+                    age.as_secs()
+                );
+                continue;
+            }
+        }
+
         // Get or create state (logs open price on new market discovery)
         let (state, _is_new) = match detector.get_or_create_state(
             market.id,
@@ -500,13 +976,17 @@ async fn run_cycle(
             start_time,
             price_buffer,
             chainlink_symbol,
+            open_tolerance,
         ) {
             Some(s) => s,
             None => {
-                // No Chainlink price data available yet
+                // Not started yet, or no buffered price close enough to the start
                 debug!(
-                    "No Chainlink price for {} yet (symbol: {})",
-                    market.name, chainlink_symbol
+                    "[SKIP] {} no Chainlink {} price within {}s of start {}",
+                    market.name,
+                    chainlink_symbol,
+                    args.open_price_tolerance_secs,
+                    start_time.format("%H:%M:%S")
                 );
                 continue;
             }
@@ -515,15 +995,60 @@ async fn run_cycle(
         // Store open price before mutable borrow
         let open_price = state.open_price;
 
-        // Get current price from Chainlink buffer
-        let current_price = match price_buffer.get_latest(chainlink_symbol) {
+        // Get current (optionally smoothed) price and its Chainlink timestamp
+        let (current_price, price_at) = match detector.current_price(price_buffer, chainlink_symbol)
+        {
             Some(p) => p,
             None => continue,
         };
 
-        // Check for direction flip
-        if let Some((flip_type, side)) = detector.update_and_check_flip(&market.id, current_price) {
-            metrics.record_flip(&market.asset);
+        // Check for direction flip (or one deferred from an earlier cycle)
+        let retrying_deferred = detector.has_deferred_flip(&market.id);
+        if let Some((flip_type, side)) =
+            detector.update_and_check_flip(&market.id, current_price, price_at)
+        {
+            if !retrying_deferred {
+                metrics.record_flip(&market.asset);
+            }
+
+            // Respect the configured direction bias
+            if !args.side_filter.allows(side) {
+                debug!(
+                    "[BIAS] {} {} flip suppressed by side filter ({})",
+                    market.name, side, args.side_filter
+                );
+                metrics.record_bias_skip();
+                continue;
+            }
+
+            // Daily P&L bound reached: keep tracking, but open nothing new
+            if !daily_stop.allows_entry() {
+                debug!(
+                    "[DAILY STOP] {} {} flip not traded ({} reached)",
+                    market.name,
+                    side,
+                    daily_stop.halted().map(|r| r.as_str()).unwrap_or_default()
+                );
+                continue;
+            }
+
+            // The snapshot must not predate the price tick that produced the
+            // flip; hold the flip and re-check it against the next snapshot
+            if !detector.snapshot_lag_allows(market) {
+                detector.defer_flip(&market.id, (flip_type, side));
+                info!(
+                    "[DEFER] {} {} snapshot is {}ms behind the price tick (max {}ms), re-checking next cycle",
+                    market.name,
+                    side,
+                    detector
+                        .snapshot_lag(market)
+                        .map(|lag| lag.num_milliseconds())
+                        .unwrap_or_default(),
+                    args.max_snapshot_lag_ms.unwrap_or_default()
+                );
+                metrics.record_snapshot_lag_skip();
+                continue;
+            }
 
             // Check if already traded this side on this market
             if traded_positions.contains(&(market.id, side.to_string())) {
@@ -531,10 +1056,20 @@ async fn run_cycle(
                 continue;
             }
 
-            // Get token ID, best ask, and orderbook for the side we want to buy
-            let (token_id, best_ask, orderbook) = match side {
-                "YES" => (&market.yes_token_id, market.yes_best_ask, &market.yes_asks),
-                "NO" => (&market.no_token_id, market.no_best_ask, &market.no_asks),
+            // Get token ID, top of book, and orderbook for the side we want to buy
+            let (token_id, best_ask, best_bid, orderbook) = match side {
+                "YES" => (
+                    &market.yes_token_id,
+                    market.yes_best_ask,
+                    market.yes_best_bid,
+                    &market.yes_asks,
+                ),
+                "NO" => (
+                    &market.no_token_id,
+                    market.no_best_ask,
+                    market.no_best_bid,
+                    &market.no_asks,
+                ),
                 _ => continue,
             };
 
@@ -558,8 +1093,8 @@ async fn run_cycle(
             let shares = (position_size / limit_price).round_dp(2);
 
             // Ensure shares is within limits
-            if shares > dec!(99.99) {
-                warn!("Shares {} exceeds max 99.99", shares);
+            if shares > max_shares {
+                warn!("Shares {} exceeds max {}", shares, max_shares);
                 continue;
             }
 
@@ -569,6 +1104,7 @@ async fn run_cycle(
                 best_ask,
                 shares,
                 dec!(20), // 20% slippage fallback if orderbook unavailable
+                args.fill_model,
             );
 
             // Check if we can fully fill at acceptable price
@@ -580,6 +1116,26 @@ async fn run_cycle(
                 continue;
             }
 
+            // Thin book: skip on excessive slippage even if still under the limit
+            if let Some(max_slippage) = args
+                .max_slippage_pct
+                .and_then(|m| Decimal::try_from(m).ok())
+            {
+                if fill_estimate.exceeds_slippage_cap(best_ask, max_slippage) {
+                    info!(
+                        "[SKIP] {} {} slippage {:.2}% > max {}% (eff ${:.3} vs ask ${:.3})",
+                        market.name,
+                        side,
+                        fill_estimate.slippage_pct(best_ask),
+                        max_slippage,
+                        fill_estimate.effective_price,
+                        best_ask
+                    );
+                    metrics.record_slippage_skip();
+                    continue;
+                }
+            }
+
             // Check if effective fill price exceeds our limit
             if fill_estimate.effective_price > limit_price {
                 debug!(
@@ -589,21 +1145,43 @@ async fn run_cycle(
                 continue;
             }
 
+            // Take at the limit, or post inside the spread and let the order
+            // manager cancel it if nobody trades against it. Passive prices step
+            // by the market's own tick (dry-run has no CLOB session to ask)
+            let entry_price = match args.entry_offset {
+                EntryOffset::Ask => limit_price,
+                offset => {
+                    let tick_size = if args.dry_run {
+                        DEFAULT_TICK_SIZE
+                    } else {
+                        fetch_tick_size(cached_auth, token_id).await
+                    };
+                    offset.price(best_bid, best_ask, tick_size)
+                }
+            };
+
             info!(
-                "[FLIP] {} {} -> {} LIMIT @ ${:.2} ({:.2} shares) | Chainlink Open: ${}, Current: ${} | Best ask: ${:.3}, Eff fill: ${:.3}",
-                flip_type, market.name, side, limit_price, shares, open_price, current_price, best_ask, fill_estimate.effective_price
+                "[FLIP] {} {} -> {} LIMIT @ {} ({} shares) | Chainlink Open: ${}, Current: ${} | Best ask: ${:.3}, Eff fill: ${:.3}",
+                flip_type, market.name, side, fmt_price(entry_price), fmt_shares(shares), open_price, current_price, best_ask, fill_estimate.effective_price
             );
 
+            let mut intent = TradeIntent {
+                market_id: market.id,
+                token_id: token_id.clone(),
+                side: side.to_string(),
+                shares,
+                limit_price: entry_price,
+                effective_price: fill_estimate.effective_price,
+                strategy_tag: STRATEGY_TAG.to_string(),
+                mode: TradeMode::from_dry_run(args.dry_run),
+            };
+            info!("[INTENT] {}", intent);
+
             if args.dry_run {
                 // DRY RUN - track in portfolio with realistic fill price
                 let effective_price = fill_estimate.effective_price;
                 let cost = shares * effective_price;
-                info!(
-                    "[DRY RUN] {} {:.2} shares @ ${:.3} (eff), cost: ${:.2}",
-                    side, shares, effective_price, cost
-                );
-
-                portfolio.add_position(SimulatedPosition {
+                let position = SimulatedPosition {
                     market_id: market.id,
                     condition_id: market.condition_id.clone(),
                     market_name: market.name.clone(),
@@ -614,36 +1192,38 @@ async fn run_cycle(
                     no_token_id: market.no_token_id.clone(),
                     side: side.to_string(),
                     shares,
-                    entry_price: limit_price,
+                    entry_price,
                     best_ask_price: best_ask,
                     effective_fill_price: effective_price,
                     cost,
+                    last_price: best_ask,
                     end_time: market.end_time,
                     created_at: Utc::now(),
                     resolution_retries: 0,
                     last_retry_time: None,
-                });
+                };
 
-                // Also add to exit manager for trailing stop tracking
-                if exit_manager.is_enabled() {
-                    let token_id_for_exit = match side {
-                        "YES" => &market.yes_token_id,
-                        "NO" => &market.no_token_id,
-                        _ => token_id,
-                    };
-                    exit_manager.add_position(
-                        market.id,
-                        market.name.clone(),
-                        token_id_for_exit.clone(),
-                        side.to_string(),
-                        shares,
-                        effective_price, // Use effective fill price as entry
+                if args.paper_fill == PaperFillMode::Realistic {
+                    info!(
+                        "[DRY RUN] {} {:.2} shares resting @ ${:.3} until the ask reaches it",
+                        side, shares, entry_price
+                    );
+                    paper_orders.place(position);
+                    record_trade_audit(repo, &intent, TradeOutcome::Placed).await;
+                } else {
+                    info!(
+                        "[DRY RUN] {} {:.2} shares @ ${:.3} (eff), cost: ${:.2}",
+                        side, shares, effective_price, cost
                     );
+                    if !record_dry_run_fill(position, portfolio, exit_manager, metrics) {
+                        record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
+                        continue;
+                    }
+                    record_trade_audit(repo, &intent, TradeOutcome::Filled).await;
                 }
 
                 traded_positions.insert((market.id, side.to_string()));
                 detector.mark_traded(&market.id);
-                metrics.record_trade(&market.asset, side);
             } else {
                 // Check if we already have a pending order for this market/side
                 if order_manager.has_pending_order(&market.id, side) {
@@ -651,24 +1231,99 @@ async fn run_cycle(
                         "[SKIP] Already have pending order for {} {}",
                         market.name, side
                     );
+                    record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
+                    continue;
+                }
+
+                if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
+                    info!(
+                        "[THROTTLE] Skipping {} {}: next order allowed in {}ms",
+                        side,
+                        market.name,
+                        wait.as_millis()
+                    );
+                    record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
                     continue;
                 }
 
-                // REAL TRADE - LIMIT ORDER at specified price
-                match execute_trade(
+                // REAL TRADE - LIMIT ORDER at specified price, re-attempted
+                // once at the fresh ask if a taking order found the book moved past it
+                let mut order_price = entry_price;
+                let mut result = execute_trade(
                     cached_auth,
                     token_id,
                     shares,
-                    limit_price,
+                    order_price,
                     side,
                     &market.name,
+                    max_shares,
                 )
-                .await
-                {
+                .await;
+                throttle.record_order(std::time::Instant::now());
+                if is_price_movement(&result) && !args.entry_offset.is_passive() {
+                    let retry_ceiling = args
+                        .retry_max_price
+                        .and_then(|max| Decimal::try_from(max).ok())
+                        .unwrap_or(limit_price);
+                    let retry = fresh_retry_ask(
+                        repo,
+                        market,
+                        side,
+                        order_price,
+                        retry_ceiling,
+                        args.max_orderbook_age,
+                        expiry_seconds,
+                    )
+                    .await
+                    .unwrap_or_else(|e| {
+                        error!("Failed to refresh {}: {}", market.name, e);
+                        metrics.record_db_error();
+                        None
+                    });
+                    if let Some(fresh_ask) = retry {
+                        if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
+                            info!(
+                                "[THROTTLE] Not retrying {} {} at {}: next order allowed in {}ms",
+                                side,
+                                market.name,
+                                fmt_price(fresh_ask),
+                                wait.as_millis()
+                            );
+                        } else {
+                            metrics.record_price_retry();
+                            order_price = fresh_ask;
+                            result = execute_trade(
+                                cached_auth,
+                                token_id,
+                                shares,
+                                order_price,
+                                side,
+                                &market.name,
+                                max_shares,
+                            )
+                            .await;
+                            throttle.record_order(std::time::Instant::now());
+                        }
+                    }
+                }
+
+                intent.limit_price = order_price;
+                let outcome = if result.is_ok() {
+                    TradeOutcome::Placed
+                } else {
+                    TradeOutcome::Rejected
+                };
+                record_trade_audit(repo, &intent, outcome).await;
+
+                match result {
                     Ok(order_id) => {
                         info!(
-                            "[SUCCESS] LIMIT order {} @ ${:.2} (order_id: {})",
-                            side, limit_price, order_id
+                            market = %market.name,
+                            side,
+                            shares = %fmt_shares(shares),
+                            price = %fmt_price(order_price),
+                            order_id = %order_id,
+                            "[SUCCESS] LIMIT order placed"
                         );
 
                         // Track order - don't mark as fully traded until fill verified
@@ -684,11 +1339,17 @@ async fn run_cycle(
                             side.to_string(),
                             Some(token_id.clone()),
                             Some(shares),
-                            Some(limit_price),
+                            Some(order_price),
+                            Some(market.yes_token_id.clone()),
+                            Some(market.end_time),
                         );
                     }
                     Err(e) => {
-                        error!("[FAILED] Trade execution: {:#}", e);
+                        error!(
+                            "[FAILED] Trade execution ({}): {:#}",
+                            OrderRejection::classify(&format!("{:#}", e)),
+                            e
+                        );
                         metrics.record_error();
                     }
                 }
@@ -705,4 +1366,45 @@ async fn run_cycle(
             before - traded_positions.len()
         );
     }
+
+    soonest_expiry_secs
+}
+
+/// Book a filled dry-run entry in the portfolio, exit tracking and metrics.
+///
+/// Returns false if the portfolio refused it for lack of virtual balance.
+fn record_dry_run_fill(
+    position: SimulatedPosition,
+    portfolio: &mut DryRunPortfolio,
+    exit_manager: &mut ExitManager,
+    metrics: &mut Metrics,
+) -> bool {
+    if !portfolio.add_position(position.clone()) {
+        metrics.record_balance_skip();
+        return false;
+    }
+    if exit_manager.is_enabled() {
+        let token_id = match position.side.as_str() {
+            "YES" => &position.yes_token_id,
+            _ => &position.no_token_id,
+        };
+        exit_manager.add_position(
+            position.market_id,
+            position.market_name.clone(),
+            token_id.clone(),
+            position.side.clone(),
+            position.shares,
+            position.effective_fill_price, // Use effective fill price as entry
+        );
+    }
+    metrics.record_trade(&position.asset, &position.side);
+    true
+}
+
+/// Whether an order attempt failed because the book moved past our price.
+fn is_price_movement(result: &Result<String>) -> bool {
+    match result {
+        Err(e) => OrderRejection::classify(&format!("{:#}", e)).is_price_movement(),
+        Ok(_) => false,
+    }
 }
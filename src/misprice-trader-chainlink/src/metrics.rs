@@ -7,6 +7,8 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tracing::info;
 
+use common::{LiveSettlement, SettlementTimeoutPolicy};
+
 use crate::exit_manager::ExitResult;
 
 /// Metrics tracker for the misprice trader.
@@ -22,12 +24,36 @@ pub struct Metrics {
     orders_cancelled: u32,
     /// Orders verified as filled
     verified_fills: u32,
+    /// Signals skipped because depth slippage exceeded --max-slippage-pct
+    slippage_skips: u32,
+    /// Signals not traded because --side-filter excludes their side
+    bias_skips: u32,
+    /// Dry-run trades refused because --dry-run-balance was exhausted
+    balance_skips: u32,
+    /// Markets skipped because their depth snapshot was malformed
+    invalid_depth_skips: u32,
+    /// Flips not traded because the snapshot trailed the price tick by more than --max-snapshot-lag-ms
+    snapshot_lag_skips: u32,
+    /// Markets left for a later cycle by --max-markets-per-cycle
+    markets_deferred: u64,
+    /// Rejected orders re-attempted at a fresh ask after the price moved
+    price_retries: u32,
     /// Exits via trailing stop
     exits_trailing_stop: u32,
     /// Exits via take profit
     exits_take_profit: u32,
+    /// Exits via time stop
+    exits_time_stop: u32,
     /// Total realized P&L from exits
     total_realized_pnl: Decimal,
+    /// Settlement wins (expired positions that won)
+    settled_wins: u32,
+    /// Settlement losses (expired positions that lost)
+    settled_losses: u32,
+    /// Total P&L from expired position settlements
+    settled_pnl: Decimal,
+    /// Settlements that timed out under the `unknown` policy (excluded from P&L)
+    settled_unknown: u32,
     /// Total errors
     errors: u32,
     /// Database errors
@@ -43,9 +69,21 @@ impl Metrics {
             trades_by_side: HashMap::new(),
             orders_cancelled: 0,
             verified_fills: 0,
+            slippage_skips: 0,
+            bias_skips: 0,
+            balance_skips: 0,
+            invalid_depth_skips: 0,
+            snapshot_lag_skips: 0,
+            markets_deferred: 0,
+            price_retries: 0,
             exits_trailing_stop: 0,
             exits_take_profit: 0,
+            exits_time_stop: 0,
             total_realized_pnl: dec!(0),
+            settled_wins: 0,
+            settled_losses: 0,
+            settled_pnl: dec!(0),
+            settled_unknown: 0,
             errors: 0,
             db_errors: 0,
         }
@@ -67,6 +105,41 @@ impl Metrics {
         self.orders_cancelled += 1;
     }
 
+    /// Record a signal skipped for excessive slippage.
+    pub fn record_slippage_skip(&mut self) {
+        self.slippage_skips += 1;
+    }
+
+    /// Record a signal suppressed by the side filter.
+    pub fn record_bias_skip(&mut self) {
+        self.bias_skips += 1;
+    }
+
+    /// Record a dry-run trade refused by --dry-run-balance.
+    pub fn record_balance_skip(&mut self) {
+        self.balance_skips += 1;
+    }
+
+    /// Record markets skipped for a malformed depth snapshot.
+    pub fn record_invalid_depth(&mut self, count: usize) {
+        self.invalid_depth_skips += count as u32;
+    }
+
+    /// Record a flip skipped because its orderbook snapshot was too old.
+    pub fn record_snapshot_lag_skip(&mut self) {
+        self.snapshot_lag_skips += 1;
+    }
+
+    /// Record markets deferred to a later cycle by the per-cycle cap.
+    pub fn record_deferred(&mut self, count: usize) {
+        self.markets_deferred += count as u64;
+    }
+
+    /// Record a rejected order re-attempted at a fresh ask.
+    pub fn record_price_retry(&mut self) {
+        self.price_retries += 1;
+    }
+
     /// Record a verified fill (order was filled before cancel timeout).
     pub fn record_verified_fill(&mut self) {
         self.verified_fills += 1;
@@ -90,15 +163,51 @@ impl Metrics {
             match result.reason {
                 ExitReason::TrailingStop => self.exits_trailing_stop += 1,
                 ExitReason::TakeProfit => self.exits_take_profit += 1,
+                ExitReason::TimeStop => self.exits_time_stop += 1,
                 ExitReason::MarketExpiry => {} // Not tracked separately
             }
             self.total_realized_pnl += result.pnl;
         }
     }
 
+    /// Record a live position settled by `resolve_live_settlements`.
+    pub fn record_live_settlement(&mut self, settled: LiveSettlement) {
+        match settled {
+            LiveSettlement::Resolved { pnl, won } => {
+                self.settled_pnl += pnl;
+                if won {
+                    self.settled_wins += 1;
+                } else {
+                    self.settled_losses += 1;
+                }
+            }
+            LiveSettlement::Ambiguous { pnl } => self.settled_pnl += pnl,
+            LiveSettlement::TimedOut { policy, cost } => {
+                self.record_settlement_timeout(policy, cost)
+            }
+        }
+    }
+
+    /// Record an expired position whose resolution never arrived.
+    fn record_settlement_timeout(&mut self, policy: SettlementTimeoutPolicy, cost: Decimal) {
+        match policy.timeout_pnl(cost) {
+            Some(pnl) if pnl < Decimal::ZERO => {
+                self.settled_pnl += pnl;
+                self.settled_losses += 1;
+            }
+            Some(pnl) => self.settled_pnl += pnl,
+            None => self.settled_unknown += 1,
+        }
+    }
+
+    /// P&L realized so far by exits and settlements.
+    pub fn realized_pnl(&self) -> Decimal {
+        self.total_realized_pnl + self.settled_pnl
+    }
+
     /// Get total exits.
     pub fn total_exits(&self) -> u32 {
-        self.exits_trailing_stop + self.exits_take_profit
+        self.exits_trailing_stop + self.exits_take_profit + self.exits_time_stop
     }
 
     /// Get total flips detected.
@@ -132,12 +241,31 @@ impl Metrics {
         info!("  YES / NO:          {:>4} / {:<4}", yes_trades, no_trades);
         info!("  Verified Fills:    {:>8}", self.verified_fills);
         info!("  Cancelled:         {:>8}", self.orders_cancelled);
+        info!("  Slippage Skips:    {:>8}", self.slippage_skips);
+        info!("  Bias Skips:        {:>8}", self.bias_skips);
+        info!("  Balance Skips:     {:>8}", self.balance_skips);
+        info!("  Invalid Depth:     {:>8}", self.invalid_depth_skips);
+        info!("  Snapshot Lag:      {:>8}", self.snapshot_lag_skips);
+        info!("  Markets Deferred:  {:>8}", self.markets_deferred);
+        info!("  Price Retries:     {:>8}", self.price_retries);
         info!("---------------------------------------------------------------");
         info!("  EXIT METRICS:");
         info!("  Trailing Stops:    {:>8}", self.exits_trailing_stop);
         info!("  Take Profits:      {:>8}", self.exits_take_profit);
+        info!("  Time Stops:        {:>8}", self.exits_time_stop);
         info!("  Total Exits:       {:>8}", total_exits);
-        info!("  Realized P&L:      ${:<8.2}", self.total_realized_pnl);
+        info!("  Exit P&L:          ${:<8.2}", self.total_realized_pnl);
+        info!("---------------------------------------------------------------");
+        info!("  SETTLEMENT METRICS:");
+        info!("  Settled Wins:      {:>8}", self.settled_wins);
+        info!("  Settled Losses:    {:>8}", self.settled_losses);
+        info!("  Timed Out (unk):   {:>8}", self.settled_unknown);
+        info!("  Settlement P&L:    ${:<8.2}", self.settled_pnl);
+        info!("---------------------------------------------------------------");
+        info!(
+            "  TOTAL P&L:         ${:<8.2}  (exits + settlements)",
+            self.realized_pnl()
+        );
         info!("---------------------------------------------------------------");
         info!("  Errors:            {:>8}", self.errors);
         info!("  DB Errors:         {:>8}", self.db_errors);
@@ -13,6 +13,7 @@ use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use common::{cancel_order_standalone, query_order_fill_standalone};
+use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
 /// Status of a pending order.
@@ -43,6 +44,9 @@ pub struct PendingOrder {
     pub token_id: Option<String>,
     pub shares: Option<rust_decimal::Decimal>,
     pub price: Option<rust_decimal::Decimal>,
+    // Fields for settlement tracking (live trading)
+    pub yes_token_id: Option<String>,
+    pub end_time: Option<DateTime<Utc>>,
 }
 
 /// Result of a cancel attempt, sent back from the spawned task.
@@ -58,8 +62,24 @@ pub struct CancelResult {
     pub error_msg: Option<String>,
     // Market info for exit manager (live trading)
     pub token_id: Option<String>,
+    /// Shares the order was placed for
     pub shares: Option<rust_decimal::Decimal>,
+    /// Shares actually matched, when the order status could be queried
+    pub filled_shares: Option<rust_decimal::Decimal>,
     pub price: Option<rust_decimal::Decimal>,
+    // Fields for settlement tracking (live trading)
+    pub yes_token_id: Option<String>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl CancelResult {
+    /// Shares held after a fill: the matched size when known, otherwise the
+    /// ordered size (the fill was only inferred from the cancel error).
+    pub fn held_shares(&self) -> Option<Decimal> {
+        self.filled_shares
+            .filter(|filled| *filled > Decimal::ZERO)
+            .or(self.shares)
+    }
 }
 
 /// Manages pending orders and their auto-cancel tasks.
@@ -96,10 +116,20 @@ impl OrderManager {
         market_name: String,
         side: String,
     ) -> bool {
-        self.track_order_with_market_info(order_id, market_id, market_name, side, None, None, None)
+        self.track_order_with_market_info(
+            order_id,
+            market_id,
+            market_name,
+            side,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
     }
 
-    /// Track a new order with market info for exit manager.
+    /// Track a new order with market info for exit manager and settlement.
     ///
     /// Returns true if order was added, false if order_id already exists.
     #[allow(clippy::too_many_arguments)]
@@ -112,6 +142,8 @@ impl OrderManager {
         token_id: Option<String>,
         shares: Option<rust_decimal::Decimal>,
         price: Option<rust_decimal::Decimal>,
+        yes_token_id: Option<String>,
+        end_time: Option<DateTime<Utc>>,
     ) -> bool {
         if self.pending_orders.contains_key(&order_id) {
             warn!(
@@ -132,6 +164,8 @@ impl OrderManager {
             token_id: token_id.clone(),
             shares,
             price,
+            yes_token_id: yes_token_id.clone(),
+            end_time,
         };
 
         self.pending_orders.insert(order_id.clone(), order);
@@ -145,6 +179,8 @@ impl OrderManager {
         let tid = token_id;
         let sh = shares;
         let pr = price;
+        let ytid = yes_token_id;
+        let et = end_time;
 
         self.cancel_tasks.spawn(async move {
             tokio::time::sleep(Duration::from_secs(timeout)).await;
@@ -167,7 +203,7 @@ impl OrderManager {
                             oid, timeout
                         );
                     }
-                    (filled, size_matched)
+                    (filled, Some(size_matched))
                 }
                 Err(e) => {
                     // Query failed - fall back to cancel error heuristic
@@ -182,7 +218,7 @@ impl OrderManager {
                                 || error_str.contains("does not exist")
                         })
                         .unwrap_or(false);
-                    (was_filled, dec!(0))
+                    (was_filled, None)
                 }
             };
 
@@ -195,8 +231,11 @@ impl OrderManager {
                 was_filled,
                 error_msg: cancel_error,
                 token_id: tid,
-                shares: if was_filled { Some(filled_amount) } else { sh },
+                shares: sh,
+                filled_shares: filled_amount,
                 price: pr,
+                yes_token_id: ytid,
+                end_time: et,
             }
         });
 
@@ -319,6 +358,30 @@ mod tests {
         assert_eq!(manager.cancel_timeout_secs, 15);
     }
 
+    #[test]
+    fn test_held_shares_prefers_matched_size() {
+        let mut partial = CancelResult {
+            order_id: "order123".to_string(),
+            market_id: Uuid::new_v4(),
+            market_name: "Test Market".to_string(),
+            side: "YES".to_string(),
+            success: false,
+            was_filled: true,
+            error_msg: None,
+            token_id: Some("token".to_string()),
+            shares: Some(dec!(10)),
+            filled_shares: Some(dec!(5)),
+            price: Some(dec!(0.40)),
+            yes_token_id: Some("token".to_string()),
+            end_time: None,
+        };
+        assert_eq!(partial.held_shares(), Some(dec!(5)));
+
+        // Fill inferred from the cancel error: assume the full order
+        partial.filled_shares = None;
+        assert_eq!(partial.held_shares(), Some(dec!(10)));
+    }
+
     #[tokio::test]
     async fn test_has_pending_order() {
         let mut manager = OrderManager::new(10);
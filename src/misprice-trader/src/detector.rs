@@ -8,7 +8,7 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
-use common::KlineBuffer;
+use common::{KlineBuffer, MarketWithOrderbook};
 use rust_decimal::Decimal;
 use tracing::{debug, info};
 use uuid::Uuid;
@@ -31,6 +31,16 @@ pub enum FlipType {
     UpToDown, // UP -> DOWN -> Buy NO
 }
 
+impl FlipType {
+    /// Confirmed direction the flip moved into.
+    pub fn direction(&self) -> Direction {
+        match self {
+            FlipType::DownToUp => Direction::Up,
+            FlipType::UpToDown => Direction::Down,
+        }
+    }
+}
+
 impl std::fmt::Display for FlipType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -55,17 +65,55 @@ pub struct MarketState {
     /// Count of consecutive readings in the same direction
     pub consecutive_count: u32,
     pub has_traded: bool,
+    /// Timestamp of the price reading that last updated the direction
+    pub last_price_at: Option<DateTime<Utc>>,
+    /// Confirmed flip held back by a gate, offered again next reading
+    pub deferred_flip: Option<(FlipType, &'static str)>,
 }
 
 /// Misprice detector that tracks direction flips.
 pub struct MispriceDetector {
     states: HashMap<Uuid, MarketState>,
+    /// Minimum bid/ask depth ratio on the traded side before a flip may fire.
+    min_imbalance: Option<Decimal>,
+    /// How far the orderbook snapshot may trail the flip's price reading.
+    max_snapshot_lag: Option<chrono::Duration>,
 }
 
 impl MispriceDetector {
     pub fn new() -> Self {
         Self {
             states: HashMap::new(),
+            min_imbalance: None,
+            max_snapshot_lag: None,
+        }
+    }
+
+    /// Require at least `min_imbalance` bid/ask depth on the traded side.
+    pub fn with_min_imbalance(mut self, min_imbalance: Option<Decimal>) -> Self {
+        self.min_imbalance = min_imbalance;
+        self
+    }
+
+    /// Veto flips whose orderbook snapshot is more than `max_lag` older than
+    /// the price reading that triggered them.
+    pub fn with_max_snapshot_lag(mut self, max_lag: Option<chrono::Duration>) -> Self {
+        self.max_snapshot_lag = max_lag;
+        self
+    }
+
+    /// Whether the orderbook leans far enough toward `side` to trade it.
+    ///
+    /// Always true without a configured minimum, and when the market carries
+    /// no usable depth to measure.
+    pub fn imbalance_allows(&self, market: &MarketWithOrderbook, side: &str) -> bool {
+        let min = match self.min_imbalance {
+            Some(min) => min,
+            None => return true,
+        };
+        match market.imbalance_ratio(side) {
+            Some(ratio) => ratio >= min,
+            None => true,
         }
     }
 
@@ -106,6 +154,8 @@ impl MispriceDetector {
                     raw_direction: None,
                     consecutive_count: 0,
                     has_traded: false,
+                    last_price_at: None,
+                    deferred_flip: None,
                 },
             );
         }
@@ -128,10 +178,14 @@ impl MispriceDetector {
     /// Requires DEBOUNCE_COUNT consecutive readings on the same side before
     /// confirming a direction change. This prevents false signals from
     /// rapid price fluctuations around the open price.
+    ///
+    /// `price_at` is when `current_price` was observed; it is kept on the
+    /// state so a flip can be checked against the orderbook snapshot's age.
     pub fn update_and_check_flip(
         &mut self,
         market_id: &Uuid,
         current_price: Decimal,
+        price_at: DateTime<Utc>,
     ) -> Option<(FlipType, &'static str)> {
         let state = self.states.get_mut(market_id)?;
 
@@ -139,6 +193,7 @@ impl MispriceDetector {
         if state.has_traded {
             return None;
         }
+        state.last_price_at = Some(price_at);
 
         let new_direction = Self::calculate_direction(current_price, state.open_price);
 
@@ -156,6 +211,13 @@ impl MispriceDetector {
             );
         }
 
+        // A deferred flip fires again while the price still agrees with it
+        if let Some(flip) = state.deferred_flip.take() {
+            if flip.0.direction() == new_direction {
+                return Some(flip);
+            }
+        }
+
         // Check if we have enough consecutive readings to confirm direction
         if state.consecutive_count < DEBOUNCE_COUNT {
             return None;
@@ -192,6 +254,38 @@ impl MispriceDetector {
         None
     }
 
+    /// How far `market`'s orderbook snapshot trails the latest price reading
+    /// for it. Negative when the snapshot is newer; None before any reading.
+    pub fn snapshot_lag(&self, market: &MarketWithOrderbook) -> Option<chrono::Duration> {
+        let price_at = self.states.get(&market.id)?.last_price_at?;
+        Some(price_at - market.captured_at)
+    }
+
+    /// Whether `market`'s snapshot is recent enough to trade the latest flip.
+    ///
+    /// Always true without a configured maximum, and before any price reading.
+    pub fn snapshot_lag_allows(&self, market: &MarketWithOrderbook) -> bool {
+        match (self.max_snapshot_lag, self.snapshot_lag(market)) {
+            (Some(max), Some(lag)) => lag <= max,
+            _ => true,
+        }
+    }
+
+    /// Hold a confirmed flip that a gate vetoed this cycle so the next price
+    /// reading offers it again, unless the direction has reversed by then.
+    pub fn defer_flip(&mut self, market_id: &Uuid, flip: (FlipType, &'static str)) {
+        if let Some(state) = self.states.get_mut(market_id) {
+            state.deferred_flip = Some(flip);
+        }
+    }
+
+    /// Whether `market_id` has a flip waiting from an earlier cycle.
+    pub fn has_deferred_flip(&self, market_id: &Uuid) -> bool {
+        self.states
+            .get(market_id)
+            .is_some_and(|s| s.deferred_flip.is_some())
+    }
+
     /// Mark a market as traded (no more trades on this market).
     pub fn mark_traded(&mut self, market_id: &Uuid) {
         if let Some(state) = self.states.get_mut(market_id) {
@@ -243,6 +337,103 @@ mod tests {
         );
     }
 
+    fn market_with_depth(
+        yes_bids: Option<serde_json::Value>,
+        yes_asks: Option<serde_json::Value>,
+    ) -> MarketWithOrderbook {
+        MarketWithOrderbook {
+            id: Uuid::new_v4(),
+            condition_id: "cond".to_string(),
+            market_type: "up_down".to_string(),
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "yes".to_string(),
+            no_token_id: "no".to_string(),
+            name: "BTC Up or Down".to_string(),
+            end_time: Utc::now(),
+            is_active: true,
+            yes_best_ask: Some(dec!(0.45)),
+            yes_best_bid: Some(dec!(0.43)),
+            no_best_ask: Some(dec!(0.57)),
+            no_best_bid: Some(dec!(0.55)),
+            yes_asks,
+            no_asks: None,
+            yes_bids,
+            no_bids: None,
+            captured_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_imbalance_gate() {
+        let market = market_with_depth(
+            Some(serde_json::json!([{"price": "0.43", "size": "30"}])),
+            Some(serde_json::json!([{"price": "0.45", "size": "20"}])),
+        );
+
+        // No minimum configured: never gates
+        assert!(MispriceDetector::new().imbalance_allows(&market, "YES"));
+
+        // YES depth ratio is 1.5
+        let detector = MispriceDetector::new().with_min_imbalance(Some(dec!(1.5)));
+        assert!(detector.imbalance_allows(&market, "YES"));
+        let detector = MispriceDetector::new().with_min_imbalance(Some(dec!(2)));
+        assert!(!detector.imbalance_allows(&market, "YES"));
+
+        // NO side has no depth: check is skipped
+        assert!(detector.imbalance_allows(&market, "NO"));
+        let bare = market_with_depth(None, None);
+        assert!(detector.imbalance_allows(&bare, "YES"));
+    }
+
+    #[test]
+    fn test_snapshot_lag_gate() {
+        let mut market = market_with_depth(None, None);
+        let price_at = market.captured_at + chrono::Duration::milliseconds(300);
+
+        let mut detector = MispriceDetector::new()
+            .with_max_snapshot_lag(Some(chrono::Duration::milliseconds(500)));
+        detector.states.insert(
+            market.id,
+            MarketState {
+                market_id: market.id,
+                market_name: market.name.clone(),
+                start_time: Utc::now(),
+                open_price: dec!(100),
+                confirmed_direction: None,
+                raw_direction: None,
+                consecutive_count: 0,
+                has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
+            },
+        );
+
+        // No price reading yet: nothing to compare against
+        assert_eq!(detector.snapshot_lag(&market), None);
+        assert!(detector.snapshot_lag_allows(&market));
+
+        detector.update_and_check_flip(&market.id, dec!(101), price_at);
+        assert_eq!(
+            detector.snapshot_lag(&market),
+            Some(chrono::Duration::milliseconds(300))
+        );
+        assert!(detector.snapshot_lag_allows(&market));
+
+        // Snapshot 600ms behind the price exceeds the 500ms limit
+        market.captured_at = price_at - chrono::Duration::milliseconds(600);
+        assert!(!detector.snapshot_lag_allows(&market));
+
+        // A snapshot newer than the price is never stale
+        market.captured_at = price_at + chrono::Duration::seconds(1);
+        assert!(detector.snapshot_lag_allows(&market));
+
+        // Without a limit the lag is ignored
+        market.captured_at = price_at - chrono::Duration::seconds(60);
+        detector.max_snapshot_lag = None;
+        assert!(detector.snapshot_lag_allows(&market));
+    }
+
     #[test]
     fn test_flip_type_display() {
         assert_eq!(format!("{}", FlipType::DownToUp), "DOWN->UP");
@@ -267,25 +458,27 @@ mod tests {
                 raw_direction: Some(Direction::Down),
                 consecutive_count: DEBOUNCE_COUNT,
                 has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
             },
         );
 
         // Single reading above open should NOT trigger flip (debouncing)
-        let result = detector.update_and_check_flip(&market_id, dec!(101));
+        let result = detector.update_and_check_flip(&market_id, dec!(101), Utc::now());
         assert!(
             result.is_none(),
             "Single UP reading should not trigger flip"
         );
 
         // Second reading above open still shouldn't flip (need DEBOUNCE_COUNT)
-        let result = detector.update_and_check_flip(&market_id, dec!(102));
+        let result = detector.update_and_check_flip(&market_id, dec!(102), Utc::now());
         assert!(
             result.is_none(),
             "Two UP readings should not trigger flip yet"
         );
 
         // Third consecutive reading above open SHOULD trigger flip
-        let result = detector.update_and_check_flip(&market_id, dec!(103));
+        let result = detector.update_and_check_flip(&market_id, dec!(103), Utc::now());
         assert!(
             result.is_some(),
             "Three consecutive UP readings should trigger flip"
@@ -311,15 +504,17 @@ mod tests {
                 raw_direction: Some(Direction::Down),
                 consecutive_count: DEBOUNCE_COUNT,
                 has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
             },
         );
 
         // Two UP readings (not enough to flip)
-        detector.update_and_check_flip(&market_id, dec!(101));
-        detector.update_and_check_flip(&market_id, dec!(102));
+        detector.update_and_check_flip(&market_id, dec!(101), Utc::now());
+        detector.update_and_check_flip(&market_id, dec!(102), Utc::now());
 
         // Now price goes back down - should reset counter
-        let result = detector.update_and_check_flip(&market_id, dec!(99));
+        let result = detector.update_and_check_flip(&market_id, dec!(99), Utc::now());
         assert!(
             result.is_none(),
             "Direction change should reset counter, no flip"
@@ -331,6 +526,50 @@ mod tests {
         assert_eq!(state.raw_direction, Some(Direction::Down));
     }
 
+    #[test]
+    fn test_deferred_flip_fires_until_direction_reverses() {
+        let mut detector = MispriceDetector::new();
+        let market_id = Uuid::new_v4();
+        detector.states.insert(
+            market_id,
+            MarketState {
+                market_id,
+                market_name: "Test Market".to_string(),
+                start_time: Utc::now(),
+                open_price: dec!(100),
+                confirmed_direction: Some(Direction::Down),
+                raw_direction: Some(Direction::Down),
+                consecutive_count: DEBOUNCE_COUNT,
+                has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
+            },
+        );
+
+        let mut flip = None;
+        for price in [dec!(101), dec!(102), dec!(103)] {
+            flip = detector.update_and_check_flip(&market_id, price, Utc::now());
+        }
+        let flip = flip.expect("third UP reading confirms the flip");
+
+        // Vetoed this cycle: the next reading in the same direction offers it again
+        detector.defer_flip(&market_id, flip);
+        assert!(detector.has_deferred_flip(&market_id));
+        assert_eq!(
+            detector.update_and_check_flip(&market_id, dec!(104), Utc::now()),
+            Some((FlipType::DownToUp, "YES"))
+        );
+        assert!(!detector.has_deferred_flip(&market_id));
+
+        // Deferred again, but the price fell back below the open: dropped
+        detector.defer_flip(&market_id, flip);
+        assert_eq!(
+            detector.update_and_check_flip(&market_id, dec!(99), Utc::now()),
+            None
+        );
+        assert!(!detector.has_deferred_flip(&market_id));
+    }
+
     #[test]
     fn test_no_flip_on_first_direction_confirmation() {
         let mut detector = MispriceDetector::new();
@@ -349,12 +588,14 @@ mod tests {
                 raw_direction: None,
                 consecutive_count: 0,
                 has_traded: false,
+                last_price_at: None,
+                deferred_flip: None,
             },
         );
 
         // First DEBOUNCE_COUNT readings should confirm direction but NOT trigger flip
         for i in 0..DEBOUNCE_COUNT {
-            let result = detector.update_and_check_flip(&market_id, dec!(101));
+            let result = detector.update_and_check_flip(&market_id, dec!(101), Utc::now());
             assert!(
                 result.is_none(),
                 "Reading {} should not trigger flip on initial confirmation",
@@ -1,17 +1,19 @@
 //! Exit management for trailing take profit strategy.
 //!
 //! After entry, tracks peak price and exits when price drops X% from peak
-//! to lock in gains. Also supports optional hard take profit target.
+//! to lock in gains. Also supports optional hard take profit target (or
+//! scale-out tiers that each sell part of the position) and a time stop that
+//! closes positions shortly before expiry.
 
 use std::collections::{HashMap, HashSet};
 
 use chrono::{DateTime, Utc};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use common::{execute_sell_order, CachedAuth, MarketWithOrderbook};
+use common::{execute_sell_order, fmt_price, fmt_shares, CachedAuth, MarketWithOrderbook};
 
 /// Maximum number of exit attempts before abandoning a position.
 const MAX_EXIT_ATTEMPTS: u32 = 3;
@@ -26,7 +28,12 @@ pub struct ActivePosition {
     pub market_name: String,
     pub token_id: String,
     pub side: String, // YES or NO
+    /// Shares still held
     pub shares: Decimal,
+    /// Shares held before any scale-out exit
+    pub initial_shares: Decimal,
+    /// Take-profit tiers already sold
+    pub tiers_taken: usize,
     pub entry_price: Decimal,
     pub peak_price: Decimal, // Highest price since entry
     #[allow(dead_code)]
@@ -43,6 +50,9 @@ pub struct ActivePosition {
 pub enum ExitReason {
     TrailingStop,
     TakeProfit,
+    /// Scale-out tier reached; holds the number of tiers taken so far
+    TakeProfitTier(usize),
+    TimeStop,
     MarketExpiry,
 }
 
@@ -51,11 +61,152 @@ impl std::fmt::Display for ExitReason {
         match self {
             ExitReason::TrailingStop => write!(f, "TRAILING_STOP"),
             ExitReason::TakeProfit => write!(f, "TAKE_PROFIT"),
+            ExitReason::TakeProfitTier(n) => write!(f, "TAKE_PROFIT_T{}", n),
+            ExitReason::TimeStop => write!(f, "TIME_STOP"),
             ExitReason::MarketExpiry => write!(f, "MARKET_EXPIRY"),
         }
     }
 }
 
+/// Close positions a fixed time before expiry rather than holding them into
+/// settlement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeStop {
+    /// Fire once the market is this many seconds (or fewer) from its end time
+    pub secs_before_expiry: i64,
+    /// Only fire while the position is below its entry price
+    pub only_if_underwater: bool,
+}
+
+impl TimeStop {
+    /// Whether a position entered at `entry_price`, currently bid at
+    /// `current_price`, should be closed at `now`.
+    pub fn triggered(
+        &self,
+        entry_price: Decimal,
+        current_price: Decimal,
+        end_time: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if (end_time - now).num_seconds() > self.secs_before_expiry {
+            return false;
+        }
+        !self.only_if_underwater || current_price < entry_price
+    }
+}
+
+/// One scale-out step: sell part of the position once profit reaches a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TakeProfitTier {
+    /// Profit from entry that triggers the tier (e.g. 0.2 = +20%)
+    pub profit_pct: Decimal,
+    /// Percent of the initial position to sell (e.g. 50)
+    pub position_pct: Decimal,
+}
+
+/// Take-profit tiers in ascending profit order, parsed from
+/// `profit_pct:percent_of_position` pairs such as `0.2:50,0.5:50`.
+///
+/// Percentages may sum to less than 100; the rest of the position is left to
+/// the trailing stop, time stop or settlement.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TakeProfitTiers(Vec<TakeProfitTier>);
+
+impl TakeProfitTiers {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Profit level of the last tier.
+    pub fn max_profit_pct(&self) -> Option<Decimal> {
+        self.0.last().map(|t| t.profit_pct)
+    }
+
+    /// Number of tiers reached at `profit_pct`, counting from the first.
+    fn reached(&self, profit_pct: Decimal) -> usize {
+        self.0
+            .iter()
+            .take_while(|t| profit_pct >= t.profit_pct)
+            .count()
+    }
+
+    /// Shares sold in total once the first `tiers` tiers are taken. The last
+    /// tier of a 100% schedule sells everything, leaving no rounding dust.
+    fn cumulative_shares(&self, initial_shares: Decimal, tiers: usize) -> Decimal {
+        let pct: Decimal = self.0.iter().take(tiers).map(|t| t.position_pct).sum();
+        if pct >= dec!(100) {
+            return initial_shares;
+        }
+        (initial_shares * pct / dec!(100)).round_dp_with_strategy(2, RoundingStrategy::ToZero)
+    }
+}
+
+impl std::str::FromStr for TakeProfitTiers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tiers: Vec<TakeProfitTier> = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (profit, position) = part
+                .split_once(':')
+                .ok_or_else(|| format!("tier '{}' is not profit_pct:percent_of_position", part))?;
+            let parse = |v: &str| {
+                v.trim()
+                    .parse::<Decimal>()
+                    .map_err(|_| format!("invalid number '{}' in tier '{}'", v.trim(), part))
+            };
+            let tier = TakeProfitTier {
+                profit_pct: parse(profit)?,
+                position_pct: parse(position)?,
+            };
+            if tier.profit_pct <= Decimal::ZERO {
+                return Err(format!("tier '{}' must trigger above entry", part));
+            }
+            if tier.position_pct <= Decimal::ZERO || tier.position_pct > dec!(100) {
+                return Err(format!(
+                    "tier '{}' must sell between 0 and 100% of the position",
+                    part
+                ));
+            }
+            if tiers
+                .last()
+                .is_some_and(|prev| tier.profit_pct <= prev.profit_pct)
+            {
+                return Err(format!(
+                    "tiers must be in ascending profit order (got '{}')",
+                    s
+                ));
+            }
+            tiers.push(tier);
+        }
+        if tiers.is_empty() {
+            return Err("no take-profit tiers given".to_string());
+        }
+        let total: Decimal = tiers.iter().map(|t| t.position_pct).sum();
+        if total > dec!(100) {
+            return Err(format!("tiers sell {}% of the position, above 100%", total));
+        }
+        Ok(Self(tiers))
+    }
+}
+
+impl std::fmt::Display for TakeProfitTiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self
+            .0
+            .iter()
+            .map(|t| {
+                format!(
+                    "+{}%: sell {}%",
+                    (t.profit_pct * dec!(100)).normalize(),
+                    t.position_pct.normalize()
+                )
+            })
+            .collect();
+        f.write_str(&parts.join(", "))
+    }
+}
+
 /// Result of an exit attempt.
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -63,7 +214,12 @@ pub struct ExitResult {
     pub market_id: Uuid,
     pub market_name: String,
     pub side: String,
+    /// Shares sold by this exit
     pub shares: Decimal,
+    /// Shares still held after a successful exit
+    pub remaining_shares: Decimal,
+    /// Part of a scale-out: this exit or an earlier one sold only some shares
+    pub scaled_out: bool,
     pub entry_price: Decimal,
     pub exit_price: Decimal,
     pub peak_price: Decimal,
@@ -83,6 +239,10 @@ pub struct ExitManager {
     trailing_stop_pct: Decimal,
     /// Optional take profit percentage (e.g., 0.30 = 30% profit)
     take_profit_pct: Option<Decimal>,
+    /// Scale-out take-profit tiers (empty if disabled)
+    take_profit_tiers: TakeProfitTiers,
+    /// Optional time-before-expiry exit
+    time_stop: Option<TimeStop>,
     /// Dry run mode
     dry_run: bool,
 }
@@ -98,13 +258,29 @@ impl ExitManager {
             active_positions: HashMap::new(),
             trailing_stop_pct,
             take_profit_pct,
+            take_profit_tiers: TakeProfitTiers::default(),
+            time_stop: None,
             dry_run,
         }
     }
 
-    /// Check if trailing exit is enabled.
+    /// Sell the position in steps as profit reaches each tier.
+    pub fn with_take_profit_tiers(mut self, tiers: TakeProfitTiers) -> Self {
+        self.take_profit_tiers = tiers;
+        self
+    }
+
+    /// Also close positions shortly before expiry.
+    pub fn with_time_stop(mut self, time_stop: TimeStop) -> Self {
+        self.time_stop = Some(time_stop);
+        self
+    }
+
+    /// Check if any exit (trailing stop, take-profit tiers or time stop) is enabled.
     pub fn is_enabled(&self) -> bool {
         self.trailing_stop_pct > dec!(0)
+            || !self.take_profit_tiers.is_empty()
+            || self.time_stop.is_some()
     }
 
     /// Add a position after fill confirmed.
@@ -123,6 +299,8 @@ impl ExitManager {
             token_id,
             side: side.clone(),
             shares,
+            initial_shares: shares,
+            tiers_taken: 0,
             entry_price,
             peak_price: entry_price, // Start with entry as peak
             entered_at: Utc::now(),
@@ -163,6 +341,7 @@ impl ExitManager {
         // Build a map for quick market lookup
         let market_map: HashMap<Uuid, &MarketWithOrderbook> =
             markets.iter().map(|m| (m.id, m)).collect();
+        let now = Utc::now();
 
         // First pass: update peaks and collect positions to exit
         let mut exits_to_process: Vec<(ActivePosition, Decimal, ExitReason)> = Vec::new();
@@ -230,6 +409,55 @@ impl ExitManager {
                 }
             }
 
+            // Check for scale-out tiers; tiers reached together sell as one order
+            let reached = self.take_profit_tiers.reached(profit_pct);
+            if reached > position.tiers_taken {
+                let already_sold = position.initial_shares - position.shares;
+                let sell = (self
+                    .take_profit_tiers
+                    .cumulative_shares(position.initial_shares, reached)
+                    - already_sold)
+                    .min(position.shares);
+                if sell > dec!(0) {
+                    info!(
+                        "[EXIT_MGR] 🎯 TAKE PROFIT tier {} triggered: {} {} @ ${:.3} (+{:.1}%), selling {} of {} shares",
+                        reached,
+                        position.market_name,
+                        position.side,
+                        current_price,
+                        profit_pct * dec!(100),
+                        fmt_shares(sell),
+                        fmt_shares(position.shares)
+                    );
+                    let mut tranche = position.clone();
+                    tranche.shares = sell;
+                    exits_to_process.push((
+                        tranche,
+                        current_price,
+                        ExitReason::TakeProfitTier(reached),
+                    ));
+                    continue;
+                }
+                // Too small to sell at share precision; nothing left for these tiers
+                position.tiers_taken = reached;
+            }
+
+            // Check for time stop trigger
+            if let Some(time_stop) = self.time_stop {
+                if time_stop.triggered(position.entry_price, current_price, market.end_time, now) {
+                    info!(
+                        "[EXIT_MGR] ⏰ TIME STOP triggered: {} {} @ ${:.3} ({}s to expiry, {:+.1}%)",
+                        position.market_name,
+                        position.side,
+                        current_price,
+                        (market.end_time - now).num_seconds(),
+                        profit_pct * dec!(100)
+                    );
+                    exits_to_process.push((position.clone(), current_price, ExitReason::TimeStop));
+                    continue;
+                }
+            }
+
             // Calculate drawdown from peak
             let drawdown = if position.peak_price > dec!(0) {
                 (position.peak_price - current_price) / position.peak_price
@@ -238,7 +466,7 @@ impl ExitManager {
             };
 
             // Check for trailing stop trigger
-            if drawdown >= self.trailing_stop_pct {
+            if self.trailing_stop_pct > dec!(0) && drawdown >= self.trailing_stop_pct {
                 info!(
                     "[EXIT_MGR] 📉 TRAILING STOP triggered: {} {} @ ${:.3} (peak ${:.3}, down {:.1}%)",
                     position.market_name,
@@ -270,11 +498,27 @@ impl ExitManager {
                 }
             }
 
-            let exit_result = self
+            let (held, initial) = match self.active_positions.get(&market_id) {
+                Some(pos) => (pos.shares, pos.initial_shares),
+                None => continue,
+            };
+            let mut exit_result = self
                 .execute_exit(position, exit_price, reason, cached_auth)
                 .await;
+            exit_result.scaled_out = exit_result.shares < held || held < initial;
 
-            if exit_result.success {
+            if exit_result.success && exit_result.shares < held {
+                // Partial exit: keep the rest of the position open
+                if let Some(pos) = self.active_positions.get_mut(&market_id) {
+                    pos.shares -= exit_result.shares;
+                    if let ExitReason::TakeProfitTier(n) = exit_result.reason {
+                        pos.tiers_taken = n;
+                    }
+                    pos.exit_attempts = 0;
+                    pos.last_exit_attempt = None;
+                    exit_result.remaining_shares = pos.shares;
+                }
+            } else if exit_result.success {
                 self.active_positions.remove(&market_id);
             } else {
                 // Track failed attempt
@@ -324,6 +568,8 @@ impl ExitManager {
                 market_name: position.market_name,
                 side: position.side,
                 shares: position.shares,
+                remaining_shares: dec!(0),
+                scaled_out: false,
                 entry_price: position.entry_price,
                 exit_price,
                 peak_price: position.peak_price,
@@ -348,8 +594,13 @@ impl ExitManager {
         {
             Ok(order_id) => {
                 info!(
-                    "[EXIT] {} {} {} @ ${:.3} (order_id: {})",
-                    reason, position.market_name, position.side, exit_price, order_id
+                    "[EXIT] {} {} {} {} shares @ {} (order_id: {})",
+                    reason,
+                    position.market_name,
+                    position.side,
+                    fmt_shares(position.shares),
+                    fmt_price(exit_price),
+                    order_id
                 );
 
                 ExitResult {
@@ -357,6 +608,8 @@ impl ExitManager {
                     market_name: position.market_name,
                     side: position.side,
                     shares: position.shares,
+                    remaining_shares: dec!(0),
+                    scaled_out: false,
                     entry_price: position.entry_price,
                     exit_price,
                     peak_price: position.peak_price,
@@ -379,6 +632,8 @@ impl ExitManager {
                     market_name: position.market_name,
                     side: position.side,
                     shares: position.shares,
+                    remaining_shares: dec!(0),
+                    scaled_out: false,
                     entry_price: position.entry_price,
                     exit_price,
                     peak_price: position.peak_price,
@@ -450,12 +705,30 @@ impl ExitManager {
                 tp * dec!(100)
             );
         }
+        if !self.take_profit_tiers.is_empty() {
+            info!("║  Take Profit Tiers: {}", self.take_profit_tiers);
+        }
+        if let Some(ts) = self.time_stop {
+            info!(
+                "║  Time Stop:         {:<6}s ({:<15})             ║",
+                ts.secs_before_expiry,
+                if ts.only_if_underwater {
+                    "underwater only"
+                } else {
+                    "always"
+                }
+            );
+        }
         info!("╠════════════════════════════════════════════════════════════╣");
 
         for pos in self.active_positions.values() {
             info!(
-                "║  {} {} @ ${:.3} (peak: ${:.3})                     ║",
-                pos.side, pos.market_name, pos.entry_price, pos.peak_price
+                "║  {} {} @ ${:.3} (peak: ${:.3}, {} shares)                     ║",
+                pos.side,
+                pos.market_name,
+                pos.entry_price,
+                pos.peak_price,
+                fmt_shares(pos.shares)
             );
         }
 
@@ -466,6 +739,31 @@ impl ExitManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
+
+    fn market(id: Uuid, end_time: DateTime<Utc>, yes_best_bid: Decimal) -> MarketWithOrderbook {
+        MarketWithOrderbook {
+            id,
+            condition_id: "cond".to_string(),
+            market_type: "up_down".to_string(),
+            asset: "BTC".to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "yes".to_string(),
+            no_token_id: "no".to_string(),
+            name: "Test Market".to_string(),
+            end_time,
+            is_active: true,
+            yes_best_ask: Some(yes_best_bid + dec!(0.01)),
+            yes_best_bid: Some(yes_best_bid),
+            no_best_ask: None,
+            no_best_bid: None,
+            yes_asks: None,
+            no_asks: None,
+            yes_bids: None,
+            no_bids: None,
+            captured_at: Utc::now(),
+        }
+    }
 
     #[test]
     fn test_exit_manager_creation() {
@@ -497,4 +795,180 @@ mod tests {
         let manager = ExitManager::new(dec!(0), None, true);
         assert!(!manager.is_enabled());
     }
+
+    #[test]
+    fn test_time_stop_boundary() {
+        let stop = TimeStop {
+            secs_before_expiry: 60,
+            only_if_underwater: false,
+        };
+        let now = Utc::now();
+        let fires = |secs_left| {
+            stop.triggered(
+                dec!(0.40),
+                dec!(0.50),
+                now + Duration::seconds(secs_left),
+                now,
+            )
+        };
+
+        assert!(!fires(61));
+        assert!(fires(60));
+        assert!(fires(10));
+        // Already past expiry still closes
+        assert!(fires(-5));
+    }
+
+    #[test]
+    fn test_time_stop_only_if_underwater() {
+        let stop = TimeStop {
+            secs_before_expiry: 60,
+            only_if_underwater: true,
+        };
+        let now = Utc::now();
+        let end = now + Duration::seconds(30);
+
+        assert!(stop.triggered(dec!(0.40), dec!(0.35), end, now));
+        assert!(!stop.triggered(dec!(0.40), dec!(0.40), end, now));
+        assert!(!stop.triggered(dec!(0.40), dec!(0.55), end, now));
+        // Underwater but outside the window
+        assert!(!stop.triggered(dec!(0.40), dec!(0.35), now + Duration::seconds(120), now));
+    }
+
+    #[tokio::test]
+    async fn test_check_exits_time_stop() {
+        // Time stop alone enables the manager; no trailing stop fires
+        let mut manager = ExitManager::new(dec!(0), None, true).with_time_stop(TimeStop {
+            secs_before_expiry: 60,
+            only_if_underwater: true,
+        });
+        assert!(manager.is_enabled());
+
+        let (underwater, profitable, early) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        for id in [underwater, profitable, early] {
+            manager.add_position(
+                id,
+                "Test Market".to_string(),
+                "token123".to_string(),
+                "YES".to_string(),
+                dec!(10),
+                dec!(0.40),
+            );
+        }
+        let now = Utc::now();
+        let markets = vec![
+            market(underwater, now + Duration::seconds(30), dec!(0.30)),
+            market(profitable, now + Duration::seconds(30), dec!(0.60)),
+            market(early, now + Duration::seconds(600), dec!(0.30)),
+        ];
+
+        let exits = manager.check_exits(&markets, &mut None).await;
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].market_id, underwater);
+        assert_eq!(exits[0].reason, ExitReason::TimeStop);
+        assert_eq!(exits[0].pnl, dec!(-1.0));
+        assert!(!manager.has_position(&underwater));
+        assert_eq!(manager.position_count(), 2);
+    }
+
+    #[test]
+    fn test_parse_take_profit_tiers() {
+        let tiers: TakeProfitTiers = "0.2:50, 0.5:50".parse().unwrap();
+        assert_eq!(tiers.max_profit_pct(), Some(dec!(0.5)));
+        assert_eq!(tiers.to_string(), "+20%: sell 50%, +50%: sell 50%");
+
+        assert!("0.2:50".parse::<TakeProfitTiers>().is_ok());
+        assert!("".parse::<TakeProfitTiers>().is_err());
+        assert!("0.2".parse::<TakeProfitTiers>().is_err());
+        assert!("0.2:abc".parse::<TakeProfitTiers>().is_err());
+        assert!("0:50".parse::<TakeProfitTiers>().is_err());
+        assert!("0.2:0".parse::<TakeProfitTiers>().is_err());
+        // Out of order or selling more than the whole position
+        assert!("0.5:50,0.2:50".parse::<TakeProfitTiers>().is_err());
+        assert!("0.2:60,0.5:50".parse::<TakeProfitTiers>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_tiers_scale_out_in_order() {
+        let tiers = "0.2:50,0.5:50".parse().unwrap();
+        let mut manager = ExitManager::new(dec!(0), None, true).with_take_profit_tiers(tiers);
+        assert!(manager.is_enabled());
+
+        let id = Uuid::new_v4();
+        manager.add_position(
+            id,
+            "Test Market".to_string(),
+            "token123".to_string(),
+            "YES".to_string(),
+            dec!(10),
+            dec!(0.40),
+        );
+        let end = Utc::now() + Duration::minutes(10);
+
+        // Below the first tier: nothing sells
+        assert!(manager
+            .check_exits(&[market(id, end, dec!(0.45))], &mut None)
+            .await
+            .is_empty());
+
+        // +25%: first tier sells half
+        let exits = manager
+            .check_exits(&[market(id, end, dec!(0.50))], &mut None)
+            .await;
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].reason, ExitReason::TakeProfitTier(1));
+        assert_eq!(exits[0].shares, dec!(5));
+        assert_eq!(exits[0].remaining_shares, dec!(5));
+        assert!(exits[0].scaled_out);
+        assert_eq!(exits[0].pnl, dec!(0.50));
+        assert_eq!(manager.get_positions()[0].shares, dec!(5));
+
+        // A taken tier does not fire again
+        assert!(manager
+            .check_exits(&[market(id, end, dec!(0.52))], &mut None)
+            .await
+            .is_empty());
+
+        // +55%: second tier sells the rest and closes the position
+        let exits = manager
+            .check_exits(&[market(id, end, dec!(0.62))], &mut None)
+            .await;
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].reason, ExitReason::TakeProfitTier(2));
+        assert_eq!(exits[0].shares, dec!(5));
+        assert_eq!(exits[0].remaining_shares, dec!(0));
+        assert!(exits[0].scaled_out);
+        assert!(!manager.has_position(&id));
+    }
+
+    #[tokio::test]
+    async fn test_take_profit_tiers_reached_together_and_partial_schedule() {
+        let tiers = "0.2:30,0.5:30".parse().unwrap();
+        let mut manager = ExitManager::new(dec!(0), None, true).with_take_profit_tiers(tiers);
+        let id = Uuid::new_v4();
+        manager.add_position(
+            id,
+            "Test Market".to_string(),
+            "token123".to_string(),
+            "YES".to_string(),
+            dec!(7),
+            dec!(0.40),
+        );
+        let end = Utc::now() + Duration::minutes(10);
+
+        // Both tiers at once: 60% of 7 shares, rounded down to share precision
+        let exits = manager
+            .check_exits(&[market(id, end, dec!(0.70))], &mut None)
+            .await;
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].reason, ExitReason::TakeProfitTier(2));
+        assert_eq!(exits[0].shares, dec!(4.2));
+        // The untiered 40% stays open for the other exits
+        assert_eq!(exits[0].remaining_shares, dec!(2.8));
+        assert!(manager.has_position(&id));
+        assert!(manager
+            .check_exits(&[market(id, end, dec!(0.80))], &mut None)
+            .await
+            .is_empty());
+    }
 }
@@ -7,65 +7,73 @@
 //!    - DOWN -> UP -> Place LIMIT order at $0.40 to BUY YES
 //!    - UP -> DOWN -> Place LIMIT order at $0.40 to BUY NO
 //! 4. Auto-cancel order after 10 seconds if not filled
+//!    (optionally re-place it a step higher, see --chase-steps)
 //! 5. Only trade once per market (first qualifying flip)
 
-use std::collections::{HashMap, HashSet};
+use std::cell::Cell;
+use std::collections::HashSet;
 use std::time::Duration;
 
-use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
 use clap::Parser;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
+use sqlx::PgPool;
 use tracing::{debug, error, info, warn};
-use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
+use common::assets::{binance_symbol, mapped_assets, parse_assets};
+use common::logging::SampledLogger;
+use common::models::{TradeIntent, TradeMode, TradeOutcome};
+use common::validation::{
+    validate_exit_targets, validate_expiry_window, validate_max_shares, validate_pct,
+    validate_positive, validate_price,
+};
 use common::{
-    calculate_fill_price_with_slippage, execute_trade, get_15m_updown_markets_with_orderbooks,
-    BinanceEvent, BinanceStreamType, BinanceWsClient, CachedAuth, Config, Database,
-    DryRunPortfolio, GammaClient, KlineBuffer, SimulatedPosition,
+    aggregate_depth_to_ticks, calculate_fill_price_from_levels, calculate_fill_price_with_slippage,
+    cancel_on_stall, check_signals_concurrently, cycle_interval, depth_levels,
+    ensure_authenticated, execute_trade, fetch_live_best_ask, fetch_tick_size, fmt_price,
+    fmt_shares, fresh_retry_ask, get_15m_updown_markets_with_orderbooks, get_active_markets,
+    log_unresolved_live_positions, price_divergence_exceeded, reconcile_open_orders,
+    record_trade_audit, resolve_live_settlements, retain_valid_depth, spawn_watchdog, BinanceEvent,
+    BinanceStreamType, BinanceWsClient, CachedAuth, Config, DailyPnlStop, Database,
+    DryRunPortfolio, EntryOffset, FillPriceModel, GammaClient, Heartbeat, KlineBuffer,
+    LivePosition, LivePositions, MarketRepository, MarketRotation, MarketWithOrderbook,
+    OrderRejection, PaperFillMode, PaperOrders, SettlementTimeout, SettlementTimeoutPolicy,
+    SideFilter, SimulatedPosition, TradeThrottle, UnresolvedPolicy, DEFAULT_MAX_RESOLUTION_RETRIES,
+    DEFAULT_MAX_SHARES, DEFAULT_TICK_SIZE,
 };
 
-/// Tracks a live position for settlement resolution.
-///
-/// Unlike ExitManager positions, these survive past exit failures and
-/// are used to query on-chain settlement outcomes for expired markets.
-#[derive(Debug, Clone)]
-struct LivePosition {
-    yes_token_id: String,
-    side: String,
+/// A flip that passed the orderbook checks, waiting on live price
+/// verification and execution.
+struct Signal<'a> {
+    market: &'a MarketWithOrderbook,
+    flip_type: FlipType,
+    side: &'static str,
+    token_id: &'a str,
+    best_ask: Decimal,
+    best_bid: Option<Decimal>,
     shares: Decimal,
-    cost: Decimal,
-    end_time: DateTime<Utc>,
-    market_name: String,
-    /// Whether this position was successfully exited via trailing stop/take profit
-    exited: bool,
-    /// Number of resolution fetch attempts
-    resolution_retries: u32,
-    /// Last time we attempted to fetch resolution
-    last_retry_time: Option<DateTime<Utc>>,
+    /// Depth-based fill estimate for `shares`
+    effective_price: Decimal,
+    open_price: Decimal,
+    current_price: Decimal,
 }
 
-/// Maximum resolution retry attempts before force-expiring a live position
-const MAX_LIVE_RESOLUTION_RETRIES: u32 = 30;
+/// Strategy tag recorded with each trade intent.
+const STRATEGY_TAG: &str = "misprice";
 
-/// Calculate backoff delay in seconds based on retry count.
-/// Uses exponential backoff: 60s, 120s, 240s... capped at 600s (10 min)
-fn live_resolution_backoff_secs(retries: u32) -> i64 {
-    let base_delay = 60i64;
-    let max_delay = 600i64;
-    let delay = base_delay * (1i64 << retries.min(4));
-    delay.min(max_delay)
-}
+/// Highest price a chased order may be re-placed at.
+const MAX_CHASE_PRICE: Decimal = dec!(0.99);
 
 mod detector;
 mod exit_manager;
 mod metrics;
 mod order_manager;
 
-use detector::MispriceDetector;
-use exit_manager::ExitManager;
+use detector::{FlipType, MispriceDetector};
+use exit_manager::{ExitManager, TakeProfitTiers, TimeStop};
 use metrics::Metrics;
 use order_manager::OrderManager;
 
@@ -78,14 +86,64 @@ struct Args {
     #[arg(long)]
     dry_run: bool,
 
+    /// How to account for unresolved dry-run positions at shutdown
+    /// (mark-to-market, assume-loss, win-if-above-half, leave-pending)
+    #[arg(long, default_value = "mark-to-market")]
+    unresolved_policy: UnresolvedPolicy,
+
+    /// Virtual starting bankroll in USDC for dry-run; trades beyond the
+    /// available balance (bankroll + realized P&L - open cost) are refused
+    #[arg(long)]
+    dry_run_balance: Option<f64>,
+
+    /// Stop opening new positions once the day's realized P&L reaches this
+    /// many USDC (open positions still exit and settle; resets at UTC midnight)
+    #[arg(long)]
+    daily_profit_target: Option<f64>,
+
+    /// Stop opening new positions once the day's realized loss reaches this
+    /// many USDC (open positions still exit and settle; resets at UTC midnight)
+    #[arg(long)]
+    daily_max_loss: Option<f64>,
+
+    /// Resolution attempts for an expired position before its settlement times out
+    #[arg(long, default_value_t = DEFAULT_MAX_RESOLUTION_RETRIES)]
+    settlement_max_retries: u32,
+
+    /// How to count a position whose settlement times out
+    /// (loss, unknown = excluded from P&L, breakeven)
+    #[arg(long, default_value = "loss")]
+    settlement_timeout_policy: SettlementTimeoutPolicy,
+
     /// Limit order price (place orders at this price)
     #[arg(long, default_value = "0.40")]
     limit_price: f64,
 
+    /// Where to price entries: ask (take at the limit price), midpoint
+    /// (between best bid and ask), or join-bid (best bid + one tick). The
+    /// passive modes rest on the book until filled or --cancel-timeout
+    #[arg(long, default_value = "ask")]
+    entry_offset: EntryOffset,
+
+    /// Price used to plan fills from orderbook depth: vwap (size-weighted
+    /// average), worst-touched (deepest level reached) or best-only
+    #[arg(long, default_value = "vwap")]
+    fill_model: FillPriceModel,
+
+    /// How dry-run orders fill: optimistic (immediately at the estimated
+    /// fill) or realistic (only once a later snapshot's ask reaches the
+    /// order price; cancelled after --cancel-timeout otherwise)
+    #[arg(long, default_value = "optimistic")]
+    paper_fill: PaperFillMode,
+
     /// Position size in USDC
     #[arg(long, default_value = "5")]
     position_size: f64,
 
+    /// Maximum shares per order; a signal needing more (position size / price) is skipped
+    #[arg(long, default_value_t = DEFAULT_MAX_SHARES)]
+    max_shares: Decimal,
+
     /// Maximum time to market expiry in minutes
     #[arg(long, default_value = "10")]
     max_expiry_minutes: i64,
@@ -98,7 +156,7 @@ struct Args {
     #[arg(long, default_value = "1")]
     max_orderbook_age: i32,
 
-    /// Assets to trade (comma-separated)
+    /// Assets to trade (comma-separated), or ALL for every asset with active markets
     #[arg(long, default_value = "BTC")]
     assets: String,
 
@@ -106,6 +164,28 @@ struct Args {
     #[arg(long, default_value = "10")]
     cancel_timeout: u64,
 
+    /// Cancel open orders on the configured assets found at startup
+    /// (live mode; without this they are only logged)
+    #[arg(long)]
+    cancel_orphans: bool,
+
+    /// Cancel all resting orders if no trading cycle completes within this
+    /// many seconds (0 = disabled)
+    #[arg(long, default_value = "60")]
+    watchdog_timeout_secs: u64,
+
+    /// Re-place unfilled orders at a higher price up to this many times (0 = disabled)
+    #[arg(long, default_value = "0")]
+    chase_steps: u32,
+
+    /// Price increment per chase re-price
+    #[arg(long, default_value = "0.01")]
+    chase_step_size: f64,
+
+    /// Maximum price a chased order may reach (default: limit price + steps * step size)
+    #[arg(long)]
+    chase_max_price: Option<f64>,
+
     /// Trailing stop percentage (exit when price drops this much from peak). 0 to disable.
     #[arg(long, default_value = "0")]
     trailing_stop_pct: f64,
@@ -114,55 +194,236 @@ struct Args {
     #[arg(long)]
     take_profit_pct: Option<f64>,
 
+    /// Scale out instead of a single take profit: profit_pct:percent_of_position
+    /// tiers, e.g. "0.2:50,0.5:50" sells half at +20% and the rest at +50%.
+    /// Any untiered remainder is left to the other exits. Optional.
+    #[arg(long, conflicts_with = "take_profit_pct")]
+    take_profit_tiers: Option<TakeProfitTiers>,
+
+    /// Close any open position this many seconds before market expiry,
+    /// regardless of price. Optional.
+    #[arg(long)]
+    time_stop_secs: Option<i64>,
+
+    /// Only apply --time-stop-secs to positions trading below their entry price
+    #[arg(long)]
+    time_stop_if_underwater: bool,
+
+    /// Skip a signal when the depth-based fill is more than this percent above
+    /// the best ask (e.g. 5 = 5%), even if it is under the limit price. Optional.
+    #[arg(long)]
+    max_slippage_pct: Option<f64>,
+
+    /// Merge ask levels into price buckets of this size (e.g. 0.01) before
+    /// estimating fills, collapsing sub-tick levels. Optional.
+    #[arg(long)]
+    depth_tick_size: Option<f64>,
+
+    /// Fully process at most this many markets per cycle, most urgent first
+    /// (soonest expiry, then cheapest ask); the rest wait for a later cycle. Optional.
+    #[arg(long)]
+    max_markets_per_cycle: Option<usize>,
+
+    /// Skip a flip when the orderbook snapshot is more than this many ms older
+    /// than the price tick that triggered it (the book hasn't caught up with
+    /// the move yet). Optional.
+    #[arg(long)]
+    max_snapshot_lag_ms: Option<u64>,
+
+    /// Only trade one side of a signal (both, yes-only, no-only); signals for
+    /// the other side are still tracked but not acted upon
+    #[arg(long, default_value = "both")]
+    side_filter: SideFilter,
+
+    /// Minimum bid/ask depth ratio on the side being bought before a flip is
+    /// traded (e.g. 1.5 = 50% more resting bids than asks). Skipped when the
+    /// market has no depth data. Optional.
+    #[arg(long)]
+    min_imbalance: Option<f64>,
+
+    /// Minimum USDC resting on the ask side being bought (sum of price * size
+    /// across the snapshot depth). Markets without depth data are skipped. Optional.
+    #[arg(long)]
+    min_liquidity_usdc: Option<f64>,
+
+    /// Re-check the best ask against the live CLOB book before each trade
+    #[arg(long)]
+    verify_live_price: bool,
+
+    /// With --verify-live-price, skip when the live ask is more than this many
+    /// dollars above the DB ask
+    #[arg(long, default_value = "0.02")]
+    max_price_divergence: f64,
+
+    /// Markets whose signals are priced concurrently (live book fetches);
+    /// orders are still placed one at a time
+    #[arg(long, default_value = "8")]
+    max_concurrency: usize,
+
+    /// Shortest interval between trading cycles in ms, used when a market is
+    /// about to expire
+    #[arg(long, default_value = "100")]
+    min_cycle_ms: u64,
+
+    /// Longest interval between trading cycles in ms, used when every market
+    /// is far from expiry
+    #[arg(long, default_value = "1000")]
+    max_cycle_ms: u64,
+
     /// Timeframes to trade (comma-separated, e.g. "5m,15m"). Default: "5m,15m"
     #[arg(long, default_value = "5m,15m")]
     timeframes: String,
+
+    /// Minimum milliseconds between live orders across all markets, on top of
+    /// per-market cooldowns (0 = no limit; ignored in dry-run)
+    #[arg(long, default_value = "0")]
+    min_trade_interval_ms: u64,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
+
+    /// Log only 1 in N repeats of per-cycle debug messages (e.g. a market
+    /// still waiting for kline data); 1 logs every occurrence
+    #[arg(long, default_value = "1")]
+    log_sample_rate: u64,
+}
+
+/// Key for sampled per-cycle debug messages: reason, and market when the
+/// message is about one.
+type SampleKey = (&'static str, Option<Uuid>);
+
+/// Log open orders on the configured assets' markets left over from a previous
+/// run, cancelling them when `cancel` is set.
+async fn reconcile_orphan_orders(
+    pool: &PgPool,
+    assets: &[String],
+    cancel: bool,
+    cached_auth: &mut Option<CachedAuth>,
+) -> Result<()> {
+    let token_ids: HashSet<String> = get_active_markets(pool)
+        .await?
+        .into_iter()
+        .filter(|m| assets.contains(&m.asset))
+        .flat_map(|m| [m.yes_token_id, m.no_token_id])
+        .collect();
+
+    let auth = ensure_authenticated(cached_auth).await?;
+    reconcile_open_orders(auth, &token_ids, cancel).await?;
+    Ok(())
+}
+
+/// Reject flag values and combinations that would otherwise misbehave silently.
+fn validate_args(args: &Args) -> Result<()> {
+    validate_expiry_window(args.min_expiry_minutes, args.max_expiry_minutes)?;
+    validate_price("limit-price", args.limit_price)?;
+    validate_positive("position-size", args.position_size)?;
+    validate_max_shares(args.max_shares)?;
+    validate_exit_targets(
+        args.limit_price,
+        args.trailing_stop_pct,
+        args.take_profit_pct,
+    )?;
+    if let Some(max) = args.max_slippage_pct {
+        if max < 0.0 {
+            bail!("--max-slippage-pct must be non-negative (got {})", max);
+        }
+    }
+    if args.chase_steps > 0 {
+        validate_positive("chase-step-size", args.chase_step_size)?;
+    }
+    validate_time_stop(args.time_stop_secs, args.time_stop_if_underwater)?;
+    if let Some(top) = args
+        .take_profit_tiers
+        .as_ref()
+        .and_then(|tiers| tiers.max_profit_pct())
+    {
+        let target = Decimal::try_from(args.limit_price)? * (Decimal::ONE + top);
+        if target > Decimal::ONE {
+            bail!(
+                "--take-profit-tiers top tier targets ${:.2} from a ${:.2} entry, above the $1.00 payout",
+                target,
+                args.limit_price
+            );
+        }
+    }
+    if args.max_concurrency == 0 {
+        bail!("--max-concurrency must be at least 1");
+    }
+    if let Some(max) = args.chase_max_price {
+        validate_price("chase-max-price", max)?;
+        if max < args.limit_price {
+            bail!(
+                "--chase-max-price ({}) must not be below --limit-price ({})",
+                max,
+                args.limit_price
+            );
+        }
+    }
+    if args.max_markets_per_cycle == Some(0) {
+        bail!("--max-markets-per-cycle must be at least 1");
+    }
+    if args.log_sample_rate == 0 {
+        bail!("--log-sample-rate must be at least 1");
+    }
+    if args.min_cycle_ms == 0 || args.min_cycle_ms > args.max_cycle_ms {
+        bail!(
+            "--min-cycle-ms must be at least 1 and no greater than --max-cycle-ms (got {} / {})",
+            args.min_cycle_ms,
+            args.max_cycle_ms
+        );
+    }
+    if let Some(min) = args.min_imbalance {
+        validate_positive("min-imbalance", min)?;
+    }
+    if let Some(min) = args.min_liquidity_usdc {
+        validate_positive("min-liquidity-usdc", min)?;
+    }
+    if let Some(tick) = args.depth_tick_size {
+        validate_positive("depth-tick-size", tick)?;
+    }
+    if let Some(balance) = args.dry_run_balance {
+        validate_positive("dry-run-balance", balance)?;
+    }
+    if let Some(target) = args.daily_profit_target {
+        validate_positive("daily-profit-target", target)?;
+    }
+    if let Some(loss) = args.daily_max_loss {
+        validate_positive("daily-max-loss", loss)?;
+    }
+    validate_pct("max-price-divergence", args.max_price_divergence, 0.0..=1.0)?;
+    if args.settlement_max_retries == 0 {
+        bail!("--settlement-max-retries must be at least 1");
+    }
+    Ok(())
 }
 
-/// Map of asset -> Binance symbol. Returns None for unsupported assets.
-fn asset_to_binance_symbol(asset: &str) -> Option<&'static str> {
-    match asset.to_uppercase().as_str() {
-        "BTC" => Some("BTCUSDT"),
-        "ETH" => Some("ETHUSDT"),
-        "SOL" => Some("SOLUSDT"),
-        "XRP" => Some("XRPUSDT"),
-        _ => {
-            warn!("Unsupported asset: {}, skipping", asset);
-            None
+fn validate_time_stop(time_stop_secs: Option<i64>, only_if_underwater: bool) -> Result<()> {
+    match time_stop_secs {
+        Some(secs) if secs <= 0 => bail!("--time-stop-secs must be positive (got {})", secs),
+        None if only_if_underwater => {
+            bail!("--time-stop-if-underwater requires --time-stop-secs")
         }
+        _ => Ok(()),
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
+    validate_args(&args)?;
 
-    // Validate percentage arguments
-    if !(0.0..=1.0).contains(&args.trailing_stop_pct) {
-        anyhow::bail!(
-            "--trailing-stop-pct must be between 0.0 and 1.0 (got {})",
-            args.trailing_stop_pct
-        );
-    }
-    if let Some(tp) = args.take_profit_pct {
-        if !(0.0..=10.0).contains(&tp) {
-            anyhow::bail!(
-                "--take-profit-pct must be between 0.0 and 10.0 (got {})",
-                tp
-            );
-        }
-    }
+    let settlement = SettlementTimeout {
+        max_retries: args.settlement_max_retries,
+        policy: args.settlement_timeout_policy,
+    };
 
     info!("=== Misprice Trader ===");
     info!("Limit price: ${}", args.limit_price);
+    info!("Entry offset: {}", args.entry_offset);
     info!("Position size: ${}", args.position_size);
+    info!("Max shares per order: {}", args.max_shares);
     info!(
         "Expiry window: {}-{} minutes",
         args.min_expiry_minutes, args.max_expiry_minutes
@@ -170,14 +431,74 @@ async fn main() -> Result<()> {
     info!("Max orderbook age: {}s", args.max_orderbook_age);
     info!("Assets: {}", args.assets);
     info!("Cancel timeout: {}s", args.cancel_timeout);
+    if args.watchdog_timeout_secs > 0 {
+        info!("Watchdog timeout: {}s", args.watchdog_timeout_secs);
+    }
     if args.trailing_stop_pct > 0.0 {
         info!("Trailing stop: {:.1}%", args.trailing_stop_pct * 100.0);
         if let Some(tp) = args.take_profit_pct {
             info!("Take profit: {:.1}%", tp * 100.0);
         }
     }
+    if let Some(tiers) = &args.take_profit_tiers {
+        info!("Take profit tiers: {}", tiers);
+    }
+    if let Some(secs) = args.time_stop_secs {
+        info!(
+            "Time stop: {}s before expiry{}",
+            secs,
+            if args.time_stop_if_underwater {
+                " (underwater positions only)"
+            } else {
+                ""
+            }
+        );
+    }
     info!("Timeframes: {}", args.timeframes);
+    info!(
+        "Cycle interval: {}-{}ms (adaptive to time-to-expiry)",
+        args.min_cycle_ms, args.max_cycle_ms
+    );
+    if args.side_filter != SideFilter::Both {
+        info!("Side filter: {}", args.side_filter);
+    }
+    if let Some(ms) = args.max_snapshot_lag_ms {
+        info!("Max snapshot lag behind price: {}ms", ms);
+    }
+    if args.log_sample_rate > 1 {
+        info!("Per-cycle debug logs sampled 1 in {}", args.log_sample_rate);
+    }
+    if let Some(min) = args.min_imbalance {
+        info!("Min orderbook imbalance: {:.2}x", min);
+    }
+    if let Some(min) = args.min_liquidity_usdc {
+        info!("Min ask liquidity: ${:.2}", min);
+    }
+    if args.verify_live_price {
+        info!(
+            "Live price check: skip if CLOB ask > DB ask + ${} ({} books at a time)",
+            args.max_price_divergence, args.max_concurrency
+        );
+    }
     info!("Dry run: {}", args.dry_run);
+    info!("Fill model: {}", args.fill_model);
+    info!(
+        "Settlement timeout: {} after {} retries",
+        settlement.policy, settlement.max_retries
+    );
+    if args.dry_run {
+        info!("Paper fills: {}", args.paper_fill);
+        info!("Unresolved policy: {}", args.unresolved_policy);
+        if let Some(balance) = args.dry_run_balance {
+            info!("Virtual balance: ${:.2}", balance);
+        }
+    }
+    if let Some(target) = args.daily_profit_target {
+        info!("Daily profit target: ${:.2}", target);
+    }
+    if let Some(loss) = args.daily_max_loss {
+        info!("Daily max loss: ${:.2}", loss);
+    }
 
     // Load config and connect to database
     dotenvy::dotenv().ok();
@@ -187,17 +508,17 @@ async fn main() -> Result<()> {
 
     info!("Connected to database");
 
-    // Parse assets
-    let assets: Vec<String> = args
-        .assets
-        .split(',')
-        .map(|s| s.trim().to_uppercase())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    if assets.is_empty() {
-        anyhow::bail!("No valid assets specified");
-    }
+    // Parse assets (ALL = every asset with active markets), keeping those
+    // Binance can price
+    let selection = parse_assets(&args.assets)?;
+    let (assets, binance_symbols): (Vec<String>, Vec<String>) = mapped_assets(
+        &selection.resolve(db.pool()).await?,
+        "Binance",
+        binance_symbol,
+    )
+    .into_iter()
+    .map(|(asset, symbol)| (asset, symbol.to_string()))
+    .unzip();
 
     // Parse timeframes
     let timeframes: Vec<String> = args
@@ -208,17 +529,11 @@ async fn main() -> Result<()> {
         .collect();
 
     if timeframes.is_empty() {
-        anyhow::bail!("No valid timeframes specified");
+        bail!("No valid timeframes specified");
     }
 
-    // Build Binance symbols list
-    let binance_symbols: Vec<String> = assets
-        .iter()
-        .filter_map(|a| asset_to_binance_symbol(a).map(|s| s.to_string()))
-        .collect();
-
     if binance_symbols.is_empty() {
-        anyhow::bail!("No supported assets specified");
+        bail!("No supported assets specified");
     }
 
     info!("Binance symbols: {:?}", binance_symbols);
@@ -226,27 +541,91 @@ async fn main() -> Result<()> {
     // Convert parameters to Decimal
     let limit_price = Decimal::try_from(args.limit_price).context("Invalid limit_price")?;
     let position_size = Decimal::try_from(args.position_size).context("Invalid position_size")?;
+    let max_shares = args.max_shares;
 
     // Initialize components
     // Buffer needs to hold ~20 minutes of 1-minute klines to cover market start times
     let mut kline_buffer = KlineBuffer::new(25);
-    let mut detector = MispriceDetector::new();
+    let min_imbalance = match args.min_imbalance {
+        Some(min) => Some(Decimal::try_from(min).context("Invalid min_imbalance")?),
+        None => None,
+    };
+    let max_snapshot_lag = args
+        .max_snapshot_lag_ms
+        .map(|ms| chrono::Duration::milliseconds(ms as i64));
+    let mut detector = MispriceDetector::new()
+        .with_min_imbalance(min_imbalance)
+        .with_max_snapshot_lag(max_snapshot_lag);
     let mut metrics = Metrics::new();
     let mut portfolio = DryRunPortfolio::new();
+    let mut rotation = MarketRotation::default();
+    if let Some(balance) = args.dry_run_balance {
+        portfolio =
+            portfolio.with_balance(Decimal::try_from(balance).context("Invalid dry_run_balance")?);
+    }
+    let mut daily_stop = DailyPnlStop::new(
+        args.daily_profit_target
+            .map(Decimal::try_from)
+            .transpose()
+            .context("Invalid daily_profit_target")?,
+        args.daily_max_loss
+            .map(Decimal::try_from)
+            .transpose()
+            .context("Invalid daily_max_loss")?,
+    );
+    let mut paper_orders = PaperOrders::new(args.cancel_timeout);
+    let mut sampler: SampledLogger<SampleKey> = SampledLogger::new(args.log_sample_rate);
     let mut cached_auth: Option<CachedAuth> = None;
+    let mut throttle = if args.dry_run {
+        TradeThrottle::disabled()
+    } else {
+        TradeThrottle::new(Duration::from_millis(args.min_trade_interval_ms))
+    };
     // Track (market_id, side) - only trade once per market per side
     let mut traded_positions: HashSet<(Uuid, String)> = HashSet::new();
     // Order manager for tracking pending orders and auto-cancel (live trading only)
     let mut order_manager = OrderManager::new(args.cancel_timeout);
+    let chase_step_size =
+        Decimal::try_from(args.chase_step_size).context("Invalid chase_step_size")?;
+    order_manager.enable_chase(args.chase_steps, chase_step_size);
+    let chase_ceiling = match args.chase_max_price {
+        Some(max) => Decimal::try_from(max).context("Invalid chase_max_price")?,
+        None => limit_price + chase_step_size * Decimal::from(args.chase_steps),
+    }
+    .min(MAX_CHASE_PRICE);
+    if args.chase_steps > 0 && !args.dry_run {
+        info!(
+            "Chase: up to {} re-prices of ${} (ceiling ${})",
+            args.chase_steps, chase_step_size, chase_ceiling
+        );
+    }
     // Exit manager for trailing stop and take profit exits
     let trailing_stop_pct = Decimal::try_from(args.trailing_stop_pct).unwrap_or(dec!(0));
     let take_profit_pct = args
         .take_profit_pct
         .map(|tp| Decimal::try_from(tp).unwrap_or(dec!(0)));
     let mut exit_manager = ExitManager::new(trailing_stop_pct, take_profit_pct, args.dry_run);
+    if let Some(tiers) = args.take_profit_tiers.clone() {
+        exit_manager = exit_manager.with_take_profit_tiers(tiers);
+    }
+    if let Some(secs_before_expiry) = args.time_stop_secs {
+        exit_manager = exit_manager.with_time_stop(TimeStop {
+            secs_before_expiry,
+            only_if_underwater: args.time_stop_if_underwater,
+        });
+    }
     // Live position tracker for settlement resolution (live mode only)
     // Key is (market_id, side) to support both YES and NO on the same market
-    let mut live_positions: HashMap<(Uuid, String), LivePosition> = HashMap::new();
+    let mut live_positions = LivePositions::new();
+
+    // Surface orders left resting by a previous run before trading resumes
+    if !args.dry_run {
+        if let Err(e) =
+            reconcile_orphan_orders(db.pool(), &assets, args.cancel_orphans, &mut cached_auth).await
+        {
+            warn!("[ORPHAN] Startup reconciliation failed: {}", e);
+        }
+    }
 
     // Connect to Binance WebSocket (Both = bookTicker for real-time + klines for history)
     let binance_client =
@@ -256,8 +635,25 @@ async fn main() -> Result<()> {
     let mut binance_ws = binance_client.connect_with_retry(5).await?;
     info!("Connected to Binance WebSocket");
 
+    // Dead-man's switch: cancel resting orders if the loop stops cycling
+    let heartbeat = Heartbeat::new();
+    let watchdog = (args.watchdog_timeout_secs > 0).then(|| {
+        let resting = order_manager.resting_orders();
+        spawn_watchdog(
+            heartbeat.clone(),
+            Duration::from_secs(args.watchdog_timeout_secs),
+            move |stalled_for| {
+                let resting = resting.clone();
+                async move { cancel_on_stall(&resting, stalled_for).await }
+            },
+        )
+    });
+
     // Main loop with graceful shutdown
     let mut last_cycle_time = std::time::Instant::now();
+    let min_cycle = Duration::from_millis(args.min_cycle_ms);
+    let max_cycle = Duration::from_millis(args.max_cycle_ms);
+    let mut next_cycle_after = max_cycle;
     let mut last_cleanup_time = std::time::Instant::now();
     let mut klines_since_heartbeat: u64 = 0;
 
@@ -282,12 +678,18 @@ async fn main() -> Result<()> {
                 }
                 if args.dry_run {
                     portfolio.print_summary();
+                    portfolio.print_timeframe_summary();
                     // Resolve expired positions during heartbeat
-                    portfolio.resolve_expired(db.pool(), &gamma).await;
+                    portfolio.resolve_expired(db.pool(), &gamma, settlement).await;
                 }
                 // Resolve expired live positions during heartbeat (live mode)
                 if !args.dry_run {
-                    resolve_live_settlements(&mut live_positions, &gamma, &mut metrics).await;
+                    for settled in
+                        resolve_live_settlements(&mut live_positions, db.pool(), &gamma, settlement)
+                            .await
+                    {
+                        metrics.record_live_settlement(settled);
+                    }
                 }
             }
             event_opt = binance_ws.next_event() => {
@@ -304,14 +706,34 @@ async fn main() -> Result<()> {
                                 // Add kline to buffer for historical data
                                 kline_buffer.add(kline);
                             }
+                            BinanceEvent::Trade(trade) => {
+                                kline_buffer.add_trade(&trade);
+                            }
                         }
 
-                        // Run trading cycle every 100ms (fast enough for bookTicker)
-                        if last_cycle_time.elapsed() >= Duration::from_millis(100) {
+                        // Run trading cycle at an adaptive cadence: faster as the
+                        // soonest tradeable market approaches expiry
+                        if last_cycle_time.elapsed() >= next_cycle_after {
                             last_cycle_time = std::time::Instant::now();
 
-                            run_cycle(
-                                &db,
+                            // Realized P&L: paper results in dry-run, exits and settlements live
+                            if daily_stop.is_enabled() {
+                                let realized = if args.dry_run {
+                                    portfolio.total_pnl
+                                } else {
+                                    metrics.realized_pnl()
+                                };
+                                if let Some(reason) = daily_stop.update(realized, Utc::now()) {
+                                    warn!(
+                                        "[DAILY STOP] {} reached (day P&L ${:.2}); no new positions until UTC midnight, open positions will still settle",
+                                        reason,
+                                        daily_stop.day_pnl()
+                                    );
+                                }
+                            }
+
+                            let soonest_expiry_secs = run_cycle(
+                                db.pool(),
                                 &assets,
                                 &timeframes,
                                 &args,
@@ -319,14 +741,24 @@ async fn main() -> Result<()> {
                                 &mut detector,
                                 &mut metrics,
                                 &mut portfolio,
+                                &mut paper_orders,
                                 &mut cached_auth,
+                                &mut throttle,
                                 &mut traded_positions,
                                 &mut order_manager,
                                 &mut exit_manager,
                                 &mut live_positions,
+                                &daily_stop,
+                                &mut sampler,
+                                &mut rotation,
                                 limit_price,
+                                chase_ceiling,
                                 position_size,
+                                max_shares,
                             ).await;
+                            next_cycle_after =
+                                cycle_interval(soonest_expiry_secs, min_cycle, max_cycle);
+                            heartbeat.beat();
                         }
 
                         // Cleanup every 5 minutes
@@ -345,6 +777,9 @@ async fn main() -> Result<()> {
                             ).await {
                                 let active_ids: Vec<Uuid> = markets.iter().map(|m| m.id).collect();
                                 detector.cleanup_expired(&active_ids);
+                                sampler.retain(|(_, id)| {
+                                    id.is_none_or(|id| active_ids.contains(&id))
+                                });
 
                                 // Also cleanup expired positions in exit manager
                                 if exit_manager.is_enabled() {
@@ -371,6 +806,10 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some(watchdog) = watchdog {
+        watchdog.abort();
+    }
+
     // Cancel all pending orders on shutdown (live trading only)
     if !args.dry_run {
         order_manager.cancel_all_pending().await;
@@ -380,25 +819,12 @@ async fn main() -> Result<()> {
     info!("=== FINAL STATUS ===");
     metrics.print_summary();
     if args.dry_run {
+        portfolio.settle_unresolved(args.unresolved_policy);
         portfolio.print_summary();
+        portfolio.print_timeframe_summary();
     }
-    if !args.dry_run && !live_positions.is_empty() {
-        let unresolved: Vec<_> = live_positions.values().filter(|p| !p.exited).collect();
-        if !unresolved.is_empty() {
-            info!(
-                "[SHUTDOWN] {} unresolved live positions (will settle on-chain):",
-                unresolved.len()
-            );
-            for pos in unresolved {
-                info!(
-                    "  {} {} | cost: ${:.2} | expires: {}",
-                    pos.side,
-                    pos.market_name,
-                    pos.cost,
-                    pos.end_time.format("%H:%M:%S UTC")
-                );
-            }
-        }
+    if !args.dry_run {
+        log_unresolved_live_positions(&live_positions);
     }
 
     binance_ws.close().await;
@@ -407,9 +833,12 @@ async fn main() -> Result<()> {
 }
 
 /// Run a single trading cycle.
+///
+/// Returns the soonest time-to-expiry (seconds) among tradeable markets, or
+/// None when there were none.
 #[allow(clippy::too_many_arguments)]
 async fn run_cycle(
-    db: &Database,
+    repo: &dyn MarketRepository,
     assets: &[String],
     timeframes: &[String],
     args: &Args,
@@ -417,14 +846,21 @@ async fn run_cycle(
     detector: &mut MispriceDetector,
     metrics: &mut Metrics,
     portfolio: &mut DryRunPortfolio,
+    paper_orders: &mut PaperOrders,
     cached_auth: &mut Option<CachedAuth>,
+    throttle: &mut TradeThrottle,
     traded_positions: &mut HashSet<(Uuid, String)>,
     order_manager: &mut OrderManager,
     exit_manager: &mut ExitManager,
-    live_positions: &mut HashMap<(Uuid, String), LivePosition>,
+    live_positions: &mut LivePositions,
+    daily_stop: &DailyPnlStop,
+    sampler: &mut SampledLogger<SampleKey>,
+    rotation: &mut MarketRotation,
     limit_price: Decimal,
+    chase_ceiling: Decimal,
     position_size: Decimal,
-) {
+    max_shares: Decimal,
+) -> Option<i64> {
     // Poll for completed cancel tasks and process results
     for result in order_manager.poll_completed() {
         if result.was_filled {
@@ -434,10 +870,24 @@ async fn run_cycle(
             );
             metrics.record_verified_fill();
 
+            // A partial fill holds only the matched shares
+            let held_shares = result.held_shares();
+            if let (Some(held), Some(ordered)) = (held_shares, result.shares) {
+                if held < ordered {
+                    info!(
+                        "[PARTIAL] {} {} filled {}/{} shares",
+                        result.market_name,
+                        result.side,
+                        fmt_shares(held),
+                        fmt_shares(ordered)
+                    );
+                }
+            }
+
             // Add to exit manager for trailing stop tracking (live mode)
             if exit_manager.is_enabled() && !args.dry_run {
                 if let (Some(token_id), Some(shares), Some(price)) =
-                    (&result.token_id, result.shares, result.price)
+                    (&result.token_id, held_shares, result.price)
                 {
                     exit_manager.add_position(
                         result.market_id,
@@ -458,7 +908,7 @@ async fn run_cycle(
             // Track in live positions for settlement resolution (live mode)
             if !args.dry_run {
                 if let (Some(shares), Some(price), Some(yes_token_id), Some(end_time)) = (
-                    result.shares,
+                    held_shares,
                     result.price,
                     &result.yes_token_id,
                     result.end_time,
@@ -469,25 +919,24 @@ async fn run_cycle(
                             result.market_name, result.side
                         );
                     } else {
-                        let cost = shares * price;
                         let key = (result.market_id, result.side.clone());
                         live_positions.insert(
                             key,
-                            LivePosition {
-                                yes_token_id: yes_token_id.clone(),
-                                side: result.side.clone(),
+                            LivePosition::new(
+                                yes_token_id.clone(),
+                                result.side.clone(),
                                 shares,
-                                cost,
+                                price,
                                 end_time,
-                                market_name: result.market_name.clone(),
-                                exited: false,
-                                resolution_retries: 0,
-                                last_retry_time: None,
-                            },
+                                result.market_name.clone(),
+                            ),
                         );
                         debug!(
-                            "[LIVE_TRACK] Tracking position: {} {} @ ${:.3} ({:.2} shares)",
-                            result.market_name, result.side, price, shares
+                            "[LIVE_TRACK] Tracking position: {} {} @ {} ({} shares)",
+                            result.market_name,
+                            result.side,
+                            fmt_price(price),
+                            fmt_shares(shares)
                         );
                     }
                 } else {
@@ -498,6 +947,62 @@ async fn run_cycle(
                 }
             }
         } else {
+            // Chase: re-place the unfilled order one step higher instead of
+            // giving up; a throttled chase gives up like any other cancel
+            let chase = match (
+                order_manager.next_chase_price(&result, chase_ceiling),
+                result.token_id.as_deref(),
+                result.shares,
+            ) {
+                (Some(new_price), Some(token_id), Some(shares)) => {
+                    match throttle.remaining(std::time::Instant::now()) {
+                        Some(wait) => {
+                            info!(
+                                "[THROTTLE] Not chasing {} {}: next order allowed in {}ms",
+                                result.market_name,
+                                result.side,
+                                wait.as_millis()
+                            );
+                            None
+                        }
+                        None => Some((new_price, token_id, shares)),
+                    }
+                }
+                _ => None,
+            };
+            if let Some((new_price, token_id, shares)) = chase {
+                info!(
+                    "[CHASE] Re-pricing {} {} {} -> {} (attempt {})",
+                    result.market_name,
+                    result.side,
+                    fmt_price(result.price.unwrap_or_default()),
+                    fmt_price(new_price),
+                    result.reprices + 1
+                );
+                metrics.record_reprice();
+                let chased = execute_trade(
+                    cached_auth,
+                    token_id,
+                    shares,
+                    new_price,
+                    &result.side,
+                    &result.market_name,
+                    max_shares,
+                )
+                .await;
+                throttle.record_order(std::time::Instant::now());
+                match chased {
+                    Ok(order_id) => {
+                        order_manager.track_reprice(order_id, &result, new_price);
+                        continue;
+                    }
+                    Err(e) => {
+                        error!("[CHASE] Re-price failed: {:#}", e);
+                        metrics.record_error();
+                    }
+                }
+            }
+
             // Order was cancelled, remove from traded_positions to allow retry
             debug!(
                 "[CANCELLED] Order for market {} {} was cancelled, allowing retry",
@@ -512,62 +1017,145 @@ async fn run_cycle(
     let expiry_seconds = args.max_expiry_minutes * 60;
     let min_expiry_seconds = args.min_expiry_minutes * 60;
 
-    let markets = match get_15m_updown_markets_with_orderbooks(
-        db.pool(),
-        args.max_orderbook_age,
-        assets,
-        expiry_seconds,
-        timeframes,
-    )
-    .await
+    let markets = match repo
+        .get_15m_updown_markets_with_orderbooks(
+            args.max_orderbook_age,
+            assets,
+            expiry_seconds,
+            timeframes,
+        )
+        .await
     {
         Ok(m) => m,
         Err(e) => {
             error!("Failed to query markets: {}", e);
             metrics.record_db_error();
-            return;
+            return None;
         }
     };
 
-    // Filter by minimum expiry
-    let now = Utc::now();
-    let markets: Vec<_> = markets
-        .into_iter()
-        .filter(|m| {
-            let secs_to_expiry = (m.end_time - now).num_seconds();
-            secs_to_expiry >= min_expiry_seconds
-        })
-        .collect();
-
-    if markets.is_empty() {
-        return;
+    // Mark open dry-run positions to the latest orderbook
+    if args.dry_run {
+        portfolio.update_last_prices(&markets);
     }
 
-    debug!("Found {} tradeable markets", markets.len());
+    // Fill resting paper orders the book has traded through; expired ones
+    // free the market/side for another attempt, like a cancelled live order
+    if !paper_orders.is_empty() {
+        let fills = paper_orders.check(&markets, Utc::now());
+        for position in fills.filled {
+            info!(
+                "[PAPER] {} {} filled @ {} ({} shares)",
+                position.side,
+                position.market_name,
+                fmt_price(position.entry_price),
+                fmt_shares(position.shares)
+            );
+            let key = (position.market_id, position.side.clone());
+            if !record_dry_run_fill(position, portfolio, exit_manager, metrics) {
+                // Refused for lack of balance: free the market/side for later
+                traded_positions.remove(&key);
+            }
+        }
+        for position in fills.expired {
+            debug!(
+                "[PAPER] {} {} not filled within timeout, allowing retry",
+                position.side, position.market_name
+            );
+            traded_positions.remove(&(position.market_id, position.side));
+            metrics.record_cancel();
+        }
+    }
 
-    // Check for trailing stop/take profit exits
+    // Check for trailing stop/take profit/time stop exits. Runs before the
+    // minimum-expiry filter so positions in markets about to expire are seen.
     if exit_manager.is_enabled() && exit_manager.position_count() > 0 {
         let exits = exit_manager.check_exits(&markets, cached_auth).await;
         for exit in exits {
             metrics.record_exit(&exit);
             if args.dry_run && exit.success {
-                portfolio.close_position(exit.market_id, exit.exit_price, exit.pnl);
+                if exit.scaled_out {
+                    portfolio.close_partial(
+                        exit.market_id,
+                        &exit.side,
+                        exit.shares,
+                        exit.exit_price,
+                    );
+                } else {
+                    portfolio.close_position(exit.market_id, exit.exit_price, exit.pnl);
+                }
             }
-            // Mark live position as exited so settlement doesn't double-count
+            // Mark live position as exited so settlement doesn't double-count;
+            // after a partial exit only the remaining shares settle
             if !args.dry_run && exit.success {
                 let key = (exit.market_id, exit.side.clone());
                 if let Some(pos) = live_positions.get_mut(&key) {
-                    pos.exited = true;
-                    debug!(
-                        "[LIVE_TRACK] Marked {} {} as exited (trailing stop/take profit)",
-                        pos.market_name, pos.side
-                    );
+                    if exit.remaining_shares > Decimal::ZERO {
+                        pos.reduce(exit.shares);
+                        debug!(
+                            "[LIVE_TRACK] {} {} scaled out, {} shares left to settle",
+                            pos.market_name, pos.side, pos.shares
+                        );
+                    } else {
+                        pos.exited = true;
+                        debug!(
+                            "[LIVE_TRACK] Marked {} {} as exited (exit manager)",
+                            pos.market_name, pos.side
+                        );
+                    }
                 }
             }
         }
     }
 
-    // Process each market
+    // Filter by minimum expiry
+    let now = Utc::now();
+    let markets: Vec<_> = markets
+        .into_iter()
+        .filter(|m| {
+            let secs_to_expiry = (m.end_time - now).num_seconds();
+            secs_to_expiry >= min_expiry_seconds
+        })
+        .collect();
+
+    if markets.is_empty() {
+        return None;
+    }
+    let soonest_expiry_secs = markets
+        .iter()
+        .map(|m| (m.end_time - now).num_seconds())
+        .min();
+
+    if let Some(n) = sampler.sample(("tradeable", None)) {
+        debug!("Found {} tradeable markets (cycle {})", markets.len(), n);
+    }
+
+    let mut markets = markets;
+    // A malformed depth snapshot can pass for a thin book; never trade on one
+    let rejected = retain_valid_depth(&mut markets);
+    for (name, e) in &rejected {
+        warn!("[SKIP] {} - invalid depth snapshot: {}", name, e);
+    }
+    metrics.record_invalid_depth(rejected.len());
+
+    // Bound per-cycle work: most urgent markets first, the rest rotate
+    // through the remaining slots so none is deferred indefinitely
+    let deferred = rotation.select(&mut markets, args.max_markets_per_cycle);
+    if deferred > 0 {
+        if let Some(n) = sampler.sample(("cap", None)) {
+            debug!(
+                "[CAP] Processing {} markets this cycle, {} deferred ({} capped cycles)",
+                markets.len(),
+                deferred,
+                n
+            );
+        }
+        metrics.record_deferred(deferred);
+    }
+
+    // Evaluate each market; detector state is updated in order, orders wait
+    // until every signal has been priced
+    let mut signals = Vec::new();
     for market in &markets {
         // Calculate market start time based on timeframe
         let timeframe_minutes: i64 = match market.timeframe.as_str() {
@@ -581,7 +1169,7 @@ async fn run_cycle(
         let start_time = market.end_time - chrono::Duration::minutes(timeframe_minutes);
 
         // Get Binance symbol for this asset
-        let binance_symbol = match asset_to_binance_symbol(&market.asset) {
+        let binance_symbol = match binance_symbol(&market.asset) {
             Some(s) => s,
             None => continue,
         };
@@ -597,11 +1185,14 @@ async fn run_cycle(
             Some(s) => s,
             None => {
                 // No kline data for market start time yet
-                debug!(
-                    "No kline data for {} at start time {}",
-                    market.name,
-                    start_time.format("%H:%M:%S")
-                );
+                if let Some(n) = sampler.sample(("no-kline", Some(market.id))) {
+                    debug!(
+                        "No kline data for {} at start time {} ({} cycles)",
+                        market.name,
+                        start_time.format("%H:%M:%S"),
+                        n
+                    );
+                }
                 continue;
             }
         };
@@ -609,15 +1200,71 @@ async fn run_cycle(
         // Store open price before mutable borrow
         let open_price = state.open_price;
 
-        // Get current price from kline buffer
-        let current_price = match kline_buffer.get_latest_close(binance_symbol) {
-            Some(p) => p,
-            None => continue,
-        };
+        // Get current price (and when it was received) from kline buffer
+        let (current_price, price_at) =
+            match kline_buffer.get_latest_close_with_time(binance_symbol) {
+                Some(p) => p,
+                None => continue,
+            };
+
+        // Check for direction flip (or one deferred from an earlier cycle)
+        let retrying_deferred = detector.has_deferred_flip(&market.id);
+        if let Some((flip_type, side)) =
+            detector.update_and_check_flip(&market.id, current_price, price_at)
+        {
+            if !retrying_deferred {
+                metrics.record_flip(&market.asset);
+            }
+
+            // Respect the configured direction bias
+            if !args.side_filter.allows(side) {
+                debug!(
+                    "[BIAS] {} {} flip suppressed by side filter ({})",
+                    market.name, side, args.side_filter
+                );
+                metrics.record_bias_skip();
+                continue;
+            }
+
+            // Daily P&L bound reached: keep tracking, but open nothing new
+            if !daily_stop.allows_entry() {
+                debug!(
+                    "[DAILY STOP] {} {} flip not traded ({} reached)",
+                    market.name,
+                    side,
+                    daily_stop.halted().map(|r| r.as_str()).unwrap_or_default()
+                );
+                continue;
+            }
+
+            // The snapshot must not predate the price tick that produced the
+            // flip; hold the flip and re-check it against the next snapshot
+            if !detector.snapshot_lag_allows(market) {
+                detector.defer_flip(&market.id, (flip_type, side));
+                info!(
+                    "[DEFER] {} {} snapshot is {}ms behind the price tick (max {}ms), re-checking next cycle",
+                    market.name,
+                    side,
+                    detector
+                        .snapshot_lag(market)
+                        .map(|lag| lag.num_milliseconds())
+                        .unwrap_or_default(),
+                    args.max_snapshot_lag_ms.unwrap_or_default()
+                );
+                metrics.record_snapshot_lag_skip();
+                continue;
+            }
 
-        // Check for direction flip
-        if let Some((flip_type, side)) = detector.update_and_check_flip(&market.id, current_price) {
-            metrics.record_flip(&market.asset);
+            // Require the book to lean toward the side we are buying
+            if !detector.imbalance_allows(market, side) {
+                debug!(
+                    "[SKIP] {} {} orderbook imbalance below {:.2}x",
+                    market.name,
+                    side,
+                    args.min_imbalance.unwrap_or_default()
+                );
+                continue;
+            }
 
             // Check if already traded this side on this market
             if traded_positions.contains(&(market.id, side.to_string())) {
@@ -625,10 +1272,20 @@ async fn run_cycle(
                 continue;
             }
 
-            // Get token ID, best ask, and orderbook for the side we want to buy
-            let (token_id, best_ask, orderbook) = match side {
-                "YES" => (&market.yes_token_id, market.yes_best_ask, &market.yes_asks),
-                "NO" => (&market.no_token_id, market.no_best_ask, &market.no_asks),
+            // Get token ID, top of book, and orderbook for the side we want to buy
+            let (token_id, best_ask, best_bid, orderbook) = match side {
+                "YES" => (
+                    &market.yes_token_id,
+                    market.yes_best_ask,
+                    market.yes_best_bid,
+                    &market.yes_asks,
+                ),
+                "NO" => (
+                    &market.no_token_id,
+                    market.no_best_ask,
+                    market.no_best_bid,
+                    &market.no_asks,
+                ),
                 _ => continue,
             };
 
@@ -648,22 +1305,65 @@ async fn run_cycle(
                 }
             };
 
+            // Thin market: not enough USDC on the ask side to be worth trading
+            if let Some(min_liquidity) = args
+                .min_liquidity_usdc
+                .and_then(|m| Decimal::try_from(m).ok())
+            {
+                match market.ask_liquidity_usdc(side) {
+                    Some(liquidity) if liquidity >= min_liquidity => {}
+                    Some(liquidity) => {
+                        info!(
+                            "[SKIP] {} {} ask liquidity ${:.2} < min ${:.2}",
+                            market.name, side, liquidity, min_liquidity
+                        );
+                        continue;
+                    }
+                    None => {
+                        info!(
+                            "[SKIP] {} {} no depth data, cannot check min liquidity ${:.2}",
+                            market.name, side, min_liquidity
+                        );
+                        continue;
+                    }
+                }
+            }
+
             // Calculate shares at limit price
             let shares = (position_size / limit_price).round_dp(2);
 
             // Ensure shares is within limits
-            if shares > dec!(99.99) {
-                warn!("Shares {} exceeds max 99.99", shares);
+            if shares > max_shares {
+                warn!("Shares {} exceeds max {}", shares, max_shares);
                 continue;
             }
 
             // Calculate realistic fill price using orderbook depth (20% slippage fallback)
-            let fill_estimate = calculate_fill_price_with_slippage(
-                orderbook.as_ref(),
-                best_ask,
-                shares,
-                dec!(20), // 20% slippage fallback if orderbook unavailable
-            );
+            let fill_estimate = match args.depth_tick_size.and_then(|t| Decimal::try_from(t).ok()) {
+                Some(tick) => {
+                    let depth = orderbook.as_ref().and_then(depth_levels).map(|levels| {
+                        aggregate_depth_to_ticks(
+                            &levels,
+                            tick,
+                            RoundingStrategy::ToPositiveInfinity,
+                        )
+                    });
+                    calculate_fill_price_from_levels(
+                        depth.as_deref(),
+                        best_ask,
+                        shares,
+                        dec!(20),
+                        args.fill_model,
+                    )
+                }
+                None => calculate_fill_price_with_slippage(
+                    orderbook.as_ref(),
+                    best_ask,
+                    shares,
+                    dec!(20), // 20% slippage fallback if orderbook unavailable
+                    args.fill_model,
+                ),
+            };
 
             // Check if we can fully fill at acceptable price
             if !fill_estimate.fully_filled {
@@ -674,6 +1374,26 @@ async fn run_cycle(
                 continue;
             }
 
+            // Thin book: skip on excessive slippage even if still under the limit
+            if let Some(max_slippage) = args
+                .max_slippage_pct
+                .and_then(|m| Decimal::try_from(m).ok())
+            {
+                if fill_estimate.exceeds_slippage_cap(best_ask, max_slippage) {
+                    info!(
+                        "[SKIP] {} {} slippage {:.2}% > max {}% (eff ${:.3} vs ask ${:.3})",
+                        market.name,
+                        side,
+                        fill_estimate.slippage_pct(best_ask),
+                        max_slippage,
+                        fill_estimate.effective_price,
+                        best_ask
+                    );
+                    metrics.record_slippage_skip();
+                    continue;
+                }
+            }
+
             // Check if effective fill price exceeds our limit
             if fill_estimate.effective_price > limit_price {
                 debug!(
@@ -683,114 +1403,291 @@ async fn run_cycle(
                 continue;
             }
 
-            info!(
-                "[FLIP] {} {} -> {} LIMIT @ ${:.2} ({:.2} shares) | Open: ${}, Current: ${} | Best ask: ${:.3}, Eff fill: ${:.3}",
-                flip_type, market.name, side, limit_price, shares, open_price, current_price, best_ask, fill_estimate.effective_price
+            signals.push(Signal {
+                market,
+                flip_type,
+                side,
+                token_id,
+                best_ask,
+                best_bid,
+                shares,
+                effective_price: fill_estimate.effective_price,
+                open_price,
+                current_price,
+            });
+        }
+    }
+
+    // Stale snapshot guard: confirm each ask against the live book, fetching
+    // up to --max-concurrency books at once
+    let max_divergence = Decimal::try_from(args.max_price_divergence).unwrap_or(Decimal::ZERO);
+    let divergence_skips = Cell::new(0u32);
+    let signals = check_signals_concurrently(
+        signals,
+        |signal| (signal.market.id, signal.side.to_string()),
+        traded_positions,
+        args.max_concurrency,
+        |signal| {
+            let (market_name, side, token_id, best_ask) = (
+                signal.market.name.as_str(),
+                signal.side,
+                signal.token_id,
+                signal.best_ask,
             );
+            let divergence_skips = &divergence_skips;
+            async move {
+                if !args.verify_live_price {
+                    return true;
+                }
+                match fetch_live_best_ask(token_id).await {
+                    Some(live_ask)
+                        if price_divergence_exceeded(best_ask, live_ask, max_divergence) =>
+                    {
+                        info!(
+                            "[SKIP] {} {} live ask ${} > DB ask ${} + ${}",
+                            market_name, side, live_ask, best_ask, max_divergence
+                        );
+                        divergence_skips.set(divergence_skips.get() + 1);
+                        false
+                    }
+                    Some(_) => true,
+                    None => {
+                        info!(
+                            "[SKIP] {} {} live ask unavailable, cannot verify DB price",
+                            market_name, side
+                        );
+                        divergence_skips.set(divergence_skips.get() + 1);
+                        false
+                    }
+                }
+            }
+        },
+    )
+    .await;
+    for _ in 0..divergence_skips.get() {
+        metrics.record_divergence_skip();
+    }
 
-            if args.dry_run {
-                // DRY RUN - track in portfolio with realistic fill price
-                let effective_price = fill_estimate.effective_price;
-                let cost = shares * effective_price;
-                info!(
-                    "[DRY RUN] {} {:.2} shares @ ${:.3} (eff), cost: ${:.2}",
-                    side, shares, effective_price, cost
-                );
+    // Place orders one at a time, in market priority order
+    for signal in signals {
+        let Signal {
+            market,
+            flip_type,
+            side,
+            token_id,
+            best_ask,
+            best_bid,
+            shares,
+            effective_price,
+            open_price,
+            current_price,
+        } = signal;
+
+        // Take at the limit, or post inside the spread and let the order
+        // manager cancel it if nobody trades against it. Passive prices step
+        // by the market's own tick (dry-run has no CLOB session to ask)
+        let entry_price = match args.entry_offset {
+            EntryOffset::Ask => limit_price,
+            offset => {
+                let tick_size = if args.dry_run {
+                    DEFAULT_TICK_SIZE
+                } else {
+                    fetch_tick_size(cached_auth, token_id).await
+                };
+                offset.price(best_bid, best_ask, tick_size)
+            }
+        };
 
-                portfolio.add_position(SimulatedPosition {
-                    market_id: market.id,
-                    condition_id: market.condition_id.clone(),
-                    market_name: market.name.clone(),
-                    market_type: market.market_type.clone(),
-                    asset: market.asset.clone(),
-                    timeframe: market.timeframe.clone(),
-                    yes_token_id: market.yes_token_id.clone(),
-                    no_token_id: market.no_token_id.clone(),
-                    side: side.to_string(),
-                    shares,
-                    entry_price: limit_price,
-                    best_ask_price: best_ask,
-                    effective_fill_price: effective_price,
-                    cost,
-                    end_time: market.end_time,
-                    created_at: Utc::now(),
-                    resolution_retries: 0,
-                    last_retry_time: None,
-                });
+        info!(
+            "[FLIP] {} {} -> {} LIMIT @ {} ({} shares) | Open: ${}, Current: ${} | Best ask: ${:.3}, Eff fill: ${:.3}",
+            flip_type, market.name, side, fmt_price(entry_price), fmt_shares(shares), open_price, current_price, best_ask, effective_price
+        );
 
-                // Also add to exit manager for trailing stop tracking
-                if exit_manager.is_enabled() {
-                    let token_id_for_exit = match side {
-                        "YES" => &market.yes_token_id,
-                        "NO" => &market.no_token_id,
-                        _ => token_id,
-                    };
-                    exit_manager.add_position(
-                        market.id,
-                        market.name.clone(),
-                        token_id_for_exit.clone(),
-                        side.to_string(),
-                        shares,
-                        effective_price, // Use effective fill price as entry
-                    );
-                }
+        let mut intent = TradeIntent {
+            market_id: market.id,
+            token_id: token_id.to_string(),
+            side: side.to_string(),
+            shares,
+            limit_price: entry_price,
+            effective_price,
+            strategy_tag: STRATEGY_TAG.to_string(),
+            mode: TradeMode::from_dry_run(args.dry_run),
+        };
+        info!("[INTENT] {}", intent);
+
+        if args.dry_run {
+            // DRY RUN - track in portfolio with realistic fill price
+            let cost = shares * effective_price;
+            let position = SimulatedPosition {
+                market_id: market.id,
+                condition_id: market.condition_id.clone(),
+                market_name: market.name.clone(),
+                market_type: market.market_type.clone(),
+                asset: market.asset.clone(),
+                timeframe: market.timeframe.clone(),
+                yes_token_id: market.yes_token_id.clone(),
+                no_token_id: market.no_token_id.clone(),
+                side: side.to_string(),
+                shares,
+                entry_price,
+                best_ask_price: best_ask,
+                effective_fill_price: effective_price,
+                cost,
+                last_price: best_ask,
+                end_time: market.end_time,
+                created_at: Utc::now(),
+                resolution_retries: 0,
+                last_retry_time: None,
+            };
 
-                traded_positions.insert((market.id, side.to_string()));
-                detector.mark_traded(&market.id);
-                metrics.record_trade(&market.asset, side);
+            if args.paper_fill == PaperFillMode::Realistic {
+                info!(
+                    "[DRY RUN] {} {:.2} shares resting @ ${:.3} until the ask reaches it",
+                    side, shares, entry_price
+                );
+                paper_orders.place(position);
+                record_trade_audit(repo, &intent, TradeOutcome::Placed).await;
             } else {
-                // Check if we already have a pending order for this market/side
-                if order_manager.has_pending_order(&market.id, side) {
-                    debug!(
-                        "[SKIP] Already have pending order for {} {}",
-                        market.name, side
-                    );
+                info!(
+                    "[DRY RUN] {} {:.2} shares @ ${:.3} (eff), cost: ${:.2}",
+                    side, shares, effective_price, cost
+                );
+                if !record_dry_run_fill(position, portfolio, exit_manager, metrics) {
+                    record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
                     continue;
                 }
+                record_trade_audit(repo, &intent, TradeOutcome::Filled).await;
+            }
 
-                // REAL TRADE - LIMIT ORDER at specified price
-                match execute_trade(
-                    cached_auth,
-                    token_id,
-                    shares,
-                    limit_price,
+            traded_positions.insert((market.id, side.to_string()));
+            detector.mark_traded(&market.id);
+        } else {
+            // Check if we already have a pending order for this market/side
+            if order_manager.has_pending_order(&market.id, side) {
+                debug!(
+                    "[SKIP] Already have pending order for {} {}",
+                    market.name, side
+                );
+                record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
+                continue;
+            }
+
+            if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
+                info!(
+                    "[THROTTLE] Skipping {} {}: next order allowed in {}ms",
+                    side,
+                    market.name,
+                    wait.as_millis()
+                );
+                record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
+                continue;
+            }
+
+            // REAL TRADE - LIMIT ORDER at specified price, re-attempted
+            // once at the fresh ask if a taking order found the book moved past it
+            let mut order_price = entry_price;
+            let mut result = execute_trade(
+                cached_auth,
+                token_id,
+                shares,
+                order_price,
+                side,
+                &market.name,
+                max_shares,
+            )
+            .await;
+            throttle.record_order(std::time::Instant::now());
+            if is_price_movement(&result) && !args.entry_offset.is_passive() {
+                let retry = fresh_retry_ask(
+                    repo,
+                    market,
                     side,
-                    &market.name,
+                    order_price,
+                    chase_ceiling,
+                    args.max_orderbook_age,
+                    expiry_seconds,
                 )
                 .await
-                {
-                    Ok(order_id) => {
+                .unwrap_or_else(|e| {
+                    error!("Failed to refresh {}: {}", market.name, e);
+                    metrics.record_db_error();
+                    None
+                });
+                if let Some(fresh_ask) = retry {
+                    if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
                         info!(
-                            "[SUCCESS] LIMIT order {} @ ${:.2} (order_id: {})",
-                            side, limit_price, order_id
+                            "[THROTTLE] Not retrying {} {} at {}: next order allowed in {}ms",
+                            side,
+                            market.name,
+                            fmt_price(fresh_ask),
+                            wait.as_millis()
                         );
-
-                        // Track order - don't mark as fully traded until fill verified
-                        traded_positions.insert((market.id, side.to_string()));
-                        detector.mark_traded(&market.id);
-                        metrics.record_trade(&market.asset, side);
-
-                        // Track order with market info for exit manager and settlement
-                        order_manager.track_order_with_market_info(
-                            order_id,
-                            market.id,
-                            market.name.clone(),
-                            side.to_string(),
-                            Some(token_id.clone()),
-                            Some(shares),
-                            Some(limit_price),
-                            Some(market.condition_id.clone()),
-                            Some(market.yes_token_id.clone()),
-                            Some(market.end_time),
-                            Some(market.asset.clone()),
-                        );
-                    }
-                    Err(e) => {
-                        error!("[FAILED] Trade execution: {:#}", e);
-                        metrics.record_error();
+                    } else {
+                        metrics.record_price_retry();
+                        order_price = fresh_ask;
+                        result = execute_trade(
+                            cached_auth,
+                            token_id,
+                            shares,
+                            order_price,
+                            side,
+                            &market.name,
+                            max_shares,
+                        )
+                        .await;
+                        throttle.record_order(std::time::Instant::now());
                     }
                 }
             }
+
+            intent.limit_price = order_price;
+            let outcome = if result.is_ok() {
+                TradeOutcome::Placed
+            } else {
+                TradeOutcome::Rejected
+            };
+            record_trade_audit(repo, &intent, outcome).await;
+
+            match result {
+                Ok(order_id) => {
+                    info!(
+                        market = %market.name,
+                        side,
+                        shares = %fmt_shares(shares),
+                        price = %fmt_price(order_price),
+                        order_id = %order_id,
+                        "[SUCCESS] LIMIT order placed"
+                    );
+
+                    // Track order - don't mark as fully traded until fill verified
+                    traded_positions.insert((market.id, side.to_string()));
+                    detector.mark_traded(&market.id);
+                    metrics.record_trade(&market.asset, side);
+
+                    // Track order with market info for exit manager and settlement
+                    order_manager.track_order_with_market_info(
+                        order_id,
+                        market.id,
+                        market.name.clone(),
+                        side.to_string(),
+                        Some(token_id.to_string()),
+                        Some(shares),
+                        Some(order_price),
+                        Some(market.condition_id.clone()),
+                        Some(market.yes_token_id.clone()),
+                        Some(market.end_time),
+                        Some(market.asset.clone()),
+                    );
+                }
+                Err(e) => {
+                    error!(
+                        "[FAILED] Trade execution ({}): {:#}",
+                        OrderRejection::classify(&format!("{:#}", e)),
+                        e
+                    );
+                    metrics.record_error();
+                }
+            }
         }
     }
 
@@ -803,156 +1700,45 @@ async fn run_cycle(
             before - traded_positions.len()
         );
     }
+
+    soonest_expiry_secs
 }
 
-/// Resolve expired live positions by querying Gamma API for settlement outcomes.
+/// Book a filled dry-run entry in the portfolio, exit tracking and metrics.
 ///
-/// Only processes positions that:
-/// - Were NOT already exited via trailing stop/take profit
-/// - Have expired (end_time + 60s buffer < now)
-/// - Are within retry limits
-async fn resolve_live_settlements(
-    live_positions: &mut HashMap<(Uuid, String), LivePosition>,
-    gamma: &GammaClient,
+/// Returns false if the portfolio refused it for lack of virtual balance.
+fn record_dry_run_fill(
+    position: SimulatedPosition,
+    portfolio: &mut DryRunPortfolio,
+    exit_manager: &mut ExitManager,
     metrics: &mut Metrics,
-) {
-    let now = Utc::now();
-    let expired_cutoff = now - chrono::Duration::seconds(60);
-
-    // Collect expired unexited position keys to process
-    let expired_keys: Vec<(Uuid, String)> = live_positions
-        .iter()
-        .filter(|(_, p)| !p.exited && p.end_time < expired_cutoff)
-        .map(|(k, _)| k.clone())
-        .collect();
-
-    if expired_keys.is_empty() {
-        // Still clean up stale exited positions
-        live_positions.retain(|_, p| !(p.exited && p.end_time < expired_cutoff));
-        return;
+) -> bool {
+    if !portfolio.add_position(position.clone()) {
+        metrics.record_balance_skip();
+        return false;
     }
-
-    // Clean up exited positions that have expired (no longer needed)
-    live_positions.retain(|_, p| !(p.exited && p.end_time < expired_cutoff));
-
-    let mut api_calls = 0u32;
-
-    for key in expired_keys {
-        let pos = match live_positions.get(&key) {
-            Some(p) => p,
-            None => continue,
+    if exit_manager.is_enabled() {
+        let token_id = match position.side.as_str() {
+            "YES" => &position.yes_token_id,
+            _ => &position.no_token_id,
         };
+        exit_manager.add_position(
+            position.market_id,
+            position.market_name.clone(),
+            token_id.clone(),
+            position.side.clone(),
+            position.shares,
+            position.effective_fill_price, // Use effective fill price as entry
+        );
+    }
+    metrics.record_trade(&position.asset, &position.side);
+    true
+}
 
-        // Check backoff
-        let backoff_secs = live_resolution_backoff_secs(pos.resolution_retries);
-        if let Some(last_retry) = pos.last_retry_time {
-            let elapsed = (now - last_retry).num_seconds();
-            if elapsed < backoff_secs {
-                continue;
-            }
-        }
-
-        // Rate limit API calls (max 2 per second)
-        if api_calls > 0 {
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
-        api_calls += 1;
-
-        match gamma.fetch_market_resolution(&pos.yes_token_id).await {
-            Ok(Some(winning_side)) => {
-                let winning_side = winning_side.to_uppercase();
-                if winning_side != "YES" && winning_side != "NO" {
-                    // Treat as unresolved - increment retries to avoid infinite loop
-                    let pos = live_positions.get_mut(&key).unwrap();
-                    pos.resolution_retries += 1;
-                    pos.last_retry_time = Some(now);
-                    warn!(
-                        "[SETTLEMENT] Invalid winning_side '{}' for {} (retry {}/{})",
-                        winning_side,
-                        pos.market_name,
-                        pos.resolution_retries,
-                        MAX_LIVE_RESOLUTION_RETRIES
-                    );
-                    if pos.resolution_retries >= MAX_LIVE_RESOLUTION_RETRIES {
-                        warn!(
-                            "[SETTLEMENT] ⚠️ EXPIRED: {} - max retries exceeded with invalid resolution, treating as loss (-${:.2})",
-                            pos.market_name, pos.cost
-                        );
-                        metrics.record_settlement(-pos.cost, false);
-                        live_positions.remove(&key);
-                    }
-                    continue;
-                }
-
-                let we_won = pos.side.to_uppercase() == winning_side;
-                let pnl = if we_won {
-                    // Win: payout = shares ($1 per share), profit = shares - cost
-                    pos.shares - pos.cost
-                } else {
-                    // Loss: lose entire cost
-                    -pos.cost
-                };
-
-                metrics.record_settlement(pnl, we_won);
-
-                if we_won {
-                    info!(
-                        "[SETTLEMENT] ✅ WIN: {} {} -> +${:.2} (resolved: {})",
-                        pos.side, pos.market_name, pnl, winning_side
-                    );
-                } else {
-                    info!(
-                        "[SETTLEMENT] ❌ LOSS: {} {} -> -${:.2} (resolved: {})",
-                        pos.side, pos.market_name, pos.cost, winning_side
-                    );
-                }
-
-                live_positions.remove(&key);
-            }
-            Ok(None) => {
-                // Not resolved yet, retry next heartbeat
-                let pos = live_positions.get_mut(&key).unwrap();
-                pos.resolution_retries += 1;
-                pos.last_retry_time = Some(now);
-
-                if pos.resolution_retries >= MAX_LIVE_RESOLUTION_RETRIES {
-                    warn!(
-                        "[SETTLEMENT] ⚠️ EXPIRED: {} - max retries ({}) exceeded, treating as loss (-${:.2})",
-                        pos.market_name, MAX_LIVE_RESOLUTION_RETRIES, pos.cost
-                    );
-                    metrics.record_settlement(-pos.cost, false);
-                    live_positions.remove(&key);
-                } else {
-                    let next_backoff = live_resolution_backoff_secs(pos.resolution_retries);
-                    debug!(
-                        "[SETTLEMENT] {} not yet resolved (retry {}/{}, next in {}s)",
-                        pos.market_name,
-                        pos.resolution_retries,
-                        MAX_LIVE_RESOLUTION_RETRIES,
-                        next_backoff
-                    );
-                }
-            }
-            Err(e) => {
-                // API error, retry with backoff
-                let pos = live_positions.get_mut(&key).unwrap();
-                pos.resolution_retries += 1;
-                pos.last_retry_time = Some(now);
-
-                if pos.resolution_retries >= MAX_LIVE_RESOLUTION_RETRIES {
-                    warn!(
-                        "[SETTLEMENT] ⚠️ EXPIRED: {} - max retries ({}) exceeded after API errors, treating as loss (-${:.2})",
-                        pos.market_name, MAX_LIVE_RESOLUTION_RETRIES, pos.cost
-                    );
-                    metrics.record_settlement(-pos.cost, false);
-                    live_positions.remove(&key);
-                } else {
-                    warn!(
-                        "[SETTLEMENT] Failed to fetch resolution for {} (retry {}/{}): {}",
-                        pos.market_name, pos.resolution_retries, MAX_LIVE_RESOLUTION_RETRIES, e
-                    );
-                }
-            }
-        }
+/// Whether an order attempt failed because the book moved past our price.
+fn is_price_movement(result: &Result<String>) -> bool {
+    match result {
+        Err(e) => OrderRejection::classify(&format!("{:#}", e)).is_price_movement(),
+        Ok(_) => false,
     }
 }
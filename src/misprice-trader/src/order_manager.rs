@@ -3,16 +3,20 @@
 //! Solves two issues:
 //! 1. Fire-and-forget cancel tasks are not tracked (orphan orders on crash)
 //! 2. Orders assumed successful without verifying fills
+//!
+//! Optionally "chases" unfilled limit orders: after a confirmed zero-fill cancel,
+//! the caller can re-place the order one price step higher, up to a ceiling.
 
 use std::collections::HashMap;
 use std::time::Duration;
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use tokio::task::JoinSet;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use common::{cancel_order_standalone, query_order_fill_standalone};
+use common::{cancel_order_standalone, query_order_fill_standalone, RestingOrders};
 use rust_decimal_macros::dec;
 
 /// Status of a pending order.
@@ -48,6 +52,8 @@ pub struct PendingOrder {
     pub yes_token_id: Option<String>,
     pub end_time: Option<DateTime<Utc>>,
     pub asset: Option<String>,
+    /// Number of times this order has been re-priced (0 for the original order)
+    pub reprices: u32,
 }
 
 /// Result of a cancel attempt, sent back from the spawned task.
@@ -63,13 +69,37 @@ pub struct CancelResult {
     pub error_msg: Option<String>,
     // Market info for exit manager (live trading)
     pub token_id: Option<String>,
+    /// Shares the order was placed for
     pub shares: Option<rust_decimal::Decimal>,
+    /// Shares actually matched, when the order status could be queried
+    pub filled_shares: Option<rust_decimal::Decimal>,
     pub price: Option<rust_decimal::Decimal>,
     // Market info for settlement tracking (live trading)
     pub condition_id: Option<String>,
     pub yes_token_id: Option<String>,
     pub end_time: Option<DateTime<Utc>>,
     pub asset: Option<String>,
+    /// Re-prices already made before this order
+    pub reprices: u32,
+}
+
+impl CancelResult {
+    /// Shares held after a fill: the matched size when known, otherwise the
+    /// ordered size (the fill was only inferred from the cancel error).
+    pub fn held_shares(&self) -> Option<Decimal> {
+        self.filled_shares
+            .filter(|filled| *filled > Decimal::ZERO)
+            .or(self.shares)
+    }
+}
+
+/// Re-pricing settings for unfilled limit orders.
+#[derive(Debug, Clone, Copy)]
+pub struct ChaseConfig {
+    /// Maximum re-prices per original order
+    pub max_reprices: u32,
+    /// Amount added to the price on each re-price
+    pub price_step: Decimal,
 }
 
 /// Manages pending orders and their auto-cancel tasks.
@@ -80,6 +110,10 @@ pub struct OrderManager {
     cancel_tasks: JoinSet<CancelResult>,
     /// Default cancel timeout in seconds
     cancel_timeout_secs: u64,
+    /// Re-price unfilled orders instead of abandoning them (disabled if None)
+    chase: Option<ChaseConfig>,
+    /// Resting order IDs, shared with the watchdog
+    resting: RestingOrders,
 }
 
 impl OrderManager {
@@ -92,9 +126,74 @@ impl OrderManager {
             pending_orders: HashMap::new(),
             cancel_tasks: JoinSet::new(),
             cancel_timeout_secs,
+            chase: None,
+            resting: RestingOrders::new(),
         }
     }
 
+    /// Handle to the resting order IDs, for cancelling them from outside the
+    /// main loop (e.g. the watchdog) when the manager itself is unreachable.
+    pub fn resting_orders(&self) -> RestingOrders {
+        self.resting.clone()
+    }
+
+    /// Enable chasing: unfilled orders may be re-placed at `price + price_step`
+    /// up to `max_reprices` times. A zero count or step leaves chasing disabled.
+    pub fn enable_chase(&mut self, max_reprices: u32, price_step: Decimal) {
+        if max_reprices == 0 || price_step <= Decimal::ZERO {
+            self.chase = None;
+            return;
+        }
+        self.chase = Some(ChaseConfig {
+            max_reprices,
+            price_step,
+        });
+    }
+
+    /// Price to re-place a cancelled order at, or None if it should be abandoned.
+    ///
+    /// Only orders confirmed cancelled with no fill are chased. The new price is
+    /// capped at `ceiling`; an order already at the ceiling is not re-priced.
+    pub fn next_chase_price(&self, result: &CancelResult, ceiling: Decimal) -> Option<Decimal> {
+        let chase = self.chase?;
+        if !result.success || result.was_filled || result.reprices >= chase.max_reprices {
+            return None;
+        }
+        if result.end_time.is_some_and(|end| end <= Utc::now()) {
+            return None;
+        }
+
+        let price = result.price?;
+        if price >= ceiling {
+            return None;
+        }
+        Some((price + chase.price_step).min(ceiling))
+    }
+
+    /// Track the re-placed order for a chased `CancelResult`, carrying over its
+    /// market info and incrementing the re-price count.
+    pub fn track_reprice(
+        &mut self,
+        order_id: String,
+        previous: &CancelResult,
+        new_price: Decimal,
+    ) -> bool {
+        self.track_order_inner(
+            order_id,
+            previous.market_id,
+            previous.market_name.clone(),
+            previous.side.clone(),
+            previous.token_id.clone(),
+            previous.shares,
+            Some(new_price),
+            previous.condition_id.clone(),
+            previous.yes_token_id.clone(),
+            previous.end_time,
+            previous.asset.clone(),
+            previous.reprices + 1,
+        )
+    }
+
     /// Track a new order and schedule its auto-cancel.
     ///
     /// Returns true if order was added, false if order_id already exists.
@@ -138,6 +237,38 @@ impl OrderManager {
         yes_token_id: Option<String>,
         end_time: Option<DateTime<Utc>>,
         asset: Option<String>,
+    ) -> bool {
+        self.track_order_inner(
+            order_id,
+            market_id,
+            market_name,
+            side,
+            token_id,
+            shares,
+            price,
+            condition_id,
+            yes_token_id,
+            end_time,
+            asset,
+            0,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn track_order_inner(
+        &mut self,
+        order_id: String,
+        market_id: Uuid,
+        market_name: String,
+        side: String,
+        token_id: Option<String>,
+        shares: Option<rust_decimal::Decimal>,
+        price: Option<rust_decimal::Decimal>,
+        condition_id: Option<String>,
+        yes_token_id: Option<String>,
+        end_time: Option<DateTime<Utc>>,
+        asset: Option<String>,
+        reprices: u32,
     ) -> bool {
         if self.pending_orders.contains_key(&order_id) {
             warn!(
@@ -162,9 +293,11 @@ impl OrderManager {
             yes_token_id: yes_token_id.clone(),
             end_time,
             asset: asset.clone(),
+            reprices,
         };
 
         self.pending_orders.insert(order_id.clone(), order);
+        self.resting.insert(&order_id);
 
         // Spawn the cancel task
         let oid = order_id.clone();
@@ -179,12 +312,14 @@ impl OrderManager {
         let ytid = yes_token_id;
         let et = end_time;
         let ast = asset;
+        let resting = self.resting.clone();
 
         self.cancel_tasks.spawn(async move {
             tokio::time::sleep(Duration::from_secs(timeout)).await;
 
             // First, try to cancel the order
             let cancel_result = cancel_order_standalone(oid.clone()).await;
+            resting.remove(&oid);
             let cancel_success = cancel_result.is_ok();
             let cancel_error = cancel_result.err().map(|e| e.to_string());
 
@@ -201,7 +336,7 @@ impl OrderManager {
                             oid, timeout
                         );
                     }
-                    (filled, size_matched)
+                    (filled, Some(size_matched))
                 }
                 Err(e) => {
                     // Query failed - fall back to cancel error heuristic
@@ -216,7 +351,7 @@ impl OrderManager {
                                 || error_str.contains("does not exist")
                         })
                         .unwrap_or(false);
-                    (was_filled, dec!(0))
+                    (was_filled, None)
                 }
             };
 
@@ -229,12 +364,14 @@ impl OrderManager {
                 was_filled,
                 error_msg: cancel_error,
                 token_id: tid,
-                shares: if was_filled { Some(filled_amount) } else { sh },
+                shares: sh,
+                filled_shares: filled_amount,
                 price: pr,
                 condition_id: cid,
                 yes_token_id: ytid,
                 end_time: et,
                 asset: ast,
+                reprices,
             }
         });
 
@@ -331,6 +468,7 @@ impl OrderManager {
         // Abort any remaining background tasks
         self.cancel_tasks.abort_all();
         self.pending_orders.clear();
+        self.resting.take_all();
     }
 
     /// Get order status by order_id.
@@ -376,6 +514,96 @@ mod tests {
         assert!(manager.has_pending_order(&market_id, "YES"));
         assert!(!manager.has_pending_order(&market_id, "NO"));
         assert_eq!(manager.pending_count(), 1);
+
+        // Visible to the watchdog until cancelled
+        let resting = manager.resting_orders();
+        assert_eq!(resting.len(), 1);
+        manager.cancel_all_pending().await;
+        assert!(resting.is_empty());
+    }
+
+    fn cancelled(price: Decimal, reprices: u32) -> CancelResult {
+        CancelResult {
+            order_id: "order123".to_string(),
+            market_id: Uuid::new_v4(),
+            market_name: "Test Market".to_string(),
+            side: "YES".to_string(),
+            success: true,
+            was_filled: false,
+            error_msg: None,
+            token_id: Some("token".to_string()),
+            shares: Some(dec!(10)),
+            filled_shares: Some(dec!(0)),
+            price: Some(price),
+            condition_id: None,
+            yes_token_id: None,
+            end_time: Some(Utc::now() + chrono::Duration::minutes(5)),
+            asset: None,
+            reprices,
+        }
+    }
+
+    #[test]
+    fn test_next_chase_price() {
+        let mut manager = OrderManager::new(10);
+        // Disabled by default
+        assert_eq!(
+            manager.next_chase_price(&cancelled(dec!(0.40), 0), dec!(0.45)),
+            None
+        );
+
+        manager.enable_chase(2, dec!(0.02));
+        assert_eq!(
+            manager.next_chase_price(&cancelled(dec!(0.40), 0), dec!(0.45)),
+            Some(dec!(0.42))
+        );
+        // Capped at the ceiling, then stops
+        assert_eq!(
+            manager.next_chase_price(&cancelled(dec!(0.44), 1), dec!(0.45)),
+            Some(dec!(0.45))
+        );
+        assert_eq!(
+            manager.next_chase_price(&cancelled(dec!(0.45), 1), dec!(0.45)),
+            None
+        );
+        // Re-price budget exhausted
+        assert_eq!(
+            manager.next_chase_price(&cancelled(dec!(0.40), 2), dec!(0.45)),
+            None
+        );
+
+        // Filled or ambiguous cancels are never chased
+        let mut filled = cancelled(dec!(0.40), 0);
+        filled.was_filled = true;
+        assert_eq!(manager.next_chase_price(&filled, dec!(0.45)), None);
+        let mut unknown = cancelled(dec!(0.40), 0);
+        unknown.success = false;
+        assert_eq!(manager.next_chase_price(&unknown, dec!(0.45)), None);
+    }
+
+    #[test]
+    fn test_held_shares_prefers_matched_size() {
+        let mut partial = cancelled(dec!(0.40), 0);
+        partial.was_filled = true;
+        partial.filled_shares = Some(dec!(5));
+        assert_eq!(partial.held_shares(), Some(dec!(5)));
+
+        // Fill inferred from the cancel error: assume the full order
+        partial.filled_shares = None;
+        assert_eq!(partial.held_shares(), Some(dec!(10)));
+    }
+
+    #[tokio::test]
+    async fn test_track_reprice_increments_count() {
+        let mut manager = OrderManager::new(10);
+        manager.enable_chase(3, dec!(0.01));
+        let previous = cancelled(dec!(0.40), 1);
+
+        assert!(manager.track_reprice("order456".to_string(), &previous, dec!(0.41)));
+        let order = &manager.pending_orders["order456"];
+        assert_eq!(order.reprices, 2);
+        assert_eq!(order.price, Some(dec!(0.41)));
+        assert!(manager.has_pending_order(&previous.market_id, "YES"));
     }
 
     #[tokio::test]
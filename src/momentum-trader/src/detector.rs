@@ -3,13 +3,59 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 
+use common::{KlineBuffer, MomentumDirection, MomentumWindow};
+
+/// Parse momentum windows like "1:0.2,5:0.5,15:0.3" into (minutes, weight) pairs.
+/// Minutes must be positive and distinct; weights must be positive.
+pub fn parse_momentum_windows(s: &str) -> Result<Vec<MomentumWindow>> {
+    let mut windows: Vec<MomentumWindow> = Vec::new();
+
+    for entry in s.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let (minutes_str, weight_str) = entry.split_once(':').with_context(|| {
+            format!("Invalid window entry '{}' (expected minutes:weight)", entry)
+        })?;
+
+        let minutes: usize = minutes_str
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid window minutes '{}'", minutes_str))?;
+        let weight: Decimal = weight_str
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid window weight '{}'", weight_str))?;
+
+        if minutes == 0 {
+            bail!("Window minutes must be at least 1");
+        }
+        if weight <= Decimal::ZERO {
+            bail!("Window weight {} must be positive", weight);
+        }
+        if windows.iter().any(|w| w.minutes == minutes) {
+            bail!("Duplicate {}m window", minutes);
+        }
+
+        windows.push(MomentumWindow { minutes, weight });
+    }
+
+    if windows.is_empty() {
+        bail!("No momentum windows given");
+    }
+
+    Ok(windows)
+}
+
 /// Signal detector with cooldown management.
 pub struct SignalDetector {
     pub min_momentum: Decimal,
-    #[allow(dead_code)]
     pub lookback_minutes: usize,
+    /// Per-timeframe lookbacks (e.g. "5m" -> 3) overriding `lookback_minutes`
+    timeframe_lookbacks: HashMap<String, usize>,
+    /// Weighted lookback windows; when empty, the single lookback is used
+    windows: Vec<MomentumWindow>,
     pub max_entry_price: Decimal,
     cooldown_duration: Duration,
     /// Map of condition_id -> last trade time
@@ -26,12 +72,71 @@ impl SignalDetector {
         Self {
             min_momentum,
             lookback_minutes,
+            timeframe_lookbacks: HashMap::new(),
+            windows: Vec::new(),
             max_entry_price,
             cooldown_duration: Duration::from_secs(cooldown_secs),
             cooldowns: HashMap::new(),
         }
     }
 
+    /// Score momentum as a weighted composite of `windows` instead of a single lookback.
+    pub fn with_windows(mut self, windows: Vec<MomentumWindow>) -> Self {
+        self.windows = windows;
+        self
+    }
+
+    /// Use a `minutes` lookback for markets of `timeframe` instead of `lookback_minutes`.
+    pub fn with_timeframe_lookback(mut self, timeframe: &str, minutes: usize) -> Self {
+        self.timeframe_lookbacks
+            .insert(timeframe.to_string(), minutes);
+        self
+    }
+
+    /// Single-window lookback for markets of `timeframe`.
+    pub fn lookback_for(&self, timeframe: &str) -> usize {
+        self.timeframe_lookbacks
+            .get(timeframe)
+            .copied()
+            .unwrap_or(self.lookback_minutes)
+    }
+
+    /// Minutes of kline history needed to score any market (sizes the buffer).
+    pub fn required_minutes(&self) -> usize {
+        match self.windows.iter().map(|w| w.minutes).max() {
+            Some(longest) => longest,
+            None => self
+                .timeframe_lookbacks
+                .values()
+                .copied()
+                .fold(self.lookback_minutes, usize::max),
+        }
+    }
+
+    /// Minutes of kline history needed to score a market of `timeframe`.
+    pub fn required_minutes_for(&self, timeframe: &str) -> usize {
+        match self.windows.iter().map(|w| w.minutes).max() {
+            Some(longest) => longest,
+            None => self.lookback_for(timeframe),
+        }
+    }
+
+    /// Momentum for `symbol` as seen by a market of `timeframe`: the weighted
+    /// composite when windows are configured, otherwise the change over that
+    /// timeframe's lookback. Compare against `min_momentum`.
+    pub fn momentum(
+        &self,
+        buffer: &KlineBuffer,
+        symbol: &str,
+        timeframe: &str,
+    ) -> Option<(Decimal, MomentumDirection)> {
+        if self.windows.is_empty() {
+            buffer.calculate_momentum(symbol, self.lookback_for(timeframe))
+        } else {
+            buffer.weighted_momentum(symbol, &self.windows)
+        }
+    }
+
     /// Check if we can trade a market (not in cooldown).
     pub fn can_trade(&self, condition_id: &str) -> bool {
         match self.cooldowns.get(condition_id) {
@@ -46,9 +151,178 @@ impl SignalDetector {
             .insert(condition_id.to_string(), Instant::now());
     }
 
+    /// Cooldowns as wall-clock trade times, for persisting across restarts.
+    pub fn export_cooldowns(&self) -> HashMap<String, DateTime<Utc>> {
+        let now = Utc::now();
+        self.cooldowns
+            .iter()
+            .filter_map(|(id, instant)| {
+                let elapsed = chrono::Duration::from_std(instant.elapsed()).ok()?;
+                Some((id.clone(), now - elapsed))
+            })
+            .collect()
+    }
+
+    /// Restore cooldowns saved by `export_cooldowns`, skipping any already elapsed.
+    pub fn restore_cooldowns(&mut self, cooldowns: &HashMap<String, DateTime<Utc>>) -> usize {
+        let now = Utc::now();
+        let mut restored = 0;
+        for (id, traded_at) in cooldowns {
+            let elapsed = match (now - *traded_at).to_std() {
+                Ok(d) => d,
+                Err(_) => Duration::ZERO, // Saved in the future (clock skew)
+            };
+            if elapsed >= self.cooldown_duration {
+                continue;
+            }
+            if let Some(instant) = Instant::now().checked_sub(elapsed) {
+                self.cooldowns.insert(id.clone(), instant);
+                restored += 1;
+            }
+        }
+        restored
+    }
+
     /// Clean up old cooldown entries.
     pub fn cleanup_cooldowns(&mut self) {
         self.cooldowns
             .retain(|_, instant| instant.elapsed() < self.cooldown_duration * 2);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::BinanceKline;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_momentum_windows() {
+        let windows = parse_momentum_windows("1:0.2, 5:0.5 ,15:0.3").unwrap();
+        assert_eq!(
+            windows
+                .iter()
+                .map(|w| (w.minutes, w.weight))
+                .collect::<Vec<_>>(),
+            vec![(1, dec!(0.2)), (5, dec!(0.5)), (15, dec!(0.3))]
+        );
+
+        assert!(parse_momentum_windows("").is_err());
+        assert!(parse_momentum_windows("5").is_err());
+        assert!(parse_momentum_windows("0:1").is_err());
+        assert!(parse_momentum_windows("5:0").is_err());
+        assert!(parse_momentum_windows("5:-1").is_err());
+        assert!(parse_momentum_windows("5:1,5:2").is_err());
+        assert!(parse_momentum_windows("x:1").is_err());
+    }
+
+    /// Two closed 1m klines: 100 -> 104, then 104 -> 102.
+    fn two_minute_buffer() -> KlineBuffer {
+        let mut buffer = KlineBuffer::new(10);
+        let base = Utc::now() - chrono::Duration::minutes(5);
+        for (i, (open, close)) in [(dec!(100), dec!(104)), (dec!(104), dec!(102))]
+            .into_iter()
+            .enumerate()
+        {
+            buffer.add(BinanceKline {
+                symbol: "BTCUSDT".to_string(),
+                open_time: base + chrono::Duration::minutes(i as i64),
+                close_time: base + chrono::Duration::minutes(i as i64 + 1),
+                open,
+                high: open.max(close),
+                low: open.min(close),
+                close,
+                volume: dec!(1),
+                is_closed: true,
+                timestamp: base + chrono::Duration::minutes(i as i64 + 1),
+            });
+        }
+
+        buffer
+    }
+
+    #[test]
+    fn test_momentum_uses_windows_when_configured() {
+        let buffer = two_minute_buffer();
+
+        // Single 2m lookback: 102 vs 100 = +2%
+        let single = SignalDetector::new(dec!(0.002), 2, dec!(0.70), 900);
+        assert_eq!(single.required_minutes(), 2);
+        assert_eq!(
+            single.momentum(&buffer, "BTCUSDT", "15m"),
+            Some((dec!(0.02), MomentumDirection::Up))
+        );
+
+        // 1m: 102 vs 104 ~ -1.92%; 2m: +2%; equal weights net slightly positive
+        let weighted = SignalDetector::new(dec!(0.002), 2, dec!(0.70), 900).with_windows(vec![
+            MomentumWindow {
+                minutes: 1,
+                weight: dec!(1),
+            },
+            MomentumWindow {
+                minutes: 2,
+                weight: dec!(1),
+            },
+        ]);
+        let (composite, direction) = weighted.momentum(&buffer, "BTCUSDT", "15m").unwrap();
+        assert_eq!(direction, MomentumDirection::Up);
+        assert!(composite < weighted.min_momentum);
+
+        // A window the buffer cannot cover yet suppresses the signal
+        let warming = SignalDetector::new(dec!(0.002), 2, dec!(0.70), 900).with_windows(vec![
+            MomentumWindow {
+                minutes: 1,
+                weight: dec!(1),
+            },
+            MomentumWindow {
+                minutes: 15,
+                weight: dec!(1),
+            },
+        ]);
+        assert_eq!(warming.required_minutes(), 15);
+        assert_eq!(warming.momentum(&buffer, "BTCUSDT", "15m"), None);
+    }
+
+    #[test]
+    fn test_momentum_lookback_per_timeframe() {
+        let buffer = two_minute_buffer();
+        let detector =
+            SignalDetector::new(dec!(0.002), 2, dec!(0.70), 900).with_timeframe_lookback("5m", 1);
+
+        // 5m markets look back 1m (102 vs 104), 15m falls back to the global 2m (102 vs 100)
+        assert_eq!(detector.lookback_for("5m"), 1);
+        assert_eq!(detector.lookback_for("15m"), 2);
+        let (five, five_dir) = detector.momentum(&buffer, "BTCUSDT", "5m").unwrap();
+        assert_eq!(five_dir, MomentumDirection::Down);
+        assert_eq!(five.round_dp(4), dec!(-0.0192));
+        assert_eq!(
+            detector.momentum(&buffer, "BTCUSDT", "15m"),
+            Some((dec!(0.02), MomentumDirection::Up))
+        );
+        assert_eq!(detector.required_minutes_for("5m"), 1);
+        assert_eq!(detector.required_minutes(), 2);
+
+        // The buffer is sized for the longest lookback of any timeframe
+        let long_15m = detector.with_timeframe_lookback("15m", 10);
+        assert_eq!(long_15m.required_minutes(), 10);
+        assert_eq!(long_15m.momentum(&buffer, "BTCUSDT", "15m"), None);
+        assert!(long_15m.momentum(&buffer, "BTCUSDT", "5m").is_some());
+    }
+
+    #[test]
+    fn test_cooldowns_round_trip() {
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        detector.record_trade("cond-a");
+
+        let mut saved = detector.export_cooldowns();
+        saved.insert(
+            "cond-old".to_string(),
+            Utc::now() - chrono::Duration::seconds(901),
+        );
+
+        let mut restarted = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        assert_eq!(restarted.restore_cooldowns(&saved), 1);
+        assert!(!restarted.can_trade("cond-a"));
+        assert!(restarted.can_trade("cond-old"));
+    }
+}
@@ -6,29 +6,44 @@
 //! Expected win rate: 84-87% based on backtest results.
 
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use anyhow::{Context, Result};
-use chrono::Utc;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
-use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
+use common::assets::{binance_symbol, mapped_assets, parse_assets};
+use common::models::{TradeIntent, TradeMode, TradeOutcome};
+use common::validation::{
+    validate_expiry_window, validate_max_shares, validate_pct, validate_positive, validate_price,
+};
 use common::{
-    calculate_fill_price_with_slippage, cancel_order_standalone, execute_trade,
-    get_15m_updown_markets_with_orderbooks, BinanceEvent, BinanceStreamType, BinanceWsClient,
-    CachedAuth, Config, Database, DryRunPortfolio, GammaClient, KlineBuffer, MomentumDirection,
-    SimulatedPosition, MAX_SHARES,
+    calculate_fill_price_with_slippage, cancel_on_stall, cancel_order_standalone,
+    ensure_authenticated, execute_trade, fetch_live_best_ask, fmt_price, fmt_shares,
+    fresh_retry_ask, get_active_markets, insert_fill, log_unresolved_live_positions,
+    price_divergence_exceeded, query_order_fill_standalone, reconcile_open_orders,
+    record_trade_audit, resolve_live_settlements, retain_valid_depth, spawn_watchdog, BinanceEvent,
+    BinanceStreamType, BinanceWsClient, CachedAuth, Config, DailyPnlStop, Database,
+    DryRunPortfolio, FillEstimate, FillInsert, FillPriceModel, GammaClient, Heartbeat, KlineBuffer,
+    LivePosition, LivePositions, MarketRepository, MarketRotation, MarketWithOrderbook,
+    MomentumDirection, OrderRejection, RestingOrders, SettlementTimeout, SideFilter,
+    SimulatedPosition, TradeThrottle, UnresolvedPolicy, DEFAULT_MAX_SHARES,
 };
 
 mod detector;
 mod metrics;
+mod state;
 
-use detector::SignalDetector;
+use detector::{parse_momentum_windows, SignalDetector};
 use metrics::Metrics;
+use state::{TradedPositions, TraderState};
 
 /// Momentum Trader - trades based on Binance price momentum
 #[derive(Parser, Debug)]
@@ -43,6 +58,39 @@ struct Args {
     #[arg(long, default_value = "5")]
     lookback_minutes: u64,
 
+    /// Lookback in minutes for 5m markets (default: --lookback-minutes)
+    #[arg(long = "lookback-5m")]
+    lookback_5m: Option<u64>,
+
+    /// Lookback in minutes for 15m markets (default: --lookback-minutes)
+    #[arg(long = "lookback-15m")]
+    lookback_15m: Option<u64>,
+
+    /// Score momentum as a weighted blend of several lookback windows, given as
+    /// minutes:weight pairs (e.g. "1:0.2,5:0.5,15:0.3"). Overrides all lookback flags.
+    #[arg(long)]
+    momentum_windows: Option<String>,
+
+    /// Only trade one side of a signal (both, yes-only, no-only); signals for
+    /// the other side are still tracked but not acted upon
+    #[arg(long, default_value = "both")]
+    side_filter: SideFilter,
+
+    /// Only enter if the ticker trend over the last N seconds agrees with the
+    /// momentum direction (disabled if not set)
+    #[arg(long)]
+    trend_confirm_secs: Option<u64>,
+
+    /// Only enter if Binance taker volume over the last N seconds leans the
+    /// momentum direction (disabled if not set; subscribes to aggTrade)
+    #[arg(long)]
+    volume_confirm_secs: Option<u64>,
+
+    /// With --volume-confirm-secs, minimum fraction (0-1) of taker volume
+    /// that must be in the momentum direction
+    #[arg(long, default_value = "0.55")]
+    min_volume_share: f64,
+
     /// Maximum entry price on Polymarket (skip if price > this)
     #[arg(long, default_value = "0.70")]
     max_entry_price: f64,
@@ -51,6 +99,10 @@ struct Args {
     #[arg(long, default_value = "5")]
     position_size: f64,
 
+    /// Maximum shares per order; a signal needing more (position size / price) is skipped
+    #[arg(long, default_value_t = DEFAULT_MAX_SHARES)]
+    max_shares: Decimal,
+
     /// Maximum time to market expiry in minutes
     #[arg(long, default_value = "10")]
     max_expiry_minutes: i64,
@@ -67,7 +119,12 @@ struct Args {
     #[arg(long, default_value = "1")]
     max_orderbook_age: i32,
 
-    /// Assets to trade (comma-separated)
+    /// Fully process at most this many markets per cycle, soonest expiry
+    /// first; the rest wait for a later cycle. Optional.
+    #[arg(long)]
+    max_markets_per_cycle: Option<usize>,
+
+    /// Assets to trade (comma-separated), or ALL for every asset with active markets
     #[arg(long, default_value = "BTC,ETH,SOL,XRP")]
     assets: String,
 
@@ -75,48 +132,239 @@ struct Args {
     #[arg(long)]
     dry_run: bool,
 
+    /// How to account for unresolved dry-run positions at shutdown
+    /// (mark-to-market, assume-loss, win-if-above-half, leave-pending)
+    #[arg(long, default_value = "mark-to-market")]
+    unresolved_policy: UnresolvedPolicy,
+
+    /// Virtual starting bankroll in USDC for dry-run; trades beyond the
+    /// available balance (bankroll + realized P&L - open cost) are refused
+    #[arg(long)]
+    dry_run_balance: Option<f64>,
+
+    /// Stop opening new positions once the day's realized P&L reaches this
+    /// many USDC (open positions still settle; resets at UTC midnight)
+    #[arg(long)]
+    daily_profit_target: Option<f64>,
+
+    /// Stop opening new positions once the day's realized loss reaches this
+    /// many USDC (open positions still settle; resets at UTC midnight)
+    #[arg(long)]
+    daily_max_loss: Option<f64>,
+
+    /// Dry-run only: wait this many milliseconds after a signal and price the
+    /// fill off the snapshot as it stands then, approximating the gap between
+    /// signal and fill a live order races (0 = fill at the signal snapshot)
+    #[arg(long, default_value_t = 0)]
+    simulate_latency_ms: u64,
+
+    /// Re-check the best ask against the live CLOB book before each trade
+    #[arg(long)]
+    verify_live_price: bool,
+
+    /// With --verify-live-price, skip when the live ask is more than this many
+    /// dollars above the DB ask
+    #[arg(long, default_value = "0.02")]
+    max_price_divergence: f64,
+
+    /// Price used to plan fills from orderbook depth: vwap (size-weighted
+    /// average), worst-touched (deepest level reached) or best-only
+    #[arg(long, default_value = "vwap")]
+    fill_model: FillPriceModel,
+
     /// Slippage percentage for fill price estimation
     #[arg(long, default_value = "20")]
     slippage_pct: f64,
+
+    /// Skip a signal when the depth-based fill is more than this percent above
+    /// the best ask (e.g. 5 = 5%), even if it is under the limit price. Optional.
+    #[arg(long)]
+    max_slippage_pct: Option<f64>,
+
+    /// Minimum USDC resting on the ask side being bought (sum of price * size
+    /// across the snapshot depth). Markets without depth data are skipped. Optional.
+    #[arg(long)]
+    min_liquidity_usdc: Option<f64>,
+
+    /// JSON file for persisting cooldowns and traded positions across restarts
+    #[arg(long)]
+    state_file: Option<PathBuf>,
+
+    /// Cancel open orders on the configured assets found at startup
+    /// (live mode; without this they are only logged)
+    #[arg(long)]
+    cancel_orphans: bool,
+
+    /// Minimum milliseconds between live orders across all markets, on top of
+    /// per-market cooldowns (0 = no limit; ignored in dry-run)
+    #[arg(long, default_value = "0")]
+    min_trade_interval_ms: u64,
+
+    /// Cancel all resting orders if no trading cycle completes within this
+    /// many seconds (0 = disabled)
+    #[arg(long, default_value = "60")]
+    watchdog_timeout_secs: u64,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
+}
+
+/// Strategy tag recorded with each trade intent.
+const STRATEGY_TAG: &str = "momentum";
+
+/// How long to poll a live order for fills before cancelling the remainder.
+const FILL_POLL_TIMEOUT_SECS: u64 = 10;
+
+/// Interval between fill status queries.
+const FILL_POLL_INTERVAL_MS: u64 = 1000;
+
+/// Final fill status of a live order, reported back to the main loop.
+#[derive(Debug)]
+struct FillReport {
+    order_id: String,
+    market_id: Uuid,
+    token_id: String,
+    price: Decimal,
+    asset: String,
+    side: String,
+    market_name: String,
+    requested_shares: Decimal,
+    filled_shares: Decimal,
+    // For settlement tracking once the market expires
+    yes_token_id: String,
+    end_time: DateTime<Utc>,
 }
 
-/// Map of asset -> Binance symbol. Returns None for unsupported assets.
-fn asset_to_binance_symbol(asset: &str) -> Option<&'static str> {
-    match asset.to_uppercase().as_str() {
-        "BTC" => Some("BTCUSDT"),
-        "ETH" => Some("ETHUSDT"),
-        "SOL" => Some("SOLUSDT"),
-        "XRP" => Some("XRPUSDT"),
-        _ => {
-            warn!("Unsupported asset: {}, skipping", asset);
-            None
+/// Log open orders on the configured assets' markets left over from a previous
+/// run, cancelling them when `cancel` is set.
+async fn reconcile_orphan_orders(
+    pool: &PgPool,
+    assets: &[String],
+    cancel: bool,
+    cached_auth: &mut Option<CachedAuth>,
+) -> Result<()> {
+    let token_ids: HashSet<String> = get_active_markets(pool)
+        .await?
+        .into_iter()
+        .filter(|m| assets.contains(&m.asset))
+        .flat_map(|m| [m.yes_token_id, m.no_token_id])
+        .collect();
+
+    let auth = ensure_authenticated(cached_auth).await?;
+    reconcile_open_orders(auth, &token_ids, cancel).await?;
+    Ok(())
+}
+
+/// Reject flag values and combinations that would otherwise misbehave silently.
+fn validate_args(args: &Args) -> Result<()> {
+    validate_expiry_window(args.min_expiry_minutes, args.max_expiry_minutes)?;
+    validate_pct("min-momentum", args.min_momentum, 0.0..=1.0)?;
+    if args.lookback_minutes < 1 {
+        bail!("--lookback-minutes must be at least 1");
+    }
+    if args.lookback_5m == Some(0) {
+        bail!("--lookback-5m must be at least 1");
+    }
+    if args.lookback_15m == Some(0) {
+        bail!("--lookback-15m must be at least 1");
+    }
+    if let Some(ref windows) = args.momentum_windows {
+        parse_momentum_windows(windows).context("Invalid --momentum-windows")?;
+    }
+    if args.max_markets_per_cycle == Some(0) {
+        bail!("--max-markets-per-cycle must be at least 1");
+    }
+    if args.trend_confirm_secs == Some(0) {
+        bail!("--trend-confirm-secs must be at least 1");
+    }
+    if args.volume_confirm_secs == Some(0) {
+        bail!("--volume-confirm-secs must be at least 1");
+    }
+    validate_pct("min-volume-share", args.min_volume_share, 0.0..=1.0)?;
+    validate_price("max-entry-price", args.max_entry_price)?;
+    validate_positive("position-size", args.position_size)?;
+    validate_max_shares(args.max_shares)?;
+    validate_pct("slippage-pct", args.slippage_pct, 0.0..=100.0)?;
+    if let Some(max) = args.max_slippage_pct {
+        if max < 0.0 {
+            bail!("--max-slippage-pct must be non-negative (got {})", max);
         }
     }
+    validate_pct("max-price-divergence", args.max_price_divergence, 0.0..=1.0)?;
+    if let Some(min) = args.min_liquidity_usdc {
+        validate_positive("min-liquidity-usdc", min)?;
+    }
+    if let Some(balance) = args.dry_run_balance {
+        validate_positive("dry-run-balance", balance)?;
+    }
+    if let Some(target) = args.daily_profit_target {
+        validate_positive("daily-profit-target", target)?;
+    }
+    if let Some(loss) = args.daily_max_loss {
+        validate_positive("daily-max-loss", loss)?;
+    }
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
+    validate_args(&args)?;
 
     info!("=== Momentum Trader ===");
     info!("Min momentum: {}%", args.min_momentum * 100.0);
-    info!("Lookback: {} minutes", args.lookback_minutes);
+    match args.momentum_windows {
+        Some(ref windows) => info!("Momentum windows (minutes:weight): {}", windows),
+        None => info!(
+            "Lookback: {} minutes (5m markets: {}, 15m markets: {})",
+            args.lookback_minutes,
+            args.lookback_5m.unwrap_or(args.lookback_minutes),
+            args.lookback_15m.unwrap_or(args.lookback_minutes)
+        ),
+    }
     info!("Max entry price: ${}", args.max_entry_price);
     info!("Position size: ${}", args.position_size);
+    info!("Max shares per order: {}", args.max_shares);
     info!(
         "Expiry window: {}-{} minutes",
         args.min_expiry_minutes, args.max_expiry_minutes
     );
     info!("Cooldown: {} seconds", args.cooldown_secs);
     info!("Assets: {}", args.assets);
+    if args.side_filter != SideFilter::Both {
+        info!("Side filter: {}", args.side_filter);
+    }
+    if args.verify_live_price {
+        info!(
+            "Live price check: skip if CLOB ask > DB ask + ${}",
+            args.max_price_divergence
+        );
+    }
+    if let Some(min) = args.min_liquidity_usdc {
+        info!("Min ask liquidity: ${:.2}", min);
+    }
+    if args.watchdog_timeout_secs > 0 {
+        info!("Watchdog timeout: {}s", args.watchdog_timeout_secs);
+    }
     info!("Dry run: {}", args.dry_run);
+    info!("Fill model: {}", args.fill_model);
+    if args.dry_run {
+        info!("Unresolved policy: {}", args.unresolved_policy);
+        if let Some(balance) = args.dry_run_balance {
+            info!("Virtual balance: ${:.2}", balance);
+        }
+        if args.simulate_latency_ms > 0 {
+            info!("Simulated fill latency: {}ms", args.simulate_latency_ms);
+        }
+    }
+    if let Some(target) = args.daily_profit_target {
+        info!("Daily profit target: ${:.2}", target);
+    }
+    if let Some(loss) = args.daily_max_loss {
+        info!("Daily max loss: ${:.2}", loss);
+    }
 
     // Load config and connect to database
     dotenvy::dotenv().ok();
@@ -126,26 +374,20 @@ async fn main() -> Result<()> {
 
     info!("Connected to database");
 
-    // Parse assets
-    let assets: Vec<String> = args
-        .assets
-        .split(',')
-        .map(|s| s.trim().to_uppercase())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    if assets.is_empty() {
-        anyhow::bail!("No valid assets specified");
-    }
-
-    // Build Binance symbols list (filter out unsupported assets)
-    let binance_symbols: Vec<String> = assets
-        .iter()
-        .filter_map(|a| asset_to_binance_symbol(a).map(|s| s.to_string()))
-        .collect();
+    // Parse assets (ALL = every asset with active markets), keeping those
+    // Binance can price
+    let selection = parse_assets(&args.assets)?;
+    let (assets, binance_symbols): (Vec<String>, Vec<String>) = mapped_assets(
+        &selection.resolve(db.pool()).await?,
+        "Binance",
+        binance_symbol,
+    )
+    .into_iter()
+    .map(|(asset, symbol)| (asset, symbol.to_string()))
+    .unzip();
 
     if binance_symbols.is_empty() {
-        anyhow::bail!("No supported assets specified");
+        bail!("No supported assets specified");
     }
 
     info!("Binance symbols: {:?}", binance_symbols);
@@ -158,27 +400,121 @@ async fn main() -> Result<()> {
     let slippage_pct = Decimal::try_from(args.slippage_pct).context("Invalid slippage_pct")?;
 
     // Initialize components
-    let mut kline_buffer = KlineBuffer::new(args.lookback_minutes as usize + 2);
+    let momentum_windows = match args.momentum_windows {
+        Some(ref windows) => parse_momentum_windows(windows)?,
+        None => Vec::new(),
+    };
     let mut detector = SignalDetector::new(
         min_momentum,
         args.lookback_minutes as usize,
         max_entry_price,
         args.cooldown_secs,
-    );
+    )
+    .with_windows(momentum_windows);
+    for (timeframe, lookback) in [("5m", args.lookback_5m), ("15m", args.lookback_15m)] {
+        if let Some(minutes) = lookback {
+            detector = detector.with_timeframe_lookback(timeframe, minutes as usize);
+        }
+    }
+    let mut kline_buffer = KlineBuffer::new(detector.required_minutes() + 2)
+        .with_tick_history(args.trend_confirm_secs.map_or(0, |secs| secs + 1))
+        .with_trade_history(args.volume_confirm_secs.unwrap_or(0));
+    if let Some(secs) = args.trend_confirm_secs {
+        info!("Short-window trend filter: last {}s must agree", secs);
+    }
+    if let Some(secs) = args.volume_confirm_secs {
+        info!(
+            "Volume filter: {:.0}%+ of last {}s taker volume must agree",
+            args.min_volume_share * 100.0,
+            secs
+        );
+    }
     let mut metrics = Metrics::new();
     let mut portfolio = DryRunPortfolio::new();
+    if let Some(balance) = args.dry_run_balance {
+        portfolio =
+            portfolio.with_balance(Decimal::try_from(balance).context("Invalid dry_run_balance")?);
+    }
+    let mut daily_stop = DailyPnlStop::new(
+        args.daily_profit_target
+            .map(Decimal::try_from)
+            .transpose()
+            .context("Invalid daily_profit_target")?,
+        args.daily_max_loss
+            .map(Decimal::try_from)
+            .transpose()
+            .context("Invalid daily_max_loss")?,
+    );
+    // Live position tracker for settlement resolution (live mode only)
+    let mut live_positions = LivePositions::new();
+    // Dry-run entries waiting out --simulate-latency-ms
+    let mut latency_fills: Vec<LatencyFill> = Vec::new();
     let mut cached_auth: Option<CachedAuth> = None;
+    let mut throttle = if args.dry_run {
+        TradeThrottle::disabled()
+    } else {
+        TradeThrottle::new(Duration::from_millis(args.min_trade_interval_ms))
+    };
     // Track (market_id, side) - allows trading both YES and NO on same market
-    let mut traded_positions: HashSet<(Uuid, String)> = HashSet::new();
+    let mut traded_positions = TradedPositions::new();
+    let mut rotation = MarketRotation::default();
+
+    // Restore recent activity so a restart does not immediately re-enter markets
+    if let Some(path) = &args.state_file {
+        let mut state = TraderState::load(path)?;
+        let pruned = state.prune(Utc::now(), args.cooldown_secs);
+        let restored = detector.restore_cooldowns(&state.cooldowns);
+        traded_positions = state.traded_map();
+        info!(
+            "[STATE] Loaded {}: {} cooldowns, {} traded positions ({} expired entries pruned)",
+            path.display(),
+            restored,
+            traded_positions.len(),
+            pruned
+        );
+    }
+
+    // Surface orders left resting by a previous run before trading resumes
+    if !args.dry_run {
+        if let Err(e) =
+            reconcile_orphan_orders(db.pool(), &assets, args.cancel_orphans, &mut cached_auth).await
+        {
+            warn!("[ORPHAN] Startup reconciliation failed: {}", e);
+        }
+    }
 
-    // Connect to Binance WebSocket (Both = bookTicker for real-time + klines for momentum)
-    let binance_client =
-        BinanceWsClient::with_stream_type(binance_symbols.clone(), BinanceStreamType::Both);
+    // Fill results from spawned order pollers
+    let (fill_tx, mut fill_rx) = mpsc::unbounded_channel::<FillReport>();
 
-    info!("Connecting to Binance WebSocket (bookTicker + klines)...");
+    // Connect to Binance WebSocket (Both = bookTicker for real-time + klines for
+    // momentum; Trades adds aggTrade volume for the volume filter)
+    let stream_type = if args.volume_confirm_secs.is_some() {
+        BinanceStreamType::Trades
+    } else {
+        BinanceStreamType::Both
+    };
+    let binance_client = BinanceWsClient::with_stream_type(binance_symbols.clone(), stream_type);
+
+    info!("Connecting to Binance WebSocket ({:?})...", stream_type);
     let mut binance_ws = binance_client.connect_with_retry(5).await?;
     info!("Connected to Binance WebSocket");
 
+    // Dead-man's switch: cancel orders still being polled if the loop stops
+    // cycling
+    let resting = RestingOrders::new();
+    let heartbeat = Heartbeat::new();
+    let watchdog = (args.watchdog_timeout_secs > 0).then(|| {
+        let resting = resting.clone();
+        spawn_watchdog(
+            heartbeat.clone(),
+            Duration::from_secs(args.watchdog_timeout_secs),
+            move |stalled_for| {
+                let resting = resting.clone();
+                async move { cancel_on_stall(&resting, stalled_for).await }
+            },
+        )
+    });
+
     // Main loop with graceful shutdown
     let mut last_cycle_time = std::time::Instant::now();
     let mut last_cleanup_time = std::time::Instant::now();
@@ -196,15 +532,62 @@ async fn main() -> Result<()> {
             }
             _ = heartbeat_interval.tick() => {
                 // Heartbeat: print metrics and portfolio summary
-                info!("[ALIVE] Heartbeat - klines received: {}", klines_since_heartbeat);
+                info!("[ALIVE] Heartbeat - klines received: {}, live tracked: {}",
+                    klines_since_heartbeat, live_positions.len());
                 klines_since_heartbeat = 0;
                 metrics.print_summary();
                 if args.dry_run {
                     portfolio.print_summary();
                     // Also resolve any expired positions during heartbeat
-                    portfolio.resolve_expired(db.pool(), &gamma).await;
+                    portfolio.resolve_expired(db.pool(), &gamma, SettlementTimeout::default())
+                        .await;
+                }
+                // Resolve expired live positions during heartbeat (live mode)
+                if !args.dry_run {
+                    for settled in resolve_live_settlements(
+                        &mut live_positions,
+                        db.pool(),
+                        &gamma,
+                        SettlementTimeout::default(),
+                    )
+                    .await
+                    {
+                        metrics.record_live_settlement(settled);
+                    }
                 }
             }
+            Some(report) = fill_rx.recv() => {
+                if report.filled_shares > Decimal::ZERO {
+                    info!(
+                        "[FILL] Order {} {} {}: {}/{} shares filled",
+                        report.order_id,
+                        report.side,
+                        report.market_name,
+                        report.filled_shares,
+                        report.requested_shares
+                    );
+                    metrics.record_trade(&report.asset, &report.side);
+                    record_fill(db.pool(), &report).await;
+                    // Held to expiry: track for settlement resolution
+                    live_positions.insert(
+                        (report.market_id, report.side.clone()),
+                        LivePosition::new(
+                            report.yes_token_id.clone(),
+                            report.side.clone(),
+                            report.filled_shares,
+                            report.price,
+                            report.end_time,
+                            report.market_name.clone(),
+                        ),
+                    );
+                } else {
+                    info!(
+                        "[FILL] Order {} {} {}: unfilled after {}s",
+                        report.order_id, report.side, report.market_name, FILL_POLL_TIMEOUT_SECS
+                    );
+                }
+                metrics.record_fill(report.requested_shares, report.filled_shares);
+            }
             event_opt = binance_ws.next_event() => {
                 match event_opt {
                     Some(event) => {
@@ -219,15 +602,35 @@ async fn main() -> Result<()> {
                                 // Add kline to buffer for momentum calculation
                                 kline_buffer.add(kline);
                             }
+                            BinanceEvent::Trade(trade) => {
+                                // Accumulate executed volume for the volume filter
+                                kline_buffer.add_trade(&trade);
+                            }
                         }
 
                         // Check if we should run a trading cycle (every 500ms for faster response)
                         if last_cycle_time.elapsed() >= Duration::from_millis(500) {
                             last_cycle_time = std::time::Instant::now();
 
+                            // Realized P&L: paper results in dry-run, settlements live
+                            if daily_stop.is_enabled() {
+                                let realized = if args.dry_run {
+                                    portfolio.total_pnl
+                                } else {
+                                    metrics.realized_pnl()
+                                };
+                                if let Some(reason) = daily_stop.update(realized, Utc::now()) {
+                                    warn!(
+                                        "[DAILY STOP] {} reached (day P&L ${:.2}); no new positions until UTC midnight, open positions will still settle",
+                                        reason,
+                                        daily_stop.day_pnl()
+                                    );
+                                }
+                            }
+
                             // Run trading cycle
-                            run_cycle(
-                                &db,
+                            let state_changed = run_cycle(
+                                db.pool(),
                                 &assets,
                                 &args,
                                 &kline_buffer,
@@ -235,10 +638,22 @@ async fn main() -> Result<()> {
                                 &mut metrics,
                                 &mut portfolio,
                                 &mut cached_auth,
+                                &mut throttle,
                                 &mut traded_positions,
+                                &mut rotation,
+                                &daily_stop,
+                                &mut latency_fills,
+                                &fill_tx,
+                                &resting,
                                 position_size,
                                 slippage_pct,
                             ).await;
+                            heartbeat.beat();
+                            if state_changed {
+                                if let Some(path) = &args.state_file {
+                                    save_state(path, &detector, &traded_positions);
+                                }
+                            }
                         }
 
                         // Cleanup cooldowns every 5 minutes
@@ -246,6 +661,9 @@ async fn main() -> Result<()> {
                             last_cleanup_time = std::time::Instant::now();
                             detector.cleanup_cooldowns();
                             portfolio.cleanup_stale_positions();
+                            if let Some(path) = &args.state_file {
+                                save_state(path, &detector, &traded_positions);
+                            }
                         }
                     }
                     None => {
@@ -254,6 +672,8 @@ async fn main() -> Result<()> {
                             Ok(new_ws) => {
                                 binance_ws = new_ws;
                                 info!("Reconnected to Binance WebSocket");
+                                // Fill the kline gap left by the disconnect before trading again
+                                binance_client.backfill_klines(&mut kline_buffer).await;
                             }
                             Err(e) => {
                                 error!("Failed to reconnect: {}", e);
@@ -266,12 +686,24 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some(watchdog) = watchdog {
+        watchdog.abort();
+    }
+
+    if let Some(path) = &args.state_file {
+        save_state(path, &detector, &traded_positions);
+    }
+
     // Final summary
     info!("=== FINAL STATUS ===");
     metrics.print_summary();
     if args.dry_run {
+        portfolio.settle_unresolved(args.unresolved_policy);
         portfolio.print_summary();
     }
+    if !args.dry_run {
+        log_unresolved_live_positions(&live_positions);
+    }
 
     binance_ws.close().await;
     info!("Shutdown complete");
@@ -281,7 +713,7 @@ async fn main() -> Result<()> {
 /// Run a single trading cycle.
 #[allow(clippy::too_many_arguments)]
 async fn run_cycle(
-    db: &Database,
+    repo: &dyn MarketRepository,
     assets: &[String],
     args: &Args,
     kline_buffer: &KlineBuffer,
@@ -289,35 +721,67 @@ async fn run_cycle(
     metrics: &mut Metrics,
     portfolio: &mut DryRunPortfolio,
     cached_auth: &mut Option<CachedAuth>,
-    traded_positions: &mut HashSet<(Uuid, String)>,
+    throttle: &mut TradeThrottle,
+    traded_positions: &mut TradedPositions,
+    rotation: &mut MarketRotation,
+    daily_stop: &DailyPnlStop,
+    latency_fills: &mut Vec<LatencyFill>,
+    fill_tx: &mpsc::UnboundedSender<FillReport>,
+    resting: &RestingOrders,
     position_size: Decimal,
     slippage_pct: Decimal,
-) {
+) -> bool {
     // Get markets expiring within window
     let expiry_seconds = args.max_expiry_minutes * 60;
     let min_expiry_seconds = args.min_expiry_minutes * 60;
 
+    let max_shares = args.max_shares;
     let all_timeframes = vec!["5m".to_string(), "15m".to_string()];
-    let markets = match get_15m_updown_markets_with_orderbooks(
-        db.pool(),
-        args.max_orderbook_age,
-        assets,
-        expiry_seconds,
-        &all_timeframes,
-    )
-    .await
+    let markets = match repo
+        .get_15m_updown_markets_with_orderbooks(
+            args.max_orderbook_age,
+            assets,
+            expiry_seconds,
+            &all_timeframes,
+        )
+        .await
     {
         Ok(m) => m,
         Err(e) => {
             error!("Failed to query markets: {}", e);
             metrics.record_db_error();
-            return;
+            return false;
         }
     };
 
+    // Mark open dry-run positions to the latest orderbook
+    if args.dry_run {
+        portfolio.update_last_prices(&markets);
+    }
+
+    // Book delayed dry-run entries whose simulated latency has elapsed
+    let mut state_changed = false;
+    if !latency_fills.is_empty() {
+        state_changed = fill_due_latency_entries(
+            latency_fills,
+            &markets,
+            repo,
+            portfolio,
+            metrics,
+            traded_positions,
+            args,
+            position_size,
+            max_shares,
+            detector.max_entry_price,
+            slippage_pct,
+            Utc::now(),
+        )
+        .await;
+    }
+
     // Filter by minimum expiry
     let now = Utc::now();
-    let markets: Vec<_> = markets
+    let mut markets: Vec<_> = markets
         .into_iter()
         .filter(|m| {
             let secs_to_expiry = (m.end_time - now).num_seconds();
@@ -326,32 +790,61 @@ async fn run_cycle(
         .collect();
 
     if markets.is_empty() {
-        return;
+        return state_changed;
+    }
+
+    // A malformed depth snapshot can pass for a thin book; never trade on one
+    let rejected = retain_valid_depth(&mut markets);
+    for (name, e) in &rejected {
+        warn!("[SKIP] {} - invalid depth snapshot: {}", name, e);
+    }
+    metrics.record_invalid_depth(rejected.len());
+
+    // Bound per-cycle work: most urgent markets first, the rest rotate
+    // through the remaining slots so none is deferred indefinitely
+    let deferred = rotation.select(&mut markets, args.max_markets_per_cycle);
+    if deferred > 0 {
+        info!(
+            "[CAP] Processing {} markets this cycle, {} deferred",
+            markets.len(),
+            deferred
+        );
+        metrics.record_deferred(deferred);
     }
 
     debug!("Found {} tradeable markets", markets.len());
 
     // Check each asset for momentum signals
     for asset in assets {
-        let binance_symbol = match asset_to_binance_symbol(asset) {
+        let binance_symbol = match binance_symbol(asset) {
             Some(s) => s,
             None => continue,
         };
 
+        // Find the candidate market first: its timeframe picks the lookback
+        let market = match markets.iter().find(|m| m.asset.to_uppercase() == *asset) {
+            Some(m) => m,
+            None => {
+                debug!("No matching market for {}", asset);
+                continue;
+            }
+        };
+
         // Check if we have enough data for momentum calculation
-        if kline_buffer.len(binance_symbol) < args.lookback_minutes as usize {
+        let required_minutes = detector.required_minutes_for(&market.timeframe);
+        if kline_buffer.len(binance_symbol) < required_minutes {
             debug!(
-                "Not enough data for {} ({}/{} klines)",
+                "Not enough data for {} {} ({}/{} klines)",
                 asset,
+                market.timeframe,
                 kline_buffer.len(binance_symbol),
-                args.lookback_minutes
+                required_minutes
             );
             continue;
         }
 
-        // Calculate momentum
-        let momentum_result =
-            kline_buffer.calculate_momentum(binance_symbol, args.lookback_minutes as usize);
+        // Calculate momentum (weighted composite when windows are configured)
+        let momentum_result = detector.momentum(kline_buffer, binance_symbol, &market.timeframe);
 
         let (momentum_pct, direction) = match momentum_result {
             Some(r) => r,
@@ -363,18 +856,58 @@ async fn run_cycle(
             continue;
         }
 
-        metrics.record_signal(asset);
-
-        // Find matching market for this asset
-        let matching_market = markets.iter().find(|m| m.asset.to_uppercase() == *asset);
+        // Skip if the price just reversed against the lookback momentum
+        if let Some(window_secs) = args.trend_confirm_secs {
+            match kline_buffer.short_term_change(binance_symbol, window_secs) {
+                Some(change) if direction.agrees_with(change) => {}
+                Some(change) => {
+                    debug!(
+                        "{} {:?} momentum {:.4}% but last {}s moved {:.4}%, skipping",
+                        asset,
+                        direction,
+                        momentum_pct * dec!(100),
+                        window_secs,
+                        change * dec!(100)
+                    );
+                    continue;
+                }
+                None => {
+                    debug!(
+                        "Not enough ticker history for {} {}s trend check",
+                        asset, window_secs
+                    );
+                    continue;
+                }
+            }
+        }
 
-        let market = match matching_market {
-            Some(m) => m,
-            None => {
-                debug!("No matching market for {} signal", asset);
-                continue;
+        // Skip if executed volume doesn't back the move (just a quote flicker)
+        if let Some(window_secs) = args.volume_confirm_secs {
+            let min_share = Decimal::try_from(args.min_volume_share).unwrap_or(Decimal::ZERO);
+            let volume = kline_buffer.trade_volume(binance_symbol, window_secs, Utc::now());
+            match volume.share_toward(direction) {
+                Some(share) if share >= min_share => {}
+                Some(share) => {
+                    debug!(
+                        "{} {:?} momentum but only {:.0}% of last {}s volume agrees, skipping",
+                        asset,
+                        direction,
+                        share * dec!(100),
+                        window_secs
+                    );
+                    continue;
+                }
+                None => {
+                    debug!(
+                        "No {} trades in the last {}s for volume check",
+                        asset, window_secs
+                    );
+                    continue;
+                }
             }
-        };
+        }
+
+        metrics.record_signal(asset);
 
         // Determine side first to check if already traded
         let side = match direction {
@@ -382,8 +915,29 @@ async fn run_cycle(
             MomentumDirection::Down => "NO",
         };
 
+        // Respect the configured direction bias
+        if !args.side_filter.allows(side) {
+            debug!(
+                "[BIAS] {} {} signal suppressed by side filter ({})",
+                market.name, side, args.side_filter
+            );
+            metrics.record_bias_skip();
+            continue;
+        }
+
+        // Daily P&L bound reached: keep watching, but open nothing new
+        if !daily_stop.allows_entry() {
+            debug!(
+                "[DAILY STOP] {} {} signal not traded ({} reached)",
+                market.name,
+                side,
+                daily_stop.halted().map(|r| r.as_str()).unwrap_or_default()
+            );
+            continue;
+        }
+
         // Check if this specific (market, side) already traded
-        if traded_positions.contains(&(market.id, side.to_string())) {
+        if traded_positions.contains_key(&(market.id, side.to_string())) {
             debug!("Already traded {} on {}", side, market.name);
             continue;
         }
@@ -417,18 +971,76 @@ async fn run_cycle(
             continue;
         }
 
+        // Thin market: not enough USDC on the ask side to be worth trading
+        if let Some(min_liquidity) = args
+            .min_liquidity_usdc
+            .and_then(|m| Decimal::try_from(m).ok())
+        {
+            match market.ask_liquidity_usdc(side) {
+                Some(liquidity) if liquidity >= min_liquidity => {}
+                Some(liquidity) => {
+                    info!(
+                        "[SKIP] {} {} ask liquidity ${:.2} < min ${:.2}",
+                        market.name, side, liquidity, min_liquidity
+                    );
+                    continue;
+                }
+                None => {
+                    info!(
+                        "[SKIP] {} {} no depth data, cannot check min liquidity ${:.2}",
+                        market.name, side, min_liquidity
+                    );
+                    continue;
+                }
+            }
+        }
+
+        // Stale snapshot guard: confirm the ask against the live book
+        if args.verify_live_price {
+            let max_divergence =
+                Decimal::try_from(args.max_price_divergence).unwrap_or(Decimal::ZERO);
+            match fetch_live_best_ask(token_id).await {
+                Some(live_ask)
+                    if price_divergence_exceeded(entry_price, live_ask, max_divergence) =>
+                {
+                    info!(
+                        "[SKIP] {} {} live ask ${} > DB ask ${} + ${}",
+                        market.name, side, live_ask, entry_price, max_divergence
+                    );
+                    metrics.record_divergence_skip();
+                    continue;
+                }
+                Some(_) => {}
+                None => {
+                    info!(
+                        "[SKIP] {} {} live ask unavailable, cannot verify DB price",
+                        market.name, side
+                    );
+                    metrics.record_divergence_skip();
+                    continue;
+                }
+            }
+        }
+
         // Calculate fill price with slippage
         let fill_estimate = calculate_fill_price_with_slippage(
             orderbook.as_ref(),
             entry_price,
             position_size / entry_price,
             slippage_pct,
+            args.fill_model,
         );
 
+        // Thin book: skip on excessive slippage even if still under the limit
+        if exceeds_max_slippage(args, &fill_estimate, entry_price, &market.name, side) {
+            metrics.record_slippage_skip();
+            continue;
+        }
+
         // Calculate shares
         let shares = (position_size / fill_estimate.effective_price).round_dp(2);
-        if shares > MAX_SHARES {
-            warn!("Shares {} exceeds max {}", shares, MAX_SHARES);
+        if shares > max_shares {
+            warn!("Shares {} exceeds max {}", shares, max_shares);
             continue;
         }
 
@@ -447,83 +1059,184 @@ async fn run_cycle(
             shares,
         );
 
+        let mut intent = TradeIntent {
+            market_id: market.id,
+            token_id: token_id.clone(),
+            side: side.to_string(),
+            shares,
+            limit_price: entry_price,
+            effective_price: fill_estimate.effective_price,
+            strategy_tag: STRATEGY_TAG.to_string(),
+            mode: TradeMode::from_dry_run(args.dry_run),
+        };
+        info!("[INTENT] {}", intent);
+
         if args.dry_run {
-            let cost = shares * fill_estimate.effective_price;
-            info!(
-                "[DRY RUN] {} {:.2} shares @ ${:.4} -> Win: ${:.2}",
-                side,
-                shares,
-                fill_estimate.effective_price,
-                shares - cost
-            );
+            if args.simulate_latency_ms > 0 {
+                // Price the entry on a later cycle, once the latency has
+                // elapsed; hold the market/side meanwhile like a live order
+                debug!(
+                    "[LATENCY] {} {} fill scheduled in {}ms",
+                    market.name, side, args.simulate_latency_ms
+                );
+                latency_fills.push(LatencyFill {
+                    market: market.clone(),
+                    asset: asset.clone(),
+                    intent,
+                    due_at: Utc::now()
+                        + chrono::Duration::milliseconds(args.simulate_latency_ms as i64),
+                });
+                traded_positions.insert((market.id, side.to_string()), market.end_time);
+                detector.record_trade(&market.condition_id);
+                state_changed = true;
+                continue;
+            }
 
-            portfolio.add_position(SimulatedPosition {
-                market_id: market.id,
-                condition_id: market.condition_id.clone(),
-                market_name: market.name.clone(),
-                market_type: market.market_type.clone(),
-                asset: market.asset.clone(),
-                timeframe: market.timeframe.clone(),
-                yes_token_id: market.yes_token_id.clone(),
-                no_token_id: market.no_token_id.clone(),
-                side: side.to_string(),
-                shares,
+            if !book_dry_run_entry(
+                repo,
+                portfolio,
+                metrics,
+                market,
+                &mut intent,
                 entry_price,
-                best_ask_price: entry_price,
-                effective_fill_price: fill_estimate.effective_price,
-                cost,
-                end_time: market.end_time,
-                created_at: Utc::now(),
-                resolution_retries: 0,
-                last_retry_time: None,
-            });
+                &fill_estimate,
+            )
+            .await
+            {
+                continue;
+            }
 
-            traded_positions.insert((market.id, side.to_string()));
+            traded_positions.insert((market.id, side.to_string()), market.end_time);
             detector.record_trade(&market.condition_id);
+            state_changed = true;
             metrics.record_trade(asset, side);
         } else {
-            // Execute real trade
-            match execute_trade(
+            if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
+                info!(
+                    "[THROTTLE] Skipping {} {}: next order allowed in {}ms",
+                    side,
+                    market.name,
+                    wait.as_millis()
+                );
+                record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
+                continue;
+            }
+
+            // Execute real trade, re-attempting once at the fresh ask if the
+            // book moved past our price
+            let mut order_price = entry_price;
+            let mut result = execute_trade(
                 cached_auth,
                 token_id,
                 shares,
-                entry_price,
+                order_price,
                 side,
                 &market.name,
+                max_shares,
             )
-            .await
-            {
+            .await;
+            throttle.record_order(std::time::Instant::now());
+            if is_price_movement(&result) {
+                let retry = fresh_retry_ask(
+                    repo,
+                    market,
+                    side,
+                    order_price,
+                    detector.max_entry_price,
+                    args.max_orderbook_age,
+                    expiry_seconds,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to refresh {}: {}", market.name, e);
+                    metrics.record_db_error();
+                    None
+                });
+                if let Some(fresh_ask) = retry {
+                    if let Some(wait) = throttle.remaining(std::time::Instant::now()) {
+                        info!(
+                            "[THROTTLE] Not retrying {} {} at {}: next order allowed in {}ms",
+                            side,
+                            market.name,
+                            fmt_price(fresh_ask),
+                            wait.as_millis()
+                        );
+                    } else {
+                        metrics.record_price_retry();
+                        order_price = fresh_ask;
+                        result = execute_trade(
+                            cached_auth,
+                            token_id,
+                            shares,
+                            order_price,
+                            side,
+                            &market.name,
+                            max_shares,
+                        )
+                        .await;
+                        throttle.record_order(std::time::Instant::now());
+                    }
+                }
+            }
+
+            intent.limit_price = order_price;
+            let outcome = if result.is_ok() {
+                TradeOutcome::Placed
+            } else {
+                TradeOutcome::Rejected
+            };
+            record_trade_audit(repo, &intent, outcome).await;
+
+            match result {
                 Ok(order_id) => {
                     info!(
-                        "[SUCCESS] Order {} for {} {} @ ${}",
-                        order_id, side, market.name, entry_price
+                        market = %market.name,
+                        side,
+                        shares = %fmt_shares(shares),
+                        price = %fmt_price(order_price),
+                        order_id = %order_id,
+                        "[SUCCESS] Order placed"
                     );
-                    traded_positions.insert((market.id, side.to_string()));
+                    traded_positions.insert((market.id, side.to_string()), market.end_time);
                     detector.record_trade(&market.condition_id);
-                    metrics.record_trade(asset, side);
-
-                    // Cancel order after 10 seconds if not filled
-                    let order_id_for_cancel = order_id.clone();
+                    state_changed = true;
+
+                    // Poll for fills in the background; trades are recorded
+                    // in metrics once the actual filled size is known
+                    let fill_tx = fill_tx.clone();
+                    let asset = asset.clone();
+                    let side = side.to_string();
+                    let market_name = market.name.clone();
+                    let market_id = market.id;
+                    let token_id = token_id.clone();
+                    let yes_token_id = market.yes_token_id.clone();
+                    let end_time = market.end_time;
+                    let resting = resting.clone();
+                    resting.insert(&order_id);
                     tokio::spawn(async move {
-                        tokio::time::sleep(Duration::from_secs(10)).await;
-                        match cancel_order_standalone(order_id_for_cancel.clone()).await {
-                            Ok(()) => {
-                                info!(
-                                    "[CANCEL] Order {} cancelled after 10s timeout",
-                                    order_id_for_cancel
-                                );
-                            }
-                            Err(e) => {
-                                debug!(
-                                    "Cancel order {} (may already be filled): {}",
-                                    order_id_for_cancel, e
-                                );
-                            }
-                        }
+                        let filled_shares = poll_order_fill(&order_id, shares).await;
+                        resting.remove(&order_id);
+                        let _ = fill_tx.send(FillReport {
+                            order_id,
+                            market_id,
+                            token_id,
+                            price: order_price,
+                            asset,
+                            side,
+                            market_name,
+                            requested_shares: shares,
+                            filled_shares,
+                            yes_token_id,
+                            end_time,
+                        });
                     });
                 }
                 Err(e) => {
-                    error!("[FAILED] Trade execution: {:#}", e);
+                    error!(
+                        "[FAILED] Trade execution ({}): {:#}",
+                        OrderRejection::classify(&format!("{:#}", e)),
+                        e
+                    );
                     metrics.record_error();
                 }
             }
@@ -532,11 +1245,1086 @@ async fn run_cycle(
 
     // Cleanup expired markets from tracking
     let before = traded_positions.len();
-    traded_positions.retain(|(id, _)| markets.iter().any(|m| m.id == *id && m.end_time > now));
+    traded_positions.retain(|_, end_time| *end_time > now);
     if traded_positions.len() < before {
         debug!(
             "Cleaned {} expired positions from tracking",
             before - traded_positions.len()
         );
+        state_changed = true;
+    }
+
+    state_changed
+}
+
+/// Whether `fill` is more than --max-slippage-pct above `best_ask`; logs the skip.
+fn exceeds_max_slippage(
+    args: &Args,
+    fill: &FillEstimate,
+    best_ask: Decimal,
+    market_name: &str,
+    side: &str,
+) -> bool {
+    let Some(max_slippage) = args
+        .max_slippage_pct
+        .and_then(|m| Decimal::try_from(m).ok())
+    else {
+        return false;
+    };
+    if !fill.exceeds_slippage_cap(best_ask, max_slippage) {
+        return false;
+    }
+    info!(
+        "[SKIP] {} {} slippage {:.2}% > max {}% (vwap ${:.3} vs ask ${:.3})",
+        market_name,
+        side,
+        fill.slippage_pct(best_ask),
+        max_slippage,
+        fill.vwap_price,
+        best_ask
+    );
+    true
+}
+
+/// Whether an order attempt failed because the book moved past our price.
+fn is_price_movement(result: &Result<String>) -> bool {
+    match result {
+        Err(e) => OrderRejection::classify(&format!("{:#}", e)).is_price_movement(),
+        Ok(_) => false,
+    }
+}
+
+/// Book a dry-run entry of `intent.shares` at `fill` in the portfolio and
+/// audit it.
+///
+/// Returns false if the portfolio refused it for lack of virtual balance.
+async fn book_dry_run_entry(
+    repo: &dyn MarketRepository,
+    portfolio: &mut DryRunPortfolio,
+    metrics: &mut Metrics,
+    market: &MarketWithOrderbook,
+    intent: &mut TradeIntent,
+    entry_price: Decimal,
+    fill: &FillEstimate,
+) -> bool {
+    let shares = intent.shares;
+    let cost = shares * fill.effective_price;
+    info!(
+        "[DRY RUN] {} {:.2} shares @ ${:.4} -> Win: ${:.2}",
+        intent.side,
+        shares,
+        fill.effective_price,
+        shares - cost
+    );
+
+    let added = portfolio.add_position(SimulatedPosition {
+        market_id: market.id,
+        condition_id: market.condition_id.clone(),
+        market_name: market.name.clone(),
+        market_type: market.market_type.clone(),
+        asset: market.asset.clone(),
+        timeframe: market.timeframe.clone(),
+        yes_token_id: market.yes_token_id.clone(),
+        no_token_id: market.no_token_id.clone(),
+        side: intent.side.clone(),
+        shares,
+        entry_price,
+        best_ask_price: entry_price,
+        effective_fill_price: fill.effective_price,
+        cost,
+        last_price: entry_price,
+        end_time: market.end_time,
+        created_at: Utc::now(),
+        resolution_retries: 0,
+        last_retry_time: None,
+    });
+    if !added {
+        metrics.record_balance_skip();
+        record_trade_audit(repo, intent, TradeOutcome::Skipped).await;
+        return false;
+    }
+    intent.effective_price = fill.effective_price;
+    record_trade_audit(repo, intent, TradeOutcome::Filled).await;
+    true
+}
+
+/// A dry-run entry held back by `--simulate-latency-ms` before it is priced,
+/// approximating the gap between signal and fill a live order races.
+struct LatencyFill {
+    /// Snapshot the signal fired on, used if the market drops out of later cycles
+    market: MarketWithOrderbook,
+    asset: String,
+    intent: TradeIntent,
+    due_at: DateTime<Utc>,
+}
+
+/// Price and book the delayed dry-run entries whose latency has elapsed.
+///
+/// Each entry is re-priced against this cycle's snapshot of its market: the
+/// entry price becomes the ask then, and shares are re-sized from
+/// `position_size` at the new fill price. An ask that has moved past
+/// `max_price` misses, as a live order would, and frees the market/side.
+/// Returns whether any traded position was released.
+#[allow(clippy::too_many_arguments)]
+async fn fill_due_latency_entries(
+    pending: &mut Vec<LatencyFill>,
+    markets: &[MarketWithOrderbook],
+    repo: &dyn MarketRepository,
+    portfolio: &mut DryRunPortfolio,
+    metrics: &mut Metrics,
+    traded_positions: &mut TradedPositions,
+    args: &Args,
+    position_size: Decimal,
+    max_shares: Decimal,
+    max_price: Decimal,
+    slippage_pct: Decimal,
+    now: DateTime<Utc>,
+) -> bool {
+    let (due, waiting): (Vec<_>, Vec<_>) = pending.drain(..).partition(|f| f.due_at <= now);
+    *pending = waiting;
+
+    let mut released = false;
+    for LatencyFill {
+        market,
+        asset,
+        mut intent,
+        ..
+    } in due
+    {
+        let side = intent.side.clone();
+        let fresh = markets
+            .iter()
+            .find(|m| m.id == market.id)
+            .unwrap_or(&market);
+
+        let priced = match fresh.best_ask(&side) {
+            Some(ask) if ask <= max_price => {
+                let asks = match side.as_str() {
+                    "YES" => fresh.yes_asks.as_ref(),
+                    _ => fresh.no_asks.as_ref(),
+                };
+                let fill = calculate_fill_price_with_slippage(
+                    asks,
+                    ask,
+                    position_size / ask,
+                    slippage_pct,
+                    args.fill_model,
+                );
+                let shares = (position_size / fill.effective_price).round_dp(2);
+                if exceeds_max_slippage(args, &fill, ask, &market.name, &side) {
+                    metrics.record_slippage_skip();
+                    None
+                } else if shares > max_shares {
+                    warn!("Shares {} exceeds max {}", shares, max_shares);
+                    None
+                } else {
+                    debug!(
+                        "[LATENCY] {} {} fill after {}ms: ${:.4} for {} shares (signal ask ${:.4})",
+                        market.name,
+                        side,
+                        args.simulate_latency_ms,
+                        fill.effective_price,
+                        shares,
+                        intent.limit_price
+                    );
+                    intent.shares = shares;
+                    intent.limit_price = ask;
+                    Some((ask, fill))
+                }
+            }
+            ask => {
+                info!(
+                    "[SKIP] {} {} ask moved to {:?} within {}ms, above max ${:.2}",
+                    market.name, side, ask, args.simulate_latency_ms, max_price
+                );
+                None
+            }
+        };
+
+        let booked = match priced {
+            Some((ask, fill)) => {
+                book_dry_run_entry(repo, portfolio, metrics, fresh, &mut intent, ask, &fill).await
+            }
+            None => {
+                record_trade_audit(repo, &intent, TradeOutcome::Skipped).await;
+                false
+            }
+        };
+        if booked {
+            metrics.record_trade(&asset, &side);
+        } else {
+            traded_positions.remove(&(market.id, side));
+            released = true;
+        }
+    }
+    released
+}
+
+/// Persist cooldowns and traded positions to the state file (errors are logged).
+fn save_state(path: &Path, detector: &SignalDetector, traded_positions: &TradedPositions) {
+    let state = TraderState::capture(detector.export_cooldowns(), traded_positions);
+    if let Err(e) = state.save(path) {
+        warn!("[STATE] Failed to save {}: {:#}", path.display(), e);
+    }
+}
+
+/// Persist a live order's actual fill (errors are logged).
+async fn record_fill(pool: &PgPool, report: &FillReport) {
+    let fill = FillInsert {
+        market_id: report.market_id,
+        order_id: report.order_id.clone(),
+        token_id: report.token_id.clone(),
+        side: report.side.clone(),
+        filled_shares: report.filled_shares,
+        avg_price: report.price,
+        // The order endpoint does not report fees
+        fee: Decimal::ZERO,
+        filled_at: Utc::now(),
+    };
+    if let Err(e) = insert_fill(pool, &fill).await {
+        warn!(
+            "[FILL] Failed to record fill for order {}: {:#}",
+            report.order_id, e
+        );
+    }
+}
+
+/// Poll a live order until it fills or the timeout elapses.
+///
+/// If the order is not fully filled by the timeout, the remainder is cancelled
+/// and the final matched size is re-queried. Returns the filled share count.
+async fn poll_order_fill(order_id: &str, target_shares: Decimal) -> Decimal {
+    let start = std::time::Instant::now();
+    let mut last_known_fill = Decimal::ZERO;
+
+    while start.elapsed() < Duration::from_secs(FILL_POLL_TIMEOUT_SECS) {
+        tokio::time::sleep(Duration::from_millis(FILL_POLL_INTERVAL_MS)).await;
+
+        match query_order_fill_standalone(order_id).await {
+            Ok(filled) => {
+                last_known_fill = filled;
+                if filled >= target_shares {
+                    debug!(
+                        "[POLL] Order {} fully filled: {}/{}",
+                        order_id, filled, target_shares
+                    );
+                    return filled;
+                }
+                debug!(
+                    "[POLL] Order {} filled {}/{}",
+                    order_id, filled, target_shares
+                );
+            }
+            Err(e) => {
+                warn!("[POLL] Failed to query order {}: {:#}", order_id, e);
+            }
+        }
+    }
+
+    // Timed out without a full fill - cancel whatever is left on the book
+    match cancel_order_standalone(order_id.to_string()).await {
+        Ok(()) => {
+            info!(
+                "[CANCEL] Order {} cancelled after {}s ({}/{} filled)",
+                order_id, FILL_POLL_TIMEOUT_SECS, last_known_fill, target_shares
+            );
+        }
+        Err(e) => {
+            debug!("Cancel order {} (may already be filled): {}", order_id, e);
+        }
+    }
+
+    // Fills can land between the last poll and the cancel
+    match query_order_fill_standalone(order_id).await {
+        Ok(filled) => filled,
+        Err(e) => {
+            warn!(
+                "[POLL] Final fill query failed for order {}, using last known {}: {:#}",
+                order_id, last_known_fill, e
+            );
+            last_known_fill
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{BinanceBookTicker, BinanceKline, BinanceTrade, InMemoryMarketRepository};
+    use uuid::Uuid;
+
+    fn updown_market(asset: &str, expires_in_secs: i64) -> MarketWithOrderbook {
+        let now = Utc::now();
+        MarketWithOrderbook {
+            id: Uuid::new_v4(),
+            condition_id: format!("cond-{}", asset),
+            market_type: "up_down".to_string(),
+            asset: asset.to_string(),
+            timeframe: "15m".to_string(),
+            yes_token_id: "yes-token".to_string(),
+            no_token_id: "no-token".to_string(),
+            name: format!("{} Up or Down", asset),
+            end_time: now + chrono::Duration::seconds(expires_in_secs),
+            is_active: true,
+            yes_best_ask: Some(dec!(0.55)),
+            yes_best_bid: Some(dec!(0.53)),
+            no_best_ask: Some(dec!(0.47)),
+            no_best_bid: Some(dec!(0.45)),
+            yes_asks: None,
+            no_asks: None,
+            yes_bids: None,
+            no_bids: None,
+            captured_at: now,
+        }
+    }
+
+    /// Klines rising from `start` by `step` per minute.
+    fn rising_klines(symbol: &str, count: i64, start: Decimal, step: Decimal) -> KlineBuffer {
+        let mut buffer = KlineBuffer::new(count as usize + 2);
+        add_rising_klines(&mut buffer, symbol, count, start, step);
+        buffer
+    }
+
+    fn add_rising_klines(
+        buffer: &mut KlineBuffer,
+        symbol: &str,
+        count: i64,
+        start: Decimal,
+        step: Decimal,
+    ) {
+        let base = Utc::now() - chrono::Duration::minutes(count);
+        for i in 0..count {
+            let open = start + step * Decimal::from(i);
+            buffer.add(BinanceKline {
+                symbol: symbol.to_string(),
+                open_time: base + chrono::Duration::minutes(i),
+                close_time: base + chrono::Duration::minutes(i + 1),
+                open,
+                high: open + step,
+                low: open,
+                close: open + step,
+                volume: dec!(1),
+                is_closed: true,
+                timestamp: base + chrono::Duration::minutes(i + 1),
+            });
+        }
+    }
+
+    #[test]
+    fn test_validate_args_rejects_contradictory_flags() {
+        let parse = |extra: &[&str]| {
+            let mut argv = vec!["momentum-trader"];
+            argv.extend_from_slice(extra);
+            validate_args(&Args::parse_from(argv))
+        };
+
+        assert!(parse(&[]).is_ok());
+        assert!(parse(&["--trend-confirm-secs", "30"]).is_ok());
+        assert!(parse(&["--volume-confirm-secs", "10", "--min-volume-share", "0.6"]).is_ok());
+        assert!(parse(&["--momentum-windows", "1:0.2,5:0.5,15:0.3"]).is_ok());
+
+        let invalid: &[&[&str]] = &[
+            &["--min-expiry-minutes", "12", "--max-expiry-minutes", "10"],
+            &["--max-expiry-minutes", "0", "--min-expiry-minutes", "0"],
+            &["--min-momentum", "1.5"],
+            &["--lookback-minutes", "0"],
+            &["--lookback-5m", "0"],
+            &["--lookback-15m", "0"],
+            &["--trend-confirm-secs", "0"],
+            &["--volume-confirm-secs", "0"],
+            &["--min-volume-share", "1.5"],
+            &["--momentum-windows", "1:0.5,1:0.5"],
+            &["--momentum-windows", "5:0"],
+            &["--max-entry-price", "1.2"],
+            &["--position-size", "0"],
+            &["--slippage-pct", "150"],
+            &["--max-slippage-pct=-1"],
+            &["--min-liquidity-usdc", "0"],
+            &["--max-shares", "0"],
+            &["--dry-run-balance", "0"],
+            &["--max-markets-per-cycle", "0"],
+        ];
+        for flags in invalid {
+            assert!(parse(flags).is_err(), "expected {:?} to be rejected", flags);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_daily_stop_blocks_new_entries() {
+        let args = Args::parse_from(["momentum-trader", "--dry-run", "--assets", "BTC"]);
+        let assets = vec!["BTC".to_string()];
+
+        let repo = InMemoryMarketRepository::new();
+        repo.insert_market(updown_market("BTC", 300));
+        let kline_buffer = rising_klines("BTCUSDT", 5, dec!(100000), dec!(200));
+
+        let mut daily_stop = DailyPnlStop::new(None, Some(dec!(10)));
+        daily_stop.update(Decimal::ZERO, Utc::now());
+        assert!(daily_stop.update(dec!(-12), Utc::now()).is_some());
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let mut traded_positions = TradedPositions::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut None,
+            &mut TradeThrottle::disabled(),
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &daily_stop,
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+
+        // The signal fires, but the day's loss bound keeps it from trading
+        assert!(portfolio.positions.is_empty());
+        assert!(traded_positions.is_empty());
+        assert_eq!(metrics.total_trades(), 0);
+        assert!(repo.audits().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_skips_thin_book_over_slippage_cap() {
+        // $5 at a 0.55 ask wants ~9.09 shares: 2 @ 0.55, the rest @ 0.65,
+        // about 14.5% over the best ask
+        let mut market = updown_market("BTC", 300);
+        market.yes_asks = Some(serde_json::json!([
+            {"price": "0.55", "size": "2"},
+            {"price": "0.65", "size": "100"},
+        ]));
+
+        for (cap, trades) in [("14", false), ("15", true)] {
+            let args = Args::parse_from([
+                "momentum-trader",
+                "--dry-run",
+                "--assets",
+                "BTC",
+                "--max-slippage-pct",
+                cap,
+            ]);
+            let repo = InMemoryMarketRepository::new();
+            repo.insert_market(market.clone());
+            let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+            let mut portfolio = DryRunPortfolio::new();
+            let mut traded_positions = TradedPositions::new();
+            latency_cycle(
+                &repo,
+                &args,
+                &mut detector,
+                &mut portfolio,
+                &mut traded_positions,
+                &mut Vec::new(),
+            )
+            .await;
+
+            assert_eq!(
+                portfolio.get_position(&market.id).is_some(),
+                trades,
+                "cap {}",
+                cap
+            );
+            assert_eq!(traded_positions.is_empty(), !trades, "cap {}", cap);
+        }
+    }
+
+    /// Run one dry-run cycle of BTC momentum against `repo`.
+    async fn latency_cycle(
+        repo: &InMemoryMarketRepository,
+        args: &Args,
+        detector: &mut SignalDetector,
+        portfolio: &mut DryRunPortfolio,
+        traded_positions: &mut TradedPositions,
+        latency_fills: &mut Vec<LatencyFill>,
+    ) {
+        let kline_buffer = rising_klines("BTCUSDT", 5, dec!(100000), dec!(200));
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+        run_cycle(
+            repo,
+            &["BTC".to_string()],
+            args,
+            &kline_buffer,
+            detector,
+            &mut Metrics::new(),
+            portfolio,
+            &mut None,
+            &mut TradeThrottle::disabled(),
+            traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            latency_fills,
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_simulated_latency_reprices_on_a_later_cycle() {
+        let args = Args::parse_from([
+            "momentum-trader",
+            "--dry-run",
+            "--assets",
+            "BTC",
+            "--fill-model",
+            "best-only",
+            "--simulate-latency-ms",
+            "1",
+        ]);
+        let repo = InMemoryMarketRepository::new();
+        let market = updown_market("BTC", 300);
+        repo.insert_market(market.clone());
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut portfolio = DryRunPortfolio::new();
+        let mut traded_positions = TradedPositions::new();
+        let mut latency_fills = Vec::new();
+        latency_cycle(
+            &repo,
+            &args,
+            &mut detector,
+            &mut portfolio,
+            &mut traded_positions,
+            &mut latency_fills,
+        )
+        .await;
+
+        // Scheduled, not filled: the cycle did not block on the latency
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(latency_fills.len(), 1);
+        assert!(traded_positions.contains_key(&(market.id, "YES".to_string())));
+
+        // The ask moves before the fill lands
+        let mut repriced = market.clone();
+        repriced.yes_best_ask = Some(dec!(0.625));
+        repriced.captured_at = Utc::now();
+        repo.insert_market(repriced);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        latency_cycle(
+            &repo,
+            &args,
+            &mut detector,
+            &mut portfolio,
+            &mut traded_positions,
+            &mut latency_fills,
+        )
+        .await;
+
+        // Priced at the new ask, with shares re-sized from the $5 position
+        assert!(latency_fills.is_empty());
+        let position = portfolio
+            .get_position(&market.id)
+            .expect("delayed fill should open a position");
+        assert_eq!(position.entry_price, dec!(0.625));
+        assert_eq!(position.shares, dec!(8));
+        let audits = repo.audits();
+        assert_eq!(audits.len(), 1);
+        assert_eq!(audits[0].0.limit_price, dec!(0.625));
+        assert_eq!(audits[0].0.shares, dec!(8));
+        assert_eq!(audits[0].1, TradeOutcome::Filled);
+    }
+
+    #[tokio::test]
+    async fn test_simulated_latency_misses_when_ask_runs_past_max() {
+        let args = Args::parse_from([
+            "momentum-trader",
+            "--dry-run",
+            "--assets",
+            "BTC",
+            "--simulate-latency-ms",
+            "1",
+        ]);
+        let repo = InMemoryMarketRepository::new();
+        let market = updown_market("BTC", 300);
+        repo.insert_market(market.clone());
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut portfolio = DryRunPortfolio::new();
+        let mut traded_positions = TradedPositions::new();
+        let mut latency_fills = Vec::new();
+        latency_cycle(
+            &repo,
+            &args,
+            &mut detector,
+            &mut portfolio,
+            &mut traded_positions,
+            &mut latency_fills,
+        )
+        .await;
+
+        let mut repriced = market.clone();
+        repriced.yes_best_ask = Some(dec!(0.80));
+        repriced.captured_at = Utc::now();
+        repo.insert_market(repriced);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        latency_cycle(
+            &repo,
+            &args,
+            &mut detector,
+            &mut portfolio,
+            &mut traded_positions,
+            &mut latency_fills,
+        )
+        .await;
+
+        // Missed like a live order would be; the market/side is free again
+        assert!(portfolio.positions.is_empty());
+        assert!(latency_fills.is_empty());
+        assert!(!traded_positions.contains_key(&(market.id, "YES".to_string())));
+        let audits = repo.audits();
+        assert_eq!(audits.len(), 1);
+        assert_eq!(audits[0].1, TradeOutcome::Skipped);
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_dry_run_opens_position_on_momentum() {
+        let args = Args::parse_from(["momentum-trader", "--dry-run", "--assets", "BTC"]);
+        let assets = vec!["BTC".to_string()];
+
+        let repo = InMemoryMarketRepository::new();
+        let market = updown_market("BTC", 300);
+        repo.insert_market(market.clone());
+
+        // +1% over 5 minutes -> Up momentum -> buy YES
+        let kline_buffer = rising_klines("BTCUSDT", 5, dec!(100000), dec!(200));
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let mut cached_auth = None;
+        let mut throttle = TradeThrottle::disabled();
+        let mut traded_positions = TradedPositions::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut cached_auth,
+            &mut throttle,
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+
+        let position = portfolio
+            .get_position(&market.id)
+            .expect("should open a position");
+        assert_eq!(position.side, "YES");
+        assert_eq!(position.entry_price, dec!(0.55));
+        assert!(traded_positions.contains_key(&(market.id, "YES".to_string())));
+        assert_eq!(metrics.total_trades(), 1);
+
+        // The fill is audited as a dry-run momentum intent
+        let audits = repo.audits();
+        assert_eq!(audits.len(), 1);
+        assert_eq!(audits[0].0.strategy_tag, "momentum");
+        assert_eq!(audits[0].0.mode, TradeMode::DryRun);
+        assert_eq!(audits[0].0.limit_price, dec!(0.55));
+        assert_eq!(audits[0].1, TradeOutcome::Filled);
+
+        // Next cycle marks the open position to the latest YES bid
+        let mut repriced = market.clone();
+        repriced.yes_best_bid = Some(dec!(0.62));
+        repriced.captured_at = Utc::now();
+        repo.insert_market(repriced);
+
+        // Same market/side is not traded twice
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut cached_auth,
+            &mut throttle,
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+        assert_eq!(portfolio.positions.len(), 1);
+        assert_eq!(portfolio.positions[0].last_price, dec!(0.62));
+    }
+
+    /// Run one dry-run cycle while the YES ask moves from 0.55 to 0.60 10ms
+    /// after the signal; returns the simulated effective fill price.
+    async fn fill_with_moving_book(latency_ms: &str) -> Decimal {
+        let args = Args::parse_from([
+            "momentum-trader",
+            "--dry-run",
+            "--assets",
+            "BTC",
+            "--simulate-latency-ms",
+            latency_ms,
+        ]);
+        let assets = vec!["BTC".to_string()];
+
+        let repo = InMemoryMarketRepository::new();
+        let market = updown_market("BTC", 300);
+        repo.insert_market(market.clone());
+        let kline_buffer = rising_klines("BTCUSDT", 5, dec!(100000), dec!(200));
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let mut cached_auth = None;
+        let mut throttle = TradeThrottle::disabled();
+        let mut traded_positions = TradedPositions::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        let mut moved = market.clone();
+        moved.yes_best_ask = Some(dec!(0.60));
+        let move_book = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            moved.captured_at = Utc::now();
+            repo.insert_market(moved);
+        };
+        let cycle = run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut cached_auth,
+            &mut throttle,
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        );
+        tokio::join!(cycle, move_book);
+
+        portfolio
+            .get_position(&market.id)
+            .expect("should open a position")
+            .effective_fill_price
+    }
+
+    #[tokio::test]
+    async fn test_simulated_latency_fills_against_moved_book() {
+        let instant = fill_with_moving_book("0").await;
+        let delayed = fill_with_moving_book("50").await;
+
+        // Zero latency fills at the signal snapshot; with latency the order
+        // lands after the ask moved up and pays more
+        assert!(
+            delayed > instant,
+            "latency fill {} should exceed instant fill {}",
+            delayed,
+            instant
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_cap_prioritizes_soonest_expiry() {
+        let args = Args::parse_from([
+            "momentum-trader",
+            "--dry-run",
+            "--assets",
+            "BTC,ETH",
+            "--max-markets-per-cycle",
+            "1",
+        ]);
+        let assets = vec!["BTC".to_string(), "ETH".to_string()];
+
+        // Both assets signal, but ETH's market expires first
+        let repo = InMemoryMarketRepository::new();
+        let btc = updown_market("BTC", 500);
+        let eth = updown_market("ETH", 200);
+        repo.insert_market(btc.clone());
+        repo.insert_market(eth.clone());
+        let mut kline_buffer = rising_klines("BTCUSDT", 5, dec!(100000), dec!(200));
+        add_rising_klines(&mut kline_buffer, "ETHUSDT", 5, dec!(3000), dec!(6));
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut None,
+            &mut TradeThrottle::disabled(),
+            &mut TradedPositions::new(),
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+
+        // Only the soonest-expiring market was processed
+        assert_eq!(portfolio.positions.len(), 1);
+        assert!(portfolio.get_position(&eth.id).is_some());
+        assert!(portfolio.get_position(&btc.id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_side_filter_suppresses_excluded_side() {
+        let args = Args::parse_from([
+            "momentum-trader",
+            "--dry-run",
+            "--assets",
+            "BTC",
+            "--side-filter",
+            "no-only",
+        ]);
+        let assets = vec!["BTC".to_string()];
+
+        let repo = InMemoryMarketRepository::new();
+        let market = updown_market("BTC", 300);
+        repo.insert_market(market.clone());
+
+        // Up momentum -> YES signal, which the filter excludes
+        let kline_buffer = rising_klines("BTCUSDT", 5, dec!(100000), dec!(200));
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let mut cached_auth = None;
+        let mut throttle = TradeThrottle::disabled();
+        let mut traded_positions = TradedPositions::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut cached_auth,
+            &mut throttle,
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+
+        // The signal is still counted, but nothing is traded
+        assert_eq!(metrics.total_signals(), 1);
+        assert_eq!(metrics.total_trades(), 0);
+        assert!(portfolio.positions.is_empty());
+        assert!(traded_positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_trend_filter_skips_reversal() {
+        let args = Args::parse_from([
+            "momentum-trader",
+            "--dry-run",
+            "--assets",
+            "BTC",
+            "--trend-confirm-secs",
+            "3",
+        ]);
+        let assets = vec!["BTC".to_string()];
+
+        let repo = InMemoryMarketRepository::new();
+        repo.insert_market(updown_market("BTC", 300));
+
+        // Lookback momentum is still Up, but the last 3s of ticks fell
+        let mut kline_buffer =
+            rising_klines("BTCUSDT", 5, dec!(100000), dec!(200)).with_tick_history(4);
+        let now = Utc::now();
+        for (secs_ago, mid) in [(4, dec!(101000)), (0, dec!(100900))] {
+            kline_buffer.update_price(&BinanceBookTicker {
+                symbol: "BTCUSDT".to_string(),
+                best_bid: mid,
+                best_bid_qty: dec!(1),
+                best_ask: mid,
+                best_ask_qty: dec!(1),
+                timestamp: now - chrono::Duration::seconds(secs_ago),
+            });
+        }
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let mut cached_auth = None;
+        let mut throttle = TradeThrottle::disabled();
+        let mut traded_positions = TradedPositions::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut cached_auth,
+            &mut throttle,
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(metrics.total_trades(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_volume_filter_skips_unbacked_move() {
+        let args = Args::parse_from([
+            "momentum-trader",
+            "--dry-run",
+            "--assets",
+            "BTC",
+            "--volume-confirm-secs",
+            "10",
+        ]);
+        let assets = vec!["BTC".to_string()];
+
+        let repo = InMemoryMarketRepository::new();
+        repo.insert_market(updown_market("BTC", 300));
+
+        // Lookback momentum is Up, but takers have mostly been selling
+        let mut kline_buffer =
+            rising_klines("BTCUSDT", 5, dec!(100000), dec!(200)).with_trade_history(10);
+        let now = Utc::now();
+        for (secs_ago, qty, is_buyer_maker) in [(6, dec!(2), true), (2, dec!(0.5), false)] {
+            kline_buffer.add_trade(&BinanceTrade {
+                symbol: "BTCUSDT".to_string(),
+                price: dec!(101000),
+                qty,
+                is_buyer_maker,
+                trade_time: now - chrono::Duration::seconds(secs_ago),
+            });
+        }
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let mut cached_auth = None;
+        let mut throttle = TradeThrottle::disabled();
+        let mut traded_positions = TradedPositions::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut cached_auth,
+            &mut throttle,
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(metrics.total_trades(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_cycle_skips_without_markets() {
+        let args = Args::parse_from(["momentum-trader", "--dry-run", "--assets", "BTC"]);
+        let assets = vec!["BTC".to_string()];
+        let repo = InMemoryMarketRepository::new();
+        let kline_buffer = rising_klines("BTCUSDT", 5, dec!(100000), dec!(200));
+
+        let mut detector = SignalDetector::new(dec!(0.002), 5, dec!(0.70), 900);
+        let mut metrics = Metrics::new();
+        let mut portfolio = DryRunPortfolio::new();
+        let mut cached_auth = None;
+        let mut throttle = TradeThrottle::disabled();
+        let mut traded_positions = TradedPositions::new();
+        let (fill_tx, _fill_rx) = mpsc::unbounded_channel();
+
+        run_cycle(
+            &repo,
+            &assets,
+            &args,
+            &kline_buffer,
+            &mut detector,
+            &mut metrics,
+            &mut portfolio,
+            &mut cached_auth,
+            &mut throttle,
+            &mut traded_positions,
+            &mut MarketRotation::default(),
+            &DailyPnlStop::new(None, None),
+            &mut Vec::new(),
+            &fill_tx,
+            &RestingOrders::new(),
+            dec!(5),
+            dec!(20),
+        )
+        .await;
+
+        assert!(portfolio.positions.is_empty());
+        assert_eq!(metrics.total_signals(), 0);
     }
 }
@@ -3,8 +3,11 @@
 use std::collections::HashMap;
 use std::time::Instant;
 
+use rust_decimal::Decimal;
 use tracing::info;
 
+use common::{LiveSettlement, SettlementTimeoutPolicy};
+
 /// Metrics tracker for the momentum trader.
 pub struct Metrics {
     start_time: Instant,
@@ -14,6 +17,36 @@ pub struct Metrics {
     trades: HashMap<String, u32>,
     /// Trades by side (YES/NO)
     trades_by_side: HashMap<String, u32>,
+    /// Signals not traded because the fill slippage exceeded --max-slippage-pct
+    slippage_skips: u32,
+    /// Signals not traded because --side-filter excludes their side
+    bias_skips: u32,
+    /// Dry-run trades refused because --dry-run-balance was exhausted
+    balance_skips: u32,
+    /// Signals not traded because the live ask diverged from the DB snapshot
+    divergence_skips: u32,
+    /// Markets skipped because their depth snapshot was malformed
+    invalid_depth_skips: u32,
+    /// Markets left for a later cycle by --max-markets-per-cycle
+    markets_deferred: u64,
+    /// Orders re-attempted at a fresh ask after a price-movement rejection
+    price_retries: u32,
+    /// Shares requested across live orders
+    requested_shares: Decimal,
+    /// Shares actually filled across live orders
+    filled_shares: Decimal,
+    /// Live orders that filled only partially before cancel
+    partial_fills: u32,
+    /// Live orders cancelled with no fill
+    unfilled_orders: u32,
+    /// Settlement wins (expired positions that won)
+    settled_wins: u32,
+    /// Settlement losses (expired positions that lost)
+    settled_losses: u32,
+    /// Total P&L from expired position settlements
+    settled_pnl: Decimal,
+    /// Settlements that timed out under the `unknown` policy (excluded from P&L)
+    settled_unknown: u32,
     /// Total errors
     errors: u32,
     /// Database errors
@@ -27,6 +60,21 @@ impl Metrics {
             signals: HashMap::new(),
             trades: HashMap::new(),
             trades_by_side: HashMap::new(),
+            slippage_skips: 0,
+            bias_skips: 0,
+            balance_skips: 0,
+            divergence_skips: 0,
+            invalid_depth_skips: 0,
+            markets_deferred: 0,
+            price_retries: 0,
+            requested_shares: Decimal::ZERO,
+            filled_shares: Decimal::ZERO,
+            partial_fills: 0,
+            unfilled_orders: 0,
+            settled_wins: 0,
+            settled_losses: 0,
+            settled_pnl: Decimal::ZERO,
+            settled_unknown: 0,
             errors: 0,
             db_errors: 0,
         }
@@ -43,6 +91,87 @@ impl Metrics {
         *self.trades_by_side.entry(side.to_string()).or_insert(0) += 1;
     }
 
+    /// Record a signal skipped for excessive slippage.
+    pub fn record_slippage_skip(&mut self) {
+        self.slippage_skips += 1;
+    }
+
+    /// Record a signal suppressed by the side filter.
+    pub fn record_bias_skip(&mut self) {
+        self.bias_skips += 1;
+    }
+
+    /// Record a dry-run trade refused by --dry-run-balance.
+    pub fn record_balance_skip(&mut self) {
+        self.balance_skips += 1;
+    }
+
+    /// Record a signal skipped by --verify-live-price.
+    pub fn record_divergence_skip(&mut self) {
+        self.divergence_skips += 1;
+    }
+
+    /// Record an order re-attempted after the price moved.
+    pub fn record_price_retry(&mut self) {
+        self.price_retries += 1;
+    }
+
+    /// Record the final fill of a live order.
+    pub fn record_fill(&mut self, requested: Decimal, filled: Decimal) {
+        self.requested_shares += requested;
+        self.filled_shares += filled;
+        if filled.is_zero() {
+            self.unfilled_orders += 1;
+        } else if filled < requested {
+            self.partial_fills += 1;
+        }
+    }
+
+    /// Record a live position settled by `resolve_live_settlements`.
+    pub fn record_live_settlement(&mut self, settled: LiveSettlement) {
+        match settled {
+            LiveSettlement::Resolved { pnl, won } => {
+                self.settled_pnl += pnl;
+                if won {
+                    self.settled_wins += 1;
+                } else {
+                    self.settled_losses += 1;
+                }
+            }
+            LiveSettlement::Ambiguous { pnl } => self.settled_pnl += pnl,
+            LiveSettlement::TimedOut { policy, cost } => {
+                self.record_settlement_timeout(policy, cost)
+            }
+        }
+    }
+
+    /// Record an expired position whose resolution never arrived.
+    fn record_settlement_timeout(&mut self, policy: SettlementTimeoutPolicy, cost: Decimal) {
+        match policy.timeout_pnl(cost) {
+            Some(pnl) if pnl < Decimal::ZERO => {
+                self.settled_pnl += pnl;
+                self.settled_losses += 1;
+            }
+            Some(pnl) => self.settled_pnl += pnl,
+            None => self.settled_unknown += 1,
+        }
+    }
+
+    /// P&L realized so far by settlements (positions are held to expiry).
+    pub fn realized_pnl(&self) -> Decimal {
+        self.settled_pnl
+    }
+
+    /// Record markets skipped for a malformed depth snapshot.
+    pub fn record_invalid_depth(&mut self, count: usize) {
+        self.invalid_depth_skips += count as u32;
+    }
+
+    /// Record markets deferred to a later cycle by the per-cycle cap.
+    pub fn record_deferred(&mut self, count: usize) {
+        self.markets_deferred += count as u64;
+    }
+
     /// Record an error.
     pub fn record_error(&mut self) {
         self.errors += 1;
@@ -90,6 +219,54 @@ impl Metrics {
             "║  YES / NO:          {:>4} / {:<4}                             ║",
             yes_trades, no_trades
         );
+        info!(
+            "║  Slippage Skips:    {:>8}                                 ║",
+            self.slippage_skips
+        );
+        info!(
+            "║  Bias Skips:        {:>8}                                 ║",
+            self.bias_skips
+        );
+        info!(
+            "║  Balance Skips:     {:>8}                                 ║",
+            self.balance_skips
+        );
+        info!(
+            "║  Divergence Skips:  {:>8}                                 ║",
+            self.divergence_skips
+        );
+        info!(
+            "║  Invalid Depth:     {:>8}                                 ║",
+            self.invalid_depth_skips
+        );
+        info!(
+            "║  Markets Deferred:  {:>8}                                 ║",
+            self.markets_deferred
+        );
+        info!(
+            "║  Price Retries:     {:>8}                                 ║",
+            self.price_retries
+        );
+        info!(
+            "║  Filled Shares:     {:>8.2} / {:<8.2}                      ║",
+            self.filled_shares, self.requested_shares
+        );
+        info!(
+            "║  Partial / Unfilled:{:>4} / {:<4}                             ║",
+            self.partial_fills, self.unfilled_orders
+        );
+        info!(
+            "║  Settled W / L:     {:>4} / {:<4}                             ║",
+            self.settled_wins, self.settled_losses
+        );
+        info!(
+            "║  Timed Out (unk):   {:>8}                                 ║",
+            self.settled_unknown
+        );
+        info!(
+            "║  Settlement P&L:    ${:<8.2}                                ║",
+            self.settled_pnl
+        );
         info!(
             "║  Errors:            {:>8}                                 ║",
             self.errors
@@ -0,0 +1,144 @@
+//! Persistence of cooldowns and traded positions across restarts.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// (market_id, side) -> market end time.
+pub type TradedPositions = HashMap<(Uuid, String), DateTime<Utc>>;
+
+/// A traded (market, side) pair and when its market expires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedPosition {
+    pub market_id: Uuid,
+    pub side: String,
+    pub end_time: DateTime<Utc>,
+}
+
+/// Trader state written to the state file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TraderState {
+    /// condition_id -> last trade time
+    pub cooldowns: HashMap<String, DateTime<Utc>>,
+    pub traded_positions: Vec<TrackedPosition>,
+}
+
+impl TraderState {
+    /// Snapshot the in-memory tracking state.
+    pub fn capture(cooldowns: HashMap<String, DateTime<Utc>>, traded: &TradedPositions) -> Self {
+        Self {
+            cooldowns,
+            traded_positions: traded
+                .iter()
+                .map(|((market_id, side), end_time)| TrackedPosition {
+                    market_id: *market_id,
+                    side: side.clone(),
+                    end_time: *end_time,
+                })
+                .collect(),
+        }
+    }
+
+    /// Load state from `path`. A missing file yields empty state.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file {}", path.display()))?;
+        serde_json::from_str(&data)
+            .with_context(|| format!("Invalid state file {}", path.display()))
+    }
+
+    /// Write state to `path` atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write state file {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to replace state file {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Drop positions whose market has expired and cooldowns older than
+    /// `cooldown_secs`. Returns the number of entries removed.
+    pub fn prune(&mut self, now: DateTime<Utc>, cooldown_secs: u64) -> usize {
+        let before = self.cooldowns.len() + self.traded_positions.len();
+        let cooldown = chrono::Duration::seconds(cooldown_secs as i64);
+
+        self.traded_positions.retain(|p| p.end_time > now);
+        self.cooldowns
+            .retain(|_, traded_at| now - *traded_at < cooldown);
+
+        before - (self.cooldowns.len() + self.traded_positions.len())
+    }
+
+    /// Traded positions as the in-memory lookup map.
+    pub fn traded_map(&self) -> TradedPositions {
+        self.traded_positions
+            .iter()
+            .map(|p| ((p.market_id, p.side.clone()), p.end_time))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_drops_expired_markets_and_cooldowns() {
+        let now = Utc::now();
+        let live = Uuid::new_v4();
+        let mut traded = TradedPositions::new();
+        traded.insert(
+            (live, "YES".to_string()),
+            now + chrono::Duration::minutes(5),
+        );
+        traded.insert(
+            (Uuid::new_v4(), "NO".to_string()),
+            now - chrono::Duration::minutes(1),
+        );
+        let cooldowns = HashMap::from([
+            ("recent".to_string(), now - chrono::Duration::seconds(60)),
+            ("old".to_string(), now - chrono::Duration::seconds(1000)),
+        ]);
+
+        let mut state = TraderState::capture(cooldowns, &traded);
+        assert_eq!(state.prune(now, 900), 2);
+
+        let map = state.traded_map();
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key(&(live, "YES".to_string())));
+        assert!(state.cooldowns.contains_key("recent"));
+        assert!(!state.cooldowns.contains_key("old"));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = std::env::temp_dir().join(format!("momentum-state-{}.json", Uuid::new_v4()));
+        assert!(TraderState::load(&path).unwrap().cooldowns.is_empty());
+
+        let mut traded = TradedPositions::new();
+        let market_id = Uuid::new_v4();
+        let end_time = Utc::now() + chrono::Duration::minutes(10);
+        traded.insert((market_id, "NO".to_string()), end_time);
+        let cooldowns = HashMap::from([("cond".to_string(), Utc::now())]);
+        TraderState::capture(cooldowns, &traded)
+            .save(&path)
+            .unwrap();
+
+        let loaded = TraderState::load(&path).unwrap();
+        assert_eq!(
+            loaded.traded_map().get(&(market_id, "NO".to_string())),
+            Some(&end_time)
+        );
+        assert!(loaded.cooldowns.contains_key("cond"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,536 @@
+//! In-memory orderbook state built from CLOB book snapshots and
+//! price_change deltas, and the writes it produces.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use common::{BookMessage, PriceChange, PriceLevel};
+
+use crate::sink::{side_label, BestPrices, OrderbookSink, SideSnapshot};
+
+/// Per-market record of book hash mismatches.
+///
+/// A book snapshot whose hash differs from the state built by applying
+/// price_change deltas means a delta was lost or misapplied. The snapshot
+/// repairs the book, but a market that keeps drifting has a broken delta
+/// stream and is flagged for re-subscription.
+#[derive(Debug)]
+pub struct DriftTracker {
+    threshold: usize,
+    window: Duration,
+    /// Drift times per market within the window
+    recent: HashMap<Uuid, VecDeque<Instant>>,
+    /// Markets that crossed the threshold and await re-subscription
+    tripped: Vec<Uuid>,
+    /// All drifts seen since startup
+    total_drifts: u64,
+    /// Markets that have drifted at least once since startup
+    drifted_markets: HashSet<Uuid>,
+    /// Re-subscriptions triggered since startup
+    resubscribes: u64,
+}
+
+impl DriftTracker {
+    pub fn new(threshold: usize, window: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            recent: HashMap::new(),
+            tripped: Vec::new(),
+            total_drifts: 0,
+            drifted_markets: HashSet::new(),
+            resubscribes: 0,
+        }
+    }
+
+    /// Record a drift for `market_id` at `now`. Returns true when this drift
+    /// brings the market to the threshold within the window; its count then
+    /// starts over so one broken stream triggers one re-subscription.
+    pub fn record(&mut self, market_id: Uuid, now: Instant) -> bool {
+        self.total_drifts += 1;
+        self.drifted_markets.insert(market_id);
+
+        let times = self.recent.entry(market_id).or_default();
+        times.push_back(now);
+        while times
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > self.window)
+        {
+            times.pop_front();
+        }
+
+        if times.len() < self.threshold {
+            return false;
+        }
+        times.clear();
+        self.resubscribes += 1;
+        if !self.tripped.contains(&market_id) {
+            self.tripped.push(market_id);
+        }
+        true
+    }
+
+    /// Markets flagged for re-subscription since the last call.
+    pub fn take_tripped(&mut self) -> Vec<Uuid> {
+        std::mem::take(&mut self.tripped)
+    }
+
+    /// Drifts seen since startup.
+    pub fn total_drifts(&self) -> u64 {
+        self.total_drifts
+    }
+
+    /// Markets that have drifted at least once since startup.
+    pub fn drifted_market_count(&self) -> usize {
+        self.drifted_markets.len()
+    }
+
+    /// Re-subscriptions triggered since startup.
+    pub fn resubscribes(&self) -> u64 {
+        self.resubscribes
+    }
+}
+
+/// Orderbook state for a single market.
+#[derive(Debug, Default)]
+pub struct MarketOrderbook {
+    yes_asks: Vec<PriceLevel>,
+    yes_bids: Vec<PriceLevel>,
+    no_asks: Vec<PriceLevel>,
+    no_bids: Vec<PriceLevel>,
+    yes_best_ask: Option<Decimal>,
+    yes_best_bid: Option<Decimal>,
+    no_best_ask: Option<Decimal>,
+    no_best_bid: Option<Decimal>,
+    /// Timestamp from Polymarket event (more accurate than DB NOW())
+    event_timestamp: Option<DateTime<Utc>>,
+    /// Hash of YES orderbook for validation against price_change deltas
+    yes_hash: Option<String>,
+    /// Hash of NO orderbook for validation against price_change deltas
+    no_hash: Option<String>,
+    /// True if price_changes were applied since last YES book snapshot
+    yes_has_pending_changes: bool,
+    /// True if price_changes were applied since last NO book snapshot
+    no_has_pending_changes: bool,
+    /// Hash of the last price_change applied to YES, to skip resent deltas
+    yes_last_change_hash: Option<String>,
+    /// Hash of the last price_change applied to NO, to skip resent deltas
+    no_last_change_hash: Option<String>,
+}
+
+impl MarketOrderbook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// (best bid, best ask) for one side.
+    fn best_prices(&self, is_yes: bool) -> (Option<Decimal>, Option<Decimal>) {
+        if is_yes {
+            (self.yes_best_bid, self.yes_best_ask)
+        } else {
+            (self.no_best_bid, self.no_best_ask)
+        }
+    }
+}
+
+/// Apply a price_change delta to the in-memory orderbook.
+/// Updates the specific price level and best prices from the message.
+/// Returns false, leaving the book untouched, when `change` carries the same
+/// hash as the last delta applied to that side (Polymarket resends deltas).
+pub fn apply_price_change(
+    orderbook: &mut MarketOrderbook,
+    change: &PriceChange,
+    is_yes: bool,
+) -> bool {
+    let last_hash = if is_yes {
+        &mut orderbook.yes_last_change_hash
+    } else {
+        &mut orderbook.no_last_change_hash
+    };
+    if change.hash.is_some() && *last_hash == change.hash {
+        return false;
+    }
+    *last_hash = change.hash.clone();
+
+    // Parse price and size
+    let price: Option<Decimal> = change.price.parse().ok();
+    let size: Option<Decimal> = change.size.parse().ok();
+
+    if let (Some(price), Some(size)) = (price, size) {
+        // Determine which side to update
+        let levels = match (is_yes, change.side.as_str()) {
+            (true, "BUY") => &mut orderbook.yes_bids,
+            (true, "SELL") => &mut orderbook.yes_asks,
+            (false, "BUY") => &mut orderbook.no_bids,
+            (false, "SELL") => &mut orderbook.no_asks,
+            _ => return true,
+        };
+
+        // Convert price to string for comparison with PriceLevel
+        let price_str = price.to_string();
+
+        if size.is_zero() {
+            // Remove level
+            levels.retain(|l| l.price != price_str);
+        } else {
+            // Update or insert level
+            if let Some(level) = levels.iter_mut().find(|l| l.price == price_str) {
+                level.size = size.to_string();
+            } else {
+                levels.push(PriceLevel {
+                    price: price_str,
+                    size: size.to_string(),
+                });
+            }
+        }
+    }
+
+    // Update best prices from message (authoritative - Polymarket calculates these)
+    // Mark that we have pending changes for hash validation
+    if is_yes {
+        orderbook.yes_best_bid = change.best_bid.as_ref().and_then(|p| p.parse().ok());
+        orderbook.yes_best_ask = change.best_ask.as_ref().and_then(|p| p.parse().ok());
+        orderbook.yes_hash = change.hash.clone();
+        orderbook.yes_has_pending_changes = true;
+    } else {
+        orderbook.no_best_bid = change.best_bid.as_ref().and_then(|p| p.parse().ok());
+        orderbook.no_best_ask = change.best_ask.as_ref().and_then(|p| p.parse().ok());
+        orderbook.no_hash = change.hash.clone();
+        orderbook.no_has_pending_changes = true;
+    }
+    true
+}
+
+/// Validate that the book snapshot matches our accumulated price_change state.
+/// Only validates if we have pending price_changes since the last book snapshot.
+/// Returns true if valid (no pending changes, or hashes match), false if drift detected.
+fn validate_book(orderbook: &MarketOrderbook, book: &BookMessage, is_yes: bool) -> bool {
+    let (has_pending, expected_hash) = if is_yes {
+        (orderbook.yes_has_pending_changes, &orderbook.yes_hash)
+    } else {
+        (orderbook.no_has_pending_changes, &orderbook.no_hash)
+    };
+
+    // Only validate if we've accumulated price_changes since last book snapshot
+    if !has_pending {
+        return true;
+    }
+
+    match expected_hash {
+        Some(h) if h == &book.hash => true,
+        Some(h) => {
+            warn!(
+                "Hash mismatch for asset {}: expected={} got={} (state drift detected)",
+                book.asset_id, h, book.hash
+            );
+            false
+        }
+        None => true, // No prior hash to compare
+    }
+}
+
+/// Process a single book message and update orderbook state.
+///
+/// The in-memory book keeps every level so price_change deltas apply
+/// correctly; only the stored snapshot is truncated to `max_depth_levels`.
+pub async fn process_book(
+    book: &BookMessage,
+    token_to_market: &HashMap<String, (Uuid, bool)>,
+    orderbooks: &mut HashMap<Uuid, MarketOrderbook>,
+    sink: &dyn OrderbookSink,
+    snapshot_count: &mut usize,
+    max_depth_levels: Option<usize>,
+    drift: &mut DriftTracker,
+) -> Result<()> {
+    if let Some(&(market_id, is_yes)) = token_to_market.get(&book.asset_id) {
+        let orderbook = orderbooks
+            .entry(market_id)
+            .or_insert_with(MarketOrderbook::new);
+
+        // Validate hash against accumulated price_changes (if we have prior state)
+        if !validate_book(orderbook, book, is_yes) {
+            debug!(
+                "Hash mismatch for market {} ({}), resetting to book snapshot",
+                market_id,
+                side_label(is_yes)
+            );
+            if drift.record(market_id, Instant::now()) {
+                warn!(
+                    "Market {} hash drifted {} times within {}s; re-subscribing",
+                    market_id,
+                    drift.threshold,
+                    drift.window.as_secs()
+                );
+            }
+        }
+
+        // Parse the event timestamp from Polymarket (more accurate than DB NOW())
+        let event_ts = parse_event_timestamp(&book.timestamp);
+        if let Some(ts) = event_ts {
+            orderbook.event_timestamp = Some(ts);
+        }
+
+        // Update only the side that changed to prevent stale overwrites
+        // Reset pending changes flag since we're syncing to authoritative book snapshot
+        let (asks, bids, best_ask, best_bid) = if is_yes {
+            orderbook.yes_asks = book.asks.clone();
+            orderbook.yes_bids = book.bids.clone();
+            orderbook.yes_best_ask = book.best_ask();
+            orderbook.yes_best_bid = book.best_bid();
+            orderbook.yes_hash = Some(book.hash.clone());
+            orderbook.yes_has_pending_changes = false; // Reset - synced to book
+            (
+                &orderbook.yes_asks,
+                &orderbook.yes_bids,
+                orderbook.yes_best_ask,
+                orderbook.yes_best_bid,
+            )
+        } else {
+            orderbook.no_asks = book.asks.clone();
+            orderbook.no_bids = book.bids.clone();
+            orderbook.no_best_ask = book.best_ask();
+            orderbook.no_best_bid = book.best_bid();
+            orderbook.no_hash = Some(book.hash.clone());
+            orderbook.no_has_pending_changes = false; // Reset - synced to book
+            (
+                &orderbook.no_asks,
+                &orderbook.no_bids,
+                orderbook.no_best_ask,
+                orderbook.no_best_bid,
+            )
+        };
+
+        // Write only this side, with the event timestamp
+        let snapshot = SideSnapshot {
+            market_id,
+            is_yes,
+            best_ask,
+            best_bid,
+            asks: truncate_depth(asks, max_depth_levels, true),
+            bids: truncate_depth(bids, max_depth_levels, false),
+            event_ts,
+        };
+        sink.write_book(&snapshot).await?;
+        *snapshot_count += 1;
+    }
+    Ok(())
+}
+
+/// Apply one entry of a price_change message, writing the side's best prices
+/// only if they actually changed.
+pub async fn process_price_change(
+    change: &PriceChange,
+    token_to_market: &HashMap<String, (Uuid, bool)>,
+    orderbooks: &mut HashMap<Uuid, MarketOrderbook>,
+    sink: &dyn OrderbookSink,
+    event_ts: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let Some(&(market_id, is_yes)) = token_to_market.get(&change.asset_id) else {
+        return Ok(());
+    };
+    let orderbook = orderbooks
+        .entry(market_id)
+        .or_insert_with(MarketOrderbook::new);
+
+    // Track old best prices to detect changes
+    let (old_best_bid, old_best_ask) = orderbook.best_prices(is_yes);
+
+    // Apply the delta to in-memory state (resent deltas are skipped)
+    if !apply_price_change(orderbook, change, is_yes) {
+        debug!(
+            "Skipping duplicate price_change for asset {} (hash {:?})",
+            change.asset_id, change.hash
+        );
+        return Ok(());
+    }
+
+    // Only write if best prices actually changed
+    let (best_bid, best_ask) = orderbook.best_prices(is_yes);
+    if old_best_bid != best_bid || old_best_ask != best_ask {
+        sink.write_best_prices(&BestPrices {
+            market_id,
+            is_yes,
+            best_ask,
+            best_bid,
+            event_ts,
+        })
+        .await?;
+        debug!(
+            "Price change updated best prices for market {} ({})",
+            market_id,
+            side_label(is_yes)
+        );
+    }
+    Ok(())
+}
+
+/// Parse Polymarket timestamp (Unix millis as string) to DateTime<Utc>
+pub fn parse_event_timestamp(ts: &str) -> Option<DateTime<Utc>> {
+    // Polymarket sends Unix timestamp in milliseconds as a string
+    ts.parse::<i64>()
+        .ok()
+        .and_then(|millis| DateTime::from_timestamp_millis(millis))
+}
+
+/// Keep the best `max_levels` price levels for storage: lowest asks first, or
+/// highest bids first. Levels with unparseable prices sort last. `None` keeps
+/// every level in its original order.
+fn truncate_depth(
+    levels: &[PriceLevel],
+    max_levels: Option<usize>,
+    is_asks: bool,
+) -> Vec<PriceLevel> {
+    let max_levels = match max_levels {
+        Some(n) => n,
+        None => return levels.to_vec(),
+    };
+
+    let mut sorted = levels.to_vec();
+    sorted.sort_by(|a, b| match (a.price_decimal(), b.price_decimal()) {
+        (Some(pa), Some(pb)) if is_asks => pa.cmp(&pb),
+        (Some(pa), Some(pb)) => pb.cmp(&pa),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    sorted.truncate(max_levels);
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(prices: &[&str]) -> Vec<PriceLevel> {
+        prices
+            .iter()
+            .map(|p| PriceLevel {
+                price: p.to_string(),
+                size: "10".to_string(),
+            })
+            .collect()
+    }
+
+    fn prices(levels: &[PriceLevel]) -> Vec<&str> {
+        levels.iter().map(|l| l.price.as_str()).collect()
+    }
+
+    fn price_change(price: &str, size: &str, side: &str, hash: &str) -> PriceChange {
+        PriceChange {
+            asset_id: "yes-token".to_string(),
+            price: price.to_string(),
+            size: size.to_string(),
+            side: side.to_string(),
+            best_bid: Some("0.48".to_string()),
+            best_ask: Some("0.52".to_string()),
+            hash: Some(hash.to_string()),
+        }
+    }
+
+    fn book_state(orderbook: &MarketOrderbook) -> String {
+        format!("{:?}", orderbook)
+    }
+
+    #[test]
+    fn test_duplicate_price_change_is_ignored() {
+        let mut orderbook = MarketOrderbook::new();
+        orderbook.yes_bids = levels(&["0.48", "0.47", "0.45"]);
+        orderbook.yes_asks = levels(&["0.52", "0.55"]);
+
+        // Remove the 0.47 bid, then receive the same delta again
+        let removal = price_change("0.47", "0", "BUY", "hash-1");
+        assert!(apply_price_change(&mut orderbook, &removal, true));
+        let after_first = book_state(&orderbook);
+        assert_eq!(prices(&orderbook.yes_bids), vec!["0.48", "0.45"]);
+
+        assert!(!apply_price_change(&mut orderbook, &removal, true));
+        assert_eq!(book_state(&orderbook), after_first);
+
+        // A new delta still applies, and the same hash on the NO side is independent
+        let insert = price_change("0.53", "25", "SELL", "hash-2");
+        assert!(apply_price_change(&mut orderbook, &insert, true));
+        assert_eq!(prices(&orderbook.yes_asks), vec!["0.52", "0.55", "0.53"]);
+        assert!(apply_price_change(&mut orderbook, &insert, false));
+        assert_eq!(prices(&orderbook.no_asks), vec!["0.53"]);
+
+        // Deltas without a hash can't be deduplicated and always apply
+        let mut unhashed = price_change("0.44", "5", "BUY", "");
+        unhashed.hash = None;
+        assert!(apply_price_change(&mut orderbook, &unhashed, true));
+        assert!(apply_price_change(&mut orderbook, &unhashed, true));
+    }
+
+    #[test]
+    fn test_drift_tracker_trips_resubscribe_at_threshold() {
+        let mut drift = DriftTracker::new(3, Duration::from_secs(60));
+        let market = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let start = std::time::Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // Drifts spread wider than the window never accumulate
+        assert!(!drift.record(market, at(0)));
+        assert!(!drift.record(market, at(61)));
+        assert!(!drift.record(market, at(122)));
+
+        // Another market's drifts are counted separately
+        assert!(!drift.record(other, at(123)));
+
+        // The third drift within 60s trips the resubscribe decision
+        assert!(!drift.record(market, at(150)));
+        assert!(drift.record(market, at(160)));
+        assert_eq!(drift.take_tripped(), vec![market]);
+        assert!(drift.take_tripped().is_empty());
+
+        // The count starts over after tripping
+        assert!(!drift.record(market, at(161)));
+
+        assert_eq!(drift.total_drifts, 7);
+        assert_eq!(drift.drifted_markets.len(), 2);
+        assert_eq!(drift.resubscribes, 1);
+    }
+
+    #[test]
+    fn test_truncate_depth_keeps_best_levels() {
+        // Book messages are not guaranteed to arrive best-first
+        let asks = levels(&["0.55", "0.52", "0.60", "0.53"]);
+        let bids = levels(&["0.45", "0.48", "0.40", "0.47"]);
+
+        assert_eq!(
+            prices(&truncate_depth(&asks, Some(2), true)),
+            vec!["0.52", "0.53"]
+        );
+        assert_eq!(
+            prices(&truncate_depth(&bids, Some(3), false)),
+            vec!["0.48", "0.47", "0.45"]
+        );
+
+        // N larger than the book keeps everything, sorted
+        assert_eq!(
+            prices(&truncate_depth(&asks, Some(10), true)),
+            vec!["0.52", "0.53", "0.55", "0.60"]
+        );
+
+        // Unlimited preserves the original order untouched
+        assert_eq!(
+            prices(&truncate_depth(&asks, None, true)),
+            vec!["0.55", "0.52", "0.60", "0.53"]
+        );
+    }
+
+    #[test]
+    fn test_truncate_depth_unparseable_prices_sort_last() {
+        let asks = levels(&["bad", "0.52", "0.51"]);
+        assert_eq!(
+            prices(&truncate_depth(&asks, Some(2), true)),
+            vec!["0.51", "0.52"]
+        );
+        assert!(truncate_depth(&[], Some(5), true).is_empty());
+    }
+}
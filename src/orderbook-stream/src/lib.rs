@@ -0,0 +1,13 @@
+//! Orderbook state and write path for the orderbook-stream binary.
+//!
+//! Exposed as a library so the stream pipeline can be driven end to end from
+//! integration tests against a mock CLOB WebSocket.
+
+pub mod book;
+pub mod sink;
+
+pub use book::{
+    apply_price_change, parse_event_timestamp, process_book, process_price_change, DriftTracker,
+    MarketOrderbook,
+};
+pub use sink::{BestPrices, OrderbookSink, SideSnapshot, Sink};
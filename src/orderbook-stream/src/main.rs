@@ -1,23 +1,28 @@
 //! Orderbook Stream Service
 //!
 //! Connects to CLOB WebSocket and streams orderbook data to PostgreSQL.
+//! With `--no-db`, markets are discovered via the Gamma API and snapshots are
+//! logged instead of written, for verifying connectivity before Postgres exists.
 
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use chrono::{DateTime, Utc};
 use clap::Parser;
 use rust_decimal::Decimal;
 use tokio::time::sleep;
-use tracing::{debug, error, info, warn, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use common::models::Market;
 use common::{
-    get_active_markets_expiring_within, get_priority_markets_hybrid, update_no_best_prices,
-    update_yes_best_prices, BookMessage, ClobClient, ClobMessage, Config, Database, PriceChange,
-    PriceLevel,
+    get_active_markets_expiring_within, get_priority_markets_hybrid, prune_orderbook_snapshots,
+    ClobClient, ClobMessage, Config, Database, GammaClient, MarketType, ParsedMarket,
+};
+use orderbook_stream::{
+    parse_event_timestamp, process_book, process_price_change, DriftTracker, MarketOrderbook, Sink,
 };
 
 /// Maximum age (in ms) for buffered messages to be considered fresh.
@@ -33,7 +38,8 @@ struct Args {
     #[arg(long)]
     once: bool,
 
-    /// Refresh market list interval in seconds
+    /// Refresh market list interval in seconds (with --reconnect-mode staleness,
+    /// a healthy connection is still recycled this often to pick up new markets)
     #[arg(long, default_value = "300")]
     refresh_interval: u64,
 
@@ -67,18 +73,160 @@ struct Args {
     #[arg(long, default_value = "1500")]
     event_limit: i64,
 
+    /// Minimum liquidity in dollars for event markets in hybrid mode.
+    /// Event markets are ranked by liquidity, so the cap fills with the
+    /// most tradeable ones first; this also drops the thinnest entirely
+    #[arg(long)]
+    event_min_liquidity: Option<Decimal>,
+
     /// Reconnect interval in seconds to refresh all orderbooks (default: 20)
     /// This triggers a full reconnect to get fresh snapshots for all markets
     #[arg(long, default_value = "20")]
     reconnect_interval: u64,
+
+    /// When to reconnect: `interval` every --reconnect-interval seconds, or
+    /// `staleness` only once no market data (books, price changes or trades)
+    /// has arrived for --reconnect-interval seconds
+    #[arg(long, default_value = "interval")]
+    reconnect_mode: ReconnectMode,
+
+    /// Re-subscribe a market once its book hash has drifted from the applied
+    /// price_changes this many times within --drift-window-secs (default: 3)
+    #[arg(long, default_value = "3")]
+    drift_threshold: usize,
+
+    /// Window in seconds for counting hash drifts per market (default: 300)
+    #[arg(long, default_value = "300")]
+    drift_window_secs: u64,
+
+    /// Skip the database: discover markets via Gamma and log snapshots instead of saving them
+    #[arg(long)]
+    no_db: bool,
+
+    /// Store at most N price levels per side in snapshots (best first); best
+    /// bid/ask stay exact, but slippage estimates then only see these N levels.
+    /// Default: unlimited
+    #[arg(long)]
+    max_depth_levels: Option<usize>,
+
+    /// Maintenance mode: instead of streaming, delete snapshots captured more
+    /// than N hours ago every --prune-interval-mins (a single pass with --once)
+    #[arg(long)]
+    prune_older_than_hours: Option<i64>,
+
+    /// Newest snapshots to always keep per market when pruning (default: 0)
+    #[arg(long, default_value = "0")]
+    prune_keep_per_market: i64,
+
+    /// Minutes between prune passes in maintenance mode (default: 60)
+    #[arg(long, default_value = "60")]
+    prune_interval_mins: u64,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
+}
+
+/// When a healthy stream connection is dropped to refresh snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ReconnectMode {
+    /// Reconnect on a fixed schedule
+    #[default]
+    Interval,
+    /// Reconnect only when book messages stop arriving
+    Staleness,
+}
+
+impl ReconnectMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ReconnectMode::Interval => "interval",
+            ReconnectMode::Staleness => "staleness",
+        }
+    }
+
+    /// Whether to drop the connection, given how long it has been up and how
+    /// long since the last market data message.
+    ///
+    /// `Staleness` keeps a connection that is still delivering data until
+    /// `refresh` elapses, so newly listed markets still get subscribed.
+    fn should_reconnect(
+        &self,
+        connected_for: Duration,
+        since_last_data: Duration,
+        window: Duration,
+        refresh: Duration,
+    ) -> bool {
+        match self {
+            ReconnectMode::Interval => connected_for >= window,
+            ReconnectMode::Staleness => since_last_data >= window || connected_for >= refresh,
+        }
+    }
+}
+
+impl FromStr for ReconnectMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "interval" => Ok(ReconnectMode::Interval),
+            "staleness" => Ok(ReconnectMode::Staleness),
+            other => Err(format!(
+                "Unknown reconnect mode '{}' (expected interval, staleness)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ReconnectMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    FmtSubscriber::builder().with_max_level(Level::INFO).init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
+
+    if args.max_depth_levels == Some(0) {
+        bail!("--max-depth-levels must be at least 1");
+    }
+    if args.drift_threshold == 0 || args.drift_window_secs == 0 {
+        bail!("--drift-threshold and --drift-window-secs must be at least 1");
+    }
+    if let Some(liquidity) = args.event_min_liquidity {
+        if liquidity < Decimal::ZERO {
+            bail!(
+                "--event-min-liquidity must be non-negative (got {})",
+                liquidity
+            );
+        }
+        if !args.hybrid {
+            bail!("--event-min-liquidity only applies with --hybrid");
+        }
+    }
+    if let Some(hours) = args.prune_older_than_hours {
+        if hours < 1 {
+            bail!(
+                "--prune-older-than-hours must be at least 1 (got {})",
+                hours
+            );
+        }
+        if args.prune_keep_per_market < 0 {
+            bail!(
+                "--prune-keep-per-market must be non-negative (got {})",
+                args.prune_keep_per_market
+            );
+        }
+        if args.prune_interval_mins == 0 {
+            bail!("--prune-interval-mins must be at least 1");
+        }
+        if args.no_db {
+            bail!("--prune-older-than-hours requires the database (drop --no-db)");
+        }
+    }
 
     info!("Orderbook Stream starting...");
     info!(
@@ -90,21 +238,59 @@ async fn main() -> Result<()> {
         }
     );
 
-    // Load configuration
-    let config = Config::from_env()?;
+    if let Some(levels) = args.max_depth_levels {
+        info!("Storing top {} depth levels per side", levels);
+    }
+    if !args.once {
+        info!(
+            "Reconnect mode: {} ({}s)",
+            args.reconnect_mode, args.reconnect_interval
+        );
+    }
 
-    // Connect to database
-    info!("Connecting to database...");
-    let db = Database::connect(&config).await?;
-    db.health_check().await?;
-    info!("Database connected successfully");
+    // Load configuration; DATABASE_URL is only needed with a DB sink
+    let config = if args.no_db {
+        Config::from_env_without_db()
+    } else {
+        Config::from_env()?
+    };
+
+    // Connect to database (unless running in log-only mode)
+    let db = if args.no_db {
+        info!("--no-db set: skipping database, snapshots will be logged only");
+        if args.hybrid {
+            warn!("--hybrid requires the database; using the --max-expiry-hours window instead");
+        }
+        None
+    } else {
+        info!("Connecting to database...");
+        let db = Database::connect(&config).await?;
+        db.health_check().await?;
+        info!("Database connected successfully");
+        Some(db)
+    };
+    if let (Some(hours), Some(db)) = (args.prune_older_than_hours, &db) {
+        return run_prune(db, &args, hours).await;
+    }
+
+    let sink = match &db {
+        Some(db) => Sink::Db(db),
+        None => Sink::Log,
+    };
 
     // Create CLOB WebSocket client
     let clob = ClobClient::new(&config);
+    let gamma = GammaClient::new(&config);
+
+    // Drift counts outlive reconnects so the stats cover the whole run
+    let mut drift = DriftTracker::new(
+        args.drift_threshold,
+        Duration::from_secs(args.drift_window_secs),
+    );
 
     // Main loop
     loop {
-        match run_stream(&clob, &db, &args).await {
+        match run_stream(&clob, &gamma, sink, &args, &mut drift).await {
             Ok(_) => {
                 if args.once {
                     info!("Single snapshot mode - exiting");
@@ -121,35 +307,56 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Maintenance mode: prune old snapshots on a fixed interval.
+async fn run_prune(db: &Database, args: &Args, older_than_hours: i64) -> Result<()> {
+    info!(
+        "Pruning snapshots older than {}h (keeping {} per market) every {} min",
+        older_than_hours, args.prune_keep_per_market, args.prune_interval_mins
+    );
+
+    loop {
+        let cutoff = Utc::now() - chrono::Duration::hours(older_than_hours);
+        match prune_orderbook_snapshots(db.pool(), cutoff, args.prune_keep_per_market).await {
+            Ok(deleted) => info!(
+                "[PRUNE] Deleted {} snapshots captured before {}",
+                deleted, cutoff
+            ),
+            Err(e) => error!("[PRUNE] Failed to prune snapshots: {}", e),
+        }
+
+        if args.once {
+            return Ok(());
+        }
+        sleep(Duration::from_secs(args.prune_interval_mins * 60)).await;
+    }
+}
+
 /// Run the orderbook streaming loop.
-async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()> {
-    // Get active markets from database
-    let markets = if args.hybrid {
-        // Hybrid mode: crypto markets (short-term) + event markets (long-term)
-        info!(
-            "Fetching markets in HYBRID mode: crypto ({}h, max {}) + events ({}d, max {})...",
-            args.crypto_hours, args.crypto_limit, args.event_days, args.event_limit
-        );
-        get_priority_markets_hybrid(
-            db.pool(),
-            args.crypto_hours,
-            args.event_days,
-            args.crypto_limit,
-            args.event_limit,
-        )
-        .await?
-    } else {
-        // Standard mode: all markets within time window
-        info!(
-            "Fetching active markets (expiring within {} hours, max {})...",
-            args.max_expiry_hours, args.max_markets
-        );
-        get_active_markets_expiring_within(db.pool(), args.max_expiry_hours, args.max_markets)
-            .await?
+async fn run_stream(
+    clob: &ClobClient,
+    gamma: &GammaClient,
+    sink: Sink<'_>,
+    args: &Args,
+    drift: &mut DriftTracker,
+) -> Result<()> {
+    // Get active markets from database (or Gamma when running without one)
+    let markets = match sink {
+        Sink::Db(db) => fetch_db_markets(db, args).await?,
+        Sink::Log => {
+            info!(
+                "Fetching markets from Gamma API (expiring within {} hours, max {})...",
+                args.max_expiry_hours, args.max_markets
+            );
+            let parsed = gamma.fetch_supported_markets().await?;
+            markets_expiring_within(parsed, args.max_expiry_hours, args.max_markets, Utc::now())
+        }
     };
 
     if markets.is_empty() {
-        warn!("No active markets found in database. Run market-scanner first.");
+        match sink {
+            Sink::Db(_) => warn!("No active markets found in database. Run market-scanner first."),
+            Sink::Log => warn!("No markets from Gamma API within the expiry window."),
+        }
         if args.once {
             return Ok(());
         }
@@ -158,6 +365,11 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
     }
 
     info!("Found {} active markets", markets.len());
+    if matches!(sink, Sink::Log) {
+        for market in &markets {
+            debug!(market_id = %market.id, name = %market.name, "[NO-DB] Streaming market");
+        }
+    }
 
     // Build market lookup maps
     let mut token_to_market: HashMap<String, (Uuid, bool)> = HashMap::new(); // (market_id, is_yes)
@@ -210,10 +422,10 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
                         &book,
                         &token_to_market,
                         &mut orderbooks,
-                        db,
+                        &sink,
                         &mut snapshot_count,
-                        args.once,
-                        markets.len(),
+                        args.max_depth_levels,
+                        drift,
                     )
                     .await?;
                 }
@@ -235,10 +447,10 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
                     &book,
                     &token_to_market,
                     &mut orderbooks,
-                    db,
+                    &sink,
                     &mut snapshot_count,
-                    args.once,
-                    markets.len(),
+                    args.max_depth_levels,
+                    drift,
                 )
                 .await?;
             }
@@ -254,9 +466,11 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
     let mut consecutive_timeouts = 0;
     let max_consecutive_timeouts = 6; // 30 seconds without data = dead connection
 
-    // Track connection start time for periodic reconnect
+    // Track connection start and last market data for reconnect decisions
     let connection_start = std::time::Instant::now();
+    let mut last_data = connection_start;
     let reconnect_interval = Duration::from_secs(args.reconnect_interval);
+    let refresh_interval = Duration::from_secs(args.refresh_interval);
 
     // Track message stats for periodic logging
     let mut message_count = 0u64;
@@ -268,19 +482,44 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
         // Log stats every 5 seconds to confirm data is streaming
         if last_stats_log.elapsed() >= stats_interval {
             info!(
-                "Stream stats: {} messages received, {} snapshots saved, uptime {}s",
+                "Stream stats: {} messages received, {} snapshots saved, uptime {}s, \
+                 {} hash drifts across {} markets ({} re-subscribed)",
                 message_count,
                 snapshot_count,
-                connection_start.elapsed().as_secs()
+                connection_start.elapsed().as_secs(),
+                drift.total_drifts(),
+                drift.drifted_market_count(),
+                drift.resubscribes()
             );
             last_stats_log = std::time::Instant::now();
         }
+        // Ask for fresh book snapshots for markets whose deltas keep drifting
+        let drifted = drifted_asset_ids(&markets, drift.take_tripped());
+        if !drifted.is_empty() {
+            info!("Re-subscribing {} drifting token IDs", drifted.len());
+            clob.subscribe(&mut ws, drifted).await?;
+        }
         // Check if it's time to reconnect for fresh orderbook snapshots
-        if connection_start.elapsed() >= reconnect_interval {
-            info!(
-                "Reconnect interval reached ({}s). Reconnecting to refresh all orderbooks...",
-                args.reconnect_interval
-            );
+        if args.reconnect_mode.should_reconnect(
+            connection_start.elapsed(),
+            last_data.elapsed(),
+            reconnect_interval,
+            refresh_interval,
+        ) {
+            match args.reconnect_mode {
+                ReconnectMode::Interval => info!(
+                    "Reconnect interval reached ({}s). Reconnecting to refresh all orderbooks...",
+                    args.reconnect_interval
+                ),
+                ReconnectMode::Staleness if last_data.elapsed() >= reconnect_interval => info!(
+                    "No market data in {}s. Reconnecting to refresh all orderbooks...",
+                    last_data.elapsed().as_secs()
+                ),
+                ReconnectMode::Staleness => info!(
+                    "Market refresh interval reached ({}s). Reconnecting to pick up new markets...",
+                    args.refresh_interval
+                ),
+            }
             return Ok(());
         }
         // Send keepalive ping every 10 seconds per Polymarket docs
@@ -299,6 +538,7 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
         match read_result {
             Ok(Ok(Some(ClobMessage::Books(books)))) => {
                 consecutive_timeouts = 0; // Reset on successful message
+                last_data = std::time::Instant::now();
                 message_count += books.len() as u64;
                 // Batch of book snapshots (initial subscription response)
                 info!("Received batch of {} book snapshots", books.len());
@@ -307,10 +547,10 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
                         &book,
                         &token_to_market,
                         &mut orderbooks,
-                        db,
+                        &sink,
                         &mut snapshot_count,
-                        args.once,
-                        markets.len(),
+                        args.max_depth_levels,
+                        drift,
                     )
                     .await?;
                 }
@@ -321,16 +561,17 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
             }
             Ok(Ok(Some(ClobMessage::Book(book)))) => {
                 consecutive_timeouts = 0; // Reset on successful message
+                last_data = std::time::Instant::now();
                 message_count += 1;
                 debug!("Received book update for asset {}", book.asset_id);
                 process_book(
                     &book,
                     &token_to_market,
                     &mut orderbooks,
-                    db,
+                    &sink,
                     &mut snapshot_count,
-                    args.once,
-                    markets.len(),
+                    args.max_depth_levels,
+                    drift,
                 )
                 .await?;
                 if args.once && snapshot_count >= markets.len() {
@@ -340,63 +581,24 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
             }
             Ok(Ok(Some(ClobMessage::PriceChange(pc)))) => {
                 consecutive_timeouts = 0;
+                last_data = std::time::Instant::now();
                 message_count += 1;
 
                 let event_ts = parse_event_timestamp(&pc.timestamp);
 
                 for change in &pc.price_changes {
-                    if let Some(&(market_id, is_yes)) = token_to_market.get(&change.asset_id) {
-                        let orderbook = orderbooks
-                            .entry(market_id)
-                            .or_insert_with(MarketOrderbook::new);
-
-                        // Track old best prices to detect changes
-                        let (old_best_bid, old_best_ask) = if is_yes {
-                            (orderbook.yes_best_bid, orderbook.yes_best_ask)
-                        } else {
-                            (orderbook.no_best_bid, orderbook.no_best_ask)
-                        };
-
-                        // Apply the delta to in-memory state
-                        apply_price_change(orderbook, change, is_yes);
-
-                        // Only write to DB if best prices actually changed
-                        let (new_best_bid, new_best_ask) = if is_yes {
-                            (orderbook.yes_best_bid, orderbook.yes_best_ask)
-                        } else {
-                            (orderbook.no_best_bid, orderbook.no_best_ask)
-                        };
-
-                        if old_best_bid != new_best_bid || old_best_ask != new_best_ask {
-                            if is_yes {
-                                update_yes_best_prices(
-                                    db.pool(),
-                                    market_id,
-                                    new_best_ask,
-                                    new_best_bid,
-                                    event_ts,
-                                )
-                                .await?;
-                            } else {
-                                update_no_best_prices(
-                                    db.pool(),
-                                    market_id,
-                                    new_best_ask,
-                                    new_best_bid,
-                                    event_ts,
-                                )
-                                .await?;
-                            }
-                            debug!(
-                                "Price change updated best prices for market {} ({})",
-                                market_id,
-                                if is_yes { "YES" } else { "NO" }
-                            );
-                        }
-                    }
+                    process_price_change(
+                        change,
+                        &token_to_market,
+                        &mut orderbooks,
+                        &sink,
+                        event_ts,
+                    )
+                    .await?;
                 }
             }
             Ok(Ok(Some(ClobMessage::Trade(trade)))) => {
+                last_data = std::time::Instant::now();
                 debug!(
                     "Received trade: {} @ {} ({})",
                     trade.asset_id, trade.price, trade.side
@@ -437,199 +639,107 @@ async fn run_stream(clob: &ClobClient, db: &Database, args: &Args) -> Result<()>
     }
 }
 
-/// Parse Polymarket timestamp (Unix millis as string) to DateTime<Utc>
-fn parse_event_timestamp(ts: &str) -> Option<DateTime<Utc>> {
-    // Polymarket sends Unix timestamp in milliseconds as a string
-    ts.parse::<i64>()
-        .ok()
-        .and_then(|millis| DateTime::from_timestamp_millis(millis))
-}
-
-/// Process a single book message and update orderbook state.
-async fn process_book(
-    book: &common::BookMessage,
-    token_to_market: &HashMap<String, (Uuid, bool)>,
-    orderbooks: &mut HashMap<Uuid, MarketOrderbook>,
-    db: &Database,
-    snapshot_count: &mut usize,
-    _once: bool,
-    _total_markets: usize,
-) -> Result<()> {
-    if let Some(&(market_id, is_yes)) = token_to_market.get(&book.asset_id) {
-        let orderbook = orderbooks
-            .entry(market_id)
-            .or_insert_with(MarketOrderbook::new);
-
-        // Validate hash against accumulated price_changes (if we have prior state)
-        if !validate_book(orderbook, book, is_yes) {
-            debug!(
-                "Hash mismatch for market {} ({}), resetting to book snapshot",
-                market_id,
-                if is_yes { "YES" } else { "NO" }
-            );
-        }
-
-        // Parse the event timestamp from Polymarket (more accurate than DB NOW())
-        let event_ts = parse_event_timestamp(&book.timestamp);
-        if let Some(ts) = event_ts {
-            orderbook.event_timestamp = Some(ts);
-        }
-
-        // Update only the side that changed to prevent stale overwrites
-        // Reset pending changes flag since we're syncing to authoritative book snapshot
-        if is_yes {
-            orderbook.yes_asks = book.asks.clone();
-            orderbook.yes_bids = book.bids.clone();
-            orderbook.yes_best_ask = book.best_ask();
-            orderbook.yes_best_bid = book.best_bid();
-            orderbook.yes_hash = Some(book.hash.clone());
-            orderbook.yes_has_pending_changes = false; // Reset - synced to book
-
-            // Save only YES side to DB with event timestamp
-            let yes_asks = serde_json::to_value(&orderbook.yes_asks)?;
-            let yes_bids = serde_json::to_value(&orderbook.yes_bids)?;
-            common::update_yes_orderbook(
-                db.pool(),
-                market_id,
-                orderbook.yes_best_ask,
-                orderbook.yes_best_bid,
-                Some(yes_asks),
-                Some(yes_bids),
-                event_ts,
-            )
-            .await?;
-            *snapshot_count += 1;
-        } else {
-            orderbook.no_asks = book.asks.clone();
-            orderbook.no_bids = book.bids.clone();
-            orderbook.no_best_ask = book.best_ask();
-            orderbook.no_best_bid = book.best_bid();
-            orderbook.no_hash = Some(book.hash.clone());
-            orderbook.no_has_pending_changes = false; // Reset - synced to book
-
-            // Save only NO side to DB with event timestamp
-            let no_asks = serde_json::to_value(&orderbook.no_asks)?;
-            let no_bids = serde_json::to_value(&orderbook.no_bids)?;
-            common::update_no_orderbook(
-                db.pool(),
-                market_id,
-                orderbook.no_best_ask,
-                orderbook.no_best_bid,
-                Some(no_asks),
-                Some(no_bids),
-                event_ts,
-            )
-            .await?;
-            *snapshot_count += 1;
-        }
-    }
-    Ok(())
-}
-
-/// Orderbook state for a single market.
-#[derive(Debug, Default)]
-struct MarketOrderbook {
-    yes_asks: Vec<PriceLevel>,
-    yes_bids: Vec<PriceLevel>,
-    no_asks: Vec<PriceLevel>,
-    no_bids: Vec<PriceLevel>,
-    yes_best_ask: Option<Decimal>,
-    yes_best_bid: Option<Decimal>,
-    no_best_ask: Option<Decimal>,
-    no_best_bid: Option<Decimal>,
-    /// Timestamp from Polymarket event (more accurate than DB NOW())
-    event_timestamp: Option<DateTime<Utc>>,
-    /// Hash of YES orderbook for validation against price_change deltas
-    yes_hash: Option<String>,
-    /// Hash of NO orderbook for validation against price_change deltas
-    no_hash: Option<String>,
-    /// True if price_changes were applied since last YES book snapshot
-    yes_has_pending_changes: bool,
-    /// True if price_changes were applied since last NO book snapshot
-    no_has_pending_changes: bool,
-}
-
-impl MarketOrderbook {
-    fn new() -> Self {
-        Self::default()
-    }
+/// Fetch markets to stream from the database (standard or hybrid mode).
+async fn fetch_db_markets(db: &Database, args: &Args) -> Result<Vec<Market>> {
+    let markets = if args.hybrid {
+        // Hybrid mode: crypto markets (short-term) + event markets (long-term)
+        info!(
+            "Fetching markets in HYBRID mode: crypto ({}h, max {}) + events ({}d, max {})...",
+            args.crypto_hours, args.crypto_limit, args.event_days, args.event_limit
+        );
+        get_priority_markets_hybrid(
+            db.pool(),
+            args.crypto_hours,
+            args.event_days,
+            args.crypto_limit,
+            args.event_limit,
+            args.event_min_liquidity,
+        )
+        .await?
+    } else {
+        // Standard mode: all markets within time window
+        info!(
+            "Fetching active markets (expiring within {} hours, max {})...",
+            args.max_expiry_hours, args.max_markets
+        );
+        get_active_markets_expiring_within(db.pool(), args.max_expiry_hours, args.max_markets)
+            .await?
+    };
+    Ok(markets)
 }
 
-/// Apply a price_change delta to the in-memory orderbook.
-/// Updates the specific price level and best prices from the message.
-fn apply_price_change(orderbook: &mut MarketOrderbook, change: &PriceChange, is_yes: bool) {
-    // Parse price and size
-    let price: Option<Decimal> = change.price.parse().ok();
-    let size: Option<Decimal> = change.size.parse().ok();
-
-    if let (Some(price), Some(size)) = (price, size) {
-        // Determine which side to update
-        let levels = match (is_yes, change.side.as_str()) {
-            (true, "BUY") => &mut orderbook.yes_bids,
-            (true, "SELL") => &mut orderbook.yes_asks,
-            (false, "BUY") => &mut orderbook.no_bids,
-            (false, "SELL") => &mut orderbook.no_asks,
-            _ => return,
-        };
-
-        // Convert price to string for comparison with PriceLevel
-        let price_str = price.to_string();
-
-        if size.is_zero() {
-            // Remove level
-            levels.retain(|l| l.price != price_str);
-        } else {
-            // Update or insert level
-            if let Some(level) = levels.iter_mut().find(|l| l.price == price_str) {
-                level.size = size.to_string();
-            } else {
-                levels.push(PriceLevel {
-                    price: price_str,
-                    size: size.to_string(),
-                });
+/// Select Gamma markets expiring within the window, soonest first, as in-memory `Market`s.
+/// Used in `--no-db` mode; ids are generated locally since nothing is persisted.
+fn markets_expiring_within(
+    parsed: Vec<ParsedMarket>,
+    hours: i32,
+    limit: i64,
+    now: DateTime<Utc>,
+) -> Vec<Market> {
+    let cutoff = now + chrono::Duration::hours(hours as i64);
+    let mut markets: Vec<Market> = parsed
+        .into_iter()
+        .filter(|m| m.end_time > now && m.end_time <= cutoff)
+        .map(|m| Market {
+            id: Uuid::new_v4(),
+            condition_id: m.condition_id,
+            market_type: match m.market_type {
+                MarketType::UpDown => "up_down",
+                MarketType::Above => "above",
+                MarketType::PriceRange => "price_range",
+                MarketType::Unknown => "unknown",
             }
-        }
-    }
-
-    // Update best prices from message (authoritative - Polymarket calculates these)
-    // Mark that we have pending changes for hash validation
-    if is_yes {
-        orderbook.yes_best_bid = change.best_bid.as_ref().and_then(|p| p.parse().ok());
-        orderbook.yes_best_ask = change.best_ask.as_ref().and_then(|p| p.parse().ok());
-        orderbook.yes_hash = change.hash.clone();
-        orderbook.yes_has_pending_changes = true;
-    } else {
-        orderbook.no_best_bid = change.best_bid.as_ref().and_then(|p| p.parse().ok());
-        orderbook.no_best_ask = change.best_ask.as_ref().and_then(|p| p.parse().ok());
-        orderbook.no_hash = change.hash.clone();
-        orderbook.no_has_pending_changes = true;
-    }
+            .to_string(),
+            asset: m.asset,
+            timeframe: m.timeframe,
+            yes_token_id: m.yes_token_id,
+            no_token_id: m.no_token_id,
+            name: m.name,
+            end_time: m.end_time,
+            is_active: true,
+            discovered_at: now,
+            updated_at: now,
+        })
+        .collect();
+    markets.sort_by_key(|m| m.end_time);
+    markets.truncate(limit.max(0) as usize);
+    markets
 }
 
-/// Validate that the book snapshot matches our accumulated price_change state.
-/// Only validates if we have pending price_changes since the last book snapshot.
-/// Returns true if valid (no pending changes, or hashes match), false if drift detected.
-fn validate_book(orderbook: &MarketOrderbook, book: &BookMessage, is_yes: bool) -> bool {
-    let (has_pending, expected_hash) = if is_yes {
-        (orderbook.yes_has_pending_changes, &orderbook.yes_hash)
-    } else {
-        (orderbook.no_has_pending_changes, &orderbook.no_hash)
-    };
-
-    // Only validate if we've accumulated price_changes since last book snapshot
-    if !has_pending {
-        return true;
-    }
+/// Token IDs (YES and NO) of the given markets, for re-subscription.
+fn drifted_asset_ids(markets: &[Market], market_ids: Vec<Uuid>) -> Vec<String> {
+    markets
+        .iter()
+        .filter(|m| market_ids.contains(&m.id))
+        .flat_map(|m| [m.yes_token_id.clone(), m.no_token_id.clone()])
+        .collect()
+}
 
-    match expected_hash {
-        Some(h) if h == &book.hash => true,
-        Some(h) => {
-            warn!(
-                "Hash mismatch for asset {}: expected={} got={} (state drift detected)",
-                book.asset_id, h, book.hash
-            );
-            false
-        }
-        None => true, // No prior hash to compare
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_mode_decision() {
+        let secs = Duration::from_secs;
+        let (window, refresh) = (secs(20), secs(300));
+
+        // Interval drops even a connection that is streaming fine
+        let interval = ReconnectMode::Interval;
+        assert!(!interval.should_reconnect(secs(19), secs(0), window, refresh));
+        assert!(interval.should_reconnect(secs(20), secs(0), window, refresh));
+
+        // Staleness keeps a healthy connection past the window...
+        let staleness = ReconnectMode::Staleness;
+        assert!(!staleness.should_reconnect(secs(120), secs(5), window, refresh));
+        // ...drops it once market data stops arriving...
+        assert!(!staleness.should_reconnect(secs(120), secs(19), window, refresh));
+        assert!(staleness.should_reconnect(secs(120), secs(20), window, refresh));
+        // ...and still recycles it to refresh the market list
+        assert!(staleness.should_reconnect(secs(300), secs(1), window, refresh));
+
+        assert_eq!("Staleness".parse(), Ok(ReconnectMode::Staleness));
+        assert_eq!("interval".parse(), Ok(ReconnectMode::Interval));
+        assert!("sometimes".parse::<ReconnectMode>().is_err());
     }
 }
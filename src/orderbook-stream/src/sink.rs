@@ -0,0 +1,152 @@
+//! Where orderbook updates are written.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::info;
+use uuid::Uuid;
+
+use common::{Database, PriceLevel};
+
+/// One side of a market's book, as stored after a book snapshot.
+#[derive(Debug, Clone)]
+pub struct SideSnapshot {
+    pub market_id: Uuid,
+    pub is_yes: bool,
+    pub best_ask: Option<Decimal>,
+    pub best_bid: Option<Decimal>,
+    /// Ask levels, truncated to `--max-depth-levels`
+    pub asks: Vec<PriceLevel>,
+    /// Bid levels, truncated to `--max-depth-levels`
+    pub bids: Vec<PriceLevel>,
+    /// Timestamp from the Polymarket event
+    pub event_ts: Option<DateTime<Utc>>,
+}
+
+/// New best prices for one side of a market after a price_change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestPrices {
+    pub market_id: Uuid,
+    pub is_yes: bool,
+    pub best_ask: Option<Decimal>,
+    pub best_bid: Option<Decimal>,
+    pub event_ts: Option<DateTime<Utc>>,
+}
+
+/// Destination for orderbook writes.
+#[async_trait]
+pub trait OrderbookSink: Send + Sync {
+    /// Store one side of a book snapshot.
+    async fn write_book(&self, snapshot: &SideSnapshot) -> Result<()>;
+
+    /// Store best prices moved by a price_change delta.
+    async fn write_best_prices(&self, prices: &BestPrices) -> Result<()>;
+}
+
+/// Destination for orderbook updates.
+#[derive(Clone, Copy)]
+pub enum Sink<'a> {
+    /// Persist snapshots and best prices to PostgreSQL
+    Db(&'a Database),
+    /// Emit structured log lines only (`--no-db`)
+    Log,
+}
+
+#[async_trait]
+impl OrderbookSink for Sink<'_> {
+    async fn write_book(&self, snapshot: &SideSnapshot) -> Result<()> {
+        let db = match self {
+            Sink::Db(db) => db,
+            Sink::Log => {
+                log_snapshot(snapshot);
+                return Ok(());
+            }
+        };
+
+        // Save only the side that changed, with the event timestamp
+        let asks = serde_json::to_value(&snapshot.asks)?;
+        let bids = serde_json::to_value(&snapshot.bids)?;
+        if snapshot.is_yes {
+            common::update_yes_orderbook(
+                db.pool(),
+                snapshot.market_id,
+                snapshot.best_ask,
+                snapshot.best_bid,
+                Some(asks),
+                Some(bids),
+                snapshot.event_ts,
+            )
+            .await?;
+        } else {
+            common::update_no_orderbook(
+                db.pool(),
+                snapshot.market_id,
+                snapshot.best_ask,
+                snapshot.best_bid,
+                Some(asks),
+                Some(bids),
+                snapshot.event_ts,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn write_best_prices(&self, prices: &BestPrices) -> Result<()> {
+        match self {
+            Sink::Db(db) if prices.is_yes => {
+                common::update_yes_best_prices(
+                    db.pool(),
+                    prices.market_id,
+                    prices.best_ask,
+                    prices.best_bid,
+                    prices.event_ts,
+                )
+                .await?;
+            }
+            Sink::Db(db) => {
+                common::update_no_best_prices(
+                    db.pool(),
+                    prices.market_id,
+                    prices.best_ask,
+                    prices.best_bid,
+                    prices.event_ts,
+                )
+                .await?;
+            }
+            Sink::Log => {
+                info!(
+                    market_id = %prices.market_id,
+                    side = side_label(prices.is_yes),
+                    best_bid = ?prices.best_bid,
+                    best_ask = ?prices.best_ask,
+                    "[NO-DB] Best price change"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Log one side of a book snapshot in place of a DB write (`--no-db`).
+fn log_snapshot(snapshot: &SideSnapshot) {
+    info!(
+        market_id = %snapshot.market_id,
+        side = side_label(snapshot.is_yes),
+        best_bid = ?snapshot.best_bid,
+        best_ask = ?snapshot.best_ask,
+        bid_levels = snapshot.bids.len(),
+        ask_levels = snapshot.asks.len(),
+        event_ts = ?snapshot.event_ts,
+        "[NO-DB] Book snapshot"
+    );
+}
+
+pub(crate) fn side_label(is_yes: bool) -> &'static str {
+    if is_yes {
+        "YES"
+    } else {
+        "NO"
+    }
+}
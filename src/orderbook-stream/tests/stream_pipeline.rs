@@ -0,0 +1,221 @@
+//! End-to-end check of the orderbook-stream pipeline against a mock CLOB
+//! WebSocket: messages read through `ClobClient` must produce the expected
+//! sink writes.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal_macros::dec;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+use common::{ClobClient, ClobMessage, MockStep, MockWsServer};
+use orderbook_stream::{
+    parse_event_timestamp, process_book, process_price_change, BestPrices, DriftTracker,
+    MarketOrderbook, OrderbookSink, SideSnapshot,
+};
+
+/// Sink that records every write for later assertions.
+#[derive(Default)]
+struct RecordingSink {
+    books: Mutex<Vec<SideSnapshot>>,
+    best_prices: Mutex<Vec<BestPrices>>,
+}
+
+#[async_trait]
+impl OrderbookSink for RecordingSink {
+    async fn write_book(&self, snapshot: &SideSnapshot) -> Result<()> {
+        self.books.lock().unwrap().push(snapshot.clone());
+        Ok(())
+    }
+
+    async fn write_best_prices(&self, prices: &BestPrices) -> Result<()> {
+        self.best_prices.lock().unwrap().push(prices.clone());
+        Ok(())
+    }
+}
+
+const TS: &str = "1767225600000";
+
+fn book_json(asset_id: &str, bids: &[&str], asks: &[&str], hash: &str) -> String {
+    let levels = |prices: &[&str]| {
+        prices
+            .iter()
+            .map(|p| serde_json::json!({ "price": p, "size": "100" }))
+            .collect::<Vec<_>>()
+    };
+    serde_json::json!({
+        "event_type": "book",
+        "asset_id": asset_id,
+        "market": "condition-1",
+        "bids": levels(bids),
+        "asks": levels(asks),
+        "timestamp": TS,
+        "hash": hash,
+    })
+    .to_string()
+}
+
+fn price_change_json(asset_id: &str, price: &str, best_ask: &str, hash: &str) -> String {
+    serde_json::json!({
+        "event_type": "price_change",
+        "market": "condition-1",
+        "timestamp": TS,
+        "price_changes": [{
+            "asset_id": asset_id,
+            "price": price,
+            "size": "30",
+            "side": "SELL",
+            "best_bid": "0.48",
+            "best_ask": best_ask,
+            "hash": hash,
+        }],
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn test_stream_pipeline_writes_books_and_price_changes() {
+    let yes_book = book_json("yes-token", &["0.48", "0.47"], &["0.52", "0.55"], "yes-1");
+    let no_book = book_json("no-token", &["0.47"], &["0.53"], "no-1");
+    let delta = price_change_json("yes-token", "0.51", "0.51", "yes-2");
+    let server = MockWsServer::start(vec![
+        MockStep::AwaitClientText,
+        MockStep::text(format!("[{},{}]", yes_book, no_book)),
+        MockStep::Send(Message::Ping(vec![7].into())),
+        MockStep::text(delta.clone()),
+        // Polymarket resends deltas; the copy must not be written twice
+        MockStep::text(delta),
+        // A snapshot whose hash disagrees with the applied deltas is drift
+        MockStep::text(book_json(
+            "yes-token",
+            &["0.48"],
+            &["0.51", "0.52"],
+            "yes-3",
+        )),
+    ])
+    .await
+    .unwrap();
+
+    let market_id = Uuid::new_v4();
+    let token_to_market = HashMap::from([
+        ("yes-token".to_string(), (market_id, true)),
+        ("no-token".to_string(), (market_id, false)),
+    ]);
+
+    let clob = ClobClient::with_url(server.url());
+    let mut ws = clob.connect().await.unwrap();
+    clob.subscribe(&mut ws, vec!["yes-token".into(), "no-token".into()])
+        .await
+        .unwrap();
+
+    let sink = RecordingSink::default();
+    let mut orderbooks: HashMap<Uuid, MarketOrderbook> = HashMap::new();
+    let mut drift = DriftTracker::new(1, Duration::from_secs(60));
+    let mut snapshot_count = 0;
+    let (mut data_messages, mut pings) = (0, 0);
+
+    while data_messages < 4 {
+        let msg = tokio::time::timeout(Duration::from_secs(5), clob.read_message(&mut ws))
+            .await
+            .expect("mock server went quiet")
+            .unwrap();
+        match msg {
+            Some(ClobMessage::Books(books)) => {
+                for book in &books {
+                    process_book(
+                        book,
+                        &token_to_market,
+                        &mut orderbooks,
+                        &sink,
+                        &mut snapshot_count,
+                        Some(1),
+                        &mut drift,
+                    )
+                    .await
+                    .unwrap();
+                }
+            }
+            Some(ClobMessage::Book(book)) => {
+                process_book(
+                    &book,
+                    &token_to_market,
+                    &mut orderbooks,
+                    &sink,
+                    &mut snapshot_count,
+                    Some(1),
+                    &mut drift,
+                )
+                .await
+                .unwrap();
+            }
+            Some(ClobMessage::PriceChange(pc)) => {
+                let event_ts = parse_event_timestamp(&pc.timestamp);
+                for change in &pc.price_changes {
+                    process_price_change(
+                        change,
+                        &token_to_market,
+                        &mut orderbooks,
+                        &sink,
+                        event_ts,
+                    )
+                    .await
+                    .unwrap();
+                }
+            }
+            Some(ClobMessage::Ping) => {
+                pings += 1;
+                continue;
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+        data_messages += 1;
+    }
+
+    assert_eq!(pings, 1);
+    let subscription: serde_json::Value = serde_json::from_str(&server.received()[0]).unwrap();
+    assert_eq!(subscription["type"], "market");
+    assert_eq!(
+        subscription["assets_ids"],
+        serde_json::json!(["yes-token", "no-token"])
+    );
+
+    // One write per book side, truncated to the best level
+    let books = sink.books.lock().unwrap();
+    assert_eq!(snapshot_count, 3);
+    assert_eq!(
+        books
+            .iter()
+            .map(|b| (b.is_yes, b.best_bid, b.best_ask))
+            .collect::<Vec<_>>(),
+        vec![
+            (true, Some(dec!(0.48)), Some(dec!(0.52))),
+            (false, Some(dec!(0.47)), Some(dec!(0.53))),
+            (true, Some(dec!(0.48)), Some(dec!(0.51))),
+        ]
+    );
+    assert!(books.iter().all(|b| b.asks.len() == 1 && b.bids.len() <= 1));
+    assert_eq!(books[0].asks[0].price, "0.52");
+    assert!(books
+        .iter()
+        .all(|b| b.market_id == market_id && b.event_ts == parse_event_timestamp(TS)));
+
+    // The delta moved the YES ask once; its resend wrote nothing
+    assert_eq!(
+        *sink.best_prices.lock().unwrap(),
+        vec![BestPrices {
+            market_id,
+            is_yes: true,
+            best_ask: Some(dec!(0.51)),
+            best_bid: Some(dec!(0.48)),
+            event_ts: parse_event_timestamp(TS),
+        }]
+    );
+
+    // The final YES snapshot disagreed with the delta's hash
+    assert_eq!(drift.total_drifts(), 1);
+    assert_eq!(drift.take_tripped(), vec![market_id]);
+}
@@ -5,13 +5,9 @@
 
 use std::str::FromStr;
 
-use alloy::signers::local::LocalSigner;
-use alloy::signers::Signer;
 use anyhow::{Context, Result};
 use clap::Parser;
-use polymarket_client_sdk::clob::types::SignatureType;
 use polymarket_client_sdk::clob::{Client as ClobClient, Config as ClobConfig};
-use polymarket_client_sdk::POLYGON;
 use tracing::info;
 
 const CLOB_HOST: &str = "https://clob.polymarket.com";
@@ -39,23 +35,20 @@ struct Args {
     /// Dry run - don't actually place the order
     #[arg(long)]
     dry_run: bool,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("place_order=info".parse().unwrap()),
-        )
-        .init();
+    let args = Args::parse();
+    common::logging::init(args.json_logs);
 
     // Load .env
     dotenvy::dotenv().ok();
 
-    let args = Args::parse();
-
     // Validate inputs
     if args.price < 0.01 || args.price > 0.99 {
         anyhow::bail!("Price must be between 0.01 and 0.99");
@@ -91,32 +84,10 @@ async fn main() -> Result<()> {
     }
     println!("{}", "=".repeat(50));
 
-    // Get private key
-    let private_key =
-        std::env::var("WALLET_PRIVATE_KEY").context("Missing WALLET_PRIVATE_KEY in environment")?;
-
-    let private_key = if private_key.starts_with("0x") {
-        private_key
-    } else {
-        format!("0x{}", private_key)
-    };
-
-    // Create signer
-    let signer = LocalSigner::from_str(&private_key)
-        .context("Invalid private key format")?
-        .with_chain_id(Some(POLYGON));
+    let (signer, signature_type, funder) = common::resolve_signature_config()?;
 
     let signer_address = signer.address();
     info!("Signer address: {}", signer_address);
-
-    // Check for proxy wallet
-    let proxy_wallet = std::env::var("POLYMARKET_WALLET_ADDRESS").ok();
-    let signature_type = if proxy_wallet.is_some() {
-        SignatureType::GnosisSafe
-    } else {
-        SignatureType::Eoa
-    };
-
     info!("Signature type: {:?}", signature_type);
 
     // Authenticate with CLOB
@@ -126,11 +97,9 @@ async fn main() -> Result<()> {
         .authentication_builder(&signer)
         .signature_type(signature_type);
 
-    if let Some(ref proxy) = proxy_wallet {
-        let funder_address: alloy::primitives::Address =
-            proxy.parse().context("Invalid proxy wallet address")?;
+    if let Some(funder_address) = funder {
         auth_builder = auth_builder.funder(funder_address);
-        info!("Funder (proxy): {}", proxy);
+        info!("Funder (proxy): {}", funder_address);
     }
 
     let clob_client = auth_builder
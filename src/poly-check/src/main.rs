@@ -6,24 +6,49 @@
 //!   poly-check --positions     # Show positions only
 //!   poly-check --pnl           # Show profit & loss report
 //!   poly-check --audit-prices  # Audit orderbook price data quality
+//!   poly-check --winrate       # Show win-rate by asset and timeframe
+//!   poly-check --settlements   # Show recorded settlement P&L (live vs dry-run)
+//!   poly-check --unsettled     # List live-traded markets with no settlement recorded
+//!   poly-check --unsettled --backfill  # ...and fetch missing resolutions from Gamma
+//!   poly-check --coverage-report  # List active markets without fresh orderbooks
+//!   poly-check --orders        # List open (resting) orders
+//!   poly-check --orders --cancel-all  # Cancel every open order
+//!   poly-check --self-test     # Check config, DB, APIs and wallet, pass/fail per step
 
 use std::collections::HashMap;
-use std::str::FromStr;
+use std::future::Future;
+use std::time::Duration;
 
-use alloy::signers::local::LocalSigner;
-use alloy::signers::Signer;
-use anyhow::{Context, Result};
-use chrono::Utc;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Parser;
-use polymarket_client_sdk::clob::types::{BalanceAllowanceRequest, SignatureType};
+use common::rate_limit;
+use common::{CachedAuth, OrderApi, Platform};
+use polymarket_client_sdk::clob::types::BalanceAllowanceRequest;
 use polymarket_client_sdk::clob::{Client as ClobClient, Config as ClobConfig};
-use polymarket_client_sdk::POLYGON;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::Deserialize;
 use tracing::{info, warn};
 
 const CLOB_HOST: &str = "https://clob.polymarket.com";
+const DATA_API_URL: &str = "https://data-api.polymarket.com";
+
+/// Activities per Data API page.
+const ACTIVITY_PAGE_SIZE: usize = 100;
+/// Safety cap on pages fetched to cover a --since/--last-hours window.
+const MAX_ACTIVITY_PAGES: usize = 50;
+
+/// Request rates for the shared per-host limiters.
+const CLOB_REQUESTS_PER_SEC: f64 = 10.0;
+const DATA_API_REQUESTS_PER_SEC: f64 = 5.0;
+
+/// Longest a single --self-test network check may take.
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Strategy tags whose traders record live settlements; markets traded by
+/// other strategies never get one, so --unsettled leaves them out.
+const SETTLING_STRATEGIES: &[&str] = &["misprice"];
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -63,6 +88,15 @@ struct Args {
     #[arg(long)]
     pnl: bool,
 
+    /// With --pnl, only count activity at or after this time (RFC 3339,
+    /// e.g. 2024-01-01T00:00:00Z)
+    #[arg(long, conflicts_with = "last_hours")]
+    since: Option<DateTime<Utc>>,
+
+    /// With --pnl, only count activity from the last N hours
+    #[arg(long)]
+    last_hours: Option<i64>,
+
     /// Show all markets (no limit)
     #[arg(long)]
     all: bool,
@@ -78,51 +112,93 @@ struct Args {
     /// Assets to audit (comma-separated, e.g., BTC,ETH,SOL)
     #[arg(long, default_value = "BTC,ETH,SOL,XRP")]
     audit_assets: String,
+
+    /// Show realized win-rate by asset and timeframe (from DB trade history)
+    #[arg(long)]
+    winrate: bool,
+
+    /// Days of trade history to include in the win-rate report (default: 30)
+    #[arg(long, default_value = "30")]
+    winrate_days: i64,
+
+    /// Include dry-run trades in the win-rate report (live trades only by default)
+    #[arg(long)]
+    winrate_include_dry_run: bool,
+
+    /// Show realized P&L from recorded settlements, live and dry-run separately
+    #[arg(long)]
+    settlements: bool,
+
+    /// Days of settlements to include in the settlements report (default: 30)
+    #[arg(long, default_value = "30")]
+    settlements_days: i64,
+
+    /// List ended markets traded live whose settlement was never recorded
+    #[arg(long)]
+    unsettled: bool,
+
+    /// With --unsettled, fetch missing resolutions from Gamma and record them
+    #[arg(long)]
+    backfill: bool,
+
+    /// List active markets with no orderbook snapshot newer than --coverage-max-age
+    #[arg(long)]
+    coverage_report: bool,
+
+    /// Snapshot age in seconds beyond which a market counts as uncovered (default: 60)
+    #[arg(long, default_value = "60")]
+    coverage_max_age: i32,
+
+    /// Assets to check coverage for (comma-separated)
+    #[arg(long, default_value = "BTC,ETH,SOL,XRP")]
+    coverage_assets: String,
+
+    /// List open orders resting on the CLOB (e.g. orphans from a crashed bot)
+    #[arg(long)]
+    orders: bool,
+
+    /// With --orders, cancel every open order listed
+    #[arg(long)]
+    cancel_all: bool,
+
+    /// Check config, database, Gamma, Kalshi, CLOB auth and wallet balance in
+    /// order, print pass/fail for each, and exit nonzero if a required one fails
+    #[arg(long)]
+    self_test: bool,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("poly_check=info".parse().unwrap()),
-        )
-        .init();
+    let args = Args::parse();
+    common::logging::init(args.json_logs);
+
+    if args.last_hours.is_some_and(|h| h < 1) {
+        anyhow::bail!("--last-hours must be at least 1");
+    }
+    // Resolved up front so an unrepresentable window fails before any work
+    let window_start = pnl_window_start(&args, Utc::now())?;
+    if !args.pnl && (args.since.is_some() || args.last_hours.is_some()) {
+        warn!("--since/--last-hours have no effect without --pnl");
+    }
 
     // Load .env
     dotenvy::dotenv().ok();
 
-    let args = Args::parse();
-
-    // Get private key
-    let private_key =
-        std::env::var("WALLET_PRIVATE_KEY").context("Missing WALLET_PRIVATE_KEY in environment")?;
-
-    let private_key = if private_key.starts_with("0x") {
-        private_key
-    } else {
-        format!("0x{}", private_key)
-    };
+    // Runs before authenticating so a bad credential is reported, not fatal
+    if args.self_test {
+        return run_self_test().await;
+    }
 
-    // Create signer using SDK's re-exported LocalSigner from alloy
-    let signer = LocalSigner::from_str(&private_key)
-        .context("Invalid private key format")?
-        .with_chain_id(Some(POLYGON));
+    let (signer, signature_type, funder) = common::resolve_signature_config()?;
 
     let signer_address = signer.address();
     info!("Signer address: {}", signer_address);
-
-    // Check for proxy wallet
-    let proxy_wallet = std::env::var("POLYMARKET_WALLET_ADDRESS").ok();
-    let signature_type = if proxy_wallet.is_some() {
-        SignatureType::GnosisSafe
-    } else {
-        SignatureType::Eoa
-    };
-
     info!("Signature type: {:?}", signature_type);
-    if let Some(ref proxy) = proxy_wallet {
+    if let Some(proxy) = funder {
         info!("Proxy wallet: {}", proxy);
     }
 
@@ -134,9 +210,7 @@ async fn main() -> Result<()> {
         .signature_type(signature_type);
 
     // Add funder address for GnosisSafe signature type
-    if let Some(ref proxy) = proxy_wallet {
-        let funder_address: alloy::primitives::Address =
-            proxy.parse().context("Invalid proxy wallet address")?;
+    if let Some(funder_address) = funder {
         auth_builder = auth_builder.funder(funder_address);
     }
 
@@ -144,6 +218,12 @@ async fn main() -> Result<()> {
         .authenticate()
         .await
         .context("Failed to authenticate with Polymarket")?;
+    let auth = CachedAuth {
+        client: clob_client,
+        signer,
+        authenticated_at: Utc::now(),
+    };
+    let clob_client = &auth.client;
 
     println!("\n{}", "=".repeat(50));
     println!("API Credentials Verified Successfully!");
@@ -171,6 +251,21 @@ async fn main() -> Result<()> {
                 let balance_usdc = balance.balance / rust_decimal::Decimal::from(1_000_000);
                 println!("  USDC Balance: ${:.2}", balance_usdc);
                 println!("  Raw Balance: {} micro-USDC", balance.balance);
+
+                // Approval the CLOB exchange needs to spend USDC for orders
+                let raw: Vec<String> = balance.allowances.values().map(|a| a.to_string()).collect();
+                match common::usdc_allowance(&raw) {
+                    Some(allowance) if allowance == Decimal::MAX => {
+                        println!("  USDC Allowance: unlimited");
+                    }
+                    Some(allowance) if allowance > Decimal::ZERO => {
+                        println!("  USDC Allowance: ${:.2}", allowance);
+                        if allowance < balance_usdc {
+                            println!("  ⚠️  Allowance is below balance; orders may be rejected");
+                        }
+                    }
+                    _ => println!("  ⚠️  USDC Allowance: not approved - live orders will fail"),
+                }
             }
             Err(e) => {
                 warn!("Could not fetch balance: {}", e);
@@ -185,19 +280,19 @@ async fn main() -> Result<()> {
         println!("Positions:");
 
         // Use proxy wallet address if available, otherwise signer address
-        let user_address = if let Some(ref proxy) = proxy_wallet {
-            proxy.clone()
-        } else {
-            format!("{}", signer_address)
-        };
+        let user_address = format!("{}", funder.unwrap_or(signer_address));
 
         // Fetch positions via HTTP (Data API)
         let data_url = format!(
-            "https://data-api.polymarket.com/positions?user={}",
+            "{}/positions?user={}",
+            DATA_API_URL,
             user_address.to_lowercase()
         );
 
         let http_client = reqwest::Client::new();
+        rate_limit::for_url(DATA_API_URL, DATA_API_REQUESTS_PER_SEC)
+            .acquire()
+            .await;
         match http_client.get(&data_url).send().await {
             Ok(resp) => {
                 if resp.status().is_success() {
@@ -239,153 +334,613 @@ async fn main() -> Result<()> {
         println!("{}", "=".repeat(50));
 
         // Use proxy wallet address if available, otherwise signer address
-        let user_address = if let Some(ref proxy) = proxy_wallet {
-            proxy.clone()
-        } else {
-            format!("{}", signer_address)
-        };
+        let user_address = format!("{}", funder.unwrap_or(signer_address));
 
         // Fetch activity via HTTP (Data API)
-        let activity_url = format!(
-            "https://data-api.polymarket.com/activity?user={}&limit=100",
-            user_address.to_lowercase()
-        );
-
         let http_client = reqwest::Client::new();
-        match http_client.get(&activity_url).send().await {
-            Ok(resp) => {
-                if resp.status().is_success() {
-                    let activities: Vec<Activity> = resp.json().await.unwrap_or_default();
-
-                    if activities.is_empty() {
-                        println!("\n  No trading activity found");
-                    } else {
-                        // Calculate totals
-                        let mut total_spent = 0.0;
-                        let mut total_received = 0.0;
-                        let mut markets: HashMap<String, MarketPnL> = HashMap::new();
-
-                        for activity in &activities {
-                            let market = markets
+        match fetch_activities(&http_client, &user_address.to_lowercase(), window_start).await {
+            Ok(activities) => {
+                let window = pnl_window_label(window_start, activities.len());
+                if activities.is_empty() {
+                    println!("\n  No trading activity found ({})", window);
+                } else {
+                    // Calculate totals
+                    let mut total_spent = 0.0;
+                    let mut total_received = 0.0;
+                    let mut markets: HashMap<String, MarketPnL> = HashMap::new();
+
+                    for activity in &activities {
+                        let market =
+                            markets
                                 .entry(activity.condition_id.clone())
                                 .or_insert_with(|| MarketPnL {
                                     title: activity.title.clone().unwrap_or_default(),
                                     ..Default::default()
                                 });
 
-                            // Track most recent activity
-                            if activity.timestamp > market.last_activity {
-                                market.last_activity = activity.timestamp;
-                            }
+                        // Track most recent activity
+                        if activity.timestamp > market.last_activity {
+                            market.last_activity = activity.timestamp;
+                        }
 
-                            if activity.side.as_deref() == Some("BUY") {
-                                let cost = activity.size * activity.price;
-                                total_spent += cost;
-                                market.spent += cost;
-                            } else if activity.side.as_deref() == Some("SELL") {
-                                let proceeds = activity.size * activity.price;
-                                total_received += proceeds;
-                                market.received += proceeds;
-                            } else if activity.activity_type.as_deref() == Some("REDEEM") {
-                                let received = activity.usdc_size.unwrap_or(0.0);
-                                total_received += received;
-                                market.received += received;
+                        if activity.side.as_deref() == Some("BUY") {
+                            let cost = activity.size * activity.price;
+                            total_spent += cost;
+                            market.spent += cost;
+                        } else if activity.side.as_deref() == Some("SELL") {
+                            let proceeds = activity.size * activity.price;
+                            total_received += proceeds;
+                            market.received += proceeds;
+                        } else if activity.activity_type.as_deref() == Some("REDEEM") {
+                            let received = activity.usdc_size.unwrap_or(0.0);
+                            total_received += received;
+                            market.received += received;
+                        }
+                    }
+
+                    let net_pnl = total_received - total_spent;
+                    let roi = if total_spent > 0.0 {
+                        (net_pnl / total_spent) * 100.0
+                    } else {
+                        0.0
+                    };
+
+                    // Count wins/losses
+                    let mut wins = 0;
+                    let mut total_markets = 0;
+                    for market in markets.values() {
+                        if market.spent > 0.0 || market.received > 0.0 {
+                            total_markets += 1;
+                            if market.received > market.spent {
+                                wins += 1;
                             }
                         }
+                    }
 
-                        let net_pnl = total_received - total_spent;
-                        let roi = if total_spent > 0.0 {
-                            (net_pnl / total_spent) * 100.0
+                    // Print summary
+                    println!("\nSummary:");
+                    println!("  Window:         {}", window);
+                    println!("  Total Spent:    ${:.2}", total_spent);
+                    println!("  Total Received: ${:.2}", total_received);
+                    let sign = if net_pnl >= 0.0 { "+" } else { "" };
+                    println!("  Net P&L:        {}${:.2}", sign, net_pnl);
+                    println!("  ROI:            {}{:.2}%", sign, roi);
+                    println!(
+                        "  Win Rate:       {}/{} ({:.0}%)",
+                        wins,
+                        total_markets,
+                        if total_markets > 0 {
+                            (wins as f64 / total_markets as f64) * 100.0
                         } else {
                             0.0
-                        };
-
-                        // Count wins/losses
-                        let mut wins = 0;
-                        let mut total_markets = 0;
-                        for market in markets.values() {
-                            if market.spent > 0.0 || market.received > 0.0 {
-                                total_markets += 1;
-                                if market.received > market.spent {
-                                    wins += 1;
-                                }
-                            }
                         }
+                    );
 
-                        // Print summary
-                        println!("\nSummary:");
-                        println!("  Total Spent:    ${:.2}", total_spent);
-                        println!("  Total Received: ${:.2}", total_received);
-                        let sign = if net_pnl >= 0.0 { "+" } else { "" };
-                        println!("  Net P&L:        {}${:.2}", sign, net_pnl);
-                        println!("  ROI:            {}{:.2}%", sign, roi);
+                    // Sort markets by most recent activity
+                    let mut market_list: Vec<_> = markets
+                        .values()
+                        .filter(|m| m.spent > 0.0 || m.received > 0.0)
+                        .collect();
+                    market_list.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+
+                    // Print by market
+                    println!("\n{}", "-".repeat(50));
+                    println!("By Market (most recent first):\n");
+
+                    let display_limit = if args.all { market_list.len() } else { 15 };
+                    for market in market_list.iter().take(display_limit) {
+                        let pnl = market.received - market.spent;
+                        let sign = if pnl >= 0.0 { "+" } else { "" };
+                        let title = if market.title.chars().count() > 45 {
+                            format!("{}...", market.title.chars().take(45).collect::<String>())
+                        } else {
+                            market.title.clone()
+                        };
+                        println!("  [{}${:.2}] {}", sign, pnl, title);
                         println!(
-                            "  Win Rate:       {}/{} ({:.0}%)",
-                            wins,
-                            total_markets,
-                            if total_markets > 0 {
-                                (wins as f64 / total_markets as f64) * 100.0
-                            } else {
-                                0.0
-                            }
+                            "    Spent: ${:.2} | Received: ${:.2}",
+                            market.spent, market.received
                         );
+                    }
 
-                        // Sort markets by most recent activity
-                        let mut market_list: Vec<_> = markets
-                            .values()
-                            .filter(|m| m.spent > 0.0 || m.received > 0.0)
-                            .collect();
-                        market_list.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
-
-                        // Print by market
-                        println!("\n{}", "-".repeat(50));
-                        println!("By Market (most recent first):\n");
-
-                        let display_limit = if args.all { market_list.len() } else { 15 };
-                        for market in market_list.iter().take(display_limit) {
-                            let pnl = market.received - market.spent;
-                            let sign = if pnl >= 0.0 { "+" } else { "" };
-                            let title = if market.title.chars().count() > 45 {
-                                format!("{}...", market.title.chars().take(45).collect::<String>())
-                            } else {
-                                market.title.clone()
-                            };
-                            println!("  [{}${:.2}] {}", sign, pnl, title);
-                            println!(
-                                "    Spent: ${:.2} | Received: ${:.2}",
-                                market.spent, market.received
-                            );
-                        }
-
-                        if !args.all && market_list.len() > 15 {
-                            println!(
-                                "\n  ... and {} more markets (use --all to show all)",
-                                market_list.len() - 15
-                            );
-                        }
+                    if !args.all && market_list.len() > 15 {
+                        println!(
+                            "\n  ... and {} more markets (use --all to show all)",
+                            market_list.len() - 15
+                        );
                     }
-                } else {
-                    println!("  (Activity fetch failed: {})", resp.status());
                 }
             }
             Err(e) => {
-                warn!("Could not fetch activity: {}", e);
+                warn!("Could not fetch activity: {:#}", e);
                 println!("  (Activity fetch failed)");
             }
         }
     }
 
+    // List (and optionally cancel) open orders if requested
+    if args.orders {
+        run_orders_report(&auth, args.cancel_all).await?;
+    } else if args.cancel_all {
+        warn!("--cancel-all has no effect without --orders");
+    }
+    if args.backfill && !args.unsettled {
+        warn!("--backfill has no effect without --unsettled");
+    }
+
     // Run audit if requested
     if args.audit_prices {
         run_audit_prices(&args).await?;
     }
 
+    // Show win-rate breakdown if requested
+    if args.winrate {
+        run_winrate_report(&args).await?;
+    }
+
+    // Show settlement P&L if requested
+    if args.settlements {
+        run_settlements_report(&args).await?;
+    }
+
+    // Show live trades left without a settlement if requested
+    if args.unsettled {
+        run_unsettled_report(args.backfill).await?;
+    }
+
+    // Show streaming coverage gaps if requested
+    if args.coverage_report {
+        run_coverage_report(&args).await?;
+    }
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
     Ok(())
 }
 
+/// Pass/fail tally for `--self-test`.
+#[derive(Debug, Default)]
+struct SelfTest {
+    /// Required checks that failed, in order
+    failed: Vec<&'static str>,
+}
+
+impl SelfTest {
+    /// Print one check's outcome and hand back its value on success. A failed
+    /// optional check is only a warning.
+    fn record<T>(
+        &mut self,
+        name: &'static str,
+        required: bool,
+        result: Result<T>,
+        detail: impl FnOnce(&T) -> String,
+    ) -> Option<T> {
+        match result {
+            Ok(value) => {
+                println!("  [PASS] {}: {}", name, detail(&value));
+                Some(value)
+            }
+            Err(e) if required => {
+                println!("  [FAIL] {}: {:#}", name, e);
+                self.failed.push(name);
+                None
+            }
+            Err(e) => {
+                println!("  [WARN] {}: {:#} (optional)", name, e);
+                None
+            }
+        }
+    }
+
+    /// Note a check that could not run because one it depends on failed.
+    fn skip(&self, name: &str, needs: &str) {
+        println!("  [SKIP] {}: needs {}", name, needs);
+    }
+
+    /// Error naming the failed required checks, if any.
+    fn finish(self) -> Result<()> {
+        if self.failed.is_empty() {
+            println!("\nAll required checks passed");
+            return Ok(());
+        }
+        anyhow::bail!(
+            "{} required check(s) failed: {}",
+            self.failed.len(),
+            self.failed.join(", ")
+        )
+    }
+}
+
+/// Bound a self-test network call so an unreachable host fails instead of hanging.
+async fn timed<T, E>(fut: impl Future<Output = Result<T, E>>) -> Result<T>
+where
+    E: Into<anyhow::Error>,
+{
+    tokio::time::timeout(SELF_TEST_TIMEOUT, fut)
+        .await
+        .map_err(|_| anyhow!("timed out after {}s", SELF_TEST_TIMEOUT.as_secs()))?
+        .map_err(Into::into)
+}
+
+/// Check each dependency a trader needs, in order, printing one line per check.
+async fn run_self_test() -> Result<()> {
+    println!("Self-test:");
+    let mut checks = SelfTest::default();
+
+    let config = checks.record(
+        "Config",
+        true,
+        common::Config::from_env().map_err(Into::into),
+        |_| "loaded".to_string(),
+    );
+
+    match &config {
+        Some(config) => {
+            let db = timed(async {
+                let db = common::Database::connect(config).await?;
+                db.health_check().await
+            })
+            .await;
+            checks.record("Database", true, db, |_| {
+                "connected, health check passed".to_string()
+            });
+
+            let gamma = timed(common::GammaClient::new(config).fetch_one_market())
+                .await
+                .and_then(|market| market.ok_or_else(|| anyhow!("no markets returned")));
+            checks.record("Gamma API", true, gamma, |market| {
+                format!("fetched \"{}\"", market.question)
+            });
+        }
+        None => {
+            checks.skip("Database", "config");
+            checks.skip("Gamma API", "config");
+        }
+    }
+
+    let kalshi =
+        timed(common::KalshiClient::new().fetch_markets(Some("open"), None, 1, None)).await;
+    checks.record("Kalshi API", false, kalshi, |(markets, _)| {
+        format!("{} market(s) returned", markets.len())
+    });
+
+    let mut cached_auth = None;
+    let authenticated = timed(common::ensure_authenticated(&mut cached_auth))
+        .await
+        .map(|_| ());
+    match checks.record("CLOB auth", true, authenticated, |_| {
+        "authenticated".to_string()
+    }) {
+        Some(()) => {
+            let auth = cached_auth.as_ref().context("auth not cached")?;
+            let balance = timed(
+                auth.client
+                    .balance_allowance(&BalanceAllowanceRequest::default()),
+            )
+            .await;
+            checks.record("Wallet balance", true, balance, |balance| {
+                format!("${:.2} USDC", balance.balance / Decimal::from(1_000_000))
+            });
+        }
+        None => checks.skip("Wallet balance", "CLOB auth"),
+    }
+
+    checks.finish()
+}
+
+/// Print realized win-rate bucketed by asset and timeframe.
+async fn run_winrate_report(args: &Args) -> Result<()> {
+    println!("\n{}", "=".repeat(50));
+    println!(
+        "Win-Rate by Asset / Timeframe (last {} days, {})",
+        args.winrate_days,
+        if args.winrate_include_dry_run {
+            "live + dry-run"
+        } else {
+            "live only"
+        }
+    );
+    println!("{}", "=".repeat(50));
+
+    let config = common::Config::from_env()?;
+    let db = common::Database::connect(&config).await?;
+
+    let since = Utc::now() - chrono::Duration::days(args.winrate_days);
+    let rows =
+        common::get_winrate_breakdown(db.pool(), since, args.winrate_include_dry_run).await?;
+
+    if rows.is_empty() {
+        println!("\n  No resolved trades found.");
+        return Ok(());
+    }
+
+    println!(
+        "\n  {:<8} {:<12} {:>8} {:>8} {:>9}",
+        "Asset", "Timeframe", "Trades", "Wins", "Win Rate"
+    );
+    println!("  {}", "-".repeat(49));
+
+    let (mut total_trades, mut total_wins) = (0i64, 0i64);
+    for row in &rows {
+        println!(
+            "  {:<8} {:<12} {:>8} {:>8} {:>8.1}%",
+            row.asset, row.timeframe, row.trades, row.wins, row.win_rate
+        );
+        total_trades += row.trades;
+        total_wins += row.wins;
+    }
+
+    let overall = if total_trades > 0 {
+        total_wins as f64 / total_trades as f64 * 100.0
+    } else {
+        0.0
+    };
+    println!("  {}", "-".repeat(49));
+    println!(
+        "  {:<8} {:<12} {:>8} {:>8} {:>8.1}%",
+        "Total", "", total_trades, total_wins, overall
+    );
+
+    Ok(())
+}
+
+/// Print realized P&L from the settlements table, live and dry-run separately.
+async fn run_settlements_report(args: &Args) -> Result<()> {
+    println!("\n{}", "=".repeat(50));
+    println!("Settlements (last {} days)", args.settlements_days);
+    println!("{}", "=".repeat(50));
+
+    let config = common::Config::from_env()?;
+    let db = common::Database::connect(&config).await?;
+
+    let since = Utc::now() - chrono::Duration::days(args.settlements_days);
+    let rows = common::get_settlements_summary(db.pool(), since).await?;
+
+    if rows.is_empty() {
+        println!("\n  No settlements recorded.");
+        return Ok(());
+    }
+
+    println!(
+        "\n  {:<8} {:>8} {:>6} {:>9} {:>11} {:>11}",
+        "Mode", "Settled", "Wins", "Win Rate", "Cost", "P&L"
+    );
+    println!("  {}", "-".repeat(58));
+    for row in &rows {
+        let mode = if row.is_dry_run { "Dry run" } else { "Live" };
+        let sign = if row.total_pnl >= Decimal::ZERO {
+            "+"
+        } else {
+            ""
+        };
+        let cost = format!("${:.2}", row.total_cost);
+        let pnl = format!("{}${:.2}", sign, row.total_pnl);
+        println!(
+            "  {:<8} {:>8} {:>6} {:>8.1}% {:>11} {:>11}",
+            mode,
+            row.settlements,
+            row.wins,
+            row.win_rate(),
+            cost,
+            pnl
+        );
+    }
+
+    Ok(())
+}
+
+/// Print ended markets traded live with no settlement recorded and whether
+/// their resolution is known. With `backfill`, missing Polymarket resolutions
+/// are fetched from Gamma and stored in `market_resolutions`.
+async fn run_unsettled_report(backfill: bool) -> Result<()> {
+    println!("\n{}", "=".repeat(50));
+    println!("Unsettled Live Trades");
+    println!("{}", "=".repeat(50));
+
+    let config = common::Config::from_env()?;
+    let db = common::Database::connect(&config).await?;
+
+    let markets =
+        common::get_unsettled_traded_markets(db.pool(), Utc::now(), SETTLING_STRATEGIES).await?;
+    if markets.is_empty() {
+        println!("\n  No unsettled traded markets.");
+        return Ok(());
+    }
+
+    let ids: Vec<_> = markets.iter().map(|m| m.id).collect();
+    let mut resolutions: HashMap<_, String> = common::get_market_resolutions_batch(db.pool(), &ids)
+        .await?
+        .into_iter()
+        .map(|r| (r.market_id, r.winning_side.to_uppercase()))
+        .collect();
+
+    if backfill {
+        let gamma = common::GammaClient::new(&config);
+        let missing = markets.iter().filter(|m| {
+            m.platform == Platform::Polymarket.as_str() && !resolutions.contains_key(&m.id)
+        });
+        for market in missing {
+            match gamma.fetch_market_resolution(&market.yes_token_id).await {
+                Ok(Some(winning_side)) => {
+                    let winning_side = winning_side.to_uppercase();
+                    common::upsert_market_resolution(
+                        db.pool(),
+                        &common::MarketResolutionInsert {
+                            condition_id: market.condition_id.clone(),
+                            market_type: market.market_type.clone(),
+                            asset: market.asset.clone(),
+                            timeframe: market.timeframe.clone(),
+                            name: market.name.clone(),
+                            yes_token_id: market.yes_token_id.clone(),
+                            no_token_id: market.no_token_id.clone(),
+                            winning_side: winning_side.clone(),
+                            end_time: market.end_time,
+                        },
+                    )
+                    .await?;
+                    resolutions.insert(market.id, winning_side);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Could not fetch resolution for {}: {}", market.name, e),
+            }
+        }
+    }
+
+    println!(
+        "\n  {:<17} {:<6} {:<10} {}",
+        "Ended (UTC)", "Asset", "Resolved", "Market"
+    );
+    println!("  {}", "-".repeat(70));
+    for market in &markets {
+        let resolved = resolutions.get(&market.id).map_or("-", String::as_str);
+        println!(
+            "  {:<17} {:<6} {:<10} {}",
+            market.end_time.format("%Y-%m-%d %H:%M"),
+            market.asset,
+            resolved,
+            market.name.chars().take(40).collect::<String>()
+        );
+    }
+
+    let unresolved = markets.len() - resolutions.len();
+    println!(
+        "\n  {} unsettled markets, {} without a known resolution",
+        markets.len(),
+        unresolved
+    );
+    if unresolved > 0 && !backfill {
+        println!("  Run with --backfill to fetch missing resolutions from Gamma");
+    }
+
+    Ok(())
+}
+
+/// Print the account's open orders, cancelling each one when `cancel_all` is set.
+async fn run_orders_report(api: &dyn OrderApi, cancel_all: bool) -> Result<()> {
+    println!("\n{}", "=".repeat(50));
+    println!("Open Orders");
+    println!("{}", "=".repeat(50));
+
+    let orders = api.open_orders().await?;
+    if orders.is_empty() {
+        println!("\n  No open orders.");
+        return Ok(());
+    }
+
+    // Order IDs are printed in full so they can be copied into a cancel
+    println!(
+        "\n  {:<66} {:<19} {:<5} {:>7} {:>9} {:>9}",
+        "Order", "Token", "Side", "Price", "Size", "Filled"
+    );
+    println!("  {}", "-".repeat(120));
+    for order in &orders {
+        println!(
+            "  {:<66} {:<19} {:<5} {:>7} {:>9} {:>9}",
+            order.order_id,
+            short_id(&order.token_id),
+            order.side,
+            order.price,
+            order.original_size,
+            order.size_matched
+        );
+    }
+    println!("\n  {} open order(s)", orders.len());
+
+    if !cancel_all {
+        println!("  (pass --cancel-all to cancel them)");
+        return Ok(());
+    }
+
+    let mut cancelled = 0;
+    for order in &orders {
+        match api.cancel(&order.order_id).await {
+            Ok(()) => cancelled += 1,
+            Err(e) => warn!("Failed to cancel {}: {}", order.order_id, e),
+        }
+    }
+    println!("  Cancelled {}/{} order(s)", cancelled, orders.len());
+
+    Ok(())
+}
+
+/// Shorten a long token ID for table display, keeping both ends so IDs that
+/// share a prefix stay distinguishable.
+fn short_id(id: &str) -> String {
+    let chars: Vec<char> = id.chars().collect();
+    if chars.len() > 19 {
+        let head: String = chars[..8].iter().collect();
+        let tail: String = chars[chars.len() - 8..].iter().collect();
+        format!("{}...{}", head, tail)
+    } else {
+        id.to_string()
+    }
+}
+
+/// Print active markets the orderbook streams aren't keeping fresh.
+async fn run_coverage_report(args: &Args) -> Result<()> {
+    println!("\n{}", "=".repeat(50));
+    println!(
+        "Orderbook Coverage Gaps (no snapshot in last {}s)",
+        args.coverage_max_age
+    );
+    println!("{}", "=".repeat(50));
+
+    let config = common::Config::from_env()?;
+    let db = common::Database::connect(&config).await?;
+
+    let assets: Vec<String> = args
+        .coverage_assets
+        .split(',')
+        .map(|s| s.trim().to_uppercase())
+        .collect();
+    let gaps =
+        common::get_markets_without_fresh_orderbooks(db.pool(), args.coverage_max_age, &assets)
+            .await?;
+
+    if gaps.is_empty() {
+        println!("\n  All active markets have fresh orderbooks.");
+        return Ok(());
+    }
+
+    println!(
+        "\n  {:<11} {:<6} {:<6} {:>10} {:>9}  {}",
+        "Platform", "Asset", "TF", "Last Snap", "Expires", "Market"
+    );
+    println!("  {}", "-".repeat(70));
+
+    let now = Utc::now();
+    let mut never = 0;
+    for market in &gaps {
+        let last_snapshot = match market.captured_at {
+            Some(at) => format!("{}s ago", (now - at).num_seconds()),
+            None => {
+                never += 1;
+                "never".to_string()
+            }
+        };
+        println!(
+            "  {:<11} {:<6} {:<6} {:>10} {:>8}m  {}",
+            market.platform,
+            market.asset,
+            market.timeframe,
+            last_snapshot,
+            (market.end_time - now).num_minutes(),
+            market.name
+        );
+    }
+
+    println!("  {}", "-".repeat(70));
+    println!(
+        "  {} uncovered market(s): {} stale, {} never captured",
+        gaps.len(),
+        gaps.len() - never,
+        never
+    );
+
+    Ok(())
+}
+
 /// CLOB book response for a single token.
 #[derive(Debug, Deserialize)]
 struct ClobBook {
@@ -588,9 +1143,6 @@ async fn run_audit_prices(args: &Args) -> Result<()> {
         } else {
             println!("  ⚠️  No snapshot found in DB");
         }
-
-        // Rate limit API calls
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     }
 
     // Summary
@@ -664,9 +1216,105 @@ fn prices_match(a: Option<Decimal>, b: Option<Decimal>) -> bool {
     }
 }
 
+/// Start of the --pnl window as a unix timestamp, if one was requested.
+/// Errors if `--last-hours` reaches back further than a timestamp can go.
+fn pnl_window_start(args: &Args, now: DateTime<Utc>) -> Result<Option<i64>> {
+    match (args.since, args.last_hours) {
+        (Some(since), _) => Ok(Some(since.timestamp())),
+        (None, Some(hours)) => chrono::Duration::try_hours(hours)
+            .and_then(|window| now.checked_sub_signed(window))
+            .map(|start| Some(start.timestamp()))
+            .ok_or_else(|| anyhow!("--last-hours {} is out of range", hours)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Describe the activity window a P&L report covers.
+fn pnl_window_label(window_start: Option<i64>, count: usize) -> String {
+    match window_start.and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0)) {
+        Some(start) => format!(
+            "since {} ({} activities)",
+            start.format("%Y-%m-%d %H:%M:%S UTC"),
+            count
+        ),
+        None => format!("most recent {} activities", count),
+    }
+}
+
+/// Keep only activities at or after `since` (unix seconds).
+fn activities_since(activities: Vec<Activity>, since: i64) -> Vec<Activity> {
+    activities
+        .into_iter()
+        .filter(|a| a.timestamp >= since)
+        .collect()
+}
+
+/// Fetch account activity from the Data API, newest first.
+///
+/// Without a window this is the latest page only. With one, pages are
+/// followed until an activity older than `since` appears (or the history
+/// runs out), and the result is trimmed to the window.
+async fn fetch_activities(
+    http_client: &reqwest::Client,
+    user: &str,
+    since: Option<i64>,
+) -> Result<Vec<Activity>> {
+    let mut activities = Vec::new();
+    let mut pages = 0;
+
+    loop {
+        let url = format!(
+            "{}/activity?user={}&limit={}&offset={}",
+            DATA_API_URL,
+            user,
+            ACTIVITY_PAGE_SIZE,
+            pages * ACTIVITY_PAGE_SIZE
+        );
+        rate_limit::for_url(DATA_API_URL, DATA_API_REQUESTS_PER_SEC)
+            .acquire()
+            .await;
+        let resp = http_client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch activity")?;
+        if !resp.status().is_success() {
+            anyhow::bail!("Data API error: {}", resp.status());
+        }
+        let page: Vec<Activity> = resp.json().await.context("Failed to parse activity")?;
+        pages += 1;
+
+        let reached_start = match since {
+            Some(since) => page.iter().any(|a| a.timestamp < since),
+            None => true,
+        };
+        let exhausted = page.len() < ACTIVITY_PAGE_SIZE;
+        activities.extend(page);
+
+        if reached_start || exhausted {
+            break;
+        }
+        if pages >= MAX_ACTIVITY_PAGES {
+            warn!(
+                "Stopped after {} activity pages; the P&L window may be incomplete",
+                pages
+            );
+            break;
+        }
+    }
+
+    Ok(match since {
+        Some(since) => activities_since(activities, since),
+        None => activities,
+    })
+}
+
 /// Fetch orderbook from CLOB REST API.
 async fn fetch_clob_book(http_client: &reqwest::Client, token_id: &str) -> Result<ClobBook> {
     let url = format!("{}/book?token_id={}", CLOB_HOST, token_id);
+    rate_limit::for_url(CLOB_HOST, CLOB_REQUESTS_PER_SEC)
+        .acquire()
+        .await;
     let resp = http_client
         .get(&url)
         .send()
@@ -680,3 +1328,117 @@ async fn fetch_clob_book(http_client: &reqwest::Client, token_id: &str) -> Resul
     let book: ClobBook = resp.json().await.context("Failed to parse CLOB book")?;
     Ok(book)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(condition_id: &str, timestamp: i64) -> Activity {
+        Activity {
+            side: Some("BUY".to_string()),
+            activity_type: Some("TRADE".to_string()),
+            size: 10.0,
+            price: 0.5,
+            usdc_size: Some(5.0),
+            title: Some(format!("Market {}", condition_id)),
+            condition_id: condition_id.to_string(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_activities_since_filters_by_timestamp() {
+        let since = 1_704_067_200; // 2024-01-01T00:00:00Z
+        let fixture = vec![
+            activity("after", since + 3600),
+            activity("at-start", since),
+            activity("before", since - 1),
+            activity("long-before", since - 86_400),
+        ];
+
+        let kept = activities_since(fixture, since);
+        let ids: Vec<_> = kept.iter().map(|a| a.condition_id.as_str()).collect();
+        assert_eq!(ids, ["after", "at-start"]);
+    }
+
+    #[test]
+    fn test_short_id_keeps_both_ends() {
+        assert_eq!(short_id("12345"), "12345");
+        assert_eq!(
+            short_id(
+                "71321045679252212594626385532706912750332728571942532289631379312455583992563"
+            ),
+            "71321045...83992563"
+        );
+
+        // IDs sharing a long prefix still print differently
+        let a = "0x1111111111111111111111111111111111111111111111111111111111111aaa";
+        let b = "0x1111111111111111111111111111111111111111111111111111111111111bbb";
+        assert_ne!(short_id(a), short_id(b));
+    }
+
+    #[test]
+    fn test_pnl_window_start() {
+        let now = DateTime::parse_from_rfc3339("2024-01-02T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let args = Args::parse_from(["poly-check", "--pnl"]);
+        assert_eq!(pnl_window_start(&args, now).unwrap(), None);
+
+        let args = Args::parse_from(["poly-check", "--pnl", "--since", "2024-01-01T00:00:00Z"]);
+        assert_eq!(pnl_window_start(&args, now).unwrap(), Some(1_704_067_200));
+
+        let args = Args::parse_from(["poly-check", "--pnl", "--last-hours", "36"]);
+        assert_eq!(pnl_window_start(&args, now).unwrap(), Some(1_704_067_200));
+
+        // Too far back to represent: an error, not a panic
+        let hours = i64::MAX.to_string();
+        let args = Args::parse_from(["poly-check", "--pnl", "--last-hours", hours.as_str()]);
+        assert!(pnl_window_start(&args, now).is_err());
+        let args = Args::parse_from(["poly-check", "--pnl", "--last-hours", "1000000000000"]);
+        assert!(pnl_window_start(&args, now).is_err());
+
+        // The two window flags are mutually exclusive
+        assert!(Args::try_parse_from([
+            "poly-check",
+            "--since",
+            "2024-01-01T00:00:00Z",
+            "--last-hours",
+            "1"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_pnl_window_label() {
+        assert_eq!(
+            pnl_window_label(Some(1_704_067_200), 3),
+            "since 2024-01-01 00:00:00 UTC (3 activities)"
+        );
+        assert_eq!(pnl_window_label(None, 100), "most recent 100 activities");
+    }
+
+    #[test]
+    fn test_self_test_fails_only_on_required_checks() {
+        let mut checks = SelfTest::default();
+        assert_eq!(
+            checks.record("Config", true, Ok(7), |n| n.to_string()),
+            Some(7)
+        );
+        let kalshi: Result<()> = Err(anyhow!("connection refused"));
+        assert_eq!(
+            checks.record("Kalshi API", false, kalshi, |_| String::new()),
+            None
+        );
+        assert!(checks.failed.is_empty());
+
+        let db: Result<()> = Err(anyhow!("password authentication failed"));
+        assert_eq!(checks.record("Database", true, db, |_| String::new()), None);
+        assert_eq!(checks.failed, vec!["Database"]);
+
+        let err = checks.finish().unwrap_err().to_string();
+        assert_eq!(err, "1 required check(s) failed: Database");
+        assert!(SelfTest::default().finish().is_ok());
+    }
+}
@@ -28,6 +28,9 @@ pub struct ExecutorConfig {
     pub max_time_to_expiry_secs: i64,
     /// Trading fee rate (e.g., 0.001 = 0.1%)
     pub fee_rate: Decimal,
+    /// Minimum ratio of raw spread profit to the fee on both legs
+    /// (e.g., 2.0 = profit must be at least 2x fees). 0 disables the check.
+    pub min_spread_to_fee_ratio: Decimal,
     /// Assets to trade
     pub assets: Vec<String>,
     /// Maximum allowed spread widening before aborting trade.
@@ -47,6 +50,22 @@ pub struct ExecutorConfig {
     /// Enable sequential placement mode when price mismatch detected.
     /// Default: true
     pub enable_sequential_placement: bool,
+    /// Minimum milliseconds between live trades across all markets (0 = no limit).
+    /// Default: 0
+    pub min_trade_interval_ms: u64,
+    /// Rest both legs one tick above the best bid instead of lifting the asks.
+    /// Default: false
+    pub passive_legs: bool,
+    /// How long passive legs may rest before the lagging leg crosses (seconds).
+    /// Default: 10
+    pub passive_timeout_secs: u64,
+}
+
+impl ExecutorConfig {
+    /// Most a hedged YES + NO pair may cost while still clearing `min_profit`.
+    pub fn max_pair_cost(&self) -> Decimal {
+        Decimal::ONE - self.min_profit
+    }
 }
 
 impl Default for ExecutorConfig {
@@ -61,8 +80,9 @@ impl Default for ExecutorConfig {
             max_total_exposure: Decimal::new(1000, 0),
             max_orderbook_age_secs: 30,
             max_price_age_secs: 60,
-            max_time_to_expiry_secs: 3600, // 1 hour
-            fee_rate: Decimal::new(1, 3),  // 0.001 = 0.1%
+            max_time_to_expiry_secs: 3600,    // 1 hour
+            fee_rate: Decimal::new(1, 3),     // 0.001 = 0.1%
+            min_spread_to_fee_ratio: dec!(0), // Disabled
             assets: vec![
                 "BTC".to_string(),
                 "ETH".to_string(),
@@ -74,6 +94,9 @@ impl Default for ExecutorConfig {
             sequential_poll_interval_ms: 1000,    // 1 second
             sequential_poll_timeout_secs: 10,     // 10 seconds max wait
             enable_sequential_placement: true,    // Enabled by default
+            min_trade_interval_ms: 0,
+            passive_legs: false,
+            passive_timeout_secs: 10,
         }
     }
 }
@@ -14,6 +14,10 @@ pub struct SpreadDetector {
     min_profit: Decimal,
     /// Maximum price age in seconds
     max_price_age_secs: i64,
+    /// Trading fee rate applied to the cost of both legs
+    fee_rate: Decimal,
+    /// Minimum ratio of raw profit to fees (0 = disabled)
+    min_spread_to_fee_ratio: Decimal,
 }
 
 impl SpreadDetector {
@@ -22,9 +26,20 @@ impl SpreadDetector {
         Self {
             min_profit,
             max_price_age_secs,
+            fee_rate: dec!(0),
+            min_spread_to_fee_ratio: dec!(0),
         }
     }
 
+    /// Require the raw profit to cover the fee on both legs `ratio` times over.
+    ///
+    /// Opportunities that only just clear fees are wiped out by any slippage.
+    pub fn with_fee_guard(mut self, fee_rate: Decimal, ratio: Decimal) -> Self {
+        self.fee_rate = fee_rate;
+        self.min_spread_to_fee_ratio = ratio;
+        self
+    }
+
     /// Check a single market for arbitrage opportunity.
     ///
     /// An opportunity exists when:
@@ -74,6 +89,20 @@ impl SpreadDetector {
             return None;
         }
 
+        // Check profit covers the fee on both legs by the configured margin
+        let fee = spread * self.fee_rate;
+        if self.min_spread_to_fee_ratio > dec!(0) && profit_pct < fee * self.min_spread_to_fee_ratio
+        {
+            tracing::debug!(
+                "Market {}: profit {} below {}x fee {}",
+                &market.name[..market.name.len().min(40)],
+                profit_pct,
+                self.min_spread_to_fee_ratio,
+                fee
+            );
+            return None;
+        }
+
         Some(SpreadOpportunity {
             market_id: market.id,
             condition_id: market.condition_id.clone(),
@@ -229,6 +258,24 @@ mod tests {
         assert!(opp.is_none()); // Below 5% threshold
     }
 
+    #[test]
+    fn test_fee_ratio_rejects_thin_opportunity() {
+        // 2% fee: YES $0.49 + NO $0.49 costs $0.98 and pays $0.0196 in fees
+        let market = make_market(dec!(0.49), dec!(0.49));
+
+        // $0.02 profit clears the 1% minimum...
+        let detector = SpreadDetector::new(dec!(0.01), 60);
+        assert!(detector.check_opportunity(&market).is_some());
+
+        // ...but is not 2x the fee
+        let detector = SpreadDetector::new(dec!(0.01), 60).with_fee_guard(dec!(0.02), dec!(2));
+        assert!(detector.check_opportunity(&market).is_none());
+
+        // A wider spread comfortably covers it
+        let market = make_market(dec!(0.45), dec!(0.45));
+        assert!(detector.check_opportunity(&market).is_some());
+    }
+
     #[test]
     fn test_calculate_trade_details() {
         let detector = SpreadDetector::new(dec!(0.01), 60);
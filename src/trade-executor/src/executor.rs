@@ -1,18 +1,14 @@
 //! Trade executor state machine.
 
 use std::collections::{HashMap, HashSet};
-use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use alloy::signers::local::LocalSigner;
-use alloy::signers::Signer;
 use anyhow::{Context, Result};
 use chrono::Utc;
-use polymarket_client_sdk::clob::types::{OrderType, SignatureType};
+use polymarket_client_sdk::clob::types::{OrderType, TradeResponse, TradesRequestBuilder};
 use polymarket_client_sdk::clob::{Client as ClobClient, Config as ClobConfig};
-use polymarket_client_sdk::POLYGON;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use rust_decimal_macros::dec;
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
@@ -39,35 +35,23 @@ const UNFILLED_WAIT_SECS: u64 = 10;
 /// Minimum order size for Polymarket (shares)
 const MIN_ORDER_SIZE: Decimal = dec!(5);
 
-use common::models::OrderbookSnapshot;
+use common::models::{OrderbookSnapshot, TradeIntent, TradeMode, TradeOutcome};
 use common::repository::{self, MarketWithPrices};
-use common::Database;
+use common::{
+    fetch_live_best_ask, normalize_price_to_tick, record_trade_audit, resolve_signature_config,
+    ClobBook, Database, TradeThrottle, DEFAULT_TICK_SIZE,
+};
 
 use crate::balance::{
     calculate_safe_sell_amount, find_balance, BalanceChecker, GammaBalanceChecker,
 };
 
+/// Strategy tag recorded with each trade intent
+const STRATEGY_TAG: &str = "spread_arb";
+
 /// Gamma Data API URL for balance queries
 const GAMMA_DATA_API_URL: &str = "https://data-api.polymarket.com";
 
-/// CLOB orderbook response for price fetching
-#[derive(serde::Deserialize)]
-struct ClobBook {
-    #[serde(default)]
-    bids: Vec<ClobLevel>,
-    #[serde(default)]
-    asks: Vec<ClobLevel>,
-}
-
-/// CLOB orderbook price level
-#[derive(serde::Deserialize)]
-struct ClobLevel {
-    price: String,
-    #[serde(default)]
-    #[allow(dead_code)]
-    size: String,
-}
-
 /// Calculate available liquidity from orderbook depth at best ask price.
 /// Returns the minimum USDC value available at best ask between YES and NO sides.
 /// (For spread arb, we need liquidity on BOTH sides - limited by the smaller one)
@@ -286,19 +270,7 @@ async fn fetch_and_log_live_orderbook(
     // Parse YES book
     let yes_prices = match yes_result {
         Ok(resp) => match resp.json::<ClobBook>().await {
-            Ok(book) => {
-                let best_ask = book
-                    .asks
-                    .iter()
-                    .filter_map(|l| l.price.parse::<Decimal>().ok())
-                    .min();
-                let best_bid = book
-                    .bids
-                    .iter()
-                    .filter_map(|l| l.price.parse::<Decimal>().ok())
-                    .max();
-                Some((best_ask, best_bid))
-            }
+            Ok(book) => Some((book.best_ask(), book.best_bid())),
             Err(_) => None,
         },
         Err(_) => None,
@@ -307,19 +279,7 @@ async fn fetch_and_log_live_orderbook(
     // Parse NO book
     let no_prices = match no_result {
         Ok(resp) => match resp.json::<ClobBook>().await {
-            Ok(book) => {
-                let best_ask = book
-                    .asks
-                    .iter()
-                    .filter_map(|l| l.price.parse::<Decimal>().ok())
-                    .min();
-                let best_bid = book
-                    .bids
-                    .iter()
-                    .filter_map(|l| l.price.parse::<Decimal>().ok())
-                    .max();
-                Some((best_ask, best_bid))
-            }
+            Ok(book) => Some((book.best_ask(), book.best_bid())),
             Err(_) => None,
         },
         Err(_) => None,
@@ -352,66 +312,14 @@ async fn fetch_and_log_live_orderbook(
 /// Returns (yes_best_ask, no_best_ask) or None if fetch fails.
 /// Used for price mismatch detection before order placement.
 async fn fetch_live_clob_prices(
-    http_client: &reqwest::Client,
     yes_token_id: &str,
     no_token_id: &str,
 ) -> Option<(Decimal, Decimal)> {
-    let yes_url = format!("{}/book?token_id={}", CLOB_HOST, yes_token_id);
-    let no_url = format!("{}/book?token_id={}", CLOB_HOST, no_token_id);
-
-    // Fetch both in parallel with timeout
-    let (yes_result, no_result) = tokio::join!(
-        timeout(Duration::from_secs(5), http_client.get(&yes_url).send()),
-        timeout(Duration::from_secs(5), http_client.get(&no_url).send()),
+    let (yes_ask, no_ask) = tokio::join!(
+        fetch_live_best_ask(yes_token_id),
+        fetch_live_best_ask(no_token_id),
     );
-
-    // Parse YES best ask
-    let yes_ask = match yes_result {
-        Ok(Ok(resp)) => match resp.json::<ClobBook>().await {
-            Ok(book) => book
-                .asks
-                .iter()
-                .filter_map(|l| l.price.parse::<Decimal>().ok())
-                .min(),
-            Err(e) => {
-                warn!("[LIVE-PRICE] Failed to parse YES orderbook: {:?}", e);
-                None
-            }
-        },
-        Ok(Err(e)) => {
-            warn!("[LIVE-PRICE] Failed to fetch YES orderbook: {:?}", e);
-            None
-        }
-        Err(_) => {
-            warn!("[LIVE-PRICE] YES orderbook fetch timed out");
-            None
-        }
-    }?;
-
-    // Parse NO best ask
-    let no_ask = match no_result {
-        Ok(Ok(resp)) => match resp.json::<ClobBook>().await {
-            Ok(book) => book
-                .asks
-                .iter()
-                .filter_map(|l| l.price.parse::<Decimal>().ok())
-                .min(),
-            Err(e) => {
-                warn!("[LIVE-PRICE] Failed to parse NO orderbook: {:?}", e);
-                None
-            }
-        },
-        Ok(Err(e)) => {
-            warn!("[LIVE-PRICE] Failed to fetch NO orderbook: {:?}", e);
-            None
-        }
-        Err(_) => {
-            warn!("[LIVE-PRICE] NO orderbook fetch timed out");
-            None
-        }
-    }?;
-
-    Some((yes_ask, no_ask))
+    Some((yes_ask?, no_ask?))
 }
 
 /// Check if there's a price mismatch requiring sequential placement.
@@ -642,11 +550,494 @@ async fn query_order_fill(clob_client: &AuthenticatedClobClient, order_id: &str)
     }
 }
 
+/// Query the size-weighted price actually paid on an order, from the trades
+/// it matched. Returns None if the order or its trades can't be fetched.
+async fn query_fill_avg_price(
+    clob_client: &AuthenticatedClobClient,
+    order_id: &str,
+    token_id: &str,
+) -> Option<Decimal> {
+    let order = match timeout(
+        Duration::from_secs(CANCEL_TIMEOUT_SECS),
+        clob_client.order(order_id),
+    )
+    .await
+    {
+        Ok(Ok(order_info)) => order_info,
+        Ok(Err(e)) => {
+            warn!("[FILL] Failed to query order {}: {:?}", order_id, e);
+            return None;
+        }
+        Err(_) => {
+            warn!("[FILL] Query timeout for order {}", order_id);
+            return None;
+        }
+    };
+
+    let mut matches = Vec::new();
+    for trade_id in &order.associate_trades {
+        let request = TradesRequestBuilder::default()
+            .id(trade_id.as_str())
+            .build()
+            .ok()?;
+        match timeout(
+            Duration::from_secs(CANCEL_TIMEOUT_SECS),
+            clob_client.trades(&request, None),
+        )
+        .await
+        {
+            Ok(Ok(page)) => {
+                for trade in &page.data {
+                    matches.extend(order_matches(trade, order_id, token_id));
+                }
+            }
+            Ok(Err(e)) => {
+                warn!("[FILL] Failed to query trade {}: {:?}", trade_id, e);
+                return None;
+            }
+            Err(_) => {
+                warn!("[FILL] Query timeout for trade {}", trade_id);
+                return None;
+            }
+        }
+    }
+    fill_vwap(&matches)
+}
+
+/// (price, shares) our order got from one trade, priced in `token_id`.
+/// As taker we pay each maker's price; a maker on the other outcome was
+/// matched by minting, so the price we paid is its complement.
+fn order_matches(trade: &TradeResponse, order_id: &str, token_id: &str) -> Vec<(Decimal, Decimal)> {
+    if trade.taker_order_id == order_id {
+        trade
+            .maker_orders
+            .iter()
+            .map(|m| {
+                let price = if m.asset_id == token_id {
+                    m.price
+                } else {
+                    Decimal::ONE - m.price
+                };
+                (price, m.matched_amount)
+            })
+            .collect()
+    } else {
+        trade
+            .maker_orders
+            .iter()
+            .filter(|m| m.order_id == order_id)
+            .map(|m| (m.price, m.matched_amount))
+            .collect()
+    }
+}
+
+/// Size-weighted average of (price, shares) matches, or None if nothing matched.
+fn fill_vwap(matches: &[(Decimal, Decimal)]) -> Option<Decimal> {
+    let shares: Decimal = matches.iter().map(|(_, size)| *size).sum();
+    let cost: Decimal = matches.iter().map(|(price, size)| price * size).sum();
+    (shares > Decimal::ZERO).then(|| (cost / shares).round_dp(4))
+}
+
+/// Extract (order_id, immediate fill, error) from a post-order response.
+/// A response carrying an error message is treated as having no order id.
+fn extract_order_info(
+    result: &[polymarket_client_sdk::clob::types::PostOrderResponse],
+) -> Option<(Option<String>, Decimal, Option<String>)> {
+    result.first().map(|r| {
+        let has_error = r.error_msg.as_ref().map(|e| !e.is_empty()).unwrap_or(false);
+        let order_id = if r.order_id.is_empty() || has_error {
+            None
+        } else {
+            Some(r.order_id.clone())
+        };
+        let filled = r.taking_amount;
+        let error = r.error_msg.clone();
+        (order_id, filled, error)
+    })
+}
+
+/// Price a passive leg one tick above the best bid.
+/// Returns None when there is no bid or the improved price would reach the ask.
+fn passive_quote(best_bid: Option<Decimal>, best_ask: Decimal, tick: Decimal) -> Option<Decimal> {
+    let quote = best_bid? + tick;
+    (quote < best_ask).then_some(quote)
+}
+
+/// Look up the market's minimum tick size, falling back to
+/// [`DEFAULT_TICK_SIZE`]. Served from the SDK cache once warmed.
+async fn market_tick_size(clob_client: &AuthenticatedClobClient, token_id: &str) -> Decimal {
+    match timeout(
+        Duration::from_secs(ORDER_TIMEOUT_SECS),
+        clob_client.tick_size(token_id),
+    )
+    .await
+    {
+        Ok(Ok(response)) => Decimal::from(response.minimum_tick_size),
+        Ok(Err(e)) => {
+            warn!(
+                "[TICK] Token {}: {:#}, assuming {}",
+                token_id, e, DEFAULT_TICK_SIZE
+            );
+            DEFAULT_TICK_SIZE
+        }
+        Err(_) => {
+            warn!(
+                "[TICK] Token {}: tick size lookup timed out, assuming {}",
+                token_id, DEFAULT_TICK_SIZE
+            );
+            DEFAULT_TICK_SIZE
+        }
+    }
+}
+
+/// Build, sign and post a single limit buy.
+/// Returns (order_id, immediate fill, error) as reported by the CLOB.
+async fn post_limit_buy(
+    clob_client: &AuthenticatedClobClient,
+    signer: &PrivateKeySigner,
+    token_id: &str,
+    size: Decimal,
+    price: Decimal,
+    tag: &str,
+) -> Result<(Option<String>, Decimal, Option<String>)> {
+    let order = timeout(
+        Duration::from_secs(ORDER_TIMEOUT_SECS),
+        clob_client
+            .limit_order()
+            .token_id(token_id)
+            .size(size)
+            .price(price)
+            .side(polymarket_client_sdk::clob::types::Side::Buy)
+            .build(),
+    )
+    .await
+    .with_context(|| format!("{} order building timed out", tag))?
+    .with_context(|| format!("Failed to build {} order", tag))?;
+
+    let signed = timeout(
+        Duration::from_secs(ORDER_TIMEOUT_SECS),
+        clob_client.sign(signer, order),
+    )
+    .await
+    .with_context(|| format!("{} order signing timed out", tag))?
+    .with_context(|| format!("Failed to sign {} order", tag))?;
+
+    let result = timeout(
+        Duration::from_secs(ORDER_TIMEOUT_SECS),
+        clob_client.post_order(signed),
+    )
+    .await
+    .with_context(|| format!("{} order posting timed out", tag))?
+    .with_context(|| format!("Failed to post {} order", tag))?;
+
+    info!("[PASSIVE] {} order result: {:?}", tag, result);
+    Ok(extract_order_info(&result).unwrap_or((None, dec!(0), Some("No response".to_string()))))
+}
+
+/// Wait for a resting order to fill, cancelling any remainder at the timeout.
+/// Unlike sequential placement, a partial fill is cancelled too so the leg
+/// cannot keep filling after the other side has been hedged.
+async fn settle_passive_leg(
+    clob_client: &AuthenticatedClobClient,
+    order_id: &str,
+    immediate_fill: Decimal,
+    target_size: Decimal,
+    poll_interval_ms: u64,
+    timeout_secs: u64,
+    tag: &str,
+) -> Decimal {
+    if immediate_fill >= target_size {
+        return immediate_fill;
+    }
+    match poll_order_fill(
+        clob_client,
+        order_id,
+        target_size,
+        poll_interval_ms,
+        timeout_secs,
+    )
+    .await
+    {
+        PollResult::FullyFilled(filled) => filled,
+        PollResult::PartialFill(_) | PollResult::Timeout | PollResult::Error(_) => {
+            cancel_order_with_retries(clob_client, order_id, tag).await;
+            query_order_fill(clob_client, order_id)
+                .await
+                .max(immediate_fill)
+        }
+    }
+}
+
+/// Final state of one leg of a passive spread capture.
+struct LegFill {
+    order_id: Option<String>,
+    filled: Decimal,
+    cost: Decimal,
+}
+
+impl LegFill {
+    /// Volume-weighted price paid across the passive fill and any crossing fill.
+    fn avg_price(&self) -> Option<Decimal> {
+        (self.filled > Decimal::ZERO).then(|| (self.cost / self.filled).round_dp(4))
+    }
+}
+
+/// Spread capture: rest both legs at their passive quotes, poll for fills, and
+/// if one leg fills without the other, cross the spread on the lagging leg.
+/// `asks` are the detection asks, used if the live book can't be fetched.
+/// The lagging leg only crosses while the filled leg's price plus the ask
+/// stays within `max_pair_cost`; otherwise the filled leg is held unhedged.
+#[allow(clippy::too_many_arguments)]
+async fn execute_passive_legs(
+    clob_client: &AuthenticatedClobClient,
+    signer: &PrivateKeySigner,
+    opportunity: &SpreadOpportunity,
+    size: Decimal,
+    quotes: (Decimal, Decimal),
+    asks: (Decimal, Decimal),
+    tick: Decimal,
+    max_pair_cost: Decimal,
+    poll_interval_ms: u64,
+    timeout_secs: u64,
+) -> (LegFill, LegFill) {
+    let (yes_quote, no_quote) = quotes;
+    info!(
+        "[PASSIVE] Posting YES {} @ ${} and NO {} @ ${}",
+        size, yes_quote, size, no_quote
+    );
+    let (yes_post, no_post) = tokio::join!(
+        post_limit_buy(
+            clob_client,
+            signer,
+            &opportunity.yes_token_id,
+            size,
+            yes_quote,
+            "YES"
+        ),
+        post_limit_buy(
+            clob_client,
+            signer,
+            &opportunity.no_token_id,
+            size,
+            no_quote,
+            "NO"
+        ),
+    );
+
+    // A leg that failed to post counts as unfilled; the other leg still
+    // rests and is hedged by crossing if it fills.
+    let posted = |result: Result<(Option<String>, Decimal, Option<String>)>, tag: &str| match result
+    {
+        Ok((Some(order_id), filled, _)) => (Some(order_id), filled),
+        Ok((None, _, error)) => {
+            warn!("[PASSIVE] {} order rejected: {:?}", tag, error);
+            (None, Decimal::ZERO)
+        }
+        Err(e) => {
+            warn!("[PASSIVE] {} order failed: {:#}", tag, e);
+            (None, Decimal::ZERO)
+        }
+    };
+    let (yes_id, yes_immediate) = posted(yes_post, "YES");
+    let (no_id, no_immediate) = posted(no_post, "NO");
+
+    let (yes_filled, no_filled) = tokio::join!(
+        async {
+            match yes_id.as_deref() {
+                Some(order_id) => {
+                    settle_passive_leg(
+                        clob_client,
+                        order_id,
+                        yes_immediate,
+                        size,
+                        poll_interval_ms,
+                        timeout_secs,
+                        "YES",
+                    )
+                    .await
+                }
+                None => Decimal::ZERO,
+            }
+        },
+        async {
+            match no_id.as_deref() {
+                Some(order_id) => {
+                    settle_passive_leg(
+                        clob_client,
+                        order_id,
+                        no_immediate,
+                        size,
+                        poll_interval_ms,
+                        timeout_secs,
+                        "NO",
+                    )
+                    .await
+                }
+                None => Decimal::ZERO,
+            }
+        },
+    );
+
+    let mut yes_leg = LegFill {
+        order_id: yes_id,
+        filled: yes_filled,
+        cost: yes_filled * yes_quote,
+    };
+    let mut no_leg = LegFill {
+        order_id: no_id,
+        filled: no_filled,
+        cost: no_filled * no_quote,
+    };
+
+    match HedgeAction::decide(yes_leg.filled, no_leg.filled) {
+        HedgeAction::Hedged => {
+            info!("[PASSIVE] Both legs filled {} shares", yes_leg.filled);
+        }
+        HedgeAction::Abandon => {
+            info!("[PASSIVE] Neither leg filled within {}s", timeout_secs);
+        }
+        HedgeAction::Cross { side, shares } => {
+            let (leg, filled_price, token_id, fallback_ask, tag) = match side {
+                OrderSide::Yes => (
+                    &mut yes_leg,
+                    no_leg.avg_price().unwrap_or(no_quote),
+                    &opportunity.yes_token_id,
+                    asks.0,
+                    "YES",
+                ),
+                OrderSide::No => (
+                    &mut no_leg,
+                    yes_leg.avg_price().unwrap_or(yes_quote),
+                    &opportunity.no_token_id,
+                    asks.1,
+                    "NO",
+                ),
+            };
+            let ask = normalize_price_to_tick(
+                fetch_live_best_ask(token_id).await.unwrap_or(fallback_ask),
+                tick,
+                RoundingStrategy::ToPositiveInfinity,
+            );
+            if shares < MIN_ORDER_SIZE {
+                error!(
+                    "[PASSIVE] {} leg short {} shares, below minimum order size {} - position left unhedged",
+                    tag, shares, MIN_ORDER_SIZE
+                );
+            } else if filled_price + ask > max_pair_cost {
+                error!(
+                    "[PASSIVE] {} leg short {} shares, crossing at ${} would cost ${} a pair (max ${}) - position left unhedged",
+                    tag, shares, ask, filled_price + ask, max_pair_cost
+                );
+            } else {
+                warn!(
+                    "[PASSIVE] {} leg short {} shares after {}s, crossing at ${}",
+                    tag, shares, timeout_secs, ask
+                );
+                match post_limit_buy(clob_client, signer, token_id, shares, ask, tag).await {
+                    Ok((Some(order_id), immediate, _)) => {
+                        let filled = settle_passive_leg(
+                            clob_client,
+                            &order_id,
+                            immediate,
+                            shares,
+                            poll_interval_ms,
+                            timeout_secs,
+                            tag,
+                        )
+                        .await;
+                        leg.filled += filled;
+                        leg.cost += filled * ask;
+                        leg.order_id.get_or_insert(order_id);
+                    }
+                    Ok((None, _, error)) => {
+                        error!("[PASSIVE] {} crossing order rejected: {:?}", tag, error);
+                    }
+                    Err(e) => {
+                        error!("[PASSIVE] {} crossing order failed: {:#}", tag, e);
+                    }
+                }
+            }
+        }
+    }
+
+    (yes_leg, no_leg)
+}
+
+/// One trade intent per leg of a spread trade, at the detection prices.
+fn leg_intents(
+    opportunity: &SpreadOpportunity,
+    details: &TradeDetails,
+    dry_run: bool,
+) -> [TradeIntent; 2] {
+    let leg = |token_id: &str, side: &str, shares: Decimal, price: Decimal| TradeIntent {
+        market_id: opportunity.market_id,
+        token_id: token_id.to_string(),
+        side: side.to_string(),
+        shares,
+        limit_price: price,
+        effective_price: price,
+        strategy_tag: STRATEGY_TAG.to_string(),
+        mode: TradeMode::from_dry_run(dry_run),
+    };
+    [
+        leg(
+            &opportunity.yes_token_id,
+            "YES",
+            details.yes_shares,
+            details.yes_price,
+        ),
+        leg(
+            &opportunity.no_token_id,
+            "NO",
+            details.no_shares,
+            details.no_price,
+        ),
+    ]
+}
+
+/// Record the actual fill of one side of a live trade at `avg_price`, the
+/// price the exchange reports was paid.
+///
+/// Orders are already on the exchange by the time this runs, so a failed
+/// insert is logged rather than aborting the trade.
+#[allow(clippy::too_many_arguments)]
+async fn record_side_fill(
+    db: &Database,
+    market_id: Uuid,
+    order_id: Option<&str>,
+    token_id: &str,
+    side: &str,
+    filled_shares: Decimal,
+    avg_price: Decimal,
+    fee_rate: Decimal,
+) {
+    let order_id = match order_id {
+        Some(id) if filled_shares > Decimal::ZERO => id,
+        _ => return,
+    };
+    let fill = repository::FillInsert {
+        market_id,
+        order_id: order_id.to_string(),
+        token_id: token_id.to_string(),
+        side: side.to_uppercase(),
+        filled_shares,
+        avg_price,
+        fee: filled_shares * avg_price * fee_rate,
+        filled_at: Utc::now(),
+    };
+    if let Err(e) = repository::insert_fill(db.pool(), &fill).await {
+        warn!(
+            "[FILL] Failed to record fill for order {}: {:?}",
+            order_id, e
+        );
+    }
+}
+
 use crate::config::ExecutorConfig;
 use crate::detector::SpreadDetector;
 use crate::metrics::{CycleMetrics, MarketSummary};
 use crate::models::{
-    BotState, LiveTradeResult, OrderSide, PollResult, PositionCache, SessionState,
+    BotState, HedgeAction, LiveTradeResult, OrderSide, PollResult, PositionCache, SessionState,
     SpreadOpportunity, TradeDetails,
 };
 
@@ -686,12 +1077,15 @@ pub struct TradeExecutor {
     warmed_tokens: HashSet<String>,
     /// HTTP client for API calls (reusable, connection pooled)
     http_client: reqwest::Client,
+    /// Global spacing between live trades (disabled in dry-run)
+    throttle: TradeThrottle,
 }
 
 impl TradeExecutor {
     /// Create a new trade executor.
     pub async fn new(config: ExecutorConfig, db: Arc<Database>) -> Result<Self> {
-        let detector = SpreadDetector::new(config.min_profit, config.max_price_age_secs);
+        let detector = SpreadDetector::new(config.min_profit, config.max_price_age_secs)
+            .with_fee_guard(config.fee_rate, config.min_spread_to_fee_ratio);
 
         let session = SessionState {
             id: Uuid::new_v4(),
@@ -710,6 +1104,12 @@ impl TradeExecutor {
             open_positions: HashMap::new(),
         };
 
+        let throttle = if config.dry_run {
+            TradeThrottle::disabled()
+        } else {
+            TradeThrottle::new(Duration::from_millis(config.min_trade_interval_ms))
+        };
+
         Ok(Self {
             config,
             db,
@@ -719,6 +1119,7 @@ impl TradeExecutor {
             cached_auth: None, // REQ-001: Initialize as None, authenticate on first trade
             warmed_tokens: HashSet::new(),
             http_client: reqwest::Client::new(),
+            throttle,
         })
     }
 
@@ -749,36 +1150,15 @@ impl TradeExecutor {
         info!("[AUTH] Authenticating with Polymarket CLOB (cache miss)...");
 
         // Load credentials from environment (REQ-013)
-        let private_key = std::env::var("WALLET_PRIVATE_KEY")
-            .context("Missing WALLET_PRIVATE_KEY for live trading")?;
-
-        let private_key = if private_key.starts_with("0x") {
-            private_key
-        } else {
-            format!("0x{}", private_key)
-        };
-
-        // Create signer
-        let signer = PrivateKeySigner::from_str(&private_key)
-            .context("Invalid private key format")?
-            .with_chain_id(Some(POLYGON));
-
-        // Determine signature type based on proxy wallet
-        let proxy_wallet = std::env::var("POLYMARKET_WALLET_ADDRESS").ok();
-        let signature_type = if proxy_wallet.is_some() {
-            SignatureType::GnosisSafe
-        } else {
-            SignatureType::Eoa
-        };
+        let (signer, signature_type, funder) =
+            resolve_signature_config().context("Invalid credentials for live trading")?;
 
         // Build authentication
         let mut auth_builder = ClobClient::new(CLOB_HOST, ClobConfig::default())?
             .authentication_builder(&signer)
             .signature_type(signature_type);
 
-        if let Some(ref proxy) = proxy_wallet {
-            let funder_address: alloy::primitives::Address =
-                proxy.parse().context("Invalid proxy wallet address")?;
+        if let Some(funder_address) = funder {
             auth_builder = auth_builder.funder(funder_address);
         }
 
@@ -1114,12 +1494,37 @@ impl TradeExecutor {
             self.detector
                 .calculate_trade_details(opportunity, size, self.config.fee_rate);
 
+        let intents = leg_intents(opportunity, &details, self.config.dry_run);
+        for intent in &intents {
+            info!("[INTENT] {}", intent);
+        }
+
         // Execute trade (dry run or live)
         if self.config.dry_run {
             self.execute_dry_run(opportunity, &details).await?;
+            self.audit_legs(&intents, TradeOutcome::Filled).await;
             Ok(true)
         } else {
-            match self.execute_live_trade(opportunity, &details).await? {
+            if let Some(wait) = self.throttle.remaining(std::time::Instant::now()) {
+                info!(
+                    "[THROTTLE] Skipping {}: next trade allowed in {}ms",
+                    opportunity.market_name,
+                    wait.as_millis()
+                );
+                self.audit_legs(&intents, TradeOutcome::Skipped).await;
+                return Ok(false);
+            }
+
+            let result = self.execute_live_trade(opportunity, &details).await;
+            self.throttle.record_order(std::time::Instant::now());
+            let outcome = match &result {
+                Ok(LiveTradeResult::Executed { .. }) => TradeOutcome::Placed,
+                Ok(LiveTradeResult::Aborted { .. }) => TradeOutcome::Skipped,
+                Err(_) => TradeOutcome::Rejected,
+            };
+            self.audit_legs(&intents, outcome).await;
+
+            match result? {
                 LiveTradeResult::Executed {
                     invested,
                     yes_filled,
@@ -1139,6 +1544,13 @@ impl TradeExecutor {
         }
     }
 
+    /// Record the same outcome for both legs of a spread trade.
+    async fn audit_legs(&self, intents: &[TradeIntent; 2], outcome: TradeOutcome) {
+        for intent in intents {
+            record_trade_audit(self.db.pool(), intent, outcome).await;
+        }
+    }
+
     /// Execute a live trade on Polymarket.
     /// Implements REQ-001 (cached auth), REQ-006 (price consistency), REQ-007 (synchronized snapshot).
     ///
@@ -1164,32 +1576,23 @@ impl TradeExecutor {
         let db = self.db.clone();
         let max_orderbook_age = self.config.max_orderbook_age_secs;
         let http_client_for_api = self.http_client.clone();
-        let http_client_for_mismatch = self.http_client.clone();
 
         // Capture sequential placement config before mutable borrow
         let enable_sequential_placement = self.config.enable_sequential_placement;
         let price_mismatch_threshold = self.config.price_mismatch_threshold;
         let sequential_poll_interval_ms = self.config.sequential_poll_interval_ms;
         let sequential_poll_timeout_secs = self.config.sequential_poll_timeout_secs;
-
-        // Get credentials for rebalance task (read before ensure_authenticated borrows self)
-        let private_key = std::env::var("WALLET_PRIVATE_KEY")
-            .context("Missing WALLET_PRIVATE_KEY for live trading")?;
-        let private_key = if private_key.starts_with("0x") {
-            private_key
-        } else {
-            format!("0x{}", private_key)
-        };
-        let proxy_wallet = std::env::var("POLYMARKET_WALLET_ADDRESS").ok();
+        let passive_legs = self.config.passive_legs;
+        let passive_timeout_secs = self.config.passive_timeout_secs;
+        let max_pair_cost = self.config.max_pair_cost();
 
         // REQ-001: Use cached authentication (cache hit on subsequent trades)
         let (clob_client, _cached_signer) = self.ensure_authenticated().await?;
 
         // Create local signer for signing operations (SDK requires owned/mutable signer)
         // The cached client is still used for all API operations
-        let signer = PrivateKeySigner::from_str(&private_key)
-            .context("Invalid private key format")?
-            .with_chain_id(Some(POLYGON));
+        let (signer, _, _) =
+            resolve_signature_config().context("Invalid credentials for live trading")?;
 
         // REQ-006, REQ-007: Fetch latest snapshot for current prices and market depth
         let snapshot =
@@ -1237,9 +1640,9 @@ impl TradeExecutor {
 
         // Round price and size to 2 decimal places (Polymarket requirement)
         let mut yes_size = shares;
-        let yes_price = execution_yes_price.round_dp(2);
+        let mut yes_price = execution_yes_price.round_dp(2);
         let mut no_size = shares;
-        let no_price = execution_no_price.round_dp(2);
+        let mut no_price = execution_no_price.round_dp(2);
 
         // Check minimum order value ($1 minimum per Polymarket)
         let yes_value = yes_size * yes_price;
@@ -1287,20 +1690,32 @@ impl TradeExecutor {
             );
         }
 
-        // Helper to extract order result
-        let extract_order_info =
-            |result: &[polymarket_client_sdk::clob::types::PostOrderResponse]| {
-                result.first().map(|r| {
-                    let has_error = r.error_msg.as_ref().map(|e| !e.is_empty()).unwrap_or(false);
-                    let order_id = if r.order_id.is_empty() || has_error {
+        // Spread capture: rest both legs one tick above the bid instead of lifting the asks
+        let passive_tick = if passive_legs && single_side_only.is_none() {
+            market_tick_size(clob_client, &opportunity.yes_token_id).await
+        } else {
+            DEFAULT_TICK_SIZE
+        };
+        let passive_quotes: Option<(Decimal, Decimal)> =
+            if passive_legs && single_side_only.is_none() {
+                match (
+                    passive_quote(snapshot.yes_best_bid, yes_price, passive_tick),
+                    passive_quote(snapshot.no_best_bid, no_price, passive_tick),
+                ) {
+                    (Some(yes_quote), Some(no_quote)) => {
+                        info!(
+                            "[PASSIVE] Quoting YES ${} (ask ${}), NO ${} (ask ${})",
+                            yes_quote, yes_price, no_quote, no_price
+                        );
+                        Some((yes_quote, no_quote))
+                    }
+                    _ => {
+                        info!("[PASSIVE] No room inside the spread, crossing at the asks");
                         None
-                    } else {
-                        Some(r.order_id.clone())
-                    };
-                    let filled = r.taking_amount;
-                    let error = r.error_msg.clone();
-                    (order_id, filled, error)
-                })
+                    }
+                }
+            } else {
+                None
             };
 
         // REQ-007: Log market depth using the SAME snapshot used for price validation
@@ -1311,14 +1726,10 @@ impl TradeExecutor {
         // Only check for both-sides trades where sequential placement makes sense
         let use_sequential_placement: Option<(OrderSide, Decimal, Decimal)> = if single_side_only
             .is_none()
+            && passive_quotes.is_none()
             && enable_sequential_placement
         {
-            match fetch_live_clob_prices(
-                &http_client_for_mismatch,
-                &opportunity.yes_token_id,
-                &opportunity.no_token_id,
-            )
-            .await
+            match fetch_live_clob_prices(&opportunity.yes_token_id, &opportunity.no_token_id).await
             {
                 Some((live_yes, live_no)) => {
                     let (has_mismatch, priority_side, yes_diff, no_diff) = check_price_mismatch(
@@ -1481,6 +1892,44 @@ impl TradeExecutor {
                 }
                 (None, dec!(0), order_id, filled)
             }
+            _ if passive_quotes.is_some() => {
+                let quotes = passive_quotes.expect("checked by match guard");
+                let (yes_leg, no_leg) = execute_passive_legs(
+                    clob_client,
+                    &signer,
+                    opportunity,
+                    yes_size,
+                    quotes,
+                    (yes_price, no_price),
+                    passive_tick,
+                    max_pair_cost,
+                    sequential_poll_interval_ms,
+                    passive_timeout_secs,
+                )
+                .await;
+
+                if yes_leg.filled.is_zero() && no_leg.filled.is_zero() {
+                    let reason = format!(
+                        "Passive legs unfilled after {}s (orders cancelled)",
+                        passive_timeout_secs
+                    );
+                    info!("[PASSIVE] {}", reason);
+                    return Ok(LiveTradeResult::Aborted { reason });
+                }
+
+                // Record what actually filled at the prices actually paid; the
+                // legs are already settled, so nothing is left to rebalance
+                yes_price = yes_leg.avg_price().unwrap_or(yes_price);
+                no_price = no_leg.avg_price().unwrap_or(no_price);
+                yes_size = yes_leg.filled;
+                no_size = no_leg.filled;
+                (
+                    yes_leg.order_id,
+                    yes_leg.filled,
+                    no_leg.order_id,
+                    no_leg.filled,
+                )
+            }
             _ => {
                 // Both sides - check if we should use sequential or simultaneous placement
                 if let Some((priority_side, _live_yes, _live_no)) = use_sequential_placement {
@@ -2220,6 +2669,34 @@ impl TradeExecutor {
             no_filled, no_size, no_order_id
         );
 
+        // Price the fills from the exchange's trades; passive legs already
+        // carry the blended price of their passive and crossing orders
+        let mut yes_fill_price = yes_price;
+        let mut no_fill_price = no_price;
+        if passive_quotes.is_none() {
+            if let Some(id) = yes_order_id
+                .as_deref()
+                .filter(|_| yes_filled > Decimal::ZERO)
+            {
+                match query_fill_avg_price(clob_client, id, &opportunity.yes_token_id).await {
+                    Some(price) => yes_fill_price = price,
+                    None => warn!(
+                        "[FILL] No trade prices for YES order {}, using limit price",
+                        id
+                    ),
+                }
+            }
+            if let Some(id) = no_order_id.as_deref().filter(|_| no_filled > Decimal::ZERO) {
+                match query_fill_avg_price(clob_client, id, &opportunity.no_token_id).await {
+                    Some(price) => no_fill_price = price,
+                    None => warn!(
+                        "[FILL] No trade prices for NO order {}, using limit price",
+                        id
+                    ),
+                }
+            }
+        }
+
         // Check for unfilled orders (for later rebalance spawn)
         let yes_unfilled =
             single_side_only.is_none() && yes_size > Decimal::ZERO && yes_filled < yes_size;
@@ -2295,6 +2772,30 @@ impl TradeExecutor {
             trade_count += 1;
         }
 
+        // Record what the exchange actually filled, independent of the activity feed
+        record_side_fill(
+            &self.db,
+            opportunity.market_id,
+            yes_order_id.as_deref(),
+            &opportunity.yes_token_id,
+            "yes",
+            yes_filled,
+            yes_fill_price,
+            self.config.fee_rate,
+        )
+        .await;
+        record_side_fill(
+            &self.db,
+            opportunity.market_id,
+            no_order_id.as_deref(),
+            &opportunity.no_token_id,
+            "no",
+            no_filled,
+            no_fill_price,
+            self.config.fee_rate,
+        )
+        .await;
+
         // Update session state with actual order value
         let actual_invested = yes_size * yes_price + no_size * no_price;
         let actual_fee = actual_invested * self.config.fee_rate;
@@ -2320,6 +2821,7 @@ impl TradeExecutor {
         let trade_type = match single_side_only {
             Some("yes") => "SINGLE-YES",
             Some("no") => "SINGLE-NO",
+            _ if passive_quotes.is_some() => "PASSIVE-ARB",
             _ => "SPREAD-ARB",
         };
         info!(
@@ -2343,8 +2845,6 @@ impl TradeExecutor {
             let yes_token_id = opportunity.yes_token_id.clone();
             let no_token_id = opportunity.no_token_id.clone();
             let market_name = opportunity.market_name.clone();
-            let private_key_clone = private_key.clone();
-            let proxy_wallet_clone = proxy_wallet.clone();
             // Clone initial fill amounts for correct imbalance calculation
             let yes_filled_initial = yes_filled;
             let no_filled_initial = no_filled;
@@ -2361,20 +2861,14 @@ impl TradeExecutor {
                 tokio::time::sleep(Duration::from_secs(UNFILLED_WAIT_SECS)).await;
 
                 // Re-authenticate for background task
-                let signer = match LocalSigner::from_str(&private_key_clone) {
-                    Ok(s) => s.with_chain_id(Some(POLYGON)),
+                let (signer, signature_type, funder) = match resolve_signature_config() {
+                    Ok(config) => config,
                     Err(e) => {
                         error!("[REBALANCE] Failed to create signer: {:?}", e);
                         return;
                     }
                 };
 
-                let signature_type = if proxy_wallet_clone.is_some() {
-                    SignatureType::GnosisSafe
-                } else {
-                    SignatureType::Eoa
-                };
-
                 let mut auth_builder = match ClobClient::new(CLOB_HOST, ClobConfig::default()) {
                     Ok(c) => c
                         .authentication_builder(&signer)
@@ -2385,10 +2879,8 @@ impl TradeExecutor {
                     }
                 };
 
-                if let Some(ref proxy) = proxy_wallet_clone {
-                    if let Ok(funder_address) = proxy.parse::<alloy::primitives::Address>() {
-                        auth_builder = auth_builder.funder(funder_address);
-                    }
+                if let Some(funder_address) = funder {
+                    auth_builder = auth_builder.funder(funder_address);
                 }
 
                 let clob_client = match auth_builder.authenticate().await {
@@ -2770,8 +3262,7 @@ impl TradeExecutor {
                 let imbalance = final_yes_filled - final_no_filled;
 
                 // Fetch all positions once to get both YES and NO balances
-                let user_address =
-                    proxy_wallet_clone.unwrap_or_else(|| format!("{}", signer.address()));
+                let user_address = format!("{}", funder.unwrap_or(signer.address()));
                 let balance_checker = GammaBalanceChecker::new(GAMMA_DATA_API_URL, &user_address);
 
                 let positions = match balance_checker.get_all_positions().await {
@@ -3747,4 +4238,50 @@ mod tests {
         assert!(!should_abort);
         assert!(reason.contains("improved"));
     }
+
+    #[test]
+    fn test_passive_quote_improves_bid_inside_spread() {
+        let tick = dec!(0.01);
+        // Bid $0.45, ask $0.48: rest at $0.46
+        assert_eq!(
+            passive_quote(Some(dec!(0.45)), dec!(0.48), tick),
+            Some(dec!(0.46))
+        );
+        // One-tick spread: improving the bid would cross
+        assert_eq!(passive_quote(Some(dec!(0.47)), dec!(0.48), tick), None);
+        // Empty bid side
+        assert_eq!(passive_quote(None, dec!(0.48), tick), None);
+        // A finer tick improves by its own increment
+        assert_eq!(
+            passive_quote(Some(dec!(0.47)), dec!(0.48), dec!(0.001)),
+            Some(dec!(0.471))
+        );
+    }
+
+    #[test]
+    fn test_fill_vwap_weights_trades_by_size() {
+        // 10 @ 0.45 and 30 @ 0.49 -> 19.2 / 40
+        let matches = [(dec!(0.45), dec!(10)), (dec!(0.49), dec!(30))];
+        assert_eq!(fill_vwap(&matches), Some(dec!(0.48)));
+        assert_eq!(fill_vwap(&[]), None);
+        assert_eq!(fill_vwap(&[(dec!(0.45), Decimal::ZERO)]), None);
+    }
+
+    #[test]
+    fn test_leg_fill_avg_price_blends_crossing_fill() {
+        // 6 shares rested at $0.46, 4 crossed at $0.49
+        let leg = LegFill {
+            order_id: None,
+            filled: dec!(10),
+            cost: dec!(6) * dec!(0.46) + dec!(4) * dec!(0.49),
+        };
+        assert_eq!(leg.avg_price(), Some(dec!(0.472)));
+
+        let empty = LegFill {
+            order_id: None,
+            filled: dec!(0),
+            cost: dec!(0),
+        };
+        assert_eq!(empty.avg_price(), None);
+    }
 }
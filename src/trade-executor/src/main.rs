@@ -10,9 +10,8 @@ use rust_decimal_macros::dec;
 use tokio::signal;
 use tokio::time::sleep;
 use tracing::{error, info, warn};
-use tracing_subscriber::EnvFilter;
 
-use common::{Config, Database};
+use common::{check_usdc_allowance, Config, Database};
 
 mod balance;
 mod clob_ops;
@@ -51,6 +50,10 @@ struct Args {
     #[arg(long, default_value = "0.01")]
     min_profit: f64,
 
+    /// Minimum ratio of spread profit to fees (e.g., 2.0 = 2x fees, 0 = disabled)
+    #[arg(long, default_value = "0")]
+    min_spread_fee_ratio: f64,
+
     /// Base position size (minimum trade size)
     #[arg(long, default_value = "10")]
     base_position_size: f64,
@@ -90,20 +93,33 @@ struct Args {
     /// Number of cycles for benchmark
     #[arg(long, default_value = "100")]
     cycles: usize,
+
+    /// Emit logs as JSON lines (also enabled by LOG_FORMAT=json)
+    #[arg(long)]
+    json_logs: bool,
+
+    /// Minimum milliseconds between live trades across all markets
+    /// (0 = no limit; ignored in dry-run)
+    #[arg(long, default_value = "0")]
+    min_trade_interval_ms: u64,
+
+    /// Post both legs passively at best bid + 1 tick instead of crossing the spread
+    #[arg(long)]
+    passive_legs: bool,
+
+    /// Seconds to wait for passive fills before crossing the lagging leg
+    #[arg(long, default_value = "10")]
+    passive_timeout_secs: u64,
+
+    /// Abort live startup unless the USDC allowance covers --max-total-exposure
+    #[arg(long)]
+    require_allowance: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging with RUST_LOG env var support (default: info)
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .with_target(false)
-        .compact()
-        .init();
-
     let args = Args::parse();
+    common::logging::init(args.json_logs);
 
     info!("Rust Trade Executor starting...");
     info!("Mode: {}", if args.dry_run { "DRY RUN" } else { "LIVE" });
@@ -125,6 +141,17 @@ async fn main() -> Result<()> {
         );
     }
 
+    if args.min_spread_fee_ratio < 0.0 {
+        anyhow::bail!(
+            "min_spread_fee_ratio ({}) cannot be negative",
+            args.min_spread_fee_ratio
+        );
+    }
+
+    if args.passive_legs && args.passive_timeout_secs == 0 {
+        anyhow::bail!("passive_timeout_secs must be positive when --passive-legs is set");
+    }
+
     // Build executor config
     let exec_config = ExecutorConfig {
         dry_run: args.dry_run,
@@ -138,6 +165,7 @@ async fn main() -> Result<()> {
         max_price_age_secs: 60,
         max_time_to_expiry_secs: args.max_time_to_expiry,
         fee_rate: dec!(0.001), // 0.1%
+        min_spread_to_fee_ratio: Decimal::try_from(args.min_spread_fee_ratio)?,
         assets: args
             .assets
             .split(',')
@@ -163,8 +191,16 @@ async fn main() -> Result<()> {
             .ok()
             .and_then(|s| s.parse::<bool>().ok())
             .unwrap_or(true), // Enabled by default
+        min_trade_interval_ms: args.min_trade_interval_ms,
+        passive_legs: args.passive_legs,
+        passive_timeout_secs: args.passive_timeout_secs,
     };
 
+    // Live orders fail cryptically without a USDC approval, so check it up front
+    if !args.dry_run {
+        check_allowance(exec_config.max_total_exposure, args.require_allowance).await?;
+    }
+
     // Create executor
     let mut executor = TradeExecutor::new(exec_config.clone(), db).await?;
 
@@ -205,6 +241,33 @@ async fn main() -> Result<()> {
 /// Report interval for quiet periods (15 minutes).
 const REPORT_INTERVAL_SECS: u64 = 15 * 60;
 
+/// Warn loudly when the wallet's USDC allowance cannot cover `max_exposure`;
+/// with `require`, refuse to start instead.
+async fn check_allowance(max_exposure: Decimal, require: bool) -> Result<()> {
+    let mut cached_auth = None;
+    match check_usdc_allowance(&mut cached_auth, max_exposure).await {
+        Ok(status) if status.is_sufficient() => {
+            info!("[ALLOWANCE] {}", status);
+        }
+        Ok(status) => {
+            error!(
+                "[ALLOWANCE] {} (max exposure ${}) - live orders will be rejected until USDC is approved for the CLOB exchange",
+                status, max_exposure
+            );
+            if require {
+                anyhow::bail!("Insufficient USDC allowance: {}", status);
+            }
+        }
+        Err(e) => {
+            warn!("[ALLOWANCE] Could not check USDC allowance: {:#}", e);
+            if require {
+                return Err(e.context("--require-allowance set but the allowance check failed"));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Run the main trading loop.
 async fn run_loop(executor: &mut TradeExecutor, args: &Args) -> Result<()> {
     let mut last_report = Instant::now();
@@ -316,23 +379,31 @@ fn print_banner(args: &Args, config: &ExecutorConfig) {
 ═══════════════════════════════════════════════════════════════
   Assets:           {}
   Min profit:       {:.1}%
+  Min spread/fee:   {}x
   Max position:     ${}
   Max exposure:     ${}
   Poll interval:    {}ms
   Orderbook age:    {}s max
   Time to expiry:   {}s max
   Balance:          ${}
+  Passive legs:     {}
 ═══════════════════════════════════════════════════════════════
 "#,
         if args.dry_run { "DRY RUN" } else { "LIVE" },
         args.assets,
         f64::from(args.min_profit) * 100.0,
+        args.min_spread_fee_ratio,
         config.max_position_size,
         config.max_total_exposure,
         args.interval_ms,
         config.max_orderbook_age_secs,
         config.max_time_to_expiry_secs,
         config.starting_balance,
+        if config.passive_legs {
+            format!("ON ({}s then cross)", config.passive_timeout_secs)
+        } else {
+            "OFF".to_string()
+        },
     );
 }
 
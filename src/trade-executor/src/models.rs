@@ -102,6 +102,38 @@ pub enum PollResult {
     Error(String),
 }
 
+/// What to do with a pair of passive legs once polling has stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HedgeAction {
+    /// Both legs filled the same size; the position is hedged.
+    Hedged,
+    /// One leg lags the other; cross the spread on `side` for `shares`.
+    Cross { side: OrderSide, shares: Decimal },
+    /// Neither leg filled; nothing to hedge.
+    Abandon,
+}
+
+impl HedgeAction {
+    /// Decide how to hedge given the final fill of each passive leg.
+    pub fn decide(yes_filled: Decimal, no_filled: Decimal) -> Self {
+        if yes_filled.is_zero() && no_filled.is_zero() {
+            HedgeAction::Abandon
+        } else if yes_filled > no_filled {
+            HedgeAction::Cross {
+                side: OrderSide::No,
+                shares: yes_filled - no_filled,
+            }
+        } else if no_filled > yes_filled {
+            HedgeAction::Cross {
+                side: OrderSide::Yes,
+                shares: no_filled - yes_filled,
+            }
+        } else {
+            HedgeAction::Hedged
+        }
+    }
+}
+
 /// Session runtime state (in-memory).
 #[derive(Debug, Clone)]
 pub struct SessionState {
@@ -402,4 +434,32 @@ mod tests {
             _ => panic!("Clone should preserve variant"),
         }
     }
+
+    // ============ HedgeAction TESTS ============
+
+    #[test]
+    fn test_hedge_action_crosses_lagging_leg() {
+        // YES filled, NO rested untouched: cross NO for the full YES fill
+        assert_eq!(
+            HedgeAction::decide(dec!(10), dec!(0)),
+            HedgeAction::Cross {
+                side: OrderSide::No,
+                shares: dec!(10)
+            }
+        );
+        // Partial imbalance: cross only the difference
+        assert_eq!(
+            HedgeAction::decide(dec!(4), dec!(10)),
+            HedgeAction::Cross {
+                side: OrderSide::Yes,
+                shares: dec!(6)
+            }
+        );
+    }
+
+    #[test]
+    fn test_hedge_action_balanced_or_empty() {
+        assert_eq!(HedgeAction::decide(dec!(10), dec!(10)), HedgeAction::Hedged);
+        assert_eq!(HedgeAction::decide(dec!(0), dec!(0)), HedgeAction::Abandon);
+    }
 }